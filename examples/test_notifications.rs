@@ -46,6 +46,7 @@ fn main() -> anyhow::Result<()> {
         estimated_duration: None,
         actual_duration: None,
         epic_name: Some("Phase 2".to_string()),
+        due_date: None,
     };
 
     // Test 1: Task completion notification
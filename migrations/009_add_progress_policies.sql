@@ -0,0 +1,13 @@
+-- Migration 009: Add per-agent progress coalescing policies
+-- Timestamp: 2026-10-15
+-- Description: Rate-limit chatty agents by coalescing small progress deltas
+
+CREATE TABLE IF NOT EXISTS agent_progress_policies (
+    agent_id TEXT PRIMARY KEY,
+    min_delta INTEGER NOT NULL CHECK(min_delta >= 0 AND min_delta <= 100),
+    window_secs INTEGER NOT NULL CHECK(window_secs >= 0),
+    FOREIGN KEY (agent_id) REFERENCES agents(id)
+);
+
+-- Rollback support
+-- DROP TABLE IF EXISTS agent_progress_policies;
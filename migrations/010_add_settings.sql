@@ -0,0 +1,12 @@
+-- Migration 010: Add key/value settings
+-- Timestamp: 2026-10-15
+-- Description: Project-level settings stored alongside the task data
+
+CREATE TABLE IF NOT EXISTS settings (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+-- Rollback support
+-- DROP TABLE IF EXISTS settings;
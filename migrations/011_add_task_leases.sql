@@ -0,0 +1,18 @@
+-- Migration 011: Add task leases
+-- Timestamp: 2026-10-15
+-- Description: Time-limited task reservations so crashed agents can't hold tasks forever
+
+CREATE TABLE IF NOT EXISTS task_leases (
+    task_id TEXT PRIMARY KEY,
+    agent_id TEXT NOT NULL,
+    acquired_at TEXT NOT NULL,
+    expires_at TEXT NOT NULL,
+    FOREIGN KEY (task_id) REFERENCES tasks(id),
+    FOREIGN KEY (agent_id) REFERENCES agents(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_leases_expires ON task_leases(expires_at);
+
+-- Rollback support
+-- DROP INDEX IF EXISTS idx_task_leases_expires;
+-- DROP TABLE IF EXISTS task_leases;
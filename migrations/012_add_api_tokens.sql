@@ -0,0 +1,16 @@
+-- Migration 012: Add API tokens
+-- Timestamp: 2026-10-15
+-- Description: Hashed bearer tokens with scopes for `prd serve --auth`
+
+CREATE TABLE IF NOT EXISTS api_tokens (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    token_hash TEXT NOT NULL UNIQUE,
+    scope TEXT NOT NULL CHECK(scope IN ('read', 'agent', 'admin')),
+    created_at TEXT NOT NULL,
+    last_used_at TEXT,
+    revoked_at TEXT
+);
+
+-- Rollback support
+-- DROP TABLE IF EXISTS api_tokens;
@@ -0,0 +1,24 @@
+-- Migration 013: Add indices for large task sets
+-- Timestamp: 2026-10-15
+-- Description: Composite indices for status-filtered lists, epic progress,
+-- per-agent workloads and completion history (see `prd bench`)
+
+-- list --status / next: filter and order without a temporary sort
+CREATE INDEX IF NOT EXISTS idx_tasks_status_priority_created ON tasks(status, priority, created_at);
+
+-- epics: covering index for per-epic counts
+CREATE INDEX IF NOT EXISTS idx_tasks_epic ON tasks(epic_name, status);
+
+-- per-agent workloads; supersedes idx_tasks_agent
+CREATE INDEX IF NOT EXISTS idx_tasks_agent_status ON tasks(assigned_agent, status);
+DROP INDEX IF EXISTS idx_tasks_agent;
+
+-- heatmap / velocity: completion date ranges
+CREATE INDEX IF NOT EXISTS idx_tasks_completed_at ON tasks(completed_at);
+
+-- Rollback support
+-- DROP INDEX IF EXISTS idx_tasks_status_priority_created;
+-- DROP INDEX IF EXISTS idx_tasks_epic;
+-- DROP INDEX IF EXISTS idx_tasks_agent_status;
+-- DROP INDEX IF EXISTS idx_tasks_completed_at;
+-- CREATE INDEX IF NOT EXISTS idx_tasks_agent ON tasks(assigned_agent);
@@ -0,0 +1,14 @@
+-- Migration 014: Add epic dependencies
+-- Timestamp: 2026-10-15
+-- Description: Gate an epic's tasks until another epic reaches a completion threshold
+
+CREATE TABLE IF NOT EXISTS epic_dependencies (
+    epic_name TEXT NOT NULL,
+    depends_on_epic TEXT NOT NULL,
+    threshold INTEGER NOT NULL DEFAULT 100,
+    created_at TEXT NOT NULL,
+    PRIMARY KEY (epic_name, depends_on_epic)
+);
+
+-- Rollback support
+-- DROP TABLE IF EXISTS epic_dependencies;
@@ -0,0 +1,12 @@
+-- Migration 015: Add epic target dates
+-- Timestamp: 2026-10-15
+-- Description: Target completion date per epic, compared against projected completion
+
+CREATE TABLE IF NOT EXISTS epic_targets (
+    epic_name TEXT PRIMARY KEY,
+    target_date TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+-- Rollback support
+-- DROP TABLE IF EXISTS epic_targets;
@@ -0,0 +1,27 @@
+-- Migration 016: Add operation log
+-- Timestamp: 2026-10-15
+-- Description: Append-only log of row inserts and column changes for replica sync.
+-- The recording triggers are installed by the application when a database is opened.
+
+CREATE TABLE IF NOT EXISTS oplog (
+    id TEXT PRIMARY KEY,
+    clock INTEGER NOT NULL,
+    replica TEXT NOT NULL,
+    tbl TEXT NOT NULL,
+    row_key TEXT NOT NULL,
+    col TEXT,
+    value,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_oplog_clock ON oplog(clock);
+CREATE INDEX IF NOT EXISTS idx_oplog_cell ON oplog(tbl, row_key, col, clock);
+
+CREATE TABLE IF NOT EXISTS oplog_meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+
+CREATE TABLE IF NOT EXISTS oplog_state (applying INTEGER NOT NULL);
+
+-- Rollback support
+-- DROP TABLE IF EXISTS oplog_state;
+-- DROP TABLE IF EXISTS oplog_meta;
+-- DROP TABLE IF EXISTS oplog;
@@ -0,0 +1,10 @@
+-- Migration 017: Record replaced values in the operation log
+-- Timestamp: 2026-10-15
+-- Description: Cell operations keep the value they replaced and deletes the row they removed,
+-- so changes can be reverted. The oplog.prev column and the delete and epic dependency/target
+-- triggers are installed by the application when a database is opened.
+
+CREATE INDEX IF NOT EXISTS idx_oplog_replica ON oplog(replica, clock);
+
+-- Rollback support
+-- DROP INDEX IF EXISTS idx_oplog_replica;
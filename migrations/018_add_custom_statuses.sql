@@ -0,0 +1,13 @@
+-- Migration 018: Add custom statuses
+-- Timestamp: 2026-10-15
+-- Description: User-defined task statuses, each mapped to a lifecycle bucket (open, active or done).
+-- Tasks in a done status count as completed.
+
+CREATE TABLE IF NOT EXISTS custom_statuses (
+    name TEXT PRIMARY KEY,
+    bucket TEXT NOT NULL CHECK(bucket IN ('open', 'active', 'done')),
+    created_at TEXT NOT NULL
+);
+
+-- Rollback support
+-- DROP TABLE IF EXISTS custom_statuses;
@@ -0,0 +1,16 @@
+-- Migration 019: Add embedding cache
+-- Timestamp: 2026-10-15
+-- Description: Embeddings keyed by the SHA256 of the embedded text, so re-indexing after a
+-- clear or a file move reuses them instead of running the model again.
+
+CREATE TABLE IF NOT EXISTS embedding_cache (
+    model TEXT NOT NULL,              -- model that produced the embedding
+    text_hash TEXT NOT NULL,          -- SHA256 hash of the embedded text
+    embedding BLOB NOT NULL,          -- float32 vector
+    created_at TEXT NOT NULL,
+    last_used_at TEXT NOT NULL,
+    PRIMARY KEY (model, text_hash)
+);
+
+-- Rollback support
+-- DROP TABLE IF EXISTS embedding_cache;
@@ -0,0 +1,14 @@
+-- Migration 020: Add task tags
+-- Timestamp: 2026-10-15
+-- Description: Free-form tags on tasks, e.g. added by automation rules (~/.prd/rules.toml).
+
+CREATE TABLE IF NOT EXISTS task_tags (
+    task_id TEXT NOT NULL,
+    tag TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    PRIMARY KEY(task_id, tag),
+    FOREIGN KEY(task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+-- Rollback support
+-- DROP TABLE IF EXISTS task_tags;
@@ -0,0 +1,13 @@
+-- Migration 021: Add agent capacity and availability
+-- Timestamp: 2026-10-15
+-- Description: Per-agent limit on unfinished tasks (overrides the wip.agent setting) and
+-- comma-separated local-time windows ("22:00-06:00") in which the agent takes new work.
+-- The application adds these columns when a database is opened, and the migration runner skips
+-- any ADD COLUMN for a column that is already there.
+
+ALTER TABLE agents ADD COLUMN capacity INTEGER;
+ALTER TABLE agents ADD COLUMN availability TEXT;
+
+-- Rollback support
+-- ALTER TABLE agents DROP COLUMN availability;
+-- ALTER TABLE agents DROP COLUMN capacity;
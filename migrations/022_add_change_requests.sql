@@ -0,0 +1,24 @@
+-- Migration 022: Add change requests
+-- Timestamp: 2026-10-15
+-- Description: Status and priority changes to guarded tasks (approval.* settings) wait here for `prd approve`.
+
+CREATE TABLE IF NOT EXISTS change_requests (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    task_id TEXT NOT NULL,
+    field TEXT NOT NULL CHECK(field IN ('status', 'priority')),
+    value TEXT NOT NULL,
+    agent_id TEXT,
+    requested_by TEXT,
+    state TEXT NOT NULL DEFAULT 'pending'
+        CHECK(state IN ('pending', 'approved', 'rejected')),
+    decided_by TEXT,
+    created_at TEXT NOT NULL,
+    decided_at TEXT,
+    FOREIGN KEY(task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_change_requests_state ON change_requests(state);
+
+-- Rollback support
+-- DROP INDEX IF EXISTS idx_change_requests_state;
+-- DROP TABLE IF EXISTS change_requests;
@@ -0,0 +1,30 @@
+-- Migration 023: Add releases
+-- Timestamp: 2026-10-15
+-- Description: Versions bundling epics and individual tasks, managed with `prd release`.
+
+CREATE TABLE IF NOT EXISTS releases (
+    version TEXT PRIMARY KEY,
+    target_date TEXT,
+    created_at TEXT NOT NULL,
+    closed_at TEXT
+);
+
+CREATE TABLE IF NOT EXISTS release_epics (
+    version TEXT NOT NULL,
+    epic_name TEXT NOT NULL,
+    PRIMARY KEY(version, epic_name),
+    FOREIGN KEY(version) REFERENCES releases(version) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS release_tasks (
+    version TEXT NOT NULL,
+    task_id TEXT NOT NULL,
+    PRIMARY KEY(version, task_id),
+    FOREIGN KEY(version) REFERENCES releases(version) ON DELETE CASCADE,
+    FOREIGN KEY(task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+-- Rollback support
+-- DROP TABLE IF EXISTS release_tasks;
+-- DROP TABLE IF EXISTS release_epics;
+-- DROP TABLE IF EXISTS releases;
@@ -0,0 +1,18 @@
+-- Migration 024: Add prefixed display keys
+-- Timestamp: 2026-10-15
+-- Description: Optional per-project task keys like "API-42", numbered from id_sequences.
+-- The application adds the display_key column when a database is opened; the runner skips it then.
+
+ALTER TABLE tasks ADD COLUMN display_key TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_tasks_display_key ON tasks(display_key);
+
+CREATE TABLE IF NOT EXISTS id_sequences (
+    prefix TEXT PRIMARY KEY,
+    next_value INTEGER NOT NULL
+);
+
+-- Rollback support
+-- DROP TABLE IF EXISTS id_sequences;
+-- DROP INDEX IF EXISTS idx_tasks_display_key;
+-- ALTER TABLE tasks DROP COLUMN display_key;
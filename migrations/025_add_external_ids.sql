@@ -0,0 +1,13 @@
+-- Migration 025: Add external task IDs
+-- Timestamp: 2026-10-15
+-- Description: Unique key from an outside system (e.g. "gh-1234") so `prd create --external-id`
+-- can be re-run without duplicating tasks. The application adds the column when a database is opened,
+-- so the runner skips it then.
+
+ALTER TABLE tasks ADD COLUMN external_id TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_tasks_external_id ON tasks(external_id);
+
+-- Rollback support
+-- DROP INDEX IF EXISTS idx_tasks_external_id;
+-- ALTER TABLE tasks DROP COLUMN external_id;
@@ -0,0 +1,23 @@
+-- Migration 026: Add sync runs
+-- Timestamp: 2026-10-15
+-- Description: Record which tasks each `prd sync-docs` run completed, and their prior status,
+-- so a bad run can be undone with `prd sync-docs --revert <run-id>`.
+
+CREATE TABLE IF NOT EXISTS sync_runs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    source TEXT NOT NULL,
+    started_at TEXT NOT NULL,
+    reverted_at TEXT
+);
+
+CREATE TABLE IF NOT EXISTS sync_run_tasks (
+    run_id INTEGER NOT NULL,
+    task_id TEXT NOT NULL,
+    previous_status TEXT NOT NULL,
+    PRIMARY KEY(run_id, task_id),
+    FOREIGN KEY(run_id) REFERENCES sync_runs(id) ON DELETE CASCADE
+);
+
+-- Rollback support
+-- DROP TABLE IF EXISTS sync_run_tasks;
+-- DROP TABLE IF EXISTS sync_runs;
@@ -0,0 +1,16 @@
+-- Migration 027: Add sync run results
+-- Timestamp: 2026-10-15
+-- Description: Keep what each sync-docs, git sync and reconcile run changed and the errors it hit,
+-- shown by `prd sync-history`. `errors` holds a JSON array of messages.
+-- The application adds these columns when a database is opened; the runner skips them then.
+
+ALTER TABLE sync_runs ADD COLUMN finished_at TEXT;
+ALTER TABLE sync_runs ADD COLUMN changed INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE sync_runs ADD COLUMN unchanged INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE sync_runs ADD COLUMN errors TEXT;
+
+-- Rollback support
+-- ALTER TABLE sync_runs DROP COLUMN errors;
+-- ALTER TABLE sync_runs DROP COLUMN unchanged;
+-- ALTER TABLE sync_runs DROP COLUMN changed;
+-- ALTER TABLE sync_runs DROP COLUMN finished_at;
@@ -0,0 +1,13 @@
+-- Migration 028: Add task due dates
+-- Timestamp: 2026-10-15
+-- Description: Optional day a task is due (YYYY-MM-DD), set with `--due` on create/update
+-- and filtered by `prd list --due-before/--overdue`.
+-- The application adds the column when a database is opened; the runner skips it then.
+
+ALTER TABLE tasks ADD COLUMN due_date TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_tasks_due_date ON tasks(due_date);
+
+-- Rollback support
+-- DROP INDEX IF EXISTS idx_tasks_due_date;
+-- ALTER TABLE tasks DROP COLUMN due_date;
@@ -0,0 +1,8 @@
+-- Migration 029: Index task tags
+-- Timestamp: 2026-10-15
+-- Description: Look up tasks by tag for `prd tag list` and the --tag filters on `prd list` and `prd next`.
+
+CREATE INDEX IF NOT EXISTS idx_task_tags_tag ON task_tags(tag);
+
+-- Rollback support
+-- DROP INDEX IF EXISTS idx_task_tags_tag;
@@ -0,0 +1,15 @@
+-- Migration 030: Add daily stats snapshots
+-- Timestamp: 2026-10-15
+-- Description: Task counts per status and epic, stored once a day by `prd stats` and compared
+-- by `prd stats --trend 30d`. Tasks outside any epic are stored under epic ''.
+
+CREATE TABLE IF NOT EXISTS stats_snapshots (
+    day TEXT NOT NULL,
+    status TEXT NOT NULL,
+    epic TEXT NOT NULL DEFAULT '',
+    tasks INTEGER NOT NULL,
+    PRIMARY KEY(day, status, epic)
+);
+
+-- Rollback support
+-- DROP TABLE IF EXISTS stats_snapshots;
@@ -0,0 +1,20 @@
+-- Migration 031: Add task comments
+-- Timestamp: 2026-10-15
+-- Description: Discussion on tasks with their author (agent and/or human actor), written with
+-- `prd comment` and shown by `prd show`.
+
+CREATE TABLE IF NOT EXISTS task_comments (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    task_id TEXT NOT NULL,
+    agent_id TEXT,
+    actor TEXT,
+    body TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY(task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_comments_task ON task_comments(task_id);
+
+-- Rollback support
+-- DROP INDEX IF EXISTS idx_task_comments_task;
+-- DROP TABLE IF EXISTS task_comments;
@@ -0,0 +1,21 @@
+-- Migration 032: Add task attachments
+-- Timestamp: 2026-10-15
+-- Description: Files (absolute paths) and URLs attached to tasks as evidence, added with
+-- `prd attach` and listed by `prd show`.
+
+CREATE TABLE IF NOT EXISTS task_attachments (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    task_id TEXT NOT NULL,
+    location TEXT NOT NULL,
+    label TEXT,
+    agent_id TEXT,
+    actor TEXT,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY(task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_attachments_task ON task_attachments(task_id);
+
+-- Rollback support
+-- DROP INDEX IF EXISTS idx_task_attachments_task;
+-- DROP TABLE IF EXISTS task_attachments;
@@ -0,0 +1,23 @@
+-- Migration 033: Add agent errors
+-- Timestamp: 2026-10-15
+-- Description: Failures reported by agents with `prd report-error` (crash, timeout, tool, build,
+-- test or other), kept apart from blocked tasks and shown by `prd show` and the dashboard.
+
+CREATE TABLE IF NOT EXISTS agent_errors (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    agent_id TEXT NOT NULL,
+    task_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    message TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY(agent_id) REFERENCES agents(id) ON DELETE CASCADE,
+    FOREIGN KEY(task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_agent_errors_task ON agent_errors(task_id);
+CREATE INDEX IF NOT EXISTS idx_agent_errors_created ON agent_errors(created_at);
+
+-- Rollback support
+-- DROP INDEX IF EXISTS idx_agent_errors_created;
+-- DROP INDEX IF EXISTS idx_agent_errors_task;
+-- DROP TABLE IF EXISTS agent_errors;
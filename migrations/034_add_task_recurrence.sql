@@ -0,0 +1,15 @@
+-- Migration 034: Add recurring tasks
+-- Timestamp: 2026-10-15
+-- Description: Recurrence rule (e.g. "weekly:mon", "every 3d") set with `prd create --repeat` or
+-- `prd repeat`; completing the task creates the next instance, linked back through recurs_from.
+-- The application adds these columns when a database is opened; the runner skips them then.
+
+ALTER TABLE tasks ADD COLUMN recurrence TEXT;
+ALTER TABLE tasks ADD COLUMN recurs_from TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_tasks_recurs_from ON tasks(recurs_from);
+
+-- Rollback support
+-- DROP INDEX IF EXISTS idx_tasks_recurs_from;
+-- ALTER TABLE tasks DROP COLUMN recurs_from;
+-- ALTER TABLE tasks DROP COLUMN recurrence;
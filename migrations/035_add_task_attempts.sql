@@ -0,0 +1,10 @@
+-- Migration 035: Add task attempts
+-- Timestamp: 2026-10-15
+-- Description: Attempt counter for tasks, starting at 1 and raised by `prd retry` when a task in
+-- the new `failed` status goes back to the queue.
+-- The application adds the column when a database is opened; the runner skips it then.
+
+ALTER TABLE tasks ADD COLUMN attempt INTEGER NOT NULL DEFAULT 1;
+
+-- Rollback support
+-- ALTER TABLE tasks DROP COLUMN attempt;
@@ -0,0 +1,12 @@
+-- Migration 036: Add story points and sprint capacity
+-- Timestamp: 2026-10-15
+-- Description: Story point estimates on tasks and a points capacity on sprints, used by
+-- `prd sprint plan` to fill a sprint from the backlog.
+-- The application adds these columns when a database is opened; the runner skips them then.
+
+ALTER TABLE tasks ADD COLUMN points INTEGER;
+ALTER TABLE sprints ADD COLUMN capacity INTEGER;
+
+-- Rollback support
+-- ALTER TABLE tasks DROP COLUMN points;
+-- ALTER TABLE sprints DROP COLUMN capacity;
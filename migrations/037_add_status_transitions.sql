@@ -0,0 +1,14 @@
+-- Migration 037: Add status transitions
+-- Timestamp: 2026-10-15
+-- Description: Allowed moves between task statuses. While the table is empty any move is allowed;
+-- once it has rows, `prd update` rejects moves that aren't listed unless --force is given.
+
+CREATE TABLE IF NOT EXISTS status_transitions (
+    from_status TEXT NOT NULL,
+    to_status TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    PRIMARY KEY (from_status, to_status)
+);
+
+-- Rollback support
+-- DROP TABLE IF EXISTS status_transitions;
@@ -0,0 +1,10 @@
+-- Migration 038: Add soft delete for tasks
+-- Timestamp: 2026-10-15
+-- Description: `prd delete` moves a task to the trash by setting deleted_at; deleted tasks are
+-- left out of every listing until `prd trash restore` clears it or `prd trash purge` removes them.
+-- The application adds the column when a database is opened; the runner skips it then.
+
+ALTER TABLE tasks ADD COLUMN deleted_at TEXT;
+
+-- Rollback support
+-- ALTER TABLE tasks DROP COLUMN deleted_at;
@@ -0,0 +1,20 @@
+-- Migration 039: Add task history
+-- Timestamp: 2026-10-15
+-- Description: Before/after values of every task field change, written by the task_history_update
+-- trigger (installed when the database is opened) and shown by `prd history <task>`.
+
+CREATE TABLE IF NOT EXISTS task_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    task_id TEXT NOT NULL,
+    field TEXT NOT NULL,
+    old_value TEXT,
+    new_value TEXT,
+    changed_at TEXT NOT NULL,
+    FOREIGN KEY(task_id) REFERENCES tasks(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_history_task ON task_history(task_id, id);
+
+-- Rollback support
+-- DROP TRIGGER IF EXISTS task_history_update;
+-- DROP TABLE IF EXISTS task_history;
@@ -0,0 +1,19 @@
+-- Migration 040: Add operations
+-- Timestamp: 2026-10-15
+-- Description: Each command that changes tasks is recorded as an operation, and its task_history
+-- rows point at it, so `prd undo` can revert the last command's changes as a whole.
+-- The application adds operation_id when a database is opened; the runner skips it then.
+
+CREATE TABLE IF NOT EXISTS operations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    command TEXT NOT NULL,
+    actor TEXT,
+    created_at TEXT NOT NULL,
+    undone_at TEXT
+);
+
+ALTER TABLE task_history ADD COLUMN operation_id INTEGER;
+
+-- Rollback support
+-- ALTER TABLE task_history DROP COLUMN operation_id;
+-- DROP TABLE IF EXISTS operations;
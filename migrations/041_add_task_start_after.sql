@@ -0,0 +1,13 @@
+-- Migration 041: Add scheduled start dates
+-- Timestamp: 2026-10-15
+-- Description: Tasks deferred until start_after stay out of `prd ready` and `prd next`; they are
+-- listed by `prd list --scheduled`. The history trigger is dropped so it is reinstalled with the
+-- new column when the database is next opened. The application adds start_after itself on open,
+-- and the runner then skips it.
+
+ALTER TABLE tasks ADD COLUMN start_after TEXT;
+
+DROP TRIGGER IF EXISTS task_history_update;
+
+-- Rollback support
+-- ALTER TABLE tasks DROP COLUMN start_after;
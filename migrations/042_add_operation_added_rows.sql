@@ -0,0 +1,10 @@
+-- Migration 042: Count rows added by operations
+-- Timestamp: 2026-10-15
+-- Description: Operations count the tasks, dependencies, criteria, tags, comments and attachments
+-- they add, so `prd undo` refuses a command like `prd create` instead of reverting the one before.
+-- The application adds the column when a database is opened; the runner skips it then.
+
+ALTER TABLE operations ADD COLUMN added_rows INTEGER NOT NULL DEFAULT 0;
+
+-- Rollback support
+-- ALTER TABLE operations DROP COLUMN added_rows;
@@ -0,0 +1,10 @@
+-- Migration 043: Count rows removed by operations
+-- Timestamp: 2026-10-16
+-- Description: Operations also count the rows they remove from the same tables, so `prd undo`
+-- refuses a command like `prd tag remove` instead of reverting the one before.
+-- The application adds the column when a database is opened; the runner skips it then.
+
+ALTER TABLE operations ADD COLUMN removed_rows INTEGER NOT NULL DEFAULT 0;
+
+-- Rollback support
+-- ALTER TABLE operations DROP COLUMN removed_rows;
@@ -0,0 +1,298 @@
+//! Archive - moves old finished tasks out of the task database
+//!
+//! `prd archive --before <date>` moves completed and cancelled tasks, with
+//! their logs, acceptance criteria, dependencies and the rest of their
+//! history, into a database named after the task database (e.g. `tools/prd.db`
+//! gets `tools/prd-archive.db`). The archive has the same schema, so it is
+//! read as a second [`Database`] by `--include-archived`.
+
+use crate::db::{Database, COMPLETED_STATUSES_SQL, SETTING_ARCHIVE_MAX_DISPLAY_ID};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Schema the archive is attached as while tasks are moved
+const SCHEMA: &str = "archive";
+
+/// History moved along with a task, by table and the column holding the
+/// task's UUID
+const BY_TASK_ID: &[(&str, &str)] = &[
+    ("task_logs", "task_id"),
+    ("task_history", "task_id"),
+    ("task_tags", "task_id"),
+    ("task_comments", "task_id"),
+    ("task_attachments", "task_id"),
+    ("agent_errors", "task_id"),
+    ("change_requests", "task_id"),
+    ("release_tasks", "task_id"),
+];
+
+/// History moved along with a task, by table and the column holding the
+/// task's display ID
+const BY_DISPLAY_ID: &[(&str, &str)] = &[
+    ("acceptance_criteria", "task_display_id"),
+    ("task_dependencies", "task_display_id"),
+    ("agent_progress", "task_id"),
+    ("sprint_tasks", "task_id"),
+];
+
+/// Result of an archive run
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiveReport {
+    /// Display IDs of the tasks archived (or that would be, on a dry run)
+    pub tasks: Vec<i32>,
+    /// Finished tasks kept because a subtask or dependent task stays behind
+    pub kept: usize,
+    pub path: PathBuf,
+}
+
+/// Path of the archive database for a task database
+pub fn archive_path(db_path: &Path) -> PathBuf {
+    let stem = db_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "prd".to_string());
+    db_path.with_file_name(format!("{}-archive.db", stem))
+}
+
+/// Open the archive of the task database at `db_path`, if one exists
+pub fn open(db_path: &str) -> Result<Option<Database>> {
+    if db_path == ":memory:" {
+        return Ok(None);
+    }
+    let path = archive_path(Path::new(db_path));
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let archive = Database::new(&path.to_string_lossy())
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    Ok(Some(archive))
+}
+
+/// Move completed and cancelled tasks finished before `before` into the
+/// archive
+///
+/// A task whose subtask or dependent task stays behind is kept, so nothing
+/// left in the database points into the archive. Deleted tasks stay in the
+/// trash.
+pub fn archive_tasks(
+    db: &Database,
+    db_path: &str,
+    before: DateTime<Utc>,
+    dry_run: bool,
+) -> Result<ArchiveReport> {
+    if db_path == ":memory:" {
+        anyhow::bail!("An in-memory database can't be archived");
+    }
+    let path = archive_path(Path::new(db_path));
+    let conn = db.get_connection();
+    let (tasks, kept) = candidates(conn, before)?;
+    let mut report = ArchiveReport {
+        tasks: tasks.values().copied().collect(),
+        kept,
+        path: path.clone(),
+    };
+    report.tasks.sort_unstable();
+    if dry_run || tasks.is_empty() {
+        return Ok(report);
+    }
+
+    // Creates the archive with the current schema, or brings an older one up
+    // to date
+    Database::new(&path.to_string_lossy())
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    conn.execute(
+        &format!("ATTACH DATABASE ?1 AS {}", SCHEMA),
+        params![path.to_string_lossy()],
+    )?;
+    let moved = db.write_transaction(|| move_tasks(db, &tasks));
+    conn.execute_batch(&format!("DETACH DATABASE {}", SCHEMA))?;
+    moved?;
+    Ok(report)
+}
+
+/// Finished tasks (UUID to display ID) that can be archived, and how many
+/// finished tasks had to stay
+fn candidates(conn: &Connection, before: DateTime<Utc>) -> Result<(HashMap<String, i32>, usize)> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, display_id FROM tasks
+         WHERE (status IN ({}) OR status = 'cancelled') AND deleted_at IS NULL
+           AND display_id IS NOT NULL AND COALESCE(completed_at, updated_at) < ?1",
+        COMPLETED_STATUSES_SQL
+    ))?;
+    let mut tasks: HashMap<String, i32> = stmt
+        .query_map(params![before.to_rfc3339()], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })?
+        .collect::<Result<_, _>>()?;
+    let finished = tasks.len();
+
+    // Each task that must stay, with the tasks it needs: its parent and
+    // the tasks it depends on
+    let mut stmt = conn.prepare(
+        "SELECT t.id, p.id FROM tasks t JOIN tasks p ON p.id = t.parent_id
+         UNION ALL
+         SELECT t.id, dep.id FROM task_dependencies d
+         JOIN tasks t ON t.display_id = d.task_display_id
+         JOIN tasks dep ON dep.display_id = d.depends_on_display_id",
+    )?;
+    let needs: Vec<(String, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<_, _>>()?;
+    loop {
+        let staying: Vec<String> = needs
+            .iter()
+            .filter(|(task, needed)| !tasks.contains_key(task) && tasks.contains_key(needed))
+            .map(|(_, needed)| needed.clone())
+            .collect();
+        if staying.is_empty() {
+            break;
+        }
+        for id in staying {
+            tasks.remove(&id);
+        }
+    }
+    let kept = finished - tasks.len();
+    Ok((tasks, kept))
+}
+
+/// Copy the tasks and their history into the attached archive, then remove
+/// them from the task database
+fn move_tasks(db: &Database, tasks: &HashMap<String, i32>) -> Result<()> {
+    let conn = db.get_connection();
+    conn.execute_batch(
+        "CREATE TEMP TABLE IF NOT EXISTS archiving (id TEXT PRIMARY KEY, display_id INTEGER);
+         DELETE FROM temp.archiving;",
+    )?;
+    for (id, display_id) in tasks {
+        conn.execute(
+            "INSERT INTO temp.archiving (id, display_id) VALUES (?1, ?2)",
+            params![id, display_id],
+        )?;
+    }
+
+    // Tasks are copied before the rows that reference them and deleted after
+    let selections: Vec<(&str, &str, &str)> = std::iter::once(("tasks", "id", "id"))
+        .chain(
+            BY_TASK_ID
+                .iter()
+                .map(|(table, column)| (*table, *column, "id")),
+        )
+        .chain(
+            BY_DISPLAY_ID
+                .iter()
+                .map(|(table, column)| (*table, *column, "display_id")),
+        )
+        .collect();
+    for (table, column, key) in &selections {
+        let columns = copied_columns(conn, table)?;
+        conn.execute(
+            &format!(
+                "INSERT INTO {schema}.{table} ({columns})
+                 SELECT {columns} FROM main.{table}
+                 WHERE {column} IN (SELECT {key} FROM temp.archiving)",
+                schema = SCHEMA,
+            ),
+            [],
+        )?;
+    }
+    conn.execute_batch(
+        "DELETE FROM main.task_dependencies
+         WHERE depends_on_display_id IN (SELECT display_id FROM temp.archiving);
+         DELETE FROM main.task_leases WHERE task_id IN (SELECT id FROM temp.archiving);
+         UPDATE main.agents SET current_task_id = NULL
+         WHERE current_task_id IN (SELECT id FROM temp.archiving);",
+    )?;
+    for (table, column, key) in selections.iter().rev() {
+        conn.execute(
+            &format!(
+                "DELETE FROM main.{table} WHERE {column} IN (SELECT {key} FROM temp.archiving)"
+            ),
+            [],
+        )?;
+    }
+    conn.execute_batch("DROP TABLE temp.archiving;")?;
+
+    let highest = tasks.values().copied().max().unwrap_or_default();
+    let recorded = db
+        .get_setting(SETTING_ARCHIVE_MAX_DISPLAY_ID)?
+        .and_then(|value| value.parse::<i32>().ok())
+        .unwrap_or_default();
+    if highest > recorded {
+        db.set_setting(SETTING_ARCHIVE_MAX_DISPLAY_ID, &highest.to_string())?;
+    }
+    Ok(())
+}
+
+/// Columns of a task database table to copy, leaving out a lone INTEGER
+/// PRIMARY KEY so the archive numbers those rows itself
+fn copied_columns(conn: &Connection, table: &str) -> Result<String> {
+    let mut stmt = conn.prepare(
+        "SELECT name FROM pragma_table_info(?1, 'main')
+         WHERE NOT (pk = 1 AND upper(type) = 'INTEGER'
+                    AND (SELECT COUNT(*) FROM pragma_table_info(?1, 'main') WHERE pk > 0) = 1)
+         ORDER BY cid",
+    )?;
+    let columns: Vec<String> = stmt
+        .query_map(params![table], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    Ok(columns.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Priority, TaskStatus};
+    use crate::db_extensions::{AcceptanceCriteriaOps, DependencyOps};
+
+    #[test]
+    fn test_archive_moves_finished_tasks_with_history() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let db_path = dir.path().join("prd.db");
+        let db_path = db_path.to_str().unwrap();
+        let db = Database::new(db_path)?;
+
+        let task =
+            |title: &str| db.create_task(title.to_string(), None, Priority::Medium, None, None);
+        let needed = task("Needed")?;
+        let pending = task("Open")?;
+        let recent = task("Recent")?;
+        let old = task("Old")?;
+        let (old_id, needed_id) = (old.display_id.unwrap(), needed.display_id.unwrap());
+        db.get_connection()
+            .add_criterion(old_id, "Documented".to_string())?;
+        db.get_connection()
+            .add_dependency(pending.display_id.unwrap(), needed_id, "blocks")?;
+        for finished in [&old, &needed, &recent] {
+            db.update_task_status(&finished.id, TaskStatus::Completed, None)?;
+        }
+        db.get_connection().execute(
+            "UPDATE tasks SET completed_at = '2026-01-01T00:00:00+00:00' WHERE id IN (?1, ?2)",
+            params![old.id, needed.id],
+        )?;
+
+        let before = "2026-06-01T00:00:00Z".parse()?;
+        let report = archive_tasks(&db, db_path, before, true)?;
+        assert_eq!((report.tasks.clone(), report.kept), (vec![old_id], 1));
+        assert!(open(db_path)?.is_none());
+
+        archive_tasks(&db, db_path, before, false)?;
+        assert!(db.get_task(&old.id)?.is_none());
+        assert!(db.get_task(&needed.id)?.is_some());
+        assert!(db.get_task_logs(&old.id)?.is_empty());
+
+        let archive = open(db_path)?.unwrap();
+        let archived = archive.get_task(&old.id)?.unwrap();
+        assert_eq!(archived.status, TaskStatus::Completed);
+        assert!(!archive.get_task_logs(&old.id)?.is_empty());
+        assert_eq!(archive.get_connection().list_criteria(old_id)?.len(), 1);
+
+        let next = task("Next")?;
+        assert_eq!(next.display_id, Some(5));
+        assert!(archive_tasks(&db, db_path, before, false)?.tasks.is_empty());
+        Ok(())
+    }
+}
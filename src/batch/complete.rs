@@ -1,15 +1,17 @@
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use colored::*;
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 use std::path::Path;
 
-use crate::db::Database;
+use crate::db::{Database, TaskStatus};
+use crate::output::emoji;
 
 /// A single task completion record
 #[derive(Debug, Clone, Deserialize)]
 pub struct CompletionRecord {
     /// Task ID (integer or "#42" format)
+    #[serde(deserialize_with = "task_id")]
     pub task: String,
 
     /// Agent ID (e.g., "A11" or agent name)
@@ -24,6 +26,21 @@ fn default_timestamp() -> DateTime<Utc> {
     Utc::now()
 }
 
+/// Accept task IDs written as numbers (`task: 33`) as well as strings
+fn task_id<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum TaskId {
+        Number(i64),
+        Text(String),
+    }
+
+    Ok(match TaskId::deserialize(deserializer)? {
+        TaskId::Number(id) => id.to_string(),
+        TaskId::Text(id) => id,
+    })
+}
+
 /// Result of batch completion
 #[derive(Debug)]
 pub struct BatchResult {
@@ -39,6 +56,40 @@ pub struct BatchError {
     pub error: String,
 }
 
+impl BatchResult {
+    /// Print the closing tally, listing the records that failed
+    pub fn print_summary(&self, dry_run: bool) {
+        println!("\n{}", "━".repeat(50).dimmed());
+        if dry_run {
+            println!(
+                "  Would complete: {}",
+                self.completed.to_string().green().bold()
+            );
+        } else {
+            println!("\n{}", "Result:".bold());
+            println!("  Completed: {}", self.completed.to_string().green().bold());
+        }
+        if !self.failed.is_empty() {
+            let label = if dry_run {
+                "Validation errors"
+            } else {
+                "Failed"
+            };
+            println!("  {}: {}", label, self.failed.len().to_string().red());
+            for failure in &self.failed {
+                println!(
+                    "    {} task {} (agent {}): {}",
+                    emoji("❌"),
+                    failure.task_id.red(),
+                    failure.agent_id.dimmed(),
+                    failure.error
+                );
+            }
+        }
+        println!("  Time: {:.2}s", self.duration_ms as f64 / 1000.0);
+    }
+}
+
 /// Parse completion records from CLI arguments
 ///
 /// Example:
@@ -101,6 +152,29 @@ pub fn parse_json_file(path: &Path) -> Result<Vec<CompletionRecord>> {
     Ok(records)
 }
 
+/// Parse completion records from YAML file
+///
+/// Expected format:
+/// ```yaml
+/// - task: 33
+///   agent: A11
+///   timestamp: 2025-10-13T10:30:00Z
+/// - task: "#34"
+///   agent: A11
+/// ```
+pub fn parse_yaml_file(path: &Path) -> Result<Vec<CompletionRecord>> {
+    let content = std::fs::read_to_string(path).context("Failed to read YAML file")?;
+
+    let records: Vec<CompletionRecord> =
+        serde_yaml::from_str(&content).context("Failed to parse YAML")?;
+
+    if records.is_empty() {
+        anyhow::bail!("YAML file contains no records");
+    }
+
+    Ok(records)
+}
+
 /// Parse completion records from CSV file
 ///
 /// Expected format:
@@ -140,16 +214,18 @@ pub fn parse_csv_file(path: &Path) -> Result<Vec<CompletionRecord>> {
 /// - Shows progress for large batches
 /// - Validates all inputs before applying changes
 pub fn complete_batch(db: &Database, records: Vec<CompletionRecord>) -> Result<BatchResult> {
+    let _span =
+        crate::telemetry::span("batch.complete").with_attribute("batch.records", records.len());
     let start = std::time::Instant::now();
 
     println!(
         "{} Preparing to complete {} task(s)...",
-        "⚙".cyan(),
+        emoji("⚙").cyan(),
         records.len()
     );
 
     // 1. Validate all records first (fail-fast)
-    println!("{} Validating inputs...", "🔍".cyan());
+    println!("{} Validating inputs...", emoji("🔍").cyan());
     let validated = validate_records(db, &records)?;
 
     // 2. Show summary before applying
@@ -167,11 +243,10 @@ pub fn complete_batch(db: &Database, records: Vec<CompletionRecord>) -> Result<B
             .join(", ")
     );
 
-    // 3. Apply changes in transaction
-    println!("\n{} Applying changes...\n", "⚡".cyan());
+    // 3. Apply changes in transaction (holding the write lock)
+    println!("\n{} Applying changes...\n", emoji("⚡").cyan());
 
-    let conn = db.get_connection();
-    let tx = conn.unchecked_transaction()?;
+    let tx = db.immediate_transaction()?;
 
     let mut completed = 0;
     let mut failed = Vec::new();
@@ -193,7 +268,7 @@ pub fn complete_batch(db: &Database, records: Vec<CompletionRecord>) -> Result<B
     };
 
     for record in &validated {
-        match complete_single_task(&tx, record) {
+        match complete_single_task(db, &tx, record) {
             Ok(_) => {
                 completed += 1;
                 if !show_progress {
@@ -214,7 +289,8 @@ pub fn complete_batch(db: &Database, records: Vec<CompletionRecord>) -> Result<B
                     error: e.to_string(),
                 });
                 println!(
-                    "❌ Failed task {}: {}",
+                    "{} Failed task {}: {}",
+                    emoji("❌"),
                     record.task.red(),
                     e.to_string().dimmed()
                 );
@@ -235,21 +311,145 @@ pub fn complete_batch(db: &Database, records: Vec<CompletionRecord>) -> Result<B
         anyhow::bail!("Batch operation failed. No changes applied.");
     }
 
-    let duration_ms = start.elapsed().as_millis();
+    Ok(BatchResult {
+        completed,
+        failed,
+        duration_ms: start.elapsed().as_millis(),
+    })
+}
+
+/// Outcome of checking one record against the database
+#[derive(Debug)]
+pub struct RecordCheck {
+    pub record: CompletionRecord,
+    /// Task title, when the task exists
+    pub title: Option<String>,
+    /// Why the record can't be applied
+    pub error: Option<String>,
+    /// Noteworthy but harmless, e.g. the task is already completed
+    pub warnings: Vec<String>,
+    /// The agent doesn't exist yet and will be created
+    pub new_agent: bool,
+}
 
-    // 5. Show summary
-    println!("\n{}", "━".repeat(50).dimmed());
-    println!("\n{}", "Result:".bold());
-    println!("  Completed: {}", completed.to_string().green().bold());
-    if !failed.is_empty() {
-        println!("  Failed: {}", failed.len().to_string().red());
+/// Check every record without changing anything
+pub fn check_records(db: &Database, records: &[CompletionRecord]) -> Vec<RecordCheck> {
+    let mut seen = std::collections::HashSet::new();
+    let workflow = db.workflow().ok();
+
+    records
+        .iter()
+        .map(|record| {
+            let mut check = RecordCheck {
+                record: record.clone(),
+                title: None,
+                error: None,
+                warnings: Vec::new(),
+                new_agent: false,
+            };
+
+            // Check task exists
+            match crate::resolver::resolve_task_id(db.get_connection(), &record.task) {
+                Ok(task_uuid) => {
+                    if !seen.insert(task_uuid.clone()) {
+                        check.error = Some(format!("Task {} listed more than once", record.task));
+                    }
+                    if let Ok(Some(task)) = db.get_task(&task_uuid) {
+                        if task.status == TaskStatus::Completed {
+                            check.warnings.push("already completed".to_string());
+                        } else if workflow
+                            .as_ref()
+                            .is_some_and(|w| !w.allows(&task.status, &TaskStatus::Completed))
+                        {
+                            check.error = Some(format!(
+                                "Task {} can't move {} → completed",
+                                record.task,
+                                task.status.as_str()
+                            ));
+                        }
+                        check.title = Some(task.title);
+                    }
+                }
+                Err(_) => check.error = Some(format!("Task {} not found", record.task)),
+            }
+
+            // Check agent exists (or can be created)
+            check.new_agent =
+                crate::resolver::resolve_agent_id(db.get_connection(), &record.agent).is_err();
+
+            check
+        })
+        .collect()
+}
+
+/// Show what `complete_batch` would do, grouped by agent, without applying it
+///
+/// Records that fail validation are returned in `failed`.
+pub fn preview_batch(db: &Database, records: &[CompletionRecord]) -> Result<BatchResult> {
+    let start = std::time::Instant::now();
+    let checks = check_records(db, records);
+
+    println!(
+        "{} {}",
+        "DRY RUN".yellow().bold(),
+        "No changes will be made".dimmed()
+    );
+
+    // Group by agent, in order of first appearance
+    let mut groups: Vec<(&str, Vec<&RecordCheck>)> = Vec::new();
+    for check in &checks {
+        match groups
+            .iter_mut()
+            .find(|(agent, _)| *agent == check.record.agent)
+        {
+            Some((_, group)) => group.push(check),
+            None => groups.push((&check.record.agent, vec![check])),
+        }
+    }
+
+    let mut completed = 0;
+    let mut failed = Vec::new();
+    for (agent, group) in groups {
+        println!(
+            "\n{} ({} task{}{})",
+            agent.cyan().bold(),
+            group.len(),
+            if group.len() == 1 { "" } else { "s" },
+            if group[0].new_agent {
+                ", new agent"
+            } else {
+                ""
+            }
+        );
+        for check in group {
+            let label = match &check.title {
+                Some(title) => format!("{} {}", check.record.task, title.dimmed()),
+                None => check.record.task.clone(),
+            };
+            match &check.error {
+                Some(error) => {
+                    println!("  {} {}", emoji("❌"), label);
+                    failed.push(BatchError {
+                        task_id: check.record.task.clone(),
+                        agent_id: check.record.agent.clone(),
+                        error: error.clone(),
+                    });
+                }
+                None => {
+                    completed += 1;
+                    println!("  ✓ {}", label);
+                }
+            }
+            for warning in &check.warnings {
+                println!("    ⚠ {}", warning.yellow());
+            }
+        }
     }
-    println!("  Time: {:.2}s", duration_ms as f64 / 1000.0);
 
     Ok(BatchResult {
         completed,
         failed,
-        duration_ms,
+        duration_ms: start.elapsed().as_millis(),
     })
 }
 
@@ -258,29 +458,23 @@ fn validate_records(db: &Database, records: &[CompletionRecord]) -> Result<Vec<C
     let mut validated = Vec::new();
     let mut errors = Vec::new();
 
-    for record in records {
-        // Check task exists
-        let task_uuid_result = crate::resolver::resolve_task_id(db.get_connection(), &record.task);
-
-        if task_uuid_result.is_err() {
-            errors.push(format!("Task {} not found", record.task));
+    for check in check_records(db, records) {
+        if let Some(error) = check.error {
+            errors.push(error);
             continue;
         }
-
-        // Check agent exists (or can be created)
-        let agent_uuid_result =
-            crate::resolver::resolve_agent_id(db.get_connection(), &record.agent);
-
-        if agent_uuid_result.is_err() {
+        if check.new_agent {
             // Agent doesn't exist - this is OK, we'll create it
             // But warn the user
             println!(
                 "⚠ Agent {} not found. Will create if needed.",
-                record.agent.yellow()
+                check.record.agent.yellow()
             );
         }
-
-        validated.push(record.clone());
+        for warning in &check.warnings {
+            println!("⚠ Task {}: {}", check.record.task, warning.yellow());
+        }
+        validated.push(check.record);
     }
 
     if !errors.is_empty() {
@@ -291,21 +485,15 @@ fn validate_records(db: &Database, records: &[CompletionRecord]) -> Result<Vec<C
 }
 
 /// Complete a single task (within transaction)
-fn complete_single_task(tx: &rusqlite::Transaction, record: &CompletionRecord) -> Result<()> {
+fn complete_single_task(
+    db: &Database,
+    tx: &rusqlite::Transaction,
+    record: &CompletionRecord,
+) -> Result<()> {
     // 1. Resolve task UUID
     let task_uuid = crate::resolver::resolve_task_id(tx, &record.task)?;
 
-    // 2. Update task status
-    tx.execute(
-        "UPDATE tasks
-         SET status = 'completed',
-             completed_at = ?1,
-             updated_at = ?1
-         WHERE id = ?2",
-        rusqlite::params![record.timestamp.to_rfc3339(), task_uuid],
-    )?;
-
-    // 3. Resolve or create agent
+    // 2. Resolve or create agent
     let agent_uuid = match crate::resolver::resolve_agent_id(tx, &record.agent) {
         Ok(uuid) => uuid,
         Err(_) => {
@@ -314,6 +502,14 @@ fn complete_single_task(tx: &rusqlite::Transaction, record: &CompletionRecord) -
         }
     };
 
+    // 3. Update task status, checked against the allowed transitions, then
+    // backdate the completion to the record's timestamp
+    db.update_task_status(&task_uuid, TaskStatus::Completed, Some(&agent_uuid))?;
+    tx.execute(
+        "UPDATE tasks SET completed_at = ?1, updated_at = ?1 WHERE id = ?2",
+        rusqlite::params![record.timestamp.to_rfc3339(), task_uuid],
+    )?;
+
     // 4. Set agent to idle
     tx.execute(
         "UPDATE agents
@@ -1,6 +1,8 @@
 pub mod complete;
+pub mod update;
 
 pub use complete::{
-    complete_batch, parse_cli_args, parse_csv_file, parse_json_file, BatchError, BatchResult,
-    CompletionRecord,
+    complete_batch, parse_cli_args, parse_csv_file, parse_json_file, parse_yaml_file,
+    preview_batch, BatchError, BatchResult, CompletionRecord,
 };
+pub use update::{apply_to_tasks, BatchReport};
@@ -1,7 +1,8 @@
 #[cfg(test)]
 mod tests {
     use crate::batch::complete::{
-        complete_batch, parse_cli_args, parse_csv_file, parse_json_file, CompletionRecord,
+        complete_batch, parse_cli_args, parse_csv_file, parse_json_file, parse_yaml_file,
+        preview_batch, CompletionRecord,
     };
     use crate::db::{Database, Priority, TaskStatus};
     use chrono::Utc;
@@ -98,6 +99,26 @@ mod tests {
             .contains("contains no records"));
     }
 
+    #[test]
+    fn test_parse_yaml_file() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        let yaml_content = "- task: 33\n  agent: A11\n  timestamp: 2025-10-13T10:30:00Z\n- task: \"#34\"\n  agent: A12\n";
+
+        temp_file.write_all(yaml_content.as_bytes()).unwrap();
+        temp_file.flush().unwrap();
+
+        let records = parse_yaml_file(temp_file.path()).unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].task, "33");
+        assert_eq!(
+            records[0].timestamp.to_rfc3339(),
+            "2025-10-13T10:30:00+00:00"
+        );
+        assert_eq!(records[1].task, "#34");
+        assert_eq!(records[1].agent, "A12");
+    }
+
     #[test]
     fn test_parse_csv_file() {
         let mut temp_file = NamedTempFile::new().unwrap();
@@ -302,4 +323,69 @@ mod tests {
         assert_eq!(result.completed, 1);
         assert!(result.failed.is_empty());
     }
+
+    #[test]
+    fn test_preview_batch_reports_errors_without_changes() {
+        let temp_db = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_db.path().to_str().unwrap()).unwrap();
+        let task = db
+            .create_task("Task 1".to_string(), None, Priority::Medium, None, None)
+            .unwrap();
+
+        let record = |task: &str, agent: &str| CompletionRecord {
+            task: task.to_string(),
+            agent: agent.to_string(),
+            timestamp: Utc::now(),
+        };
+        let records = vec![
+            record("1", "A1"),
+            record("999", "A1"),
+            record("#1", "NewAgent"),
+        ];
+
+        let result = preview_batch(&db, &records).unwrap();
+        assert_eq!(result.completed, 1);
+        assert_eq!(result.failed.len(), 2);
+        assert!(result.failed[0].error.contains("not found"));
+        assert!(result.failed[1].error.contains("more than once"));
+
+        // Nothing was applied
+        let task = db.get_task(&task.id).unwrap().unwrap();
+        assert_eq!(task.status, TaskStatus::Pending);
+        assert!(db.list_agents().unwrap().is_empty());
+
+        // The real run rejects the same batch up front
+        assert!(complete_batch(&db, records).is_err());
+    }
+
+    #[test]
+    fn test_complete_batch_respects_transitions() {
+        let temp_db = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_db.path().to_str().unwrap()).unwrap();
+        let pending = db
+            .create_task("Task 1".to_string(), None, Priority::Medium, None, None)
+            .unwrap();
+        let started = db
+            .create_task("Task 2".to_string(), None, Priority::Medium, None, None)
+            .unwrap();
+        db.update_task_status(&started.id, TaskStatus::InProgress, None)
+            .unwrap();
+        db.reset_transitions(true).unwrap();
+
+        let record = |task: &str| CompletionRecord {
+            task: task.to_string(),
+            agent: "A1".to_string(),
+            timestamp: Utc::now(),
+        };
+        let result = preview_batch(&db, &[record("1")]).unwrap();
+        assert!(result.failed[0].error.contains("pending → completed"));
+        assert!(complete_batch(&db, vec![record("1")]).is_err());
+        let pending = db.get_task(&pending.id).unwrap().unwrap();
+        assert_eq!(pending.status, TaskStatus::Pending);
+
+        let result = complete_batch(&db, vec![record("2")]).unwrap();
+        assert_eq!(result.completed, 1);
+        let started = db.get_task(&started.id).unwrap().unwrap();
+        assert_eq!(started.status, TaskStatus::Completed);
+    }
 }
@@ -0,0 +1,219 @@
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::db::{ApprovalRequired, Database};
+
+/// What happened to one task in a batch
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemStatus {
+    /// Change applied (and kept)
+    Updated,
+    /// Change could not be applied
+    Failed,
+    /// Change succeeded but was undone because another item failed
+    RolledBack,
+    /// Task is guarded; the change was queued as a change request
+    PendingApproval,
+}
+
+/// Per-task entry in a batch report
+#[derive(Debug, Serialize)]
+pub struct ItemResult {
+    pub task: String,
+    pub status: ItemStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Structured result of `batch-update` / `batch-assign`
+#[derive(Debug, Serialize)]
+pub struct BatchReport {
+    pub atomic: bool,
+    /// Whether any changes were kept
+    pub committed: bool,
+    pub updated: usize,
+    pub failed: usize,
+    pub pending_approval: usize,
+    pub items: Vec<ItemResult>,
+}
+
+impl BatchReport {
+    /// True when an atomic batch was rolled back
+    pub fn rolled_back(&self) -> bool {
+        self.atomic && !self.committed
+    }
+}
+
+/// Apply `op` to each task in a comma-separated ID list
+///
+/// Without `atomic`, each task is updated independently and failures are
+/// skipped. With `atomic`, every task is still attempted so the report lists
+/// all errors, but a single failure rolls the whole batch back. Changes that
+/// `op` queued for approval ([`ApprovalRequired`]) are not failures.
+pub fn apply_to_tasks(
+    db: &Database,
+    task_ids: &str,
+    atomic: bool,
+    op: impl Fn(&str) -> Result<()>,
+) -> Result<BatchReport> {
+    let task_ids: Vec<&str> = task_ids
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    let run = |items: &mut Vec<ItemResult>| {
+        for task in &task_ids {
+            let result = crate::resolver::resolve_task_id(db.get_connection(), task)
+                .and_then(|task_uuid| op(&task_uuid));
+            items.push(match result {
+                Ok(()) => ItemResult {
+                    task: task.to_string(),
+                    status: ItemStatus::Updated,
+                    error: None,
+                },
+                Err(e) => ItemResult {
+                    task: task.to_string(),
+                    status: if e.is::<ApprovalRequired>() {
+                        ItemStatus::PendingApproval
+                    } else {
+                        ItemStatus::Failed
+                    },
+                    error: Some(e.to_string()),
+                },
+            });
+        }
+    };
+
+    let mut items = Vec::new();
+    let committed = if atomic {
+        // Nested writes join this transaction; an error return rolls it back
+        let outcome = db.write_transaction(|| {
+            run(&mut items);
+            if items.iter().any(|i| i.status == ItemStatus::Failed) {
+                anyhow::bail!("batch rolled back");
+            }
+            Ok(())
+        });
+        match outcome {
+            Ok(()) => true,
+            // Lock contention before anything ran is a real error
+            Err(e) if items.is_empty() => return Err(e),
+            Err(_) => {
+                for item in items.iter_mut() {
+                    if matches!(
+                        item.status,
+                        ItemStatus::Updated | ItemStatus::PendingApproval
+                    ) {
+                        item.status = ItemStatus::RolledBack;
+                    }
+                }
+                false
+            }
+        }
+    } else {
+        run(&mut items);
+        items
+            .iter()
+            .any(|i| matches!(i.status, ItemStatus::Updated | ItemStatus::PendingApproval))
+    };
+
+    Ok(BatchReport {
+        atomic,
+        committed,
+        updated: items
+            .iter()
+            .filter(|i| i.status == ItemStatus::Updated)
+            .count(),
+        failed: items
+            .iter()
+            .filter(|i| i.status == ItemStatus::Failed)
+            .count(),
+        pending_approval: items
+            .iter()
+            .filter(|i| i.status == ItemStatus::PendingApproval)
+            .count(),
+        items,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{ChangeField, ChangeRequestState, Priority, TaskStatus};
+
+    fn setup() -> Result<Database> {
+        let db = Database::new(":memory:")?;
+        db.create_task("One".to_string(), None, Priority::Low, None, None)?;
+        db.create_task("Two".to_string(), None, Priority::Low, None, None)?;
+        Ok(db)
+    }
+
+    fn status_of(db: &Database, id: &str) -> Result<TaskStatus> {
+        let uuid = crate::resolver::resolve_task_id(db.get_connection(), id)?;
+        Ok(db.get_task(&uuid)?.unwrap().status)
+    }
+
+    #[test]
+    fn test_atomic_batch_rolls_back_on_failure() -> Result<()> {
+        let db = setup()?;
+        let report = apply_to_tasks(&db, "#1, #99, #2", true, |uuid| {
+            db.update_task_status(uuid, TaskStatus::Completed, None)
+        })?;
+
+        assert!(report.rolled_back());
+        assert_eq!((report.updated, report.failed), (0, 1));
+        let statuses: Vec<ItemStatus> = report.items.iter().map(|i| i.status).collect();
+        assert_eq!(
+            statuses,
+            [
+                ItemStatus::RolledBack,
+                ItemStatus::Failed,
+                ItemStatus::RolledBack
+            ]
+        );
+        assert!(report.items[1].error.is_some());
+        assert_eq!(status_of(&db, "#1")?, TaskStatus::Pending);
+        assert_eq!(status_of(&db, "#2")?, TaskStatus::Pending);
+        Ok(())
+    }
+
+    #[test]
+    fn test_non_atomic_batch_keeps_successes() -> Result<()> {
+        let db = setup()?;
+        let report = apply_to_tasks(&db, "#1,#99", false, |uuid| {
+            db.update_task_status(uuid, TaskStatus::Blocked, None)
+        })?;
+        assert!(report.committed && !report.rolled_back());
+        assert_eq!((report.updated, report.failed), (1, 1));
+        assert_eq!(status_of(&db, "#1")?, TaskStatus::Blocked);
+
+        let report = apply_to_tasks(&db, "#1,#2", true, |uuid| {
+            db.update_task_status(uuid, TaskStatus::Review, None)
+        })?;
+        assert!(report.committed);
+        assert_eq!(report.updated, 2);
+        assert_eq!(status_of(&db, "#2")?, TaskStatus::Review);
+        Ok(())
+    }
+
+    #[test]
+    fn test_guarded_tasks_wait_for_approval() -> Result<()> {
+        let db = setup()?;
+        db.set_setting(crate::db::SETTING_APPROVAL_PRIORITIES, "low")?;
+        let report = apply_to_tasks(&db, "#1,#2", true, |uuid| {
+            db.guard_change(uuid, ChangeField::Status, "completed", None, false)?;
+            db.update_task_status(uuid, TaskStatus::Completed, None)
+        })?;
+        assert!(report.committed);
+        assert_eq!((report.updated, report.pending_approval), (0, 2));
+        assert_eq!(status_of(&db, "#1")?, TaskStatus::Pending);
+        assert_eq!(
+            db.list_change_requests(Some(ChangeRequestState::Pending))?
+                .len(),
+            2
+        );
+        Ok(())
+    }
+}
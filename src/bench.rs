@@ -0,0 +1,424 @@
+//! Synthetic-load benchmarks (`prd bench`)
+//!
+//! Generates a large database and times the operations agents and humans run
+//! most often, so regressions in db.rs and the vector search show up before
+//! release.
+
+use crate::db::{Database, TaskStatus};
+use crate::db_extensions::DependencyOps;
+use crate::vectors::{SearchScope, VectorSearch, VectorStore, EMBEDDING_DIM};
+use crate::PRDClient;
+use anyhow::{Context, Result};
+use chrono::{Duration as ChronoDuration, Utc};
+use rusqlite::params;
+use serde::Serialize;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Vector schema, normally applied by migration 008
+const VECTOR_SCHEMA: &str = include_str!("../migrations/008_add_vectors.sql");
+
+const EPICS: [&str; 8] = [
+    "Auth",
+    "Billing",
+    "Search",
+    "Onboarding",
+    "Infra",
+    "Mobile",
+    "Reporting",
+    "API",
+];
+const VERBS: [&str; 8] = [
+    "Implement",
+    "Fix",
+    "Refactor",
+    "Document",
+    "Test",
+    "Optimize",
+    "Migrate",
+    "Review",
+];
+const NOUNS: [&str; 8] = [
+    "login flow",
+    "invoice export",
+    "search index",
+    "webhook retries",
+    "rate limiter",
+    "settings page",
+    "audit log",
+    "cache layer",
+];
+const LOG_ACTIONS: [&str; 5] = [
+    "created",
+    "assigned",
+    "status_changed",
+    "progress",
+    "comment",
+];
+
+/// Size of the generated database
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchConfig {
+    pub tasks: usize,
+    pub logs: usize,
+    pub embeddings: usize,
+    pub agents: usize,
+    /// Timed runs per operation
+    pub iterations: usize,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            tasks: 50_000,
+            logs: 200_000,
+            embeddings: 100_000,
+            agents: 50,
+            iterations: 10,
+        }
+    }
+}
+
+/// Timing summary for one operation
+#[derive(Debug, Clone, Serialize)]
+pub struct OpTiming {
+    pub name: String,
+    pub runs: usize,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+impl OpTiming {
+    fn from_samples(name: &str, mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        let percentile = |p: usize| {
+            let idx = (samples.len() * p).div_ceil(100).saturating_sub(1);
+            samples.get(idx).copied().map(ms).unwrap_or(0.0)
+        };
+        let total: Duration = samples.iter().sum();
+
+        Self {
+            name: name.to_string(),
+            runs: samples.len(),
+            mean_ms: if samples.is_empty() {
+                0.0
+            } else {
+                ms(total) / samples.len() as f64
+            },
+            p50_ms: percentile(50),
+            p95_ms: percentile(95),
+            max_ms: samples.last().copied().map(ms).unwrap_or(0.0),
+        }
+    }
+}
+
+/// Result of a benchmark run
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchReport {
+    pub config: BenchConfig,
+    /// Time spent generating each kind of data
+    pub generation: Vec<OpTiming>,
+    pub operations: Vec<OpTiming>,
+}
+
+/// Generate a synthetic database at `db_path` and time common operations
+///
+/// `db_path` must not exist yet.
+pub fn run(db_path: &Path, config: &BenchConfig) -> Result<BenchReport> {
+    if db_path.exists() {
+        anyhow::bail!("{} already exists", db_path.display());
+    }
+    let path = db_path
+        .to_str()
+        .context("Benchmark database path is not valid UTF-8")?;
+
+    let db = Database::new(path)?;
+    let mut rng = Rng::new(0x5eed);
+    let generation = generate(&db, config, &mut rng)?;
+
+    let task_ids: Vec<String> = {
+        let mut stmt = db.get_connection().prepare("SELECT id FROM tasks")?;
+        let ids = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        ids
+    };
+    let client = PRDClient::new(path)?;
+
+    let mut operations = Vec::new();
+    operations.push(time("list (all tasks)", config.iterations, || {
+        db.list_tasks(None).map(drop)
+    })?);
+    operations.push(time("list --status pending", config.iterations, || {
+        db.list_tasks(Some(TaskStatus::Pending)).map(drop)
+    })?);
+    operations.push(time("stats", config.iterations, || {
+        db.get_stats().map(drop)
+    })?);
+    operations.push(time("epics", config.iterations, || {
+        db.epic_progress().map(drop)
+    })?);
+    operations.push(time("ready", config.iterations, || {
+        db.get_connection().get_ready_tasks().map(drop)
+    })?);
+    operations.push(time("show --logs", config.iterations, || {
+        let task = rng.pick(&task_ids);
+        db.get_task(task)?;
+        db.get_task_logs(task).map(drop)
+    })?);
+    if config.embeddings > 0 {
+        operations.push(time("vector search (top 10)", config.iterations, || {
+            let query = rng.unit_vector();
+            VectorSearch::search_embedding(
+                db.get_connection(),
+                &query,
+                None,
+                &SearchScope::default(),
+                10,
+                0.0,
+            )
+            .map(drop)
+        })?);
+    }
+    let mut agent = 0;
+    operations.push(time("claim (next + sync)", config.iterations, || {
+        agent = agent % config.agents.max(1) + 1;
+        if let Some(task) = client.get_next_task(None)? {
+            client.sync_agent(&format!("bench-agent-{}", agent), &task.id)?;
+        }
+        Ok(())
+    })?);
+
+    Ok(BenchReport {
+        config: config.clone(),
+        generation,
+        operations,
+    })
+}
+
+/// Fill the database, returning how long each phase took
+fn generate(db: &Database, config: &BenchConfig, rng: &mut Rng) -> Result<Vec<OpTiming>> {
+    let conn = db.get_connection();
+    let now = Utc::now();
+    let mut phases = Vec::new();
+
+    let start = Instant::now();
+    let mut agent_ids = Vec::with_capacity(config.agents);
+    for i in 1..=config.agents {
+        agent_ids.push(db.create_agent(format!("bench-agent-{}", i))?.id);
+    }
+
+    let mut task_ids: Vec<String> = Vec::with_capacity(config.tasks);
+    db.write_transaction(|| {
+        let mut insert = conn.prepare(
+            "INSERT INTO tasks (id, display_id, title, description, status, priority, parent_id,
+                                assigned_agent, created_at, updated_at, completed_at, epic_name)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?9, ?10, ?11)",
+        )?;
+        let mut depend = conn.prepare(
+            "INSERT OR IGNORE INTO task_dependencies
+                 (task_display_id, depends_on_display_id, dependency_type, created_at)
+             VALUES (?1, ?2, 'blocks', ?3)",
+        )?;
+
+        for display_id in 1..=config.tasks {
+            let id = Uuid::new_v4().to_string();
+            let status = match rng.below(100) {
+                0..=59 => TaskStatus::Pending,
+                60..=69 => TaskStatus::InProgress,
+                70..=74 => TaskStatus::Blocked,
+                75..=79 => TaskStatus::Review,
+                80..=97 => TaskStatus::Completed,
+                _ => TaskStatus::Cancelled,
+            };
+            let priority = ["low", "medium", "medium", "high", "critical"][rng.below(5)];
+            let created = now - ChronoDuration::minutes(rng.below(365 * 24 * 60) as i64);
+            let completed =
+                (status == TaskStatus::Completed).then(|| created + ChronoDuration::hours(4));
+            let agent = (status != TaskStatus::Pending && !agent_ids.is_empty())
+                .then(|| rng.pick(&agent_ids).clone());
+            let parent =
+                (display_id > 1 && rng.below(10) == 0).then(|| rng.pick(&task_ids).clone());
+
+            insert.execute(params![
+                id,
+                display_id as i64,
+                format!("{} {} {}", rng.pick(&VERBS), rng.pick(&NOUNS), display_id),
+                "Synthetic benchmark task",
+                status.as_str(),
+                priority,
+                parent,
+                agent,
+                created.to_rfc3339(),
+                completed.map(|c| c.to_rfc3339()),
+                rng.pick(&EPICS),
+            ])?;
+            if display_id > 1 && rng.below(10) == 0 {
+                let on = 1 + rng.below(display_id - 1);
+                depend.execute(params![display_id as i64, on as i64, created.to_rfc3339()])?;
+            }
+            task_ids.push(id);
+        }
+        Ok(())
+    })?;
+    phases.push(OpTiming::from_samples(
+        &format!("{} tasks", config.tasks),
+        vec![start.elapsed()],
+    ));
+
+    if !task_ids.is_empty() {
+        let start = Instant::now();
+        db.write_transaction(|| {
+            let mut insert = conn.prepare(
+                "INSERT INTO task_logs (id, task_id, agent_id, action, details, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            for _ in 0..config.logs {
+                let agent = (!agent_ids.is_empty() && rng.below(2) == 0)
+                    .then(|| rng.pick(&agent_ids).clone());
+                insert.execute(params![
+                    Uuid::new_v4().to_string(),
+                    rng.pick(&task_ids),
+                    agent,
+                    rng.pick(&LOG_ACTIONS),
+                    "Synthetic benchmark log entry",
+                    (now - ChronoDuration::minutes(rng.below(365 * 24 * 60) as i64)).to_rfc3339(),
+                ])?;
+            }
+            Ok(())
+        })?;
+        phases.push(OpTiming::from_samples(
+            &format!("{} logs", config.logs),
+            vec![start.elapsed()],
+        ));
+    }
+
+    let start = Instant::now();
+    conn.execute_batch(VECTOR_SCHEMA)?;
+    db.write_transaction(|| {
+        let mut insert = conn.prepare(
+            "INSERT INTO embeddings (content_type, content_id, chunk_index, content_preview,
+                                     content_hash, embedding, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7)",
+        )?;
+        for i in 0..config.embeddings {
+            // One embedding per task, the rest as documentation chunks
+            let (content_type, content_id, chunk) = match task_ids.get(i) {
+                Some(id) => ("task", id.clone(), 0),
+                None => ("doc", format!("docs/bench-{}.md", i / 8), (i % 8) as i64),
+            };
+            insert.execute(params![
+                content_type,
+                content_id,
+                chunk,
+                "Synthetic benchmark content",
+                format!("{:016x}", rng.next()),
+                VectorStore::encode_embedding(&rng.unit_vector())?,
+                now.to_rfc3339(),
+            ])?;
+        }
+        Ok(())
+    })?;
+    phases.push(OpTiming::from_samples(
+        &format!("{} embeddings", config.embeddings),
+        vec![start.elapsed()],
+    ));
+
+    Ok(phases)
+}
+
+/// Run `op` `iterations` times and summarize the timings
+fn time(name: &str, iterations: usize, mut op: impl FnMut() -> Result<()>) -> Result<OpTiming> {
+    let mut samples = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let start = Instant::now();
+        op().with_context(|| format!("Benchmark operation '{}' failed", name))?;
+        samples.push(start.elapsed());
+    }
+    Ok(OpTiming::from_samples(name, samples))
+}
+
+/// Small deterministic xorshift generator so runs are comparable
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed.max(1))
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n.max(1) as u64) as usize
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    fn unit_vector(&mut self) -> Vec<f32> {
+        let v: Vec<f32> = (0..EMBEDDING_DIM)
+            .map(|_| (self.next() % 2000) as f32 / 1000.0 - 1.0)
+            .collect();
+        let norm = v
+            .iter()
+            .map(|x| x * x)
+            .sum::<f32>()
+            .sqrt()
+            .max(f32::EPSILON);
+        v.into_iter().map(|x| x / norm).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_benchmark_run() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("bench.db");
+        let config = BenchConfig {
+            tasks: 200,
+            logs: 500,
+            embeddings: 250,
+            agents: 3,
+            iterations: 2,
+        };
+
+        let report = run(&path, &config)?;
+        assert_eq!(report.generation.len(), 3);
+        assert_eq!(report.operations.len(), 8);
+        assert!(report.operations.iter().all(|op| op.runs == 2));
+
+        let db = Database::new(path.to_str().unwrap())?;
+        assert_eq!(db.get_stats()?.total, 200);
+        let embeddings: i64 =
+            db.get_connection()
+                .query_row("SELECT COUNT(*) FROM embeddings", [], |row| row.get(0))?;
+        assert_eq!(embeddings, 250);
+
+        assert!(run(&path, &config).is_err(), "refuses to overwrite");
+        Ok(())
+    }
+
+    #[test]
+    fn test_percentiles() {
+        let samples = (1..=20).map(Duration::from_millis).collect();
+        let timing = OpTiming::from_samples("op", samples);
+        assert_eq!(timing.runs, 20);
+        assert_eq!(timing.p50_ms, 10.0);
+        assert_eq!(timing.p95_ms, 19.0);
+        assert_eq!(timing.max_ms, 20.0);
+        assert_eq!(timing.mean_ms, 10.5);
+    }
+}
@@ -0,0 +1,233 @@
+use crate::context::TaskRef;
+use crate::db::{Agent, Database, StatusBucket, TaskStatus};
+use crate::git::TaskCommit;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// What one agent did on a task
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentContribution {
+    pub agent_id: String,
+    pub agent_name: String,
+    pub assignments: usize,
+    pub status_changes: usize,
+    pub progress_reports: usize,
+    pub commits: Vec<TaskCommit>,
+    /// Seconds the task was in an active status while assigned to the agent
+    pub time_spent_secs: i64,
+    pub first_touched: DateTime<Utc>,
+    pub last_touched: DateTime<Utc>,
+}
+
+/// Who worked on a task, most time spent first
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskBlame {
+    pub task: TaskRef,
+    pub agents: Vec<AgentContribution>,
+    /// Linked commits whose author isn't a known agent
+    pub unattributed_commits: Vec<TaskCommit>,
+}
+
+/// Summarize which agents touched the task with UUID `task_id`, from its
+/// log, progress reports and the `commits` that reference it
+///
+/// Time spent is credited to whichever agent held the task while it was in
+/// an active status; open spans run until `now`.
+pub fn task_blame(
+    db: &Database,
+    task_id: &str,
+    commits: Vec<TaskCommit>,
+    now: DateTime<Utc>,
+) -> Result<TaskBlame> {
+    let task = db
+        .get_task(task_id)?
+        .with_context(|| format!("Task not found: {}", task_id))?;
+    let workflow = db.workflow()?;
+    let agents = db.list_agents()?;
+    let mut contributions: HashMap<String, AgentContribution> = HashMap::new();
+    let mut spent: HashMap<String, i64> = HashMap::new();
+    let mut holder: Option<String> = None;
+    let mut active_since: Option<DateTime<Utc>> = None;
+    let mut logs = db.get_task_logs(task_id)?;
+    logs.reverse();
+    for log in &logs {
+        let at = log.created_at;
+        if let Some(agent_id) = &log.agent_id {
+            if let Some(entry) = touch(&mut contributions, &agents, agent_id, at) {
+                match log.action.as_str() {
+                    "assigned" => entry.assignments += 1,
+                    "status_changed" => entry.status_changes += 1,
+                    _ => {}
+                }
+            }
+        }
+
+        match log.action.as_str() {
+            "assigned" => {
+                // Hand the running span over to the new holder
+                if let (Some(previous), Some(since)) = (&holder, active_since) {
+                    *spent.entry(previous.clone()).or_default() += (at - since).num_seconds();
+                    active_since = Some(at);
+                }
+                holder = log.agent_id.clone();
+            }
+            "status_changed" => {
+                let Some(status) = log
+                    .details
+                    .as_deref()
+                    .and_then(|d| d.strip_prefix("Changed to "))
+                else {
+                    continue;
+                };
+                if holder.is_none() {
+                    holder = log.agent_id.clone();
+                }
+                let active = workflow.bucket(&TaskStatus::from_str(status)) == StatusBucket::Active;
+                match (active, active_since) {
+                    (true, None) => active_since = Some(at),
+                    (false, Some(since)) => {
+                        if let Some(holder) = &holder {
+                            *spent.entry(holder.clone()).or_default() += (at - since).num_seconds();
+                        }
+                        active_since = None;
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+    if let (Some(holder), Some(since)) = (&holder, active_since) {
+        *spent.entry(holder.clone()).or_default() += (now - since).num_seconds();
+    }
+
+    if let Some(display_id) = task.display_id {
+        for report in db.get_task_progress(display_id)? {
+            if let Some(entry) = touch(
+                &mut contributions,
+                &agents,
+                &report.agent_id,
+                report.timestamp,
+            ) {
+                entry.progress_reports += 1;
+            }
+        }
+    }
+
+    let mut unattributed_commits = Vec::new();
+    for commit in commits {
+        // Commit authors name agents by display ID, e.g. "A12"
+        let agent = commit
+            .agent
+            .as_deref()
+            .and_then(|a| a.trim_start_matches(['A', 'a']).parse::<i32>().ok())
+            .and_then(|display_id| agents.iter().find(|a| a.display_id == Some(display_id)));
+        match agent.and_then(|a| touch(&mut contributions, &agents, &a.id, commit.committed_at)) {
+            Some(entry) => entry.commits.push(commit),
+            None => unattributed_commits.push(commit),
+        }
+    }
+
+    for (agent_id, secs) in spent {
+        if let Some(entry) = contributions.get_mut(&agent_id) {
+            entry.time_spent_secs += secs.max(0);
+        }
+    }
+    let mut agents: Vec<AgentContribution> = contributions.into_values().collect();
+    agents.sort_by(|a, b| {
+        b.time_spent_secs
+            .cmp(&a.time_spent_secs)
+            .then_with(|| a.first_touched.cmp(&b.first_touched))
+    });
+
+    Ok(TaskBlame {
+        task: TaskRef::from_task(&task),
+        agents,
+        unattributed_commits,
+    })
+}
+
+/// The contribution of `agent_id`, widened to include `at`; None for
+/// agents that no longer exist
+fn touch<'a>(
+    contributions: &'a mut HashMap<String, AgentContribution>,
+    agents: &[Agent],
+    agent_id: &str,
+    at: DateTime<Utc>,
+) -> Option<&'a mut AgentContribution> {
+    let agent = agents.iter().find(|a| a.id == agent_id)?;
+    let entry = contributions
+        .entry(agent.id.clone())
+        .or_insert_with(|| AgentContribution {
+            agent_id: agent.id.clone(),
+            agent_name: agent.name.clone(),
+            assignments: 0,
+            status_changes: 0,
+            progress_reports: 0,
+            commits: Vec::new(),
+            time_spent_secs: 0,
+            first_touched: at,
+            last_touched: at,
+        });
+    entry.first_touched = entry.first_touched.min(at);
+    entry.last_touched = entry.last_touched.max(at);
+    Some(entry)
+}
+
+/// Short form of time spent, e.g. "2h 15m" or "40m"
+pub fn format_spent(secs: i64) -> String {
+    let minutes = secs.max(0) / 60;
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{}m", minutes),
+        (hours, 0) => format!("{}h", hours),
+        (hours, minutes) => format!("{}h {}m", hours, minutes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Priority;
+
+    fn commit(agent: &str, at: DateTime<Utc>) -> TaskCommit {
+        TaskCommit {
+            hash: "abc1234".to_string(),
+            summary: "Finish #1".to_string(),
+            author: format!("agent-{}", agent),
+            agent: Some(agent.to_string()),
+            committed_at: at,
+        }
+    }
+
+    #[test]
+    fn test_blame_credits_agents_from_logs_progress_and_commits() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        let first = db.create_agent("first".to_string())?;
+        let second = db.create_agent("second".to_string())?;
+        let task = db.create_task("Port".to_string(), None, Priority::High, None, None)?;
+
+        db.assign_task(&task.id, &first.id)?;
+        db.update_task_status(&task.id, TaskStatus::InProgress, Some(&first.id))?;
+        db.report_progress(&first.id, task.display_id.unwrap(), 40, None)?;
+        db.assign_task(&task.id, &second.id)?;
+
+        let now = Utc::now() + chrono::Duration::hours(2);
+        let second_label = format!("A{}", second.display_id.unwrap());
+        let commits = vec![commit(&second_label, Utc::now()), commit("A99", Utc::now())];
+        let blame = task_blame(&db, &task.id, commits, now)?;
+
+        assert_eq!(blame.agents.len(), 2);
+        // Still in progress with the second agent, which gets the open span
+        assert_eq!(blame.agents[0].agent_id, second.id);
+        assert!(blame.agents[0].time_spent_secs >= 7_000);
+        assert_eq!(blame.agents[0].commits.len(), 1);
+        let by_first = &blame.agents[1];
+        assert_eq!((by_first.assignments, by_first.status_changes), (1, 1));
+        assert_eq!(by_first.progress_reports, 1);
+        assert_eq!(blame.unattributed_commits.len(), 1);
+        assert_eq!(format_spent(8_100), "2h 15m");
+        Ok(())
+    }
+}
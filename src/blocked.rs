@@ -0,0 +1,159 @@
+use crate::db::{Database, Task, TaskStatus};
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+
+/// Log action that records why a task is blocked
+pub const BLOCK_REASON_ACTION: &str = "blocked";
+
+/// A blocked task and how long it has been blocked
+#[derive(Debug, Clone, Serialize)]
+pub struct BlockedTask {
+    pub task: Task,
+    /// Name of the assigned agent
+    pub agent: Option<String>,
+    /// When the task last moved to blocked
+    pub since: DateTime<Utc>,
+    /// Blocking reason recorded since then, if any
+    pub reason: Option<String>,
+}
+
+impl BlockedTask {
+    pub fn duration(&self, now: DateTime<Utc>) -> Duration {
+        now - self.since
+    }
+}
+
+/// Every blocked task, longest blocked first
+///
+/// The blocked time starts at the last status change to blocked, or the
+/// task's last update when that wasn't logged. The reason is the latest
+/// `blocked` log entry, or a runner's "Blocked: ..." result, made since then.
+pub fn blocked_tasks(db: &Database) -> Result<Vec<BlockedTask>> {
+    let conn = db.get_connection();
+    let mut blocked = Vec::new();
+
+    for task in db.list_tasks(Some(TaskStatus::Blocked))? {
+        let changed_at: Option<String> = conn.query_row(
+            "SELECT MAX(created_at) FROM task_logs
+             WHERE task_id = ?1 AND action = 'status_changed' AND details = 'Changed to blocked'",
+            params![&task.id],
+            |row| row.get(0),
+        )?;
+        let since = changed_at
+            .and_then(|at| DateTime::parse_from_rfc3339(&at).ok())
+            .map(|at| at.with_timezone(&Utc))
+            .unwrap_or(task.updated_at);
+
+        let reason = block_reason(db, &task.id, Some(since))?;
+
+        let agent = match &task.assigned_agent {
+            Some(id) => db.get_agent(id)?.map(|a| a.name),
+            None => None,
+        };
+        blocked.push(BlockedTask {
+            task,
+            agent,
+            since,
+            reason,
+        });
+    }
+
+    blocked.sort_by_key(|b| b.since);
+    Ok(blocked)
+}
+
+/// Latest blocking reason logged on a task, optionally only since `since`
+///
+/// Either a `blocked` log entry or a runner's "Blocked: ..." result.
+pub fn block_reason(
+    db: &Database,
+    task_id: &str,
+    since: Option<DateTime<Utc>>,
+) -> Result<Option<String>> {
+    let reason: Option<(String, Option<String>)> = db
+        .get_connection()
+        .query_row(
+            "SELECT action, details FROM task_logs
+             WHERE task_id = ?1 AND (?2 IS NULL OR created_at >= ?2)
+               AND (action = ?3 OR (action = 'agent_run' AND details LIKE 'Blocked: %'))
+             ORDER BY created_at DESC LIMIT 1",
+            params![
+                task_id,
+                since.map(|at| at.to_rfc3339()),
+                BLOCK_REASON_ACTION
+            ],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+    Ok(reason.and_then(|(action, details)| {
+        let details = details?;
+        match action.as_str() {
+            BLOCK_REASON_ACTION => Some(details),
+            _ => details.strip_prefix("Blocked: ").map(str::to_string),
+        }
+    }))
+}
+
+/// Blocked tasks that have been blocked for longer than the `blocked.sla_hours`
+/// setting allows, longest blocked first
+pub fn over_sla(db: &Database, now: DateTime<Utc>) -> Result<Vec<BlockedTask>> {
+    let sla = db.blocked_sla()?;
+    Ok(blocked_tasks(db)?
+        .into_iter()
+        .filter(|b| b.duration(now) > sla)
+        .collect())
+}
+
+/// Short form of a blocked duration, e.g. "3d 4h" or "5h"
+pub fn format_blocked_for(duration: Duration) -> String {
+    let hours = duration.num_hours().max(0);
+    match (hours / 24, hours % 24) {
+        (0, hours) => format!("{}h", hours),
+        (days, 0) => format!("{}d", days),
+        (days, hours) => format!("{}d {}h", days, hours),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Priority, SETTING_BLOCKED_SLA_HOURS};
+
+    #[test]
+    fn test_blocked_over_sla() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        let stuck = db.create_task("Stuck".to_string(), None, Priority::Low, None, None)?;
+        let ran = db.create_task("Ran".to_string(), None, Priority::Low, None, None)?;
+        db.log_task_action(&stuck.id, None, BLOCK_REASON_ACTION, Some("Old reason"))?;
+        db.update_task_status(&stuck.id, TaskStatus::Blocked, None)?;
+        db.log_task_action(
+            &stuck.id,
+            None,
+            BLOCK_REASON_ACTION,
+            Some("Waiting on API keys"),
+        )?;
+        db.update_task_status(&ran.id, TaskStatus::Blocked, None)?;
+        db.log_task_action(&ran.id, None, "agent_run", Some("Blocked: tests fail"))?;
+
+        let blocked = blocked_tasks(&db)?;
+        assert_eq!(blocked.len(), 2);
+        assert_eq!(blocked[0].reason.as_deref(), Some("Waiting on API keys"));
+        assert_eq!(blocked[1].reason.as_deref(), Some("tests fail"));
+
+        // Default SLA is 72 hours
+        assert!(over_sla(&db, Utc::now() + Duration::hours(71))?.is_empty());
+        assert_eq!(over_sla(&db, Utc::now() + Duration::hours(73))?.len(), 2);
+        db.set_setting(SETTING_BLOCKED_SLA_HOURS, "2")?;
+        assert_eq!(over_sla(&db, Utc::now() + Duration::hours(3))?.len(), 2);
+
+        // Unblocking stops the clock
+        db.update_task_status(&ran.id, TaskStatus::InProgress, None)?;
+        let over = over_sla(&db, Utc::now() + Duration::hours(3))?;
+        assert_eq!(over.len(), 1);
+        assert_eq!(over[0].task.id, stuck.id);
+        assert_eq!(format_blocked_for(Duration::hours(76)), "3d 4h");
+        Ok(())
+    }
+}
@@ -0,0 +1,540 @@
+//! Admin - migrations, snapshots, settings, rules and the API server
+
+use anyhow::Result;
+use clap::Subcommand;
+use colored::*;
+use db::{Database, TaskStatus};
+use prd_tool::{colors, db};
+use std::path::PathBuf;
+
+use super::Context;
+use crate::migrations::MigrationRunner;
+
+/// Commands that maintain the database and the tool
+#[derive(Subcommand)]
+pub enum AdminCommands {
+    /// Named point-in-time copies of the database
+    Snapshot {
+        #[command(subcommand)]
+        action: SnapshotAction,
+    },
+
+    /// Database maintenance
+    Db {
+        #[command(subcommand)]
+        action: DbAction,
+    },
+
+    /// Database migration commands
+    Migrate {
+        #[command(subcommand)]
+        action: MigrateAction,
+    },
+
+    /// Apply the retention policy (prune old progress history)
+    Gc {
+        /// Override the configured retention period in days
+        #[arg(long)]
+        progress_days: Option<i64>,
+    },
+
+    /// Show or change project settings (e.g., progress.auto_status)
+    Config {
+        /// Setting key (omit to list all settings)
+        key: Option<String>,
+        /// New value for the setting
+        #[arg(conflicts_with = "unset")]
+        value: Option<String>,
+        /// Remove the setting (restore its default)
+        #[arg(long, requires = "key")]
+        unset: bool,
+    },
+
+    /// Automation rules applied when tasks are created, change status or are
+    /// assigned (~/.prd/rules.toml)
+    Rules {
+        #[command(subcommand)]
+        action: RulesAction,
+    },
+
+    /// Serve the REST/SSE API over HTTP
+    Serve {
+        /// Address to listen on; addresses beyond loopback need --auth
+        #[arg(long, default_value = "127.0.0.1:7878")]
+        bind: String,
+
+        /// Also serve the bundled web dashboard at /
+        #[arg(long)]
+        web: bool,
+
+        /// Seconds between change checks for the event stream
+        #[arg(long, default_value = "2")]
+        refresh_interval: u64,
+
+        /// Require an API token (see `prd token`) on API requests
+        #[arg(long)]
+        auth: bool,
+    },
+
+    /// Manage API tokens for `prd serve --auth`
+    Token {
+        #[command(subcommand)]
+        action: TokenAction,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RulesAction {
+    /// List configured rules
+    List,
+}
+
+#[derive(Subcommand)]
+pub enum MigrateAction {
+    /// Run all pending migrations
+    Latest,
+    /// Show migration status
+    Status,
+    /// Rollback to a specific version
+    Rollback {
+        /// Target version to rollback to
+        version: i32,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SnapshotAction {
+    /// Take a snapshot of the current state
+    Create {
+        /// Name, e.g. "before-replan"
+        name: String,
+    },
+    /// List snapshots
+    List {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Replace the database with a snapshot (the current state is snapshotted first)
+    Restore {
+        /// Snapshot ID or name
+        snapshot: String,
+    },
+    /// Delete a snapshot
+    Delete {
+        /// Snapshot ID or name
+        snapshot: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DbAction {
+    /// Import tasks, agents and relations from another database
+    Merge {
+        /// Database to import from
+        other: PathBuf,
+        /// Title similarity (0-1) at which a task counts as a duplicate
+        #[arg(long, default_value_t = prd_tool::merge::DEFAULT_TITLE_SIMILARITY)]
+        similarity: f64,
+        /// Output the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TokenAction {
+    /// Create a token (the secret is shown once)
+    Create {
+        /// Unique token name (e.g., "ci" or "wallboard")
+        name: String,
+        /// Scope: read, agent (read + report progress) or admin
+        #[arg(long, default_value = "read")]
+        scope: String,
+    },
+    /// Revoke a token by name
+    Revoke {
+        /// Token name
+        name: String,
+    },
+    /// List tokens
+    List,
+}
+
+/// Runs an admin command against the open database
+pub fn run(command: AdminCommands, db: Database, ctx: &Context) -> Result<()> {
+    let dry_run = ctx.dry_run;
+
+    match command {
+        AdminCommands::Db { action } => match action {
+            DbAction::Merge {
+                other,
+                similarity,
+                json,
+            } => {
+                use prd_tool::merge::{merge_database, MergeOptions};
+                let options = MergeOptions {
+                    title_similarity: similarity,
+                    dry_run,
+                };
+                let report = merge_database(&db, &other, &options)?;
+
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&report)?);
+                    return Ok(());
+                }
+
+                for dup in &report.duplicates {
+                    println!(
+                        "{} #{} \"{}\" matches #{} \"{}\" ({:.0}% similar), skipped",
+                        "≈".yellow(),
+                        dup.other_id,
+                        dup.other_title,
+                        dup.local_id,
+                        dup.local_title,
+                        dup.similarity * 100.0
+                    );
+                }
+                for (from, to) in report.renumbered.iter().filter(|(from, to)| from != to) {
+                    println!("  #{} → #{}", from, to);
+                }
+
+                let verb = if dry_run { "Would import" } else { "Imported" };
+                println!(
+                    "\n{} {} {} tasks ({} criteria, {} dependencies, {} tags, {} comments, {} attachments, {} log entries) and {} agents",
+                    if dry_run { "ℹ".blue().bold() } else { "✓".green().bold() },
+                    verb,
+                    report.tasks_added,
+                    report.criteria_added,
+                    report.dependencies_added,
+                    report.tags_added,
+                    report.comments_added,
+                    report.attachments_added,
+                    report.logs_added,
+                    report.agents_added
+                );
+                println!(
+                    "  Skipped {} tasks already present, {} title duplicates; matched {} agents",
+                    report.tasks_existing,
+                    report.duplicates.len(),
+                    report.agents_matched
+                );
+            }
+        },
+
+        AdminCommands::Snapshot { action } => {
+            let store = prd_tool::snapshot::SnapshotStore::for_database(ctx.database);
+            match action {
+                SnapshotAction::Create { name } => {
+                    let snapshot = store.create(db.get_connection(), &name)?;
+                    println!(
+                        "{} Snapshot #{} \"{}\" ({} tasks) saved to {}",
+                        "✓".green().bold(),
+                        snapshot.id,
+                        snapshot.name,
+                        snapshot.tasks,
+                        store.path(&snapshot).display().to_string().dimmed()
+                    );
+                }
+                SnapshotAction::List { json } => {
+                    let snapshots = store.list()?;
+                    if json {
+                        println!("{}", serde_json::to_string_pretty(&snapshots)?);
+                        return Ok(());
+                    }
+                    if snapshots.is_empty() {
+                        println!("No snapshots (create one with `prd snapshot create <name>`)");
+                    }
+                    let time = db.time_display()?;
+                    for snapshot in snapshots {
+                        let size = std::fs::metadata(store.path(&snapshot))
+                            .map(|m| format!("{} KB", m.len() / 1024))
+                            .unwrap_or_else(|_| "missing".red().to_string());
+                        println!(
+                            "{:>4}  {:<24} {}  {:>5} tasks  {}",
+                            format!("#{}", snapshot.id),
+                            snapshot.name.cyan(),
+                            time.format(snapshot.created_at),
+                            snapshot.tasks,
+                            size.dimmed()
+                        );
+                    }
+                }
+                SnapshotAction::Restore { snapshot } => {
+                    let snapshot = store.find(&snapshot)?;
+                    let safety = store.create(
+                        db.get_connection(),
+                        &format!("before-restore-{}", snapshot.id),
+                    )?;
+                    store.restore(&snapshot, ctx.database)?;
+                    println!(
+                        "{} Restored snapshot #{} \"{}\" from {}",
+                        "✓".green().bold(),
+                        snapshot.id,
+                        snapshot.name,
+                        db.time_display()?.format(snapshot.created_at)
+                    );
+                    println!(
+                        "  Previous state saved as snapshot #{} (`prd snapshot restore {}` to undo)",
+                        safety.id, safety.id
+                    );
+                }
+                SnapshotAction::Delete { snapshot } => {
+                    let snapshot = store.find(&snapshot)?;
+                    store.delete(&snapshot)?;
+                    println!(
+                        "{} Deleted snapshot #{} \"{}\"",
+                        "✓".green().bold(),
+                        snapshot.id,
+                        snapshot.name
+                    );
+                }
+            }
+        }
+
+        AdminCommands::Migrate { action } => {
+            let conn = db.get_connection();
+            let runner = MigrationRunner::new(conn);
+
+            match action {
+                MigrateAction::Latest => {
+                    println!("{}", "Running migrations...".cyan());
+                    let applied = runner.migrate_to_latest()?;
+                    if applied.is_empty() {
+                        println!("{}", "Already up to date!".green());
+                    } else {
+                        println!(
+                            "\n{} Applied {} migration(s)",
+                            "✓".green().bold(),
+                            applied.len()
+                        );
+                    }
+                }
+                MigrateAction::Status => {
+                    runner.status()?;
+                }
+                MigrateAction::Rollback { version } => {
+                    runner.rollback(version)?;
+                }
+            }
+        }
+
+        AdminCommands::Gc { progress_days } => {
+            use prd_tool::retention::{run_gc, RetentionConfig};
+
+            let mut config = RetentionConfig::load()?;
+            if let Some(days) = progress_days {
+                config.progress_days = days;
+            }
+
+            let report = run_gc(&db, &config)?;
+            println!(
+                "{} Pruned {} progress reports older than {} days{}",
+                "✓".green().bold(),
+                report.progress_deleted,
+                config.progress_days,
+                if config.keep_final_snapshots {
+                    " (final snapshots kept)"
+                } else {
+                    ""
+                }
+            );
+            let pruned = report.embeddings_pruned;
+            if pruned.total() > 0 {
+                println!(
+                    "{} Pruned {} orphaned embeddings ({} for deleted or cancelled tasks, {} for missing files)",
+                    "✓".green().bold(),
+                    pruned.total(),
+                    pruned.tasks,
+                    pruned.files
+                );
+            }
+        }
+
+        AdminCommands::Config { key, value, unset } => {
+            let Some(key) = key else {
+                let settings = db.list_settings()?;
+                if settings.is_empty() {
+                    println!("No settings configured");
+                }
+                for (key, value) in settings {
+                    println!("{} = {}", key.cyan(), value);
+                }
+                return Ok(());
+            };
+
+            if unset {
+                if db.unset_setting(&key)? {
+                    println!("{} Unset {}", "✓".green().bold(), key.cyan());
+                } else {
+                    println!("{} is not set", key.cyan());
+                }
+                return Ok(());
+            }
+
+            match value {
+                Some(value) => {
+                    validate_setting(&db, &key, &value)?;
+                    db.set_setting(&key, &value)?;
+                    println!("{} {} = {}", "✓".green().bold(), key.cyan(), value);
+                }
+                None => match db.get_setting(&key)? {
+                    Some(value) => println!("{}", value),
+                    None => println!("{} is not set", key.cyan()),
+                },
+            }
+        }
+
+        AdminCommands::Serve {
+            bind,
+            web,
+            refresh_interval,
+            auth,
+        } => {
+            use prd_tool::server::{run_server, ServerConfig};
+
+            run_server(ServerConfig {
+                db_path: ctx.database.to_str().unwrap().to_string(),
+                bind,
+                web,
+                refresh_interval: std::time::Duration::from_secs(refresh_interval),
+                auth,
+            })?;
+        }
+
+        AdminCommands::Token { action } => {
+            use prd_tool::server::{TokenScope, TokenStore};
+
+            let store = TokenStore::new(&db);
+
+            match action {
+                TokenAction::Create { name, scope } => {
+                    let scope = TokenScope::parse(&scope)?;
+                    let (token, secret) = store.create(&name, scope)?;
+                    println!(
+                        "{} Created {} token {}",
+                        "✓".green().bold(),
+                        token.scope.as_str(),
+                        token.name.cyan()
+                    );
+                    println!("\n  {}\n", secret.bold());
+                    println!(
+                        "{}",
+                        "Store it now: the token is kept hashed and cannot be shown again."
+                            .yellow()
+                    );
+                }
+                TokenAction::Revoke { name } => {
+                    if store.revoke(&name)? {
+                        println!("{} Revoked token {}", "✓".green().bold(), name.cyan());
+                    } else {
+                        println!("No active token named {}", name.cyan());
+                    }
+                }
+                TokenAction::List => {
+                    let tokens = store.list()?;
+                    if tokens.is_empty() {
+                        println!("No API tokens (create one with `prd token create <name>`)");
+                    }
+                    let time = db.time_display()?;
+                    for token in tokens {
+                        let state = match (token.revoked_at, token.last_used_at) {
+                            (Some(at), _) => format!("revoked {}", at.format("%Y-%m-%d"))
+                                .red()
+                                .to_string(),
+                            (None, Some(at)) => {
+                                format!("last used {}", time.format(at))
+                            }
+                            (None, None) => "never used".dimmed().to_string(),
+                        };
+                        println!(
+                            "{:<20} {:<6} created {}  {}",
+                            token.name.cyan(),
+                            token.scope.as_str(),
+                            token.created_at.format("%Y-%m-%d"),
+                            state
+                        );
+                    }
+                }
+            }
+        }
+
+        AdminCommands::Rules { action } => match action {
+            RulesAction::List => {
+                let config = prd_tool::rules::RulesConfig::load()?;
+                let path = prd_tool::rules::RulesConfig::get_config_path()?;
+                if config.rules.is_empty() {
+                    println!("No rules configured in {}", path.display());
+                    return Ok(());
+                }
+                for rule in &config.rules {
+                    println!("{}", rule.name.bold());
+                    println!("  On: {}", rule.on.as_str());
+                    println!("  When: {}", rule.when.describe());
+                    println!("  Then: {}", rule.then.describe());
+                }
+                println!("\nRules file: {}", path.display());
+            }
+        },
+    }
+
+    Ok(())
+}
+
+/// Reject values the known settings can't interpret
+fn validate_setting(db: &Database, key: &str, value: &str) -> Result<()> {
+    match key {
+        db::SETTING_AUTO_STATUS if value != "true" && value != "false" => {
+            anyhow::bail!("{} must be 'true' or 'false'", key)
+        }
+        db::SETTING_DONE_STATUS => match db.parse_status(value)? {
+            TaskStatus::Review | TaskStatus::Completed => Ok(()),
+            status @ TaskStatus::Custom(_)
+                if db.workflow()?.bucket(&status) != db::StatusBucket::Open =>
+            {
+                Ok(())
+            }
+            _ => anyhow::bail!(
+                "{} must be 'review', 'completed' or a custom active or done status",
+                key
+            ),
+        },
+        db::SETTING_QUEUE_ORDER if !matches!(value, "priority" | "wsjf") => {
+            anyhow::bail!("{} must be 'priority' or 'wsjf'", key)
+        }
+        db::SETTING_PRIORITY_LEVELS => db::PriorityLevels::parse_list(value).map(|_| ()),
+        db::SETTING_WIP_AGENT => db::WipLimits::parse_limit(value).map(|_| ()),
+        db::SETTING_BLOCKED_SLA_HOURS => db::parse_sla_hours(value).map(|_| ()),
+        db::SETTING_ID_PREFIX => db::normalize_id_prefix(value).map(|_| ()),
+        db::SETTING_DISPLAY_TIMEZONE => db::DisplayZone::parse(value).map(|_| ()),
+        db::SETTING_DISPLAY_TIME_FORMAT => db::validate_time_format(value),
+        db::SETTING_APPROVAL_PRIORITIES => {
+            for priority in db::ApprovalPolicy::parse_list(value) {
+                db.parse_priority(&priority)?;
+            }
+            Ok(())
+        }
+        db::SETTING_APPROVAL_ROLE if !matches!(value, "planner" | "agent" | "viewer") => {
+            anyhow::bail!("{} must be 'planner', 'agent' or 'viewer'", key)
+        }
+        prd_tool::hooks::SETTING_HOOKS_CONFIG => {
+            prd_tool::hooks::HookConfig::from_toml(value).map(|_| ())
+        }
+        prd_tool::notifications::config::SETTING_NOTIFICATIONS_CONFIG => {
+            prd_tool::notifications::NotificationConfig::from_toml(value).map(|_| ())
+        }
+        _ if key.starts_with(colors::COLOR_SETTING_PREFIX) => {
+            if let colors::ColorTarget::Priority(level) = colors::ColorTarget::parse(key)? {
+                db.parse_priority(&level)?;
+            }
+            colors::Rgb::parse(value).map(|_| ())
+        }
+        _ => match key.strip_prefix(db::WIP_SETTING_PREFIX) {
+            Some(status) => {
+                db.parse_status(status)?;
+                db::WipLimits::parse_limit(value).map(|_| ())
+            }
+            None => Ok(()),
+        },
+    }
+}
@@ -0,0 +1,715 @@
+//! Agents - registering agents, handing them work and taking their reports
+
+use anyhow::Result;
+use clap::Subcommand;
+use colored::*;
+use db::{AgentStatus, Database};
+use prd_tool::rules::EventKind;
+use prd_tool::{db, resolver};
+use resolver::{format_agent_id, format_task_id, resolve_agent_id, resolve_task_id};
+use std::path::PathBuf;
+use tabled::{settings::Style, Table, Tabled};
+
+use super::{parse_ttl, Context};
+
+/// Commands for agents and their reports
+#[derive(Subcommand)]
+pub enum AgentCommands {
+    /// Assign a task to an agent
+    Assign {
+        /// Task ID
+        task_id: String,
+        /// Agent ID or name
+        agent: String,
+    },
+
+    /// Create a new agent
+    #[command(alias = "create-agent")]
+    AgentCreate {
+        /// Agent name
+        name: String,
+    },
+
+    /// List all agents
+    #[command(alias = "agents", alias = "list-agents")]
+    AgentList,
+
+    /// Update agent status
+    AgentStatus {
+        /// Agent ID or name
+        agent: String,
+        /// New status (idle, working, blocked, offline)
+        status: String,
+        /// Current task ID (optional)
+        #[arg(short, long)]
+        task: Option<String>,
+    },
+
+    /// Run agents as workers
+    Agent {
+        #[command(subcommand)]
+        action: AgentAction,
+    },
+
+    /// List an agent's assigned pending tasks, then ready tasks it can claim
+    ///
+    /// Meant for polling by worker processes: output is uncolored, one task
+    /// per line (or JSON with --json), and paged with --offset/--limit.
+    Queue {
+        /// Agent ID or name
+        #[arg(short, long)]
+        agent: String,
+        /// Tasks per page
+        #[arg(long, default_value_t = prd_tool::queue::DEFAULT_QUEUE_LIMIT)]
+        limit: usize,
+        /// Tasks to skip
+        #[arg(long, default_value = "0")]
+        offset: usize,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Create and update tasks from the work items in an agent session
+    /// transcript (extractors in ~/.prd/transcripts.toml)
+    IngestTranscript {
+        /// Transcript file (JSONL, e.g. a Claude Code session)
+        path: PathBuf,
+        /// Agent ID or name credited with completed work (created if missing)
+        #[arg(short, long)]
+        agent: Option<String>,
+        /// Epic for created tasks
+        #[arg(short, long)]
+        epic: Option<String>,
+        /// Task that decisions without a task reference are logged on
+        #[arg(long)]
+        task: Option<String>,
+        /// Also run the configured llm_command
+        #[arg(long)]
+        llm: bool,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Report agent progress on a task
+    ReportProgress {
+        /// Agent ID or name (e.g., "A12" or "agent-name")
+        agent: String,
+        /// Task display ID (e.g., "37" or "#37")
+        task_id: String,
+        /// Progress percentage (0-100)
+        progress: u8,
+        /// Optional progress message
+        message: Option<String>,
+    },
+
+    /// Report that an agent failed on a task (crash, timeout, tool error...)
+    ReportError {
+        /// Agent ID or name (e.g., "A12" or "agent-name")
+        agent: String,
+        /// Task display ID (e.g., "37" or "#37")
+        task_id: String,
+        /// What went wrong
+        #[arg(long, short)]
+        message: String,
+        /// Failure kind: crash, timeout, tool, build, test or other
+        #[arg(long, default_value = "other")]
+        kind: String,
+    },
+
+    /// Configure coalescing of an agent's progress reports
+    ProgressPolicy {
+        /// Agent ID or name (e.g., "A12" or "agent-name")
+        agent: String,
+        /// Merge reports that move progress by less than this many percent
+        #[arg(long)]
+        min_delta: Option<u8>,
+        /// ...when they arrive within this many seconds of the previous report
+        #[arg(long)]
+        window: Option<i64>,
+        /// Remove the policy (record every report)
+        #[arg(long, conflicts_with_all = ["min_delta", "window"])]
+        clear: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AgentAction {
+    /// Claim ready tasks one at a time and run a command on each
+    ///
+    /// The command gets the task in PRD_* environment variables, and as JSON on
+    /// stdin and in the file named by PRD_CONTEXT_FILE. It reports back by
+    /// printing `prd:progress <pct> [message]`, `prd:log <message>` or
+    /// `prd:block <reason>`. Exiting 0 completes the task, 75 hands it back
+    /// for another run, and anything else blocks it.
+    Run {
+        /// Agent name (registered if new)
+        name: String,
+        /// Shell command to run for each task
+        #[arg(long)]
+        exec: String,
+        /// Run at most one task, then exit
+        #[arg(long, conflicts_with = "max_tasks")]
+        once: bool,
+        /// Exit after this many tasks
+        #[arg(long)]
+        max_tasks: Option<usize>,
+        /// Exit when no task is ready instead of waiting for one
+        #[arg(long)]
+        exit_when_idle: bool,
+        /// Seconds between checks for ready tasks while idle
+        #[arg(long, default_value = "30")]
+        poll: u64,
+        /// Only claim tasks in this epic
+        #[arg(short, long)]
+        epic: Option<String>,
+        /// Only claim tasks with this priority
+        #[arg(short, long)]
+        priority: Option<String>,
+        /// Lease held on a claimed task, renewed while the command runs
+        #[arg(long, default_value = "30m", value_parser = parse_ttl)]
+        lease: chrono::Duration,
+        /// Kill the command after this long (e.g., 30m, 2h)
+        #[arg(long, value_parser = parse_ttl)]
+        timeout: Option<chrono::Duration>,
+        /// Print one JSON line per finished task instead of the command's output
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show or set how many tasks an agent holds at once and when it takes
+    /// new work
+    ///
+    /// The capacity applies wherever WIP limits do (`sync`, `lease`, `next
+    /// --agent`, `agent run`); outside its windows, `next --agent` and `agent
+    /// run` hand the agent nothing new.
+    Capacity {
+        /// Agent ID or name
+        agent: String,
+        /// Most unfinished tasks at once (overrides the wip.agent setting)
+        #[arg(long)]
+        max_tasks: Option<usize>,
+        /// Local-time window for new work, e.g. "22:00-06:00" (repeatable)
+        #[arg(long = "window", value_parser = db::AvailabilityWindow::parse)]
+        windows: Vec<db::AvailabilityWindow>,
+        /// Remove the capacity and windows
+        #[arg(long, conflicts_with_all = ["max_tasks", "windows"])]
+        clear: bool,
+    },
+}
+
+#[derive(Tabled)]
+struct AgentRow {
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Status")]
+    status: String,
+    #[tabled(rename = "Current Task")]
+    current_task: String,
+    #[tabled(rename = "Last Active")]
+    last_active: String,
+}
+
+/// Runs an agent command against the open database
+pub fn run(command: AgentCommands, db: Database, ctx: &Context) -> Result<()> {
+    let dry_run = ctx.dry_run;
+    let invocation = ctx.invocation;
+
+    match command {
+        AgentCommands::Assign { task_id, agent } => {
+            let task_uuid = resolve_task_id(db.get_connection(), &task_id)?;
+
+            // Try to find agent by ID or name (resolver handles both)
+            let agent_uuid_result = resolve_agent_id(db.get_connection(), &agent);
+            let agent_obj = if let Ok(agent_uuid) = agent_uuid_result {
+                db.get_agent(&agent_uuid)?
+            } else {
+                None
+            };
+
+            match agent_obj {
+                Some(a) => {
+                    db.check_lease(&task_uuid, &a.id)?;
+                    db.assign_task(&task_uuid, &a.id)?;
+                    let task_display = format_task_id(db.get_connection(), &task_uuid);
+                    let agent_display = format_agent_id(db.get_connection(), &a.id);
+                    println!(
+                        "{} Task {} assigned to {} ({})",
+                        "✓".green().bold(),
+                        task_display.cyan(),
+                        agent_display.cyan(),
+                        a.name
+                    );
+                }
+                None => {
+                    println!("{} Agent not found. Creating new agent...", "⚠".yellow());
+                    let new_agent = db.create_agent(agent.clone())?;
+                    db.assign_task(&task_uuid, &new_agent.id)?;
+                    let task_display = format_task_id(db.get_connection(), &task_uuid);
+                    let agent_display = format_agent_id(db.get_connection(), &new_agent.id);
+                    println!(
+                        "{} Task {} assigned to new agent {} ({})",
+                        "✓".green().bold(),
+                        task_display.cyan(),
+                        agent_display.cyan(),
+                        new_agent.name
+                    );
+                }
+            }
+            invocation.emit(EventKind::Assigned, &task_uuid, true);
+        }
+
+        AgentCommands::AgentCreate { name } => {
+            let agent = db.create_agent(name)?;
+            println!("{}", "✓ Agent created successfully!".green().bold());
+            let display_id = agent
+                .display_id
+                .map(|id| format!("A{}", id))
+                .unwrap_or_else(|| agent.id[..8].to_string());
+            println!("ID: {}", display_id.cyan());
+            println!("Name: {}", agent.name);
+        }
+
+        AgentCommands::AgentList => {
+            let agents = db.list_agents()?;
+
+            if agents.is_empty() {
+                println!("{}", "No agents found.".yellow());
+                return Ok(());
+            }
+
+            let time = db.time_display()?;
+            let rows: Vec<AgentRow> = agents
+                .iter()
+                .map(|a| AgentRow {
+                    id: a
+                        .display_id
+                        .map(|id| format!("A{}", id))
+                        .unwrap_or_else(|| a.id[..8].to_string()),
+                    name: a.name.clone(),
+                    status: format_agent_status(&a.status),
+                    current_task: a
+                        .current_task_id
+                        .as_ref()
+                        .and_then(|uuid| {
+                            db.get_task(uuid)
+                                .ok()
+                                .flatten()
+                                .and_then(|t| t.display_id.map(|id| format!("#{}", id)))
+                        })
+                        .unwrap_or_else(|| "-".to_string()),
+                    last_active: time.format(a.last_active),
+                })
+                .collect();
+
+            let mut table = Table::new(rows);
+            table.with(Style::modern());
+            println!("{}", table);
+            println!("\n{} agents total", agents.len().to_string().cyan().bold());
+        }
+
+        AgentCommands::AgentStatus {
+            agent,
+            status,
+            task,
+        } => {
+            let agent_uuid = resolve_agent_id(db.get_connection(), &agent)?;
+            let agent_obj = db.get_agent(&agent_uuid)?;
+
+            match agent_obj {
+                Some(a) => {
+                    let task_uuid = if let Some(task_id) = task {
+                        Some(resolve_task_id(db.get_connection(), &task_id)?)
+                    } else {
+                        None
+                    };
+                    let status = AgentStatus::from_str(&status);
+                    db.update_agent_status(&a.id, status, task_uuid.as_deref())?;
+                    let agent_display = format_agent_id(db.get_connection(), &a.id);
+                    println!(
+                        "{} Agent {} status updated!",
+                        "✓".green().bold(),
+                        agent_display.cyan()
+                    );
+                }
+                None => {
+                    println!("{}", "Agent not found.".red());
+                }
+            }
+        }
+
+        AgentCommands::Queue {
+            agent,
+            limit,
+            offset,
+            json,
+        } => {
+            let agent_uuid = resolve_agent_id(db.get_connection(), &agent)?;
+            let queue = prd_tool::queue::agent_queue(&db, &agent_uuid, offset, limit)?;
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&queue)?);
+            } else {
+                for item in &queue.items {
+                    let id = item
+                        .display_id
+                        .map(|id| format!("#{}", id))
+                        .unwrap_or_else(|| item.id[..8].to_string());
+                    let source = match item.source {
+                        prd_tool::queue::QueueSource::Assigned => "assigned",
+                        prd_tool::queue::QueueSource::Claimable => "claimable",
+                    };
+                    println!("{}\t{}\t{}\t{}", id, source, item.priority, item.title);
+                }
+            }
+        }
+
+        AgentCommands::Agent { action } => match action {
+            AgentAction::Run {
+                name,
+                exec,
+                once,
+                max_tasks,
+                exit_when_idle,
+                poll,
+                epic,
+                priority,
+                lease,
+                timeout,
+                json,
+            } => {
+                use prd_tool::runner::{AgentRunner, Outcome, RunEvent, RunnerConfig};
+                use std::sync::atomic::Ordering;
+
+                let config = RunnerConfig {
+                    command: exec,
+                    epic,
+                    priority: priority.map(|p| db.parse_priority(&p)).transpose()?,
+                    lease,
+                    timeout: timeout.and_then(|t| t.to_std().ok()),
+                    database: Some(ctx.database.to_path_buf()),
+                };
+                let runner = AgentRunner::new(&db, &name, config)?
+                    .with_hooks(prd_tool::hooks::HookExecutor::from_database(&db)?);
+
+                // Ctrl+C interrupts the command too; its task goes back to pending
+                let running = std::sync::Arc::clone(&runner.running);
+                ctrlc::set_handler(move || {
+                    running.store(false, Ordering::SeqCst);
+                })
+                .expect("Error setting Ctrl+C handler");
+
+                let limit = if once { Some(1) } else { max_tasks };
+                let poll = if once || exit_when_idle {
+                    None
+                } else {
+                    Some(std::time::Duration::from_secs(poll))
+                };
+                let mut idle = false;
+                let runs = runner.run(limit, poll, &mut |event| match event {
+                    RunEvent::Claimed(task) if !json => {
+                        idle = false;
+                        println!(
+                            "{} {} {}",
+                            "▶".cyan().bold(),
+                            format!("#{}", task.display_id.unwrap_or_default()).cyan(),
+                            task.title
+                        );
+                    }
+                    RunEvent::Output(line) if !json => println!("  {}", line),
+                    RunEvent::Report(report) if !json => match report {
+                        prd_tool::runner::Report::Progress(pct, message) => println!(
+                            "  {} {}%{}",
+                            "↳".dimmed(),
+                            pct,
+                            message
+                                .as_ref()
+                                .map(|m| format!(" {}", m))
+                                .unwrap_or_default()
+                        ),
+                        prd_tool::runner::Report::Log(message) => {
+                            println!("  {} {}", "↳".dimmed(), message.dimmed())
+                        }
+                        prd_tool::runner::Report::Block(reason) => {
+                            println!("  {} blocked: {}", "↳".dimmed(), reason)
+                        }
+                    },
+                    RunEvent::Finished(run) => {
+                        if json {
+                            if let Ok(line) = serde_json::to_string(run) {
+                                println!("{}", line);
+                            }
+                            return;
+                        }
+                        let id = format!("#{}", run.display_id.unwrap_or_default());
+                        match &run.outcome {
+                            Outcome::Completed => println!(
+                                "{} {} completed in {}s",
+                                "✓".green().bold(),
+                                id,
+                                run.duration_secs
+                            ),
+                            Outcome::Blocked(reason) => {
+                                println!("{} {} blocked: {}", "■".red().bold(), id, reason)
+                            }
+                            Outcome::Released(reason) => println!(
+                                "{} {} back to pending: {}",
+                                "↺".yellow().bold(),
+                                id,
+                                reason
+                            ),
+                        }
+                    }
+                    RunEvent::Idle if !json && !idle => {
+                        idle = true;
+                        if poll.is_some() {
+                            println!("{}", "No tasks ready, waiting...".dimmed());
+                        } else {
+                            println!("{}", "No tasks ready.".yellow());
+                        }
+                    }
+                    _ => {}
+                })?;
+
+                if !json && !runs.is_empty() {
+                    let count =
+                        |f: fn(&Outcome) -> bool| runs.iter().filter(|r| f(&r.outcome)).count();
+                    println!(
+                        "\n{} tasks run: {} completed, {} blocked, {} back to pending",
+                        runs.len(),
+                        count(|o| matches!(o, Outcome::Completed)),
+                        count(|o| matches!(o, Outcome::Blocked(_))),
+                        count(|o| matches!(o, Outcome::Released(_)))
+                    );
+                }
+            }
+            AgentAction::Capacity {
+                agent,
+                max_tasks,
+                windows,
+                clear,
+            } => {
+                let agent_uuid = resolve_agent_id(db.get_connection(), &agent)?;
+                if clear {
+                    db.set_agent_max_tasks(&agent_uuid, None)?;
+                    db.set_agent_windows(&agent_uuid, &[])?;
+                } else {
+                    if max_tasks.is_some() {
+                        db.set_agent_max_tasks(&agent_uuid, max_tasks)?;
+                    }
+                    if !windows.is_empty() {
+                        db.set_agent_windows(&agent_uuid, &windows)?;
+                    }
+                }
+
+                let capacity = db.get_agent_capacity(&agent_uuid)?;
+                println!(
+                    "Agent {}",
+                    format_agent_id(db.get_connection(), &agent_uuid).cyan()
+                );
+                println!(
+                    "  Max tasks: {}",
+                    capacity
+                        .max_tasks
+                        .map(|n| n.to_string())
+                        .unwrap_or_else(|| "wip.agent setting".dimmed().to_string())
+                );
+                let available = if capacity.windows.is_empty() {
+                    "any time".dimmed().to_string()
+                } else {
+                    capacity.windows_str()
+                };
+                println!("  Available: {}", available);
+            }
+        },
+
+        AgentCommands::IngestTranscript {
+            path,
+            agent,
+            epic,
+            task,
+            llm,
+            json,
+        } => {
+            use prd_tool::transcript::{IngestAction, IngestOptions, TranscriptConfig};
+
+            let content = std::fs::read_to_string(&path)
+                .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+            let items = TranscriptConfig::load()?.extract(&content, llm)?;
+
+            let agent_id = match agent {
+                Some(agent) => match resolve_agent_id(db.get_connection(), &agent) {
+                    Ok(id) => Some(id),
+                    Err(_) if dry_run => None,
+                    Err(_) => Some(db.create_agent(agent)?.id),
+                },
+                None => None,
+            };
+            let decision_task = match task {
+                Some(task) => Some(resolve_task_id(db.get_connection(), &task)?),
+                None => None,
+            };
+            let options = IngestOptions {
+                source: path.display().to_string(),
+                agent_id,
+                epic,
+                decision_task,
+                dry_run,
+            };
+            let results = prd_tool::transcript::ingest(&db, &items, &options)?;
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&results)?);
+                return Ok(());
+            }
+            if results.is_empty() {
+                println!("No work items found in {}", path.display());
+            }
+            for result in &results {
+                let action = match result.action {
+                    IngestAction::Created => "created".green(),
+                    IngestAction::Completed => "completed".green(),
+                    IngestAction::Logged => "logged".cyan(),
+                    IngestAction::Skipped => "skipped".dimmed(),
+                };
+                let task_display = result
+                    .task_id
+                    .as_deref()
+                    .map(|id| format_task_id(db.get_connection(), id))
+                    .unwrap_or_else(|| "-".to_string());
+                println!(
+                    "{}\t{}\t{}\t{}",
+                    action,
+                    result.item.kind.as_str(),
+                    task_display,
+                    result.item.title
+                );
+            }
+            if dry_run {
+                println!("\n{}", "Dry run: nothing was changed".dimmed());
+            }
+        }
+
+        AgentCommands::ReportProgress {
+            agent,
+            task_id,
+            progress,
+            message,
+        } => {
+            // Resolve agent ID (supports A12, 12, name, or UUID)
+            let agent_uuid = resolve_agent_id(db.get_connection(), &agent)?;
+
+            // Resolve task ID (supports #37, 37, or UUID)
+            let task_display_id = if task_id.starts_with('#') {
+                task_id[1..]
+                    .parse::<i32>()
+                    .map_err(|_| anyhow::anyhow!("Invalid task ID format"))?
+            } else {
+                task_id
+                    .parse::<i32>()
+                    .map_err(|_| anyhow::anyhow!("Invalid task ID format"))?
+            };
+
+            // Report progress
+            db.report_progress(&agent_uuid, task_display_id, progress, message)?;
+
+            // Get agent display ID for output
+            let agent_display = format_agent_id(db.get_connection(), &agent_uuid);
+
+            println!(
+                "{} Progress updated: {} @ {}%",
+                "✓".green().bold(),
+                agent_display.cyan(),
+                progress.to_string().cyan()
+            );
+        }
+
+        AgentCommands::ReportError {
+            agent,
+            task_id,
+            message,
+            kind,
+        } => {
+            let kind = db::AgentErrorKind::parse(&kind)?;
+            let agent_uuid = resolve_agent_id(db.get_connection(), &agent)?;
+            let task_uuid = resolve_task_id(db.get_connection(), &task_id)?;
+            db.report_error(&agent_uuid, &task_uuid, kind, &message)?;
+
+            println!(
+                "{} Error reported: {} on {} ({})",
+                "✓".green().bold(),
+                format_agent_id(db.get_connection(), &agent_uuid).cyan(),
+                format_task_id(db.get_connection(), &task_uuid).cyan(),
+                kind.as_str()
+            );
+        }
+
+        AgentCommands::ProgressPolicy {
+            agent,
+            min_delta,
+            window,
+            clear,
+        } => {
+            let agent_uuid = resolve_agent_id(db.get_connection(), &agent)?;
+            let agent_display = format_agent_id(db.get_connection(), &agent_uuid);
+
+            if clear {
+                if db.clear_progress_policy(&agent_uuid)? {
+                    println!(
+                        "{} Progress policy removed for {}",
+                        "✓".green().bold(),
+                        agent_display.cyan()
+                    );
+                } else {
+                    println!("No progress policy set for {}", agent_display.cyan());
+                }
+                return Ok(());
+            }
+
+            let existing = db.get_progress_policy(&agent_uuid)?;
+            if min_delta.is_none() && window.is_none() {
+                match existing {
+                    Some(p) => println!(
+                        "{}: coalesce changes < {}% within {}s",
+                        agent_display.cyan(),
+                        p.min_delta,
+                        p.window_secs
+                    ),
+                    None => println!(
+                        "No progress policy set for {} (every report is recorded)",
+                        agent_display.cyan()
+                    ),
+                }
+                return Ok(());
+            }
+
+            let min_delta = min_delta
+                .or(existing.as_ref().map(|p| p.min_delta))
+                .unwrap_or(5);
+            let window = window
+                .or(existing.as_ref().map(|p| p.window_secs))
+                .unwrap_or(60);
+            db.set_progress_policy(&agent_uuid, min_delta, window)?;
+            println!(
+                "{} {} will coalesce progress changes < {}% within {}s",
+                "✓".green().bold(),
+                agent_display.cyan(),
+                min_delta,
+                window
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn format_agent_status(status: &AgentStatus) -> String {
+    match status {
+        AgentStatus::Idle => "Idle".dimmed().to_string(),
+        AgentStatus::Working => "Working".green().bold().to_string(),
+        AgentStatus::Blocked => "Blocked".red().to_string(),
+        AgentStatus::Offline => "Offline".dimmed().to_string(),
+    }
+}
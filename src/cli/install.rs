@@ -0,0 +1,196 @@
+//! Install - commands that run before, or without, the task database:
+//! `prd init`, `prd setup`, `prd version` and `prd self-update`
+
+use anyhow::Result;
+use clap::Subcommand;
+use colored::*;
+use db::Database;
+use prd_tool::db;
+use std::path::Path;
+
+use crate::migrations::MigrationRunner;
+use crate::setup;
+
+/// Commands that open the database themselves, if at all
+#[derive(Subcommand)]
+pub enum InstallCommands {
+    /// Initialize a new PRD database
+    Init {
+        /// Force re-initialization (drops existing data)
+        #[arg(short, long)]
+        force: bool,
+        /// Seed epics, tasks and criteria from a preset name or TOML/YAML file
+        /// (presets: feature-launch, service-migration, or ~/.prd/templates/<name>)
+        #[arg(short, long)]
+        template: Option<String>,
+    },
+
+    /// Guided setup: database, config, git hook, docs directory, agents and index
+    Setup {
+        /// Accept the defaults without prompting
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Show the prd version
+    Version {
+        /// Compare with the latest release; exits 1 when outdated (for CI)
+        #[arg(long)]
+        check: bool,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Update prd to the latest release (checked against its unsigned SHA256SUMS)
+    SelfUpdate {
+        /// Reinstall the latest release even if already up to date
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+/// Runs an install command; `database` may not exist yet
+pub fn run(command: InstallCommands, database: &Path) -> Result<()> {
+    match command {
+        InstallCommands::Init { force, template } => {
+            init_database(database, force, template.as_deref())?;
+            println!("\n{}", "Database initialized successfully!".green().bold());
+            println!("You can now use:");
+            println!("  prd create \"My first task\"");
+            println!("  prd agent-create \"my-agent\"");
+            Ok(())
+        }
+        InstallCommands::Setup { yes } => setup::Setup::new(yes).run(database),
+        InstallCommands::Version { check, json } => print_version(check, json),
+        InstallCommands::SelfUpdate { force } => self_update(force),
+    }
+}
+
+/// Create (or with `force`, recreate) the database at `path`, mark the base
+/// schema's migrations applied and seed it from `template`
+pub fn init_database(path: &Path, force: bool, template: Option<&str>) -> Result<()> {
+    use std::fs;
+
+    let db_path = path.to_str().unwrap();
+
+    if path.exists() && !force {
+        return Err(anyhow::anyhow!(
+            "Database already exists at {}. Use --force to reinitialize.",
+            db_path
+        ));
+    }
+
+    // Load the template first so a typo doesn't cost the existing database
+    let template = template
+        .map(prd_tool::templates::ProjectTemplate::resolve)
+        .transpose()?;
+
+    if path.exists() && force {
+        println!("{} Removing existing database...", "⚠".yellow());
+        fs::remove_file(path)?;
+    }
+
+    println!("{} Creating new database at {}...", "✓".green(), db_path);
+
+    // Create parent directories if needed
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    // Create and initialize database
+    let new_db = Database::new(db_path)?;
+    println!("{} Database schema initialized", "✓".green());
+
+    // Mark migrations that are already in base schema as applied
+    let conn = new_db.get_connection();
+    let runner = MigrationRunner::new(conn);
+    runner.init()?;
+
+    // Mark all migrations 001-007 as applied (base schema includes all features)
+    let base_schema_migrations = vec![1, 2, 3, 4, 5, 6, 7];
+    for version in base_schema_migrations {
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_migrations (version, applied_at) VALUES (?1, datetime('now'))",
+            [version],
+        )?;
+    }
+
+    // Check for and run any new migrations beyond 007
+    println!("{} Running migrations...", "✓".green());
+    let applied = runner.migrate_to_latest()?;
+    println!(
+        "{} Applied {} migration(s)",
+        "✓".green().bold(),
+        applied.len()
+    );
+
+    if let Some(template) = template {
+        let summary = template.apply(&new_db)?;
+        println!(
+            "{} Applied template '{}': {} epics, {} tasks, {} criteria, {} dependencies",
+            "✓".green().bold(),
+            template.name,
+            summary.epics,
+            summary.tasks,
+            summary.criteria,
+            summary.dependencies
+        );
+    }
+
+    Ok(())
+}
+
+fn print_version(check: bool, json: bool) -> Result<()> {
+    use prd_tool::self_update::{self, CURRENT_VERSION};
+
+    if !check {
+        if json {
+            println!("{}", serde_json::json!({ "version": CURRENT_VERSION }));
+        } else {
+            println!("prd {}", CURRENT_VERSION);
+        }
+        return Ok(());
+    }
+
+    let version = self_update::check()?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&version)?);
+    } else if version.update_available {
+        println!(
+            "{} prd {} is out of date: {} is available (prd self-update)",
+            "⚠".yellow(),
+            version.current,
+            version.latest.cyan().bold()
+        );
+    } else {
+        println!(
+            "{} prd {} is up to date",
+            "✓".green().bold(),
+            version.current
+        );
+    }
+    if version.update_available {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn self_update(force: bool) -> Result<()> {
+    use prd_tool::self_update::UpdateOutcome;
+
+    println!("{} Checking for updates...", "ℹ".blue().bold());
+    match prd_tool::self_update::self_update(force)? {
+        UpdateOutcome::UpToDate { version } => {
+            println!("{} prd {} is up to date", "✓".green().bold(), version);
+        }
+        UpdateOutcome::Updated { from, to, path } => println!(
+            "{} Updated prd {} → {} ({})",
+            "✓".green().bold(),
+            from,
+            to.cyan().bold(),
+            path.display()
+        ),
+    }
+    Ok(())
+}
@@ -0,0 +1,443 @@
+//! CLI - the `prd` command line: argument definitions and their handlers
+//!
+//! Commands are grouped by area, one module each; every group is a
+//! subcommand enum flattened into [`Commands`] and a `run` that handles it.
+//! `main` only parses arguments, builds the middleware pipeline and hands
+//! the parsed command to [`execute`].
+
+mod admin;
+mod agents;
+mod install;
+mod planning;
+mod reports;
+mod sync;
+mod tasks;
+mod vector;
+mod workflow;
+
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::{Parser, Subcommand};
+use colored::*;
+use db::{Database, Priority, TaskStatus};
+use output::emoji;
+use prd_tool::{db, output, resolver};
+use resolver::format_task_id;
+use std::path::{Path, PathBuf};
+use tabled::{settings::Style, Table, Tabled};
+
+use crate::middleware::Invocation;
+use admin::{AdminCommands, DbAction};
+use agents::{AgentAction, AgentCommands};
+use install::InstallCommands;
+use planning::{EpicAction, PlanningCommands, SprintAction};
+use reports::{ExportAction, ReportCommands};
+use sync::{HooksSubcommand, SyncCommands, SyncDbAction};
+use tasks::TaskCommands;
+use vector::VectorCommands;
+use workflow::WorkflowCommands;
+
+pub use install::init_database;
+
+#[derive(Parser)]
+#[command(name = "prd")]
+#[command(about = "PRD Tool - Agent Task Management and Synchronization", long_about = None)]
+pub struct Cli {
+    #[arg(short, long, default_value = "tools/prd.db")]
+    pub database: PathBuf,
+
+    /// Disable colors and emoji (also set by NO_COLOR)
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
+    /// Report how long the command took
+    #[arg(long, global = true)]
+    pub timing: bool,
+
+    /// Run the command against a copy of the database and discard its changes
+    ///
+    /// Commands with a preview of their own (archive, verify, complete-batch,
+    /// sync-docs, ingest-transcript, db merge, epic assign, sprint plan)
+    /// show that instead; commands that change files, the binary or another
+    /// replica, run shell commands or send notifications refuse it.
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+
+    #[command(subcommand)]
+    pub command: Commands,
+}
+
+#[derive(Subcommand)]
+pub enum Commands {
+    #[command(flatten)]
+    Tasks(TaskCommands),
+
+    #[command(flatten)]
+    Workflow(WorkflowCommands),
+
+    #[command(flatten)]
+    Agents(AgentCommands),
+
+    #[command(flatten)]
+    Planning(PlanningCommands),
+
+    #[command(flatten)]
+    Reports(ReportCommands),
+
+    #[command(flatten)]
+    Sync(SyncCommands),
+
+    /// Semantic vector search and indexing
+    #[command(alias = "vec")]
+    Vector {
+        #[command(subcommand)]
+        action: VectorCommands,
+    },
+
+    #[command(flatten)]
+    Admin(AdminCommands),
+
+    #[command(flatten)]
+    Install(InstallCommands),
+}
+
+impl Commands {
+    /// Whether the command previews `--dry-run` itself rather than running
+    /// against a throwaway copy of the database
+    pub fn previews_dry_run(&self) -> bool {
+        matches!(
+            self,
+            Commands::Workflow(
+                WorkflowCommands::Archive { .. }
+                    | WorkflowCommands::Verify { .. }
+                    | WorkflowCommands::CompleteBatch { .. }
+            ) | Commands::Sync(SyncCommands::SyncDocs { revert: None, .. })
+                | Commands::Agents(AgentCommands::IngestTranscript { .. })
+                | Commands::Admin(AdminCommands::Db {
+                    action: DbAction::Merge { .. }
+                })
+                | Commands::Planning(
+                    PlanningCommands::Epic {
+                        action: EpicAction::Assign { .. }
+                    } | PlanningCommands::Sprint {
+                        action: SprintAction::Plan { .. }
+                    }
+                )
+        )
+    }
+
+    /// Whether the command has effects beyond the database, which a dry run
+    /// against a copy of it can't hold back
+    pub fn writes_outside_database(&self) -> bool {
+        match self {
+            Commands::Install(
+                InstallCommands::Setup { .. } | InstallCommands::SelfUpdate { .. },
+            )
+            | Commands::Sync(SyncCommands::Docgen { .. } | SyncCommands::WatchFiles { .. }) => true,
+            Commands::Sync(SyncCommands::SyncDb { action }) => {
+                matches!(action, SyncDbAction::Push { .. })
+            }
+            Commands::Sync(SyncCommands::Hooks { subcommand }) => !matches!(
+                subcommand,
+                HooksSubcommand::List | HooksSubcommand::Test { .. } | HooksSubcommand::Push { .. }
+            ),
+            Commands::Reports(ReportCommands::Badge { out, .. }) => out.is_some(),
+            Commands::Reports(ReportCommands::Export { action }) => match action {
+                ExportAction::Org { output, .. } | ExportAction::Graph { output, .. } => {
+                    output.is_some()
+                }
+            },
+            Commands::Reports(ReportCommands::Watch { summary, .. }) => summary.is_some(),
+            Commands::Sync(SyncCommands::InstallGitHook { status, .. }) => !status,
+            // Runs the user's command on each claimed task
+            Commands::Agents(AgentCommands::Agent { action }) => {
+                matches!(action, AgentAction::Run { .. })
+            }
+            Commands::Planning(PlanningCommands::Stale { nudge, .. }) => *nudge,
+            // Snapshots live next to the database, not next to its copy
+            Commands::Admin(AdminCommands::Snapshot { .. }) => true,
+            _ => false,
+        }
+    }
+}
+
+/// What a handler needs from the invocation besides its own arguments
+pub struct Context<'a> {
+    /// Database path; under `--dry-run`, the throwaway copy
+    pub database: &'a Path,
+    pub dry_run: bool,
+    pub invocation: &'a Invocation,
+}
+
+/// Opens the database and runs the command in the group that defines it
+pub fn execute(cli: Cli, invocation: &Invocation) -> Result<()> {
+    // Init, setup, version and self-update open the database themselves, if at all
+    let command = match cli.command {
+        Commands::Install(command) => return install::run(command, &cli.database),
+        command => command,
+    };
+
+    let db = Database::new(cli.database.to_str().unwrap())?;
+    db.record_operation(&invocation.command)?;
+
+    let ctx = Context {
+        database: &cli.database,
+        dry_run: cli.dry_run,
+        invocation,
+    };
+    match command {
+        Commands::Tasks(command) => tasks::run(command, db, &ctx),
+        Commands::Workflow(command) => workflow::run(command, db, &ctx),
+        Commands::Agents(command) => agents::run(command, db, &ctx),
+        Commands::Planning(command) => planning::run(command, db, &ctx),
+        Commands::Reports(command) => reports::run(command, db, &ctx),
+        Commands::Sync(command) => sync::run(command, db, &ctx),
+        Commands::Vector { action } => vector::run(action, db, &ctx),
+        Commands::Admin(command) => admin::run(command, db, &ctx),
+        Commands::Install(_) => unreachable!("Handled before the database is opened"),
+    }
+}
+
+#[derive(Tabled)]
+struct DiffRow {
+    #[tabled(rename = "")]
+    change: String,
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "Title")]
+    title: String,
+    #[tabled(rename = "Changes")]
+    details: String,
+}
+
+fn format_status(status: &TaskStatus) -> String {
+    match status {
+        TaskStatus::Pending => "○ Pending".white().to_string(),
+        TaskStatus::InProgress => "◐ In Progress".blue().bold().to_string(),
+        TaskStatus::Blocked => "■ Blocked".red().bold().to_string(),
+        TaskStatus::Review => "◇ Review".yellow().to_string(),
+        TaskStatus::Completed => "● Completed".green().bold().to_string(),
+        TaskStatus::Failed => "✗ Failed".red().to_string(),
+        TaskStatus::Cancelled => "✕ Cancelled".dimmed().to_string(),
+        TaskStatus::Custom(name) => format!("◆ {}", name).magenta().to_string(),
+    }
+}
+
+/// Format a completion estimate, e.g. "~1h 20m" or "stalled 45m"
+/// Parse a lease duration such as "90s", "30m", "2h" or "1d"
+/// Announce the instance a recurring task came back as, if any
+fn print_next_occurrence(db: &Database, task_id: &str) -> Result<()> {
+    if let Some(next) = db.next_occurrence(task_id)? {
+        println!(
+            "{} Next occurrence: {} due {}",
+            "ℹ".blue().bold(),
+            format_task_id(db.get_connection(), &next.id).cyan(),
+            next.due_date.map(|d| d.to_string()).unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+fn parse_ttl(s: &str) -> Result<chrono::Duration, String> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (value, unit) = s.split_at(split);
+    let value: i64 = value
+        .parse()
+        .map_err(|_| format!("invalid duration '{}' (e.g. 30m, 2h, 1d)", s))?;
+
+    match unit {
+        "s" => Ok(chrono::Duration::seconds(value)),
+        "m" | "" => Ok(chrono::Duration::minutes(value)),
+        "h" => Ok(chrono::Duration::hours(value)),
+        "d" => Ok(chrono::Duration::days(value)),
+        _ => Err(format!(
+            "invalid duration unit '{}' (use s, m, h or d)",
+            unit
+        )),
+    }
+}
+
+/// Parse a due date: a day, or a span such as "3d" counted forward from today
+fn parse_due_date(s: &str) -> Result<NaiveDate, String> {
+    let s = s.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(date);
+    }
+    parse_ttl(s)
+        .map(|span| (Utc::now() + span).date_naive())
+        .map_err(|_| format!("invalid date '{}' (use YYYY-MM-DD or a span like 3d)", s))
+}
+
+/// Parse a date bound: a day (midnight UTC), an RFC 3339 timestamp, or an
+/// age such as "7d" counted back from now
+fn parse_date_bound(s: &str) -> Result<DateTime<Utc>, String> {
+    let s = s.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap().and_utc());
+    }
+    if let Ok(at) = DateTime::parse_from_rfc3339(s) {
+        return Ok(at.with_timezone(&Utc));
+    }
+    parse_ttl(s).map(|age| Utc::now() - age).map_err(|_| {
+        format!(
+            "invalid date '{}' (use YYYY-MM-DD, RFC 3339 or an age like 7d)",
+            s
+        )
+    })
+}
+
+/// Table of tasks added, removed and changed, with a count line
+pub fn format_task_diff(diff: &prd_tool::diff::TaskDiff) -> String {
+    use prd_tool::diff::TaskState;
+
+    let id = |t: &TaskState| {
+        t.display_id
+            .map(|id| format!("#{}", id))
+            .unwrap_or_else(|| t.id[..8].to_string())
+    };
+    let summary = |t: &TaskState| {
+        format!(
+            "{}, {}{}",
+            t.status,
+            t.priority,
+            t.assignee
+                .as_ref()
+                .map(|a| format!(", {}", a))
+                .unwrap_or_default()
+        )
+    };
+    let mut rows = Vec::new();
+    for task in &diff.added {
+        rows.push(DiffRow {
+            change: "+".green().to_string(),
+            id: id(task),
+            title: task.title.clone(),
+            details: summary(task),
+        });
+    }
+    for task in &diff.removed {
+        rows.push(DiffRow {
+            change: "-".red().to_string(),
+            id: id(task),
+            title: task.title.clone(),
+            details: summary(task),
+        });
+    }
+    for change in &diff.changed {
+        let details: Vec<String> = change
+            .changes
+            .iter()
+            .map(|c| {
+                format!(
+                    "{}: {} → {}",
+                    c.field,
+                    c.before.as_deref().unwrap_or("-"),
+                    c.after.as_deref().unwrap_or("-")
+                )
+            })
+            .collect();
+        rows.push(DiffRow {
+            change: "~".yellow().to_string(),
+            id: id(&change.task),
+            title: change.task.title.clone(),
+            details: details.join("\n"),
+        });
+    }
+    let mut table = Table::new(rows);
+    table.with(Style::modern());
+    format!(
+        "{}\n\n{} added, {} removed, {} changed",
+        table,
+        diff.added.len(),
+        diff.removed.len(),
+        diff.changed.len()
+    )
+}
+
+/// Whether the current actor holds the role that approves changes to guarded
+/// tasks (and so changes them directly)
+fn is_approver(db: &Database) -> Result<bool> {
+    let Some(actor) = db.actor() else {
+        return Ok(false);
+    };
+    let role = db.approval_policy()?.role;
+    let permissions = prd_tool::permissions::PermissionsConfig::load()?;
+    Ok(permissions.role_of(actor).as_str() == role)
+}
+
+/// Queue a change to a guarded task for approval instead of applying it,
+/// reporting the change request; returns whether it was held
+fn held_for_approval(
+    db: &Database,
+    task_uuid: &str,
+    field: db::ChangeField,
+    value: &str,
+    agent: Option<&str>,
+) -> Result<bool> {
+    match db.guard_change(task_uuid, field, value, agent, is_approver(db)?) {
+        Ok(()) => Ok(false),
+        Err(e) => match e.downcast_ref::<db::ApprovalRequired>() {
+            Some(held) => {
+                println!("{} {}", emoji("⏳"), held);
+                Ok(true)
+            }
+            None => Err(e),
+        },
+    }
+}
+
+/// Warn about WIP limits a forced change went over
+fn print_wip_warnings(exceeded: &[db::WipViolation]) {
+    for violation in exceeded {
+        println!("{} Over WIP limit: {}", "⚠".yellow(), violation);
+    }
+}
+
+fn format_priority(priority: &Priority) -> String {
+    match priority {
+        Priority::Low => "Low".dimmed().to_string(),
+        Priority::Medium => "Medium".normal().to_string(),
+        Priority::High => "High".yellow().bold().to_string(),
+        Priority::Critical => "Critical".red().bold().to_string(),
+        Priority::Custom(name) => name.bold().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses on a thread with a main-sized stack; clap's derive for
+    /// `Commands` outgrows the default test thread stack in debug builds
+    fn parse(args: &[&str]) -> Commands {
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        std::thread::Builder::new()
+            .stack_size(8 * 1024 * 1024)
+            .spawn(move || {
+                Cli::try_parse_from(std::iter::once("prd".to_string()).chain(args))
+                    .unwrap()
+                    .command
+            })
+            .unwrap()
+            .join()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_dry_run_refused_outside_database() {
+        for args in [
+            &["export", "org", "--output", "tasks.org"][..],
+            &["agent", "run", "worker", "--exec", "deploy.sh"],
+            &["stale", "--nudge"],
+            &["snapshot", "create", "before-replan"],
+            &["snapshot", "restore", "before-replan"],
+            &["snapshot", "list"],
+        ] {
+            assert!(parse(args).writes_outside_database(), "{:?}", args);
+        }
+        for args in [&["export", "org"][..], &["stale"], &["list"]] {
+            assert!(!parse(args).writes_outside_database(), "{:?}", args);
+        }
+    }
+}
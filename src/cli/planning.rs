@@ -0,0 +1,892 @@
+//! Planning - epics, releases, sprints, due dates and custom statuses
+
+use anyhow::Result;
+use chrono::{NaiveDate, Utc};
+use clap::Subcommand;
+use colored::*;
+use db::Database;
+use db_extensions::EpicDependencyOps;
+use prd_tool::{db, db_extensions, resolver};
+use resolver::{format_task_id, resolve_task_id};
+use std::path::PathBuf;
+use tabled::{settings::Style, Table, Tabled};
+
+use super::{parse_due_date, Context};
+
+/// Commands for planning work over time
+#[derive(Subcommand)]
+pub enum PlanningCommands {
+    /// List tasks that have stopped moving
+    ///
+    /// In-progress tasks with no log entries or progress reports, and blocked
+    /// tasks whose dependencies all finished, for at least --days days.
+    Stale {
+        /// Days without activity before a task counts as stale
+        #[arg(long, default_value_t = prd_tool::stale::DEFAULT_STALE_DAYS)]
+        days: i64,
+        /// Send each owning agent a notification and log the nudge on the task
+        #[arg(long)]
+        nudge: bool,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// List unfinished tasks that are overdue or due soon
+    Due {
+        /// Days ahead a due date counts as due soon
+        #[arg(long, default_value_t = prd_tool::due::DEFAULT_DUE_SOON_DAYS)]
+        days: i64,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// List all epics with task counts
+    Epics,
+
+    /// Manage epic membership
+    Epic {
+        #[command(subcommand)]
+        action: EpicAction,
+    },
+
+    /// Manage prefixed task key sequences (API-42)
+    Ids {
+        #[command(subcommand)]
+        action: IdsAction,
+    },
+
+    /// Plan and ship releases of epics and tasks
+    Release {
+        #[command(subcommand)]
+        action: ReleaseAction,
+    },
+
+    /// Set an epic's target date to track projected completion against
+    EpicTarget {
+        /// Epic name
+        epic: String,
+        /// Target date (YYYY-MM-DD)
+        #[arg(required_unless_present = "clear")]
+        date: Option<NaiveDate>,
+        /// Remove the target date
+        #[arg(long, conflicts_with = "date")]
+        clear: bool,
+    },
+
+    /// Plan sprints against their capacity in story points
+    Sprint {
+        #[command(subcommand)]
+        action: SprintAction,
+    },
+
+    /// Manage custom statuses (e.g., qa, deployed)
+    Status {
+        #[command(subcommand)]
+        action: StatusAction,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum StatusAction {
+    /// Add a status, mapped to a lifecycle bucket
+    ///
+    /// Tasks in a "done" status count as completed in stats, progress and
+    /// dependency checks; "open" and "active" statuses are not started and in
+    /// progress respectively.
+    Add {
+        /// Status name (letters, digits and '_')
+        name: String,
+        /// Lifecycle bucket: open, active or done
+        #[arg(short, long, default_value = "active", value_parser = db::StatusBucket::parse)]
+        bucket: db::StatusBucket,
+    },
+    /// List built-in and custom statuses with their buckets
+    List {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Remove a custom status no task is using
+    Remove {
+        /// Status name
+        name: String,
+    },
+    /// Allow tasks to move from one status to others
+    ///
+    /// Once any transition is allowed, status changes that aren't are
+    /// refused unless given --force.
+    Allow {
+        /// Status moved from
+        from: String,
+        /// Statuses it may move to
+        #[arg(required = true)]
+        to: Vec<String>,
+    },
+    /// Stop allowing moves from one status to others
+    Disallow {
+        /// Status moved from
+        from: String,
+        /// Statuses it may no longer move to
+        #[arg(required = true)]
+        to: Vec<String>,
+    },
+    /// Show the allowed transitions, or reset them
+    Transitions {
+        /// Replace the transitions with the default workflow
+        #[arg(long, conflicts_with = "clear")]
+        preset: bool,
+        /// Remove every transition so any status change is allowed
+        #[arg(long)]
+        clear: bool,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum EpicAction {
+    /// Put every task whose title or description matches a pattern into an epic
+    Assign {
+        /// Glob pattern, case-insensitive (e.g. "Firestore*")
+        #[arg(long = "match")]
+        pattern: String,
+        /// Epic to assign
+        #[arg(long)]
+        epic: String,
+        /// Also move tasks that already belong to another epic
+        #[arg(long)]
+        replace: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum IdsAction {
+    /// Set the next number handed out for a prefix (creates the prefix)
+    Set {
+        /// Prefix, e.g. "API"
+        prefix: String,
+        /// Next number to hand out
+        #[arg(long, default_value_t = 1)]
+        next: i64,
+    },
+    /// List prefixes and their next numbers
+    List,
+    /// Give an existing task a key, e.g. an imported issue's identifier
+    Key {
+        /// Task ID
+        id: String,
+        /// Key such as "IOS-17", or just a prefix for the next key in its sequence
+        key: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ReleaseAction {
+    /// Create a release from epics and individual tasks
+    Create {
+        /// Version, e.g. "1.2.0"
+        version: String,
+        /// Target date (YYYY-MM-DD)
+        #[arg(long)]
+        target: Option<NaiveDate>,
+        /// Epic to include (repeatable)
+        #[arg(long = "epic")]
+        epics: Vec<String>,
+        /// Task to include on its own (repeatable)
+        #[arg(long = "task")]
+        tasks: Vec<String>,
+    },
+    /// Show progress and what still blocks a release (all releases if omitted)
+    Status {
+        version: Option<String>,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Close a release once all its tasks are done
+    Close {
+        version: String,
+        /// Close even with unfinished tasks
+        #[arg(long)]
+        force: bool,
+    },
+    /// Print a Markdown changelog of the release's completed tasks
+    Changelog {
+        version: String,
+        /// Write to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SprintAction {
+    /// Create the next sprint
+    Create {
+        /// First day (YYYY-MM-DD, or a span from today such as 3d)
+        #[arg(long, value_parser = parse_due_date)]
+        start: NaiveDate,
+        /// Last day (YYYY-MM-DD, or a span from today such as 14d)
+        #[arg(long, value_parser = parse_due_date)]
+        end: NaiveDate,
+        /// Story points the sprint can take
+        #[arg(long)]
+        capacity: Option<u32>,
+        /// Sprint goal
+        #[arg(long)]
+        goal: Option<String>,
+    },
+    /// List sprints with their planned points
+    List {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Set how many story points a sprint can take
+    Capacity {
+        /// Sprint number
+        number: i32,
+        /// Story points
+        points: u32,
+    },
+    /// Fill a sprint from the prioritized backlog up to its capacity
+    Plan {
+        /// Sprint number
+        number: i32,
+        /// Plan against this capacity instead of the sprint's
+        #[arg(long)]
+        capacity: Option<u32>,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Tabled)]
+struct SprintRow {
+    #[tabled(rename = "Sprint")]
+    number: i32,
+    #[tabled(rename = "Dates")]
+    dates: String,
+    #[tabled(rename = "Points")]
+    points: String,
+    #[tabled(rename = "Tasks")]
+    tasks: String,
+    #[tabled(rename = "Goal")]
+    goal: String,
+}
+
+#[derive(Tabled)]
+struct StaleRow {
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "Title")]
+    title: String,
+    #[tabled(rename = "Status")]
+    status: String,
+    #[tabled(rename = "Agent")]
+    agent: String,
+    #[tabled(rename = "Why")]
+    reason: String,
+}
+
+#[derive(Tabled)]
+struct DueRow {
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "Title")]
+    title: String,
+    #[tabled(rename = "Due")]
+    due: String,
+    #[tabled(rename = "Agent")]
+    agent: String,
+    #[tabled(rename = "When")]
+    when: String,
+}
+
+/// Runs a planning command against the open database
+pub fn run(command: PlanningCommands, db: Database, ctx: &Context) -> Result<()> {
+    let dry_run = ctx.dry_run;
+
+    match command {
+        PlanningCommands::Stale { days, nudge, json } => {
+            let now = Utc::now();
+            let stale = prd_tool::stale::find_stale(&db, days, now)?;
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&stale)?);
+            } else if stale.is_empty() {
+                println!(
+                    "{} No stale tasks (nothing idle for {} days)",
+                    "✓".green().bold(),
+                    days
+                );
+            } else {
+                let rows: Vec<StaleRow> = stale
+                    .iter()
+                    .map(|s| StaleRow {
+                        id: format!("#{}", s.task.display_id.unwrap_or_default()),
+                        title: s.task.title.clone(),
+                        status: s.task.status.as_str().to_string(),
+                        agent: s.agent.clone().unwrap_or_else(|| "-".to_string()),
+                        reason: s.reason.describe(now),
+                    })
+                    .collect();
+                println!("{}", Table::new(rows).with(Style::modern()));
+                println!(
+                    "
+{} stale task(s)",
+                    stale.len().to_string().yellow().bold()
+                );
+            }
+
+            if nudge && !stale.is_empty() {
+                let mut notifier = prd_tool::notifications::Notifier::with_shared_config(&db)?;
+                let sent = prd_tool::stale::nudge(&db, &stale, &mut notifier)?;
+                if !json {
+                    println!("{} Nudged {} agent task(s)", "✓".green().bold(), sent);
+                }
+            }
+        }
+
+        PlanningCommands::Due { days, json } => {
+            let today = Utc::now().date_naive();
+            let due = prd_tool::due::find_due(&db, today, days)?;
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&due)?);
+            } else if due.is_empty() {
+                println!(
+                    "{} Nothing overdue or due in the next {} days",
+                    "✓".green().bold(),
+                    days
+                );
+            } else {
+                let rows: Vec<DueRow> = due
+                    .iter()
+                    .map(|d| DueRow {
+                        id: format!("#{}", d.task.display_id.unwrap_or_default()),
+                        title: d.task.title.clone(),
+                        due: d.due.to_string(),
+                        agent: d.agent.clone().unwrap_or_else(|| "-".to_string()),
+                        when: if d.is_overdue() {
+                            d.describe().red().to_string()
+                        } else {
+                            d.describe().yellow().to_string()
+                        },
+                    })
+                    .collect();
+                println!("{}", Table::new(rows).with(Style::modern()));
+                let overdue = due.iter().filter(|d| d.is_overdue()).count();
+                println!(
+                    "\n{} overdue, {} due soon",
+                    overdue.to_string().red().bold(),
+                    (due.len() - overdue).to_string().yellow().bold()
+                );
+            }
+        }
+
+        PlanningCommands::Epics => {
+            let today = Utc::now().date_naive();
+            let epics = db.epic_forecasts(today)?;
+            let gates = db.get_connection().epic_gates()?;
+
+            if epics.is_empty() {
+                println!("{}", "No epics found.".yellow());
+                return Ok(());
+            }
+
+            println!("\n{}", "Epics".bold().underline());
+            for epic in epics {
+                let progress = if epic.total > 0 {
+                    (epic.completed as f32 / epic.total as f32) * 100.0
+                } else {
+                    0.0
+                };
+                println!(
+                    "{} - {}/{} tasks ({:.0}%)",
+                    epic.epic.cyan().bold(),
+                    epic.completed,
+                    epic.total,
+                    progress
+                );
+                if epic.points > 0 {
+                    let points = format!("{}/{} points", epic.completed_points, epic.points);
+                    println!("  {}", points.dimmed());
+                }
+                if let Some(target) = epic.target {
+                    let projection = match (epic.projected, epic.variance_days()) {
+                        _ if epic.is_done() => "done".to_string(),
+                        (Some(projected), Some(days)) => {
+                            format!("projected {} ({:+}d)", projected, days)
+                        }
+                        _ => "no recent velocity".to_string(),
+                    };
+                    let line = format!("  target {} · {}", target, projection);
+                    if epic.is_slipping(today) {
+                        println!("{}", line.red());
+                    } else {
+                        println!("{}", line.green());
+                    }
+                }
+                for gate in gates.iter().filter(|g| g.epic == epic.epic && !g.met) {
+                    println!(
+                        "  {} waiting on {} ({}% of {}%)",
+                        "⏸".yellow(),
+                        gate.depends_on,
+                        gate.progress,
+                        gate.threshold
+                    );
+                }
+            }
+        }
+
+        PlanningCommands::Epic {
+            action:
+                EpicAction::Assign {
+                    pattern,
+                    epic,
+                    replace,
+                },
+        } => {
+            let matched = db.match_tasks(&pattern)?;
+            let (tasks, kept): (Vec<_>, Vec<_>) = matched.into_iter().partition(|t| {
+                replace || t.epic_name.is_none() || t.epic_name.as_deref() == Some(epic.as_str())
+            });
+            if tasks.is_empty() {
+                println!("{} No tasks match '{}'", "ℹ".blue().bold(), pattern);
+                return Ok(());
+            }
+
+            for task in &tasks {
+                let from = match &task.epic_name {
+                    Some(current) if *current != epic => format!(" (from {})", current),
+                    _ => String::new(),
+                };
+                println!(
+                    "  #{} {}{}",
+                    task.display_id.unwrap_or_default(),
+                    task.title,
+                    from.dimmed()
+                );
+            }
+            if !kept.is_empty() {
+                println!(
+                    "{} {} matching task(s) already in another epic were left alone (use --replace to move them)",
+                    "ℹ".blue().bold(),
+                    kept.len()
+                );
+            }
+
+            if dry_run {
+                println!(
+                    "{} Would assign {} task(s) to epic {}",
+                    "ℹ".blue().bold(),
+                    tasks.len(),
+                    epic.cyan()
+                );
+            } else {
+                let ids: Vec<String> = tasks.iter().map(|t| t.id.clone()).collect();
+                let count = db.set_task_epic(&ids, &epic)?;
+                println!(
+                    "{} Assigned {} task(s) to epic {}",
+                    "✓".green().bold(),
+                    count,
+                    epic.cyan()
+                );
+            }
+        }
+
+        PlanningCommands::Ids { action } => match action {
+            IdsAction::Set { prefix, next } => {
+                let sequence = db.set_id_sequence(&prefix, next)?;
+                println!(
+                    "{} Next {} key: {}-{}",
+                    "✓".green().bold(),
+                    sequence.prefix.cyan(),
+                    sequence.prefix,
+                    sequence.next_value
+                );
+            }
+            IdsAction::List => {
+                let sequences = db.list_id_sequences()?;
+                if sequences.is_empty() {
+                    println!("{}", "No ID prefixes defined.".yellow());
+                }
+                let default = db.get_setting(db::SETTING_ID_PREFIX)?;
+                for sequence in sequences {
+                    let marker = if default.as_deref().map(str::to_ascii_uppercase)
+                        == Some(sequence.prefix.clone())
+                    {
+                        " (default)"
+                    } else {
+                        ""
+                    };
+                    println!(
+                        "{} next {}-{}{}",
+                        sequence.prefix.cyan().bold(),
+                        sequence.prefix,
+                        sequence.next_value,
+                        marker
+                    );
+                }
+            }
+            IdsAction::Key { id, key } => {
+                let task_uuid = resolve_task_id(db.get_connection(), &id)?;
+                let key = if key.contains('-') {
+                    db.set_display_key(&task_uuid, &key)?
+                } else {
+                    db.assign_next_display_key(&task_uuid, &key)?
+                };
+                println!("{} {} is now {}", "✓".green().bold(), id, key.cyan());
+            }
+        },
+
+        PlanningCommands::Release { action } => {
+            use prd_tool::release::{self, ReleaseStatus};
+
+            let today = Utc::now().date_naive();
+            let print_status = |status: &ReleaseStatus| {
+                let r = &status.release;
+                let state = if r.is_closed() {
+                    "closed".green()
+                } else if status.is_late(today) {
+                    "late".red()
+                } else if status.is_ready() {
+                    "ready".green()
+                } else {
+                    "open".yellow()
+                };
+                let target = r
+                    .target
+                    .map(|d| format!(" · target {}", d))
+                    .unwrap_or_default();
+                println!(
+                    "{} [{}] {}/{} tasks done ({}%){}",
+                    r.version.cyan().bold(),
+                    state,
+                    status.done,
+                    status.total,
+                    status.percent(),
+                    target
+                );
+                for epic in &status.epics {
+                    println!("  {} - {}/{}", epic.epic, epic.done, epic.total);
+                }
+                if !r.is_closed() && !status.unfinished.is_empty() {
+                    println!("  {}", "Unfinished:".bold());
+                    for task in &status.unfinished {
+                        println!("    {} {} ({})", task.label(), task.title, task.status);
+                    }
+                }
+            };
+
+            match action {
+                ReleaseAction::Create {
+                    version,
+                    target,
+                    epics,
+                    tasks,
+                } => {
+                    let task_ids = tasks
+                        .iter()
+                        .map(|id| resolve_task_id(db.get_connection(), id))
+                        .collect::<Result<Vec<_>>>()?;
+                    db.create_release(&version, target, &epics, &task_ids)?;
+                    println!("{} Created release {}", "✓".green().bold(), version.cyan());
+                    print_status(&release::release_status(&db, &version)?);
+                }
+                ReleaseAction::Status { version, json } => {
+                    let versions: Vec<String> = match version {
+                        Some(version) => vec![version],
+                        None => db.list_releases()?.into_iter().map(|r| r.version).collect(),
+                    };
+                    let statuses = versions
+                        .iter()
+                        .map(|v| release::release_status(&db, v))
+                        .collect::<Result<Vec<_>>>()?;
+                    if json {
+                        println!("{}", serde_json::to_string_pretty(&statuses)?);
+                    } else if statuses.is_empty() {
+                        println!("{}", "No releases found.".yellow());
+                    } else {
+                        for status in &statuses {
+                            print_status(status);
+                        }
+                    }
+                }
+                ReleaseAction::Close { version, force } => {
+                    let status = release::close_release(&db, &version, force)?;
+                    println!("{} Closed release {}", "✓".green().bold(), version.cyan());
+                    if !status.is_ready() {
+                        println!(
+                            "{} {} task(s) were still unfinished",
+                            "⚠".yellow().bold(),
+                            status.unfinished.len()
+                        );
+                    }
+                }
+                ReleaseAction::Changelog { version, output } => {
+                    let notes = release::changelog(&db, &version)?;
+                    match output {
+                        Some(path) => {
+                            std::fs::write(&path, notes)?;
+                            println!(
+                                "{} Changelog written to {}",
+                                "✓".green().bold(),
+                                path.display()
+                            );
+                        }
+                        None => print!("{}", notes),
+                    }
+                }
+            }
+        }
+
+        PlanningCommands::EpicTarget { epic, date, .. } => {
+            db.set_epic_target(&epic, date)?;
+            match date {
+                Some(date) => println!(
+                    "{} Target for '{}' set to {}",
+                    "✓".green().bold(),
+                    epic,
+                    date
+                ),
+                None => println!("{} Target for '{}' cleared", "✓".green().bold(), epic),
+            }
+        }
+
+        PlanningCommands::Sprint { action } => match action {
+            SprintAction::Create {
+                start,
+                end,
+                capacity,
+                goal,
+            } => {
+                if end < start {
+                    anyhow::bail!("Sprint can't end before it starts");
+                }
+                let number = db
+                    .get_all_sprints()?
+                    .last()
+                    .map(|s| s.number + 1)
+                    .unwrap_or(1);
+                let sprint =
+                    db.create_sprint(number, start.to_string(), end.to_string(), goal, capacity)?;
+                println!(
+                    "{} Created sprint {} ({} - {}){}",
+                    "✓".green().bold(),
+                    sprint.number.to_string().cyan(),
+                    sprint.start_date,
+                    sprint.end_date,
+                    capacity
+                        .map(|c| format!(", capacity {} points", c))
+                        .unwrap_or_default()
+                );
+            }
+            SprintAction::List { json } => {
+                let sprints = db.get_all_sprints()?;
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&sprints)?);
+                } else if sprints.is_empty() {
+                    println!("{}", "No sprints.".yellow());
+                } else {
+                    let workflow = db.workflow()?;
+                    let mut rows = Vec::new();
+                    for sprint in &sprints {
+                        let tasks = db.get_sprint_tasks(sprint.id)?;
+                        let mut points = 0;
+                        for task in &tasks {
+                            points += db.get_task_points(&task.id)?.unwrap_or(0);
+                        }
+                        let done = tasks
+                            .iter()
+                            .filter(|t| workflow.is_completed(&t.status))
+                            .count();
+                        rows.push(SprintRow {
+                            number: sprint.number,
+                            dates: format!("{} - {}", sprint.start_date, sprint.end_date),
+                            points: match sprint.capacity {
+                                Some(capacity) => format!("{}/{}", points, capacity),
+                                None => points.to_string(),
+                            },
+                            tasks: format!("{}/{} done", done, tasks.len()),
+                            goal: sprint.goal.clone().unwrap_or_default(),
+                        });
+                    }
+                    println!("{}", Table::new(rows).with(Style::modern()));
+                }
+            }
+            SprintAction::Capacity { number, points } => {
+                let sprint = db
+                    .get_sprint_by_number(number)?
+                    .ok_or_else(|| anyhow::anyhow!("Sprint {} not found", number))?;
+                db.set_sprint_capacity(sprint.id, Some(points))?;
+                println!(
+                    "{} Sprint {} capacity set to {} points",
+                    "✓".green().bold(),
+                    number.to_string().cyan(),
+                    points
+                );
+            }
+            SprintAction::Plan {
+                number,
+                capacity,
+                json,
+            } => {
+                let plan = prd_tool::sprint::plan_sprint(&db, number, capacity, dry_run)?;
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&plan)?);
+                    return Ok(());
+                }
+                println!(
+                    "\n{} ({} - {}): {}/{} points",
+                    format!("Sprint {}", number).bold().underline(),
+                    plan.sprint.start_date,
+                    plan.sprint.end_date,
+                    plan.planned_points(),
+                    plan.capacity
+                );
+                if plan.added.is_empty() {
+                    println!("{}", "Nothing from the backlog fits.".yellow());
+                } else {
+                    println!(
+                        "\n{} {} task(s):",
+                        if dry_run { "Would add" } else { "Added" },
+                        plan.added.len()
+                    );
+                    for planned in &plan.added {
+                        println!(
+                            "  {} {} [{}] {} pts",
+                            format_task_id(db.get_connection(), &planned.task.id).cyan(),
+                            planned.task.title,
+                            planned.task.priority.as_str(),
+                            planned.points
+                        );
+                    }
+                }
+                if !plan.left_out.is_empty() {
+                    println!("\n{}", "Doesn't fit:".bold());
+                    for left in &plan.left_out {
+                        println!(
+                            "  {} {} ({}) - {}",
+                            format_task_id(db.get_connection(), &left.task.id).cyan(),
+                            left.task.title,
+                            left.points
+                                .map(|p| format!("{} pts", p))
+                                .unwrap_or_else(|| "unestimated".to_string()),
+                            left.reason.to_string().yellow()
+                        );
+                    }
+                }
+                if dry_run {
+                    println!(
+                        "\n{} Dry run: nothing changed (run without --dry-run to apply)",
+                        "ℹ".blue().bold()
+                    );
+                }
+            }
+        },
+
+        PlanningCommands::Status { action } => match action {
+            StatusAction::Add { name, bucket } => {
+                let status = db.add_custom_status(&name, bucket)?;
+                println!(
+                    "{} Added status {} ({})",
+                    "✓".green().bold(),
+                    status.name.cyan(),
+                    status.bucket.as_str()
+                );
+            }
+            StatusAction::List { json } => {
+                let statuses = db.workflow()?.statuses();
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&statuses)?);
+                    return Ok(());
+                }
+                for status in statuses {
+                    println!(
+                        "{:<16} {:<7} {}",
+                        status.name.cyan(),
+                        status.bucket.as_str(),
+                        if status.custom { "" } else { "built-in" }.dimmed()
+                    );
+                }
+            }
+            StatusAction::Remove { name } => {
+                db.remove_custom_status(&name)?;
+                println!("{} Removed status {}", "✓".green().bold(), name.cyan());
+            }
+            StatusAction::Allow { from, to } => {
+                for to in &to {
+                    if db.allow_transition(&from, to)? {
+                        println!(
+                            "{} Allowed {} → {}",
+                            "✓".green().bold(),
+                            from.cyan(),
+                            to.cyan()
+                        );
+                    } else {
+                        println!("{} {} → {} is already allowed", "ℹ".blue().bold(), from, to);
+                    }
+                }
+            }
+            StatusAction::Disallow { from, to } => {
+                for to in &to {
+                    if db.disallow_transition(&from, to)? {
+                        println!(
+                            "{} Disallowed {} → {}",
+                            "✓".green().bold(),
+                            from.cyan(),
+                            to.cyan()
+                        );
+                    } else {
+                        println!("{} {} → {} was not allowed", "ℹ".blue().bold(), from, to);
+                    }
+                }
+            }
+            StatusAction::Transitions {
+                preset,
+                clear,
+                json,
+            } => {
+                if preset || clear {
+                    db.reset_transitions(preset)?;
+                }
+                let workflow = db.workflow()?;
+                let transitions = workflow.transitions();
+                if json {
+                    println!("{}", serde_json::to_string_pretty(transitions)?);
+                    return Ok(());
+                }
+                if transitions.is_empty() {
+                    println!(
+                        "{}",
+                        "No transitions configured; any status change is allowed.".yellow()
+                    );
+                    return Ok(());
+                }
+                for status in workflow.statuses() {
+                    let to: Vec<&str> = transitions
+                        .get(&status.name)
+                        .map(|to| to.iter().map(String::as_str).collect())
+                        .unwrap_or_default();
+                    println!(
+                        "{:<16} → {}",
+                        status.name.cyan(),
+                        if to.is_empty() {
+                            "(final)".dimmed().to_string()
+                        } else {
+                            to.join(", ")
+                        }
+                    );
+                }
+            }
+        },
+    }
+
+    Ok(())
+}
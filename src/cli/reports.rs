@@ -0,0 +1,488 @@
+//! Reports - stats, boards, badges, exports and the live dashboard
+
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::Subcommand;
+use colored::*;
+use db::{Database, TaskStatus};
+use output::emoji;
+use prd_tool::{db, output, resolver};
+use resolver::resolve_agent_id;
+use std::path::PathBuf;
+use tabled::{settings::Style, Table, Tabled};
+
+use super::{format_status, parse_ttl, Context};
+
+/// Commands that summarize or export tasks
+#[derive(Subcommand)]
+pub enum ReportCommands {
+    /// Show statistics
+    ///
+    /// Each run also stores the day's counts per status and epic, which
+    /// --trend compares over time.
+    Stats {
+        /// Show visual progress timelines
+        #[arg(short, long)]
+        visual: bool,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+        /// Show deltas and sparklines over a window of daily snapshots (e.g. 30d)
+        #[arg(long, value_parser = parse_ttl, conflicts_with = "visual")]
+        trend: Option<chrono::Duration>,
+    },
+
+    /// Show a kanban board of tasks grouped by status
+    Board {
+        /// Output as Markdown (for wikis and PR descriptions)
+        #[arg(long)]
+        markdown: bool,
+        /// Only show tasks in this epic
+        #[arg(short = 'E', long)]
+        epic: Option<String>,
+    },
+
+    /// Render completion percentage as an SVG badge (for README embedding)
+    Badge {
+        /// Write the SVG to this file instead of stdout
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+        /// Only count tasks in this epic
+        #[arg(short = 'E', long)]
+        epic: Option<String>,
+        /// Left-hand text (default: the epic name, or "progress")
+        #[arg(long)]
+        label: Option<String>,
+    },
+
+    /// Export tasks to other formats
+    Export {
+        #[command(subcommand)]
+        action: ExportAction,
+    },
+
+    /// Calendar heatmap of tasks completed per day
+    Heatmap {
+        /// Only count tasks assigned to this agent
+        #[arg(short, long)]
+        agent: Option<String>,
+        /// Show a calendar year instead of the last 52 weeks
+        #[arg(long)]
+        year: Option<i32>,
+    },
+
+    /// Time common operations against a generated large database
+    Bench {
+        /// Number of synthetic tasks
+        #[arg(long, default_value_t = 50_000)]
+        tasks: usize,
+        /// Number of synthetic task log entries
+        #[arg(long, default_value_t = 200_000)]
+        logs: usize,
+        /// Number of synthetic embeddings
+        #[arg(long, default_value_t = 100_000)]
+        embeddings: usize,
+        /// Timed runs per operation
+        #[arg(long, default_value_t = 10)]
+        iterations: usize,
+        /// Keep the generated database at this path (must not exist)
+        #[arg(long)]
+        keep: Option<PathBuf>,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Live dashboard with real-time agent progress
+    #[command(alias = "dashboard")]
+    Watch {
+        /// Fallback refresh interval in seconds (database changes refresh immediately)
+        #[arg(long, default_value = "10")]
+        refresh_interval: u64,
+
+        /// Write a session summary on exit (JSON for .json files, Markdown otherwise)
+        #[arg(long)]
+        summary: Option<PathBuf>,
+
+        /// Play back task and agent history from the activity log instead
+        #[arg(long)]
+        replay: bool,
+
+        /// Replay start date (YYYY-MM-DD, defaults to the first recorded event)
+        #[arg(long, requires = "replay")]
+        from: Option<NaiveDate>,
+
+        /// Seconds of history replayed per second (+/- change it while playing)
+        #[arg(long, default_value_t = prd_tool::dashboard::DEFAULT_REPLAY_SPEED, requires = "replay")]
+        speed: f64,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ExportAction {
+    /// Export as an org-mode file (for Emacs org-agenda)
+    Org {
+        /// Output file (prints to stdout if omitted)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Only export tasks in this epic
+        #[arg(short = 'E', long)]
+        epic: Option<String>,
+    },
+    /// Export epics and their dependencies as a graph
+    Graph {
+        /// Output file (prints to stdout if omitted)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Graph format
+        #[arg(short, long, value_enum, default_value_t = GraphFormat::Dot)]
+        format: GraphFormat,
+    },
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+pub enum GraphFormat {
+    /// Graphviz (render with `dot -Tsvg`)
+    Dot,
+    /// Mermaid flowchart for markdown
+    Mermaid,
+}
+
+#[derive(Tabled)]
+struct TrendRow {
+    #[tabled(rename = "")]
+    name: String,
+    #[tabled(rename = "Trend")]
+    sparkline: String,
+    #[tabled(rename = "First")]
+    first: String,
+    #[tabled(rename = "Now")]
+    now: String,
+    #[tabled(rename = "Change")]
+    change: String,
+    #[tabled(rename = "This week")]
+    week: String,
+}
+
+/// Runs a report command against the open database
+pub fn run(command: ReportCommands, db: Database, ctx: &Context) -> Result<()> {
+    match command {
+        ReportCommands::Stats {
+            visual,
+            json,
+            trend,
+        } => {
+            let today = Utc::now().date_naive();
+            db.record_stats_snapshot(today)?;
+
+            if let Some(span) = trend {
+                let trend = prd_tool::trend::stats_trend(&db, today, span.num_days())?;
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&trend)?);
+                    return Ok(());
+                }
+                print_stats_trend(&trend);
+            } else if json {
+                // JSON output
+                let stats = db.get_stats()?;
+                println!("{}", serde_json::to_string_pretty(&stats)?);
+            } else if visual {
+                // Visual timeline
+                use prd_tool::visualization::TimelineRenderer;
+                let renderer = TimelineRenderer::new(db);
+                let output = renderer.render()?;
+                println!("{}", output);
+            } else {
+                // Simple stats (existing)
+                let stats = db.get_stats()?;
+
+                println!("\n{}", "Task Statistics".bold().underline());
+                println!("Total tasks: {}", stats.total.to_string().cyan().bold());
+                println!("  {} Pending: {}", "○".white(), stats.pending);
+                println!("  {} In Progress: {}", "◐".blue(), stats.in_progress);
+                println!("  {} Blocked: {}", "■".red(), stats.blocked);
+                println!("  {} Review: {}", "◇".yellow(), stats.review);
+                println!("  {} Completed: {}", "●".green(), stats.completed);
+                println!("  {} Failed: {}", "✗".red(), stats.failed);
+                println!("  {} Cancelled: {}", "✕".dimmed(), stats.cancelled);
+                for status in db.workflow()?.custom() {
+                    println!(
+                        "  {} {}: {} {}",
+                        "◆".magenta(),
+                        status.name,
+                        stats.custom.get(&status.name).copied().unwrap_or(0),
+                        format!("({})", status.bucket.as_str()).dimmed()
+                    );
+                }
+
+                if stats.total > 0 {
+                    let progress = (stats.done as f32 / stats.total as f32) * 100.0;
+                    println!("\nProgress: {:.1}%", progress);
+
+                    // Simple progress bar
+                    let bar_length = 40;
+                    let filled = ((progress / 100.0) * bar_length as f32) as usize;
+                    let bar = "█".repeat(filled) + &"░".repeat(bar_length - filled);
+                    println!("{}", bar.green());
+                }
+
+                if stats.points > 0 {
+                    println!(
+                        "\nPoints: {}/{} done · {} in the last {} days",
+                        stats.done_points,
+                        stats.points,
+                        stats.recent_points.to_string().cyan().bold(),
+                        db::VELOCITY_WINDOW_DAYS
+                    );
+                }
+            }
+        }
+
+        ReportCommands::Board { markdown, epic } => {
+            use prd_tool::export::KanbanBoard;
+            let board = KanbanBoard::load(&db, epic.as_deref())?;
+
+            if markdown {
+                print!("{}", board.to_markdown());
+            } else {
+                for (status, cards) in &board.columns {
+                    let status = TaskStatus::from_str(status.as_str());
+                    println!("\n{} ({})", format_status(&status), cards.len());
+                    for card in cards {
+                        let agent = card
+                            .agent
+                            .as_ref()
+                            .map(|a| format!(" [{}]", a).cyan().to_string())
+                            .unwrap_or_default();
+                        println!("  {} {}{}", card.id.cyan(), card.title, agent);
+                    }
+                }
+            }
+        }
+
+        ReportCommands::Badge { out, epic, label } => {
+            use prd_tool::export::ProgressBadge;
+            let badge = ProgressBadge::load(&db, epic.as_deref(), label.as_deref())?;
+
+            match out {
+                Some(path) => {
+                    std::fs::write(&path, badge.to_svg())?;
+                    println!(
+                        "{} Wrote {} badge ({}/{} tasks) to {}",
+                        "✓".green().bold(),
+                        badge.message(),
+                        badge.completed,
+                        badge.total,
+                        path.display()
+                    );
+                }
+                None => print!("{}", badge.to_svg()),
+            }
+        }
+
+        ReportCommands::Export { action } => match action {
+            ExportAction::Org { output, epic } => {
+                use prd_tool::export::OrgExporter;
+                let content = OrgExporter::new(db).export(epic.as_deref())?;
+
+                match output {
+                    Some(path) => {
+                        std::fs::write(&path, content)?;
+                        println!(
+                            "{} Exported tasks to {}",
+                            "✓".green().bold(),
+                            path.display()
+                        );
+                    }
+                    None => print!("{}", content),
+                }
+            }
+            ExportAction::Graph { output, format } => {
+                use prd_tool::export::EpicGraph;
+                let graph = EpicGraph::load(&db)?;
+                let content = match format {
+                    GraphFormat::Dot => graph.to_dot(),
+                    GraphFormat::Mermaid => graph.to_mermaid(),
+                };
+
+                match output {
+                    Some(path) => {
+                        std::fs::write(&path, content)?;
+                        println!(
+                            "{} Exported epic graph to {}",
+                            "✓".green().bold(),
+                            path.display()
+                        );
+                    }
+                    None => print!("{}", content),
+                }
+            }
+        },
+
+        ReportCommands::Heatmap { agent, year } => {
+            use prd_tool::visualization::HeatmapRenderer;
+            let agent_uuid = agent
+                .map(|a| resolve_agent_id(db.get_connection(), &a))
+                .transpose()?;
+            let output = HeatmapRenderer::new(db).render(agent_uuid.as_deref(), year)?;
+            print!("{}", output);
+        }
+
+        ReportCommands::Bench {
+            tasks,
+            logs,
+            embeddings,
+            iterations,
+            keep,
+            json,
+        } => {
+            use prd_tool::bench::{self, BenchConfig};
+            let config = BenchConfig {
+                tasks,
+                logs,
+                embeddings,
+                iterations,
+                ..Default::default()
+            };
+            let path = keep.clone().unwrap_or_else(|| {
+                std::env::temp_dir().join(format!("prd-bench-{}.db", std::process::id()))
+            });
+
+            if !json {
+                println!(
+                    "{} Generating {} tasks, {} logs and {} embeddings in {}...",
+                    emoji("⚙").cyan(),
+                    tasks,
+                    logs,
+                    embeddings,
+                    path.display()
+                );
+            }
+            let result = bench::run(&path, &config);
+            if keep.is_none() {
+                for suffix in ["", "-wal", "-shm", "-journal"] {
+                    let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+                }
+            }
+            let report = result?;
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                println!("\n{}", "Generation".bold().underline());
+                for phase in &report.generation {
+                    println!("  {:<28} {:>10.1} ms", phase.name, phase.mean_ms);
+                }
+                println!(
+                    "\n{} ({} runs each)",
+                    "Operations".bold().underline(),
+                    iterations
+                );
+                println!(
+                    "  {:<28} {:>10} {:>10} {:>10} {:>10}",
+                    "", "mean", "p50", "p95", "max"
+                );
+                for op in &report.operations {
+                    println!(
+                        "  {:<28} {:>7.1} ms {:>7.1} ms {:>7.1} ms {:>7.1} ms",
+                        op.name, op.mean_ms, op.p50_ms, op.p95_ms, op.max_ms
+                    );
+                }
+                if let Some(path) = keep {
+                    println!("\n{} Kept database at {}", "✓".green(), path.display());
+                }
+            }
+        }
+
+        ReportCommands::Watch {
+            refresh_interval,
+            summary,
+            replay,
+            from,
+            speed,
+        } => {
+            use prd_tool::dashboard::{run_dashboard, run_replay};
+            if replay {
+                let from = from
+                    .and_then(|d| d.and_hms_opt(0, 0, 0))
+                    .map(|dt| DateTime::<Utc>::from_naive_utc_and_offset(dt, Utc));
+                run_replay(ctx.database.to_str().unwrap(), from, speed)?;
+                return Ok(());
+            }
+            run_dashboard(
+                ctx.database.to_str().unwrap(),
+                refresh_interval,
+                summary.as_deref(),
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Print per-status and per-epic deltas with sparklines, then highlights
+fn print_stats_trend(trend: &prd_tool::trend::StatsTrend) {
+    use prd_tool::trend::sparkline;
+
+    let (Some(first), Some(last)) = (trend.days.first(), trend.days.last()) else {
+        println!(
+            "{}",
+            "No snapshots yet: `prd stats` records one per day.".yellow()
+        );
+        return;
+    };
+    println!(
+        "\n{} ({} to {}, {} snapshot(s))",
+        "Task Trend".bold().underline(),
+        first,
+        last,
+        trend.days.len()
+    );
+
+    let week = |values: &[usize]| {
+        trend
+            .week_delta(values)
+            .map(|d| format!("{:+}", d))
+            .unwrap_or_else(|| "-".to_string())
+    };
+    let row = |name: &str, values: &[usize], first: String, now: String| TrendRow {
+        name: name.to_string(),
+        sparkline: sparkline(values),
+        first,
+        now,
+        change: format!("{:+}", values[values.len() - 1] as i64 - values[0] as i64),
+        week: week(values),
+    };
+    let rows: Vec<TrendRow> = trend
+        .statuses
+        .iter()
+        .filter(|s| s.values.iter().any(|v| *v > 0))
+        .map(|s| {
+            let (first, now) = (s.values[0], s.values[s.values.len() - 1]);
+            row(&s.name, &s.values, first.to_string(), now.to_string())
+        })
+        .collect();
+    println!("{}", Table::new(rows).with(Style::modern()));
+
+    if !trend.epics.is_empty() {
+        println!("\n{}", "Completed per epic".bold());
+        let rows: Vec<TrendRow> = trend
+            .epics
+            .iter()
+            .map(|e| {
+                let last = e.done.len() - 1;
+                row(
+                    &e.epic,
+                    &e.done,
+                    format!("{}/{}", e.done[0], e.total[0]),
+                    format!("{}/{}", e.done[last], e.total[last]),
+                )
+            })
+            .collect();
+        println!("{}", Table::new(rows).with(Style::modern()));
+    }
+
+    for highlight in trend.highlights() {
+        println!("{} {}", "•".cyan(), highlight);
+    }
+}
@@ -0,0 +1,947 @@
+//! Sync - reconciling the database with completion docs, git history and
+//! other replicas
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use clap::Subcommand;
+use colored::*;
+use db::{AgentStatus, Database, TaskStatus};
+use output::emoji;
+use prd_tool::rules::EventKind;
+use prd_tool::{db, oplog, output, resolver, sync};
+use resolver::{format_agent_id, format_task_id, resolve_agent_id, resolve_task_id};
+use std::path::PathBuf;
+use tabled::{settings::Style, Table, Tabled};
+
+use super::{print_wip_warnings, Context};
+
+/// Commands that reconcile the database with the outside world
+#[derive(Subcommand)]
+pub enum SyncCommands {
+    /// Sync agent work (mark current task as in progress)
+    Sync {
+        /// Agent ID or name
+        agent: String,
+        /// Task ID to work on
+        task_id: String,
+        /// Go over WIP limits (with a warning) instead of refusing
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Reconcile with another replica of this database (file or `prd serve` URL)
+    SyncDb {
+        #[command(subcommand)]
+        action: SyncDbAction,
+    },
+
+    /// Automatically sync task completions from documentation
+    SyncDocs {
+        /// Sync from git commit history instead of files
+        #[arg(long)]
+        from_git: bool,
+
+        /// Only sync commits since this date (ISO 8601: 2025-10-01)
+        #[arg(long, requires = "from_git")]
+        since: Option<String>,
+
+        /// Only sync commits until this date (ISO 8601: 2025-10-13)
+        #[arg(long, requires = "from_git")]
+        until: Option<String>,
+
+        /// Sync from specific branch
+        #[arg(long, requires = "from_git")]
+        branch: Option<String>,
+
+        /// Custom docs directory (default: the docs.dir setting, else docs/tasks)
+        #[arg(short, long)]
+        docs_dir: Option<PathBuf>,
+
+        /// Undo the completions made by a sync run (see prd sync-history)
+        #[arg(long, value_name = "RUN_ID", conflicts_with = "from_git")]
+        revert: Option<i64>,
+    },
+
+    /// Show past sync-docs and reconcile runs and their results
+    SyncHistory {
+        /// Run to show in detail, with its errors and completed tasks
+        run: Option<i64>,
+        /// Number of runs to list
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Generate a completion document skeleton for a task
+    Docgen {
+        /// Task ID
+        task_id: String,
+
+        /// Custom docs directory (default: the docs.dir setting, else docs/tasks)
+        #[arg(short, long)]
+        docs_dir: Option<PathBuf>,
+
+        /// Overwrite an existing document
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Reconcile database with filesystem (detect and fix inconsistencies)
+    Reconcile {
+        /// Apply fixes without confirmation
+        #[arg(long)]
+        auto_fix: bool,
+
+        /// Custom docs directory (default: the docs.dir setting, else docs/tasks)
+        #[arg(short, long)]
+        docs_dir: Option<PathBuf>,
+
+        /// Take a snapshot before applying fixes
+        #[arg(long)]
+        backup: bool,
+    },
+
+    /// Install or uninstall git hook for auto-completion
+    InstallGitHook {
+        /// Uninstall the hook
+        #[arg(long)]
+        uninstall: bool,
+
+        /// Show hook status
+        #[arg(long)]
+        status: bool,
+    },
+
+    /// Manage hook system
+    Hooks {
+        #[command(subcommand)]
+        subcommand: HooksSubcommand,
+    },
+
+    /// Watch docs/tasks directory for completion documents
+    WatchFiles {
+        /// Run as background daemon
+        #[arg(long)]
+        daemon: bool,
+
+        /// Show daemon status
+        #[arg(long)]
+        status: bool,
+
+        /// Stop daemon
+        #[arg(long)]
+        stop: bool,
+
+        /// Path to docs directory (default: the docs.dir setting, else docs/tasks)
+        #[arg(long)]
+        docs_path: Option<PathBuf>,
+
+        /// Run in daemon mode (internal flag)
+        #[arg(long, hide = true)]
+        daemon_mode: bool,
+
+        #[command(subcommand)]
+        action: Option<WatchFilesAction>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum HooksSubcommand {
+    /// Initialize hooks configuration with examples
+    Init,
+
+    /// List all configured hooks
+    List,
+
+    /// Test a hook without side effects
+    Test {
+        /// Hook name (e.g., on_task_complete)
+        hook_name: String,
+
+        /// Task ID for testing
+        #[arg(long)]
+        task_id: Option<String>,
+
+        /// Agent ID for testing
+        #[arg(long)]
+        agent_id: Option<String>,
+    },
+
+    /// Enable a hook
+    Enable {
+        /// Hook name
+        hook_name: String,
+    },
+
+    /// Disable a hook
+    Disable {
+        /// Hook name
+        hook_name: String,
+    },
+
+    /// Share ~/.prd/hooks.toml and config.toml through the database, so every
+    /// machine and runner using it gets the same automation
+    Push {
+        /// Stop sharing; machines go back to their local files
+        #[arg(long)]
+        clear: bool,
+    },
+
+    /// Overwrite ~/.prd/hooks.toml and config.toml with the shared copies
+    Pull,
+}
+
+#[derive(Subcommand)]
+pub enum WatchFilesAction {
+    /// Make the running daemon re-read ~/.prd/watcher.toml (same as SIGHUP)
+    Reload,
+    /// List completion documents the watcher gave up on after repeated failures
+    Failures {
+        /// Empty the list so the watcher tries those documents again
+        #[arg(long)]
+        clear: bool,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SyncDbAction {
+    /// Send local changes the remote doesn't have
+    Push {
+        /// Remote database file, or http(s):// URL of `prd serve`
+        remote: String,
+        /// API token for a `prd serve --auth` remote (needs admin scope to push)
+        #[arg(long)]
+        token: Option<String>,
+        /// Output the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Apply remote changes this database doesn't have
+    Pull {
+        /// Remote database file, or http(s):// URL of `prd serve`
+        remote: String,
+        /// API token for a `prd serve --auth` remote
+        #[arg(long)]
+        token: Option<String>,
+        /// Output the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show recorded operations, newest first
+    Log {
+        /// Only operations on this task (and its criteria and dependencies)
+        #[arg(long)]
+        task: Option<String>,
+        /// Number of operations to show
+        #[arg(long, default_value = "20")]
+        limit: usize,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Rebuild a new database from the operation log alone
+    Replay {
+        /// Path for the rebuilt database (must not exist)
+        output: PathBuf,
+    },
+}
+
+#[derive(Tabled)]
+struct SyncRunRow {
+    #[tabled(rename = "Run")]
+    id: i64,
+    #[tabled(rename = "Source")]
+    source: String,
+    #[tabled(rename = "Started")]
+    started: String,
+    #[tabled(rename = "Changed")]
+    changed: usize,
+    #[tabled(rename = "Unchanged")]
+    unchanged: usize,
+    #[tabled(rename = "Errors")]
+    errors: usize,
+    #[tabled(rename = "State")]
+    state: String,
+}
+
+/// Runs a sync command against the open database
+pub fn run(command: SyncCommands, db: Database, ctx: &Context) -> Result<()> {
+    let dry_run = ctx.dry_run;
+    let invocation = ctx.invocation;
+
+    match command {
+        SyncCommands::Sync {
+            agent,
+            task_id,
+            force,
+        } => {
+            let agent_uuid = resolve_agent_id(db.get_connection(), &agent)?;
+            let task_uuid = resolve_task_id(db.get_connection(), &task_id)?;
+            let agent_obj = db.get_agent(&agent_uuid)?;
+
+            match agent_obj {
+                Some(a) => {
+                    db.check_lease(&task_uuid, &a.id)?;
+                    let exceeded = db.enforce_wip(
+                        &task_uuid,
+                        Some(&a.id),
+                        Some(&TaskStatus::InProgress),
+                        force,
+                    )?;
+                    print_wip_warnings(&exceeded);
+                    // Update agent to working status
+                    db.update_agent_status(&a.id, AgentStatus::Working, Some(&task_uuid))?;
+                    // Update task to in_progress
+                    db.update_task_status(&task_uuid, TaskStatus::InProgress, Some(&a.id))?;
+                    // Assign task if not already assigned
+                    db.assign_task(&task_uuid, &a.id)?;
+
+                    let agent_display = format_agent_id(db.get_connection(), &a.id);
+                    let task_display = format_task_id(db.get_connection(), &task_uuid);
+                    println!("{}", "✓ Agent synchronized!".green().bold());
+                    println!(
+                        "Agent {} ({}) is now working on task {}",
+                        agent_display.cyan(),
+                        a.name,
+                        task_display.cyan()
+                    );
+                    invocation.emit(EventKind::StatusChanged, &task_uuid, true);
+                }
+                None => {
+                    println!("{}", "Agent not found.".red());
+                }
+            }
+        }
+
+        SyncCommands::SyncDb { action } => {
+            let conn = db.get_connection();
+            let (pushing, remote, token, json) = match action {
+                SyncDbAction::Log { task, limit, json } => {
+                    let ops = match task {
+                        Some(task) => oplog::ops_for_key(conn, &resolve_task_id(conn, &task)?)?,
+                        None => oplog::load_ops(conn)?,
+                    };
+                    let ops: Vec<_> = ops.into_iter().rev().take(limit).collect();
+                    if json {
+                        println!("{}", serde_json::to_string_pretty(&ops)?);
+                        return Ok(());
+                    }
+                    if ops.is_empty() {
+                        println!("No operations recorded");
+                        return Ok(());
+                    }
+
+                    let vector = oplog::version_vector(conn)?;
+                    println!("{}", "Version vector".bold().underline());
+                    for (replica, clock) in &vector {
+                        println!("  {} @ {}", replica.dimmed(), clock);
+                    }
+                    println!();
+                    for op in &ops {
+                        let change = match &op.column {
+                            None if op.is_delete() => "deleted".red().to_string(),
+                            None => "created".green().to_string(),
+                            Some(column) => format!("{}: {} → {}", column, op.prev, op.value),
+                        };
+                        println!(
+                            "{:>6}  {}  {:<20} {} {}",
+                            op.clock,
+                            op.replica.chars().take(8).collect::<String>().dimmed(),
+                            op.table,
+                            oplog::describe_key(conn, &op.table, &op.key).cyan(),
+                            change
+                        );
+                    }
+                    return Ok(());
+                }
+                SyncDbAction::Replay { output } => {
+                    let report = oplog::replay(conn, &output)?;
+                    println!(
+                        "{} Replayed {} operation(s) into {}",
+                        "✓".green().bold(),
+                        report.applied,
+                        output.display()
+                    );
+                    for reason in &report.rejected {
+                        println!("  {} Skipped {}", "⚠".yellow(), reason);
+                    }
+                    return Ok(());
+                }
+                SyncDbAction::Push {
+                    remote,
+                    token,
+                    json,
+                } => (true, remote, token, json),
+                SyncDbAction::Pull {
+                    remote,
+                    token,
+                    json,
+                } => (false, remote, token, json),
+            };
+            let remote = oplog::Remote::parse(&remote, token);
+            let report = if pushing {
+                oplog::push(conn, &remote)?
+            } else {
+                oplog::pull(conn, &remote)?
+            };
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+                return Ok(());
+            }
+
+            println!(
+                "{}",
+                format!("Replica {} ⇄ {}", oplog::replica_id(conn)?, remote).dimmed()
+            );
+            if !report.conflicts.is_empty() {
+                println!("{}", "Conflicts (last writer wins)".bold().underline());
+                for conflict in &report.conflicts {
+                    let (local, remote) = (conflict.local.to_string(), conflict.remote.to_string());
+                    let (local, remote) = match conflict.winner {
+                        oplog::Side::Local => (local.green().bold(), remote.dimmed()),
+                        oplog::Side::Remote => (local.dimmed(), remote.green().bold()),
+                    };
+                    println!(
+                        "  {} {}: local {} / remote {}",
+                        oplog::describe_key(conn, &conflict.table, &conflict.key),
+                        conflict.column,
+                        local,
+                        remote
+                    );
+                }
+                println!();
+            }
+            for (uuid, from, to) in &report.applied.renumbered {
+                let task = oplog::describe_key(conn, "tasks", uuid);
+                if pushing {
+                    println!("  {} {} is #{} on the remote", "⚠".yellow(), task, to);
+                } else {
+                    println!("  {} {} (#{} on the remote)", "⚠".yellow(), task, from);
+                }
+            }
+            for reason in &report.applied.rejected {
+                println!("  {} Skipped {}", "⚠".yellow(), reason);
+            }
+
+            let verb = if pushing { "Pushed" } else { "Pulled" };
+            println!(
+                "{} {} {} operation(s), {} conflict(s)",
+                "✓".green().bold(),
+                verb,
+                report.transferred,
+                report.conflicts.len()
+            );
+            if pushing
+                && report
+                    .conflicts
+                    .iter()
+                    .any(|c| c.winner == oplog::Side::Remote)
+            {
+                println!("  Pull to bring in the remote's winning changes.");
+            }
+        }
+
+        SyncCommands::Docgen {
+            task_id,
+            docs_dir,
+            force,
+        } => {
+            let docs_dir = docs_dir.map_or_else(|| db.docs_dir(), Ok)?;
+            let task_uuid = resolve_task_id(db.get_connection(), &task_id)?;
+            let task = db
+                .get_task(&task_uuid)?
+                .ok_or_else(|| anyhow::anyhow!("Task not found"))?;
+            let path = sync::write_doc(&db, &task, &docs_dir, force)?;
+            println!("{} Wrote {}", "✓".green(), path.display());
+            println!(
+                "  Remove {} from the frontmatter when done, then run {}",
+                "draft: true".yellow(),
+                "prd sync-docs".cyan()
+            );
+        }
+
+        SyncCommands::SyncDocs {
+            from_git,
+            since,
+            until,
+            branch,
+            docs_dir,
+            revert,
+        } => {
+            let docs_dir = docs_dir.map_or_else(|| db.docs_dir(), Ok)?;
+            if let Some(run_id) = revert {
+                let result = db.revert_sync_run(run_id)?;
+                for task in &result.reverted {
+                    println!(
+                        "{} Reverted {} {}",
+                        "✓".green().bold(),
+                        format_task_id(db.get_connection(), &task.id).cyan(),
+                        task.title
+                    );
+                }
+                for task_id in &result.skipped {
+                    let label = format_task_id(db.get_connection(), task_id);
+                    println!(
+                        "{} Skipped {} (changed since the sync)",
+                        "⚠".yellow(),
+                        label
+                    );
+                }
+                println!(
+                    "\nSync run {}: {} reverted, {} skipped",
+                    run_id,
+                    result.reverted.len(),
+                    result.skipped.len()
+                );
+            } else if from_git {
+                // Git-based sync
+                use chrono::NaiveDate;
+                use prd_tool::git::GitSync;
+
+                let repo_path = std::env::current_dir()?;
+                let git_sync = GitSync::new(&repo_path)?;
+
+                // Parse dates
+                let since_dt = since
+                    .as_ref()
+                    .map(|s| {
+                        DateTime::parse_from_rfc3339(s)
+                            .or_else(|_| {
+                                // Try simple date format
+                                NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                                    .map(|d| d.and_hms_opt(0, 0, 0).unwrap())
+                                    .map(|dt| DateTime::<Utc>::from_naive_utc_and_offset(dt, Utc))
+                                    .map(|dt| dt.fixed_offset())
+                            })
+                            .map_err(|_| anyhow::anyhow!("Invalid date format for --since"))
+                    })
+                    .transpose()?
+                    .map(|dt| dt.with_timezone(&Utc));
+
+                let until_dt = until
+                    .as_ref()
+                    .map(|s| {
+                        DateTime::parse_from_rfc3339(s)
+                            .or_else(|_| {
+                                NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                                    .map(|d| d.and_hms_opt(23, 59, 59).unwrap())
+                                    .map(|dt| DateTime::<Utc>::from_naive_utc_and_offset(dt, Utc))
+                                    .map(|dt| dt.fixed_offset())
+                            })
+                            .map_err(|_| anyhow::anyhow!("Invalid date format for --until"))
+                    })
+                    .transpose()?
+                    .map(|dt| dt.with_timezone(&Utc));
+
+                let completions =
+                    git_sync.scan_for_completions(since_dt, until_dt, branch.as_deref())?;
+
+                if completions.is_empty() {
+                    println!("\nNo tasks found in git history");
+                    return Ok(());
+                }
+
+                if dry_run {
+                    println!("\n{} DRY RUN: No changes will be made\n", emoji("🔍"));
+                    for doc in &completions {
+                        println!("Would mark task #{} complete", doc.task_id);
+                        if let Some(agent_id) = &doc.agent_id {
+                            println!("  Agent: {}", agent_id);
+                        }
+                        println!("  Commit: {}", doc.git_commit_hash.as_ref().unwrap());
+                        println!();
+                    }
+                    println!(
+                        "Total: {} tasks would be marked complete",
+                        completions.len()
+                    );
+                } else {
+                    // Actually sync to database
+                    println!(
+                        "\n{} Syncing {} tasks to database...\n",
+                        emoji("📝"),
+                        completions.len()
+                    );
+
+                    let mut synced = 0;
+                    let mut skipped = 0;
+                    let mut errors: Vec<String> = Vec::new();
+                    let run_id = db.start_sync_run("git")?;
+
+                    for doc in completions {
+                        // Check if task exists
+                        let task_result: Result<Option<String>, _> = db.get_connection().query_row(
+                            "SELECT id FROM tasks WHERE display_id = ?1 AND deleted_at IS NULL",
+                            [doc.task_id],
+                            |row| row.get(0),
+                        );
+
+                        match task_result {
+                            Ok(Some(task_uuid)) => {
+                                // Check if already completed
+                                let task = db.get_task(&task_uuid)?;
+                                if let Some(t) = task {
+                                    if db.workflow()?.is_completed(&t.status) {
+                                        skipped += 1;
+                                        println!(
+                                            "⚠ Skipped task #{} (already complete)",
+                                            doc.task_id
+                                        );
+                                        continue;
+                                    }
+
+                                    // Mark as completed
+                                    let agent_id = if let Some(ref agent_str) = doc.agent_id {
+                                        // Try to resolve or create agent
+                                        let agent_result =
+                                            resolve_agent_id(db.get_connection(), agent_str);
+                                        match agent_result {
+                                            Ok(id) => Some(id),
+                                            Err(_) => {
+                                                // Create agent
+                                                match db.create_agent(agent_str.clone()) {
+                                                    Ok(agent) => Some(agent.id),
+                                                    Err(_) => None,
+                                                }
+                                            }
+                                        }
+                                    } else {
+                                        None
+                                    };
+
+                                    let completed = db.write_transaction(|| {
+                                        db.update_task_status(
+                                            &task_uuid,
+                                            TaskStatus::Completed,
+                                            agent_id.as_deref(),
+                                        )?;
+                                        db.record_sync_completion(run_id, &task_uuid, &t.status)
+                                    });
+                                    match completed {
+                                        Ok(_) => {
+                                            synced += 1;
+                                            println!("✓ Marked task #{} complete", doc.task_id);
+                                        }
+                                        Err(e) => {
+                                            let message =
+                                                format!("Failed task #{}: {}", doc.task_id, e);
+                                            println!("{} {}", emoji("❌"), message);
+                                            errors.push(message);
+                                        }
+                                    }
+                                }
+                            }
+                            Ok(None) => {
+                                let message =
+                                    format!("Task #{} not found in database", doc.task_id);
+                                println!("{} {}", emoji("❌"), message);
+                                errors.push(message);
+                            }
+                            Err(e) => {
+                                let message =
+                                    format!("Database error for task #{}: {}", doc.task_id, e);
+                                println!("{} {}", emoji("❌"), message);
+                                errors.push(message);
+                            }
+                        }
+                    }
+
+                    println!("\nSummary:");
+                    println!("  Newly completed: {}", synced);
+                    println!("  Already synced: {}", skipped);
+                    println!("  Errors: {}", errors.len());
+                    db.finish_sync_run(run_id, synced, skipped, &errors)?;
+                    println!(
+                        "  Sync run: {} (details: prd sync-history {})",
+                        run_id, run_id
+                    );
+
+                    if !errors.is_empty() {
+                        std::process::exit(1);
+                    }
+                }
+            } else {
+                // File-based sync (Phase 1 implementation)
+                let result = sync::sync_tasks_from_docs(&db, &docs_dir, dry_run)?;
+
+                if result.newly_completed == 0 && result.already_synced == 0 {
+                    println!("{}", "No tasks to sync.".yellow());
+                }
+
+                if !result.failed.is_empty() {
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        SyncCommands::SyncHistory { run, limit, json } => {
+            let time = db.time_display()?;
+            let state = |run: &db::SyncRun| match (run.reverted_at, run.finished_at) {
+                (Some(at), _) => format!("reverted {}", time.format(at)),
+                (None, Some(_)) => "done".to_string(),
+                (None, None) => "interrupted".to_string(),
+            };
+            match run {
+                Some(run_id) => {
+                    let run = db
+                        .get_sync_run(run_id)?
+                        .ok_or_else(|| anyhow::anyhow!("Sync run {} not found", run_id))?;
+                    if json {
+                        println!("{}", serde_json::to_string_pretty(&run)?);
+                        return Ok(());
+                    }
+                    println!("{}", format!("Sync run {}", run.id).bold());
+                    println!("Source: {}", run.source);
+                    println!("Started: {}", time.format_precise(run.started_at));
+                    println!("State: {}", state(&run));
+                    println!("Changed: {}", run.changed.to_string().green());
+                    println!("Unchanged: {}", run.unchanged);
+                    let completed = db.sync_run_task_ids(run.id)?;
+                    if !completed.is_empty() {
+                        println!("\n{}", "Completed tasks:".bold());
+                        for task_id in &completed {
+                            let title = db.get_task(task_id)?.map(|t| t.title).unwrap_or_default();
+                            println!(
+                                "  {} {}",
+                                format_task_id(db.get_connection(), task_id).cyan(),
+                                title
+                            );
+                        }
+                    }
+                    if !run.errors.is_empty() {
+                        println!("\n{}", format!("Errors ({}):", run.errors.len()).red());
+                        for error in &run.errors {
+                            println!("  {}", error);
+                        }
+                    }
+                }
+                None => {
+                    let runs = db.list_sync_runs(limit)?;
+                    if json {
+                        println!("{}", serde_json::to_string_pretty(&runs)?);
+                    } else if runs.is_empty() {
+                        println!("No sync runs recorded yet");
+                    } else {
+                        let rows: Vec<SyncRunRow> = runs
+                            .iter()
+                            .map(|run| SyncRunRow {
+                                id: run.id,
+                                source: run.source.clone(),
+                                started: time.format(run.started_at),
+                                changed: run.changed,
+                                unchanged: run.unchanged,
+                                errors: run.errors.len(),
+                                state: state(run),
+                            })
+                            .collect();
+                        println!("{}", Table::new(rows).with(Style::modern()));
+                    }
+                }
+            }
+        }
+
+        SyncCommands::Reconcile {
+            auto_fix,
+            docs_dir,
+            backup,
+        } => {
+            let docs_dir = docs_dir.map_or_else(|| db.docs_dir(), Ok)?;
+            if backup {
+                let store = prd_tool::snapshot::SnapshotStore::for_database(ctx.database);
+                let snapshot = store.create(db.get_connection(), "before-reconcile")?;
+                println!(
+                    "{} Created snapshot #{} ({})",
+                    "✓".green(),
+                    snapshot.id,
+                    store.path(&snapshot).display().to_string().dimmed()
+                );
+            }
+
+            let result = sync::reconcile(&db, &docs_dir, auto_fix)?;
+
+            if result.fixed_count == 0 && result.inconsistencies.is_empty() {
+                println!("{}", "Database is healthy!".green().bold());
+            }
+
+            if !result.failed.is_empty() {
+                std::process::exit(1);
+            }
+        }
+
+        SyncCommands::InstallGitHook { uninstall, status } => {
+            use prd_tool::git::GitHookManager;
+
+            let repo_path = std::env::current_dir()?;
+            let hook_manager = GitHookManager::new(repo_path);
+
+            if status {
+                hook_manager.status()?;
+            } else if uninstall {
+                hook_manager.uninstall()?;
+            } else {
+                hook_manager.install()?;
+            }
+        }
+
+        SyncCommands::WatchFiles {
+            daemon,
+            status,
+            stop,
+            docs_path,
+            daemon_mode,
+            action,
+        } => {
+            use prd_tool::watcher;
+            use std::sync::atomic::Ordering;
+            use std::sync::Arc;
+
+            let docs_path = docs_path.map_or_else(|| db.docs_dir(), Ok)?;
+            if let Some(WatchFilesAction::Reload) = action {
+                watcher::daemon::request_reload()?;
+            } else if let Some(WatchFilesAction::Failures { clear, json }) = action {
+                let store = watcher::retry::failures_file();
+                let failures = watcher::retry::load_failures(&store)?;
+                if clear {
+                    watcher::retry::clear_failures(&store)?;
+                    println!(
+                        "{} Cleared {} failed document(s)",
+                        "✓".green().bold(),
+                        failures.len()
+                    );
+                    println!("  Run 'prd watch-files reload' to retry them in a running watcher");
+                } else if json {
+                    println!("{}", serde_json::to_string_pretty(&failures)?);
+                } else if failures.is_empty() {
+                    println!("No failed completion documents");
+                } else {
+                    for failure in &failures {
+                        println!(
+                            "{} {}",
+                            "✗".red().bold(),
+                            failure.path.display().to_string().bold()
+                        );
+                        println!(
+                            "  {} attempts, last {}: {}",
+                            failure.attempts,
+                            db.time_display()?.format_precise(failure.last_failed_at),
+                            failure.error
+                        );
+                    }
+                    println!(
+                        "\nEditing a document retries it; 'prd watch-files failures --clear' retries all"
+                    );
+                }
+            } else if status {
+                watcher::daemon::status()?;
+            } else if stop {
+                watcher::daemon::stop_daemon()?;
+            } else if daemon {
+                let db_path = ctx.database.to_path_buf();
+                watcher::daemon::start_daemon(docs_path, db_path)?;
+            } else if daemon_mode {
+                // Internal: running as daemon
+                let _instance = watcher::daemon::acquire_instance_lock()?;
+
+                let retention = prd_tool::retention::RetentionConfig::load()?;
+                let mut watcher = watcher::FileWatcher::new(docs_path, db)?
+                    .with_config(watcher::WatcherConfig::load()?)?
+                    .with_retention(retention);
+                watcher::daemon::install_reload_handler(Arc::clone(&watcher.reload))?;
+
+                // Setup signal handler for graceful shutdown
+                let running = Arc::clone(&watcher.running);
+                ctrlc::set_handler(move || {
+                    running.store(false, Ordering::SeqCst);
+                })
+                .expect("Error setting Ctrl+C handler");
+
+                watcher.start()?;
+            } else {
+                // Foreground mode
+                let mut watcher = watcher::FileWatcher::new(docs_path, db)?
+                    .with_config(watcher::WatcherConfig::load()?)?;
+
+                // Setup Ctrl+C handler
+                let running = Arc::clone(&watcher.running);
+                ctrlc::set_handler(move || {
+                    println!("\nReceived Ctrl+C, stopping...");
+                    running.store(false, Ordering::SeqCst);
+                })
+                .expect("Error setting Ctrl+C handler");
+
+                watcher.start()?;
+            }
+        }
+
+        SyncCommands::Hooks { subcommand } => {
+            use prd_tool::hooks;
+
+            let shared = db.get_setting(hooks::SETTING_HOOKS_CONFIG)?.is_some();
+            match subcommand {
+                HooksSubcommand::Init => {
+                    hooks::init_hooks_config()?;
+                }
+                HooksSubcommand::List => {
+                    hooks::list_hooks(&db)?;
+                }
+                HooksSubcommand::Test {
+                    hook_name,
+                    task_id,
+                    agent_id,
+                } => {
+                    hooks::test_hook(&db, &hook_name, task_id.as_deref(), agent_id.as_deref())?;
+                }
+                HooksSubcommand::Enable { hook_name } => {
+                    hooks::enable_hook(&hook_name)?;
+                    if shared {
+                        println!(
+                            "{} The database shares its own hooks; run prd hooks push to apply",
+                            "ℹ".blue().bold()
+                        );
+                    }
+                }
+                HooksSubcommand::Disable { hook_name } => {
+                    hooks::disable_hook(&hook_name)?;
+                    if shared {
+                        println!(
+                            "{} The database shares its own hooks; run prd hooks push to apply",
+                            "ℹ".blue().bold()
+                        );
+                    }
+                }
+                HooksSubcommand::Push { clear: true } => {
+                    if hooks::unshare_config(&db)? {
+                        println!(
+                            "{} Stopped sharing hook and notification config",
+                            "✓".green().bold()
+                        );
+                    } else {
+                        println!("{}", "No shared config.".yellow());
+                    }
+                }
+                HooksSubcommand::Push { clear: false } => {
+                    for path in hooks::push_config(&db)? {
+                        println!("{} Pushed {}", "✓".green().bold(), path.display());
+                    }
+                }
+                HooksSubcommand::Pull => {
+                    let pulled = hooks::pull_config(&db)?;
+                    if pulled.is_empty() {
+                        println!("{}", "No shared config (prd hooks push first).".yellow());
+                    }
+                    for path in pulled {
+                        println!("{} Wrote {}", "✓".green().bold(), path.display());
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
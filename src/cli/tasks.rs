@@ -0,0 +1,2039 @@
+//! Tasks - creating, listing, showing and editing individual tasks
+
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::Subcommand;
+use colored::*;
+use db::{Database, Priority, TaskStatus};
+use db_extensions::AcceptanceCriteriaOps;
+use prd_tool::rules::EventKind;
+use prd_tool::{archive, colors, db, db_extensions, resolver, vectors};
+use resolver::{format_agent_id, format_task_id, resolve_agent_id, resolve_task_id};
+use std::path::Path;
+use tabled::{settings::Style, Table, Tabled};
+
+use super::{
+    format_priority, format_status, format_task_diff, held_for_approval, parse_date_bound,
+    parse_due_date, parse_ttl, print_next_occurrence, Context,
+};
+
+/// Commands on individual tasks
+#[derive(Subcommand)]
+pub enum TaskCommands {
+    /// Create a new task
+    Create {
+        /// Task title
+        title: String,
+        /// Task description
+        #[arg(short, long)]
+        description: Option<String>,
+        /// Priority (low, medium, high, critical, or a level from priority.levels)
+        #[arg(short, long)]
+        priority: Option<String>,
+        /// Parent task ID
+        #[arg(short = 'P', long)]
+        parent: Option<String>,
+        /// Epic name (group related tasks)
+        #[arg(short, long)]
+        epic: Option<String>,
+        /// Key prefix, e.g. "API" for API-42 (default: the ids.prefix setting)
+        #[arg(long)]
+        prefix: Option<String>,
+        /// Explicit key, e.g. an imported issue's "IOS-17"
+        #[arg(long, conflicts_with = "prefix")]
+        key: Option<String>,
+        /// Key from an outside system, e.g. "gh-1234"; re-running with the same
+        /// ID updates the existing task instead of creating a duplicate
+        #[arg(long)]
+        external_id: Option<String>,
+        /// Due date (YYYY-MM-DD, or a span from today such as 3d)
+        #[arg(long, value_parser = parse_due_date)]
+        due: Option<NaiveDate>,
+        /// Recreate the task when it is completed: daily, weekdays,
+        /// weekly[:mon,thu], monthly[:15], every 3d or every 2w
+        #[arg(long, value_parser = db::Recurrence::parse)]
+        repeat: Option<db::Recurrence>,
+        /// Story points for sprint planning
+        #[arg(long)]
+        points: Option<u32>,
+        /// Keep out of `ready`/`next` until then (YYYY-MM-DD, RFC 3339, or a
+        /// span from now such as 14d)
+        #[arg(long, value_parser = parse_start_date)]
+        start_after: Option<DateTime<Utc>>,
+        /// Print only the task ID
+        #[arg(short, long)]
+        quiet: bool,
+    },
+
+    /// List tasks
+    #[command(alias = "ls", alias = "tasks")]
+    List {
+        /// Filter by status (pending, in_progress, blocked, review, completed, failed, cancelled)
+        #[arg(short, long)]
+        status: Option<String>,
+        /// Nest subtasks under their parents
+        #[arg(short = 'S', long)]
+        subtasks: bool,
+        /// Nest at most N levels of subtasks (implies --subtasks)
+        #[arg(long, value_name = "N")]
+        depth: Option<usize>,
+        /// Filter by epic name
+        #[arg(short = 'E', long)]
+        epic: Option<String>,
+        /// Show only unassigned tasks
+        #[arg(long)]
+        no_agent: bool,
+        /// Filter by priority
+        #[arg(short = 'P', long)]
+        priority: Option<String>,
+        /// Filter by agent (ID or name)
+        #[arg(short = 'A', long)]
+        agent: Option<String>,
+        /// Limit number of results
+        #[arg(short = 'L', long)]
+        limit: Option<usize>,
+        /// Offset for pagination
+        #[arg(short = 'O', long)]
+        offset: Option<usize>,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+        /// Show progress and estimated completion for in-progress tasks
+        #[arg(long)]
+        eta: bool,
+        /// Minutes without progress before a task is flagged as stalled
+        #[arg(long, default_value_t = db::DEFAULT_STALL_MINUTES)]
+        stall_minutes: i64,
+        /// Columns to show, comma-separated (e.g. id,title,status,epic,eta)
+        #[arg(long, value_enum, value_delimiter = ',')]
+        columns: Vec<ListColumn>,
+        /// Don't truncate long titles
+        #[arg(short, long)]
+        wide: bool,
+        /// Created on or after (YYYY-MM-DD, RFC 3339, or an age such as 7d)
+        #[arg(long, value_parser = parse_date_bound)]
+        created_after: Option<DateTime<Utc>>,
+        /// Created before (YYYY-MM-DD, RFC 3339, or an age such as 7d)
+        #[arg(long, value_parser = parse_date_bound)]
+        created_before: Option<DateTime<Utc>>,
+        /// Completed on or after (YYYY-MM-DD, RFC 3339, or an age such as 7d)
+        #[arg(long, value_parser = parse_date_bound)]
+        completed_after: Option<DateTime<Utc>>,
+        /// Updated on or after (YYYY-MM-DD, RFC 3339, or an age such as 24h)
+        #[arg(long, value_parser = parse_date_bound)]
+        updated_since: Option<DateTime<Utc>>,
+        /// Due before this day (YYYY-MM-DD, or a span from today such as 3d)
+        #[arg(long, value_parser = parse_due_date)]
+        due_before: Option<NaiveDate>,
+        /// Only unfinished tasks past their due date
+        #[arg(long)]
+        overdue: bool,
+        /// Only unfinished tasks deferred to a later start date
+        #[arg(long)]
+        scheduled: bool,
+        /// Only tasks with this tag (repeatable; tasks must have all of them)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+        /// Include tasks moved to the archive by `prd archive`
+        #[arg(long)]
+        include_archived: bool,
+    },
+
+    /// Show task details
+    Show {
+        /// Task ID
+        id: String,
+        /// Show logs
+        #[arg(short, long)]
+        logs: bool,
+        /// Show progress history
+        #[arg(short, long)]
+        progress: bool,
+        /// Minutes without progress before a task is flagged as stalled
+        #[arg(long, default_value_t = db::DEFAULT_STALL_MINUTES)]
+        stall_minutes: i64,
+        /// Show similar tasks, code and docs from the vector index
+        #[arg(short, long)]
+        related: bool,
+        /// Most related items of each kind
+        #[arg(long, default_value = "3")]
+        related_limit: usize,
+        /// Minimum similarity for related items (0.0-1.0)
+        #[arg(long, default_value = "0.5")]
+        related_threshold: f32,
+        /// Look the task up in the archive when it isn't in the database
+        #[arg(long)]
+        include_archived: bool,
+    },
+
+    /// Comment on a task, or list its comments when no text is given
+    Comment {
+        /// Task ID
+        id: String,
+        /// Comment text
+        text: Option<String>,
+        /// Agent writing the comment (ID or name)
+        #[arg(short, long)]
+        agent: Option<String>,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Revert the task changes made by the last command (repeat to go further back)
+    ///
+    /// Commands that added rows, like `prd create`, can't be undone.
+    Undo {
+        /// Undo even if the changed fields have been changed again since
+        #[arg(long)]
+        force: bool,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Field-by-field change history of a task
+    History {
+        /// Task ID
+        id: String,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Attach a file path or URL to a task (screenshots, logs, generated docs)
+    Attach {
+        /// Task ID
+        id: String,
+        /// File path or URL
+        #[arg(required_unless_present = "remove")]
+        location: Option<String>,
+        /// Short description shown next to the attachment
+        #[arg(short, long)]
+        label: Option<String>,
+        /// Agent adding the attachment (ID or name)
+        #[arg(short, long)]
+        agent: Option<String>,
+        /// Remove the attachment with this ID instead
+        #[arg(long, value_name = "ATTACHMENT_ID", conflicts_with_all = ["location", "label"])]
+        remove: Option<i64>,
+    },
+
+    /// Bundle a task with its criteria, dependencies, subtasks, agent and
+    /// recent logs, for injecting into an agent prompt
+    Context {
+        /// Task ID
+        id: String,
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "markdown")]
+        format: ContextFormat,
+        /// Most recent log entries to include
+        #[arg(long, default_value_t = prd_tool::context::DEFAULT_CONTEXT_LOGS)]
+        logs: usize,
+    },
+
+    /// Summarize which agents worked on a task and for how long
+    Blame {
+        /// Task ID
+        id: String,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Update task status
+    Update {
+        /// Task ID
+        id: String,
+        /// New status (pending, in_progress, blocked, review, completed, failed, cancelled)
+        #[arg(required_unless_present_any = ["due", "clear_due", "points", "start_after", "clear_start"])]
+        status: Option<String>,
+        /// Set the due date (YYYY-MM-DD, or a span from today such as 3d)
+        #[arg(long, value_parser = parse_due_date, conflicts_with = "clear_due")]
+        due: Option<NaiveDate>,
+        /// Remove the due date
+        #[arg(long)]
+        clear_due: bool,
+        /// Set the story points
+        #[arg(long)]
+        points: Option<u32>,
+        /// Defer the task until then (YYYY-MM-DD, RFC 3339, or a span from now
+        /// such as 14d)
+        #[arg(long, value_parser = parse_start_date, conflicts_with = "clear_start")]
+        start_after: Option<DateTime<Utc>>,
+        /// Make a deferred task available now
+        #[arg(long)]
+        clear_start: bool,
+        /// Agent ID performing the update
+        #[arg(short, long)]
+        agent: Option<String>,
+        /// Why the task is blocked (only with the blocked status)
+        #[arg(short, long, requires = "status")]
+        reason: Option<String>,
+        /// Make a status change the allowed transitions don't permit
+        #[arg(long, requires = "status")]
+        force: bool,
+    },
+
+    /// Break down a task into subtasks
+    Breakdown {
+        /// Parent task ID
+        id: String,
+        /// Number of subtasks to create interactively
+        #[arg(short, long)]
+        interactive: bool,
+    },
+
+    /// Label tasks with cross-cutting tags (backend, needs-design, tech-debt)
+    Tag {
+        #[command(subcommand)]
+        action: TagAction,
+    },
+
+    /// Show tasks added, removed and changed between two states
+    ///
+    /// Each side is a snapshot (ID or name) or a database file; with one
+    /// argument, it is compared against the current database.
+    Diff {
+        /// Earlier state
+        from: String,
+        /// Later state (default: the current database)
+        to: Option<String>,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Show the parent/subtask hierarchy as a tree
+    Tree {
+        /// Root task ID (shows all top-level tasks if omitted)
+        id: Option<String>,
+        /// Only show trees within an epic
+        #[arg(short = 'E', long, conflicts_with = "id")]
+        epic: Option<String>,
+        /// Show at most N levels of subtasks
+        #[arg(long, value_name = "N")]
+        depth: Option<usize>,
+    },
+
+    /// Manage acceptance criteria
+    Ac {
+        /// Task ID
+        task_id: String,
+        #[command(subcommand)]
+        action: AcAction,
+    },
+
+    /// Set task duration estimates
+    Duration {
+        /// Task ID
+        id: String,
+        /// Estimated duration in minutes
+        #[arg(short, long)]
+        estimated: Option<i32>,
+        /// Actual duration in minutes
+        #[arg(short, long)]
+        actual: Option<i32>,
+    },
+
+    /// Set a task's business value and effort for WSJF scoring
+    Value {
+        /// Task ID
+        id: String,
+        /// Business value (cost of delay), e.g. 1-20
+        #[arg(short, long, required_unless_present_any = ["effort", "clear"])]
+        business_value: Option<i32>,
+        /// Effort (job size), e.g. 1-20
+        #[arg(short, long)]
+        effort: Option<i32>,
+        /// Remove both estimates
+        #[arg(long, conflicts_with_all = ["business_value", "effort"])]
+        clear: bool,
+    },
+
+    /// Set a task's story points for sprint planning
+    Points {
+        /// Task ID
+        id: String,
+        /// Story points, e.g. 1, 2, 3, 5, 8
+        #[arg(required_unless_present = "clear")]
+        points: Option<u32>,
+        /// Remove the estimate
+        #[arg(long, conflicts_with = "points")]
+        clear: bool,
+    },
+
+    /// Make a task recurring: completing it creates the next instance
+    Repeat {
+        /// Task ID
+        id: String,
+        /// daily, weekdays, weekly[:mon,thu], monthly[:15], every 3d or every 2w
+        #[arg(required_unless_present = "clear", value_parser = db::Recurrence::parse)]
+        rule: Option<db::Recurrence>,
+        /// Stop the task recurring
+        #[arg(long, conflicts_with = "rule")]
+        clear: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AcAction {
+    /// Add acceptance criterion
+    Add {
+        /// Criterion text
+        criterion: String,
+    },
+    /// List all acceptance criteria
+    List,
+    /// Mark criterion as completed
+    Check {
+        /// Criterion ID
+        id: i32,
+    },
+    /// Mark criterion as incomplete
+    Uncheck {
+        /// Criterion ID
+        id: i32,
+    },
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+pub enum ContextFormat {
+    /// Markdown sections
+    Markdown,
+    /// One JSON document
+    Json,
+}
+
+#[derive(Subcommand)]
+pub enum TagAction {
+    /// Add tags to a task
+    Add {
+        /// Task ID
+        id: String,
+        /// Tags to add
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+    /// Remove tags from a task
+    Remove {
+        /// Task ID
+        id: String,
+        /// Tags to remove
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+    /// List a task's tags, or every tag in use with its task count
+    List {
+        /// Task ID
+        id: Option<String>,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// A column of `prd list` output
+#[derive(Clone, Copy, Debug, PartialEq, clap::ValueEnum)]
+pub enum ListColumn {
+    Id,
+    Uuid,
+    Title,
+    Status,
+    Priority,
+    Agent,
+    Epic,
+    Created,
+    Updated,
+    Completed,
+    Progress,
+    Eta,
+    Due,
+    Tags,
+}
+
+impl ListColumn {
+    /// Columns shown without `--columns`
+    const DEFAULT: [ListColumn; 6] = [
+        ListColumn::Id,
+        ListColumn::Title,
+        ListColumn::Status,
+        ListColumn::Priority,
+        ListColumn::Agent,
+        ListColumn::Created,
+    ];
+
+    /// Columns shown with `--eta`
+    const ETA: [ListColumn; 5] = [
+        ListColumn::Id,
+        ListColumn::Title,
+        ListColumn::Status,
+        ListColumn::Progress,
+        ListColumn::Eta,
+    ];
+
+    fn header(self) -> &'static str {
+        match self {
+            ListColumn::Id => "ID",
+            ListColumn::Uuid => "UUID",
+            ListColumn::Title => "Title",
+            ListColumn::Status => "Status",
+            ListColumn::Priority => "Priority",
+            ListColumn::Agent => "Agent",
+            ListColumn::Epic => "Epic",
+            ListColumn::Created => "Created",
+            ListColumn::Updated => "Updated",
+            ListColumn::Completed => "Completed",
+            ListColumn::Progress => "Progress",
+            ListColumn::Eta => "ETA",
+            ListColumn::Due => "Due",
+            ListColumn::Tags => "Tags",
+        }
+    }
+}
+
+/// Longest title shown in `prd list` unless `--wide` is given
+const LIST_TITLE_WIDTH: usize = 40;
+
+#[derive(Tabled)]
+struct BlameRow {
+    #[tabled(rename = "Agent")]
+    agent: String,
+    #[tabled(rename = "Time")]
+    time: String,
+    #[tabled(rename = "Assigned")]
+    assignments: usize,
+    #[tabled(rename = "Status changes")]
+    status_changes: usize,
+    #[tabled(rename = "Progress")]
+    progress_reports: usize,
+    #[tabled(rename = "Commits")]
+    commits: usize,
+    #[tabled(rename = "Last touched")]
+    last_touched: String,
+}
+
+/// Runs a task command against the open database
+pub fn run(command: TaskCommands, db: Database, ctx: &Context) -> Result<()> {
+    let invocation = ctx.invocation;
+
+    match command {
+        TaskCommands::Create {
+            title,
+            description,
+            priority,
+            parent,
+            epic,
+            prefix,
+            key,
+            external_id,
+            due,
+            repeat,
+            points,
+            start_after,
+            quiet,
+        } => {
+            let priority = priority.map(|name| db.parse_priority(&name)).transpose()?;
+            let prefix = match prefix {
+                Some(prefix) => Some(prefix),
+                None => db.get_setting(db::SETTING_ID_PREFIX)?,
+            };
+            let (task, key, outcome) = db.write_transaction(|| {
+                let (mut task, mut outcome) = match &external_id {
+                    Some(external_id) => db.upsert_external_task(
+                        external_id,
+                        title,
+                        description,
+                        priority,
+                        parent,
+                        epic,
+                    )?,
+                    None => {
+                        let priority = match priority {
+                            Some(priority) => priority,
+                            None => db.priority_levels()?.default_level(),
+                        };
+                        let task = db.create_task(title, description, priority, parent, epic)?;
+                        (task, db::ExternalUpsert::Created)
+                    }
+                };
+                // Existing tasks keep the key they were created with
+                let key = match (outcome, &key, &prefix) {
+                    (db::ExternalUpsert::Created, Some(key), _) => {
+                        Some(db.set_display_key(&task.id, key)?)
+                    }
+                    (db::ExternalUpsert::Created, None, Some(prefix)) => {
+                        Some(db.assign_next_display_key(&task.id, prefix)?)
+                    }
+                    _ => db.get_display_key(&task.id)?,
+                };
+                if let Some(due) = due.filter(|due| task.due_date != Some(*due)) {
+                    db.set_task_due_date(&task.id, Some(due), None)?;
+                    task.due_date = Some(due);
+                    if outcome == db::ExternalUpsert::Unchanged {
+                        outcome = db::ExternalUpsert::Updated;
+                    }
+                }
+                if let Some(points) = points {
+                    if db.get_task_points(&task.id)? != Some(points) {
+                        db.set_task_points(&task.id, Some(points))?;
+                        if outcome == db::ExternalUpsert::Unchanged {
+                            outcome = db::ExternalUpsert::Updated;
+                        }
+                    }
+                }
+                if let Some(start) = start_after {
+                    if db.get_task_start_after(&task.id)? != Some(start) {
+                        db.set_task_start_after(&task.id, Some(start), None)?;
+                        if outcome == db::ExternalUpsert::Unchanged {
+                            outcome = db::ExternalUpsert::Updated;
+                        }
+                    }
+                }
+                if let Some(repeat) = &repeat {
+                    if db.get_task_recurrence(&task.id)?.as_ref() != Some(repeat) {
+                        db.set_task_recurrence(&task.id, Some(repeat), None)?;
+                        if outcome == db::ExternalUpsert::Unchanged {
+                            outcome = db::ExternalUpsert::Updated;
+                        }
+                    }
+                }
+                Ok((task, key, outcome))
+            })?;
+            if outcome == db::ExternalUpsert::Created {
+                invocation.emit(EventKind::Created, &task.id, !quiet);
+            }
+            let display_id = key.unwrap_or_else(|| {
+                task.display_id
+                    .map(|id| format!("#{}", id))
+                    .unwrap_or_else(|| task.id[..8].to_string())
+            });
+            if quiet {
+                println!("{}", display_id);
+                return Ok(());
+            }
+            match outcome {
+                db::ExternalUpsert::Created => {
+                    println!("{}", "✓ Task created successfully!".green().bold())
+                }
+                db::ExternalUpsert::Updated => {
+                    println!("{}", "✓ Existing task updated".green().bold())
+                }
+                db::ExternalUpsert::Unchanged => {
+                    println!("{} Task already exists, nothing changed", "ℹ".blue().bold())
+                }
+            }
+            println!("ID: {}", display_id.cyan());
+            if let Some(external_id) = &external_id {
+                println!("External ID: {}", external_id);
+            }
+            println!("Title: {}", task.title);
+            println!("Priority: {}", task.priority.as_str().yellow());
+            if let Some(due) = task.due_date {
+                println!("Due: {}", due);
+            }
+            if let Some(repeat) = &repeat {
+                println!("Repeats: {}", repeat);
+            }
+            if let Some(points) = points {
+                println!("Points: {}", points);
+            }
+            if let Some(epic_name) = &task.epic_name {
+                println!("Epic: {}", epic_name.cyan());
+            }
+        }
+
+        TaskCommands::List {
+            status,
+            subtasks,
+            depth,
+            epic,
+            no_agent,
+            priority,
+            agent,
+            limit,
+            offset,
+            json,
+            eta,
+            stall_minutes,
+            columns,
+            wide,
+            created_after,
+            created_before,
+            completed_after,
+            updated_since,
+            due_before,
+            overdue,
+            scheduled,
+            tags,
+            include_archived,
+        } => {
+            let stall_window = chrono::Duration::minutes(stall_minutes);
+            let status_filter = status.map(|s| db.parse_status(&s)).transpose()?;
+            let priority_filter = priority.map(|p| db.parse_priority(&p)).transpose()?;
+            let today = Utc::now().date_naive();
+            let workflow = db.workflow()?;
+            let time = db.time_display()?;
+            let colors = colors::ColorScheme::load(&db)?;
+            let dates = db::TaskDateFilter {
+                created_after,
+                created_before,
+                completed_after,
+                updated_since,
+                due_before: match (due_before, overdue) {
+                    (Some(day), true) => Some(day.min(today)),
+                    (None, true) => Some(today),
+                    (day, false) => day,
+                },
+            };
+            let mut tasks = db.list_tasks_filtered(status_filter.clone(), &dates)?;
+            let archive = if include_archived {
+                archive::open(ctx.database.to_str().unwrap())?
+            } else {
+                None
+            };
+            if let Some(archive) = &archive {
+                tasks.extend(archive.list_tasks_filtered(status_filter, &dates)?);
+            }
+            if overdue {
+                tasks.retain(|t| workflow.bucket(&t.status) != db::StatusBucket::Done);
+            }
+
+            if scheduled {
+                let deferred: std::collections::HashSet<String> = db
+                    .list_scheduled_tasks(Utc::now())?
+                    .into_iter()
+                    .map(|(id, _)| id)
+                    .collect();
+                tasks.retain(|t| deferred.contains(&t.id));
+            }
+
+            // Apply additional filters
+            if let Some(epic_name) = epic {
+                tasks.retain(|t| t.epic_name.as_ref().map_or(false, |e| e == &epic_name));
+            }
+            if no_agent {
+                tasks.retain(|t| t.assigned_agent.is_none());
+            }
+            if !tags.is_empty() {
+                let mut tagged = db.task_ids_with_tags(&tags)?;
+                if let Some(archive) = &archive {
+                    tagged.extend(archive.task_ids_with_tags(&tags)?);
+                }
+                tasks.retain(|t| tagged.contains(&t.id));
+            }
+            if let Some(prio) = priority_filter {
+                tasks.retain(|t| t.priority == prio);
+            }
+            if let Some(agent_filter) = agent {
+                // Try to resolve agent ID
+                let agent_uuid_result = resolve_agent_id(db.get_connection(), &agent_filter);
+                if let Ok(agent_uuid) = agent_uuid_result {
+                    tasks.retain(|t| {
+                        t.assigned_agent
+                            .as_ref()
+                            .map_or(false, |a| a == &agent_uuid)
+                    });
+                } else {
+                    // If resolution fails, no matches
+                    tasks.clear();
+                }
+            }
+
+            // Apply pagination
+            let total_count = tasks.len();
+            let offset_val = offset.unwrap_or(0);
+            if offset_val > 0 && offset_val < tasks.len() {
+                tasks = tasks.into_iter().skip(offset_val).collect();
+            } else if offset_val >= tasks.len() {
+                tasks.clear();
+            }
+            if let Some(limit_val) = limit {
+                tasks.truncate(limit_val);
+            }
+
+            if tasks.is_empty() {
+                if !json {
+                    println!("{}", "No tasks found.".yellow());
+                } else {
+                    println!("[]");
+                }
+                return Ok(());
+            }
+
+            // In tree mode each row carries its place in the hierarchy
+            let by_id: std::collections::HashMap<&str, &db::Task> =
+                tasks.iter().map(|t| (t.id.as_str(), t)).collect();
+            let tree_tasks: Vec<db::Task>;
+            let rows: Vec<(&db::Task, Option<prd_tool::visualization::TreeNode>)> =
+                if subtasks || depth.is_some() {
+                    let mut all_tasks = db.list_tasks(None)?;
+                    if archive.is_some() {
+                        if let Some(archive_db) =
+                            prd_tool::archive::open(ctx.database.to_str().unwrap())?
+                        {
+                            all_tasks.extend(archive_db.list_tasks(None)?);
+                        }
+                    }
+                    tree_tasks = all_tasks
+                        .into_iter()
+                        .filter(|t| by_id.contains_key(t.id.as_str()))
+                        .collect();
+                    let forest = prd_tool::visualization::TaskForest::new(&tree_tasks)
+                        .with_workflow(db.workflow()?);
+                    forest
+                        .flatten(&forest.roots(), depth)
+                        .into_iter()
+                        .map(|node| (by_id[node.task.id.as_str()], Some(node)))
+                        .collect()
+                } else {
+                    tasks.iter().map(|t| (t, None)).collect()
+                };
+
+            // JSON output
+            if json {
+                #[derive(serde::Serialize)]
+                struct TaskJson {
+                    id: String,
+                    uuid: String,
+                    title: String,
+                    description: Option<String>,
+                    status: String,
+                    priority: String,
+                    agent: Option<String>,
+                    epic: Option<String>,
+                    created_at: String,
+                    updated_at: String,
+                    completed_at: Option<String>,
+                    #[serde(skip_serializing_if = "Option::is_none")]
+                    eta: Option<db::TaskEta>,
+                    #[serde(skip_serializing_if = "Option::is_none")]
+                    depth: Option<usize>,
+                }
+
+                let json_tasks: Vec<TaskJson> = rows
+                    .iter()
+                    .map(|(t, node)| TaskJson {
+                        id: t
+                            .display_id
+                            .map(|id| format!("#{}", id))
+                            .unwrap_or_else(|| t.id[..8].to_string()),
+                        uuid: t.id.clone(),
+                        title: t.title.clone(),
+                        description: t.description.clone(),
+                        status: t.status.as_str().to_string(),
+                        priority: t.priority.as_str().to_string(),
+                        agent: t.assigned_agent.as_ref().and_then(|uuid| {
+                            db.get_agent(uuid)
+                                .ok()
+                                .flatten()
+                                .and_then(|a| a.display_id.map(|id| format!("A{}", id)))
+                        }),
+                        epic: t.epic_name.clone(),
+                        created_at: t.created_at.to_rfc3339(),
+                        updated_at: t.updated_at.to_rfc3339(),
+                        completed_at: t.completed_at.map(|dt| dt.to_rfc3339()),
+                        eta: if eta && t.status == TaskStatus::InProgress {
+                            t.display_id
+                                .and_then(|id| db.get_task_eta(id, stall_window).ok().flatten())
+                        } else {
+                            None
+                        },
+                        depth: node.as_ref().map(|n| n.depth),
+                    })
+                    .collect();
+
+                println!("{}", serde_json::to_string_pretty(&json_tasks)?);
+                return Ok(());
+            }
+
+            let columns = if !columns.is_empty() {
+                columns
+            } else if eta {
+                ListColumn::ETA.to_vec()
+            } else {
+                ListColumn::DEFAULT.to_vec()
+            };
+            let wants_eta = columns
+                .iter()
+                .any(|c| matches!(c, ListColumn::Progress | ListColumn::Eta));
+
+            let mut builder = tabled::builder::Builder::default();
+            builder.push_record(columns.iter().map(|c| c.header()));
+            for (t, node) in &rows {
+                let task_eta = match (&t.status, t.display_id) {
+                    (TaskStatus::InProgress, Some(id)) if wants_eta => {
+                        db.get_task_eta(id, stall_window).ok().flatten()
+                    }
+                    _ => None,
+                };
+                builder.push_record(columns.iter().map(|column| {
+                    match column {
+                        ListColumn::Id => t
+                            .display_id
+                            .map(|id| format!("#{}", id))
+                            .unwrap_or_else(|| t.id[..8].to_string()),
+                        ListColumn::Uuid => t.id.clone(),
+                        ListColumn::Title => {
+                            let mut title = if !wide && t.title.chars().count() > LIST_TITLE_WIDTH {
+                                let cut: String =
+                                    t.title.chars().take(LIST_TITLE_WIDTH - 3).collect();
+                                format!("{}...", cut)
+                            } else {
+                                t.title.clone()
+                            };
+                            if let Some(node) = node {
+                                title.insert_str(0, &node.prefix);
+                                let (completed, total) = node.subtotal;
+                                if total > 0 {
+                                    let counts = format!("({}/{})", completed, total);
+                                    title.push_str(&format!(" {}", counts.dimmed()));
+                                }
+                            }
+                            title
+                        }
+                        ListColumn::Status => format_status(&t.status),
+                        ListColumn::Priority => paint_priority(&t.priority, &colors),
+                        ListColumn::Agent => t
+                            .assigned_agent
+                            .as_ref()
+                            .and_then(|uuid| {
+                                db.get_agent(uuid)
+                                    .ok()
+                                    .flatten()
+                                    .and_then(|a| a.display_id.map(|id| format!("A{}", id)))
+                            })
+                            .unwrap_or_else(|| "-".to_string()),
+                        ListColumn::Epic => match &t.epic_name {
+                            Some(epic) => paint_epic(epic, &colors),
+                            None => "-".to_string(),
+                        },
+                        ListColumn::Created => time.format(t.created_at),
+                        ListColumn::Updated => time.format(t.updated_at),
+                        ListColumn::Completed => t
+                            .completed_at
+                            .map(|dt| time.format(dt))
+                            .unwrap_or_else(|| "-".to_string()),
+                        ListColumn::Progress => task_eta
+                            .as_ref()
+                            .map(|e| format!("{}%", e.progress))
+                            .unwrap_or_else(|| "-".to_string()),
+                        ListColumn::Eta => task_eta
+                            .as_ref()
+                            .map(format_eta)
+                            .unwrap_or_else(|| "-".to_string()),
+                        ListColumn::Due => match t.due_date {
+                            Some(due)
+                                if due < today
+                                    && workflow.bucket(&t.status) != db::StatusBucket::Done =>
+                            {
+                                due.to_string().red().to_string()
+                            }
+                            Some(due) => due.to_string(),
+                            None => "-".to_string(),
+                        },
+                        ListColumn::Tags => match db.get_task_tags(&t.id).unwrap_or_default() {
+                            tags if tags.is_empty() => "-".to_string(),
+                            tags => colors.paint_tags(&tags, |tag| tag.normal()),
+                        },
+                    }
+                }));
+            }
+
+            let mut table = builder.build();
+            table.with(Style::modern());
+            println!("{}", table);
+
+            if limit.is_some() || offset.is_some() {
+                println!(
+                    "\n{} of {} tasks total",
+                    tasks.len().to_string().cyan().bold(),
+                    total_count.to_string().cyan().bold()
+                );
+            } else {
+                println!("\n{} tasks total", tasks.len().to_string().cyan().bold());
+            }
+        }
+
+        TaskCommands::Attach {
+            id,
+            location,
+            label,
+            agent,
+            remove,
+        } => {
+            let task_id = resolve_task_id(db.get_connection(), &id)?;
+            let agent_id = agent
+                .as_deref()
+                .map(|a| resolve_agent_id(db.get_connection(), a))
+                .transpose()?;
+            if let Some(attachment_id) = remove {
+                if !db.remove_attachment(&task_id, attachment_id, agent_id.as_deref())? {
+                    anyhow::bail!("{} has no attachment {}", id, attachment_id);
+                }
+                println!(
+                    "{} Removed attachment {} from {}",
+                    "✓".green().bold(),
+                    attachment_id,
+                    id
+                );
+            } else if let Some(location) = location {
+                let attachment =
+                    db.add_attachment(&task_id, &location, label.as_deref(), agent_id.as_deref())?;
+                println!(
+                    "{} Attached {} to {} (attachment {})",
+                    "✓".green().bold(),
+                    attachment.location.cyan(),
+                    id,
+                    attachment.id
+                );
+                if !attachment.is_url() && !Path::new(&attachment.location).exists() {
+                    println!("{} File doesn't exist yet", "⚠".yellow());
+                }
+            }
+        }
+
+        TaskCommands::Comment {
+            id,
+            text,
+            agent,
+            json,
+        } => {
+            let task_id = resolve_task_id(db.get_connection(), &id)?;
+            if let Some(text) = text {
+                let agent_id = agent
+                    .as_deref()
+                    .map(|a| resolve_agent_id(db.get_connection(), a))
+                    .transpose()?;
+                let comment = db.add_comment(&task_id, agent_id.as_deref(), &text)?;
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&comment)?);
+                } else {
+                    println!("{} Commented on {}", "✓".green().bold(), id);
+                }
+            } else {
+                let comments = db.get_task_comments(&task_id)?;
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&comments)?);
+                } else if comments.is_empty() {
+                    println!("{}", "No comments.".yellow());
+                } else {
+                    print_comments(&db, &comments)?;
+                }
+            }
+        }
+
+        TaskCommands::Undo { force, json } => {
+            let Some(operation) = db.last_operation()? else {
+                if json {
+                    println!("null");
+                } else {
+                    println!("{}", "Nothing to undo.".yellow());
+                }
+                return Ok(());
+            };
+            let time = db.time_display()?;
+            if !json {
+                println!(
+                    "Undoing `prd {}` from {}",
+                    operation.command,
+                    time.format(operation.created_at)
+                );
+            }
+            let report = db.undo_operation(&operation, force)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+                return Ok(());
+            }
+            println!(
+                "{} Undid `prd {}`",
+                "✓".green().bold(),
+                report.operation.command
+            );
+            let conn = db.get_connection();
+            for change in &report.reverted {
+                let show =
+                    |value: &Option<String>| format_history_value(conn, &change.field, value);
+                println!(
+                    "  {} {}: {} → {}",
+                    format_task_id(conn, &change.task_id).cyan(),
+                    change.field,
+                    show(&change.new_value),
+                    show(&change.old_value)
+                );
+            }
+        }
+
+        TaskCommands::History { id, json } => {
+            let task_id = resolve_task_id(db.get_connection(), &id)?;
+            let changes = db.get_task_history(&task_id)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&changes)?);
+            } else if changes.is_empty() {
+                println!("{}", "No changes recorded.".yellow());
+            } else {
+                print_task_history(&db, &changes)?;
+            }
+        }
+
+        TaskCommands::Show {
+            id,
+            logs,
+            progress,
+            stall_minutes,
+            related,
+            related_limit,
+            related_threshold,
+            include_archived,
+        } => {
+            // Resolve ID (supports #42, 42, or UUID), falling back to the
+            // archive, which is then read in place of the database
+            let archive;
+            let (db, task_uuid) = match resolve_task_id(db.get_connection(), &id) {
+                Ok(task_uuid) => (&db, task_uuid),
+                Err(err) if include_archived => {
+                    match archive::open(ctx.database.to_str().unwrap())? {
+                        Some(opened) => {
+                            archive = opened;
+                            (&archive, resolve_task_id(archive.get_connection(), &id)?)
+                        }
+                        None => return Err(err),
+                    }
+                }
+                Err(err) => return Err(err),
+            };
+            let task = db.get_task(&task_uuid)?;
+            let time = db.time_display()?;
+            let colors = colors::ColorScheme::load(db)?;
+            match task {
+                Some(t) => {
+                    println!("\n{}", "Task Details".bold().underline());
+                    let display_id = t
+                        .display_id
+                        .map(|id| format!("#{}", id))
+                        .unwrap_or_else(|| t.id[..8].to_string());
+                    match db.get_display_key(&t.id)? {
+                        Some(key) => println!("ID: {} ({})", key.cyan(), display_id),
+                        None => println!("ID: {}", display_id.cyan()),
+                    }
+                    if let Some(external_id) = db.get_external_id(&t.id)? {
+                        println!("External ID: {}", external_id);
+                    }
+                    println!("Title: {}", t.title.bold());
+                    if let Some(desc) = &t.description {
+                        println!("Description: {}", desc);
+                    }
+                    println!("Status: {}", format_status(&t.status));
+                    println!("Priority: {}", paint_priority(&t.priority, &colors));
+                    if let Some(epic) = &t.epic_name {
+                        let epic = match colors.epic(epic) {
+                            Some(color) => color.paint(epic),
+                            None => epic.cyan(),
+                        };
+                        println!("Epic: {}", epic);
+                    }
+                    if let Some(due) = t.due_date {
+                        let overdue = due < Utc::now().date_naive()
+                            && db.workflow()?.bucket(&t.status) != db::StatusBucket::Done;
+                        if overdue {
+                            println!("Due: {} {}", due, "(overdue)".red().bold());
+                        } else {
+                            println!("Due: {}", due);
+                        }
+                    }
+                    if let Some(start) = db.get_task_start_after(&t.id)? {
+                        let start_at = db.time_display()?.format(start);
+                        if start > Utc::now() {
+                            println!("Starts: {} {}", start_at, "(scheduled)".yellow());
+                        } else {
+                            println!("Starts: {}", start_at);
+                        }
+                    }
+                    if let Some(points) = db.get_task_points(&t.id)? {
+                        println!("Points: {}", points);
+                    }
+                    let attempt = db.get_task_attempt(&t.id)?;
+                    if attempt > 1 {
+                        println!("Attempt: {}", attempt);
+                    }
+                    if let Some(recurrence) = db.get_task_recurrence(&t.id)? {
+                        println!("Repeats: {}", recurrence.to_string().cyan());
+                    }
+                    let tags = db.get_task_tags(&t.id)?;
+                    if !tags.is_empty() {
+                        println!("Tags: {}", colors.paint_tags(&tags, |tag| tag.cyan()));
+                    }
+                    if let Some(agent_uuid) = &t.assigned_agent {
+                        let agent_display = db
+                            .get_agent(agent_uuid)
+                            .ok()
+                            .flatten()
+                            .and_then(|a| a.display_id.map(|id| format!("A{} ({})", id, a.name)))
+                            .unwrap_or_else(|| agent_uuid[..8].to_string());
+                        println!("Assigned to: {}", agent_display.cyan());
+                    }
+                    if let Some(lease) = db.get_active_lease(&t.id)? {
+                        println!(
+                            "Leased to: {} until {}",
+                            format_agent_id(db.get_connection(), &lease.agent_id).cyan(),
+                            time.format(lease.expires_at)
+                        );
+                    }
+                    if let Some(parent) = &t.parent_id {
+                        let parent_display = db
+                            .get_task(parent)
+                            .ok()
+                            .flatten()
+                            .and_then(|p| p.display_id.map(|id| format!("#{}", id)))
+                            .unwrap_or_else(|| parent[..8].to_string());
+                        println!("Parent task: {}", parent_display.cyan());
+                    }
+                    if let Some(est) = t.estimated_duration {
+                        println!("Estimated duration: {} minutes", est);
+                    }
+                    if let Some(act) = t.actual_duration {
+                        println!("Actual duration: {} minutes", act);
+                    }
+                    let value = db.get_task_value(&t.id)?;
+                    if let Some(bv) = value.business_value {
+                        println!("Business value: {}", bv);
+                    }
+                    if let Some(effort) = value.effort {
+                        println!("Effort: {}", effort);
+                    }
+                    if let Some(score) = value.wsjf() {
+                        println!("WSJF: {:.2}", score);
+                    }
+                    let attribution = db.get_task_attribution(&t.id)?;
+                    let by = |who: &Option<String>| {
+                        who.as_ref()
+                            .map(|w| format!(" by {}", w.cyan()))
+                            .unwrap_or_default()
+                    };
+                    println!(
+                        "Created: {}{}",
+                        time.format_precise(t.created_at),
+                        by(&attribution.created_by)
+                    );
+                    println!(
+                        "Updated: {}{}",
+                        time.format_precise(t.updated_at),
+                        by(&attribution.updated_by)
+                    );
+                    if let Some(completed) = t.completed_at {
+                        println!("Completed: {}", time.format_precise(completed).green());
+                    }
+                    if let (TaskStatus::InProgress, Some(task_display_id)) =
+                        (&t.status, t.display_id)
+                    {
+                        let stall_window = chrono::Duration::minutes(stall_minutes);
+                        if let Some(eta) = db.get_task_eta(task_display_id, stall_window)? {
+                            println!("Progress: {}% (ETA: {})", eta.progress, format_eta(&eta));
+                        }
+                    }
+
+                    // Show subtasks
+                    let subtasks = db.get_subtasks(&t.id)?;
+                    if !subtasks.is_empty() {
+                        println!("\n{}", "Subtasks:".bold());
+                        for (i, st) in subtasks.iter().enumerate() {
+                            let st_id = st
+                                .display_id
+                                .map(|id| format!("#{}", id))
+                                .unwrap_or_else(|| st.id[..8].to_string());
+                            println!(
+                                "  {}. {} ({}) - {}",
+                                i + 1,
+                                st.title,
+                                st_id,
+                                format_status(&st.status)
+                            );
+                        }
+                    }
+
+                    let attachments = db.get_task_attachments(&t.id)?;
+                    if !attachments.is_empty() {
+                        println!("\n{}", "Attachments:".bold());
+                        for attachment in &attachments {
+                            let missing =
+                                !attachment.is_url() && !Path::new(&attachment.location).exists();
+                            println!(
+                                "  [{}] {}{}{}",
+                                attachment.id,
+                                attachment.location.cyan(),
+                                attachment
+                                    .label
+                                    .as_ref()
+                                    .map(|l| format!(" - {}", l))
+                                    .unwrap_or_default(),
+                                if missing {
+                                    format!(" {}", "(missing)".red())
+                                } else {
+                                    String::new()
+                                }
+                            );
+                        }
+                    }
+
+                    let errors = db.get_task_errors(&t.id)?;
+                    if !errors.is_empty() {
+                        println!("\n{}", "Agent errors:".bold());
+                        for error in &errors {
+                            println!(
+                                "  {} {} [{}] {}",
+                                time.format(error.created_at),
+                                format_agent_id(db.get_connection(), &error.agent_id).cyan(),
+                                error.kind.as_str().red(),
+                                error.message
+                            );
+                        }
+                    }
+
+                    let comments = db.get_task_comments(&t.id)?;
+                    if !comments.is_empty() {
+                        println!("\n{}", "Comments:".bold());
+                        print_comments(db, &comments)?;
+                    }
+
+                    // Show related content if requested
+                    if related {
+                        println!("\n{}", "Related:".bold());
+                        // Tasks are indexed under their display ID, else UUID
+                        let content_id = t
+                            .display_id
+                            .map(|id| format!("#{}", id))
+                            .unwrap_or_else(|| t.id.clone());
+                        match vectors::VectorSearch::related(
+                            db.get_connection(),
+                            &content_id,
+                            related_limit,
+                            related_threshold,
+                        ) {
+                            Err(e) => {
+                                println!("  {}", format!("Vector search failed: {}", e).dimmed())
+                            }
+                            Ok(None) => println!(
+                                "  {}",
+                                "Task not indexed yet. Run: prd vector index".dimmed()
+                            ),
+                            Ok(Some(found)) if found.is_empty() => {
+                                println!("  {}", "Nothing above the similarity threshold.".dimmed())
+                            }
+                            Ok(Some(found)) => {
+                                print_related("Similar tasks", &found.tasks);
+                                print_related("Code", &found.code);
+                                print_related("Docs", &found.docs);
+                            }
+                        }
+                    }
+
+                    // Show logs if requested
+                    if logs {
+                        let task_logs = db.get_task_logs(&t.id)?;
+                        if !task_logs.is_empty() {
+                            println!("\n{}", "Activity Log:".bold());
+                            for log in task_logs {
+                                let author = db
+                                    .log_author(&log)
+                                    .map(|a| format!(" ({})", a).dimmed().to_string())
+                                    .unwrap_or_default();
+                                println!(
+                                    "  {} - {} {}{}",
+                                    time.format_precise(log.created_at).dimmed(),
+                                    log.action.cyan(),
+                                    log.details.unwrap_or_default(),
+                                    author
+                                );
+                            }
+                        }
+                    }
+
+                    // Show progress if requested
+                    if progress {
+                        if let Some(task_display_id) = t.display_id {
+                            let progress_records = db.get_task_progress(task_display_id)?;
+                            if !progress_records.is_empty() {
+                                println!("\n{}", "Progress History:".bold());
+                                for prog in progress_records {
+                                    let agent_display = db
+                                        .get_agent(&prog.agent_id)
+                                        .ok()
+                                        .flatten()
+                                        .and_then(|a| a.display_id.map(|id| format!("A{}", id)))
+                                        .unwrap_or_else(|| prog.agent_id[..8].to_string());
+                                    let msg = prog
+                                        .message
+                                        .map(|m| format!(" - {}", m))
+                                        .unwrap_or_default();
+                                    println!(
+                                        "  {} - {} @ {}%{}",
+                                        time.format_precise(prog.timestamp).dimmed(),
+                                        agent_display.cyan(),
+                                        prog.progress.to_string().cyan(),
+                                        msg
+                                    );
+                                }
+                            }
+                        }
+                    }
+                }
+                None => {
+                    println!("{}", "Task not found.".red());
+                }
+            }
+        }
+
+        TaskCommands::Context { id, format, logs } => {
+            let task_uuid = resolve_task_id(db.get_connection(), &id)?;
+            let context = prd_tool::context::task_context(&db, &task_uuid, logs)?;
+            match format {
+                ContextFormat::Markdown => print!("{}", context.to_markdown()),
+                ContextFormat::Json => println!("{}", serde_json::to_string_pretty(&context)?),
+            }
+        }
+
+        TaskCommands::Blame { id, json } => {
+            use prd_tool::blame::{format_spent, task_blame};
+
+            let task_uuid = resolve_task_id(db.get_connection(), &id)?;
+            let display_id = db.get_task(&task_uuid)?.and_then(|t| t.display_id);
+            // Outside a git repository the summary comes from the database alone
+            let git = prd_tool::git::GitSync::new(&std::env::current_dir()?);
+            let commits = match (display_id, git) {
+                (Some(display_id), Ok(git)) => git.task_commits(display_id)?,
+                _ => Vec::new(),
+            };
+            let blame = task_blame(&db, &task_uuid, commits, Utc::now())?;
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&blame)?);
+            } else if blame.agents.is_empty() && blame.unattributed_commits.is_empty() {
+                println!("No agent has worked on {} yet", blame.task.label());
+            } else {
+                println!(
+                    "{} {}\n",
+                    blame.task.label().bold(),
+                    blame.task.title.bold()
+                );
+                let time = db.time_display()?;
+                let rows: Vec<BlameRow> = blame
+                    .agents
+                    .iter()
+                    .map(|a| BlameRow {
+                        agent: a.agent_name.clone(),
+                        time: format_spent(a.time_spent_secs),
+                        assignments: a.assignments,
+                        status_changes: a.status_changes,
+                        progress_reports: a.progress_reports,
+                        commits: a.commits.len(),
+                        last_touched: time.format(a.last_touched),
+                    })
+                    .collect();
+                if !rows.is_empty() {
+                    println!("{}", Table::new(rows).with(Style::modern()));
+                }
+
+                let commits: Vec<(&str, &prd_tool::git::TaskCommit)> = blame
+                    .agents
+                    .iter()
+                    .flat_map(|a| a.commits.iter().map(move |c| (a.agent_name.as_str(), c)))
+                    .chain(
+                        blame
+                            .unattributed_commits
+                            .iter()
+                            .map(|c| (c.author.as_str(), c)),
+                    )
+                    .collect();
+                if !commits.is_empty() {
+                    println!("\n{}", "Linked commits:".bold());
+                    for (who, commit) in commits {
+                        println!(
+                            "  {} {} {:<12} {}",
+                            commit.hash[..7].yellow(),
+                            commit.committed_at.format("%Y-%m-%d"),
+                            who,
+                            commit.summary
+                        );
+                    }
+                }
+            }
+        }
+
+        TaskCommands::Update {
+            id,
+            status,
+            agent,
+            reason,
+            due,
+            clear_due,
+            points,
+            start_after,
+            clear_start,
+            force,
+        } => {
+            let task_uuid = resolve_task_id(db.get_connection(), &id)?;
+            let display_id = format_task_id(db.get_connection(), &task_uuid);
+            if let Some(points) = points {
+                db.set_task_points(&task_uuid, Some(points))?;
+                println!(
+                    "{} Task {} estimated at {} points",
+                    "✓".green().bold(),
+                    display_id.cyan(),
+                    points
+                );
+            }
+            if due.is_some() || clear_due {
+                db.set_task_due_date(&task_uuid, due, agent.as_deref())?;
+                match due {
+                    Some(due) => println!(
+                        "{} Task {} due {}",
+                        "✓".green().bold(),
+                        display_id.cyan(),
+                        due
+                    ),
+                    None => println!(
+                        "{} Task {} due date cleared",
+                        "✓".green().bold(),
+                        display_id.cyan()
+                    ),
+                }
+            }
+            if start_after.is_some() || clear_start {
+                db.set_task_start_after(&task_uuid, start_after, agent.as_deref())?;
+                match start_after {
+                    Some(start) => println!(
+                        "{} Task {} deferred until {}",
+                        "✓".green().bold(),
+                        display_id.cyan(),
+                        db.time_display()?.format(start)
+                    ),
+                    None => println!(
+                        "{} Task {} can start now",
+                        "✓".green().bold(),
+                        display_id.cyan()
+                    ),
+                }
+            }
+            let Some(status) = status else {
+                return Ok(());
+            };
+            let status_enum = db.parse_status(&status)?;
+            if reason.is_some() && status_enum != TaskStatus::Blocked {
+                anyhow::bail!("--reason can only be given when blocking a task");
+            }
+            if held_for_approval(
+                &db,
+                &task_uuid,
+                db::ChangeField::Status,
+                status_enum.as_str(),
+                agent.as_deref(),
+            )? {
+                return Ok(());
+            }
+            db.write_transaction(|| {
+                if force {
+                    db.force_task_status(&task_uuid, status_enum.clone(), agent.as_deref())?;
+                } else {
+                    db.update_task_status(&task_uuid, status_enum.clone(), agent.as_deref())?;
+                }
+                if let Some(reason) = &reason {
+                    db.log_task_action(
+                        &task_uuid,
+                        agent.as_deref(),
+                        prd_tool::blocked::BLOCK_REASON_ACTION,
+                        Some(reason),
+                    )?;
+                }
+                Ok(())
+            })?;
+            println!(
+                "{} Task {} updated to {}",
+                "✓".green().bold(),
+                display_id.cyan(),
+                status_enum.as_str()
+            );
+            if db.workflow()?.is_completed(&status_enum) {
+                print_next_occurrence(&db, &task_uuid)?;
+            }
+            invocation.emit(EventKind::StatusChanged, &task_uuid, true);
+        }
+
+        TaskCommands::Breakdown { id, interactive } => {
+            let task_uuid = resolve_task_id(db.get_connection(), &id)?;
+            let task = db.get_task(&task_uuid)?;
+            match task {
+                Some(t) => {
+                    println!("Breaking down task: {}", t.title.bold());
+
+                    if interactive {
+                        use dialoguer::{Confirm, Input};
+
+                        loop {
+                            let subtask_title: String = Input::new()
+                                .with_prompt("Subtask title (or empty to finish)")
+                                .allow_empty(true)
+                                .interact_text()?;
+
+                            if subtask_title.is_empty() {
+                                break;
+                            }
+
+                            let description: String = Input::new()
+                                .with_prompt("Description (optional)")
+                                .allow_empty(true)
+                                .interact_text()?;
+
+                            let levels = db.priority_levels()?;
+                            let priority_str: String = Input::new()
+                                .with_prompt(format!("Priority ({})", levels.names().join("/")))
+                                .default(levels.default_level().as_str().to_string())
+                                .interact_text()?;
+
+                            let priority = levels.parse(&priority_str)?;
+                            let desc = if description.is_empty() {
+                                None
+                            } else {
+                                Some(description)
+                            };
+
+                            let subtask = db.create_task(
+                                subtask_title,
+                                desc,
+                                priority,
+                                Some(task_uuid.clone()),
+                                None,
+                            )?;
+                            let subtask_display = subtask
+                                .display_id
+                                .map(|id| format!("#{}", id))
+                                .unwrap_or_else(|| subtask.id[..8].to_string());
+                            println!(
+                                "{} Created subtask: {}",
+                                "✓".green(),
+                                subtask_display.cyan()
+                            );
+
+                            let continue_adding = Confirm::new()
+                                .with_prompt("Add another subtask?")
+                                .default(true)
+                                .interact()?;
+
+                            if !continue_adding {
+                                break;
+                            }
+                        }
+                    } else {
+                        let task_display = format_task_id(db.get_connection(), &task_uuid);
+                        println!("Use --interactive to add subtasks interactively");
+                        println!("Or use: prd create --parent {} <title>", task_display);
+                    }
+
+                    let subtasks = db.get_subtasks(&task_uuid)?;
+                    println!(
+                        "\n{} {} subtasks created",
+                        "✓".green().bold(),
+                        subtasks.len()
+                    );
+                }
+                None => {
+                    println!("{}", "Task not found.".red());
+                }
+            }
+        }
+
+        TaskCommands::Tag { action } => match action {
+            TagAction::Add { id, tags } => {
+                let task_id = resolve_task_id(db.get_connection(), &id)?;
+                for tag in &tags {
+                    if db.add_task_tag(&task_id, tag, None)? {
+                        println!("{} Tagged {} with {}", "✓".green().bold(), id, tag.cyan());
+                    } else {
+                        println!("{} {} already has tag {}", "ℹ".blue().bold(), id, tag);
+                    }
+                }
+            }
+            TagAction::Remove { id, tags } => {
+                let task_id = resolve_task_id(db.get_connection(), &id)?;
+                for tag in &tags {
+                    if db.remove_task_tag(&task_id, tag, None)? {
+                        println!(
+                            "{} Removed tag {} from {}",
+                            "✓".green().bold(),
+                            tag.cyan(),
+                            id
+                        );
+                    } else {
+                        println!("{} {} doesn't have tag {}", "ℹ".blue().bold(), id, tag);
+                    }
+                }
+            }
+            TagAction::List { id: Some(id), json } => {
+                let task_id = resolve_task_id(db.get_connection(), &id)?;
+                let tags = db.get_task_tags(&task_id)?;
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&tags)?);
+                } else if tags.is_empty() {
+                    println!("{}", "No tags.".yellow());
+                } else {
+                    let colors = colors::ColorScheme::load(&db)?;
+                    println!("{}", colors.paint_tags(&tags, |tag| tag.cyan()));
+                }
+            }
+            TagAction::List { id: None, json } => {
+                let tags = db.list_tags()?;
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&tags)?);
+                } else if tags.is_empty() {
+                    println!("{}", "No tags in use.".yellow());
+                } else {
+                    let colors = colors::ColorScheme::load(&db)?;
+                    for tag in &tags {
+                        let name = match colors.tag(&tag.tag) {
+                            Some(color) => color.paint(&tag.tag),
+                            None => tag.tag.cyan(),
+                        };
+                        println!("{:<24} {} task(s)", name, tag.tasks);
+                    }
+                }
+            }
+        },
+
+        TaskCommands::Diff { from, to, json } => {
+            use prd_tool::diff::{open_read_only, TaskDiff};
+            let store = prd_tool::snapshot::SnapshotStore::for_database(ctx.database);
+            let open = |target: &str| -> Result<(String, rusqlite::Connection)> {
+                let path = std::path::Path::new(target);
+                if path.is_file() {
+                    return Ok((target.to_string(), open_read_only(path)?));
+                }
+                let snapshot = store.find(target)?;
+                let label = format!("snapshot #{} \"{}\"", snapshot.id, snapshot.name);
+                Ok((label, open_read_only(&store.path(&snapshot))?))
+            };
+            let (from_label, before) = open(&from)?;
+            let (to_label, after) = match &to {
+                Some(to) => open(to)?,
+                None => ("current".to_string(), open_read_only(ctx.database)?),
+            };
+            let diff = TaskDiff::between(&before, &after)?;
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&diff)?);
+                return Ok(());
+            }
+            println!("{} → {}", from_label.dimmed(), to_label.dimmed());
+            if diff.is_empty() {
+                println!("{}", "No task changes".green());
+                return Ok(());
+            }
+
+            println!("{}", format_task_diff(&diff));
+        }
+
+        TaskCommands::Tree { id, epic, depth } => {
+            use prd_tool::visualization::TaskTreeRenderer;
+            let task_uuid = id
+                .map(|id| resolve_task_id(db.get_connection(), &id))
+                .transpose()?;
+            let renderer = TaskTreeRenderer::new(db).with_max_depth(depth);
+
+            let output = match task_uuid {
+                Some(task_uuid) => renderer.render_task(&task_uuid)?,
+                None => renderer.render_roots(epic.as_deref())?,
+            };
+            print!("{}", output);
+        }
+
+        TaskCommands::Value {
+            id,
+            business_value,
+            effort,
+            clear,
+        } => {
+            let task_uuid = resolve_task_id(db.get_connection(), &id)?;
+            if clear {
+                db.clear_task_value(&task_uuid)?;
+            } else {
+                db.set_task_value(&task_uuid, business_value, effort)?;
+            }
+            let value = db.get_task_value(&task_uuid)?;
+            let display_id = format_task_id(db.get_connection(), &task_uuid);
+            match value.wsjf() {
+                Some(score) => println!(
+                    "{} Task {} value {} / effort {} = WSJF {:.2}",
+                    "✓".green().bold(),
+                    display_id.cyan(),
+                    value.business_value.unwrap_or_default(),
+                    value.effort.unwrap_or_default(),
+                    score
+                ),
+                None => println!(
+                    "{} Task {} estimates updated{}",
+                    "✓".green().bold(),
+                    display_id.cyan(),
+                    if clear {
+                        ""
+                    } else {
+                        " (WSJF needs both value and effort)"
+                    }
+                ),
+            }
+        }
+
+        TaskCommands::Points { id, points, clear } => {
+            let task_uuid = resolve_task_id(db.get_connection(), &id)?;
+            let points = if clear { None } else { points };
+            db.set_task_points(&task_uuid, points)?;
+            let display_id = format_task_id(db.get_connection(), &task_uuid);
+            match points {
+                Some(points) => println!(
+                    "{} Task {} estimated at {} points",
+                    "✓".green().bold(),
+                    display_id.cyan(),
+                    points
+                ),
+                None => println!(
+                    "{} Task {} points cleared",
+                    "✓".green().bold(),
+                    display_id.cyan()
+                ),
+            }
+        }
+
+        TaskCommands::Repeat { id, rule, clear } => {
+            let task_uuid = resolve_task_id(db.get_connection(), &id)?;
+            let rule = if clear { None } else { rule };
+            db.set_task_recurrence(&task_uuid, rule.as_ref(), None)?;
+            let display_id = format_task_id(db.get_connection(), &task_uuid);
+            match rule {
+                Some(rule) => println!(
+                    "{} Task {} repeats {}",
+                    "✓".green().bold(),
+                    display_id.cyan(),
+                    rule
+                ),
+                None => println!(
+                    "{} Task {} no longer repeats",
+                    "✓".green().bold(),
+                    display_id.cyan()
+                ),
+            }
+        }
+
+        TaskCommands::Ac { task_id, action } => {
+            let task_uuid = resolve_task_id(db.get_connection(), &task_id)?;
+            let task = db
+                .get_task(&task_uuid)?
+                .ok_or_else(|| anyhow::anyhow!("Task not found"))?;
+            let task_display_id = task
+                .display_id
+                .ok_or_else(|| anyhow::anyhow!("Task missing display_id"))?;
+
+            match action {
+                AcAction::Add { criterion } => {
+                    let ac_id = db
+                        .get_connection()
+                        .add_criterion(task_display_id, criterion.clone())?;
+                    println!(
+                        "{} Added acceptance criterion #{}",
+                        "✓".green().bold(),
+                        ac_id
+                    );
+                }
+                AcAction::List => {
+                    let criteria = db.get_connection().list_criteria(task_display_id)?;
+
+                    if criteria.is_empty() {
+                        println!("{}", "No acceptance criteria defined.".yellow());
+                        return Ok(());
+                    }
+
+                    println!(
+                        "\nAcceptance Criteria for #{} - {}",
+                        task_display_id,
+                        task.title.bold()
+                    );
+                    for (i, ac) in criteria.iter().enumerate() {
+                        let checkbox = if ac.completed { "☑" } else { "☐" };
+                        println!("  {}. {} {}", i + 1, checkbox, ac.criterion);
+                    }
+
+                    let completed_count = criteria.iter().filter(|ac| ac.completed).count();
+                    println!("\n{}/{} criteria met", completed_count, criteria.len());
+                }
+                AcAction::Check { id } => {
+                    db.get_connection().check_criterion(id)?;
+                    println!(
+                        "{} Criterion #{} marked as completed",
+                        "✓".green().bold(),
+                        id
+                    );
+                }
+                AcAction::Uncheck { id } => {
+                    db.get_connection().uncheck_criterion(id)?;
+                    println!(
+                        "{} Criterion #{} marked as incomplete",
+                        "✓".green().bold(),
+                        id
+                    );
+                }
+            }
+        }
+
+        TaskCommands::Duration {
+            id,
+            estimated,
+            actual,
+        } => {
+            let task_uuid = resolve_task_id(db.get_connection(), &id)?;
+            db.update_task_duration(&task_uuid, estimated, actual)?;
+            let display_id = format_task_id(db.get_connection(), &task_uuid);
+            println!(
+                "{} Task {} duration updated!",
+                "✓".green().bold(),
+                display_id.cyan()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse a start date: a day (midnight UTC), an RFC 3339 timestamp, or a
+/// span such as "14d" counted forward from now
+fn parse_start_date(s: &str) -> Result<DateTime<Utc>, String> {
+    let s = s.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap().and_utc());
+    }
+    if let Ok(at) = DateTime::parse_from_rfc3339(s) {
+        return Ok(at.with_timezone(&Utc));
+    }
+    match parse_ttl(s) {
+        Ok(span) => Ok(Utc::now() + span),
+        Err(_) => Err(format!(
+            "invalid date '{}' (use YYYY-MM-DD, RFC 3339 or a span like 3d)",
+            s
+        )),
+    }
+}
+
+/// Print comments oldest first, each with its author and time
+fn print_comments(db: &Database, comments: &[db::TaskComment]) -> Result<()> {
+    let time = db.time_display()?;
+    for comment in comments {
+        let author = db
+            .comment_author(comment)
+            .unwrap_or_else(|| "unknown".to_string());
+        println!(
+            "  {} {}: {}",
+            time.format(comment.created_at).dimmed(),
+            author.cyan(),
+            comment.body
+        );
+    }
+    Ok(())
+}
+
+/// A `task_history` value for display, with agents and parents by ID
+fn format_history_value(
+    conn: &rusqlite::Connection,
+    field: &str,
+    value: &Option<String>,
+) -> String {
+    match value.as_deref() {
+        None => "(none)".to_string(),
+        Some(uuid) if field == "assigned_agent" => format_agent_id(conn, uuid),
+        Some(uuid) if field == "parent_id" => format_task_id(conn, uuid),
+        Some(value) => value.to_string(),
+    }
+}
+
+/// Print task changes as a diff per point in time
+fn print_task_history(db: &Database, changes: &[db::TaskChange]) -> Result<()> {
+    let time = db.time_display()?;
+    let show = |field: &str, value: &Option<String>| {
+        format_history_value(db.get_connection(), field, value)
+    };
+    let mut last = None;
+    for change in changes {
+        if last != Some(change.changed_at) {
+            println!("{}", time.format(change.changed_at).bold());
+            last = Some(change.changed_at);
+        }
+        let field = &change.field;
+        let old = format!("- {}: {}", field, show(field, &change.old_value));
+        let new = format!("+ {}: {}", field, show(field, &change.new_value));
+        println!("  {}", old.red());
+        println!("  {}", new.green());
+    }
+    Ok(())
+}
+
+/// Print one kind of related content under `prd show --related`
+fn print_related(heading: &str, results: &[vectors::SearchResult]) {
+    if results.is_empty() {
+        return;
+    }
+    println!("  {}", heading.underline());
+    for result in results {
+        let preview = result
+            .record
+            .content_preview
+            .as_deref()
+            .map(|p| p.chars().take(60).collect::<String>())
+            .unwrap_or_default();
+        println!(
+            "    {} {} {}",
+            result.record.content_id.cyan(),
+            format!("{}%", (result.similarity * 100.0) as u32).green(),
+            preview.dimmed()
+        );
+    }
+}
+
+fn format_eta(eta: &db::TaskEta) -> String {
+    fn humanize(duration: chrono::Duration) -> String {
+        let minutes = duration.num_minutes().max(0);
+        if minutes >= 60 {
+            format!("{}h {}m", minutes / 60, minutes % 60)
+        } else {
+            format!("{}m", minutes)
+        }
+    }
+
+    if eta.stalled {
+        let idle = Utc::now() - eta.last_change;
+        return format!("stalled {}", humanize(idle))
+            .red()
+            .bold()
+            .to_string();
+    }
+    match eta.eta {
+        Some(at) if at > Utc::now() => format!("~{}", humanize(at - Utc::now())),
+        Some(_) => "overdue".yellow().to_string(),
+        None => "unknown".dimmed().to_string(),
+    }
+}
+
+/// Priority in its configured color, or as [`format_priority`] shows it
+fn paint_priority(priority: &Priority, colors: &colors::ColorScheme) -> String {
+    let Some(color) = colors.priority(priority.as_str()) else {
+        return format_priority(priority);
+    };
+    let label = match priority {
+        Priority::Low => "Low",
+        Priority::Medium => "Medium",
+        Priority::High => "High",
+        Priority::Critical => "Critical",
+        Priority::Custom(name) => name.as_str(),
+    };
+    color.paint(label).bold().to_string()
+}
+
+/// Epic name in its configured color
+fn paint_epic(epic: &str, colors: &colors::ColorScheme) -> String {
+    colors
+        .epic(epic)
+        .map_or_else(|| epic.to_string(), |color| color.paint(epic).to_string())
+}
@@ -0,0 +1,590 @@
+//! Vector - `prd vector`, semantic indexing and search
+
+use anyhow::Result;
+use clap::Subcommand;
+use colored::*;
+use db::Database;
+use output::emoji;
+use prd_tool::{db, output, resolver, vectors};
+use resolver::resolve_task_id;
+use std::path::PathBuf;
+
+use super::Context;
+
+#[derive(Subcommand)]
+pub enum VectorCommands {
+    /// Index content for semantic search
+    Index {
+        /// What to index: tasks, code, docs, all
+        #[arg(default_value = "all")]
+        content: String,
+        /// Directory to index (for code/docs)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+        /// File patterns to include (e.g., "*.rs", "*.ts")
+        #[arg(short = 'i', long = "include")]
+        patterns: Vec<String>,
+        /// Force re-index everything
+        #[arg(long)]
+        force: bool,
+        /// Threads for reading, chunking and embedding files (default: all cores)
+        #[arg(short, long)]
+        jobs: Option<usize>,
+    },
+
+    /// Semantic search across indexed content
+    Search {
+        /// Search query
+        query: String,
+        /// Filter by type: tasks, code, docs
+        #[arg(short, long)]
+        r#type: Option<String>,
+        /// Number of results
+        #[arg(short, long, default_value = "10")]
+        limit: usize,
+        /// Minimum similarity threshold (0.0-1.0)
+        #[arg(long, default_value = "0.5")]
+        threshold: f32,
+        /// Only match tasks in this epic
+        #[arg(long)]
+        epic: Option<String>,
+        /// Only match code and docs under this path (e.g. backend/)
+        #[arg(long)]
+        path_prefix: Option<String>,
+    },
+
+    /// Find similar content to a task
+    Similar {
+        /// Task ID to find similar content for
+        task_id: String,
+        /// Include code matches
+        #[arg(long)]
+        code: bool,
+        /// Include doc matches
+        #[arg(long)]
+        docs: bool,
+        /// Number of results
+        #[arg(short, long, default_value = "5")]
+        limit: usize,
+    },
+
+    /// Show indexing statistics
+    Stats {
+        /// Also verify a sample of embeddings against their sources
+        #[arg(long)]
+        check: bool,
+        /// How many items --check verifies
+        #[arg(long, default_value = "20")]
+        sample: usize,
+    },
+
+    /// Clear all vector indexes
+    Clear {
+        /// Type to clear: tasks, code, docs, all
+        content: Option<String>,
+        /// Also empty the embedding cache, so re-indexing runs the model again
+        #[arg(long)]
+        cache: bool,
+    },
+
+    /// Move embeddings into a separate <name>-vectors.db next to the database
+    Split,
+
+    /// Move embeddings back into the task database
+    Join,
+}
+
+/// Runs a `prd vector` command against the open database
+pub fn run(action: VectorCommands, db: Database, ctx: &Context) -> Result<()> {
+    use vectors::{ContentIndexer, ContentType, Embedder, SearchScope, VectorSearch, VectorStore};
+
+    // Ensure vector schema exists (migration 008)
+    let conn = db.get_connection();
+    vectors::attach::ensure_schema(conn)?;
+
+    match action {
+        VectorCommands::Index {
+            content,
+            path,
+            patterns,
+            force,
+            jobs,
+        } => {
+            let mut embedder = Embedder::new();
+
+            println!(
+                "{} Loading embedding model (first run may download ~100MB)...",
+                emoji("⏳").yellow()
+            );
+
+            let conn = db.get_connection();
+            let mut indexer = ContentIndexer::new(&mut embedder, conn).with_progress(true);
+            if let Some(jobs) = jobs {
+                indexer = indexer.with_jobs(jobs);
+            }
+
+            let content_lower = content.to_lowercase();
+            let mut total_items = 0;
+            let mut total_chunks = 0;
+            let mut total_errors = 0;
+            let mut total_pruned = 0;
+            let mut total_cached = 0;
+
+            if content_lower == "all" || content_lower == "tasks" {
+                println!("{} Indexing tasks...", emoji("📋").cyan());
+                let stats = indexer.index_tasks(force)?;
+                println!(
+                    "  {} {} tasks indexed, {} skipped, {} chunks",
+                    "✓".green(),
+                    stats.items_indexed,
+                    stats.items_skipped,
+                    stats.chunks_created
+                );
+                total_items += stats.items_indexed;
+                total_chunks += stats.chunks_created;
+                total_errors += stats.errors;
+                total_pruned += stats.chunks_pruned;
+                total_cached += stats.cache_hits;
+            }
+
+            if content_lower == "all" || content_lower == "code" {
+                let code_path = path.clone().unwrap_or_else(|| PathBuf::from("."));
+                println!(
+                    "{} Indexing code in {}...",
+                    emoji("💻").cyan(),
+                    code_path.display()
+                );
+                let stats =
+                    indexer.index_directory(&code_path, ContentType::Code, &patterns, force)?;
+                println!(
+                    "  {} {} files indexed, {} skipped, {} chunks",
+                    "✓".green(),
+                    stats.items_indexed,
+                    stats.items_skipped,
+                    stats.chunks_created
+                );
+                total_items += stats.items_indexed;
+                total_chunks += stats.chunks_created;
+                total_errors += stats.errors;
+                total_pruned += stats.chunks_pruned;
+                total_cached += stats.cache_hits;
+            }
+
+            if content_lower == "all" || content_lower == "docs" {
+                let docs_path = path.unwrap_or_else(|| PathBuf::from("."));
+                println!(
+                    "{} Indexing docs in {}...",
+                    emoji("📄").cyan(),
+                    docs_path.display()
+                );
+                let stats =
+                    indexer.index_directory(&docs_path, ContentType::Doc, &patterns, force)?;
+                println!(
+                    "  {} {} files indexed, {} skipped, {} chunks",
+                    "✓".green(),
+                    stats.items_indexed,
+                    stats.items_skipped,
+                    stats.chunks_created
+                );
+                total_items += stats.items_indexed;
+                total_chunks += stats.chunks_created;
+                total_errors += stats.errors;
+                total_pruned += stats.chunks_pruned;
+                total_cached += stats.cache_hits;
+            }
+
+            println!("\n{}", "Indexing complete!".green().bold());
+            println!(
+                "Total: {} items, {} chunks{}{}{}",
+                total_items.to_string().cyan().bold(),
+                total_chunks.to_string().cyan().bold(),
+                if total_cached > 0 {
+                    format!(" ({} reused from the embedding cache)", total_cached)
+                } else {
+                    String::new()
+                },
+                if total_pruned > 0 {
+                    format!(", {} orphaned chunks removed", total_pruned)
+                } else {
+                    String::new()
+                },
+                if total_errors > 0 {
+                    format!(", {} errors", total_errors.to_string().red())
+                } else {
+                    String::new()
+                }
+            );
+        }
+
+        VectorCommands::Search {
+            query,
+            r#type,
+            limit,
+            threshold,
+            epic,
+            path_prefix,
+        } => {
+            let mut embedder = Embedder::new();
+            let conn = db.get_connection();
+
+            let content_type = r#type.as_ref().and_then(|t| ContentType::from_str(t));
+            let scope = SearchScope { epic, path_prefix };
+
+            let mut scope_desc = Vec::new();
+            if let Some(epic) = &scope.epic {
+                scope_desc.push(format!("epic \"{}\"", epic));
+            }
+            if let Some(prefix) = &scope.path_prefix {
+                scope_desc.push(format!("under {}", prefix));
+            }
+            println!(
+                "{} Searching for: \"{}\"{}",
+                emoji("🔍").cyan(),
+                query.bold(),
+                if scope_desc.is_empty() {
+                    String::new()
+                } else {
+                    format!(" ({})", scope_desc.join(" or "))
+                        .dimmed()
+                        .to_string()
+                }
+            );
+
+            let results = VectorSearch::search_text(
+                conn,
+                &mut embedder,
+                &query,
+                content_type,
+                &scope,
+                limit,
+                threshold,
+            )?;
+
+            if results.is_empty() {
+                println!("{}", "No results found.".yellow());
+                return Ok(());
+            }
+
+            println!("\n{} results:\n", results.len().to_string().cyan().bold());
+
+            for result in results {
+                let type_icon = match result.record.content_type {
+                    ContentType::Task => emoji("📋"),
+                    ContentType::Code => emoji("💻"),
+                    ContentType::Doc => emoji("📄"),
+                };
+
+                let similarity_pct = (result.similarity * 100.0) as u32;
+                let similarity_str = format!("{}%", similarity_pct);
+                let similarity_colored = if similarity_pct >= 80 {
+                    similarity_str.green().bold()
+                } else if similarity_pct >= 60 {
+                    similarity_str.yellow()
+                } else {
+                    similarity_str.dimmed()
+                };
+
+                println!(
+                    "{}. {} {} [{}] {}",
+                    result.rank,
+                    type_icon,
+                    result.record.content_id.cyan(),
+                    similarity_colored,
+                    result.record.content_type
+                );
+
+                if let Some(preview) = &result.record.content_preview {
+                    let preview_trimmed = if preview.len() > 80 {
+                        format!("{}...", &preview[..77])
+                    } else {
+                        preview.clone()
+                    };
+                    println!("   {}", preview_trimmed.dimmed());
+                }
+                println!();
+            }
+        }
+
+        VectorCommands::Similar {
+            task_id,
+            code,
+            docs,
+            limit,
+        } => {
+            let conn = db.get_connection();
+
+            // Resolve task ID
+            let task_uuid = resolve_task_id(conn, &task_id)?;
+            let task = db.get_task(&task_uuid)?;
+            let task = task.ok_or_else(|| anyhow::anyhow!("Task not found"))?;
+
+            let display_id = task
+                .display_id
+                .map(|id| format!("#{}", id))
+                .unwrap_or_else(|| task_id.clone());
+
+            println!(
+                "{} Finding content similar to task {} ({})",
+                emoji("🔍").cyan(),
+                display_id.cyan().bold(),
+                task.title
+            );
+
+            // Determine which types to search
+            let search_types = if code || docs {
+                let mut types = vec![];
+                if code {
+                    types.push(ContentType::Code);
+                }
+                if docs {
+                    types.push(ContentType::Doc);
+                }
+                // Always include similar tasks
+                types.push(ContentType::Task);
+                Some(types)
+            } else {
+                None // Search all
+            };
+
+            let results = VectorSearch::find_similar(
+                conn,
+                ContentType::Task,
+                &display_id,
+                search_types,
+                limit,
+                0.3, // Lower threshold for similar search
+            )?;
+
+            if results.is_empty() {
+                println!(
+                    "{}",
+                    "No similar content found. Try indexing first with: prd vector index".yellow()
+                );
+                return Ok(());
+            }
+
+            println!(
+                "\n{} similar items:\n",
+                results.len().to_string().cyan().bold()
+            );
+
+            for result in results {
+                let type_icon = match result.record.content_type {
+                    ContentType::Task => emoji("📋"),
+                    ContentType::Code => emoji("💻"),
+                    ContentType::Doc => emoji("📄"),
+                };
+
+                let similarity_pct = (result.similarity * 100.0) as u32;
+                let similarity_str = format!("{}%", similarity_pct);
+                let similarity_colored = if similarity_pct >= 70 {
+                    similarity_str.green().bold()
+                } else if similarity_pct >= 50 {
+                    similarity_str.yellow()
+                } else {
+                    similarity_str.dimmed()
+                };
+
+                println!(
+                    "{}. {} {} [{}]",
+                    result.rank,
+                    type_icon,
+                    result.record.content_id.cyan(),
+                    similarity_colored
+                );
+
+                if let Some(preview) = &result.record.content_preview {
+                    let preview_trimmed = if preview.len() > 80 {
+                        format!("{}...", &preview[..77])
+                    } else {
+                        preview.clone()
+                    };
+                    println!("   {}", preview_trimmed.dimmed());
+                }
+                println!();
+            }
+        }
+
+        VectorCommands::Stats { check, sample } => {
+            let conn = db.get_connection();
+            let stats = VectorStore::get_stats(conn)?;
+
+            println!("\n{}", "Vector Index Statistics".bold().underline());
+            println!(
+                "Model: {} ({} dimensions)",
+                vectors::EMBEDDING_MODEL,
+                vectors::EMBEDDING_DIM
+            );
+            if vectors::attach::is_separate(conn)? {
+                println!(
+                    "Stored in: {}",
+                    vectors::attach::sibling_path(ctx.database).display()
+                );
+            }
+            println!();
+
+            for health in vectors::health::index_health(conn)? {
+                let type_icon = match health.content_type {
+                    ContentType::Task => emoji("📋"),
+                    ContentType::Code => emoji("💻"),
+                    ContentType::Doc => emoji("📄"),
+                };
+
+                println!(
+                    "{} {}: {} items, {} chunks, {}",
+                    type_icon,
+                    health.content_type.to_string().cyan().bold(),
+                    health.items,
+                    health.chunks,
+                    format_bytes(health.bytes)
+                );
+                if health.stale_items > 0 {
+                    println!(
+                        "   {} {} items ({} chunks) changed since indexing; run `prd vector index`",
+                        "Stale:".yellow(),
+                        health.stale_items,
+                        health.stale_chunks
+                    );
+                }
+                if health.missing_items > 0 {
+                    println!(
+                        "   {} {} items whose task or file is gone; run `prd gc`",
+                        "Orphaned:".yellow(),
+                        health.missing_items
+                    );
+                }
+                if health
+                    .dimensions
+                    .iter()
+                    .any(|&d| d != vectors::EMBEDDING_DIM)
+                {
+                    let dims: Vec<String> =
+                        health.dimensions.iter().map(|d| d.to_string()).collect();
+                    println!(
+                            "   {} stored dimensions {} don't match the model; run `prd vector index --force`",
+                            "⚠".yellow(),
+                            dims.join(", ")
+                        );
+                }
+
+                if let Some(stat) = stats.iter().find(|s| s.content_type == health.content_type) {
+                    if let Some(last_indexed) = stat.last_indexed_at {
+                        println!(
+                            "   Last indexed: {}",
+                            db.time_display()?.format_precise(last_indexed).dimmed()
+                        );
+                    }
+                    if let Some(duration) = stat.index_duration_ms {
+                        println!("   Duration: {}ms", duration);
+                    }
+                }
+                println!();
+            }
+
+            let (cached, cache_bytes) = VectorStore::cache_size(conn)?;
+            println!(
+                "{}  {}: {} embeddings, {}\n",
+                emoji("🗄"),
+                "cache".cyan().bold(),
+                cached,
+                format_bytes(cache_bytes)
+            );
+
+            if check {
+                let report = vectors::health::check_sample(conn, sample)?;
+                println!(
+                    "{} Checked {} items ({} chunks)",
+                    if report.issues.is_empty() {
+                        "✓".green()
+                    } else {
+                        "⚠".yellow()
+                    },
+                    report.items_checked,
+                    report.chunks_checked
+                );
+                for issue in &report.issues {
+                    println!(
+                        "   {} {}: {}",
+                        issue.content_type,
+                        issue.content_id.cyan(),
+                        issue.problem
+                    );
+                }
+            }
+        }
+
+        VectorCommands::Clear { content, cache } => {
+            let conn = db.get_connection();
+
+            let content_type = content.as_ref().and_then(|c| ContentType::from_str(c));
+
+            match content_type {
+                Some(ct) => {
+                    let deleted = VectorStore::delete_all_by_type(conn, ct)?;
+                    println!(
+                        "{} Cleared {} embeddings from {} index",
+                        "✓".green(),
+                        deleted,
+                        ct
+                    );
+                }
+                None => {
+                    // Clear all
+                    let mut total = 0;
+                    for ct in [ContentType::Task, ContentType::Code, ContentType::Doc] {
+                        total += VectorStore::delete_all_by_type(conn, ct)?;
+                    }
+                    println!(
+                        "{} Cleared {} embeddings from all indexes",
+                        "✓".green(),
+                        total
+                    );
+                }
+            }
+            if cache {
+                let cleared = VectorStore::clear_cache(conn)?;
+                println!(
+                    "{} Cleared {} embeddings from the cache",
+                    "✓".green(),
+                    cleared
+                );
+            }
+        }
+
+        VectorCommands::Split => {
+            let path = vectors::attach::split(conn, ctx.database.to_str().unwrap())?;
+            println!(
+                "{} Moved embeddings to {} (attached automatically)",
+                "✓".green(),
+                path.display().to_string().cyan()
+            );
+        }
+
+        VectorCommands::Join => {
+            let path = vectors::attach::join(conn)?;
+            println!(
+                "{} Moved embeddings back into the task database and removed {}",
+                "✓".green(),
+                path.display()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Human-readable size, e.g. "1.5 MB"
+fn format_bytes(bytes: i64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
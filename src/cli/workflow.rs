@@ -0,0 +1,1282 @@
+//! Workflow - moving tasks through their lifecycle: completing, cancelling,
+//! approving, dependencies and the ready queue
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use clap::Subcommand;
+use colored::*;
+use db::{AgentStatus, Database, TaskStatus};
+use db_extensions::{DependencyOps, EpicDependencyOps};
+use output::emoji;
+use prd_tool::rules::EventKind;
+use prd_tool::{archive, db, db_extensions, output, resolver};
+use resolver::{
+    format_agent_id, format_task_id, resolve_agent_id, resolve_deleted_task_id, resolve_task_id,
+};
+use std::path::PathBuf;
+use tabled::{settings::Style, Table, Tabled};
+
+use super::{
+    format_priority, held_for_approval, is_approver, parse_date_bound, parse_ttl,
+    print_next_occurrence, print_wip_warnings, Context,
+};
+use crate::batch;
+
+/// Commands that move tasks through their lifecycle
+#[derive(Subcommand)]
+pub enum WorkflowCommands {
+    /// Manage task dependencies
+    Depends {
+        /// Task ID
+        task_id: String,
+        /// Task this depends on
+        #[arg(long)]
+        on: Option<String>,
+        /// Task this blocks
+        #[arg(long)]
+        blocks: Option<String>,
+        /// List dependencies
+        #[arg(long)]
+        list: bool,
+    },
+
+    /// Hold back an epic until another epic reaches a completion threshold
+    EpicDepends {
+        /// Epic to gate (omit with --list to show all)
+        #[arg(required_unless_present = "list")]
+        epic: Option<String>,
+        /// Epic that must progress first
+        #[arg(long, required_unless_present_any = ["list"])]
+        on: Option<String>,
+        /// Percent of the prerequisite epic that must be completed
+        #[arg(long, default_value_t = 100, value_parser = clap::value_parser!(u8).range(1..=100))]
+        threshold: u8,
+        /// Remove the dependency instead
+        #[arg(long, requires = "on")]
+        remove: bool,
+        /// List epic dependencies and whether they're met
+        #[arg(long, conflicts_with_all = ["on", "remove"])]
+        list: bool,
+    },
+
+    /// Complete a task (shortcut for update completed + agent sync)
+    Complete {
+        /// Task ID
+        task_id: String,
+        /// Agent completing the task (optional, uses assigned agent)
+        #[arg(short, long)]
+        agent: Option<String>,
+        /// Complete even if the allowed transitions don't permit it
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Cancel a task (shortcut for update cancelled)
+    Cancel {
+        /// Task ID
+        task_id: String,
+        /// Reason for cancellation (optional)
+        #[arg(short, long)]
+        reason: Option<String>,
+        /// Cancel even if the allowed transitions don't permit it
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Requeue a failed task as its next attempt
+    Retry {
+        /// Task ID
+        task_id: String,
+        /// Hand the retry to another agent (default: the agent that failed)
+        #[arg(short, long)]
+        agent: Option<String>,
+        /// Why it is being retried
+        #[arg(short, long)]
+        reason: Option<String>,
+    },
+
+    /// Move a task to the trash (`prd trash restore` brings it back)
+    Delete {
+        /// Task ID
+        task_id: String,
+        /// Agent deleting the task
+        #[arg(short, long)]
+        agent: Option<String>,
+    },
+
+    /// Tasks deleted with `prd delete`
+    Trash {
+        #[command(subcommand)]
+        action: TrashAction,
+    },
+
+    /// Move completed and cancelled tasks into the archive database
+    Archive {
+        /// Archive tasks finished before this (YYYY-MM-DD, RFC 3339, or an age such as 90d)
+        #[arg(long, value_parser = parse_date_bound)]
+        before: DateTime<Utc>,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Approve or reject held changes to guarded tasks (approval.* settings)
+    Approve {
+        /// Change request number; lists pending requests when omitted
+        id: Option<i64>,
+        /// Reject the change instead of applying it
+        #[arg(long)]
+        reject: bool,
+        /// List decided requests too
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// Reserve a task for an agent for a limited time
+    Lease {
+        /// Task ID (e.g., "#42")
+        task_id: String,
+        /// Agent ID or name holding the lease
+        #[arg(short, long, required_unless_present = "release")]
+        agent: Option<String>,
+        /// Lease duration (e.g., 30m, 2h, 1d)
+        #[arg(long, default_value = "1h", value_parser = parse_ttl)]
+        ttl: chrono::Duration,
+        /// Release the lease (only the holder may, when --agent is given)
+        #[arg(long)]
+        release: bool,
+        /// Go over the agent's WIP limit (with a warning) instead of refusing
+        #[arg(long, conflicts_with = "release")]
+        force: bool,
+        /// Print only the task ID
+        #[arg(short, long)]
+        quiet: bool,
+    },
+
+    /// Get the next task to work on (smart selection)
+    Next {
+        /// Filter by priority
+        #[arg(short, long)]
+        priority: Option<String>,
+        /// Filter by epic
+        #[arg(short, long)]
+        epic: Option<String>,
+        /// Auto-assign to agent
+        #[arg(short, long)]
+        agent: Option<String>,
+        /// Auto-sync agent to task
+        #[arg(long)]
+        sync: bool,
+        /// Print only the task ID, or nothing when no task is ready
+        #[arg(short, long)]
+        quiet: bool,
+        /// Go over the agent's capacity or WIP limits (with a warning) instead of refusing
+        #[arg(long, requires = "agent")]
+        force: bool,
+        /// Only tasks with this tag (repeatable; tasks must have all of them)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+    },
+
+    /// Show the ready queue with WSJF scores, or choose how it is ordered
+    Prioritize {
+        /// Order the ready queue by WSJF score (business value / effort)
+        #[arg(long, conflicts_with = "by_priority")]
+        wsjf: bool,
+        /// Order the ready queue by priority again
+        #[arg(long)]
+        by_priority: bool,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Update multiple tasks at once
+    BatchUpdate {
+        /// Comma-separated task IDs (e.g., "#1,#2,#3")
+        task_ids: String,
+        /// New status
+        status: String,
+        #[arg(short, long)]
+        agent: Option<String>,
+        /// Update every task or none (roll back if any fails)
+        #[arg(long)]
+        atomic: bool,
+        /// Print a per-task report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Assign multiple tasks to an agent
+    BatchAssign {
+        /// Comma-separated task IDs (e.g., "#1,#2,#3")
+        task_ids: String,
+        /// Agent ID or name
+        agent: String,
+        /// Assign every task or none (roll back if any fails)
+        #[arg(long)]
+        atomic: bool,
+        /// Go over the agent's WIP limit (with a warning) instead of refusing
+        #[arg(long)]
+        force: bool,
+        /// Print a per-task report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// List tasks ready to work on (all dependencies completed)
+    Ready,
+
+    /// Recompute derived data (agent metrics, completed_at, vector stats)
+    /// from the source tables and repair what disagrees
+    ///
+    /// Useful after manual SQL edits or a crash. Parents whose status
+    /// contradicts their subtasks are reported but left alone.
+    Verify {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Complete multiple tasks at once (batch operation)
+    CompleteBatch {
+        /// Comma-separated task IDs (e.g., "33,34,35")
+        #[arg(long, conflicts_with_all = ["from_file", "from_csv", "from_yaml"])]
+        tasks: Option<String>,
+
+        /// Agent mapping (e.g., "33:A11,34:A11,35:A12")
+        #[arg(long, requires = "tasks")]
+        agent_map: Option<String>,
+
+        /// JSON file path
+        #[arg(long, conflicts_with_all = ["from_csv", "from_yaml"])]
+        from_file: Option<PathBuf>,
+
+        /// CSV file path
+        #[arg(long, conflicts_with_all = ["from_file", "from_yaml"])]
+        from_csv: Option<PathBuf>,
+
+        /// YAML file path
+        #[arg(long, conflicts_with_all = ["from_file", "from_csv"])]
+        from_yaml: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TrashAction {
+    /// List deleted tasks, most recent first
+    List {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Take a task out of the trash
+    Restore {
+        /// Task ID
+        task_id: String,
+    },
+    /// Permanently remove deleted tasks and their history
+    Purge {
+        /// Task ID; every task in the trash when omitted
+        #[arg(conflicts_with = "older_than")]
+        task_id: Option<String>,
+        /// Only tasks deleted longer ago than this (e.g. 30d)
+        #[arg(long, value_parser = parse_ttl)]
+        older_than: Option<chrono::Duration>,
+    },
+}
+
+#[derive(Tabled)]
+struct QueueRow {
+    #[tabled(rename = "#")]
+    position: usize,
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "Title")]
+    title: String,
+    #[tabled(rename = "Priority")]
+    priority: String,
+    #[tabled(rename = "Value")]
+    value: String,
+    #[tabled(rename = "Effort")]
+    effort: String,
+    #[tabled(rename = "WSJF")]
+    wsjf: String,
+}
+
+#[derive(Tabled)]
+struct VerifyRow {
+    #[tabled(rename = "Data")]
+    data: String,
+    #[tabled(rename = "Subject")]
+    subject: String,
+    #[tabled(rename = "Found")]
+    found: String,
+    #[tabled(rename = "Expected")]
+    expected: String,
+    #[tabled(rename = "Action")]
+    action: String,
+}
+
+/// Runs a workflow command against the open database
+pub fn run(command: WorkflowCommands, db: Database, ctx: &Context) -> Result<()> {
+    let dry_run = ctx.dry_run;
+    let invocation = ctx.invocation;
+
+    match command {
+        WorkflowCommands::Complete {
+            task_id,
+            agent,
+            force,
+        } => {
+            let task_uuid = resolve_task_id(db.get_connection(), &task_id)?;
+            let task = db
+                .get_task(&task_uuid)?
+                .ok_or_else(|| anyhow::anyhow!("Task not found"))?;
+
+            let agent_id = if let Some(agent_name) = agent {
+                resolve_agent_id(db.get_connection(), &agent_name)?
+            } else if let Some(assigned) = task.assigned_agent {
+                assigned
+            } else {
+                return Err(anyhow::anyhow!("No agent specified and task not assigned"));
+            };
+
+            if force {
+                db.force_task_status(&task_uuid, TaskStatus::Completed, Some(&agent_id))?;
+            } else {
+                db.update_task_status(&task_uuid, TaskStatus::Completed, Some(&agent_id))?;
+            }
+            db.update_agent_status(&agent_id, AgentStatus::Idle, None)?;
+
+            let task_display = format_task_id(db.get_connection(), &task_uuid);
+            let agent_display = format_agent_id(db.get_connection(), &agent_id);
+            println!(
+                "{} Task {} completed by agent {}",
+                "✓".green().bold(),
+                task_display.cyan(),
+                agent_display.cyan()
+            );
+            print_next_occurrence(&db, &task_uuid)?;
+            invocation.emit(EventKind::StatusChanged, &task_uuid, true);
+        }
+
+        WorkflowCommands::Cancel {
+            task_id,
+            reason,
+            force,
+        } => {
+            let task_uuid = resolve_task_id(db.get_connection(), &task_id)?;
+            let task = db
+                .get_task(&task_uuid)?
+                .ok_or_else(|| anyhow::anyhow!("Task not found"))?;
+            if held_for_approval(
+                &db,
+                &task_uuid,
+                db::ChangeField::Status,
+                TaskStatus::Cancelled.as_str(),
+                None,
+            )? {
+                return Ok(());
+            }
+
+            // Update task status to cancelled
+            if force {
+                db.force_task_status(&task_uuid, TaskStatus::Cancelled, None)?;
+            } else {
+                db.update_task_status(&task_uuid, TaskStatus::Cancelled, None)?;
+            }
+
+            // If task had an assigned agent, set them to idle
+            if let Some(agent_id) = &task.assigned_agent {
+                db.update_agent_status(agent_id, AgentStatus::Idle, None)?;
+            }
+
+            let task_display = format_task_id(db.get_connection(), &task_uuid);
+            println!(
+                "{} Task {} cancelled",
+                "✕".yellow().bold(),
+                task_display.cyan()
+            );
+
+            if let Some(reason_text) = reason {
+                println!("Reason: {}", reason_text.dimmed());
+            }
+            invocation.emit(EventKind::StatusChanged, &task_uuid, true);
+        }
+
+        WorkflowCommands::Retry {
+            task_id,
+            agent,
+            reason,
+        } => {
+            let task_uuid = resolve_task_id(db.get_connection(), &task_id)?;
+            let agent_uuid = agent
+                .as_deref()
+                .map(|a| resolve_agent_id(db.get_connection(), a))
+                .transpose()?;
+            let attempt = db.retry_task(&task_uuid, agent_uuid.as_deref(), reason.as_deref())?;
+            let task = db
+                .get_task(&task_uuid)?
+                .ok_or_else(|| anyhow::anyhow!("Task not found"))?;
+
+            let task_display = format_task_id(db.get_connection(), &task_uuid);
+            match &task.assigned_agent {
+                Some(agent_id) => println!(
+                    "{} Task {} queued for attempt {} by agent {}",
+                    "✓".green().bold(),
+                    task_display.cyan(),
+                    attempt,
+                    format_agent_id(db.get_connection(), agent_id).cyan()
+                ),
+                None => println!(
+                    "{} Task {} queued for attempt {}",
+                    "✓".green().bold(),
+                    task_display.cyan(),
+                    attempt
+                ),
+            }
+            invocation.emit(EventKind::StatusChanged, &task_uuid, true);
+        }
+
+        WorkflowCommands::Delete { task_id, agent } => {
+            let task_uuid = resolve_task_id(db.get_connection(), &task_id)?;
+            let agent_uuid = agent
+                .as_deref()
+                .map(|a| resolve_agent_id(db.get_connection(), a))
+                .transpose()?;
+            db.delete_task(&task_uuid, agent_uuid.as_deref())?;
+            let task_display = format_task_id(db.get_connection(), &task_uuid);
+            println!(
+                "{} Task {} moved to the trash (`prd trash restore {}` to undo)",
+                "✓".green().bold(),
+                task_display.cyan(),
+                task_display
+            );
+        }
+
+        WorkflowCommands::Trash { action } => match action {
+            TrashAction::List { json } => {
+                let trash = db.list_deleted_tasks()?;
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&trash)?);
+                    return Ok(());
+                }
+                if trash.is_empty() {
+                    println!("{}", "Trash is empty.".yellow());
+                    return Ok(());
+                }
+                let time = db.time_display()?;
+                for deleted in trash {
+                    println!(
+                        "{:>6}  {:<40} {:<12} deleted {}",
+                        format_task_id(db.get_connection(), &deleted.task.id),
+                        deleted.task.title.cyan(),
+                        deleted.task.status.as_str(),
+                        time.format(deleted.deleted_at).dimmed()
+                    );
+                }
+            }
+            TrashAction::Restore { task_id } => {
+                let task_uuid = resolve_deleted_task_id(db.get_connection(), &task_id)?;
+                db.restore_task(&task_uuid, None)?;
+                println!(
+                    "{} Task {} restored",
+                    "✓".green().bold(),
+                    format_task_id(db.get_connection(), &task_uuid).cyan()
+                );
+            }
+            TrashAction::Purge {
+                task_id,
+                older_than,
+            } => {
+                if let Some(task_id) = task_id {
+                    let task_uuid = resolve_deleted_task_id(db.get_connection(), &task_id)?;
+                    let task_display = format_task_id(db.get_connection(), &task_uuid);
+                    db.purge_task(&task_uuid)?;
+                    println!(
+                        "{} Task {} permanently removed",
+                        "✓".green().bold(),
+                        task_display.cyan()
+                    );
+                } else {
+                    let purged = db.empty_trash(older_than.map(|age| Utc::now() - age))?;
+                    println!(
+                        "{} Permanently removed {} task(s) from the trash",
+                        "✓".green().bold(),
+                        purged
+                    );
+                }
+            }
+        },
+
+        WorkflowCommands::Archive { before, json } => {
+            let report =
+                archive::archive_tasks(&db, ctx.database.to_str().unwrap(), before, dry_run)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+                return Ok(());
+            }
+            if report.tasks.is_empty() {
+                println!("{}", "No tasks to archive.".yellow());
+            } else {
+                let ids: Vec<String> = report.tasks.iter().map(|id| format!("#{}", id)).collect();
+                println!(
+                    "{} {} {} task(s) to {}: {}",
+                    "✓".green().bold(),
+                    if dry_run { "Would archive" } else { "Archived" },
+                    report.tasks.len(),
+                    report.path.display(),
+                    ids.join(", ")
+                );
+            }
+            if report.kept > 0 {
+                println!(
+                    "{} Kept {} finished task(s) that unfinished tasks still depend on",
+                    "ℹ".blue().bold(),
+                    report.kept
+                );
+            }
+        }
+
+        WorkflowCommands::Approve { id, reject, all } => {
+            let Some(id) = id else {
+                let state = (!all).then_some(db::ChangeRequestState::Pending);
+                let requests = db.list_change_requests(state)?;
+                if requests.is_empty() {
+                    println!("No change requests awaiting approval");
+                }
+                for request in requests {
+                    println!(
+                        "#{}\t{}\t{} -> {}\t{}\t{}",
+                        request.id,
+                        format_task_id(db.get_connection(), &request.task_id),
+                        request.field.as_str(),
+                        request.value,
+                        request.requested_by.as_deref().unwrap_or("-"),
+                        request.state.as_str()
+                    );
+                }
+                return Ok(());
+            };
+
+            let role = db.approval_policy()?.role;
+            let Some(actor) = db.actor().map(str::to_string) else {
+                anyhow::bail!(
+                    "Set {} (or actor in ~/.prd/config.toml) to decide change requests",
+                    db::ACTOR_ENV
+                );
+            };
+            if !is_approver(&db)? {
+                anyhow::bail!(
+                    "Only a {} may decide change requests (roles are in ~/.prd/permissions.toml)",
+                    role
+                );
+            }
+
+            let request = db.decide_change_request(id, !reject, &actor)?;
+            let task_display = format_task_id(db.get_connection(), &request.task_id);
+            if reject {
+                println!(
+                    "{} Rejected change request #{}: {} stays unchanged",
+                    "✕".yellow().bold(),
+                    id,
+                    task_display.cyan()
+                );
+            } else {
+                println!(
+                    "{} Approved change request #{}: {} {} set to {}",
+                    "✓".green().bold(),
+                    id,
+                    task_display.cyan(),
+                    request.field.as_str(),
+                    request.value
+                );
+                if request.field == db::ChangeField::Status {
+                    invocation.emit(EventKind::StatusChanged, &request.task_id, true);
+                }
+            }
+        }
+
+        WorkflowCommands::Lease {
+            task_id,
+            agent,
+            ttl,
+            release,
+            force,
+            quiet,
+        } => {
+            let task_uuid = resolve_task_id(db.get_connection(), &task_id)?;
+            let task_display = format_task_id(db.get_connection(), &task_uuid);
+            let agent_uuid = agent
+                .as_deref()
+                .map(|a| resolve_agent_id(db.get_connection(), a))
+                .transpose()?;
+
+            if release {
+                if let Some(agent_uuid) = &agent_uuid {
+                    db.check_lease(&task_uuid, agent_uuid)?;
+                }
+                if quiet {
+                    db.release_lease(&task_uuid)?;
+                } else if db.release_lease(&task_uuid)? {
+                    println!(
+                        "{} Lease on {} released",
+                        "✓".green().bold(),
+                        task_display.cyan()
+                    );
+                } else {
+                    println!("No lease on {}", task_display.cyan());
+                }
+                return Ok(());
+            }
+
+            let agent_uuid = agent_uuid.expect("clap requires --agent without --release");
+            db.check_lease(&task_uuid, &agent_uuid)?;
+            let exceeded = db.enforce_wip(&task_uuid, Some(&agent_uuid), None, force)?;
+            let lease = db.acquire_lease(&task_uuid, &agent_uuid, ttl)?;
+            if quiet {
+                println!("{}", task_display);
+                return Ok(());
+            }
+            print_wip_warnings(&exceeded);
+            println!(
+                "{} {} leased to {} until {}",
+                "✓".green().bold(),
+                task_display.cyan(),
+                format_agent_id(db.get_connection(), &agent_uuid).cyan(),
+                db.time_display()?.format(lease.expires_at)
+            );
+        }
+
+        WorkflowCommands::Next {
+            priority,
+            epic,
+            agent,
+            sync,
+            quiet,
+            force,
+            tags,
+        } => {
+            let ready_ids = db.get_connection().get_ready_tasks()?;
+
+            if ready_ids.is_empty() {
+                if quiet {
+                    return Ok(());
+                }
+                println!(
+                    "{}",
+                    "No tasks ready (all have pending dependencies).".yellow()
+                );
+                return Ok(());
+            }
+
+            // Get full task details and filter
+            let mut ready_tasks = Vec::new();
+            for task_id in &ready_ids {
+                let task_result: Result<_, rusqlite::Error> = db.get_connection().query_row(
+                    "SELECT id FROM tasks WHERE display_id = ?1",
+                    [task_id],
+                    |row| row.get::<_, String>(0),
+                );
+                if let Ok(uuid) = task_result {
+                    if let Ok(Some(task)) = db.get_task(&uuid) {
+                        ready_tasks.push(task);
+                    }
+                }
+            }
+
+            // Apply filters
+            if let Some(prio_str) = priority {
+                let prio_filter = db.parse_priority(&prio_str)?;
+                ready_tasks.retain(|t| t.priority == prio_filter);
+            }
+            if let Some(epic_name) = epic {
+                ready_tasks.retain(|t| t.epic_name.as_ref().map_or(false, |e| e == &epic_name));
+            }
+            if !tags.is_empty() {
+                let tagged = db.task_ids_with_tags(&tags)?;
+                ready_tasks.retain(|t| tagged.contains(&t.id));
+            }
+
+            // Skip tasks leased to other agents
+            let agent_uuid = agent
+                .as_deref()
+                .map(|a| resolve_agent_id(db.get_connection(), a))
+                .transpose()?;
+            let mut unleased = Vec::with_capacity(ready_tasks.len());
+            for task in ready_tasks {
+                match db.get_active_lease(&task.id)? {
+                    Some(lease) if Some(&lease.agent_id) != agent_uuid.as_ref() => {}
+                    _ => unleased.push(task),
+                }
+            }
+            let mut ready_tasks = unleased;
+
+            if ready_tasks.is_empty() {
+                if !quiet {
+                    println!("{}", "No matching tasks ready.".yellow());
+                }
+                return Ok(());
+            }
+
+            db.sort_ready_queue(&mut ready_tasks)?;
+
+            let next_task = &ready_tasks[0];
+            let task_display = next_task
+                .display_id
+                .map(|id| format!("#{}", id))
+                .unwrap_or_else(|| next_task.id[..8].to_string());
+
+            // Honor the agent's availability windows and capacity
+            let mut exceeded = Vec::new();
+            if let Some(agent_uuid) = &agent_uuid {
+                db.check_available(agent_uuid, chrono::Local::now().time())?;
+                let status = sync.then_some(&TaskStatus::InProgress);
+                exceeded = db.enforce_wip(&next_task.id, Some(agent_uuid), status, force)?;
+            }
+
+            if quiet {
+                println!("{}", task_display);
+            } else {
+                println!("\n{}", "Next task:".bold().underline());
+                println!(
+                    "{} - {} [{}]",
+                    task_display.cyan(),
+                    next_task.title,
+                    format_priority(&next_task.priority)
+                );
+                if let Some(desc) = &next_task.description {
+                    println!("Description: {}", desc.dimmed());
+                }
+                let value = db.get_task_value(&next_task.id)?;
+                if let Some(score) = value.wsjf() {
+                    println!(
+                        "WSJF: {} (value {} / effort {})",
+                        format!("{:.2}", score).cyan(),
+                        value.business_value.unwrap_or_default(),
+                        value.effort.unwrap_or_default()
+                    );
+                }
+            }
+
+            // Auto-assign and sync if requested
+            if let Some(agent_uuid) = agent_uuid {
+                if !quiet {
+                    print_wip_warnings(&exceeded);
+                }
+                let agent_obj = db
+                    .get_agent(&agent_uuid)?
+                    .ok_or_else(|| anyhow::anyhow!("Agent not found"))?;
+
+                if sync {
+                    // Update agent to working status
+                    db.update_agent_status(
+                        &agent_obj.id,
+                        AgentStatus::Working,
+                        Some(&next_task.id),
+                    )?;
+                    // Update task to in_progress
+                    db.update_task_status(
+                        &next_task.id,
+                        TaskStatus::InProgress,
+                        Some(&agent_obj.id),
+                    )?;
+                    // Assign task if not already assigned
+                    db.assign_task(&next_task.id, &agent_obj.id)?;
+
+                    if !quiet {
+                        let agent_display = format_agent_id(db.get_connection(), &agent_obj.id);
+                        println!("\n{}", "✓ Task assigned and synced!".green().bold());
+                        println!(
+                            "Agent {} ({}) is now working on {}",
+                            agent_display.cyan(),
+                            agent_obj.name,
+                            task_display.cyan()
+                        );
+                    }
+                } else {
+                    db.assign_task(&next_task.id, &agent_obj.id)?;
+                    if !quiet {
+                        let agent_display = format_agent_id(db.get_connection(), &agent_obj.id);
+                        println!(
+                            "\n{} Task assigned to {}",
+                            "✓".green().bold(),
+                            agent_display.cyan()
+                        );
+                    }
+                }
+            }
+        }
+
+        WorkflowCommands::BatchUpdate {
+            task_ids,
+            status,
+            agent,
+            atomic,
+            json,
+        } => {
+            let status_enum = db.parse_status(&status)?;
+
+            let agent_uuid = if let Some(agent_name) = agent {
+                Some(resolve_agent_id(db.get_connection(), &agent_name)?)
+            } else {
+                None
+            };
+
+            let approver = is_approver(&db)?;
+            let report = batch::apply_to_tasks(&db, &task_ids, atomic, |task_uuid| {
+                db.guard_change(
+                    task_uuid,
+                    db::ChangeField::Status,
+                    status_enum.as_str(),
+                    agent_uuid.as_deref(),
+                    approver,
+                )?;
+                db.update_task_status(task_uuid, status_enum.clone(), agent_uuid.as_deref())
+            })?;
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                if !report.rolled_back() {
+                    println!(
+                        "{} Updated {} task(s) to {}",
+                        "✓".green().bold(),
+                        report.updated.to_string().cyan(),
+                        status_enum.as_str()
+                    );
+                }
+                if report.pending_approval > 0 && !report.rolled_back() {
+                    println!(
+                        "{} {} guarded task(s) await approval (prd approve)",
+                        emoji("⏳"),
+                        report.pending_approval.to_string().cyan()
+                    );
+                }
+                print_batch_failures(&report, "update");
+            }
+            if report.rolled_back() {
+                // An error rather than exiting here, so the middleware still
+                // sees the command finish
+                anyhow::bail!(
+                    "{} task(s) failed; rolled back, no tasks were changed",
+                    report.failed
+                );
+            }
+        }
+
+        WorkflowCommands::BatchAssign {
+            task_ids,
+            agent,
+            atomic,
+            force,
+            json,
+        } => {
+            let agent_uuid = resolve_agent_id(db.get_connection(), &agent)?;
+            let agent_obj = db
+                .get_agent(&agent_uuid)?
+                .ok_or_else(|| anyhow::anyhow!("Agent not found"))?;
+
+            let exceeded = std::cell::RefCell::new(Vec::new());
+            let report = batch::apply_to_tasks(&db, &task_ids, atomic, |task_uuid| {
+                exceeded.borrow_mut().extend(db.enforce_wip(
+                    task_uuid,
+                    Some(&agent_obj.id),
+                    None,
+                    force,
+                )?);
+                db.assign_task(task_uuid, &agent_obj.id)
+            })?;
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                if !report.rolled_back() {
+                    let agent_display = format_agent_id(db.get_connection(), &agent_obj.id);
+                    println!(
+                        "{} Assigned {} task(s) to {} ({})",
+                        "✓".green().bold(),
+                        report.updated.to_string().cyan(),
+                        agent_display.cyan(),
+                        agent_obj.name
+                    );
+                }
+                print_batch_failures(&report, "assign");
+                if !report.rolled_back() {
+                    print_wip_warnings(&exceeded.into_inner());
+                }
+            }
+            if report.rolled_back() {
+                anyhow::bail!(
+                    "{} task(s) failed; rolled back, no tasks were changed",
+                    report.failed
+                );
+            }
+        }
+
+        WorkflowCommands::Depends {
+            task_id,
+            on,
+            blocks,
+            list,
+        } => {
+            let task_uuid = resolve_task_id(db.get_connection(), &task_id)?;
+            let task = db
+                .get_task(&task_uuid)?
+                .ok_or_else(|| anyhow::anyhow!("Task not found"))?;
+            let task_display_id = task
+                .display_id
+                .ok_or_else(|| anyhow::anyhow!("Task missing display_id"))?;
+
+            if list {
+                let deps = db.get_connection().get_dependencies(task_display_id)?;
+                let blocking = db.get_connection().get_blocking_tasks(task_display_id)?;
+
+                println!("\nDependencies for task #{}", task_display_id);
+                println!("Title: {}", task.title.bold());
+
+                if !deps.is_empty() {
+                    println!("\nDepends on:");
+                    for dep_id in deps {
+                        println!("  #{}", dep_id);
+                    }
+                } else {
+                    println!("\nNo dependencies");
+                }
+
+                if !blocking.is_empty() {
+                    println!("\nBlocks:");
+                    for block_id in blocking {
+                        println!("  #{}", block_id);
+                    }
+                }
+            } else if let Some(depends_on_input) = on {
+                let depends_on_uuid = resolve_task_id(db.get_connection(), &depends_on_input)?;
+                let depends_on_task = db
+                    .get_task(&depends_on_uuid)?
+                    .ok_or_else(|| anyhow::anyhow!("Dependency task not found"))?;
+                let depends_on_id = depends_on_task
+                    .display_id
+                    .ok_or_else(|| anyhow::anyhow!("Dependency task missing display_id"))?;
+
+                db.get_connection()
+                    .add_dependency(task_display_id, depends_on_id, "blocks")?;
+                println!(
+                    "{} Task #{} now depends on #{}",
+                    "✓".green().bold(),
+                    task_display_id,
+                    depends_on_id
+                );
+            } else if let Some(blocks_input) = blocks {
+                let blocks_uuid = resolve_task_id(db.get_connection(), &blocks_input)?;
+                let blocks_task = db
+                    .get_task(&blocks_uuid)?
+                    .ok_or_else(|| anyhow::anyhow!("Blocked task not found"))?;
+                let blocks_id = blocks_task
+                    .display_id
+                    .ok_or_else(|| anyhow::anyhow!("Blocked task missing display_id"))?;
+
+                db.get_connection()
+                    .add_dependency(blocks_id, task_display_id, "blocks")?;
+                println!(
+                    "{} Task #{} now blocks #{}",
+                    "✓".green().bold(),
+                    task_display_id,
+                    blocks_id
+                );
+            } else {
+                println!("Use --on <task-id>, --blocks <task-id>, or --list");
+            }
+        }
+
+        WorkflowCommands::EpicDepends {
+            epic,
+            on,
+            threshold,
+            remove,
+            list,
+        } => {
+            if list {
+                let gates: Vec<_> = db
+                    .get_connection()
+                    .epic_gates()?
+                    .into_iter()
+                    .filter(|g| epic.as_ref().is_none_or(|e| &g.epic == e))
+                    .collect();
+                if gates.is_empty() {
+                    println!("{}", "No epic dependencies.".yellow());
+                    return Ok(());
+                }
+                for gate in gates {
+                    let state = if gate.met {
+                        "✓ met".green()
+                    } else {
+                        "⏸ waiting".yellow()
+                    };
+                    println!(
+                        "{} ← {} (needs {}%, at {}%) {}",
+                        gate.epic.cyan().bold(),
+                        gate.depends_on,
+                        gate.threshold,
+                        gate.progress,
+                        state
+                    );
+                }
+                return Ok(());
+            }
+
+            let epic = epic.ok_or_else(|| anyhow::anyhow!("Epic name required"))?;
+            let on = on.ok_or_else(|| anyhow::anyhow!("--on <epic> required"))?;
+            if remove {
+                if db.get_connection().remove_epic_dependency(&epic, &on)? {
+                    println!(
+                        "{} Epic '{}' no longer depends on '{}'",
+                        "✓".green().bold(),
+                        epic,
+                        on
+                    );
+                } else {
+                    println!("{}", "No such epic dependency.".yellow());
+                }
+            } else {
+                db.get_connection()
+                    .add_epic_dependency(&epic, &on, threshold)?;
+                println!(
+                    "{} Epic '{}' now waits for '{}' to reach {}%",
+                    "✓".green().bold(),
+                    epic,
+                    on,
+                    threshold
+                );
+            }
+        }
+
+        WorkflowCommands::Ready => {
+            let ready_ids = db.get_connection().get_ready_tasks()?;
+
+            if ready_ids.is_empty() {
+                println!(
+                    "{}",
+                    "No tasks ready (all have pending dependencies).".yellow()
+                );
+                return Ok(());
+            }
+
+            println!("\n{}", "Tasks Ready to Work On".bold().underline());
+            for task in &ready_queue(&db)? {
+                let score = db
+                    .get_task_value(&task.id)?
+                    .wsjf()
+                    .map(|s| format!(" WSJF {:.2}", s).dimmed().to_string())
+                    .unwrap_or_default();
+                println!(
+                    "#{} - {} [{}]{}",
+                    task.display_id.unwrap_or_default(),
+                    task.title,
+                    format_priority(&task.priority),
+                    score
+                );
+            }
+            println!(
+                "\n{} tasks ready",
+                ready_ids.len().to_string().cyan().bold()
+            );
+        }
+
+        WorkflowCommands::Prioritize {
+            wsjf,
+            by_priority,
+            json,
+        } => {
+            if wsjf {
+                db.set_setting(db::SETTING_QUEUE_ORDER, "wsjf")?;
+            } else if by_priority {
+                db.unset_setting(db::SETTING_QUEUE_ORDER)?;
+            }
+            let by_wsjf = db.orders_by_wsjf()?;
+            let queue = ready_queue(&db)?;
+            let mut entries = Vec::with_capacity(queue.len());
+            for task in &queue {
+                entries.push((task, db.get_task_value(&task.id)?));
+            }
+
+            if json {
+                let items: Vec<_> = entries
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (task, value))| {
+                        serde_json::json!({
+                            "position": i + 1,
+                            "id": task.id,
+                            "display_id": task.display_id,
+                            "title": task.title,
+                            "priority": task.priority.as_str(),
+                            "business_value": value.business_value,
+                            "effort": value.effort,
+                            "wsjf": value.wsjf(),
+                        })
+                    })
+                    .collect();
+                let output = serde_json::json!({
+                    "order": if by_wsjf { "wsjf" } else { "priority" },
+                    "queue": items,
+                });
+                println!("{}", serde_json::to_string_pretty(&output)?);
+                return Ok(());
+            }
+
+            if queue.is_empty() {
+                println!("{}", "No tasks ready.".yellow());
+            } else {
+                let show = |v: Option<i32>| v.map_or("-".to_string(), |v| v.to_string());
+                let rows: Vec<QueueRow> = entries
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (task, value))| QueueRow {
+                        position: i + 1,
+                        id: format!("#{}", task.display_id.unwrap_or_default()),
+                        title: task.title.clone(),
+                        priority: task.priority.as_str().to_string(),
+                        value: show(value.business_value),
+                        effort: show(value.effort),
+                        wsjf: value
+                            .wsjf()
+                            .map_or("-".to_string(), |s| format!("{:.2}", s)),
+                    })
+                    .collect();
+                println!("{}", Table::new(rows).with(Style::modern()));
+            }
+
+            let unscored = entries.iter().filter(|(_, v)| v.wsjf().is_none()).count();
+            if by_wsjf {
+                println!("\n{} Ready queue ordered by WSJF score", "✓".green().bold());
+                if unscored > 0 {
+                    println!(
+                        "{} tasks without value and effort follow by priority \
+                         (set them with `prd value`)",
+                        unscored
+                    );
+                }
+            } else {
+                println!(
+                    "\nReady queue ordered by priority{}",
+                    if wsjf || by_priority {
+                        ""
+                    } else {
+                        " (use --wsjf to order by score)"
+                    }
+                );
+            }
+        }
+
+        WorkflowCommands::Verify { json } => {
+            let report = prd_tool::verify::verify(&db, dry_run)?;
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else if report.is_clean() {
+                println!("{} Derived data matches its sources", "✓".green().bold());
+            } else {
+                let rows: Vec<VerifyRow> = report
+                    .discrepancies
+                    .iter()
+                    .map(|d| VerifyRow {
+                        data: d.data.as_str().to_string(),
+                        subject: d.subject.clone(),
+                        found: d.found.clone(),
+                        expected: d.expected.clone(),
+                        action: match (d.repaired, d.data) {
+                            (true, _) => "repaired",
+                            (false, prd_tool::verify::DerivedData::ParentRollup) => "review",
+                            (false, _) => "would repair",
+                        }
+                        .to_string(),
+                    })
+                    .collect();
+                println!("{}", Table::new(rows).with(Style::modern()));
+                if dry_run {
+                    println!(
+                        "{} Dry run: nothing changed (run without --dry-run to repair)",
+                        "ℹ".blue().bold()
+                    );
+                } else {
+                    println!(
+                        "{} Repaired {} of {} discrepancies",
+                        "✓".green().bold(),
+                        report.repaired(),
+                        report.discrepancies.len()
+                    );
+                }
+            }
+        }
+
+        WorkflowCommands::CompleteBatch {
+            tasks,
+            agent_map,
+            from_file,
+            from_csv,
+            from_yaml,
+        } => {
+            let records = if let Some(tasks_str) = tasks {
+                let map = agent_map
+                    .ok_or_else(|| anyhow::anyhow!("--agent-map required with --tasks"))?;
+                batch::parse_cli_args(&tasks_str, &map)?
+            } else if let Some(json_path) = from_file {
+                batch::parse_json_file(&json_path)?
+            } else if let Some(csv_path) = from_csv {
+                batch::parse_csv_file(&csv_path)?
+            } else if let Some(yaml_path) = from_yaml {
+                batch::parse_yaml_file(&yaml_path)?
+            } else {
+                anyhow::bail!("Must specify --tasks, --from-file, --from-csv, or --from-yaml");
+            };
+
+            let result = if dry_run {
+                batch::preview_batch(&db, &records)?
+            } else {
+                batch::complete_batch(&db, records)?
+            };
+            result.print_summary(dry_run);
+
+            if !result.failed.is_empty() {
+                std::process::exit(1);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Ready tasks in the order `next` hands them out
+fn ready_queue(db: &Database) -> Result<Vec<db::Task>> {
+    let mut tasks = Vec::new();
+    for display_id in db.get_connection().get_ready_tasks()? {
+        let uuid: String = db.get_connection().query_row(
+            "SELECT id FROM tasks WHERE display_id = ?1",
+            [display_id],
+            |row| row.get(0),
+        )?;
+        if let Some(task) = db.get_task(&uuid)? {
+            tasks.push(task);
+        }
+    }
+    db.sort_ready_queue(&mut tasks)?;
+    Ok(tasks)
+}
+
+/// Print failed items of a batch, and whether the batch was rolled back
+fn print_batch_failures(report: &batch::BatchReport, verb: &str) {
+    if report.failed == 0 {
+        return;
+    }
+    println!("\n{} Failed to {}:", "⚠".yellow(), verb);
+    for item in &report.items {
+        if item.status != batch::update::ItemStatus::Failed {
+            continue;
+        }
+        if let Some(error) = &item.error {
+            println!("  {}", format!("{}: {}", item.task, error).dimmed());
+        }
+    }
+}
@@ -0,0 +1,194 @@
+//! Colors - user-assigned colors for epics, tags and priorities
+//!
+//! Colors are stored as settings, e.g. `prd config color.epic.Auth cyan` or
+//! `prd config color.priority.critical "#ff0000"`, and applied wherever those
+//! values are shown: list output, the dashboards and the HTML/SVG exports.
+//! Values are a named color or `#rrggbb`.
+
+use crate::db::Database;
+use anyhow::Result;
+use colored::{ColoredString, Colorize};
+use serde::{Serialize, Serializer};
+use std::collections::BTreeMap;
+
+/// Prefix of color settings, e.g. "color.epic.Auth"
+pub const COLOR_SETTING_PREFIX: &str = "color.";
+
+/// Named colors accepted in place of `#rrggbb`
+const NAMED_COLORS: &[(&str, Rgb)] = &[
+    ("black", Rgb(0x00, 0x00, 0x00)),
+    ("red", Rgb(0xd7, 0x3a, 0x49)),
+    ("green", Rgb(0x28, 0xa7, 0x45)),
+    ("yellow", Rgb(0xdb, 0xab, 0x09)),
+    ("blue", Rgb(0x03, 0x66, 0xd6)),
+    ("magenta", Rgb(0xc0, 0x39, 0xb6)),
+    ("cyan", Rgb(0x17, 0xa2, 0xb8)),
+    ("white", Rgb(0xff, 0xff, 0xff)),
+    ("gray", Rgb(0x6a, 0x73, 0x7d)),
+    ("orange", Rgb(0xf6, 0x6a, 0x0a)),
+    ("purple", Rgb(0x6f, 0x42, 0xc1)),
+    ("pink", Rgb(0xe8, 0x3e, 0x8c)),
+];
+
+/// A 24-bit color
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    /// Parse a named color such as `cyan`, or `#rrggbb`
+    pub fn parse(value: &str) -> Result<Self> {
+        let value = value.trim().to_ascii_lowercase();
+        if let Some((_, rgb)) = NAMED_COLORS.iter().find(|(name, _)| *name == value) {
+            return Ok(*rgb);
+        }
+        let hex = value
+            .strip_prefix('#')
+            .filter(|hex| hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()));
+        let Some(hex) = hex else {
+            let names: Vec<&str> = NAMED_COLORS.iter().map(|(name, _)| *name).collect();
+            anyhow::bail!(
+                "Invalid color '{}'; use #rrggbb or one of: {}",
+                value,
+                names.join(", ")
+            );
+        };
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap();
+        Ok(Self(channel(0), channel(2), channel(4)))
+    }
+
+    /// CSS/SVG form, e.g. "#17a2b8"
+    pub fn hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+
+    /// Color text for the terminal
+    pub fn paint(&self, text: &str) -> ColoredString {
+        text.truecolor(self.0, self.1, self.2)
+    }
+}
+
+impl Serialize for Rgb {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.hex())
+    }
+}
+
+/// What a color setting applies to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColorTarget {
+    Epic(String),
+    Tag(String),
+    Priority(String),
+}
+
+impl ColorTarget {
+    /// Parse a setting key such as "color.tag.backend"
+    pub fn parse(key: &str) -> Result<Self> {
+        let target = key.strip_prefix(COLOR_SETTING_PREFIX).unwrap_or(key);
+        let (kind, name) = target.split_once('.').unwrap_or((target, ""));
+        if name.is_empty() {
+            anyhow::bail!(
+                "Color settings are color.epic.<name>, color.tag.<name> or color.priority.<level>"
+            );
+        }
+        match kind {
+            "epic" => Ok(Self::Epic(name.to_string())),
+            "tag" => Ok(Self::Tag(name.to_ascii_lowercase())),
+            "priority" => Ok(Self::Priority(name.to_ascii_lowercase())),
+            _ => anyhow::bail!("Unknown color target '{}'; use epic, tag or priority", kind),
+        }
+    }
+}
+
+/// Colors configured for epics, tags and priorities
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ColorScheme {
+    pub epics: BTreeMap<String, Rgb>,
+    pub tags: BTreeMap<String, Rgb>,
+    pub priorities: BTreeMap<String, Rgb>,
+}
+
+impl ColorScheme {
+    /// Colors from the `color.*` settings; malformed entries are ignored
+    pub fn load(db: &Database) -> Result<Self> {
+        let mut scheme = Self::default();
+        for (key, value) in db.list_settings()? {
+            if !key.starts_with(COLOR_SETTING_PREFIX) {
+                continue;
+            }
+            let (Ok(target), Ok(color)) = (ColorTarget::parse(&key), Rgb::parse(&value)) else {
+                continue;
+            };
+            match target {
+                ColorTarget::Epic(name) => scheme.epics.insert(name, color),
+                ColorTarget::Tag(name) => scheme.tags.insert(name, color),
+                ColorTarget::Priority(name) => scheme.priorities.insert(name, color),
+            };
+        }
+        Ok(scheme)
+    }
+
+    pub fn epic(&self, epic: &str) -> Option<Rgb> {
+        self.epics.get(epic).copied()
+    }
+
+    pub fn tag(&self, tag: &str) -> Option<Rgb> {
+        self.tags.get(&tag.to_ascii_lowercase()).copied()
+    }
+
+    /// Color of a priority level, by its name (e.g. "critical")
+    pub fn priority(&self, priority: &str) -> Option<Rgb> {
+        self.priorities.get(&priority.to_ascii_lowercase()).copied()
+    }
+
+    /// Tags joined with ", ", each in its color or `fallback`
+    pub fn paint_tags(&self, tags: &[String], fallback: fn(&str) -> ColoredString) -> String {
+        tags.iter()
+            .map(|tag| match self.tag(tag) {
+                Some(color) => color.paint(tag).to_string(),
+                None => fallback(tag).to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_scheme_from_settings() -> Result<()> {
+        assert_eq!(Rgb::parse("#FF8000")?, Rgb(0xff, 0x80, 0x00));
+        assert_eq!(Rgb::parse("Cyan")?.hex(), "#17a2b8");
+        assert!(Rgb::parse("#fff").is_err());
+        assert!(Rgb::parse("chartreuse").is_err());
+        assert_eq!(
+            ColorTarget::parse("color.epic.Phase 1.2")?,
+            ColorTarget::Epic("Phase 1.2".to_string())
+        );
+        assert!(ColorTarget::parse("color.status.done").is_err());
+        assert!(ColorTarget::parse("color.epic").is_err());
+
+        let db = Database::new(":memory:")?;
+        db.set_setting("color.epic.Auth", "purple")?;
+        db.set_setting("color.tag.Backend", "#00ff00")?;
+        db.set_setting("color.priority.critical", "red")?;
+        db.set_setting("color.tag.broken", "not-a-color")?;
+
+        let scheme = ColorScheme::load(&db)?;
+        assert_eq!(scheme.epic("Auth"), Some(Rgb(0x6f, 0x42, 0xc1)));
+        assert_eq!(scheme.epic("auth"), None);
+        assert_eq!(scheme.tag("backend"), Some(Rgb(0x00, 0xff, 0x00)));
+        assert_eq!(scheme.tag("broken"), None);
+        assert_eq!(
+            scheme.priority("Critical").map(|c| c.hex()).as_deref(),
+            Some("#d73a49")
+        );
+        assert_eq!(
+            serde_json::to_value(&scheme)?["epics"]["Auth"],
+            serde_json::json!("#6f42c1")
+        );
+        Ok(())
+    }
+}
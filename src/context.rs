@@ -0,0 +1,262 @@
+use crate::db::{Agent, Database, Task, TaskLease, TaskLog};
+use crate::db_extensions::{AcceptanceCriteriaOps, AcceptanceCriterion};
+use anyhow::{Context, Result};
+use rusqlite::params;
+use serde::Serialize;
+use std::fmt::Write;
+
+/// Default number of recent log entries included in a task context
+pub const DEFAULT_CONTEXT_LOGS: usize = 10;
+
+/// Another task referenced from a context, with its current status
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TaskRef {
+    pub id: String,
+    pub display_id: Option<i32>,
+    pub title: String,
+    pub status: String,
+}
+
+impl TaskRef {
+    pub(crate) fn from_task(task: &Task) -> Self {
+        TaskRef {
+            id: task.id.clone(),
+            display_id: task.display_id,
+            title: task.title.clone(),
+            status: task.status.as_str().to_string(),
+        }
+    }
+
+    /// "#42", or the UUID prefix for tasks without a display ID
+    pub fn label(&self) -> String {
+        self.display_id
+            .map(|id| format!("#{}", id))
+            .unwrap_or_else(|| self.id.chars().take(8).collect())
+    }
+}
+
+/// Everything an agent needs to know about a task, gathered in one document
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskContext {
+    pub task: Task,
+    pub acceptance_criteria: Vec<AcceptanceCriterion>,
+    /// Tasks this task depends on
+    pub dependencies: Vec<TaskRef>,
+    /// Tasks that depend on this task
+    pub dependents: Vec<TaskRef>,
+    pub parent: Option<TaskRef>,
+    pub subtasks: Vec<TaskRef>,
+    pub agent: Option<Agent>,
+    pub lease: Option<TaskLease>,
+    /// Latest log entries, newest first
+    pub recent_logs: Vec<TaskLog>,
+}
+
+/// Gather the context of the task with UUID `task_id`, keeping the `log_limit`
+/// most recent log entries
+pub fn task_context(db: &Database, task_id: &str, log_limit: usize) -> Result<TaskContext> {
+    let task = db
+        .get_task(task_id)?
+        .with_context(|| format!("Task not found: {}", task_id))?;
+
+    let (acceptance_criteria, dependencies, dependents) = match task.display_id {
+        Some(display_id) => (
+            db.get_connection().list_criteria(display_id)?,
+            dependency_refs(
+                db,
+                "SELECT t.id FROM task_dependencies d
+                 JOIN tasks t ON t.display_id = d.depends_on_display_id
+                 WHERE d.task_display_id = ?1 AND t.deleted_at IS NULL ORDER BY t.display_id",
+                display_id,
+            )?,
+            dependency_refs(
+                db,
+                "SELECT t.id FROM task_dependencies d
+                 JOIN tasks t ON t.display_id = d.task_display_id
+                 WHERE d.depends_on_display_id = ?1 AND t.deleted_at IS NULL
+                 ORDER BY t.display_id",
+                display_id,
+            )?,
+        ),
+        None => (Vec::new(), Vec::new(), Vec::new()),
+    };
+
+    let parent = match &task.parent_id {
+        Some(id) => db.get_task(id)?.as_ref().map(TaskRef::from_task),
+        None => None,
+    };
+    let subtasks = db
+        .get_subtasks(&task.id)?
+        .iter()
+        .map(TaskRef::from_task)
+        .collect();
+    let agent = match &task.assigned_agent {
+        Some(id) => db.get_agent(id)?,
+        None => None,
+    };
+    let lease = db.get_active_lease(&task.id)?;
+    let mut recent_logs = db.get_task_logs(&task.id)?;
+    recent_logs.truncate(log_limit);
+
+    Ok(TaskContext {
+        task,
+        acceptance_criteria,
+        dependencies,
+        dependents,
+        parent,
+        subtasks,
+        agent,
+        lease,
+        recent_logs,
+    })
+}
+
+/// Tasks whose UUIDs `sql` selects for `display_id`
+fn dependency_refs(db: &Database, sql: &str, display_id: i32) -> Result<Vec<TaskRef>> {
+    let ids: Vec<String> = db
+        .get_connection()
+        .prepare(sql)?
+        .query_map(params![display_id], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    let mut refs = Vec::new();
+    for id in ids {
+        if let Some(task) = db.get_task(&id)? {
+            refs.push(TaskRef::from_task(&task));
+        }
+    }
+    Ok(refs)
+}
+
+impl TaskContext {
+    /// Render as markdown for pasting into a prompt
+    pub fn to_markdown(&self) -> String {
+        let t = &self.task;
+        let mut out = String::new();
+        let _ = writeln!(out, "# {} {}\n", TaskRef::from_task(t).label(), t.title);
+        let _ = writeln!(out, "- Status: {}", t.status.as_str());
+        let _ = writeln!(out, "- Priority: {}", t.priority.as_str());
+        if let Some(epic) = &t.epic_name {
+            let _ = writeln!(out, "- Epic: {}", epic);
+        }
+        if let Some(agent) = &self.agent {
+            let _ = writeln!(
+                out,
+                "- Assigned to: {} ({})",
+                agent.name,
+                agent.status.as_str()
+            );
+        }
+        if let Some(lease) = &self.lease {
+            let _ = writeln!(out, "- Leased until: {}", lease.expires_at.to_rfc3339());
+        }
+        if let Some(parent) = &self.parent {
+            let _ = writeln!(
+                out,
+                "- Parent: {} {} ({})",
+                parent.label(),
+                parent.title,
+                parent.status
+            );
+        }
+        if let Some(description) = &t.description {
+            let _ = writeln!(out, "\n## Description\n\n{}", description);
+        }
+
+        if !self.acceptance_criteria.is_empty() {
+            let _ = writeln!(out, "\n## Acceptance criteria\n");
+            for ac in &self.acceptance_criteria {
+                let mark = if ac.completed { "x" } else { " " };
+                let _ = writeln!(out, "- [{}] {}", mark, ac.criterion);
+            }
+        }
+
+        for (heading, refs) in [
+            ("Depends on", &self.dependencies),
+            ("Blocks", &self.dependents),
+            ("Subtasks", &self.subtasks),
+        ] {
+            if refs.is_empty() {
+                continue;
+            }
+            let _ = writeln!(out, "\n## {}\n", heading);
+            for r in refs {
+                let _ = writeln!(out, "- {} {} ({})", r.label(), r.title, r.status);
+            }
+        }
+
+        if !self.recent_logs.is_empty() {
+            let _ = writeln!(out, "\n## Recent activity\n");
+            for log in &self.recent_logs {
+                let _ = writeln!(
+                    out,
+                    "- {} {}{}",
+                    log.created_at.format("%Y-%m-%d %H:%M"),
+                    log.action,
+                    log.details
+                        .as_ref()
+                        .map(|d| format!(": {}", d))
+                        .unwrap_or_default()
+                );
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Priority, TaskStatus};
+    use crate::db_extensions::DependencyOps;
+
+    #[test]
+    fn test_task_context() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        let epic = db.create_task("Epic".to_string(), None, Priority::High, None, None)?;
+        let task = db.create_task(
+            "Build API".to_string(),
+            Some("REST endpoints".to_string()),
+            Priority::High,
+            Some(epic.id.clone()),
+            None,
+        )?;
+        let schema = db.create_task("Schema".to_string(), None, Priority::Low, None, None)?;
+        let docs = db.create_task("Docs".to_string(), None, Priority::Low, None, None)?;
+        let sub = db.create_task(
+            "Routes".to_string(),
+            None,
+            Priority::Low,
+            Some(task.id.clone()),
+            None,
+        )?;
+        let conn = db.get_connection();
+        let id = task.display_id.unwrap();
+        conn.add_dependency(id, schema.display_id.unwrap(), "blocks")?;
+        conn.add_dependency(docs.display_id.unwrap(), id, "blocks")?;
+        conn.add_criterion(id, "Endpoints documented".to_string())?;
+        db.update_task_status(&schema.id, TaskStatus::Completed, None)?;
+        let agent = db.create_agent("builder".to_string())?;
+        db.assign_task(&task.id, &agent.id)?;
+        db.log_task_action(&task.id, None, "note", Some("Started"))?;
+
+        let context = task_context(&db, &task.id, 1)?;
+        assert_eq!(
+            context.parent.as_ref().map(|p| p.id.as_str()),
+            Some(epic.id.as_str())
+        );
+        assert_eq!(context.subtasks[0].id, sub.id);
+        assert_eq!(context.dependencies[0].status, "completed");
+        assert_eq!(context.dependents[0].id, docs.id);
+        assert_eq!(context.acceptance_criteria.len(), 1);
+        assert_eq!(
+            context.agent.as_ref().map(|a| a.name.as_str()),
+            Some("builder")
+        );
+        assert_eq!(context.recent_logs.len(), 1);
+
+        let json = serde_json::to_value(&context)?;
+        assert_eq!(json["task"]["title"], "Build API");
+        assert!(context.to_markdown().contains("- [ ] Endpoints documented"));
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+use crate::db::Database;
+use anyhow::Result;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+
+/// Detects database changes made by other processes
+///
+/// Watches the SQLite data file and its `-wal`/`-journal` siblings for
+/// filesystem events, then confirms with `PRAGMA data_version` (which only
+/// changes when another connection commits). When the file cannot be watched
+/// (e.g. `:memory:`), falls back to checking `data_version` on every call.
+pub struct ChangeDetector {
+    rx: Option<Receiver<()>>,
+    _watcher: Option<RecommendedWatcher>,
+    last_version: i64,
+}
+
+impl ChangeDetector {
+    /// Create a detector for the database at `db_path`
+    pub fn new(db_path: &str, db: &Database) -> Result<Self> {
+        let last_version = data_version(db)?;
+        let (rx, watcher) = match watch_db_files(Path::new(db_path)) {
+            Ok((rx, watcher)) => (Some(rx), Some(watcher)),
+            Err(_) => (None, None),
+        };
+
+        Ok(Self {
+            rx,
+            _watcher: watcher,
+            last_version,
+        })
+    }
+
+    /// Whether a file watcher is active (otherwise only polling is used)
+    pub fn is_watching(&self) -> bool {
+        self.rx.is_some()
+    }
+
+    /// Check whether the database changed since the last call
+    pub fn has_changed(&mut self, db: &Database) -> Result<bool> {
+        if let Some(rx) = &self.rx {
+            // Drain pending events; skip the query entirely when nothing happened
+            let mut saw_event = false;
+            while rx.try_recv().is_ok() {
+                saw_event = true;
+            }
+            if !saw_event {
+                return Ok(false);
+            }
+        }
+
+        let version = data_version(db)?;
+        if version != self.last_version {
+            self.last_version = version;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+}
+
+/// Read SQLite's per-connection data version counter
+fn data_version(db: &Database) -> Result<i64> {
+    let version = db
+        .get_connection()
+        .query_row("PRAGMA data_version", [], |row| row.get(0))?;
+    Ok(version)
+}
+
+/// Watch the directory holding the database and forward events for its files
+fn watch_db_files(db_path: &Path) -> Result<(Receiver<()>, RecommendedWatcher)> {
+    let db_path = db_path.canonicalize()?;
+    let dir = db_path
+        .parent()
+        .ok_or_else(|| anyhow::anyhow!("Database path has no parent directory"))?
+        .to_path_buf();
+    let file_name = db_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| anyhow::anyhow!("Invalid database file name"))?
+        .to_string();
+
+    let watched: Vec<PathBuf> = ["", "-wal", "-journal"]
+        .iter()
+        .map(|suffix| dir.join(format!("{}{}", file_name, suffix)))
+        .collect();
+
+    let (tx, rx) = channel();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        if let Ok(event) = res {
+            if event.paths.iter().any(|p| watched.contains(p)) {
+                let _ = tx.send(());
+            }
+        }
+    })?;
+    watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+
+    Ok((rx, watcher))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Priority;
+
+    #[test]
+    fn test_detects_write_from_other_connection() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("prd.db");
+        let path_str = path.to_str().unwrap();
+
+        let reader = Database::new(path_str)?;
+        let writer = Database::new(path_str)?;
+        let mut detector = ChangeDetector::new(path_str, &reader)?;
+        // Ignore any events caused by schema setup
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        detector.has_changed(&reader)?;
+
+        writer.create_task("Task".to_string(), None, Priority::Low, None, None)?;
+
+        // File events are delivered asynchronously
+        let mut changed = false;
+        for _ in 0..50 {
+            if detector.has_changed(&reader)? {
+                changed = true;
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        assert!(changed);
+        assert!(!detector.has_changed(&reader)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_memory_database_falls_back_to_polling() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        let mut detector = ChangeDetector::new(":memory:", &db)?;
+
+        assert!(!detector.is_watching());
+        assert!(!detector.has_changed(&db)?);
+
+        Ok(())
+    }
+}
@@ -1,5 +1,12 @@
+mod changes;
+mod replay;
+mod session;
 mod state;
 mod ui;
+pub mod widgets;
 
+pub use changes::ChangeDetector;
+pub use replay::{Replay, DEFAULT_REPLAY_SPEED};
+pub use session::{AgentProgressDelta, CompletedTaskEntry, SessionSummary, SessionTracker};
 pub use state::{ActivityEvent, AgentDisplay, DashboardState};
-pub use ui::run_dashboard;
+pub use ui::{run_dashboard, run_replay};
@@ -0,0 +1,326 @@
+use super::state::{ActivityEvent, AgentDisplay, DashboardState, TaskWindow};
+use crate::colors::ColorScheme;
+use crate::db::{
+    Agent, AgentProgress, AgentStatus, Database, Priority, Task, TaskLog, TaskStatus, TimeDisplay,
+    Workflow,
+};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Default replay speed: one hour of history per second
+pub const DEFAULT_REPLAY_SPEED: f64 = 3600.0;
+
+/// Activity entries kept while replaying, matching the live dashboard
+const ACTIVITY_LIMIT: usize = 10;
+
+/// A recorded change, from the task log or an agent progress report
+#[derive(Debug, Clone)]
+enum ReplayEvent {
+    Log(TaskLog),
+    Progress(AgentProgress),
+}
+
+impl ReplayEvent {
+    fn at(&self) -> DateTime<Utc> {
+        match self {
+            ReplayEvent::Log(log) => log.created_at,
+            ReplayEvent::Progress(progress) => progress.timestamp,
+        }
+    }
+}
+
+/// Task and agent state rebuilt from the activity log, played forward
+/// through time
+///
+/// Tasks appear when their first log entry is replayed and take the status,
+/// priority and assignee recorded up to the replay clock. Agents count as
+/// working while they hold an in-progress task.
+pub struct Replay {
+    events: Vec<ReplayEvent>,
+    next: usize,
+    /// Point in history currently shown
+    pub clock: DateTime<Utc>,
+    /// Current rows, used for titles and as templates for replayed tasks
+    templates: HashMap<String, Task>,
+    agents: Vec<Agent>,
+    tasks: HashMap<String, Task>,
+    /// When each task last moved to in progress
+    started: HashMap<String, DateTime<Utc>>,
+    progress: HashMap<String, AgentProgress>,
+    activity: Vec<ActivityEvent>,
+    workflow: Workflow,
+    time: TimeDisplay,
+    colors: ColorScheme,
+}
+
+impl Replay {
+    /// Load the history and fast-forward to `from`, or to the first recorded
+    /// event when not given
+    pub fn load(db: &Database, from: Option<DateTime<Utc>>) -> Result<Self> {
+        let mut events: Vec<ReplayEvent> = db
+            .task_log_history()?
+            .into_iter()
+            .map(ReplayEvent::Log)
+            .chain(
+                db.progress_history()?
+                    .into_iter()
+                    .map(ReplayEvent::Progress),
+            )
+            .collect();
+        events.sort_by_key(|e| e.at());
+
+        let start = from
+            .or_else(|| events.first().map(|e| e.at()))
+            .unwrap_or_else(Utc::now);
+        let mut replay = Self {
+            events,
+            next: 0,
+            clock: start,
+            templates: db
+                .list_tasks(None)?
+                .into_iter()
+                .map(|t| (t.id.clone(), t))
+                .collect(),
+            agents: db.list_agents()?,
+            tasks: HashMap::new(),
+            started: HashMap::new(),
+            progress: HashMap::new(),
+            activity: Vec::new(),
+            workflow: db.workflow()?,
+            time: db.time_display()?,
+            colors: ColorScheme::load(db)?,
+        };
+        replay.advance_to(start);
+        Ok(replay)
+    }
+
+    /// Whether every recorded event has been replayed
+    pub fn finished(&self) -> bool {
+        self.next >= self.events.len()
+    }
+
+    /// Move the clock forward by `elapsed` of history, returning whether
+    /// any event was replayed
+    pub fn advance_by(&mut self, elapsed: chrono::Duration) -> bool {
+        let to = self.clock + elapsed;
+        self.advance_to(to)
+    }
+
+    /// Jump to the next recorded event
+    pub fn step(&mut self) -> bool {
+        match self.events.get(self.next) {
+            Some(event) => {
+                let at = event.at();
+                self.advance_to(at)
+            }
+            None => false,
+        }
+    }
+
+    /// Apply every event up to `to`, returning whether any was applied
+    pub fn advance_to(&mut self, to: DateTime<Utc>) -> bool {
+        let before = self.next;
+        while let Some(event) = self.events.get(self.next) {
+            if event.at() > to {
+                break;
+            }
+            let event = event.clone();
+            self.apply(&event);
+            self.next += 1;
+        }
+        self.clock = self.clock.max(to);
+        self.next > before
+    }
+
+    fn apply(&mut self, event: &ReplayEvent) {
+        let message = match event {
+            ReplayEvent::Log(log) => self.apply_log(log),
+            ReplayEvent::Progress(progress) => {
+                let message = format!(
+                    "{} at {}% on #{}",
+                    self.agent_name(&progress.agent_id),
+                    progress.progress,
+                    progress.task_id
+                );
+                self.progress
+                    .insert(progress.agent_id.clone(), progress.clone());
+                Some(message)
+            }
+        };
+
+        if let Some(message) = message {
+            self.activity.insert(
+                0,
+                ActivityEvent {
+                    timestamp: event.at(),
+                    message,
+                },
+            );
+            self.activity.truncate(ACTIVITY_LIMIT);
+        }
+    }
+
+    fn apply_log(&mut self, log: &TaskLog) -> Option<String> {
+        if !self.tasks.contains_key(&log.task_id) {
+            // Tasks deleted since have no row to show
+            let mut task = self.templates.get(&log.task_id)?.clone();
+            task.status = TaskStatus::Pending;
+            task.assigned_agent = None;
+            task.completed_at = None;
+            task.created_at = log.created_at;
+            self.tasks.insert(log.task_id.clone(), task);
+        }
+        let changed = log
+            .details
+            .as_deref()
+            .and_then(|d| d.strip_prefix("Changed to "));
+        let task = self.tasks.get_mut(&log.task_id)?;
+        task.updated_at = log.created_at;
+        let label = format!("#{}", task.display_id.unwrap_or(0));
+
+        match (log.action.as_str(), changed) {
+            ("created", _) => Some(format!("{} created: {}", label, task.title)),
+            ("status_changed", Some(status)) => {
+                task.status = TaskStatus::from_str(status);
+                if task.status == TaskStatus::InProgress {
+                    self.started.insert(log.task_id.clone(), log.created_at);
+                } else if self.workflow.is_completed(&task.status) {
+                    task.completed_at = Some(log.created_at);
+                }
+                Some(format!("{} → {}", label, task.status.as_str()))
+            }
+            ("priority_changed", Some(priority)) => {
+                task.priority = Priority::from_str(priority);
+                Some(format!("{} priority → {}", label, task.priority.as_str()))
+            }
+            ("assigned", _) => {
+                task.assigned_agent = log.agent_id.clone();
+                let agent = log.agent_id.as_deref().map(|id| self.agent_name(id));
+                Some(format!(
+                    "{} assigned to {}",
+                    label,
+                    agent.unwrap_or_else(|| "nobody".to_string())
+                ))
+            }
+            (action, _) => Some(match &log.details {
+                Some(details) => format!("{} {}: {}", label, action, details),
+                None => format!("{} {}", label, action),
+            }),
+        }
+    }
+
+    fn agent_name(&self, agent_id: &str) -> String {
+        self.agents
+            .iter()
+            .find(|a| a.id == agent_id)
+            .map(|a| a.name.clone())
+            .unwrap_or_else(|| agent_id.chars().take(8).collect())
+    }
+
+    /// Dashboard state as of the replay clock, keeping the task list
+    /// selection at `selected`
+    pub fn snapshot(&self, selected: usize) -> DashboardState {
+        let mut state = DashboardState::new();
+        state.last_refresh = self.clock;
+        state.time = self.time.clone();
+        state.colors = self.colors.clone();
+
+        let mut tasks: Vec<Task> = self.tasks.values().cloned().collect();
+        tasks.sort_by_key(|t| t.display_id);
+        state.total_count = tasks.len() as i32;
+        state.completed_count = tasks
+            .iter()
+            .filter(|t| self.workflow.is_completed(&t.status))
+            .count() as i32;
+        state.overall_progress = if tasks.is_empty() {
+            0.0
+        } else {
+            state.completed_count as f64 / tasks.len() as f64 * 100.0
+        };
+
+        for agent in &self.agents {
+            let current = tasks.iter().find(|t| {
+                t.status == TaskStatus::InProgress
+                    && t.assigned_agent.as_deref() == Some(agent.id.as_str())
+            });
+            let progress = self
+                .progress
+                .get(&agent.id)
+                .filter(|p| current.is_some_and(|t| t.display_id == Some(p.task_id)));
+            let elapsed = current
+                .and_then(|t| self.started.get(&t.id))
+                .map(|at| (self.clock - *at).num_seconds().max(0) as u64)
+                .unwrap_or(0);
+
+            state.agents.push(AgentDisplay {
+                id: agent.id.clone(),
+                name: agent.name.clone(),
+                status: if current.is_some() {
+                    AgentStatus::Working
+                } else {
+                    AgentStatus::Idle
+                },
+                current_task: current.and_then(|t| t.display_id),
+                progress: progress.map(|p| p.progress).unwrap_or(0),
+                elapsed: Duration::from_secs(elapsed),
+                last_active: progress.map(|p| p.timestamp).unwrap_or(self.clock),
+                eta: None,
+            });
+        }
+        state.agents.sort_by(|a, b| {
+            (a.status != AgentStatus::Working, &a.name)
+                .cmp(&(b.status != AgentStatus::Working, &b.name))
+        });
+
+        state.recent_activity = self.activity.iter().cloned().collect();
+        state.tasks = TaskWindow {
+            total: tasks.len(),
+            selected: selected.min(tasks.len().saturating_sub(1)),
+            offset: 0,
+            tasks,
+        };
+        state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replay_rebuilds_past_state() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        let agent = db.create_agent("A1".to_string())?;
+        let first = db.create_task("Schema".to_string(), None, Priority::High, None, None)?;
+        let start = Utc::now();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        db.assign_task(&first.id, &agent.id)?;
+        db.update_task_status(&first.id, TaskStatus::InProgress, Some(&agent.id))?;
+        db.update_task_status(&first.id, TaskStatus::Completed, Some(&agent.id))?;
+        db.create_task("Docs".to_string(), None, Priority::Low, None, None)?;
+
+        // From the start only the first task exists, still pending
+        let mut replay = Replay::load(&db, Some(start))?;
+        let state = replay.snapshot(0);
+        assert_eq!(state.total_count, 1);
+        assert_eq!(state.tasks.tasks[0].status, TaskStatus::Pending);
+        assert_eq!(state.agents[0].status, AgentStatus::Idle);
+
+        // Assigned and started: the agent is shown working on it
+        assert!(replay.step());
+        assert!(replay.step());
+        let state = replay.snapshot(0);
+        assert_eq!(state.agents[0].status, AgentStatus::Working);
+        assert_eq!(state.agents[0].current_task, first.display_id);
+
+        // Played to the end it matches the live database
+        replay.advance_by(chrono::Duration::days(1));
+        assert!(replay.finished());
+        let state = replay.snapshot(0);
+        assert_eq!((state.completed_count, state.total_count), (1, 2));
+        assert_eq!(state.agents[0].status, AgentStatus::Idle);
+        Ok(())
+    }
+}
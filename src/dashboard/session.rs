@@ -0,0 +1,296 @@
+use crate::db::{Database, TaskStatus, TimeDisplay};
+use crate::notifications::SentNotification;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// A task that was completed while the dashboard was running
+#[derive(Debug, Clone, Serialize)]
+pub struct CompletedTaskEntry {
+    pub id: String,
+    pub title: String,
+    pub agent: Option<String>,
+    pub epic: Option<String>,
+}
+
+/// Progress change for a single agent over the session
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentProgressDelta {
+    pub agent: String,
+    pub name: String,
+    pub task: Option<String>,
+    pub start_progress: u8,
+    pub end_progress: u8,
+}
+
+/// Summary of a `prd watch` session, suitable for standup notes
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionSummary {
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    pub completed_tasks: Vec<CompletedTaskEntry>,
+    pub agent_progress: Vec<AgentProgressDelta>,
+    pub notifications: Vec<SentNotification>,
+    /// How the Markdown summary shows times
+    #[serde(skip)]
+    pub time: TimeDisplay,
+}
+
+/// Captures the database state at session start so it can be diffed on exit
+pub struct SessionTracker {
+    started_at: DateTime<Utc>,
+    initially_completed: HashSet<String>,
+    /// Agent UUID -> (task display ID, progress) of the latest report
+    initial_progress: HashMap<String, (i32, u8)>,
+}
+
+impl SessionTracker {
+    /// Snapshot the current state of the database
+    pub fn start(db: &Database) -> Result<Self> {
+        let initially_completed = db
+            .list_tasks(Some(TaskStatus::Completed))?
+            .into_iter()
+            .map(|t| t.id)
+            .collect();
+
+        let mut initial_progress = HashMap::new();
+        for agent in db.list_agents()? {
+            if let Some(p) = db.get_latest_progress(&agent.id)? {
+                initial_progress.insert(agent.id.clone(), (p.task_id, p.progress));
+            }
+        }
+
+        Ok(Self {
+            started_at: Utc::now(),
+            initially_completed,
+            initial_progress,
+        })
+    }
+
+    /// Build the summary by comparing the current state with the snapshot
+    pub fn finish(
+        &self,
+        db: &Database,
+        notifications: &[SentNotification],
+    ) -> Result<SessionSummary> {
+        let mut completed_tasks = Vec::new();
+        for task in db.list_tasks(Some(TaskStatus::Completed))? {
+            if self.initially_completed.contains(&task.id) {
+                continue;
+            }
+            let agent = match &task.assigned_agent {
+                Some(agent_id) => db.get_agent(agent_id)?.map(|a| a.name),
+                None => None,
+            };
+            completed_tasks.push(CompletedTaskEntry {
+                id: task
+                    .display_id
+                    .map(|id| format!("#{}", id))
+                    .unwrap_or_else(|| task.id[..8].to_string()),
+                title: task.title,
+                agent,
+                epic: task.epic_name,
+            });
+        }
+
+        let mut agent_progress = Vec::new();
+        for agent in db.list_agents()? {
+            let end = db.get_latest_progress(&agent.id)?;
+            let start = self.initial_progress.get(&agent.id);
+
+            let (task, start_progress, end_progress) = match (start, &end) {
+                (_, None) => continue,
+                // Same task: report how far it moved
+                (Some(&(task_id, start)), Some(end)) if task_id == end.task_id => {
+                    (end.task_id, start, end.progress)
+                }
+                // New task (or first report): count from zero
+                (_, Some(end)) => (end.task_id, 0, end.progress),
+            };
+
+            if start.is_some() && start_progress == end_progress {
+                continue;
+            }
+
+            agent_progress.push(AgentProgressDelta {
+                agent: agent
+                    .display_id
+                    .map(|id| format!("A{}", id))
+                    .unwrap_or_else(|| agent.id[..8].to_string()),
+                name: agent.name,
+                task: Some(format!("#{}", task)),
+                start_progress,
+                end_progress,
+            });
+        }
+
+        Ok(SessionSummary {
+            started_at: self.started_at,
+            ended_at: Utc::now(),
+            completed_tasks,
+            agent_progress,
+            notifications: notifications.to_vec(),
+            time: db.time_display()?,
+        })
+    }
+}
+
+impl SessionSummary {
+    /// Render the summary as Markdown
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let minutes = (self.ended_at - self.started_at).num_minutes();
+
+        out.push_str("# PRD Watch Session Summary\n\n");
+        out.push_str(&format!(
+            "- **Started**: {} {}\n- **Ended**: {} {}\n- **Duration**: {} min\n\n",
+            self.time.format_precise(self.started_at),
+            self.time.zone,
+            self.time.format_precise(self.ended_at),
+            self.time.zone,
+            minutes
+        ));
+
+        out.push_str(&format!(
+            "## Completed Tasks ({})\n\n",
+            self.completed_tasks.len()
+        ));
+        if self.completed_tasks.is_empty() {
+            out.push_str("_None_\n");
+        }
+        for task in &self.completed_tasks {
+            out.push_str(&format!("- {} {}", task.id, task.title));
+            if let Some(agent) = &task.agent {
+                out.push_str(&format!(" ({})", agent));
+            }
+            if let Some(epic) = &task.epic {
+                out.push_str(&format!(" [{}]", epic));
+            }
+            out.push('\n');
+        }
+
+        out.push_str("\n## Agent Progress\n\n");
+        if self.agent_progress.is_empty() {
+            out.push_str("_No progress reported_\n");
+        }
+        for delta in &self.agent_progress {
+            out.push_str(&format!(
+                "- {} {}: {} {}% → {}%\n",
+                delta.agent,
+                delta.name,
+                delta.task.as_deref().unwrap_or("-"),
+                delta.start_progress,
+                delta.end_progress
+            ));
+        }
+
+        out.push_str(&format!(
+            "\n## Notifications ({})\n\n",
+            self.notifications.len()
+        ));
+        if self.notifications.is_empty() {
+            out.push_str("_None_\n");
+        }
+        for n in &self.notifications {
+            out.push_str(&format!(
+                "- {} **{}** {}\n",
+                self.time.clock(n.timestamp),
+                n.title,
+                n.body.replace('\n', " ")
+            ));
+        }
+
+        out
+    }
+
+    /// Write the summary to a file (JSON for `.json`, Markdown otherwise)
+    pub fn write_to_file(&self, path: &Path) -> Result<()> {
+        let is_json = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.eq_ignore_ascii_case("json"))
+            .unwrap_or(false);
+
+        let content = if is_json {
+            serde_json::to_string_pretty(self)?
+        } else {
+            self.to_markdown()
+        };
+
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Priority;
+
+    #[test]
+    fn test_summary_tracks_session_changes() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        let agent = db.create_agent("agent-1".to_string())?;
+        let done = db.create_task("Already done".to_string(), None, Priority::Low, None, None)?;
+        let task = db.create_task(
+            "Build API".to_string(),
+            None,
+            Priority::High,
+            None,
+            Some("Backend".to_string()),
+        )?;
+        db.update_task_status(&done.id, TaskStatus::Completed, None)?;
+        db.assign_task(&task.id, &agent.id)?;
+        db.report_progress(&agent.id, task.display_id.unwrap(), 20, None)?;
+
+        let tracker = SessionTracker::start(&db)?;
+
+        db.report_progress(&agent.id, task.display_id.unwrap(), 100, None)?;
+        db.update_task_status(&task.id, TaskStatus::Completed, Some(&agent.id))?;
+
+        let summary = tracker.finish(&db, &[])?;
+        assert_eq!(summary.completed_tasks.len(), 1);
+        assert_eq!(summary.completed_tasks[0].id, "#2");
+        assert_eq!(
+            summary.completed_tasks[0].agent,
+            Some("agent-1".to_string())
+        );
+        assert_eq!(summary.agent_progress.len(), 1);
+        assert_eq!(summary.agent_progress[0].start_progress, 20);
+        assert_eq!(summary.agent_progress[0].end_progress, 100);
+
+        let md = summary.to_markdown();
+        assert!(md.contains("#2 Build API (agent-1) [Backend]"));
+        assert!(md.contains("20% → 100%"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_json_by_extension() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        let tracker = SessionTracker::start(&db)?;
+        let summary = tracker.finish(
+            &db,
+            &[SentNotification {
+                timestamp: Utc::now(),
+                title: "Milestone".to_string(),
+                body: "50% done".to_string(),
+            }],
+        )?;
+
+        let dir = tempfile::tempdir()?;
+        let json_path = dir.path().join("session.json");
+        summary.write_to_file(&json_path)?;
+        let value: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&json_path)?)?;
+        assert_eq!(value["notifications"][0]["title"], "Milestone");
+
+        let md_path = dir.path().join("session.md");
+        summary.write_to_file(&md_path)?;
+        assert!(std::fs::read_to_string(&md_path)?.starts_with("# PRD Watch Session Summary"));
+
+        Ok(())
+    }
+}
@@ -1,7 +1,12 @@
-use crate::db::{Agent, AgentProgress, AgentStatus, Database};
+use crate::blocked::{self, BlockedTask};
+use crate::colors::ColorScheme;
+use crate::db::{
+    Agent, AgentProgress, AgentStatus, Database, EpicForecast, Task, TaskEta, TimeDisplay,
+    DEFAULT_STALL_MINUTES,
+};
 use anyhow::Result;
 use chrono::{DateTime, Utc};
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::time::Duration;
 
 /// Represents an agent's display information for the dashboard
@@ -14,6 +19,7 @@ pub struct AgentDisplay {
     pub progress: u8,
     pub elapsed: Duration,
     pub last_active: DateTime<Utc>,
+    pub eta: Option<TaskEta>,
 }
 
 /// Represents an activity event in the dashboard log
@@ -23,6 +29,54 @@ pub struct ActivityEvent {
     pub message: String,
 }
 
+/// Tasks fetched per query for the task list
+pub const TASK_WINDOW_SIZE: usize = 100;
+
+/// The part of the task list that is loaded
+///
+/// Only one window of [`TASK_WINDOW_SIZE`] tasks around the selection is
+/// fetched, so databases with thousands of tasks stay cheap to refresh.
+/// Scrolling past the window loads the next one.
+#[derive(Debug, Clone, Default)]
+pub struct TaskWindow {
+    /// Loaded tasks, starting at `offset` in the full list
+    pub tasks: Vec<Task>,
+    pub offset: usize,
+    /// Tasks in the full list
+    pub total: usize,
+    /// Selected position in the full list
+    pub selected: usize,
+}
+
+impl TaskWindow {
+    /// Move the selection by `delta` rows, returning whether it left the
+    /// loaded window (and so needs a [`load`](Self::load))
+    pub fn move_selection(&mut self, delta: isize) -> bool {
+        let last = self.total.saturating_sub(1) as isize;
+        self.selected = (self.selected as isize + delta).clamp(0, last) as usize;
+        !self.is_loaded(self.selected)
+    }
+
+    fn is_loaded(&self, position: usize) -> bool {
+        position >= self.offset && position < self.offset + self.tasks.len()
+    }
+
+    /// Fetch the window holding the selection
+    pub fn load(&mut self, db: &Database) -> Result<()> {
+        self.total = db.count_tasks()?;
+        self.selected = self.selected.min(self.total.saturating_sub(1));
+        self.offset = self.selected / TASK_WINDOW_SIZE * TASK_WINDOW_SIZE;
+        self.tasks = db.list_tasks_window(self.offset, TASK_WINDOW_SIZE)?;
+        Ok(())
+    }
+
+    /// Index of the selection within `tasks`
+    pub fn selected_index(&self) -> Option<usize> {
+        self.is_loaded(self.selected)
+            .then(|| self.selected - self.offset)
+    }
+}
+
 /// Dashboard state containing all live data
 pub struct DashboardState {
     pub agents: Vec<AgentDisplay>,
@@ -31,6 +85,17 @@ pub struct DashboardState {
     pub last_refresh: DateTime<Utc>,
     pub completed_count: i32,
     pub total_count: i32,
+    /// Epics with a target date, for the schedule panel
+    pub epics: Vec<EpicForecast>,
+    /// Tasks blocked for longer than the blocked SLA, longest first
+    pub blocked_over_sla: Vec<BlockedTask>,
+    pub tasks: TaskWindow,
+    /// Replay clock and speed, when showing history instead of live data
+    pub replay: Option<String>,
+    /// How activity times are shown
+    pub time: TimeDisplay,
+    /// Configured epic and priority colors
+    pub colors: ColorScheme,
 }
 
 impl DashboardState {
@@ -43,32 +108,63 @@ impl DashboardState {
             last_refresh: Utc::now(),
             completed_count: 0,
             total_count: 0,
+            epics: Vec::new(),
+            blocked_over_sla: Vec::new(),
+            tasks: TaskWindow::default(),
+            replay: None,
+            time: TimeDisplay::default(),
+            colors: ColorScheme::default(),
         }
     }
 
     /// Refresh dashboard state from database
     pub fn refresh(&mut self, db: &Database) -> Result<()> {
         self.last_refresh = Utc::now();
+        self.time = db.time_display()?;
+        self.colors = ColorScheme::load(db)?;
 
         // Get all agents
         let agents = db.list_agents()?;
 
         // Get statistics
         let stats = db.get_stats()?;
-        self.completed_count = stats.completed;
+        self.completed_count = stats.done;
         self.total_count = stats.total;
         self.overall_progress = if stats.total > 0 {
-            (stats.completed as f64 / stats.total as f64) * 100.0
+            (stats.done as f64 / stats.total as f64) * 100.0
         } else {
             0.0
         };
 
+        self.epics = db
+            .epic_forecasts(self.last_refresh.date_naive())?
+            .into_iter()
+            .filter(|e| e.target.is_some())
+            .collect();
+        self.blocked_over_sla = blocked::over_sla(db, self.last_refresh)?;
+        self.tasks.load(db)?;
+
+        // Latest progress for every agent in a single query
+        let latest_progress: HashMap<String, AgentProgress> = db
+            .get_all_progress()?
+            .into_iter()
+            .map(|p| (p.agent_id.clone(), p))
+            .collect();
+        let stall_window = chrono::Duration::minutes(DEFAULT_STALL_MINUTES);
+
         // Build agent displays
         self.agents.clear();
         for agent in &agents {
             let elapsed = self.calculate_elapsed(&agent);
             let current_task = self.extract_task_id(&agent);
-            let progress = self.get_agent_progress(db, &agent.id)?;
+            let latest = latest_progress.get(&agent.id);
+            let progress = latest.map(|p| p.progress).unwrap_or(0);
+            let eta = match latest {
+                Some(p) if agent.status == AgentStatus::Working => {
+                    db.get_task_eta(p.task_id, stall_window)?
+                }
+                _ => None,
+            };
 
             self.agents.push(AgentDisplay {
                 id: agent.id.clone(),
@@ -78,6 +174,7 @@ impl DashboardState {
                 progress,
                 elapsed,
                 last_active: agent.last_active,
+                eta,
             });
         }
 
@@ -127,14 +224,6 @@ impl DashboardState {
         })
     }
 
-    /// Get agent's latest progress percentage
-    fn get_agent_progress(&self, db: &Database, agent_id: &str) -> Result<u8> {
-        match db.get_latest_progress(agent_id)? {
-            Some(progress) => Ok(progress.progress),
-            None => Ok(0),
-        }
-    }
-
     /// Update recent activity log
     fn update_activity(&mut self, agents: &[Agent]) {
         // Add new activities (e.g., status changes, completions)
@@ -212,3 +301,32 @@ impl Default for DashboardState {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Priority;
+
+    #[test]
+    fn test_task_window_loads_lazily() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        for i in 0..TASK_WINDOW_SIZE + 5 {
+            db.create_task(format!("Task {}", i), None, Priority::Low, None, None)?;
+        }
+
+        let mut window = TaskWindow::default();
+        window.load(&db)?;
+        assert_eq!(
+            (window.total, window.tasks.len()),
+            (TASK_WINDOW_SIZE + 5, TASK_WINDOW_SIZE)
+        );
+        assert!(!window.move_selection(10));
+        assert_eq!(window.selected_index(), Some(10));
+
+        assert!(window.move_selection(TASK_WINDOW_SIZE as isize));
+        window.load(&db)?;
+        assert_eq!((window.offset, window.tasks.len()), (TASK_WINDOW_SIZE, 5));
+        assert_eq!(window.selected, TASK_WINDOW_SIZE + 4);
+        Ok(())
+    }
+}
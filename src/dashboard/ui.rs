@@ -1,7 +1,15 @@
+use super::changes::ChangeDetector;
+use super::replay::Replay;
+use super::session::SessionTracker;
 use super::state::{AgentDisplay, DashboardState};
-use crate::db::{AgentStatus, Database, TaskStatus};
-use crate::notifications::{NotificationConfig, Notifier};
+use super::widgets::{AgentTable, ProgressBar};
+use crate::blocked;
+use crate::colors::Rgb;
+use crate::db::{Agent, AgentError, Database, Task};
+use crate::due::DeadlineTracker;
+use crate::notifications::{AgentAlertTracker, NotificationConfig, Notifier};
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
     execute,
@@ -12,15 +20,37 @@ use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Cell, Gauge, Paragraph, Row, Table},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
     Frame, Terminal,
 };
 use std::collections::HashSet;
 use std::io;
+use std::path::Path;
 use std::time::{Duration, Instant};
 
+/// Most epics listed in the schedule panel
+const MAX_EPIC_ROWS: usize = 5;
+
+/// Most agents listed before the agent table stops growing
+const MAX_AGENT_ROWS: usize = 10;
+
+/// Rows moved by PageUp/PageDown in the task list
+const TASK_PAGE_ROWS: isize = 20;
+
+/// How often to check for database changes between key presses
+const CHANGE_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How often the replay clock advances
+const REPLAY_TICK: Duration = Duration::from_millis(100);
+
 /// Run the live dashboard in the terminal
-pub fn run_dashboard(db_path: &str, refresh_interval: u64) -> Result<()> {
+///
+/// When `summary_path` is set, a session summary is written there on exit.
+pub fn run_dashboard(
+    db_path: &str,
+    refresh_interval: u64,
+    summary_path: Option<&Path>,
+) -> Result<()> {
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -32,37 +62,45 @@ pub fn run_dashboard(db_path: &str, refresh_interval: u64) -> Result<()> {
     let db = Database::new(db_path)?;
     let mut state = DashboardState::new();
     state.refresh(&db)?;
+    let session = SessionTracker::start(&db)?;
 
     // Initialize notification system
-    let notif_config = NotificationConfig::load().unwrap_or_default();
+    let notif_config = NotificationConfig::load_shared(&db).unwrap_or_default();
     let mut notifier = Notifier::new(notif_config);
 
-    // Track state for change detection
-    let mut completed_tasks: HashSet<String> = HashSet::new();
-    let mut blocked_agents: HashSet<String> = HashSet::new();
+    // Track state for change detection; completions are read incrementally
+    // from this watermark instead of re-listing every completed task
+    let mut completed_since = Utc::now();
+    let mut errors_since = completed_since;
+    let mut agent_alerts = AgentAlertTracker::new(&db)?;
+    let mut blocked_alerted: HashSet<String> = HashSet::new();
+    let mut deadlines = DeadlineTracker::new(&db, completed_since.date_naive())?;
     let mut last_overall_progress = 0.0;
 
-    // Initialize with current state
-    for task in db.list_tasks(Some(TaskStatus::Completed))? {
-        completed_tasks.insert(task.id.clone());
-    }
-
-    let refresh_duration = Duration::from_secs(refresh_interval);
+    // Refresh as soon as another process writes to the database, with a
+    // heartbeat as a fallback for missed events and elapsed-time updates
+    let mut changes = ChangeDetector::new(db_path, &db)?;
+    let heartbeat = Duration::from_secs(refresh_interval);
     let mut last_refresh = Instant::now();
+    let mut needs_redraw = true;
 
     // Main loop
-    let result = loop {
-        // Draw UI
-        terminal.draw(|f| ui(f, &state))?;
+    let result: Result<()> = loop {
+        // Draw UI only when something changed
+        if needs_redraw {
+            terminal.draw(|f| ui(f, &state))?;
+            needs_redraw = false;
+        }
 
         // Handle events
-        let timeout = refresh_duration
+        let timeout = heartbeat
             .checked_sub(last_refresh.elapsed())
-            .unwrap_or(Duration::from_secs(0));
+            .unwrap_or(Duration::from_secs(0))
+            .min(CHANGE_POLL_INTERVAL);
 
         if event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                match key.code {
+            match event::read()? {
+                Event::Key(key) => match key.code {
                     KeyCode::Char('q') => break Ok(()),
                     KeyCode::Char('r') => {
                         state.refresh(&db)?;
@@ -73,20 +111,39 @@ pub fn run_dashboard(db_path: &str, refresh_interval: u64) -> Result<()> {
                         state.add_activity("Manual sync triggered".to_string());
                     }
                     KeyCode::Char('h') | KeyCode::Char('?') => {
-                        state.add_activity("Help: q=quit, r=refresh, s=sync".to_string());
+                        state.add_activity(
+                            "Help: q=quit, r=refresh, s=sync, j/k/PgUp/PgDn/g/G=scroll tasks"
+                                .to_string(),
+                        );
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => scroll_tasks(&mut state, &db, 1)?,
+                    KeyCode::Up | KeyCode::Char('k') => scroll_tasks(&mut state, &db, -1)?,
+                    KeyCode::PageDown => scroll_tasks(&mut state, &db, TASK_PAGE_ROWS)?,
+                    KeyCode::PageUp => scroll_tasks(&mut state, &db, -TASK_PAGE_ROWS)?,
+                    KeyCode::Home | KeyCode::Char('g') => {
+                        let top = -(state.tasks.selected as isize);
+                        scroll_tasks(&mut state, &db, top)?
+                    }
+                    KeyCode::End | KeyCode::Char('G') => {
+                        let bottom = state.tasks.total as isize;
+                        scroll_tasks(&mut state, &db, bottom)?
                     }
                     _ => {}
-                }
+                },
+                Event::Resize(_, _) => {}
+                _ => continue,
             }
+            needs_redraw = true;
         }
 
-        // Auto refresh
-        if last_refresh.elapsed() >= refresh_duration {
+        // Refresh on database change or heartbeat
+        if changes.has_changed(&db)? || last_refresh.elapsed() >= heartbeat {
             state.refresh(&db)?;
             last_refresh = Instant::now();
+            needs_redraw = true;
 
             // Check for newly completed tasks
-            if let Ok(newly_completed) = detect_newly_completed_tasks(&db, &mut completed_tasks) {
+            if let Ok(newly_completed) = detect_newly_completed_tasks(&db, &mut completed_since) {
                 for (task, agent) in newly_completed {
                     if let Err(e) = notifier.notify_task_complete(&task, &agent) {
                         eprintln!("Failed to send completion notification: {}", e);
@@ -94,15 +151,69 @@ pub fn run_dashboard(db_path: &str, refresh_interval: u64) -> Result<()> {
                 }
             }
 
-            // Check for blocked agents
-            if let Ok(errors) = detect_agent_errors(&db, &mut blocked_agents) {
-                for (task, agent, error) in errors {
-                    if let Err(e) = notifier.notify_agent_error(&task, &agent, &error) {
+            // Surface failures agents reported since the last refresh
+            if let Ok(errors) = detect_new_agent_errors(&db, &mut errors_since) {
+                for (error, task, agent) in errors {
+                    if let Err(e) = notifier.notify_agent_error(&task, &agent, &error.message) {
+                        eprintln!("Failed to send error notification: {}", e);
+                    }
+                    state.add_activity(format!(
+                        "{} failed on #{} ({}): {}",
+                        agent.name,
+                        task.display_id.unwrap_or(0),
+                        error.kind.as_str(),
+                        error.message
+                    ));
+                }
+            }
+
+            // Check for agents going blocked or offline mid-task
+            if let Ok(alerts) = agent_alerts.detect(&db) {
+                for alert in alerts {
+                    if let Err(e) = notifier.notify_agent_alert(&alert) {
                         eprintln!("Failed to send error notification: {}", e);
                     }
                 }
             }
 
+            // Alert once per task when it stays blocked past the SLA
+            let now = state.last_refresh;
+            for item in state.blocked_over_sla.clone() {
+                if !blocked_alerted.insert(item.task.id.clone()) {
+                    continue;
+                }
+                let blocked_for = blocked::format_blocked_for(item.duration(now));
+                if let Err(e) = notifier.notify_blocked_too_long(
+                    &item.task,
+                    &blocked_for,
+                    item.reason.as_deref(),
+                ) {
+                    eprintln!("Failed to send blocked notification: {}", e);
+                }
+                state.add_activity(format!(
+                    "#{} blocked for {}: {}",
+                    item.task.display_id.unwrap_or(0),
+                    blocked_for,
+                    item.reason.as_deref().unwrap_or("no reason recorded")
+                ));
+            }
+            blocked_alerted.retain(|id| state.blocked_over_sla.iter().any(|b| &b.task.id == id));
+
+            // Alert when a task goes past its due date
+            if let Ok(overdue) = deadlines.detect(&db, Utc::now().date_naive()) {
+                for due in overdue {
+                    if let Err(e) = notifier.notify_overdue(&due) {
+                        eprintln!("Failed to send overdue notification: {}", e);
+                    }
+                    state.add_activity(format!(
+                        "#{} is overdue (due {}): {}",
+                        due.task.display_id.unwrap_or(0),
+                        due.due,
+                        due.task.title
+                    ));
+                }
+            }
+
             // Check milestones
             let current_progress = state.overall_progress;
             if current_progress != last_overall_progress {
@@ -126,6 +237,102 @@ pub fn run_dashboard(db_path: &str, refresh_interval: u64) -> Result<()> {
     )?;
     terminal.show_cursor()?;
 
+    result?;
+
+    if let Some(path) = summary_path {
+        let summary = session.finish(&db, notifier.sent())?;
+        summary.write_to_file(path)?;
+        println!("✓ Session summary written to {}", path.display());
+    }
+
+    Ok(())
+}
+
+/// Play back task and agent state from the activity log
+///
+/// Starts at `from` (or the first recorded event) and advances `speed`
+/// seconds of history per second until the end of the log.
+pub fn run_replay(db_path: &str, from: Option<DateTime<Utc>>, speed: f64) -> Result<()> {
+    let db = Database::new(db_path)?;
+    let mut replay = Replay::load(&db, from)?;
+    let mut speed = speed.max(1.0);
+    let mut paused = false;
+
+    // Setup terminal
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    let mut state = replay.snapshot(0);
+    let mut last_tick = Instant::now();
+    let mut needs_redraw = true;
+
+    let result: Result<()> = loop {
+        if needs_redraw {
+            let status = if replay.finished() {
+                "finished"
+            } else if paused {
+                "paused"
+            } else {
+                "playing"
+            };
+            state.replay = Some(format!(
+                "{} - {}x, {}",
+                state.time.format(replay.clock),
+                speed,
+                status
+            ));
+            terminal.draw(|f| ui(f, &state))?;
+            needs_redraw = false;
+        }
+
+        if event::poll(REPLAY_TICK)? {
+            match event::read()? {
+                Event::Key(key) => match key.code {
+                    KeyCode::Char('q') => break Ok(()),
+                    KeyCode::Char(' ') => paused = !paused,
+                    KeyCode::Char('+') | KeyCode::Char('=') => speed *= 2.0,
+                    KeyCode::Char('-') => speed = (speed / 2.0).max(1.0),
+                    KeyCode::Char('n') | KeyCode::Right => {
+                        replay.step();
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        state.tasks.move_selection(1);
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        state.tasks.move_selection(-1);
+                    }
+                    _ => {}
+                },
+                Event::Resize(_, _) => {}
+                _ => continue,
+            }
+            state = replay.snapshot(state.tasks.selected);
+            needs_redraw = true;
+        }
+
+        let elapsed = last_tick.elapsed();
+        last_tick = Instant::now();
+        if !paused && !replay.finished() {
+            let history =
+                chrono::Duration::milliseconds((elapsed.as_millis() as f64 * speed) as i64);
+            replay.advance_by(history);
+            state = replay.snapshot(state.tasks.selected);
+            needs_redraw = true;
+        }
+    };
+
+    // Restore terminal
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+    terminal.show_cursor()?;
+
     result
 }
 
@@ -133,14 +340,24 @@ pub fn run_dashboard(db_path: &str, refresh_interval: u64) -> Result<()> {
 fn ui(f: &mut Frame, state: &DashboardState) {
     let size = f.size();
 
+    // Epic schedule panel only takes space when targets are set
+    let epic_rows = match state.epics.len() {
+        0 => 0,
+        n => n.min(MAX_EPIC_ROWS) as u16 + 3,
+    };
+
+    let agent_rows = state.agents.len().clamp(1, MAX_AGENT_ROWS) as u16 + 3;
+
     // Create layout
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(5), // Header with progress
-            Constraint::Min(10),   // Agent table
-            Constraint::Length(8), // Activity log
-            Constraint::Length(1), // Footer
+            Constraint::Length(5),          // Header with progress
+            Constraint::Length(agent_rows), // Agent table
+            Constraint::Min(6),             // Task list
+            Constraint::Length(epic_rows),  // Epic schedule
+            Constraint::Length(8),          // Activity log
+            Constraint::Length(1),          // Footer
         ])
         .split(size);
 
@@ -150,99 +367,171 @@ fn ui(f: &mut Frame, state: &DashboardState) {
     // Render agent table
     render_agent_table(f, state, chunks[1]);
 
+    // Render the visible part of the task list
+    render_task_list(f, state, chunks[2]);
+
+    // Render epic targets vs projections
+    if epic_rows > 0 {
+        render_epic_schedule(f, state, chunks[3]);
+    }
+
     // Render activity log
-    render_activity_log(f, state, chunks[2]);
+    render_activity_log(f, state, chunks[4]);
 
     // Render footer
-    render_footer(f, chunks[3]);
+    render_footer(f, state, chunks[5]);
 }
 
 /// Render header with overall progress
 fn render_header(f: &mut Frame, state: &DashboardState, area: Rect) {
+    let title = match &state.replay {
+        Some(replay) => format!(" PRD Tool - Replay {} ", replay),
+        None => " PRD Tool - Live Agent Dashboard ".to_string(),
+    };
     let block = Block::default()
-        .title(" PRD Tool - Live Agent Dashboard ")
+        .title(title)
         .title_alignment(Alignment::Center)
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Cyan));
 
-    let inner = block.inner(area);
-    f.render_widget(block, area);
-
-    // Layout for header content
-    let header_chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Length(1), Constraint::Length(1)])
-        .split(inner);
-
-    // Progress info
-    let progress_text = format!(
-        "Overall Progress: {}/{} tasks ({:.1}%)",
-        state.completed_count, state.total_count, state.overall_progress
-    );
-    let progress_para = Paragraph::new(progress_text)
-        .style(Style::default().fg(Color::White))
-        .alignment(Alignment::Center);
-    f.render_widget(progress_para, header_chunks[0]);
-
-    // Progress bar
-    let gauge = Gauge::default()
-        .block(Block::default())
-        .gauge_style(
-            Style::default()
-                .fg(Color::Green)
-                .bg(Color::Black)
-                .add_modifier(Modifier::BOLD),
-        )
-        .ratio(state.overall_progress / 100.0);
-    f.render_widget(gauge, header_chunks[1]);
+    let progress = ProgressBar::new(state.completed_count, state.total_count)
+        .blocked_over_sla(state.blocked_over_sla.len())
+        .block(block);
+    f.render_widget(progress, area);
 }
 
 /// Render agent table
 fn render_agent_table(f: &mut Frame, state: &DashboardState, area: Rect) {
+    f.render_widget(AgentTable::new(&state.agents), area);
+}
+
+/// Render the rows of the task list that fit in `area`, keeping the
+/// selection in view
+fn render_task_list(f: &mut Frame, state: &DashboardState, area: Rect) {
+    let window = &state.tasks;
+    let title = if window.total == 0 {
+        " Tasks ".to_string()
+    } else {
+        format!(" Tasks ({}/{}) ", window.selected + 1, window.total)
+    };
     let block = Block::default()
-        .title(" Agents ")
+        .title(title)
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Cyan));
 
+    let bold = Style::default().add_modifier(Modifier::BOLD);
     let header = Row::new(vec![
-        Cell::from("Agent").style(Style::default().add_modifier(Modifier::BOLD)),
-        Cell::from("Status").style(Style::default().add_modifier(Modifier::BOLD)),
-        Cell::from("Task").style(Style::default().add_modifier(Modifier::BOLD)),
-        Cell::from("Progress").style(Style::default().add_modifier(Modifier::BOLD)),
-        Cell::from("Elapsed").style(Style::default().add_modifier(Modifier::BOLD)),
-    ])
-    .height(1);
-
-    let rows: Vec<Row> = state
-        .agents
+        Cell::from("ID").style(bold),
+        Cell::from("Status").style(bold),
+        Cell::from("Priority").style(bold),
+        Cell::from("Epic").style(bold),
+        Cell::from("Title").style(bold),
+    ]);
+
+    // Borders and header take three lines
+    let visible = area.height.saturating_sub(3) as usize;
+    let selected = window.selected_index();
+    let start = selected
+        .unwrap_or(0)
+        .saturating_sub(visible.saturating_sub(1));
+    let rows: Vec<Row> = window
+        .tasks
         .iter()
-        .map(|agent| {
-            let status_style = get_status_style(&agent.status);
-            let status_text = format_status(&agent.status);
-
-            let task_text = agent
-                .current_task
-                .map(|id| format!("#{}", id))
-                .unwrap_or_else(|| "-".to_string());
-
-            let progress_text = if agent.status == AgentStatus::Working {
-                format!("{}%", agent.progress)
+        .enumerate()
+        .skip(start)
+        .take(visible)
+        .map(|(index, task)| {
+            let style = if Some(index) == selected {
+                Style::default().add_modifier(Modifier::REVERSED)
             } else {
-                "-".to_string()
+                Style::default()
             };
+            Row::new(vec![
+                Cell::from(
+                    task.display_id
+                        .map(|id| format!("#{}", id))
+                        .unwrap_or_default(),
+                ),
+                Cell::from(task.status.as_str().to_string()),
+                Cell::from(task.priority.as_str().to_string())
+                    .style(color_style(state.colors.priority(task.priority.as_str()))),
+                Cell::from(task.epic_name.clone().unwrap_or_default()).style(color_style(
+                    task.epic_name.as_deref().and_then(|e| state.colors.epic(e)),
+                )),
+                Cell::from(task.title.clone()),
+            ])
+            .style(style)
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(7),
+            Constraint::Length(12),
+            Constraint::Length(9),
+            Constraint::Length(14),
+            Constraint::Min(10),
+        ],
+    )
+    .header(header)
+    .block(block)
+    .column_spacing(1);
 
-            let elapsed_text = if agent.status == AgentStatus::Working {
-                DashboardState::format_elapsed(agent.elapsed)
+    f.render_widget(table, area);
+}
+
+/// Foreground in a configured color, or the default style
+fn color_style(color: Option<Rgb>) -> Style {
+    match color {
+        Some(Rgb(r, g, b)) => Style::default().fg(Color::Rgb(r, g, b)),
+        None => Style::default(),
+    }
+}
+
+/// Render epic target dates against projected completion
+fn render_epic_schedule(f: &mut Frame, state: &DashboardState, area: Rect) {
+    let block = Block::default()
+        .title(" Epic Schedule ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let header = Row::new(vec![
+        Cell::from("Epic").style(bold),
+        Cell::from("Progress").style(bold),
+        Cell::from("Target").style(bold),
+        Cell::from("Projected").style(bold),
+        Cell::from("Variance").style(bold),
+    ]);
+
+    let today = state.last_refresh.date_naive();
+    let rows: Vec<Row> = state
+        .epics
+        .iter()
+        .take(MAX_EPIC_ROWS)
+        .map(|epic| {
+            let style = if epic.is_slipping(today) {
+                Style::default().fg(Color::Red)
             } else {
-                "-".to_string()
+                Style::default().fg(Color::Green)
+            };
+            let projected = match epic.projected {
+                _ if epic.is_done() => "done".to_string(),
+                Some(date) => date.to_string(),
+                None => "-".to_string(),
             };
+            let variance = epic
+                .variance_days()
+                .map(|days| format!("{:+}d", days))
+                .unwrap_or_else(|| "-".to_string());
 
             Row::new(vec![
-                Cell::from(agent.name.clone()),
-                Cell::from(status_text).style(status_style),
-                Cell::from(task_text),
-                Cell::from(progress_text),
-                Cell::from(elapsed_text),
+                Cell::from(epic.epic.clone()).style(color_style(state.colors.epic(&epic.epic))),
+                Cell::from(format!("{}/{}", epic.completed, epic.total)),
+                Cell::from(epic.target.map(|d| d.to_string()).unwrap_or_default()),
+                Cell::from(projected),
+                Cell::from(variance).style(style),
             ])
         })
         .collect();
@@ -250,11 +539,11 @@ fn render_agent_table(f: &mut Frame, state: &DashboardState, area: Rect) {
     let table = Table::new(
         rows,
         [
-            Constraint::Percentage(25),
-            Constraint::Percentage(20),
-            Constraint::Percentage(15),
-            Constraint::Percentage(20),
-            Constraint::Percentage(20),
+            Constraint::Percentage(32),
+            Constraint::Percentage(14),
+            Constraint::Percentage(18),
+            Constraint::Percentage(18),
+            Constraint::Percentage(18),
         ],
     )
     .header(header)
@@ -279,7 +568,7 @@ fn render_activity_log(f: &mut Frame, state: &DashboardState, area: Rect) {
         .iter()
         .take(6)
         .map(|event| {
-            let time_str = event.timestamp.format("%H:%M:%S").to_string();
+            let time_str = state.time.clock(event.timestamp);
             Line::from(vec![
                 Span::styled(time_str, Style::default().fg(Color::DarkGray)),
                 Span::raw(" - "),
@@ -293,58 +582,35 @@ fn render_activity_log(f: &mut Frame, state: &DashboardState, area: Rect) {
 }
 
 /// Render footer with keyboard shortcuts
-fn render_footer(f: &mut Frame, area: Rect) {
-    let footer_text = " [q] Quit  [r] Refresh  [s] Sync  [h] Help ";
+fn render_footer(f: &mut Frame, state: &DashboardState, area: Rect) {
+    let footer_text = if state.replay.is_some() {
+        " [q] Quit  [space] Pause  [+/-] Speed  [n] Next event  [j/k] Scroll tasks "
+    } else {
+        " [q] Quit  [r] Refresh  [s] Sync  [j/k] Scroll tasks  [h] Help "
+    };
     let footer = Paragraph::new(footer_text)
         .style(Style::default().fg(Color::DarkGray))
         .alignment(Alignment::Center);
     f.render_widget(footer, area);
 }
 
-/// Get color style for agent status
-fn get_status_style(status: &AgentStatus) -> Style {
-    match status {
-        AgentStatus::Working => Style::default()
-            .fg(Color::Green)
-            .add_modifier(Modifier::BOLD),
-        AgentStatus::Idle => Style::default().fg(Color::Gray),
-        AgentStatus::Blocked => Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
-        AgentStatus::Offline => Style::default().fg(Color::DarkGray),
-    }
-}
-
-/// Format status as display text
-fn format_status(status: &AgentStatus) -> String {
-    match status {
-        AgentStatus::Working => "● Working",
-        AgentStatus::Idle => "○ Idle",
-        AgentStatus::Blocked => "■ Blocked",
-        AgentStatus::Offline => "✕ Offline",
-    }
-    .to_string()
-}
-
-/// Detect newly completed tasks since last check
+/// Tasks completed after `since`, with the agents that did them, moving
+/// `since` past them
 fn detect_newly_completed_tasks(
     db: &Database,
-    completed_tasks: &mut HashSet<String>,
-) -> Result<Vec<(crate::db::Task, crate::db::Agent)>> {
+    since: &mut DateTime<Utc>,
+) -> Result<Vec<(Task, Agent)>> {
     let mut newly_completed = Vec::new();
 
-    // Get all currently completed tasks
-    let current_completed = db.list_tasks(Some(TaskStatus::Completed))?;
-
-    for task in current_completed {
-        // If this task wasn't in our set, it's newly completed
-        if !completed_tasks.contains(&task.id) {
-            // Mark as seen
-            completed_tasks.insert(task.id.clone());
+    for task in db.tasks_completed_since(*since)? {
+        if let Some(completed_at) = task.completed_at {
+            *since = (*since).max(completed_at);
+        }
 
-            // Try to get the agent who completed it
-            if let Some(agent_id) = &task.assigned_agent {
-                if let Ok(Some(agent)) = db.get_agent(agent_id) {
-                    newly_completed.push((task, agent));
-                }
+        // Try to get the agent who completed it
+        if let Some(agent_id) = &task.assigned_agent {
+            if let Ok(Some(agent)) = db.get_agent(agent_id) {
+                newly_completed.push((task, agent));
             }
         }
     }
@@ -352,41 +618,35 @@ fn detect_newly_completed_tasks(
     Ok(newly_completed)
 }
 
-/// Detect agents with errors or blocked status
-fn detect_agent_errors(
+/// Errors reported after `since`, with their task and agent, moving `since`
+/// past them
+fn detect_new_agent_errors(
     db: &Database,
-    blocked_agents: &mut HashSet<String>,
-) -> Result<Vec<(crate::db::Task, crate::db::Agent, String)>> {
+    since: &mut DateTime<Utc>,
+) -> Result<Vec<(AgentError, Task, Agent)>> {
     let mut errors = Vec::new();
 
-    // Get all agents
-    let agents = db.list_agents()?;
-
-    for agent in agents {
-        // Check if agent is blocked
-        if agent.status == AgentStatus::Blocked {
-            // If this agent wasn't blocked before, it's a new error
-            if !blocked_agents.contains(&agent.id) {
-                blocked_agents.insert(agent.id.clone());
-
-                // Try to get the task they're blocked on
-                if let Some(task_id) = &agent.current_task_id {
-                    if let Ok(Some(task)) = db.get_task(task_id) {
-                        let error_msg =
-                            format!("Agent blocked on task #{}", task.display_id.unwrap_or(0));
-                        errors.push((task, agent.clone(), error_msg));
-                    }
-                }
-            }
-        } else {
-            // Agent is no longer blocked, remove from tracking
-            blocked_agents.remove(&agent.id);
+    for error in db.agent_errors_since(*since)? {
+        *since = (*since).max(error.created_at);
+        if let (Some(task), Some(agent)) =
+            (db.get_task(&error.task_id)?, db.get_agent(&error.agent_id)?)
+        {
+            errors.push((error, task, agent));
         }
     }
 
     Ok(errors)
 }
 
+/// Move the task list selection, loading another window of tasks when it
+/// leaves the loaded one
+fn scroll_tasks(state: &mut DashboardState, db: &Database, delta: isize) -> Result<()> {
+    if state.tasks.move_selection(delta) {
+        state.tasks.load(db)?;
+    }
+    Ok(())
+}
+
 /// Check and notify for milestone achievements
 fn check_and_notify_milestones(
     notifier: &mut Notifier,
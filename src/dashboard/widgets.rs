@@ -0,0 +1,378 @@
+//! Dashboard panels as standalone ratatui widgets
+//!
+//! The live dashboard renders through these, and other TUIs can embed the
+//! same panels by handing them data from [`DashboardState`] or the database:
+//!
+//! ```no_run
+//! # use prd_tool::dashboard::{widgets::AgentTable, DashboardState};
+//! # fn draw(f: &mut ratatui::Frame, state: &DashboardState, area: ratatui::layout::Rect) {
+//! f.render_widget(AgentTable::new(&state.agents), area);
+//! # }
+//! ```
+//!
+//! [`DashboardState`]: super::DashboardState
+
+use super::state::{AgentDisplay, DashboardState};
+use crate::db::{AgentStatus, TaskStats};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Gauge, Paragraph, Row, Table, Widget},
+};
+use std::time::Duration;
+
+/// Bordered block with a title, in the dashboard's style
+pub fn panel(title: &str) -> Block<'_> {
+    Block::default()
+        .title(format!(" {} ", title))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+}
+
+/// Table of agents with their status, task, progress, elapsed time and ETA
+pub struct AgentTable<'a> {
+    agents: &'a [AgentDisplay],
+    block: Option<Block<'a>>,
+}
+
+impl<'a> AgentTable<'a> {
+    /// Table of `agents`, framed by an "Agents" [`panel`]
+    pub fn new(agents: &'a [AgentDisplay]) -> Self {
+        Self {
+            agents,
+            block: Some(panel("Agents")),
+        }
+    }
+
+    /// Frame the table with `block`, or draw it bare with None
+    pub fn block(mut self, block: Option<Block<'a>>) -> Self {
+        self.block = block;
+        self
+    }
+}
+
+impl Widget for AgentTable<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let header = Row::new(vec![
+            Cell::from("Agent").style(bold),
+            Cell::from("Status").style(bold),
+            Cell::from("Task").style(bold),
+            Cell::from("Progress").style(bold),
+            Cell::from("Elapsed").style(bold),
+            Cell::from("ETA").style(bold),
+        ])
+        .height(1);
+
+        let rows: Vec<Row> = self
+            .agents
+            .iter()
+            .map(|agent| {
+                let working = agent.status == AgentStatus::Working;
+                let task_text = agent
+                    .current_task
+                    .map(|id| format!("#{}", id))
+                    .unwrap_or_else(|| "-".to_string());
+                let progress_text = if working {
+                    format!("{}%", agent.progress)
+                } else {
+                    "-".to_string()
+                };
+                let elapsed_text = if working {
+                    DashboardState::format_elapsed(agent.elapsed)
+                } else {
+                    "-".to_string()
+                };
+
+                let (eta_text, eta_style) = match &agent.eta {
+                    Some(eta) if eta.stalled => {
+                        ("stalled".to_string(), Style::default().fg(Color::Red))
+                    }
+                    Some(eta) => match eta.eta {
+                        Some(at) => {
+                            let remaining = (at - chrono::Utc::now()).num_seconds().max(0) as u64;
+                            (
+                                DashboardState::format_elapsed(Duration::from_secs(remaining)),
+                                Style::default(),
+                            )
+                        }
+                        None => ("-".to_string(), Style::default()),
+                    },
+                    None => ("-".to_string(), Style::default()),
+                };
+
+                Row::new(vec![
+                    Cell::from(agent.name.clone()),
+                    Cell::from(format_status(&agent.status)).style(status_style(&agent.status)),
+                    Cell::from(task_text),
+                    Cell::from(progress_text),
+                    Cell::from(elapsed_text),
+                    Cell::from(eta_text).style(eta_style),
+                ])
+            })
+            .collect();
+
+        let mut table = Table::new(
+            rows,
+            [
+                Constraint::Percentage(22),
+                Constraint::Percentage(18),
+                Constraint::Percentage(12),
+                Constraint::Percentage(16),
+                Constraint::Percentage(16),
+                Constraint::Percentage(16),
+            ],
+        )
+        .header(header)
+        .column_spacing(1);
+        if let Some(block) = self.block {
+            table = table.block(block);
+        }
+        Widget::render(table, area, buf);
+    }
+}
+
+/// Completed/total summary line over a progress gauge, two lines tall
+pub struct ProgressBar<'a> {
+    completed: i32,
+    total: i32,
+    blocked_over_sla: usize,
+    block: Option<Block<'a>>,
+}
+
+impl<'a> ProgressBar<'a> {
+    /// Progress of `completed` out of `total` tasks
+    pub fn new(completed: i32, total: i32) -> Self {
+        Self {
+            completed,
+            total,
+            blocked_over_sla: 0,
+            block: None,
+        }
+    }
+
+    /// Flag this many tasks blocked past their SLA next to the summary
+    pub fn blocked_over_sla(mut self, count: usize) -> Self {
+        self.blocked_over_sla = count;
+        self
+    }
+
+    /// Frame the bar with `block`
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    /// Completed share, 0.0 to 1.0 (0.0 when there are no tasks)
+    pub fn ratio(&self) -> f64 {
+        if self.total > 0 {
+            (self.completed as f64 / self.total as f64).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+impl Widget for ProgressBar<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let inner = match &self.block {
+            Some(block) => {
+                let inner = block.inner(area);
+                block.clone().render(area, buf);
+                inner
+            }
+            None => area,
+        };
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Length(1)])
+            .split(inner);
+
+        let mut line = vec![Span::styled(
+            format!(
+                "Overall Progress: {}/{} tasks ({:.1}%)",
+                self.completed,
+                self.total,
+                self.ratio() * 100.0
+            ),
+            Style::default().fg(Color::White),
+        )];
+        if self.blocked_over_sla > 0 {
+            line.push(Span::raw("  "));
+            line.push(Span::styled(
+                format!(" ⏱ {} blocked past SLA ", self.blocked_over_sla),
+                Style::default()
+                    .fg(Color::White)
+                    .bg(Color::Red)
+                    .add_modifier(Modifier::BOLD),
+            ));
+        }
+        Paragraph::new(Line::from(line))
+            .alignment(Alignment::Center)
+            .render(chunks[0], buf);
+
+        Gauge::default()
+            .gauge_style(
+                Style::default()
+                    .fg(Color::Green)
+                    .bg(Color::Black)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .ratio(self.ratio())
+            .render(chunks[1], buf);
+    }
+}
+
+/// Task counts per status, one line each, custom statuses last
+pub struct StatsPanel<'a> {
+    stats: &'a TaskStats,
+    block: Option<Block<'a>>,
+}
+
+impl<'a> StatsPanel<'a> {
+    /// Panel for `stats` (from `Database::get_stats`), framed by a "Stats" [`panel`]
+    pub fn new(stats: &'a TaskStats) -> Self {
+        Self {
+            stats,
+            block: Some(panel("Stats")),
+        }
+    }
+
+    /// Frame the panel with `block`, or draw it bare with None
+    pub fn block(mut self, block: Option<Block<'a>>) -> Self {
+        self.block = block;
+        self
+    }
+}
+
+impl Widget for StatsPanel<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let stats = self.stats;
+        let mut counts = vec![
+            ("Pending", stats.pending, Color::Yellow),
+            ("In progress", stats.in_progress, Color::Blue),
+            ("Blocked", stats.blocked, Color::Red),
+            ("Review", stats.review, Color::Magenta),
+            ("Completed", stats.completed, Color::Green),
+            ("Failed", stats.failed, Color::LightRed),
+            ("Cancelled", stats.cancelled, Color::DarkGray),
+        ];
+        counts.extend(
+            stats
+                .custom
+                .iter()
+                .map(|(status, count)| (status.as_str(), *count, Color::Cyan)),
+        );
+
+        let mut lines: Vec<Line> = counts
+            .into_iter()
+            .map(|(label, count, color)| {
+                Line::from(vec![
+                    Span::styled(format!("{:<12}", label), Style::default().fg(color)),
+                    Span::raw(format!("{:>5}", count)),
+                ])
+            })
+            .collect();
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{:<12}", "Total"),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("{:>5}", stats.total),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+        ]));
+
+        let mut paragraph = Paragraph::new(lines);
+        if let Some(block) = self.block {
+            paragraph = paragraph.block(block);
+        }
+        paragraph.render(area, buf);
+    }
+}
+
+/// Color for an agent status
+pub fn status_style(status: &AgentStatus) -> Style {
+    match status {
+        AgentStatus::Working => Style::default()
+            .fg(Color::Green)
+            .add_modifier(Modifier::BOLD),
+        AgentStatus::Idle => Style::default().fg(Color::Gray),
+        AgentStatus::Blocked => Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        AgentStatus::Offline => Style::default().fg(Color::DarkGray),
+    }
+}
+
+/// Agent status with its marker, e.g. "● Working"
+pub fn format_status(status: &AgentStatus) -> String {
+    match status {
+        AgentStatus::Working => "● Working",
+        AgentStatus::Idle => "○ Idle",
+        AgentStatus::Blocked => "■ Blocked",
+        AgentStatus::Offline => "✕ Offline",
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(buf: &Buffer) -> String {
+        let width = buf.area.width as usize;
+        buf.content
+            .chunks(width)
+            .map(|row| row.iter().map(|c| c.symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_widgets_render_into_buffer() {
+        let agents = vec![AgentDisplay {
+            id: "a1".to_string(),
+            name: "builder".to_string(),
+            status: AgentStatus::Working,
+            current_task: Some(12),
+            progress: 40,
+            elapsed: Duration::from_secs(90),
+            last_active: chrono::Utc::now(),
+            eta: None,
+        }];
+        let mut buf = Buffer::empty(Rect::new(0, 0, 60, 4));
+        AgentTable::new(&agents).render(buf.area, &mut buf);
+        let table = text(&buf);
+        assert!(table.contains("Agents"));
+        assert!(table.contains("builder"));
+        assert!(table.contains("#12"));
+        assert!(table.contains("40%"));
+
+        let bar = ProgressBar::new(3, 4).blocked_over_sla(2);
+        assert_eq!(bar.ratio(), 0.75);
+        assert_eq!(ProgressBar::new(0, 0).ratio(), 0.0);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 70, 2));
+        bar.render(buf.area, &mut buf);
+        let bar = text(&buf);
+        assert!(bar.contains("3/4 tasks (75.0%)"));
+        assert!(bar.contains("2 blocked past SLA"));
+
+        let mut stats = TaskStats {
+            total: 5,
+            pending: 2,
+            completed: 3,
+            ..Default::default()
+        };
+        stats.custom.insert("deployed".to_string(), 1);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 20, 10));
+        StatsPanel::new(&stats)
+            .block(None)
+            .render(buf.area, &mut buf);
+        let panel = text(&buf);
+        assert!(panel.contains("Pending         2"));
+        assert!(panel.contains("deployed        1"));
+        assert!(panel.contains("Total           5"));
+    }
+}
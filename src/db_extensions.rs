@@ -1,3 +1,4 @@
+use crate::db::{priority_rank_sql, PriorityLevels, COMPLETED_STATUSES_SQL};
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use rusqlite::{params, Connection};
@@ -22,6 +23,35 @@ pub struct AcceptanceCriterion {
     pub completed_at: Option<DateTime<Utc>>,
 }
 
+/// Epic `epic` can't start until `depends_on` is `threshold`% complete
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EpicDependency {
+    pub epic: String,
+    pub depends_on: String,
+    pub threshold: u8,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An epic dependency together with the prerequisite's current progress
+#[derive(Debug, Clone, Serialize)]
+pub struct EpicGate {
+    pub epic: String,
+    pub depends_on: String,
+    pub threshold: u8,
+    /// Percent of the prerequisite epic's tasks completed
+    pub progress: u8,
+    pub met: bool,
+}
+
+/// Epics with at least one prerequisite below its threshold
+///
+/// A prerequisite epic with no tasks doesn't hold anything back. Completed
+/// tasks are the ones in `COMPLETED_STATUSES_SQL`.
+pub const GATED_EPICS_SQL: &str = "SELECT d.epic_name FROM epic_dependencies d
+     WHERE (SELECT 100.0 * SUM(status IN (SELECT 'completed' UNION ALL
+                SELECT name FROM custom_statuses WHERE bucket = 'done')) / COUNT(*)
+            FROM tasks WHERE epic_name = d.depends_on_epic AND deleted_at IS NULL) < d.threshold";
+
 pub trait DependencyOps {
     fn add_dependency(&self, task_id: i32, depends_on_id: i32, dep_type: &str) -> Result<()>;
     fn get_dependencies(&self, task_id: i32) -> Result<Vec<i32>>;
@@ -30,6 +60,13 @@ pub trait DependencyOps {
     fn get_ready_tasks(&self) -> Result<Vec<i32>>;
 }
 
+pub trait EpicDependencyOps {
+    fn add_epic_dependency(&self, epic: &str, depends_on: &str, threshold: u8) -> Result<()>;
+    fn remove_epic_dependency(&self, epic: &str, depends_on: &str) -> Result<bool>;
+    fn list_epic_dependencies(&self) -> Result<Vec<EpicDependency>>;
+    fn epic_gates(&self) -> Result<Vec<EpicGate>>;
+}
+
 pub trait AcceptanceCriteriaOps {
     fn add_criterion(&self, task_id: i32, criterion: String) -> Result<i32>;
     fn list_criteria(&self, task_id: i32) -> Result<Vec<AcceptanceCriterion>>;
@@ -99,26 +136,140 @@ impl DependencyOps for Connection {
     }
 
     fn get_ready_tasks(&self) -> Result<Vec<i32>> {
-        // Tasks with all dependencies completed or no dependencies
-        let mut stmt = self.prepare(
+        // Tasks with all dependencies completed or no dependencies, outside
+        // gated epics and not deferred to a later start. The set of tasks still waiting on something is
+        // computed once rather than per row.
+        let mut stmt = self.prepare(&format!(
             "SELECT t.display_id FROM tasks t
-             WHERE t.status != 'completed' AND t.status != 'cancelled'
-             AND NOT EXISTS (
-                 SELECT 1 FROM task_dependencies td
+             WHERE t.status NOT IN ('failed', 'cancelled') AND t.status NOT IN ({completed})
+             AND t.deleted_at IS NULL
+             AND t.display_id NOT IN (
+                 SELECT td.task_display_id FROM task_dependencies td
                  JOIN tasks dep ON dep.display_id = td.depends_on_display_id
-                 WHERE td.task_display_id = t.display_id
-                 AND dep.status != 'completed'
+                 WHERE dep.status NOT IN ({completed}) AND dep.deleted_at IS NULL
              )
-             ORDER BY t.priority DESC, t.created_at ASC",
-        )?;
+             AND (t.epic_name IS NULL OR t.epic_name NOT IN ({}))
+             AND (t.start_after IS NULL OR t.start_after <= ?2)
+             ORDER BY {}, t.created_at ASC",
+            GATED_EPICS_SQL,
+            priority_rank_sql("t.priority", "?1"),
+            completed = COMPLETED_STATUSES_SQL
+        ))?;
 
+        let levels = PriorityLevels::load(self)?.sql_list();
         let ready = stmt
-            .query_map([], |row| row.get(0))?
+            .query_map(params![levels, Utc::now().to_rfc3339()], |row| row.get(0))?
             .collect::<Result<Vec<_>, _>>()?;
         Ok(ready)
     }
 }
 
+impl EpicDependencyOps for Connection {
+    fn add_epic_dependency(&self, epic: &str, depends_on: &str, threshold: u8) -> Result<()> {
+        if epic == depends_on {
+            anyhow::bail!("An epic can't depend on itself");
+        }
+        if !(1..=100).contains(&threshold) {
+            anyhow::bail!("Threshold must be between 1 and 100");
+        }
+        for name in [epic, depends_on] {
+            let exists: bool = self.query_row(
+                "SELECT EXISTS(SELECT 1 FROM tasks WHERE epic_name = ?1 AND deleted_at IS NULL)",
+                [name],
+                |row| row.get(0),
+            )?;
+            if !exists {
+                anyhow::bail!("Epic '{}' has no tasks", name);
+            }
+        }
+
+        // Walk prerequisites of `depends_on`; reaching `epic` would close a cycle
+        let deps = self.list_epic_dependencies()?;
+        let mut queue = vec![depends_on];
+        let mut visited = std::collections::HashSet::new();
+        while let Some(current) = queue.pop() {
+            if current == epic {
+                anyhow::bail!("Circular dependency detected!");
+            }
+            if visited.insert(current) {
+                queue.extend(
+                    deps.iter()
+                        .filter(|d| d.epic == current)
+                        .map(|d| d.depends_on.as_str()),
+                );
+            }
+        }
+
+        self.execute(
+            "INSERT INTO epic_dependencies (epic_name, depends_on_epic, threshold, created_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(epic_name, depends_on_epic) DO UPDATE SET threshold = excluded.threshold",
+            params![epic, depends_on, threshold, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    fn remove_epic_dependency(&self, epic: &str, depends_on: &str) -> Result<bool> {
+        let removed = self.execute(
+            "DELETE FROM epic_dependencies WHERE epic_name = ?1 AND depends_on_epic = ?2",
+            params![epic, depends_on],
+        )?;
+        Ok(removed > 0)
+    }
+
+    fn list_epic_dependencies(&self) -> Result<Vec<EpicDependency>> {
+        let mut stmt = self.prepare(
+            "SELECT epic_name, depends_on_epic, threshold, created_at FROM epic_dependencies
+             ORDER BY epic_name, depends_on_epic",
+        )?;
+        let deps = stmt
+            .query_map([], |row| {
+                let created_at: String = row.get(3)?;
+                Ok(EpicDependency {
+                    epic: row.get(0)?,
+                    depends_on: row.get(1)?,
+                    threshold: row.get(2)?,
+                    created_at: DateTime::parse_from_rfc3339(&created_at)
+                        .map(|dt| dt.with_timezone(&Utc))
+                        .unwrap_or_else(|_| Utc::now()),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(deps)
+    }
+
+    fn epic_gates(&self) -> Result<Vec<EpicGate>> {
+        let mut stmt = self.prepare(&format!(
+            "SELECT d.epic_name, d.depends_on_epic, d.threshold,
+                    (SELECT COUNT(*) FROM tasks
+                     WHERE epic_name = d.depends_on_epic AND deleted_at IS NULL),
+                    (SELECT COUNT(*) FROM tasks
+                     WHERE epic_name = d.depends_on_epic AND status IN ({})
+                       AND deleted_at IS NULL)
+             FROM epic_dependencies d
+             ORDER BY d.epic_name, d.depends_on_epic",
+            COMPLETED_STATUSES_SQL
+        ))?;
+        let gates = stmt
+            .query_map([], |row| {
+                let threshold: u8 = row.get(2)?;
+                let total: i64 = row.get(3)?;
+                let completed: i64 = row.get(4)?;
+                // Matches GATED_EPICS_SQL, including the empty-epic case
+                let met = total == 0 || completed * 100 >= threshold as i64 * total;
+                Ok(EpicGate {
+                    epic: row.get(0)?,
+                    depends_on: row.get(1)?,
+                    threshold,
+                    progress: (completed * 100).checked_div(total).unwrap_or(0) as u8,
+                    met,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(gates)
+    }
+}
+
 impl AcceptanceCriteriaOps for Connection {
     fn add_criterion(&self, task_id: i32, criterion: String) -> Result<i32> {
         self.execute(
@@ -0,0 +1,179 @@
+use anyhow::{Context, Result};
+use rusqlite::{Connection, OpenFlags};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// The fields of a task that a diff compares
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TaskState {
+    pub id: String,
+    pub display_id: Option<i32>,
+    pub title: String,
+    pub status: String,
+    pub priority: String,
+    /// Assigned agent's name
+    pub assignee: Option<String>,
+    pub epic: Option<String>,
+}
+
+/// One field that differs between the two states
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// A task present in both states with different fields
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskChange {
+    /// The task as it is in the newer state
+    pub task: TaskState,
+    pub changes: Vec<FieldChange>,
+}
+
+/// Tasks added, removed and changed between two database states
+#[derive(Debug, Default, Serialize)]
+pub struct TaskDiff {
+    pub added: Vec<TaskState>,
+    pub removed: Vec<TaskState>,
+    pub changed: Vec<TaskChange>,
+}
+
+impl TaskDiff {
+    /// Compare the tasks in `before` and `after`, matching them by UUID
+    pub fn between(before: &Connection, after: &Connection) -> Result<Self> {
+        let mut old = load_tasks(before)?;
+        let mut diff = TaskDiff::default();
+
+        for (id, task) in load_tasks(after)? {
+            match old.remove(&id) {
+                None => diff.added.push(task),
+                Some(prev) => {
+                    let changes = compare(&prev, &task);
+                    if !changes.is_empty() {
+                        diff.changed.push(TaskChange { task, changes });
+                    }
+                }
+            }
+        }
+        diff.removed = old.into_values().collect();
+
+        let by_display_id = |t: &TaskState| (t.display_id.unwrap_or(i32::MAX), t.id.clone());
+        diff.added.sort_by_key(by_display_id);
+        diff.removed.sort_by_key(by_display_id);
+        diff.changed.sort_by_key(|c| by_display_id(&c.task));
+        Ok(diff)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Open a database or snapshot read-only, so comparing never migrates it
+pub fn open_read_only(path: &Path) -> Result<Connection> {
+    if !path.is_file() {
+        anyhow::bail!("Database not found: {}", path.display());
+    }
+    Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .with_context(|| format!("Failed to open {}", path.display()))
+}
+
+fn load_tasks(conn: &Connection) -> Result<BTreeMap<String, TaskState>> {
+    // Snapshots taken before soft delete existed have no deleted_at column
+    let soft_delete: bool = conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info('tasks') WHERE name = 'deleted_at'",
+        [],
+        |row| Ok(row.get::<_, i32>(0)? > 0),
+    )?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT t.id, t.display_id, t.title, t.status, t.priority, a.name, t.epic_name
+         FROM tasks t LEFT JOIN agents a ON a.id = t.assigned_agent{}",
+        if soft_delete {
+            " WHERE t.deleted_at IS NULL"
+        } else {
+            ""
+        }
+    ))?;
+    let tasks = stmt
+        .query_map([], |row| {
+            let task = TaskState {
+                id: row.get(0)?,
+                display_id: row.get(1)?,
+                title: row.get(2)?,
+                status: row.get(3)?,
+                priority: row.get(4)?,
+                assignee: row.get(5)?,
+                epic: row.get(6)?,
+            };
+            Ok((task.id.clone(), task))
+        })?
+        .collect::<Result<_, _>>()?;
+    Ok(tasks)
+}
+
+fn compare(before: &TaskState, after: &TaskState) -> Vec<FieldChange> {
+    let fields: [(&'static str, Option<&String>, Option<&String>); 5] = [
+        ("status", Some(&before.status), Some(&after.status)),
+        (
+            "assignee",
+            before.assignee.as_ref(),
+            after.assignee.as_ref(),
+        ),
+        ("priority", Some(&before.priority), Some(&after.priority)),
+        ("title", Some(&before.title), Some(&after.title)),
+        ("epic", before.epic.as_ref(), after.epic.as_ref()),
+    ];
+    fields
+        .into_iter()
+        .filter(|(_, b, a)| b != a)
+        .map(|(field, b, a)| FieldChange {
+            field,
+            before: b.cloned(),
+            after: a.cloned(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Database, Priority, TaskStatus};
+    use crate::snapshot::SnapshotStore;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_diff_against_snapshot() -> Result<()> {
+        let dir = tempdir()?;
+        let db_path = dir.path().join("prd.db");
+        let db = Database::new(db_path.to_str().unwrap())?;
+        let kept = db.create_task("Kept".to_string(), None, Priority::Low, None, None)?;
+        let moved = db.create_task("Moved".to_string(), None, Priority::Low, None, None)?;
+        let store = SnapshotStore::for_database(&db_path);
+        let snapshot = store.create(db.get_connection(), "start")?;
+
+        let agent = db.create_agent("agent-1".to_string())?;
+        db.update_task_status(&moved.id, TaskStatus::Completed, None)?;
+        db.assign_task(&moved.id, &agent.id)?;
+        db.create_task("New".to_string(), None, Priority::High, None, None)?;
+
+        let before = open_read_only(&store.path(&snapshot))?;
+        let diff = TaskDiff::between(&before, db.get_connection())?;
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].title, "New");
+        assert!(diff.removed.is_empty());
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].task.id, moved.id);
+        let fields: Vec<&str> = diff.changed[0].changes.iter().map(|c| c.field).collect();
+        assert!(fields.contains(&"status") && fields.contains(&"assignee"));
+        assert!(diff.changed.iter().all(|c| c.task.id != kept.id));
+
+        // Reversed, the new task shows as removed
+        let reversed = TaskDiff::between(db.get_connection(), &before)?;
+        assert_eq!(reversed.removed[0].title, "New");
+        assert!(TaskDiff::between(&before, &before)?.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+use crate::db::{Database, StatusBucket, Task, TaskDateFilter};
+use anyhow::Result;
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// Default number of days ahead a due date counts as due soon
+pub const DEFAULT_DUE_SOON_DAYS: i64 = 3;
+
+/// An unfinished task that is overdue or due soon
+#[derive(Debug, Clone, Serialize)]
+pub struct DueTask {
+    pub task: Task,
+    pub due: NaiveDate,
+    /// Days until the due date; negative once overdue
+    pub days_left: i64,
+    /// Name of the assigned agent
+    pub agent: Option<String>,
+}
+
+impl DueTask {
+    pub fn is_overdue(&self) -> bool {
+        self.days_left < 0
+    }
+
+    /// One-line description, e.g. "2 days overdue" or "due tomorrow"
+    pub fn describe(&self) -> String {
+        match self.days_left {
+            -1 => "1 day overdue".to_string(),
+            days if days < 0 => format!("{} days overdue", -days),
+            0 => "due today".to_string(),
+            1 => "due tomorrow".to_string(),
+            days => format!("due in {} days", days),
+        }
+    }
+}
+
+/// Unfinished tasks that are overdue or due within `days` days of `today`,
+/// earliest due date first
+pub fn find_due(db: &Database, today: NaiveDate, days: i64) -> Result<Vec<DueTask>> {
+    let workflow = db.workflow()?;
+    let dates = TaskDateFilter {
+        due_before: Some(today + Duration::days(days + 1)),
+        ..Default::default()
+    };
+    let mut due: Vec<DueTask> = Vec::new();
+    for task in db.list_tasks_filtered(None, &dates)? {
+        let Some(day) = task.due_date else {
+            continue;
+        };
+        if workflow.bucket(&task.status) == StatusBucket::Done {
+            continue;
+        }
+        let agent = match &task.assigned_agent {
+            Some(id) => db.get_agent(id)?.map(|a| a.name),
+            None => None,
+        };
+        due.push(DueTask {
+            days_left: (day - today).num_days(),
+            due: day,
+            task,
+            agent,
+        });
+    }
+    due.sort_by_key(|d| (d.due, d.task.display_id));
+    Ok(due)
+}
+
+/// Detects tasks crossing their deadline between polls
+///
+/// Tasks already overdue when tracking starts don't alert; a task alerts
+/// again only if it is finished or rescheduled and then slips once more.
+pub struct DeadlineTracker {
+    overdue: HashSet<String>,
+}
+
+impl DeadlineTracker {
+    pub fn new(db: &Database, today: NaiveDate) -> Result<Self> {
+        let overdue = find_due(db, today, -1)?
+            .into_iter()
+            .map(|d| d.task.id)
+            .collect();
+        Ok(Self { overdue })
+    }
+
+    /// Tasks that became overdue since the last call
+    pub fn detect(&mut self, db: &Database, today: NaiveDate) -> Result<Vec<DueTask>> {
+        let overdue = find_due(db, today, -1)?;
+        let previous = std::mem::take(&mut self.overdue);
+        self.overdue = overdue.iter().map(|d| d.task.id.clone()).collect();
+        Ok(overdue
+            .into_iter()
+            .filter(|d| !previous.contains(&d.task.id))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::TaskStatus;
+    use crate::testing::{Fixture, TaskFixture};
+
+    #[test]
+    fn test_due_tasks_and_deadline_alerts() -> Result<()> {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+        let day = |offset: i64| today + Duration::days(offset);
+        let seeded = Fixture::new()
+            .agent("worker")
+            .task(TaskFixture::new("Late").due(day(-2)).agent("worker"))
+            .task(TaskFixture::new("Today").due(today))
+            .task(TaskFixture::new("Soon").due(day(3)))
+            .task(TaskFixture::new("Later").due(day(4)))
+            .task(
+                TaskFixture::new("Done")
+                    .due(day(-5))
+                    .status(TaskStatus::Completed),
+            )
+            .build()?;
+        let db = &seeded.db;
+
+        let due = find_due(db, today, DEFAULT_DUE_SOON_DAYS)?;
+        let titles: Vec<&str> = due.iter().map(|d| d.task.title.as_str()).collect();
+        assert_eq!(titles, ["Late", "Today", "Soon"]);
+        assert!(due[0].is_overdue());
+        assert_eq!(due[0].describe(), "2 days overdue");
+        assert_eq!(due[0].agent.as_deref(), Some("worker"));
+        assert_eq!(due[1].describe(), "due today");
+
+        let mut tracker = DeadlineTracker::new(db, today)?;
+        assert!(tracker.detect(db, today)?.is_empty());
+        let crossed = tracker.detect(db, day(1))?;
+        assert_eq!(crossed.len(), 1);
+        assert_eq!(crossed[0].task.title, "Today");
+        assert!(tracker.detect(db, day(1))?.is_empty());
+        Ok(())
+    }
+}
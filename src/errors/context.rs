@@ -148,9 +148,10 @@ impl ErrorContext {
                 if let Some(completed_at) = task.completed_at {
                     msg.push_str(&format!(
                         "Completed: {}\n",
-                        completed_at
-                            .format("%Y-%m-%d %H:%M:%S")
-                            .to_string()
+                        self.db
+                            .time_display()
+                            .unwrap_or_default()
+                            .format_precise(completed_at)
                             .dimmed()
                     ));
                 }
@@ -182,6 +183,7 @@ impl ErrorContext {
             "blocked",
             "review",
             "completed",
+            "failed",
             "cancelled",
         ];
 
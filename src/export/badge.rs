@@ -0,0 +1,185 @@
+use crate::colors::{ColorScheme, Rgb};
+use crate::db::Database;
+use anyhow::Result;
+
+/// Approximate width of a character in 11px Verdana, as used by shields.io
+const CHAR_WIDTH: f32 = 6.5;
+
+/// Horizontal padding on each side of a badge half
+const PADDING: u32 = 6;
+
+/// A shields-style completion badge
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressBadge {
+    pub label: String,
+    pub completed: usize,
+    pub total: usize,
+    /// Background of the label half; the epic's color for epic badges
+    pub label_color: Option<Rgb>,
+}
+
+impl ProgressBadge {
+    /// Count completed tasks, optionally restricted to one epic
+    ///
+    /// Custom statuses in the done bucket count as completed.
+    pub fn load(db: &Database, epic: Option<&str>, label: Option<&str>) -> Result<Self> {
+        let workflow = db.workflow()?;
+        let tasks: Vec<_> = db
+            .list_tasks(None)?
+            .into_iter()
+            .filter(|t| epic.is_none_or(|e| t.epic_name.as_deref() == Some(e)))
+            .collect();
+
+        Ok(Self {
+            label: label.or(epic).unwrap_or("progress").to_string(),
+            completed: tasks
+                .iter()
+                .filter(|t| workflow.is_completed(&t.status))
+                .count(),
+            total: tasks.len(),
+            label_color: match epic {
+                Some(epic) => ColorScheme::load(db)?.epic(epic),
+                None => None,
+            },
+        })
+    }
+
+    /// Completion percentage, rounded down (0 when there are no tasks)
+    pub fn percent(&self) -> u32 {
+        if self.total == 0 {
+            return 0;
+        }
+        (self.completed * 100 / self.total) as u32
+    }
+
+    /// Text on the right-hand side of the badge
+    pub fn message(&self) -> String {
+        if self.total == 0 {
+            "no tasks".to_string()
+        } else {
+            format!("{}%", self.percent())
+        }
+    }
+
+    /// Shields color scale for the percentage
+    pub fn color(&self) -> &'static str {
+        if self.total == 0 {
+            return "#9f9f9f";
+        }
+        match self.percent() {
+            100 => "#4c1",
+            80..=99 => "#97ca00",
+            60..=79 => "#a4a61d",
+            40..=59 => "#dfb317",
+            20..=39 => "#fe7d37",
+            _ => "#e05d44",
+        }
+    }
+
+    /// Render the badge as a standalone SVG document
+    pub fn to_svg(&self) -> String {
+        let label = escape_xml(&self.label);
+        let message = self.message();
+        let label_width = text_width(&self.label);
+        let message_width = text_width(&message);
+        let width = label_width + message_width;
+        let label_x = label_width * 5;
+        let message_x = label_width * 10 + message_width * 5;
+        let label_color = self
+            .label_color
+            .map_or_else(|| "#555".to_string(), |color| color.hex());
+
+        format!(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20" role="img" aria-label="{label}: {message}">
+  <title>{label}: {message}</title>
+  <linearGradient id="s" x2="0" y2="100%">
+    <stop offset="0" stop-color="#bbb" stop-opacity=".1"/>
+    <stop offset="1" stop-opacity=".1"/>
+  </linearGradient>
+  <clipPath id="r"><rect width="{width}" height="20" rx="3" fill="#fff"/></clipPath>
+  <g clip-path="url(#r)">
+    <rect width="{label_width}" height="20" fill="{label_color}"/>
+    <rect x="{label_width}" width="{message_width}" height="20" fill="{color}"/>
+    <rect width="{width}" height="20" fill="url(#s)"/>
+  </g>
+  <g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="110">
+    <text x="{label_x}" y="150" fill="#010101" fill-opacity=".3" transform="scale(.1)">{label}</text>
+    <text x="{label_x}" y="140" transform="scale(.1)">{label}</text>
+    <text x="{message_x}" y="150" fill="#010101" fill-opacity=".3" transform="scale(.1)">{message}</text>
+    <text x="{message_x}" y="140" transform="scale(.1)">{message}</text>
+  </g>
+</svg>
+"##,
+            color = self.color(),
+        )
+    }
+}
+
+/// Width of one badge half, text plus padding
+fn text_width(text: &str) -> u32 {
+    (text.chars().count() as f32 * CHAR_WIDTH).ceil() as u32 + 2 * PADDING
+}
+
+/// Escape text for use in SVG content and attributes
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Priority, TaskStatus};
+
+    #[test]
+    fn test_badge_percent_and_color() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        let empty = ProgressBadge::load(&db, None, None)?;
+        assert_eq!(empty.message(), "no tasks");
+
+        for i in 0..3 {
+            let task = db.create_task(
+                format!("Task {}", i),
+                None,
+                Priority::Medium,
+                None,
+                Some("Auth".to_string()),
+            )?;
+            if i == 0 {
+                db.update_task_status(&task.id, TaskStatus::Completed, None)?;
+            }
+        }
+        db.create_task("Other".to_string(), None, Priority::Low, None, None)?;
+        db.set_setting("color.epic.Auth", "#0366d6")?;
+
+        let badge = ProgressBadge::load(&db, None, None)?;
+        assert_eq!(badge.label_color, None);
+        assert_eq!((badge.completed, badge.total), (1, 4));
+        assert_eq!(badge.message(), "25%");
+        assert_eq!(badge.color(), "#fe7d37");
+
+        let epic = ProgressBadge::load(&db, Some("Auth"), None)?;
+        assert_eq!(epic.label, "Auth");
+        assert_eq!(epic.percent(), 33);
+        assert!(epic.to_svg().contains("fill=\"#0366d6\""));
+        Ok(())
+    }
+
+    #[test]
+    fn test_badge_svg() {
+        let badge = ProgressBadge {
+            label: "R&D".to_string(),
+            completed: 5,
+            total: 5,
+            label_color: None,
+        };
+        let svg = badge.to_svg();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.contains("aria-label=\"R&amp;D: 100%\""));
+        assert!(svg.contains("fill=\"#4c1\""));
+        assert!(svg.contains("fill=\"#555\""));
+        assert!(!svg.contains("R&D"));
+    }
+}
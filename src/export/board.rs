@@ -0,0 +1,192 @@
+use crate::db::{Database, Task, TaskStatus, TimeDisplay};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+
+/// A card on the board
+#[derive(Debug, Clone)]
+pub struct BoardCard {
+    pub id: String,
+    pub title: String,
+    pub priority: String,
+    pub agent: Option<String>,
+    pub epic: Option<String>,
+}
+
+/// A point-in-time kanban board grouped by status
+#[derive(Debug, Clone)]
+pub struct KanbanBoard {
+    pub generated_at: DateTime<Utc>,
+    pub epic: Option<String>,
+    pub columns: Vec<(TaskStatus, Vec<BoardCard>)>,
+    /// How the snapshot time is shown
+    pub time: TimeDisplay,
+}
+
+impl KanbanBoard {
+    /// Load the board from the database, optionally restricted to one epic
+    pub fn load(db: &Database, epic: Option<&str>) -> Result<Self> {
+        let tasks: Vec<Task> = db
+            .list_tasks(None)?
+            .into_iter()
+            .filter(|t| epic.is_none_or(|e| t.epic_name.as_deref() == Some(e)))
+            .collect();
+
+        // Status columns in workflow order, custom statuses included
+        let mut columns = Vec::new();
+        for status in db.workflow()?.columns() {
+            let mut cards = Vec::new();
+            for task in tasks.iter().filter(|t| t.status == status) {
+                let agent = match &task.assigned_agent {
+                    Some(uuid) => db
+                        .get_agent(uuid)?
+                        .map(|a| a.display_id.map(|id| format!("A{}", id)).unwrap_or(a.name)),
+                    None => None,
+                };
+                cards.push(BoardCard {
+                    id: task
+                        .display_id
+                        .map(|id| format!("#{}", id))
+                        .unwrap_or_else(|| task.id[..8].to_string()),
+                    title: db.redactor().redact(&task.title).into_owned(),
+                    priority: task.priority.as_str().to_string(),
+                    agent,
+                    epic: task.epic_name.clone(),
+                });
+            }
+            columns.push((status, cards));
+        }
+
+        Ok(Self {
+            generated_at: Utc::now(),
+            epic: epic.map(|e| e.to_string()),
+            columns,
+            time: db.time_display()?,
+        })
+    }
+
+    /// Render the board as Markdown, one heading and table per status
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+
+        match &self.epic {
+            Some(epic) => out.push_str(&format!("# Task Board: {}\n\n", epic)),
+            None => out.push_str("# Task Board\n\n"),
+        }
+        out.push_str(&format!(
+            "_Snapshot taken {} {}_\n",
+            self.time.format(self.generated_at),
+            self.time.zone
+        ));
+
+        for (status, cards) in &self.columns {
+            out.push_str(&format!(
+                "\n## {} ({})\n\n",
+                status_heading(status),
+                cards.len()
+            ));
+            if cards.is_empty() {
+                out.push_str("_No tasks_\n");
+                continue;
+            }
+
+            out.push_str("| ID | Title | Priority | Agent | Epic |\n");
+            out.push_str("|----|-------|----------|-------|------|\n");
+            for card in cards {
+                out.push_str(&format!(
+                    "| {} | {} | {} | {} | {} |\n",
+                    card.id,
+                    escape_cell(&card.title),
+                    card.priority,
+                    card.agent.as_deref().unwrap_or("-"),
+                    card.epic
+                        .as_deref()
+                        .map(escape_cell)
+                        .unwrap_or_else(|| "-".to_string())
+                ));
+            }
+        }
+
+        out
+    }
+}
+
+/// Human-readable column heading for a status
+pub fn status_heading(status: &TaskStatus) -> String {
+    match status {
+        TaskStatus::Pending => "Pending".to_string(),
+        TaskStatus::InProgress => "In Progress".to_string(),
+        TaskStatus::Blocked => "Blocked".to_string(),
+        TaskStatus::Review => "Review".to_string(),
+        TaskStatus::Completed => "Completed".to_string(),
+        TaskStatus::Failed => "Failed".to_string(),
+        TaskStatus::Cancelled => "Cancelled".to_string(),
+        TaskStatus::Custom(name) => name
+            .split('_')
+            .filter(|word| !word.is_empty())
+            .map(|word| {
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|c| c.to_ascii_uppercase().to_string() + chars.as_str())
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+    }
+}
+
+/// Escape characters that would break a Markdown table cell
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Priority;
+
+    #[test]
+    fn test_board_markdown() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        let agent = db.create_agent("agent-1".to_string())?;
+        let task = db.create_task(
+            "Build | API".to_string(),
+            None,
+            Priority::High,
+            None,
+            Some("Backend".to_string()),
+        )?;
+        db.create_task("Write docs".to_string(), None, Priority::Low, None, None)?;
+        db.assign_task(&task.id, &agent.id)?;
+        db.update_task_status(&task.id, TaskStatus::InProgress, Some(&agent.id))?;
+
+        let board = KanbanBoard::load(&db, None)?;
+        let md = board.to_markdown();
+
+        assert!(md.starts_with("# Task Board\n"));
+        assert!(md.contains("## Pending (1)"));
+        assert!(md.contains("## In Progress (1)"));
+        assert!(md.contains("| #1 | Build \\| API | high | A1 | Backend |"));
+        assert!(md.contains("## Blocked (0)\n\n_No tasks_"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_board_epic_filter() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        db.create_task(
+            "In epic".to_string(),
+            None,
+            Priority::Medium,
+            None,
+            Some("A".to_string()),
+        )?;
+        db.create_task("Other".to_string(), None, Priority::Medium, None, None)?;
+
+        let board = KanbanBoard::load(&db, Some("A"))?;
+        let total: usize = board.columns.iter().map(|(_, cards)| cards.len()).sum();
+        assert_eq!(total, 1);
+        assert!(board.to_markdown().starts_with("# Task Board: A\n"));
+        Ok(())
+    }
+}
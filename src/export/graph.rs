@@ -0,0 +1,226 @@
+use crate::colors::ColorScheme;
+use crate::db::{Database, EpicProgress};
+use crate::db_extensions::{EpicDependencyOps, EpicGate};
+use anyhow::Result;
+
+/// Epics and the dependencies between them, for Graphviz or Mermaid
+///
+/// Nodes show each epic's progress, outlined in the epic's configured color;
+/// edges point from a prerequisite to the epic it gates and are drawn dashed
+/// while the threshold isn't met.
+pub struct EpicGraph {
+    pub epics: Vec<EpicProgress>,
+    pub gates: Vec<EpicGate>,
+    pub colors: ColorScheme,
+}
+
+impl EpicGraph {
+    pub fn load(db: &Database) -> Result<Self> {
+        Ok(Self {
+            epics: db.epic_progress()?,
+            gates: db.get_connection().epic_gates()?,
+            colors: ColorScheme::load(db)?,
+        })
+    }
+
+    /// Whether any of the epic's prerequisites is below its threshold
+    fn is_gated(&self, epic: &str) -> bool {
+        self.gates.iter().any(|g| g.epic == epic && !g.met)
+    }
+
+    fn node_label(epic: &EpicProgress) -> String {
+        format!(
+            "{}\\n{}/{} ({}%)",
+            epic.epic,
+            epic.completed,
+            epic.total,
+            (epic.completed * 100).checked_div(epic.total).unwrap_or(0)
+        )
+    }
+
+    fn edge_label(gate: &EpicGate) -> String {
+        if gate.threshold == 100 {
+            "done".to_string()
+        } else {
+            format!("≥{}%", gate.threshold)
+        }
+    }
+
+    /// Graphviz DOT document
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph epics {\n");
+        dot.push_str("  rankdir=LR;\n");
+        dot.push_str("  node [shape=box, style=\"rounded,filled\", fontname=\"Helvetica\"];\n");
+
+        for (i, epic) in self.epics.iter().enumerate() {
+            let fill = if epic.total > 0 && epic.completed == epic.total {
+                "#c6efce"
+            } else if self.is_gated(&epic.epic) {
+                "#eeeeee"
+            } else {
+                "#ddebf7"
+            };
+            let outline = match self.colors.epic(&epic.epic) {
+                Some(color) => format!(", color=\"{}\", penwidth=2", color.hex()),
+                None => String::new(),
+            };
+            dot.push_str(&format!(
+                "  e{} [label=\"{}\", fillcolor=\"{}\"{}];\n",
+                i,
+                escape_dot(&Self::node_label(epic)),
+                fill,
+                outline
+            ));
+        }
+
+        for gate in &self.gates {
+            let (Some(from), Some(to)) =
+                (self.index_of(&gate.depends_on), self.index_of(&gate.epic))
+            else {
+                continue;
+            };
+            let style = if gate.met {
+                ""
+            } else {
+                ", style=dashed, color=\"#c00000\""
+            };
+            dot.push_str(&format!(
+                "  e{} -> e{} [label=\"{}\"{}];\n",
+                from,
+                to,
+                Self::edge_label(gate),
+                style
+            ));
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Mermaid flowchart (renders in GitHub/GitLab markdown)
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::from("flowchart LR\n");
+        for (i, epic) in self.epics.iter().enumerate() {
+            out.push_str(&format!(
+                "  e{}[\"{}\"]\n",
+                i,
+                Self::node_label(epic)
+                    .replace("\\n", "<br/>")
+                    .replace('"', "#quot;")
+            ));
+        }
+        for gate in &self.gates {
+            let (Some(from), Some(to)) =
+                (self.index_of(&gate.depends_on), self.index_of(&gate.epic))
+            else {
+                continue;
+            };
+            let arrow = if gate.met { "-->" } else { "-.->" };
+            out.push_str(&format!(
+                "  e{} {}|{}| e{}\n",
+                from,
+                arrow,
+                Self::edge_label(gate),
+                to
+            ));
+        }
+        for (i, epic) in self.epics.iter().enumerate() {
+            let mut style = Vec::new();
+            if let Some(color) = self.colors.epic(&epic.epic) {
+                style.push(format!("stroke:{},stroke-width:2px", color.hex()));
+            }
+            if self.is_gated(&epic.epic) {
+                style.push("stroke-dasharray: 5 5".to_string());
+            }
+            if !style.is_empty() {
+                out.push_str(&format!("  style e{} {}\n", i, style.join(",")));
+            }
+        }
+        out
+    }
+
+    fn index_of(&self, epic: &str) -> Option<usize> {
+        self.epics.iter().position(|e| e.epic == epic)
+    }
+}
+
+/// Escape a DOT string literal, keeping `\n` line breaks
+fn escape_dot(text: &str) -> String {
+    text.replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Priority, TaskStatus};
+    use crate::db_extensions::DependencyOps;
+
+    fn setup() -> Result<Database> {
+        let db = Database::new(":memory:")?;
+        for (epic, n) in [("Phase 1", 4), ("Phase 2", 2)] {
+            for i in 0..n {
+                db.create_task(
+                    format!("{} task {}", epic, i),
+                    None,
+                    Priority::Medium,
+                    None,
+                    Some(epic.to_string()),
+                )?;
+            }
+        }
+        db.get_connection()
+            .add_epic_dependency("Phase 2", "Phase 1", 50)?;
+        Ok(db)
+    }
+
+    #[test]
+    fn test_gated_epic_tasks_are_not_ready() -> Result<()> {
+        let db = setup()?;
+        let conn = db.get_connection();
+        assert!(conn.add_epic_dependency("Phase 1", "Phase 2", 100).is_err());
+        assert!(conn.add_epic_dependency("Phase 2", "Missing", 100).is_err());
+
+        // Only Phase 1 (#1-#4) is ready until half of it is done
+        assert_eq!(conn.get_ready_tasks()?.len(), 4);
+        assert!(!conn.epic_gates()?[0].met);
+        let next = db.next_pending_task(None)?.unwrap();
+        assert_eq!(next.epic_name.as_deref(), Some("Phase 1"));
+
+        for task in db
+            .list_tasks(None)?
+            .iter()
+            .filter(|t| t.title.ends_with(" 0") || t.title.ends_with(" 1"))
+        {
+            if task.epic_name.as_deref() == Some("Phase 1") {
+                db.update_task_status(&task.id, TaskStatus::Completed, None)?;
+            }
+        }
+        let gate = &conn.epic_gates()?[0];
+        assert!(gate.met);
+        assert_eq!(gate.progress, 50);
+        assert_eq!(conn.get_ready_tasks()?.len(), 4);
+
+        assert!(conn.remove_epic_dependency("Phase 2", "Phase 1")?);
+        assert!(conn.epic_gates()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_graph_export_formats() -> Result<()> {
+        let db = setup()?;
+        db.set_setting("color.epic.Phase 2", "#ff8800")?;
+        let graph = EpicGraph::load(&db)?;
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph epics {"));
+        assert!(dot.contains("e0 [label=\"Phase 1\\n0/4 (0%)\", fillcolor=\"#ddebf7\"];"));
+        assert!(dot.contains("color=\"#ff8800\", penwidth=2"));
+        assert!(dot.contains("e0 -> e1 [label=\"≥50%\", style=dashed"));
+
+        let mermaid = graph.to_mermaid();
+        assert!(mermaid.contains("e1[\"Phase 2<br/>0/2 (0%)\"]"));
+        assert!(mermaid.contains("e0 -.->|≥50%| e1"));
+        assert!(mermaid.contains("style e1 stroke:#ff8800,stroke-width:2px,stroke-dasharray"));
+        Ok(())
+    }
+}
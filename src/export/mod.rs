@@ -0,0 +1,9 @@
+pub mod badge;
+pub mod board;
+pub mod graph;
+pub mod org;
+
+pub use badge::ProgressBadge;
+pub use board::KanbanBoard;
+pub use graph::EpicGraph;
+pub use org::OrgExporter;
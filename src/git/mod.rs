@@ -5,4 +5,4 @@ pub mod sync;
 mod tests;
 
 pub use hooks::GitHookManager;
-pub use sync::GitSync;
+pub use sync::{GitSync, TaskCommit};
@@ -2,10 +2,23 @@ use anyhow::Result;
 use chrono::{DateTime, TimeZone, Utc};
 use git2::{Commit, Repository, Time};
 use regex::Regex;
+use serde::Serialize;
 use std::path::Path;
 
 use crate::sync::CompletionDoc;
 
+/// A commit whose message references a task
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskCommit {
+    pub hash: String,
+    /// First line of the commit message
+    pub summary: String,
+    pub author: String,
+    /// Agent parsed from the author, e.g. "A12"
+    pub agent: Option<String>,
+    pub committed_at: DateTime<Utc>,
+}
+
 /// Git sync manager for scanning commit history
 pub struct GitSync {
     repo: Repository,
@@ -41,6 +54,7 @@ impl GitSync {
         until: Option<DateTime<Utc>>,
         branch: Option<&str>,
     ) -> Result<Vec<CompletionDoc>> {
+        let _span = crate::telemetry::span("git.scan_for_completions");
         println!("🔍 Scanning git log for task completions...");
 
         let mut revwalk = self.repo.revwalk()?;
@@ -131,6 +145,32 @@ impl GitSync {
 
         Ok(completions)
     }
+
+    /// Commits reachable from HEAD whose message references task
+    /// `display_id`, newest first
+    pub fn task_commits(&self, display_id: i32) -> Result<Vec<TaskCommit>> {
+        let patterns = TaskPatterns::new()?;
+        let mut revwalk = self.repo.revwalk()?;
+        revwalk.push_head()?;
+
+        let mut commits = Vec::new();
+        for oid in revwalk {
+            let commit = self.repo.find_commit(oid?)?;
+            let message = commit.message().unwrap_or("");
+            if !patterns.extract_task_ids(message).contains(&display_id) {
+                continue;
+            }
+            commits.push(TaskCommit {
+                hash: commit.id().to_string(),
+                summary: message.lines().next().unwrap_or("").to_string(),
+                author: commit.author().name().unwrap_or("Unknown").to_string(),
+                agent: parse_agent_from_author(&commit),
+                committed_at: convert_git_time_to_datetime(commit.time()),
+            });
+        }
+
+        Ok(commits)
+    }
 }
 
 /// Supported commit message patterns
@@ -1,9 +1,13 @@
+use crate::db::Database;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
+/// Setting holding the hooks.toml shared through the database
+pub const SETTING_HOOKS_CONFIG: &str = "hooks.config";
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct HookConfig {
     #[serde(default)]
@@ -52,10 +56,21 @@ impl HookConfig {
         }
 
         let content = fs::read_to_string(&config_path)?;
-        let config: HookConfig = toml::from_str(&content)
-            .map_err(|e| anyhow::anyhow!("Failed to parse hooks config: {}", e))?;
+        Self::from_toml(&content)
+    }
+
+    /// Load the configuration shared in `db` (`prd hooks push`), or the
+    /// local file when the database has none
+    pub fn load_shared(db: &Database) -> Result<Self> {
+        match db.get_setting(SETTING_HOOKS_CONFIG)? {
+            Some(content) => Self::from_toml(&content),
+            None => Self::load(),
+        }
+    }
 
-        Ok(config)
+    /// Parse hooks.toml content
+    pub fn from_toml(content: &str) -> Result<Self> {
+        toml::from_str(content).map_err(|e| anyhow::anyhow!("Failed to parse hooks config: {}", e))
     }
 
     /// Save hook configuration to the default location
@@ -202,4 +217,22 @@ mod tests {
         assert!(config.get_hook_command("on_task_start").is_none());
         assert!(config.get_hook_command("invalid").is_none());
     }
+
+    #[test]
+    fn test_shared_config_from_database() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        db.set_setting(
+            SETTING_HOOKS_CONFIG,
+            "on_sync = \"echo synced\"\n\n[enabled]\non_sync = true\n",
+        )?;
+
+        let config = HookConfig::load_shared(&db)?;
+        assert!(config.is_enabled("on_sync"));
+        assert_eq!(
+            config.get_hook_command("on_sync").map(String::as_str),
+            Some("echo synced")
+        );
+        assert!(HookConfig::from_toml("on_sync = 3").is_err());
+        Ok(())
+    }
 }
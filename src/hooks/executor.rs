@@ -1,5 +1,6 @@
 use super::config::HookConfig;
-use crate::db::{Agent, Task};
+use crate::db::{Agent, Database, Task};
+use crate::redact::Redactor;
 use anyhow::Result;
 use chrono::Utc;
 use std::collections::HashMap;
@@ -9,18 +10,35 @@ use std::time::Duration;
 
 pub struct HookExecutor {
     config: HookConfig,
+    redactor: Redactor,
 }
 
 impl HookExecutor {
     /// Create a new hook executor with the given configuration
     pub fn new(config: HookConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            redactor: Redactor::default(),
+        }
+    }
+
+    /// Use a custom filter for secrets in hook payloads
+    pub fn with_redactor(mut self, redactor: Redactor) -> Self {
+        self.redactor = redactor;
+        self
     }
 
     /// Create a hook executor with default configuration loaded from disk
     pub fn from_default() -> Result<Self> {
         let config = HookConfig::load()?;
-        Ok(Self::new(config))
+        Ok(Self::new(config).with_redactor(Redactor::load()))
+    }
+
+    /// Create a hook executor with the configuration shared in `db`, falling
+    /// back to the one on disk
+    pub fn from_database(db: &Database) -> Result<Self> {
+        let config = HookConfig::load_shared(db)?;
+        Ok(Self::new(config).with_redactor(Redactor::load()))
     }
 
     /// Trigger the on_task_complete hook
@@ -122,6 +140,15 @@ impl HookExecutor {
         Ok(())
     }
 
+    /// Substitute `{variable}` placeholders, masking secrets in the values
+    fn substitute(&self, hook_cmd: &str, vars: &HashMap<String, String>) -> String {
+        let mut cmd = hook_cmd.to_string();
+        for (key, value) in vars {
+            cmd = cmd.replace(&format!("{{{}}}", key), &self.redactor.redact(value));
+        }
+        cmd
+    }
+
     /// Execute a hook command asynchronously with variable substitution
     fn execute_hook(
         &self,
@@ -129,11 +156,7 @@ impl HookExecutor {
         hook_cmd: &str,
         vars: HashMap<String, String>,
     ) -> Result<()> {
-        // Substitute variables
-        let mut cmd = hook_cmd.to_string();
-        for (key, value) in &vars {
-            cmd = cmd.replace(&format!("{{{}}}", key), value);
-        }
+        let cmd = self.substitute(hook_cmd, &vars);
 
         // Execute asynchronously with timeout
         let cmd_clone = cmd.clone();
@@ -232,6 +255,7 @@ mod tests {
             estimated_duration: None,
             actual_duration: None,
             epic_name: None,
+            due_date: None,
         }
     }
 
@@ -262,6 +286,20 @@ mod tests {
         assert_eq!(result, "echo 'Task 42 by A10'");
     }
 
+    #[test]
+    fn test_substitution_redacts_secrets() {
+        let executor = HookExecutor::new(HookConfig::default());
+        let vars = HashMap::from([(
+            "error".to_string(),
+            "401 with api_key=abcd1234efgh".to_string(),
+        )]);
+
+        assert_eq!(
+            executor.substitute("notify '{error}'", &vars),
+            "notify '401 with api_key=[REDACTED]'"
+        );
+    }
+
     #[test]
     fn test_format_task_id() {
         let task = create_test_task();
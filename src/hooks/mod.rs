@@ -4,20 +4,24 @@ pub mod executor;
 #[cfg(test)]
 mod tests;
 
-pub use config::HookConfig;
+pub use config::{HookConfig, SETTING_HOOKS_CONFIG};
 pub use executor::HookExecutor;
 
 // Helper functions for CLI integration
+use crate::db::Database;
+use crate::notifications::config::{NotificationConfig, SETTING_NOTIFICATIONS_CONFIG};
 use anyhow::Result;
+use std::fs;
+use std::path::PathBuf;
 
 /// Initialize hooks configuration with default examples
 pub fn init_hooks_config() -> Result<()> {
     HookConfig::init_default()
 }
 
-/// List all configured hooks
-pub fn list_hooks() -> Result<()> {
-    let config = HookConfig::load()?;
+/// List all configured hooks, as shared in `db` when it has a copy
+pub fn list_hooks(db: &Database) -> Result<()> {
+    let config = HookConfig::load_shared(db)?;
     let hooks = config.list_hooks();
 
     println!("Configured hooks:\n");
@@ -44,15 +48,24 @@ pub fn list_hooks() -> Result<()> {
         println!();
     }
 
-    let config_path = HookConfig::get_config_path()?;
-    println!("Configuration file: {}", config_path.display());
+    if db.get_setting(SETTING_HOOKS_CONFIG)?.is_some() {
+        println!("Configuration: shared in the database (prd hooks pull to edit)");
+    } else {
+        let config_path = HookConfig::get_config_path()?;
+        println!("Configuration file: {}", config_path.display());
+    }
 
     Ok(())
 }
 
 /// Test a hook without side effects
-pub fn test_hook(hook_name: &str, _task_id: Option<&str>, _agent_id: Option<&str>) -> Result<()> {
-    let config = HookConfig::load()?;
+pub fn test_hook(
+    db: &Database,
+    hook_name: &str,
+    _task_id: Option<&str>,
+    _agent_id: Option<&str>,
+) -> Result<()> {
+    let config = HookConfig::load_shared(db)?;
 
     println!("Testing hook: {}\n", hook_name);
 
@@ -94,3 +107,73 @@ pub fn disable_hook(hook_name: &str) -> Result<()> {
     println!("✓ Disabled hook: {}", hook_name);
     Ok(())
 }
+
+/// Local config files that `prd hooks push` shares, with their settings keys
+fn shared_files() -> Result<[(&'static str, PathBuf); 2]> {
+    Ok([
+        (SETTING_HOOKS_CONFIG, HookConfig::get_config_path()?),
+        (
+            SETTING_NOTIFICATIONS_CONFIG,
+            NotificationConfig::get_config_path()?,
+        ),
+    ])
+}
+
+/// Copy the local hooks.toml and notification config.toml into `db`, so
+/// every machine and runner using the database gets the same automation
+///
+/// Files are validated first and stored as written, comments included.
+/// Returns the files pushed.
+pub fn push_config(db: &Database) -> Result<Vec<PathBuf>> {
+    let mut pushed = Vec::new();
+    let mut contents = Vec::new();
+    for (key, path) in shared_files()? {
+        if !path.exists() {
+            continue;
+        }
+        let content = fs::read_to_string(&path)?;
+        match key {
+            SETTING_HOOKS_CONFIG => HookConfig::from_toml(&content).map(|_| ())?,
+            _ => NotificationConfig::from_toml(&content).map(|_| ())?,
+        }
+        contents.push((key, content));
+        pushed.push(path);
+    }
+    if pushed.is_empty() {
+        anyhow::bail!("No hooks.toml or config.toml in ~/.prd to push (prd hooks init)");
+    }
+    db.write_transaction(|| {
+        for (key, content) in &contents {
+            db.set_setting(key, content)?;
+        }
+        Ok(())
+    })?;
+    Ok(pushed)
+}
+
+/// Write the configuration shared in `db` over the local files, returning
+/// the files written
+pub fn pull_config(db: &Database) -> Result<Vec<PathBuf>> {
+    let mut pulled = Vec::new();
+    for (key, path) in shared_files()? {
+        let Some(content) = db.get_setting(key)? else {
+            continue;
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, content)?;
+        pulled.push(path);
+    }
+    Ok(pulled)
+}
+
+/// Stop sharing configuration through `db`; machines go back to their
+/// local files. Returns whether anything was shared.
+pub fn unshare_config(db: &Database) -> Result<bool> {
+    let mut removed = false;
+    for (key, _) in shared_files()? {
+        removed |= db.unset_setting(key)?;
+    }
+    Ok(removed)
+}
@@ -1,38 +1,125 @@
+pub mod archive;
+pub mod bench;
+#[cfg(feature = "git")]
+pub mod blame;
+pub mod blocked;
+pub mod colors;
+pub mod context;
+#[cfg(feature = "dashboard")]
 pub mod dashboard;
 pub mod db;
+pub mod db_extensions;
+pub mod diff;
+pub mod due;
 pub mod errors;
+pub mod export;
+#[cfg(feature = "git")]
 pub mod git;
 pub mod hooks;
+pub mod merge;
 pub mod notifications;
+pub mod oplog;
+pub mod output;
+pub mod permissions;
+pub mod queue;
+pub mod redact;
+pub mod release;
 pub mod resolver;
+pub mod retention;
+pub mod rules;
+pub mod runner;
+pub mod self_update;
+pub mod server;
+pub mod snapshot;
+pub mod sprint;
+pub mod stale;
 pub mod suggestions;
 pub mod sync;
+pub mod telemetry;
+pub mod templates;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod transcript;
+pub mod trend;
 pub mod vectors;
+pub mod verify;
 pub mod visualization;
 pub mod watcher;
 
 pub use db::{
-    Agent, AgentMetrics, AgentProgress, AgentStatus, Database, Priority, Task, TaskLog, TaskStats,
-    TaskStatus,
+    Agent, AgentMetrics, AgentProgress, AgentStatus, Database, Priority, Task, TaskAttribution,
+    TaskEta, TaskLease, TaskLog, TaskStats, TaskStatus,
 };
 pub use suggestions::{AgentMatcher, AgentRecommendation};
+#[cfg(feature = "vectors")]
+pub use vectors::{ContentIndexer, Embedder, IndexStats};
 pub use vectors::{
-    ContentIndexer, ContentType, Embedder, EmbeddingRecord, IndexStats, SearchResult, VectorSearch,
-    VectorStore, EMBEDDING_DIM,
+    ContentType, EmbeddingRecord, SearchResult, VectorSearch, VectorStore, EMBEDDING_DIM,
 };
 
 use anyhow::Result;
+use permissions::{Operation, PermissionsConfig};
 
 /// PRD Client for programmatic access
 pub struct PRDClient {
     db: Database,
+    permissions: PermissionsConfig,
+    principal: Option<String>,
 }
 
 impl PRDClient {
     /// Create a new client connected to the specified database
     pub fn new(db_path: &str) -> Result<Self> {
         let db = Database::new(db_path)?;
-        Ok(PRDClient { db })
+        Ok(PRDClient {
+            db,
+            permissions: PermissionsConfig::default(),
+            principal: None,
+        })
+    }
+
+    /// Create a client acting as `principal` (agent name or actor), restricted
+    /// by the roles in ~/.prd/permissions.toml
+    pub fn for_principal(db_path: &str, principal: &str) -> Result<Self> {
+        Ok(Self::new(db_path)?.with_permissions(PermissionsConfig::load()?, principal))
+    }
+
+    /// Restrict this client's writes to what `principal`'s role allows
+    pub fn with_permissions(mut self, permissions: PermissionsConfig, principal: &str) -> Self {
+        self.permissions = permissions;
+        self.principal = Some(principal.to_string());
+        self
+    }
+
+    /// Fail unless the principal may perform `operation` on `task_id`
+    fn authorize(&self, operation: Operation, task_id: Option<&str>) -> Result<()> {
+        let Some(principal) = &self.principal else {
+            return Ok(());
+        };
+        let task = match task_id {
+            Some(id) => self.db.get_task(id)?,
+            None => None,
+        };
+        self.permissions
+            .check(&self.db, principal, operation, task.as_ref())?;
+        Ok(())
+    }
+
+    /// Queue a change to a guarded task for approval unless the principal
+    /// holds the approving role, failing with [`db::ApprovalRequired`]
+    fn guard(
+        &self,
+        id: &str,
+        field: db::ChangeField,
+        value: &str,
+        agent_id: Option<&str>,
+    ) -> Result<()> {
+        let Some(principal) = &self.principal else {
+            return Ok(());
+        };
+        let approver =
+            self.permissions.role_of(principal).as_str() == self.db.approval_policy()?.role;
+        self.db.guard_change(id, field, value, agent_id, approver)
     }
 
     /// Create a new client with the default database path
@@ -49,6 +136,7 @@ impl PRDClient {
         parent_id: Option<String>,
         epic_name: Option<String>,
     ) -> Result<Task> {
+        self.authorize(Operation::CreateTask, None)?;
         self.db
             .create_task(title, description, priority, parent_id, epic_name)
     }
@@ -71,10 +159,24 @@ impl PRDClient {
         status: TaskStatus,
         agent_id: Option<&str>,
     ) -> Result<()> {
+        let operation = if status == TaskStatus::Cancelled {
+            Operation::CancelTask
+        } else {
+            Operation::UpdateTask
+        };
+        self.authorize(operation, Some(id))?;
+        self.guard(id, db::ChangeField::Status, status.as_str(), agent_id)?;
         self.db.update_task_status(id, status, agent_id)
     }
 
+    pub fn update_task_priority(&self, id: &str, priority: Priority) -> Result<()> {
+        self.authorize(Operation::Reprioritize, Some(id))?;
+        self.guard(id, db::ChangeField::Priority, priority.as_str(), None)?;
+        self.db.update_task_priority(id, priority, None)
+    }
+
     pub fn assign_task(&self, task_id: &str, agent_id: &str) -> Result<()> {
+        self.authorize(Operation::AssignTask, Some(task_id))?;
         self.db.assign_task(task_id, agent_id)
     }
 
@@ -84,6 +186,7 @@ impl PRDClient {
         estimated: Option<i32>,
         actual: Option<i32>,
     ) -> Result<()> {
+        self.authorize(Operation::UpdateTask, Some(task_id))?;
         self.db.update_task_duration(task_id, estimated, actual)
     }
 
@@ -142,12 +245,17 @@ impl PRDClient {
     /// - Update task status to InProgress
     /// - Assign the task to the agent if not already assigned
     pub fn sync_agent(&self, agent_name: &str, task_id: &str) -> Result<()> {
+        self.authorize(Operation::UpdateTask, Some(task_id))?;
+
         // Get or create agent
         let agent = match self.db.get_agent_by_name(agent_name)? {
             Some(a) => a,
             None => self.db.create_agent(agent_name.to_string())?,
         };
 
+        // Respect leases held by other agents
+        self.db.check_lease(task_id, &agent.id)?;
+
         // Update agent status
         self.db
             .update_agent_status(&agent.id, AgentStatus::Working, Some(task_id))?;
@@ -163,44 +271,10 @@ impl PRDClient {
     }
 
     /// Get the next pending task for an agent to work on
+    ///
+    /// Highest priority first, then oldest first.
     pub fn get_next_task(&self, priority_filter: Option<Priority>) -> Result<Option<Task>> {
-        let tasks = self.db.list_tasks(Some(TaskStatus::Pending))?;
-
-        // Filter by priority if specified
-        let mut filtered_tasks: Vec<Task> = if let Some(priority) = priority_filter {
-            tasks
-                .into_iter()
-                .filter(|t| t.priority == priority)
-                .collect()
-        } else {
-            tasks
-        };
-
-        // Sort by priority (highest first) then by creation date (oldest first)
-        filtered_tasks.sort_by(|a, b| {
-            use std::cmp::Ordering;
-
-            let priority_cmp = match (&b.priority, &a.priority) {
-                (Priority::Critical, Priority::Critical) => Ordering::Equal,
-                (Priority::Critical, _) => Ordering::Greater,
-                (_, Priority::Critical) => Ordering::Less,
-                (Priority::High, Priority::High) => Ordering::Equal,
-                (Priority::High, _) => Ordering::Greater,
-                (_, Priority::High) => Ordering::Less,
-                (Priority::Medium, Priority::Medium) => Ordering::Equal,
-                (Priority::Medium, _) => Ordering::Greater,
-                (_, Priority::Medium) => Ordering::Less,
-                (Priority::Low, Priority::Low) => Ordering::Equal,
-            };
-
-            if priority_cmp == Ordering::Equal {
-                a.created_at.cmp(&b.created_at)
-            } else {
-                priority_cmp
-            }
-        });
-
-        Ok(filtered_tasks.into_iter().next())
+        self.db.next_pending_task(priority_filter)
     }
 
     /// Mark an agent as idle
@@ -214,6 +288,7 @@ impl PRDClient {
 
     /// Complete a task and set agent to idle
     pub fn complete_task(&self, task_id: &str, agent_name: &str) -> Result<()> {
+        self.authorize(Operation::UpdateTask, Some(task_id))?;
         if let Some(agent) = self.db.get_agent_by_name(agent_name)? {
             self.db
                 .update_task_status(task_id, TaskStatus::Completed, Some(&agent.id))?;
@@ -225,6 +300,7 @@ impl PRDClient {
 
     /// Block a task and set agent to blocked
     pub fn block_task(&self, task_id: &str, agent_name: &str, reason: Option<&str>) -> Result<()> {
+        self.authorize(Operation::UpdateTask, Some(task_id))?;
         if let Some(agent) = self.db.get_agent_by_name(agent_name)? {
             self.db
                 .update_task_status(task_id, TaskStatus::Blocked, Some(&agent.id))?;
@@ -286,6 +362,41 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_client_enforces_permissions() -> Result<()> {
+        let config: PermissionsConfig = toml::from_str(
+            r#"
+            enabled = true
+            [roles]
+            lead = "planner"
+            "#,
+        )?;
+        let planner = PRDClient::new(":memory:")?.with_permissions(config.clone(), "lead");
+        let task = planner.create_task("Task".to_string(), None, Priority::Low, None, None)?;
+        planner.update_task_priority(&task.id, Priority::High)?;
+
+        let agent = PRDClient {
+            db: planner.db,
+            permissions: config,
+            principal: Some("worker".to_string()),
+        };
+        agent.create_agent("worker".to_string())?;
+        agent.sync_agent("worker", &task.id)?;
+
+        let err = agent
+            .update_task_priority(&task.id, Priority::Low)
+            .unwrap_err();
+        assert!(err
+            .downcast_ref::<permissions::PermissionDenied>()
+            .is_some());
+        assert!(agent
+            .update_task_status(&task.id, TaskStatus::Cancelled, None)
+            .is_err());
+        agent.complete_task(&task.id, "worker")?;
+
+        Ok(())
+    }
+
     #[test]
     fn test_get_next_task() -> Result<()> {
         let client = PRDClient::new(":memory:")?;
@@ -0,0 +1,712 @@
+use crate::db::{Database, NEXT_TASK_DISPLAY_ID_SQL};
+use crate::diff::open_read_only;
+use crate::errors::similarity_score;
+use anyhow::Result;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Default title similarity above which a task counts as a duplicate
+pub const DEFAULT_TITLE_SIMILARITY: f64 = 0.9;
+
+/// Options for `prd db merge`
+#[derive(Debug, Clone)]
+pub struct MergeOptions {
+    /// Title similarity (0.0-1.0) at which tasks with different UUIDs are
+    /// treated as the same task; above 1.0 disables title matching
+    pub title_similarity: f64,
+    /// Report what would be merged without writing anything
+    pub dry_run: bool,
+}
+
+impl Default for MergeOptions {
+    fn default() -> Self {
+        Self {
+            title_similarity: DEFAULT_TITLE_SIMILARITY,
+            dry_run: false,
+        }
+    }
+}
+
+/// A task in the other database matched to an existing task by title
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateTask {
+    pub other_id: i32,
+    pub local_id: i32,
+    pub other_title: String,
+    pub local_title: String,
+    pub similarity: f64,
+}
+
+/// What a merge imported and skipped
+#[derive(Debug, Default, Serialize)]
+pub struct MergeReport {
+    pub dry_run: bool,
+    pub agents_added: usize,
+    /// Agents already present by UUID or name
+    pub agents_matched: usize,
+    pub tasks_added: usize,
+    /// Tasks already present by UUID or external ID (the local copy is kept)
+    pub tasks_existing: usize,
+    /// Tasks skipped as title duplicates of a local task
+    pub duplicates: Vec<DuplicateTask>,
+    pub criteria_added: usize,
+    pub dependencies_added: usize,
+    pub tags_added: usize,
+    pub comments_added: usize,
+    pub attachments_added: usize,
+    pub logs_added: usize,
+    /// Display ID in the other database → new display ID here, for added tasks
+    pub renumbered: Vec<(i32, i32)>,
+}
+
+/// Agent row copied from the other database
+struct AgentRow {
+    id: String,
+    name: String,
+    created_at: String,
+    last_active: String,
+}
+
+/// Task row copied verbatim from the other database
+struct TaskRow {
+    id: String,
+    display_id: i32,
+    title: String,
+    description: Option<String>,
+    status: String,
+    priority: String,
+    parent_id: Option<String>,
+    assigned_agent: Option<String>,
+    created_at: String,
+    updated_at: String,
+    completed_at: Option<String>,
+    estimated_duration: Option<i32>,
+    actual_duration: Option<i32>,
+    epic_name: Option<String>,
+    business_value: Option<i32>,
+    effort: Option<i32>,
+    due_date: Option<String>,
+    points: Option<i64>,
+    start_after: Option<String>,
+    external_id: Option<String>,
+    display_key: Option<String>,
+    recurrence: Option<String>,
+    recurs_from: Option<String>,
+    attempt: i64,
+}
+
+/// Import tasks, agents and their relations from another database
+///
+/// Agents match by UUID, then by name. Tasks match by UUID or external ID,
+/// then by title similarity; matched tasks keep the local copy. Imported
+/// tasks keep their UUIDs and get the next free display IDs, and criteria,
+/// dependencies, tags, comments, attachments, progress reports and logs
+/// follow them. Tasks in either
+/// database's trash are left out. Everything runs in one transaction.
+///
+/// The other database is only read, so it must already have the current
+/// schema (any `prd` command run against it migrates it).
+pub fn merge_database(
+    db: &Database,
+    other_path: &Path,
+    options: &MergeOptions,
+) -> Result<MergeReport> {
+    if !other_path.is_file() {
+        anyhow::bail!("Database not found: {}", other_path.display());
+    }
+    let local_path = db.get_connection().path().map(Path::new);
+    if let (Some(local), Ok(other)) = (local_path, other_path.canonicalize()) {
+        if local.canonicalize().ok().as_deref() == Some(other.as_path()) {
+            anyhow::bail!("Cannot merge a database into itself");
+        }
+    }
+    // Opened read-only so merging (or a dry run) never migrates or writes
+    // to the other database
+    let other = open_read_only(other_path)?;
+
+    let mut report = MergeReport {
+        dry_run: options.dry_run,
+        ..Default::default()
+    };
+    let mut finished = false;
+    let outcome = db.write_transaction(|| {
+        merge_into(db, &other, options, &mut report)?;
+        finished = true;
+        if options.dry_run {
+            // An error return rolls the transaction back
+            anyhow::bail!("dry run");
+        }
+        Ok(())
+    });
+    match outcome {
+        Err(_) if options.dry_run && finished => Ok(report),
+        Err(e) => Err(e),
+        Ok(()) => Ok(report),
+    }
+}
+
+fn merge_into(
+    db: &Database,
+    src: &Connection,
+    options: &MergeOptions,
+    report: &mut MergeReport,
+) -> Result<()> {
+    let conn = db.get_connection();
+
+    // Agents: other UUID → local UUID
+    let mut agent_map: HashMap<String, String> = HashMap::new();
+    let mut stmt =
+        src.prepare("SELECT id, name, created_at, last_active FROM agents ORDER BY name")?;
+    let agents: Vec<AgentRow> = stmt
+        .query_map([], |row| {
+            Ok(AgentRow {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                created_at: row.get(2)?,
+                last_active: row.get(3)?,
+            })
+        })?
+        .collect::<Result<_, _>>()?;
+    for agent in agents {
+        let local: Option<String> = conn
+            .query_row(
+                "SELECT id FROM agents WHERE id = ?1 OR name = ?2 ORDER BY id = ?1 DESC LIMIT 1",
+                params![agent.id, agent.name],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if let Some(local) = local {
+            agent_map.insert(agent.id, local);
+            report.agents_matched += 1;
+            continue;
+        }
+
+        let display_id: i32 = conn.query_row(
+            "SELECT COALESCE(MAX(display_id), 0) + 1 FROM agents",
+            [],
+            |row| row.get(0),
+        )?;
+        // Current task is machine-local state, so imported agents start idle
+        conn.execute(
+            "INSERT INTO agents (id, display_id, name, status, current_task_id, created_at, last_active)
+             VALUES (?1, ?2, ?3, 'idle', NULL, ?4, ?5)",
+            params![
+                agent.id,
+                display_id,
+                agent.name,
+                agent.created_at,
+                agent.last_active
+            ],
+        )?;
+        agent_map.insert(agent.id.clone(), agent.id);
+        report.agents_added += 1;
+    }
+
+    let local_titles: Vec<(i32, String, String)> = {
+        let mut stmt = conn.prepare(
+            "SELECT display_id, id, title FROM tasks
+                 WHERE display_id IS NOT NULL AND deleted_at IS NULL",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+        rows.collect::<Result<_, _>>()?
+    };
+
+    let mut stmt = src.prepare(
+        "SELECT id, display_id, title, description, status, priority, parent_id, assigned_agent,
+                created_at, updated_at, completed_at, estimated_duration, actual_duration, epic_name,
+                business_value, effort, due_date, points, start_after, external_id,
+                display_key, recurrence, recurs_from, attempt
+         FROM tasks WHERE display_id IS NOT NULL AND deleted_at IS NULL ORDER BY display_id",
+    )?;
+    let tasks: Vec<TaskRow> = stmt
+        .query_map([], |row| {
+            Ok(TaskRow {
+                id: row.get(0)?,
+                display_id: row.get(1)?,
+                title: row.get(2)?,
+                description: row.get(3)?,
+                status: row.get(4)?,
+                priority: row.get(5)?,
+                parent_id: row.get(6)?,
+                assigned_agent: row.get(7)?,
+                created_at: row.get(8)?,
+                updated_at: row.get(9)?,
+                completed_at: row.get(10)?,
+                estimated_duration: row.get(11)?,
+                actual_duration: row.get(12)?,
+                epic_name: row.get(13)?,
+                business_value: row.get(14)?,
+                effort: row.get(15)?,
+                due_date: row.get(16)?,
+                points: row.get(17)?,
+                start_after: row.get(18)?,
+                external_id: row.get(19)?,
+                display_key: row.get(20)?,
+                recurrence: row.get(21)?,
+                recurs_from: row.get(22)?,
+                attempt: row.get(23)?,
+            })
+        })?
+        .collect::<Result<_, _>>()?;
+
+    // Tasks: other display ID → local display ID, and other UUID → local UUID
+    let mut id_map: HashMap<i32, i32> = HashMap::new();
+    let mut uuid_map: HashMap<String, String> = HashMap::new();
+    let mut added: Vec<&TaskRow> = Vec::new();
+
+    for task in &tasks {
+        // The same import run on both sides gives different UUIDs but the
+        // same external ID
+        let existing: Option<(Option<i32>, String)> = conn
+            .query_row(
+                "SELECT display_id, id FROM tasks WHERE id = ?1 OR external_id = ?2
+                 ORDER BY id = ?1 DESC LIMIT 1",
+                params![task.id, task.external_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        if let Some((Some(local_id), local_uuid)) = existing {
+            id_map.insert(task.display_id, local_id);
+            uuid_map.insert(task.id.clone(), local_uuid);
+            report.tasks_existing += 1;
+            continue;
+        }
+
+        let best = local_titles
+            .iter()
+            .map(|(id, uuid, title)| (id, uuid, title, similarity_score(&task.title, title)))
+            .max_by(|a, b| a.3.total_cmp(&b.3));
+        if let Some((local_id, local_uuid, local_title, score)) = best {
+            if score >= options.title_similarity {
+                id_map.insert(task.display_id, *local_id);
+                uuid_map.insert(task.id.clone(), local_uuid.clone());
+                report.duplicates.push(DuplicateTask {
+                    other_id: task.display_id,
+                    local_id: *local_id,
+                    other_title: task.title.clone(),
+                    local_title: local_title.clone(),
+                    similarity: score,
+                });
+                continue;
+            }
+        }
+
+        let display_id: i32 = conn.query_row(NEXT_TASK_DISPLAY_ID_SQL, [], |row| row.get(0))?;
+        // A key already used here stays with the local task
+        let key_taken = match &task.display_key {
+            Some(key) => conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM tasks WHERE display_key = ?1)",
+                params![key],
+                |row| row.get(0),
+            )?,
+            None => false,
+        };
+        // Parents and recurrences are linked below, once every imported
+        // task exists
+        conn.execute(
+            "INSERT INTO tasks (id, display_id, title, description, status, priority, parent_id,
+                                assigned_agent, created_at, updated_at, completed_at,
+                                estimated_duration, actual_duration, epic_name,
+                                business_value, effort, due_date, points, start_after,
+                                external_id, display_key, recurrence, attempt)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, NULL, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16,
+                     ?17, ?18, ?19, ?20, ?21, ?22)",
+            params![
+                task.id,
+                display_id,
+                task.title,
+                task.description,
+                task.status,
+                task.priority,
+                task.assigned_agent.as_ref().and_then(|a| agent_map.get(a)),
+                task.created_at,
+                task.updated_at,
+                task.completed_at,
+                task.estimated_duration,
+                task.actual_duration,
+                task.epic_name,
+                task.business_value,
+                task.effort,
+                task.due_date,
+                task.points,
+                task.start_after,
+                task.external_id,
+                task.display_key.as_ref().filter(|_| !key_taken),
+                task.recurrence,
+                task.attempt,
+            ],
+        )?;
+        id_map.insert(task.display_id, display_id);
+        uuid_map.insert(task.id.clone(), task.id.clone());
+        report.renumbered.push((task.display_id, display_id));
+        added.push(task);
+    }
+    report.tasks_added = added.len();
+
+    for task in &added {
+        if let Some(parent) = task.parent_id.as_ref().and_then(|p| uuid_map.get(p)) {
+            conn.execute(
+                "UPDATE tasks SET parent_id = ?1 WHERE id = ?2",
+                params![parent, task.id],
+            )?;
+        }
+        if let Some(previous) = task.recurs_from.as_ref().and_then(|p| uuid_map.get(p)) {
+            conn.execute(
+                "UPDATE tasks SET recurs_from = ?1 WHERE id = ?2",
+                params![previous, task.id],
+            )?;
+        }
+        let local_id = id_map[&task.display_id];
+
+        let mut stmt = src.prepare(
+            "SELECT criterion, completed, created_at, completed_at FROM acceptance_criteria
+             WHERE task_display_id = ?1 ORDER BY id",
+        )?;
+        let criteria = stmt.query_map(params![task.display_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, bool>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })?;
+        for criterion in criteria {
+            let (text, completed, created_at, completed_at) = criterion?;
+            conn.execute(
+                "INSERT INTO acceptance_criteria
+                     (task_display_id, criterion, completed, created_at, completed_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![local_id, text, completed, created_at, completed_at],
+            )?;
+            report.criteria_added += 1;
+        }
+
+        let mut stmt = src.prepare("SELECT tag, created_at FROM task_tags WHERE task_id = ?1")?;
+        let tags = stmt.query_map(params![task.id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        for tag in tags {
+            let (tag, created_at) = tag?;
+            report.tags_added += conn.execute(
+                "INSERT OR IGNORE INTO task_tags (task_id, tag, created_at) VALUES (?1, ?2, ?3)",
+                params![task.id, tag, created_at],
+            )?;
+        }
+
+        let mut stmt = src.prepare(
+            "SELECT agent_id, actor, body, created_at FROM task_comments
+             WHERE task_id = ?1 ORDER BY created_at, id",
+        )?;
+        let comments = stmt.query_map(params![task.id], |row| {
+            Ok((
+                row.get::<_, Option<String>>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })?;
+        for comment in comments {
+            let (agent, actor, body, created_at) = comment?;
+            conn.execute(
+                "INSERT INTO task_comments (task_id, agent_id, actor, body, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    task.id,
+                    agent.and_then(|a| agent_map.get(&a).cloned()),
+                    actor,
+                    body,
+                    created_at
+                ],
+            )?;
+            report.comments_added += 1;
+        }
+
+        let mut stmt = src.prepare(
+            "SELECT location, label, agent_id, actor, created_at FROM task_attachments
+             WHERE task_id = ?1 ORDER BY created_at, id",
+        )?;
+        let attachments = stmt.query_map(params![task.id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, String>(4)?,
+            ))
+        })?;
+        for attachment in attachments {
+            let (location, label, agent, actor, created_at) = attachment?;
+            conn.execute(
+                "INSERT INTO task_attachments (task_id, location, label, agent_id, actor, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    task.id,
+                    location,
+                    label,
+                    agent.and_then(|a| agent_map.get(&a).cloned()),
+                    actor,
+                    created_at
+                ],
+            )?;
+            report.attachments_added += 1;
+        }
+
+        let mut stmt = src.prepare(
+            "SELECT agent_id, progress, message, timestamp FROM agent_progress WHERE task_id = ?1",
+        )?;
+        let progress = stmt.query_map(params![task.display_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })?;
+        for entry in progress {
+            let (agent, progress, message, timestamp) = entry?;
+            if let Some(agent) = agent_map.get(&agent) {
+                conn.execute(
+                    "INSERT INTO agent_progress (agent_id, task_id, progress, message, timestamp)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![agent, local_id, progress, message, timestamp],
+                )?;
+            }
+        }
+
+        let mut stmt = src.prepare(
+            "SELECT id, agent_id, action, details, created_at, actor FROM task_logs
+             WHERE task_id = ?1",
+        )?;
+        let logs = stmt.query_map(params![task.id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, Option<String>>(5)?,
+            ))
+        })?;
+        for log in logs {
+            let (id, agent, action, details, created_at, actor) = log?;
+            report.logs_added += conn.execute(
+                "INSERT OR IGNORE INTO task_logs
+                     (id, task_id, agent_id, action, details, created_at, actor)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    id,
+                    task.id,
+                    agent.and_then(|a| agent_map.get(&a).cloned()),
+                    action,
+                    details,
+                    created_at,
+                    actor
+                ],
+            )?;
+        }
+    }
+
+    // Dependencies touching an imported task, remapped on both ends
+    let mut stmt = src.prepare(
+        "SELECT task_display_id, depends_on_display_id, dependency_type, created_at
+         FROM task_dependencies",
+    )?;
+    let dependencies = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, i32>(0)?,
+            row.get::<_, i32>(1)?,
+            row.get::<_, Option<String>>(2)?,
+            row.get::<_, String>(3)?,
+        ))
+    })?;
+    let added_ids: Vec<i32> = added.iter().map(|t| t.display_id).collect();
+    for dependency in dependencies {
+        let (task, depends_on, dep_type, created_at) = dependency?;
+        if !added_ids.contains(&task) && !added_ids.contains(&depends_on) {
+            continue;
+        }
+        if let (Some(task), Some(depends_on)) = (id_map.get(&task), id_map.get(&depends_on)) {
+            if task != depends_on {
+                report.dependencies_added += conn.execute(
+                    "INSERT OR IGNORE INTO task_dependencies
+                         (task_display_id, depends_on_display_id, dependency_type, created_at)
+                     VALUES (?1, ?2, ?3, ?4)",
+                    params![task, depends_on, dep_type, created_at],
+                )?;
+            }
+        }
+    }
+
+    // Epic-level settings are keyed by name; local values win
+    let mut stmt = src.prepare(
+        "SELECT epic_name, depends_on_epic, threshold, created_at FROM epic_dependencies",
+    )?;
+    let gates = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, i64>(2)?,
+            row.get::<_, String>(3)?,
+        ))
+    })?;
+    for gate in gates {
+        let (epic, depends_on, threshold, created_at) = gate?;
+        conn.execute(
+            "INSERT OR IGNORE INTO epic_dependencies
+                 (epic_name, depends_on_epic, threshold, created_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![epic, depends_on, threshold, created_at],
+        )?;
+    }
+
+    let mut stmt = src.prepare("SELECT epic_name, target_date, updated_at FROM epic_targets")?;
+    let targets = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+        ))
+    })?;
+    for target in targets {
+        let (epic, date, updated_at) = target?;
+        conn.execute(
+            "INSERT OR IGNORE INTO epic_targets (epic_name, target_date, updated_at)
+             VALUES (?1, ?2, ?3)",
+            params![epic, date, updated_at],
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Priority, Recurrence, TaskStatus};
+    use crate::db_extensions::{AcceptanceCriteriaOps, DependencyOps};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_merge_remaps_ids_and_skips_duplicates() -> Result<()> {
+        let dir = tempdir()?;
+        let local_path = dir.path().join("local.db");
+        let other_path = dir.path().join("other.db");
+
+        let local = Database::new(local_path.to_str().unwrap())?;
+        local.create_task("Set up CI".to_string(), None, Priority::High, None, None)?;
+        local.create_agent("shared-agent".to_string())?;
+
+        let other = Database::new(other_path.to_str().unwrap())?;
+        other.create_task("Set up CI.".to_string(), None, Priority::Low, None, None)?;
+        let parent = other.create_task(
+            "Write docs".to_string(),
+            None,
+            Priority::Medium,
+            None,
+            Some("Docs".to_string()),
+        )?;
+        let child = other.create_task(
+            "Document the API".to_string(),
+            None,
+            Priority::Medium,
+            Some(parent.id.clone()),
+            Some("Docs".to_string()),
+        )?;
+        other
+            .get_connection()
+            .add_criterion(3, "Every endpoint listed".to_string())?;
+        other.get_connection().add_dependency(3, 1, "blocks")?;
+        let agent = other.create_agent("shared-agent".to_string())?;
+        other.create_agent("other-agent".to_string())?;
+        other.assign_task(&child.id, &agent.id)?;
+        other.update_task_status(&child.id, TaskStatus::InProgress, None)?;
+        other.set_task_points(&child.id, Some(3))?;
+        other.set_task_recurrence(&child.id, Some(&Recurrence::Days(7)), None)?;
+        let start = chrono::Utc::now() + chrono::Duration::days(3);
+        other.set_task_start_after(&child.id, Some(start), None)?;
+        local.upsert_external_task("gh-9", "Imported".to_string(), None, None, None, None)?;
+        other.upsert_external_task(
+            "gh-9",
+            "Renamed upstream".to_string(),
+            None,
+            None,
+            None,
+            None,
+        )?;
+        other.assign_next_display_key(&parent.id, "DOC")?;
+        other.add_task_tag(&parent.id, "docs", None)?;
+        other.add_comment(&child.id, Some(&agent.id), "Half the endpoints done")?;
+        other.add_attachment(&child.id, "https://docs.example/api", None, None)?;
+        other.update_task_status(&parent.id, TaskStatus::Failed, None)?;
+        other.retry_task(&parent.id, None, None)?;
+        let scrapped =
+            other.create_task("Scrapped".to_string(), None, Priority::Low, None, None)?;
+        other.delete_task(&scrapped.id, None)?;
+        drop(other);
+        let untouched = std::fs::read(&other_path)?;
+
+        let dry = merge_database(
+            &local,
+            &other_path,
+            &MergeOptions {
+                dry_run: true,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(dry.tasks_added, 2);
+        assert_eq!(local.list_tasks(None)?.len(), 2);
+
+        let report = merge_database(&local, &other_path, &MergeOptions::default())?;
+        assert_eq!(report.duplicates.len(), 1);
+        assert_eq!(report.duplicates[0].local_id, 1);
+        // The other copy of the imported issue matches by external ID
+        assert_eq!(report.tasks_existing, 1);
+        assert_eq!(report.renumbered, vec![(2, 3), (3, 4)]);
+        assert_eq!((report.agents_added, report.agents_matched), (1, 1));
+        assert_eq!(report.criteria_added, 1);
+        assert_eq!(report.dependencies_added, 1);
+        assert_eq!(report.tags_added, 1);
+        assert_eq!(report.comments_added, 1);
+        assert_eq!(report.attachments_added, 1);
+
+        // #3 (now #4) depended on the duplicate #1, so it now depends on the
+        // local #1
+        let conn = local.get_connection();
+        assert_eq!(conn.get_dependencies(4)?, vec![1]);
+        assert_eq!(conn.list_criteria(4)?.len(), 1);
+        assert_eq!(local.get_display_key(&parent.id)?.as_deref(), Some("DOC-1"));
+        assert_eq!(local.get_task_tags(&parent.id)?, ["docs"]);
+        assert_eq!(local.get_task_attempt(&parent.id)?, 2);
+        let child = local.get_task(&child.id)?.unwrap();
+        assert_eq!(child.parent_id, Some(parent.id));
+        assert_eq!(child.status, TaskStatus::InProgress);
+        let local_agent = local.get_agent_by_name("shared-agent")?.unwrap();
+        let comments = local.get_task_comments(&child.id)?;
+        assert_eq!(
+            comments[0].agent_id.as_deref(),
+            Some(local_agent.id.as_str())
+        );
+        assert_eq!(child.assigned_agent, Some(local_agent.id));
+        assert_eq!(local.get_task_points(&child.id)?, Some(3));
+        assert_eq!(
+            local.get_task_recurrence(&child.id)?,
+            Some(Recurrence::Days(7))
+        );
+        assert_eq!(
+            local
+                .get_task_start_after(&child.id)?
+                .map(|at| at.timestamp()),
+            Some(start.timestamp())
+        );
+        // Trashed tasks stay behind
+        assert!(local.get_task(&scrapped.id)?.is_none());
+
+        // Merging again finds everything by UUID or external ID
+        let again = merge_database(&local, &other_path, &MergeOptions::default())?;
+        assert_eq!((again.tasks_added, again.tasks_existing), (0, 3));
+        assert_eq!(std::fs::read(&other_path)?, untouched);
+        Ok(())
+    }
+}
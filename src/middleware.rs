@@ -0,0 +1,396 @@
+//! Middleware - behavior wrapped around every command
+//!
+//! `run` hands each command to a [`Pipeline`] instead of each arm of
+//! `execute` timing, auditing and dispatching rule events by hand. Layers
+//! see the command before it runs, in the order they were added, and its
+//! result afterwards, in reverse order, so the first layer wraps all others.
+
+use crate::output::emoji;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use colored::*;
+use prd_tool::diff::{open_read_only, TaskDiff};
+use prd_tool::rules::{EventKind, RulesConfig, TaskEvent};
+use prd_tool::telemetry;
+use rusqlite::{Connection, DatabaseName};
+use serde::Serialize;
+use std::cell::RefCell;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// File `prd` appends a JSON line to for every command it runs
+pub const AUDIT_LOG_ENV: &str = "PRD_AUDIT_LOG";
+
+/// One command being run
+pub struct Invocation {
+    /// Subcommand name, e.g. "list"
+    pub command: String,
+    /// Arguments as given, without the program name
+    pub args: Vec<String>,
+    started: Instant,
+    events: RefCell<Vec<(TaskEvent, bool)>>,
+}
+
+impl Invocation {
+    pub fn new(command: &str, args: Vec<String>) -> Self {
+        Self {
+            command: command.to_string(),
+            args,
+            started: Instant::now(),
+            events: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Queue a task event for the automation rules, applied by [`Events`]
+    /// once the command has succeeded; `report` prints each rule applied
+    pub fn emit(&self, kind: EventKind, task_uuid: &str, report: bool) {
+        self.events
+            .borrow_mut()
+            .push((TaskEvent::new(kind, task_uuid), report));
+    }
+
+    fn take_events(&self) -> Vec<(TaskEvent, bool)> {
+        self.events.take()
+    }
+}
+
+/// Behavior run around a command
+pub trait Middleware {
+    /// Runs before the command; an error stops the command from running
+    fn before(&mut self, _invocation: &Invocation) -> Result<()> {
+        Ok(())
+    }
+
+    /// Runs after the command, or after a later layer's `before` failed;
+    /// an error fails a command that had succeeded
+    fn after(&mut self, _invocation: &Invocation, _result: &Result<()>) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Ordered layers of [`Middleware`]
+#[derive(Default)]
+pub struct Pipeline {
+    layers: Vec<Box<dyn Middleware>>,
+}
+
+impl Pipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, layer: impl Middleware + 'static) -> Self {
+        self.layers.push(Box::new(layer));
+        self
+    }
+
+    /// Run `command` inside every layer
+    ///
+    /// Only layers whose `before` ran get `after`, and the command's own
+    /// error wins over errors from `after`.
+    pub fn run(
+        mut self,
+        invocation: &Invocation,
+        command: impl FnOnce(&Invocation) -> Result<()>,
+    ) -> Result<()> {
+        let mut entered = 0;
+        let mut result = Ok(());
+        for layer in &mut self.layers {
+            if let Err(e) = layer.before(invocation) {
+                result = Err(e);
+                break;
+            }
+            entered += 1;
+        }
+        if result.is_ok() {
+            result = command(invocation);
+        }
+        for layer in self.layers[..entered].iter_mut().rev() {
+            let after = layer.after(invocation, &result);
+            if let (Ok(()), Err(e)) = (&result, after) {
+                result = Err(e);
+            }
+        }
+        result
+    }
+}
+
+/// A telemetry span covering the command
+#[derive(Default)]
+pub struct Telemetry {
+    span: Option<telemetry::Span>,
+}
+
+impl Middleware for Telemetry {
+    fn before(&mut self, invocation: &Invocation) -> Result<()> {
+        self.span = Some(telemetry::span(format!("prd {}", invocation.command)));
+        Ok(())
+    }
+
+    fn after(&mut self, _invocation: &Invocation, result: &Result<()>) -> Result<()> {
+        if let (Some(span), Err(e)) = (&mut self.span, result) {
+            span.set_error(e);
+        }
+        self.span = None;
+        Ok(())
+    }
+}
+
+/// Report how long the command took on stderr (`--timing`)
+pub struct Timing;
+
+impl Middleware for Timing {
+    fn after(&mut self, invocation: &Invocation, _result: &Result<()>) -> Result<()> {
+        eprintln!(
+            "{} prd {} took {:.1?}",
+            emoji("⏱").dimmed(),
+            invocation.command,
+            invocation.elapsed()
+        );
+        Ok(())
+    }
+}
+
+/// Append each command, who ran it and how it went to a JSON lines file
+pub struct Audit {
+    path: PathBuf,
+}
+
+#[derive(Serialize)]
+struct AuditEntry<'a> {
+    at: String,
+    command: &'a str,
+    args: Vec<String>,
+    actor: Option<String>,
+    ok: bool,
+    error: Option<String>,
+    duration_ms: u128,
+}
+
+impl Audit {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Audit to the file named by `PRD_AUDIT_LOG`, if set
+    pub fn from_env() -> Option<Self> {
+        std::env::var_os(AUDIT_LOG_ENV)
+            .filter(|path| !path.is_empty())
+            .map(|path| Self::new(PathBuf::from(path)))
+    }
+}
+
+impl Middleware for Audit {
+    fn after(&mut self, invocation: &Invocation, result: &Result<()>) -> Result<()> {
+        // Arguments can carry tokens or passwords, like task descriptions
+        let redactor = prd_tool::redact::Redactor::load();
+        let entry = AuditEntry {
+            at: Utc::now().to_rfc3339(),
+            command: &invocation.command,
+            args: invocation
+                .args
+                .iter()
+                .map(|arg| redactor.redact(arg).into_owned())
+                .collect(),
+            actor: prd_tool::db::current_actor(),
+            ok: result.is_ok(),
+            error: result.as_ref().err().map(|e| format!("{:#}", e)),
+            duration_ms: invocation.elapsed().as_millis(),
+        };
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open audit log {}", self.path.display()))?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        Ok(())
+    }
+}
+
+/// Apply the automation rules to the events a successful command emitted
+pub struct Events {
+    database: PathBuf,
+}
+
+impl Events {
+    pub fn new(database: &Path) -> Self {
+        Self {
+            database: database.to_path_buf(),
+        }
+    }
+}
+
+impl Middleware for Events {
+    fn after(&mut self, invocation: &Invocation, result: &Result<()>) -> Result<()> {
+        let events = invocation.take_events();
+        if result.is_err() || events.is_empty() {
+            return Ok(());
+        }
+        let config = RulesConfig::load()?;
+        if config.rules.is_empty() {
+            return Ok(());
+        }
+        let db = prd_tool::Database::new(self.database.to_str().unwrap())?;
+        for (event, report) in events {
+            for name in config.apply(&db, &event)? {
+                if report {
+                    println!("{} Rule applied: {}", emoji("⚙").cyan(), name);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Run the command against a throwaway copy of the database and show the
+/// task changes it would have made (`--dry-run`), ending with a footer
+/// saying nothing was written
+///
+/// Only database changes are discarded: hooks and notifications still
+/// fire, which is why commands that mainly write files or other replicas
+/// refuse `--dry-run`. The report goes to stderr so `--json` output stays
+/// parseable.
+///
+/// The copy lives in a temporary directory that is removed, with the copy's
+/// WAL files, when the layer is dropped, including when the command panics.
+pub struct DryRun {
+    original: PathBuf,
+    copy: PathBuf,
+    _dir: tempfile::TempDir,
+}
+
+impl DryRun {
+    /// Copy the database, returning the layer and the path to run against
+    pub fn new(database: &Path) -> Result<(Self, PathBuf)> {
+        if !database.is_file() {
+            anyhow::bail!("No database at {} to dry-run against", database.display());
+        }
+        let dir = tempfile::Builder::new()
+            .prefix("prd-dry-run-")
+            .tempdir()
+            .context("Failed to create a directory for the dry-run copy")?;
+        let copy = dir.path().join("prd.db");
+        Connection::open(database)?
+            .backup(DatabaseName::Main, &copy, None)
+            .context("Failed to copy the database for a dry run")?;
+        let layer = Self {
+            original: database.to_path_buf(),
+            copy: copy.clone(),
+            _dir: dir,
+        };
+        Ok((layer, copy))
+    }
+}
+
+impl Middleware for DryRun {
+    fn after(&mut self, _invocation: &Invocation, result: &Result<()>) -> Result<()> {
+        let diff = match result {
+            Ok(()) => Some(TaskDiff::between(
+                &open_read_only(&self.original)?,
+                &open_read_only(&self.copy)?,
+            )?),
+            Err(_) => None,
+        };
+
+        match diff {
+            Some(diff) if !diff.is_empty() => {
+                eprintln!(
+                    "\n{} Dry run: the command would have made these changes:",
+                    "ℹ".blue().bold()
+                );
+                eprintln!("{}", crate::cli::format_task_diff(&diff));
+            }
+            Some(_) => eprintln!("\n{} Dry run: no task changes", "ℹ".blue().bold()),
+            None => {}
+        }
+        // The command's own output reads as if it made real changes
+        eprintln!(
+            "{} dry run: no changes were written to {}",
+            "ℹ".yellow().bold(),
+            self.original.display()
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    struct Record {
+        name: &'static str,
+        fail_before: bool,
+        trace: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl Middleware for Record {
+        fn before(&mut self, _invocation: &Invocation) -> Result<()> {
+            self.trace
+                .borrow_mut()
+                .push(format!("before {}", self.name));
+            if self.fail_before {
+                anyhow::bail!("{} refused", self.name);
+            }
+            Ok(())
+        }
+
+        fn after(&mut self, _invocation: &Invocation, result: &Result<()>) -> Result<()> {
+            let outcome = if result.is_ok() { "ok" } else { "err" };
+            self.trace
+                .borrow_mut()
+                .push(format!("after {} {}", self.name, outcome));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_pipeline_wraps_command_in_layers() {
+        let trace = Rc::new(RefCell::new(Vec::new()));
+        let layer = |name, fail_before| Record {
+            name,
+            fail_before,
+            trace: trace.clone(),
+        };
+        let invocation = Invocation::new("list", Vec::new());
+
+        let result = Pipeline::new()
+            .with(layer("outer", false))
+            .with(layer("inner", false))
+            .run(&invocation, |invocation| {
+                invocation.emit(EventKind::Created, "task", false);
+                trace.borrow_mut().push("command".to_string());
+                Ok(())
+            });
+        assert!(result.is_ok());
+        assert_eq!(invocation.take_events().len(), 1);
+        assert_eq!(
+            trace.take(),
+            [
+                "before outer",
+                "before inner",
+                "command",
+                "after inner ok",
+                "after outer ok"
+            ]
+        );
+
+        // A failing layer stops the command; only entered layers unwind
+        let result = Pipeline::new()
+            .with(layer("outer", false))
+            .with(layer("guard", true))
+            .with(layer("inner", false))
+            .run(&invocation, |_| unreachable!());
+        assert_eq!(result.unwrap_err().to_string(), "guard refused");
+        assert_eq!(
+            trace.take(),
+            ["before outer", "before guard", "after outer err"]
+        );
+    }
+}
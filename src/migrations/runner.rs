@@ -1,8 +1,11 @@
 use anyhow::{Context, Result};
+use regex::Regex;
 use rusqlite::{Connection, OptionalExtension};
 use std::fs;
 use std::path::Path;
 
+use crate::output::emoji;
+
 pub struct MigrationRunner<'a> {
     conn: &'a Connection,
 }
@@ -104,7 +107,7 @@ impl<'a> MigrationRunner<'a> {
 
     fn apply_migration(&self, version: i32, sql: &str) -> Result<()> {
         // Execute the migration SQL
-        self.conn.execute_batch(sql)?;
+        self.conn.execute_batch(&self.skip_existing_columns(sql)?)?;
 
         // Record the migration
         self.conn.execute(
@@ -115,6 +118,29 @@ impl<'a> MigrationRunner<'a> {
         Ok(())
     }
 
+    /// Leave out `ALTER TABLE ... ADD COLUMN` statements for columns that
+    /// already exist, since `Database::new` adds newer columns itself
+    fn skip_existing_columns(&self, sql: &str) -> Result<String> {
+        let add_column =
+            Regex::new(r"(?i)^\s*ALTER\s+TABLE\s+(\w+)\s+ADD\s+COLUMN\s+(\w+)\b[^;]*;\s*$")?;
+        let mut kept = String::with_capacity(sql.len());
+        for line in sql.lines() {
+            if let Some(caps) = add_column.captures(line) {
+                let exists: bool = self.conn.query_row(
+                    "SELECT COUNT(*) FROM pragma_table_info(?1) WHERE name = ?2",
+                    [&caps[1], &caps[2]],
+                    |row| Ok(row.get::<_, i32>(0)? > 0),
+                )?;
+                if exists {
+                    continue;
+                }
+            }
+            kept.push_str(line);
+            kept.push('\n');
+        }
+        Ok(kept)
+    }
+
     pub fn rollback(&self, target_version: i32) -> Result<()> {
         let current_version = self.get_current_version()?;
 
@@ -124,7 +150,8 @@ impl<'a> MigrationRunner<'a> {
         }
 
         println!(
-            "⚠️  Warning: Rollback support is limited. This will only remove the migration record."
+            "{}  Warning: Rollback support is limited. This will only remove the migration record.",
+            emoji("⚠️")
         );
         println!("   Manual intervention may be required to reverse schema changes.");
 
@@ -160,3 +187,30 @@ impl<'a> MigrationRunner<'a> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prd_tool::Database;
+
+    #[test]
+    fn test_migrate_after_database_new() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let db_path = dir.path().join("prd.db");
+        let db = Database::new(db_path.to_str().unwrap())?;
+        let runner = MigrationRunner::new(db.get_connection());
+
+        let applied = runner.migrate_to_latest()?;
+        assert!(applied.contains(&43));
+        assert!(runner.migrate_to_latest()?.is_empty());
+
+        let added_rows: i32 = db.get_connection().query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('operations') WHERE name = 'added_rows'",
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(added_rows, 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,124 @@
+use crate::blocked::block_reason;
+use crate::db::{Agent, AgentStatus, Database, StatusBucket, Task};
+use anyhow::Result;
+use std::collections::HashMap;
+
+/// An agent that just went blocked, or offline while holding work
+#[derive(Debug, Clone)]
+pub struct AgentAlert {
+    pub agent: Agent,
+    /// The agent's current task, or another unfinished task assigned to it
+    pub task: Option<Task>,
+    /// Latest blocking reason logged on the task
+    pub reason: Option<String>,
+}
+
+/// Detects agents moving into error states between polls
+///
+/// Going blocked always alerts. Going offline only alerts when the agent
+/// still holds a started task; an idle agent signing off is expected.
+pub struct AgentAlertTracker {
+    last_status: HashMap<String, AgentStatus>,
+}
+
+impl AgentAlertTracker {
+    /// Start tracking from the agents' current statuses, so agents that were
+    /// already blocked or offline don't alert
+    pub fn new(db: &Database) -> Result<Self> {
+        let last_status = db
+            .list_agents()?
+            .into_iter()
+            .map(|a| (a.id, a.status))
+            .collect();
+        Ok(Self { last_status })
+    }
+
+    /// Agents that went blocked or unexpectedly offline since the last call
+    pub fn detect(&mut self, db: &Database) -> Result<Vec<AgentAlert>> {
+        let workflow = db.workflow()?;
+        let mut alerts = Vec::new();
+
+        for agent in db.list_agents()? {
+            let previous = self
+                .last_status
+                .insert(agent.id.clone(), agent.status.clone());
+            if previous.as_ref() == Some(&agent.status)
+                || !matches!(agent.status, AgentStatus::Blocked | AgentStatus::Offline)
+            {
+                continue;
+            }
+
+            let current = match &agent.current_task_id {
+                Some(id) => db.get_task(id)?,
+                None => None,
+            };
+            let task = match current {
+                Some(task) => Some(task),
+                None => db
+                    .list_tasks(None)?
+                    .into_iter()
+                    .filter(|t| t.assigned_agent.as_deref() == Some(agent.id.as_str()))
+                    .find(|t| workflow.bucket(&t.status) == StatusBucket::Active),
+            };
+            if agent.status == AgentStatus::Offline && task.is_none() {
+                continue;
+            }
+
+            let reason = match &task {
+                Some(task) => block_reason(db, &task.id, None)?,
+                None => None,
+            };
+            alerts.push(AgentAlert {
+                agent,
+                task,
+                reason,
+            });
+        }
+
+        Ok(alerts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Priority, TaskStatus};
+
+    #[test]
+    fn test_alerts_on_blocked_and_unexpected_offline() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        let worker = db.create_agent("worker".to_string())?;
+        let idle = db.create_agent("idle".to_string())?;
+        let task = db.create_task("Migrate".to_string(), None, Priority::High, None, None)?;
+        db.assign_task(&task.id, &worker.id)?;
+        db.update_task_status(&task.id, TaskStatus::InProgress, Some(&worker.id))?;
+        db.update_agent_status(&worker.id, AgentStatus::Working, Some(&task.id))?;
+
+        let mut tracker = AgentAlertTracker::new(&db)?;
+        assert!(tracker.detect(&db)?.is_empty());
+
+        db.log_task_action(
+            &task.id,
+            Some(&worker.id),
+            "blocked",
+            Some("Waiting on DBA"),
+        )?;
+        db.update_agent_status(&worker.id, AgentStatus::Blocked, Some(&task.id))?;
+        db.update_agent_status(&idle.id, AgentStatus::Offline, None)?;
+        let alerts = tracker.detect(&db)?;
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].agent.id, worker.id);
+        assert_eq!(alerts[0].reason.as_deref(), Some("Waiting on DBA"));
+        // Still blocked: no repeat
+        assert!(tracker.detect(&db)?.is_empty());
+
+        db.update_agent_status(&worker.id, AgentStatus::Offline, None)?;
+        let alerts = tracker.detect(&db)?;
+        assert_eq!(alerts[0].agent.status, AgentStatus::Offline);
+        assert_eq!(
+            alerts[0].task.as_ref().map(|t| t.id.as_str()),
+            Some(task.id.as_str())
+        );
+        Ok(())
+    }
+}
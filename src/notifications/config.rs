@@ -1,15 +1,20 @@
+use crate::db::Database;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 
+/// Setting holding the notification config.toml shared through the database
+pub const SETTING_NOTIFICATIONS_CONFIG: &str = "notifications.config";
+
 /// Configuration for desktop notifications
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub struct NotificationConfig {
     /// Whether notifications are enabled
     pub enabled: bool,
 
-    /// Types of events to notify about: "complete", "error", "milestone"
+    /// Types of events to notify about: "complete", "error", "milestone",
+    /// "blocked", "overdue"
     pub events: Vec<String>,
 
     /// Whether to play sound with notifications
@@ -30,6 +35,8 @@ impl Default for NotificationConfig {
                 "complete".to_string(),
                 "error".to_string(),
                 "milestone".to_string(),
+                "blocked".to_string(),
+                "overdue".to_string(),
             ],
             sound: true,
             min_priority: "medium".to_string(),
@@ -53,13 +60,24 @@ impl NotificationConfig {
             let content = fs::read_to_string(&config_path)
                 .context(format!("Failed to read config from {:?}", config_path))?;
 
-            let config: Self =
-                toml::from_str(&content).context("Failed to parse config file as TOML")?;
+            Self::from_toml(&content)
+        }
+    }
 
-            Ok(config)
+    /// Load the configuration shared in `db` (`prd hooks push`), or the
+    /// local file when the database has none
+    pub fn load_shared(db: &Database) -> Result<Self> {
+        match db.get_setting(SETTING_NOTIFICATIONS_CONFIG)? {
+            Some(content) => Self::from_toml(&content),
+            None => Self::load(),
         }
     }
 
+    /// Parse config.toml content
+    pub fn from_toml(content: &str) -> Result<Self> {
+        toml::from_str(content).context("Failed to parse config file as TOML")
+    }
+
     /// Save configuration to the standard location
     pub fn save(&self) -> Result<()> {
         let config_path = Self::get_config_path()?;
@@ -103,10 +121,12 @@ mod tests {
     fn test_default_config() {
         let config = NotificationConfig::default();
         assert!(config.enabled);
-        assert_eq!(config.events.len(), 3);
+        assert_eq!(config.events.len(), 5);
         assert!(config.events.contains(&"complete".to_string()));
         assert!(config.events.contains(&"error".to_string()));
         assert!(config.events.contains(&"milestone".to_string()));
+        assert!(config.events.contains(&"blocked".to_string()));
+        assert!(config.events.contains(&"overdue".to_string()));
         assert!(config.sound);
         assert_eq!(config.min_priority, "medium");
         assert_eq!(config.rate_limit_seconds, 60);
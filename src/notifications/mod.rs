@@ -3,13 +3,16 @@
 //! This module provides desktop notifications for important events during
 //! `prd watch` dashboard monitoring, including:
 //! - Task completions
-//! - Agent errors
+//! - Agent errors (an agent going blocked, or offline while holding work)
 //! - Project milestones (25%, 50%, 75%, 100%)
+//! - Tasks going past their due date
 //!
 //! Configuration is loaded from `~/.prd/config.toml` with sensible defaults.
 
+pub mod alerts;
 pub mod config;
 pub mod notifier;
 
+pub use alerts::{AgentAlert, AgentAlertTracker};
 pub use config::NotificationConfig;
-pub use notifier::Notifier;
+pub use notifier::{Notifier, SentNotification};
@@ -1,10 +1,23 @@
+use super::alerts::AgentAlert;
 use super::config::NotificationConfig;
-use crate::db::{Agent, Task};
+use crate::db::{Agent, AgentStatus, Database, Task};
+use crate::due::DueTask;
 use anyhow::Result;
+use chrono::{DateTime, Utc};
+#[cfg(feature = "notifications")]
 use notify_rust::{Notification, Timeout};
+use serde::Serialize;
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
 
+/// A notification that was fired (whether or not the desktop accepted it)
+#[derive(Debug, Clone, Serialize)]
+pub struct SentNotification {
+    pub timestamp: DateTime<Utc>,
+    pub title: String,
+    pub body: String,
+}
+
 /// Desktop notification manager
 pub struct Notifier {
     config: NotificationConfig,
@@ -12,6 +25,8 @@ pub struct Notifier {
     last_notification: HashMap<String, Instant>,
     /// Track which milestones have been triggered to prevent duplicates
     milestone_triggered: HashMap<u8, bool>,
+    /// Notifications fired during this notifier's lifetime
+    sent: Vec<SentNotification>,
 }
 
 impl Notifier {
@@ -21,6 +36,7 @@ impl Notifier {
             config,
             last_notification: HashMap::new(),
             milestone_triggered: HashMap::new(),
+            sent: Vec::new(),
         }
     }
 
@@ -30,6 +46,13 @@ impl Notifier {
         Ok(Self::new(config))
     }
 
+    /// Create a notifier with the configuration shared in `db`, falling back
+    /// to the one on disk
+    pub fn with_shared_config(db: &Database) -> Result<Self> {
+        let config = NotificationConfig::load_shared(db)?;
+        Ok(Self::new(config))
+    }
+
     /// Notify about a completed task
     pub fn notify_task_complete(&mut self, task: &Task, agent: &Agent) -> Result<()> {
         if !self.should_notify("complete", &agent.id) {
@@ -68,6 +91,104 @@ impl Notifier {
         Ok(())
     }
 
+    /// Notify that an agent went blocked, or offline while holding work
+    pub fn notify_agent_alert(&mut self, alert: &AgentAlert) -> Result<()> {
+        let agent = &alert.agent;
+        if !self.should_notify("error", &agent.id) {
+            return Ok(());
+        }
+
+        let (title, state) = match agent.status {
+            AgentStatus::Offline => ("🔌 Agent Offline!", "went offline"),
+            _ => ("⛔ Agent Blocked!", "is blocked"),
+        };
+        let mut body = format!("Agent {} {}", agent.name, state);
+        if let Some(task) = &alert.task {
+            body.push_str(&format!(
+                " on task #{}: {}",
+                task.display_id.unwrap_or(0),
+                task.title
+            ));
+        }
+        if let Some(reason) = &alert.reason {
+            body.push_str(&format!(" ({})", reason));
+        }
+
+        self.send_notification(title, &body)?;
+        self.update_last_notification(&agent.id);
+        Ok(())
+    }
+
+    /// Nudge an agent about a task that has gone stale
+    ///
+    /// Sent on request (`prd stale --nudge`), so only the `enabled` switch
+    /// applies, not the event list or rate limit.
+    pub fn notify_stale(&mut self, task: &Task, agent: &Agent, reason: &str) -> Result<()> {
+        if !self.config.enabled {
+            return Ok(());
+        }
+
+        let title = "⏰ Stale Task";
+        let body = format!(
+            "Agent {}, task #{} needs attention: {} ({})",
+            agent.name,
+            task.display_id.unwrap_or(0),
+            task.title,
+            reason
+        );
+
+        self.send_notification(title, &body)
+    }
+
+    /// Notify that a task has stayed blocked past the blocked SLA
+    ///
+    /// Callers alert once per blocked spell, so the per-agent rate limit
+    /// doesn't apply.
+    pub fn notify_blocked_too_long(
+        &mut self,
+        task: &Task,
+        blocked_for: &str,
+        reason: Option<&str>,
+    ) -> Result<()> {
+        if !self.config.is_event_enabled("blocked") {
+            return Ok(());
+        }
+
+        let title = "⏱ Blocked Too Long";
+        let body = format!(
+            "Task #{} has been blocked for {}: {} ({})",
+            task.display_id.unwrap_or(0),
+            blocked_for,
+            task.title,
+            reason.unwrap_or("no reason recorded")
+        );
+
+        self.send_notification(title, &body)
+    }
+
+    /// Notify that a task went past its due date
+    ///
+    /// Callers alert once per deadline, so the per-agent rate limit doesn't
+    /// apply.
+    pub fn notify_overdue(&mut self, due: &DueTask) -> Result<()> {
+        if !self.config.is_event_enabled("overdue") {
+            return Ok(());
+        }
+
+        let title = "📅 Task Overdue";
+        let mut body = format!(
+            "Task #{} was due {}: {}",
+            due.task.display_id.unwrap_or(0),
+            due.due,
+            due.task.title
+        );
+        if let Some(agent) = &due.agent {
+            body.push_str(&format!(" (assigned to {})", agent));
+        }
+
+        self.send_notification(title, &body)
+    }
+
     /// Notify about a milestone reached
     pub fn notify_milestone(&mut self, percentage: u8, completed: i32, total: i32) -> Result<()> {
         if !self.config.is_event_enabled("milestone") {
@@ -119,7 +240,13 @@ impl Notifier {
     }
 
     /// Send a desktop notification
-    fn send_notification(&self, title: &str, body: &str) -> Result<()> {
+    fn send_notification(&mut self, title: &str, body: &str) -> Result<()> {
+        self.sent.push(SentNotification {
+            timestamp: Utc::now(),
+            title: title.to_string(),
+            body: body.to_string(),
+        });
+
         // Try to send notification, but don't fail if notification system is unavailable
         match self.try_send_notification(title, body) {
             Ok(_) => Ok(()),
@@ -132,6 +259,7 @@ impl Notifier {
     }
 
     /// Actually try to send the notification (may fail on some systems)
+    #[cfg(feature = "notifications")]
     fn try_send_notification(&self, title: &str, body: &str) -> Result<()> {
         let mut notification = Notification::new();
         notification
@@ -148,6 +276,12 @@ impl Notifier {
         Ok(())
     }
 
+    /// Built without desktop notifications, so they are only recorded
+    #[cfg(not(feature = "notifications"))]
+    fn try_send_notification(&self, _title: &str, _body: &str) -> Result<()> {
+        Ok(())
+    }
+
     /// Update the last notification time for an agent
     fn update_last_notification(&mut self, agent_id: &str) {
         self.last_notification
@@ -159,6 +293,11 @@ impl Notifier {
         &self.config
     }
 
+    /// Notifications fired so far
+    pub fn sent(&self) -> &[SentNotification] {
+        &self.sent
+    }
+
     /// Reset milestone triggers (useful for testing)
     #[allow(dead_code)]
     pub fn reset_milestones(&mut self) {
@@ -175,7 +314,7 @@ impl Notifier {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::db::{AgentStatus, Priority, TaskStatus};
+    use crate::db::{Priority, TaskStatus};
     use chrono::Utc;
 
     fn create_test_config() -> NotificationConfig {
@@ -220,6 +359,7 @@ mod tests {
             estimated_duration: None,
             actual_duration: None,
             epic_name: None,
+            due_date: None,
         }
     }
 
@@ -356,6 +496,10 @@ mod tests {
 
         // Verify last notification was updated
         assert!(notifier.last_notification.contains_key(&agent.id));
+
+        // Verify the notification was recorded
+        assert_eq!(notifier.sent().len(), 1);
+        assert!(notifier.sent()[0].body.contains("Test Task"));
     }
 
     #[test]
@@ -373,6 +517,49 @@ mod tests {
         assert!(notifier.last_notification.contains_key(&agent.id));
     }
 
+    #[test]
+    fn test_notify_agent_alert() {
+        let mut notifier = Notifier::new(create_test_config());
+        let mut agent = create_test_agent();
+        agent.status = AgentStatus::Offline;
+        let alert = AgentAlert {
+            agent,
+            task: Some(create_test_task()),
+            reason: Some("Waiting on credentials".to_string()),
+        };
+
+        notifier.notify_agent_alert(&alert).unwrap();
+        assert_eq!(notifier.sent().len(), 1);
+        assert_eq!(
+            notifier.sent()[0].body,
+            "Agent Test Agent went offline on task #1: Test Task (Waiting on credentials)"
+        );
+    }
+
+    #[test]
+    fn test_notify_overdue() {
+        let mut config = create_test_config();
+        let due = DueTask {
+            task: create_test_task(),
+            due: chrono::NaiveDate::from_ymd_opt(2026, 3, 1).unwrap(),
+            days_left: -1,
+            agent: Some("Test Agent".to_string()),
+        };
+
+        // Not in the configured events
+        let mut notifier = Notifier::new(config.clone());
+        notifier.notify_overdue(&due).unwrap();
+        assert!(notifier.sent().is_empty());
+
+        config.events.push("overdue".to_string());
+        let mut notifier = Notifier::new(config);
+        notifier.notify_overdue(&due).unwrap();
+        assert_eq!(
+            notifier.sent()[0].body,
+            "Task #1 was due 2026-03-01: Test Task (assigned to Test Agent)"
+        );
+    }
+
     #[test]
     fn test_clear_rate_limits() {
         let config = create_test_config();
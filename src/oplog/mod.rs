@@ -0,0 +1,1349 @@
+//! Append-only operation log for replicating a database between machines
+//!
+//! Triggers record every insert and delete as a row operation and every
+//! changed column as a cell operation, stamped with a Lamport clock and the
+//! replica that made it. Replicas exchange the operations the other side is
+//! missing (found by comparing version vectors) and apply them
+//! last-writer-wins per row and per cell, ordered by (clock, replica, id), so
+//! any replica that has seen the same operations holds the same data no
+//! matter the order they arrived in.
+//!
+//! Cell operations keep the value they replaced, and deletes the row they
+//! removed, so a change can be reverted by recording its inverse.
+
+pub mod remote;
+
+use anyhow::{Context, Result};
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+pub use remote::{pull, push, Remote, Side};
+
+/// Bump when the trigger definitions change so existing databases reinstall them
+const OPLOG_VERSION: &str = "14";
+
+/// A replicated table and how its rows are identified across replicas
+struct Tracked {
+    table: &'static str,
+    /// Key expression over the row alias `r`; display IDs differ between
+    /// replicas, so rows are keyed by task UUIDs
+    key: &'static str,
+    /// JSON object of the full row over `r`, used to insert it elsewhere
+    row: &'static str,
+    /// Row filter for applying an operation, with the key as ?1
+    target: &'static str,
+    /// Columns replicated as last-writer-wins cell updates
+    columns: &'static [&'static str],
+    /// Replicated columns with a unique index; a value another row already
+    /// holds here is rejected rather than failing the whole batch
+    unique: &'static [&'static str],
+}
+
+const TRACKED: &[Tracked] = &[
+    Tracked {
+        table: "tasks",
+        key: "r.id",
+        row: "json_object('id', r.id, 'display_id', r.display_id, 'title', r.title,
+              'description', r.description, 'status', r.status, 'priority', r.priority,
+              'parent_id', r.parent_id, 'assigned_agent', r.assigned_agent,
+              'created_at', r.created_at, 'updated_at', r.updated_at,
+              'completed_at', r.completed_at, 'estimated_duration', r.estimated_duration,
+              'actual_duration', r.actual_duration, 'epic_name', r.epic_name,
+              'business_value', r.business_value, 'effort', r.effort, 'due_date', r.due_date,
+              'points', r.points, 'start_after', r.start_after, 'external_id', r.external_id,
+              'display_key', r.display_key, 'recurrence', r.recurrence,
+              'recurs_from', r.recurs_from, 'attempt', r.attempt, 'deleted_at', r.deleted_at)",
+        target: "id = ?1",
+        columns: &[
+            "title",
+            "description",
+            "status",
+            "priority",
+            "parent_id",
+            "assigned_agent",
+            "updated_at",
+            "completed_at",
+            "estimated_duration",
+            "actual_duration",
+            "epic_name",
+            "business_value",
+            "effort",
+            "due_date",
+            "points",
+            "start_after",
+            "external_id",
+            "display_key",
+            "recurrence",
+            "recurs_from",
+            "attempt",
+            // Trashing and restoring a task replicate like any other edit
+            "deleted_at",
+        ],
+        unique: &["external_id", "display_key"],
+    },
+    Tracked {
+        table: "agents",
+        key: "r.id",
+        row: "json_object('id', r.id, 'display_id', r.display_id, 'name', r.name,
+              'status', r.status, 'current_task_id', r.current_task_id,
+              'created_at', r.created_at, 'last_active', r.last_active)",
+        target: "id = ?1",
+        columns: &["status", "current_task_id", "last_active"],
+        unique: &[],
+    },
+    Tracked {
+        table: "acceptance_criteria",
+        key: "json_array((SELECT id FROM tasks WHERE display_id = r.task_display_id), r.criterion)",
+        row: "json_object('task', (SELECT id FROM tasks WHERE display_id = r.task_display_id),
+              'criterion', r.criterion, 'completed', r.completed,
+              'created_at', r.created_at, 'completed_at', r.completed_at)",
+        target:
+            "task_display_id = (SELECT display_id FROM tasks WHERE id = json_extract(?1, '$[0]'))
+                 AND criterion = json_extract(?1, '$[1]')",
+        columns: &["completed", "completed_at"],
+        unique: &[],
+    },
+    Tracked {
+        table: "task_dependencies",
+        key: "json_array((SELECT id FROM tasks WHERE display_id = r.task_display_id),
+                         (SELECT id FROM tasks WHERE display_id = r.depends_on_display_id))",
+        row: "json_object('task', (SELECT id FROM tasks WHERE display_id = r.task_display_id),
+              'depends_on', (SELECT id FROM tasks WHERE display_id = r.depends_on_display_id),
+              'dependency_type', r.dependency_type, 'created_at', r.created_at)",
+        target:
+            "task_display_id = (SELECT display_id FROM tasks WHERE id = json_extract(?1, '$[0]'))
+                 AND depends_on_display_id =
+                     (SELECT display_id FROM tasks WHERE id = json_extract(?1, '$[1]'))",
+        columns: &[],
+        unique: &[],
+    },
+    Tracked {
+        table: "task_tags",
+        key: "json_array(r.task_id, r.tag)",
+        row: "json_object('task_id', r.task_id, 'tag', r.tag, 'created_at', r.created_at)",
+        target: "task_id = json_extract(?1, '$[0]') AND tag = json_extract(?1, '$[1]')",
+        columns: &[],
+        unique: &[],
+    },
+    Tracked {
+        table: "task_comments",
+        key: "json_array(r.task_id, r.created_at)",
+        row: "json_object('task_id', r.task_id, 'agent_id', r.agent_id, 'actor', r.actor,
+              'body', r.body, 'created_at', r.created_at)",
+        target: "task_id = json_extract(?1, '$[0]') AND created_at = json_extract(?1, '$[1]')",
+        columns: &[],
+        unique: &[],
+    },
+    Tracked {
+        table: "task_attachments",
+        key: "json_array(r.task_id, r.created_at)",
+        row: "json_object('task_id', r.task_id, 'location', r.location, 'label', r.label,
+              'agent_id', r.agent_id, 'actor', r.actor, 'created_at', r.created_at)",
+        target: "task_id = json_extract(?1, '$[0]') AND created_at = json_extract(?1, '$[1]')",
+        columns: &[],
+        unique: &[],
+    },
+    Tracked {
+        table: "task_logs",
+        key: "r.id",
+        row: "json_object('id', r.id, 'task_id', r.task_id, 'agent_id', r.agent_id,
+              'action', r.action, 'details', r.details, 'created_at', r.created_at,
+              'actor', r.actor)",
+        target: "id = ?1",
+        columns: &[],
+        unique: &[],
+    },
+    Tracked {
+        table: "epic_dependencies",
+        key: "json_array(r.epic_name, r.depends_on_epic)",
+        row: "json_object('epic_name', r.epic_name, 'depends_on_epic', r.depends_on_epic,
+              'threshold', r.threshold, 'created_at', r.created_at)",
+        target:
+            "epic_name = json_extract(?1, '$[0]') AND depends_on_epic = json_extract(?1, '$[1]')",
+        columns: &["threshold"],
+        unique: &[],
+    },
+    Tracked {
+        table: "epic_targets",
+        key: "r.epic_name",
+        row: "json_object('epic_name', r.epic_name, 'target_date', r.target_date,
+              'updated_at', r.updated_at)",
+        target: "epic_name = ?1",
+        columns: &["target_date", "updated_at"],
+        unique: &[],
+    },
+];
+
+/// Values shared by every recorded operation
+const OP_COLUMNS: &str = "lower(hex(randomblob(16))),
+    (SELECT COALESCE(MAX(clock), 0) + 1 FROM oplog),
+    (SELECT value FROM oplog_meta WHERE key = 'replica_id')";
+
+/// One recorded change
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Op {
+    pub id: String,
+    /// Lamport clock: greater than every operation this replica had seen
+    pub clock: i64,
+    pub replica: String,
+    pub table: String,
+    pub key: String,
+    /// Changed column, or None for a row insert or delete
+    pub column: Option<String>,
+    /// New column value, the JSON row for inserts, or null for deletes
+    pub value: Value,
+    /// Value the change replaced: the old column value, or the deleted row
+    #[serde(default)]
+    pub prev: Value,
+    pub created_at: String,
+}
+
+impl Op {
+    /// Whether this operation removes its row
+    pub fn is_delete(&self) -> bool {
+        self.column.is_none() && self.value.is_null()
+    }
+
+    /// Sort key for last-writer-wins
+    fn order(&self) -> (i64, &str, &str) {
+        (self.clock, &self.replica, &self.id)
+    }
+
+    /// The cell this operation writes, if it is an update
+    pub fn cell(&self) -> Option<(&str, &str, &str)> {
+        Some((&self.table, &self.key, self.column.as_deref()?))
+    }
+}
+
+/// Latest clock seen from each replica
+///
+/// A replica's clocks only grow and its operations are always exchanged
+/// together, so a database has everything a replica made up to that clock.
+pub type VersionVector = BTreeMap<String, i64>;
+
+/// A replica's ID and what it has seen, exchanged before operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplicaState {
+    pub replica: String,
+    pub vector: VersionVector,
+}
+
+impl ReplicaState {
+    pub fn load(conn: &Connection) -> Result<Self> {
+        Ok(Self {
+            replica: replica_id(conn)?,
+            vector: version_vector(conn)?,
+        })
+    }
+}
+
+/// `replica:clock,...` form of a version vector, for URLs
+pub fn encode_vector(vector: &VersionVector) -> String {
+    vector
+        .iter()
+        .map(|(replica, clock)| format!("{}:{}", replica, clock))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Parse a version vector written by [`encode_vector`]
+pub fn decode_vector(text: &str) -> Result<VersionVector> {
+    text.split(',')
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (replica, clock) = entry
+                .rsplit_once(':')
+                .with_context(|| format!("Invalid version vector entry: {}", entry))?;
+            Ok((replica.to_string(), clock.parse()?))
+        })
+        .collect()
+}
+
+/// Result of applying a batch of operations
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ApplyReport {
+    pub applied: usize,
+    /// Operations the database already had
+    pub skipped: usize,
+    /// Tasks whose display ID was taken here: (task UUID, remote ID, new ID)
+    pub renumbered: Vec<(String, i32, i32)>,
+    /// Rows that couldn't be inserted, with the reason
+    pub rejected: Vec<String>,
+}
+
+/// Create the log tables and install the recording triggers
+///
+/// Called on every open; triggers are only (re)installed when
+/// `OPLOG_VERSION` changes, and skipped on legacy databases that are missing
+/// tracked columns until they are migrated.
+pub fn init_schema(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS oplog (
+            id TEXT PRIMARY KEY,
+            clock INTEGER NOT NULL,
+            replica TEXT NOT NULL,
+            tbl TEXT NOT NULL,
+            row_key TEXT NOT NULL,
+            col TEXT,
+            value,
+            prev,
+            created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+        );
+        CREATE INDEX IF NOT EXISTS idx_oplog_clock ON oplog(clock);
+        CREATE INDEX IF NOT EXISTS idx_oplog_replica ON oplog(replica, clock);
+        CREATE INDEX IF NOT EXISTS idx_oplog_cell ON oplog(tbl, row_key, col, clock);
+
+        -- Replica ID and installed trigger version
+        CREATE TABLE IF NOT EXISTS oplog_meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+
+        -- Holds a row while remote operations are applied, to pause recording
+        CREATE TABLE IF NOT EXISTS oplog_state (applying INTEGER NOT NULL);",
+    )?;
+    let has_prev: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM pragma_table_info('oplog') WHERE name = 'prev'",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_prev {
+        conn.execute_batch("ALTER TABLE oplog ADD COLUMN prev")?;
+    }
+
+    let installed: Option<String> = conn
+        .query_row(
+            "SELECT value FROM oplog_meta WHERE key = 'oplog_version'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?;
+    if installed.is_some() {
+        track_path(conn)?;
+    }
+    if installed.as_deref() == Some(OPLOG_VERSION) || !has_tracked_columns(conn)? {
+        return Ok(());
+    }
+
+    let tx = rusqlite::Transaction::new_unchecked(conn, rusqlite::TransactionBehavior::Immediate)?;
+    tx.execute(
+        "INSERT OR IGNORE INTO oplog_meta (key, value) VALUES ('replica_id', ?1)",
+        params![uuid::Uuid::new_v4().to_string()],
+    )?;
+
+    for tracked in TRACKED {
+        tx.execute_batch(&format!(
+            "DROP TRIGGER IF EXISTS oplog_{t}_insert;
+             DROP TRIGGER IF EXISTS oplog_{t}_update;
+             DROP TRIGGER IF EXISTS oplog_{t}_delete;",
+            t = tracked.table
+        ))?;
+        tx.execute_batch(&insert_trigger(tracked))?;
+        tx.execute_batch(&delete_trigger(tracked))?;
+        if !tracked.columns.is_empty() {
+            tx.execute_batch(&update_trigger(tracked))?;
+        }
+
+        // Existing rows of newly tracked tables become clock-0 inserts so a
+        // first sync carries them
+        let backfill: bool = tx.query_row(
+            "SELECT NOT EXISTS(SELECT 1 FROM oplog WHERE tbl = ?1)",
+            params![tracked.table],
+            |r| r.get(0),
+        )?;
+        if backfill {
+            tx.execute(
+                &format!(
+                    "INSERT INTO oplog (id, clock, replica, tbl, row_key, col, value)
+                     SELECT lower(hex(randomblob(16))), 0,
+                            (SELECT value FROM oplog_meta WHERE key = 'replica_id'),
+                            '{t}', {key}, NULL, {row}
+                     FROM {t} AS r",
+                    t = tracked.table,
+                    key = tracked.key,
+                    row = tracked.row
+                ),
+                [],
+            )?;
+        }
+    }
+
+    tx.execute(
+        "INSERT OR REPLACE INTO oplog_meta (key, value) VALUES ('oplog_version', ?1)",
+        params![OPLOG_VERSION],
+    )?;
+    tx.commit()?;
+    track_path(conn)?;
+    Ok(())
+}
+
+/// Re-key the replica when its file was copied (or moved) since the last open
+///
+/// A copy would otherwise record operations under its original's replica ID,
+/// which version vectors can't tell apart.
+fn track_path(conn: &Connection) -> Result<()> {
+    let Some(path) = conn.path().filter(|p| !p.is_empty()) else {
+        return Ok(());
+    };
+    let recorded: Option<String> = conn
+        .query_row(
+            "SELECT value FROM oplog_meta WHERE key = 'replica_path'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?;
+    if recorded.as_deref() == Some(path) {
+        return Ok(());
+    }
+    if recorded.is_some() {
+        reset_replica_id(conn)?;
+    }
+    conn.execute(
+        "INSERT OR REPLACE INTO oplog_meta (key, value) VALUES ('replica_path', ?1)",
+        params![path],
+    )?;
+    Ok(())
+}
+
+fn has_tracked_columns(conn: &Connection) -> Result<bool> {
+    for (table, column) in [
+        ("tasks", "epic_name"),
+        ("tasks", "display_id"),
+        ("tasks", "deleted_at"),
+        ("agents", "display_id"),
+        ("task_logs", "actor"),
+    ] {
+        let exists: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info(?1) WHERE name = ?2",
+            params![table, column],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+fn insert_trigger(tracked: &Tracked) -> String {
+    format!(
+        "CREATE TRIGGER oplog_{t}_insert AFTER INSERT ON {t}
+         WHEN NOT EXISTS (SELECT 1 FROM oplog_state)
+         BEGIN
+             INSERT INTO oplog (id, clock, replica, tbl, row_key, col, value)
+             VALUES ({ops}, '{t}', {key}, NULL, {row});
+         END;",
+        t = tracked.table,
+        ops = OP_COLUMNS,
+        key = tracked.key.replace("r.", "NEW."),
+        row = tracked.row.replace("r.", "NEW."),
+    )
+}
+
+fn delete_trigger(tracked: &Tracked) -> String {
+    format!(
+        "CREATE TRIGGER oplog_{t}_delete AFTER DELETE ON {t}
+         WHEN NOT EXISTS (SELECT 1 FROM oplog_state)
+         BEGIN
+             INSERT INTO oplog (id, clock, replica, tbl, row_key, col, value, prev)
+             VALUES ({ops}, '{t}', {key}, NULL, NULL, {row});
+         END;",
+        t = tracked.table,
+        ops = OP_COLUMNS,
+        key = tracked.key.replace("r.", "OLD."),
+        row = tracked.row.replace("r.", "OLD."),
+    )
+}
+
+fn update_trigger(tracked: &Tracked) -> String {
+    let key = tracked.key.replace("r.", "NEW.");
+    let statements: String = tracked
+        .columns
+        .iter()
+        .map(|col| {
+            format!(
+                "INSERT INTO oplog (id, clock, replica, tbl, row_key, col, value, prev)
+                 SELECT {ops}, '{t}', {key}, '{col}', NEW.{col}, OLD.{col}
+                 WHERE OLD.{col} IS NOT NEW.{col};\n",
+                ops = OP_COLUMNS,
+                t = tracked.table,
+            )
+        })
+        .collect();
+    format!(
+        "CREATE TRIGGER oplog_{t}_update AFTER UPDATE ON {t}
+         WHEN NOT EXISTS (SELECT 1 FROM oplog_state)
+         BEGIN
+             {statements}
+         END;",
+        t = tracked.table,
+    )
+}
+
+/// This database's replica ID
+pub fn replica_id(conn: &Connection) -> Result<String> {
+    conn.query_row(
+        "SELECT value FROM oplog_meta WHERE key = 'replica_id'",
+        [],
+        |row| row.get(0),
+    )
+    .optional()?
+    .context("Operation log is not installed; run `prd migrate latest` first")
+}
+
+/// Human-readable name for a row key, e.g. `#12 Add login` or `A3 (agent-1)`
+pub fn describe_key(conn: &Connection, table: &str, key: &str) -> String {
+    let task = |uuid: &str| -> Option<String> {
+        conn.query_row(
+            "SELECT display_id, title FROM tasks WHERE id = ?1",
+            params![uuid],
+            |row| {
+                Ok(format!(
+                    "#{} {}",
+                    row.get::<_, i32>(0)?,
+                    row.get::<_, String>(1)?
+                ))
+            },
+        )
+        .ok()
+    };
+    let described = match table {
+        "tasks" => task(key),
+        "agents" => conn
+            .query_row(
+                "SELECT display_id, name FROM agents WHERE id = ?1",
+                params![key],
+                |row| {
+                    Ok(format!(
+                        "A{} ({})",
+                        row.get::<_, i32>(0)?,
+                        row.get::<_, String>(1)?
+                    ))
+                },
+            )
+            .ok(),
+        "task_logs" => conn
+            .query_row(
+                "SELECT task_id FROM task_logs WHERE id = ?1",
+                params![key],
+                |row| row.get::<_, String>(0),
+            )
+            .ok()
+            .and_then(|uuid| task(&uuid)),
+        "acceptance_criteria" => serde_json::from_str::<(String, String)>(key)
+            .ok()
+            .map(|(uuid, criterion)| format!("{}: {}", task(&uuid).unwrap_or(uuid), criterion)),
+        _ => None,
+    };
+    described.unwrap_or_else(|| key.to_string())
+}
+
+/// Give this database a fresh replica ID
+///
+/// Used when a sync finds the other side has the same ID, i.e. one database
+/// was copied from the other. Operations already recorded keep the old ID.
+pub fn reset_replica_id(conn: &Connection) -> Result<String> {
+    let replica = uuid::Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT OR REPLACE INTO oplog_meta (key, value) VALUES ('replica_id', ?1)",
+        params![replica],
+    )?;
+    Ok(replica)
+}
+
+/// Latest clock per replica
+pub fn version_vector(conn: &Connection) -> Result<VersionVector> {
+    let mut stmt = conn.prepare("SELECT replica, MAX(clock) FROM oplog GROUP BY replica")?;
+    let vector = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<VersionVector, _>>()?;
+    Ok(vector)
+}
+
+const SELECT_OPS: &str =
+    "SELECT id, clock, replica, tbl, row_key, col, value, prev, created_at FROM oplog";
+
+fn query_ops(conn: &Connection, filter: &str, params: impl rusqlite::Params) -> Result<Vec<Op>> {
+    let mut stmt = conn.prepare(&format!(
+        "{} {} ORDER BY clock, replica, id",
+        SELECT_OPS, filter
+    ))?;
+    let ops = stmt
+        .query_map(params, |row| {
+            let column: Option<String> = row.get(5)?;
+            // Row operations store the whole row as JSON text
+            let decode = |value: SqlValue| match (&column, value) {
+                (None, SqlValue::Text(text)) => {
+                    serde_json::from_str(&text).unwrap_or(Value::String(text))
+                }
+                (_, value) => to_json(value),
+            };
+            Ok(Op {
+                id: row.get(0)?,
+                clock: row.get(1)?,
+                replica: row.get(2)?,
+                table: row.get(3)?,
+                key: row.get(4)?,
+                value: decode(row.get(6)?),
+                prev: decode(row.get(7)?),
+                column,
+                created_at: row.get(8)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(ops)
+}
+
+/// Every recorded operation, oldest first
+pub fn load_ops(conn: &Connection) -> Result<Vec<Op>> {
+    query_ops(conn, "", [])
+}
+
+/// Operations for one row (or, for tasks, its criteria and dependencies too)
+pub fn ops_for_key(conn: &Connection, key: &str) -> Result<Vec<Op>> {
+    query_ops(
+        conn,
+        "WHERE row_key = ?1 OR (row_key LIKE '[%' AND EXISTS (
+             SELECT 1 FROM json_each(oplog.row_key) WHERE json_each.value = ?1))",
+        params![key],
+    )
+}
+
+/// Operations a replica at `seen` is missing, oldest first
+pub fn ops_since(conn: &Connection, seen: &VersionVector) -> Result<Vec<Op>> {
+    let mut ops = Vec::new();
+    for (replica, clock) in version_vector(conn)? {
+        let from = seen.get(&replica).copied().unwrap_or(i64::MIN);
+        if from < clock {
+            ops.extend(query_ops(
+                conn,
+                "WHERE replica = ?1 AND clock > ?2",
+                params![replica, from],
+            )?);
+        }
+    }
+    ops.sort_by(|a, b| a.order().cmp(&b.order()));
+    Ok(ops)
+}
+
+/// Rebuild a database at `output` from this one's operation log alone
+pub fn replay(conn: &Connection, output: &std::path::Path) -> Result<ApplyReport> {
+    if output.exists() {
+        anyhow::bail!("{} already exists", output.display());
+    }
+    let db = crate::db::Database::new(output.to_str().context("Path is not valid UTF-8")?)?;
+    apply_ops(db.get_connection(), &load_ops(conn)?)
+}
+
+fn to_json(value: SqlValue) -> Value {
+    match value {
+        SqlValue::Null => Value::Null,
+        SqlValue::Integer(i) => Value::from(i),
+        SqlValue::Real(f) => Value::from(f),
+        SqlValue::Text(s) => Value::String(s),
+        SqlValue::Blob(b) => Value::String(String::from_utf8_lossy(&b).into_owned()),
+    }
+}
+
+fn to_sql(value: &Value) -> SqlValue {
+    match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(b) => SqlValue::Integer(*b as i64),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => SqlValue::Integer(i),
+            None => SqlValue::Real(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => SqlValue::Text(s.clone()),
+        other => SqlValue::Text(other.to_string()),
+    }
+}
+
+/// Apply operations from another replica
+///
+/// Operations already in the log are skipped. Each operation is written only
+/// if it is now the latest for its row (inserts and deletes) or cell
+/// (updates), so the result doesn't depend on arrival order. Recording is
+/// paused so applied operations aren't logged twice, and the whole batch runs
+/// in one transaction.
+pub fn apply_ops(conn: &Connection, ops: &[Op]) -> Result<ApplyReport> {
+    let mut ops: Vec<&Op> = ops.iter().collect();
+    ops.sort_by(|a, b| a.order().cmp(&b.order()));
+
+    let tx = rusqlite::Transaction::new_unchecked(conn, rusqlite::TransactionBehavior::Immediate)?;
+    tx.execute("INSERT INTO oplog_state (applying) VALUES (1)", [])?;
+
+    let mut report = ApplyReport::default();
+    for op in ops {
+        let inserted = tx.execute(
+            "INSERT OR IGNORE INTO oplog
+                 (id, clock, replica, tbl, row_key, col, value, prev, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                op.id,
+                op.clock,
+                op.replica,
+                op.table,
+                op.key,
+                op.column,
+                to_sql(&op.value),
+                to_sql(&op.prev),
+                op.created_at
+            ],
+        )?;
+        if inserted == 0 {
+            report.skipped += 1;
+            continue;
+        }
+        report.applied += 1;
+
+        let tracked = tracked(&op.table)?;
+        if !is_latest(&tx, op)? {
+            continue;
+        }
+        match &op.column {
+            None if op.is_delete() => {
+                tx.execute(
+                    &format!("DELETE FROM {} WHERE {}", tracked.table, tracked.target),
+                    params![op.key],
+                )?;
+            }
+            None => {
+                if insert_row(&tx, op, &mut report)? {
+                    replay_cells(&tx, tracked, op, &mut report)?;
+                }
+            }
+            Some(column) => update_cell(&tx, tracked, op, column, &mut report)?,
+        }
+    }
+
+    tx.execute("DELETE FROM oplog_state", [])?;
+    tx.commit()?;
+    Ok(report)
+}
+
+fn tracked(table: &str) -> Result<&'static Tracked> {
+    TRACKED
+        .iter()
+        .find(|t| t.table == table)
+        .with_context(|| format!("Unknown table in operation log: {}", table))
+}
+
+/// Whether no operation in the log supersedes `op` for its row or cell
+fn is_latest(conn: &Connection, op: &Op) -> Result<bool> {
+    let latest: String = conn.query_row(
+        "SELECT id FROM oplog WHERE tbl = ?1 AND row_key = ?2 AND col IS ?3
+         ORDER BY clock DESC, replica DESC, id DESC LIMIT 1",
+        params![op.table, op.key, op.column],
+        |row| row.get(0),
+    )?;
+    Ok(latest == op.id)
+}
+
+fn update_cell(
+    conn: &Connection,
+    tracked: &Tracked,
+    op: &Op,
+    column: &str,
+    report: &mut ApplyReport,
+) -> Result<()> {
+    if !tracked.columns.contains(&column) {
+        anyhow::bail!("Column {}.{} is not replicated", op.table, column);
+    }
+    if tracked.unique.contains(&column) && !op.value.is_null() {
+        let taken: bool = conn.query_row(
+            &format!(
+                "SELECT EXISTS(SELECT 1 FROM {} WHERE {} = ?2 AND NOT ({}))",
+                tracked.table, column, tracked.target
+            ),
+            params![op.key, to_sql(&op.value)],
+            |r| r.get(0),
+        )?;
+        if taken {
+            report.rejected.push(format!(
+                "{} of {} ({}) is already used here",
+                column,
+                describe_key(conn, &op.table, &op.key),
+                op.value
+            ));
+            return Ok(());
+        }
+    }
+    conn.execute(
+        &format!(
+            "UPDATE {} SET {} = ?2 WHERE {}",
+            tracked.table, column, tracked.target
+        ),
+        params![op.key, to_sql(&op.value)],
+    )?;
+    Ok(())
+}
+
+/// Re-apply cell updates that arrived before the row they belong to
+fn replay_cells(
+    conn: &Connection,
+    tracked: &Tracked,
+    insert: &Op,
+    report: &mut ApplyReport,
+) -> Result<()> {
+    let mut latest: BTreeMap<String, Op> = BTreeMap::new();
+    for op in query_ops(
+        conn,
+        "WHERE tbl = ?1 AND row_key = ?2 AND col IS NOT NULL",
+        params![insert.table, insert.key],
+    )? {
+        if op.order() > insert.order() {
+            latest.insert(op.column.clone().unwrap_or_default(), op);
+        }
+    }
+    for (column, op) in &latest {
+        update_cell(conn, tracked, op, column, report)?;
+    }
+    Ok(())
+}
+
+/// Insert the row an insert operation carries, returning whether it was added
+fn insert_row(conn: &Connection, op: &Op, report: &mut ApplyReport) -> Result<bool> {
+    let row: serde_json::Map<String, Value> = match &op.value {
+        Value::String(s) => serde_json::from_str(s)?,
+        Value::Object(map) => map.clone(),
+        _ => anyhow::bail!("Malformed insert operation {}", op.id),
+    };
+    let field = |name: &str| row.get(name).map(to_sql).unwrap_or(SqlValue::Null);
+    let task_display_id = |uuid: &SqlValue| -> Result<Option<i32>> {
+        Ok(conn
+            .query_row(
+                "SELECT display_id FROM tasks WHERE id = ?1",
+                params![uuid],
+                |r| r.get(0),
+            )
+            .optional()?
+            .flatten())
+    };
+
+    match op.table.as_str() {
+        "tasks" | "agents" => {
+            let exists: bool = conn.query_row(
+                &format!("SELECT EXISTS(SELECT 1 FROM {} WHERE id = ?1)", op.table),
+                params![op.key],
+                |r| r.get(0),
+            )?;
+            if exists {
+                return Ok(false);
+            }
+            // An external ID names the task, so a clash means it's already
+            // here; a display key taken here is left off instead
+            let mut dropped: Vec<&str> = Vec::new();
+            for column in tracked(&op.table)?.unique {
+                let value = field(column);
+                if value == SqlValue::Null {
+                    continue;
+                }
+                let clash: bool = conn.query_row(
+                    &format!(
+                        "SELECT EXISTS(SELECT 1 FROM {} WHERE {} = ?1)",
+                        op.table, column
+                    ),
+                    params![value],
+                    |r| r.get(0),
+                )?;
+                if !clash {
+                    continue;
+                }
+                if *column == "display_key" {
+                    report.rejected.push(format!(
+                        "display_key {} of task {} is already used here",
+                        row[*column], op.key
+                    ));
+                    dropped.push(column);
+                } else {
+                    report.rejected.push(format!(
+                        "task with {} {} exists here with a different ID",
+                        column, row[*column]
+                    ));
+                    return Ok(false);
+                }
+            }
+            if op.table == "agents" {
+                let clash: bool = conn.query_row(
+                    "SELECT EXISTS(SELECT 1 FROM agents WHERE name = ?1)",
+                    params![field("name")],
+                    |r| r.get(0),
+                )?;
+                if clash {
+                    report.rejected.push(format!(
+                        "agent '{}' exists here with a different ID",
+                        row.get("name").and_then(Value::as_str).unwrap_or_default()
+                    ));
+                    return Ok(false);
+                }
+            }
+
+            // Display IDs are per-replica; take the next free one on collision
+            let mut display_id = row.get("display_id").and_then(Value::as_i64);
+            if let Some(wanted) = display_id {
+                let taken: bool = conn.query_row(
+                    &format!(
+                        "SELECT EXISTS(SELECT 1 FROM {} WHERE display_id = ?1)",
+                        op.table
+                    ),
+                    params![wanted],
+                    |r| r.get(0),
+                )?;
+                if taken {
+                    let next: i64 = conn.query_row(
+                        &format!("SELECT COALESCE(MAX(display_id), 0) + 1 FROM {}", op.table),
+                        [],
+                        |r| r.get(0),
+                    )?;
+                    if op.table == "tasks" {
+                        report
+                            .renumbered
+                            .push((op.key.clone(), wanted as i32, next as i32));
+                    }
+                    display_id = Some(next);
+                }
+            }
+
+            let columns: Vec<&str> = row.keys().map(String::as_str).collect();
+            let values: Vec<SqlValue> = columns
+                .iter()
+                .map(|c| match *c {
+                    "display_id" => display_id.map(SqlValue::Integer).unwrap_or(SqlValue::Null),
+                    c if dropped.contains(&c) => SqlValue::Null,
+                    c => field(c),
+                })
+                .collect();
+            insert_values(conn, "INSERT", &op.table, &columns, values)
+        }
+        "acceptance_criteria" => {
+            let Some(task) = task_display_id(&field("task"))? else {
+                report
+                    .rejected
+                    .push(format!("criterion for missing task ({})", op.key));
+                return Ok(false);
+            };
+            let inserted = conn.execute(
+                "INSERT INTO acceptance_criteria
+                     (task_display_id, criterion, completed, created_at, completed_at)
+                 SELECT ?1, ?2, ?3, ?4, ?5
+                 WHERE NOT EXISTS (SELECT 1 FROM acceptance_criteria
+                                   WHERE task_display_id = ?1 AND criterion = ?2)",
+                params![
+                    task,
+                    field("criterion"),
+                    field("completed"),
+                    field("created_at"),
+                    field("completed_at")
+                ],
+            )?;
+            Ok(inserted > 0)
+        }
+        "task_dependencies" => {
+            let (Some(task), Some(depends_on)) = (
+                task_display_id(&field("task"))?,
+                task_display_id(&field("depends_on"))?,
+            ) else {
+                report
+                    .rejected
+                    .push(format!("dependency on missing task ({})", op.key));
+                return Ok(false);
+            };
+            let inserted = conn.execute(
+                "INSERT OR IGNORE INTO task_dependencies
+                     (task_display_id, depends_on_display_id, dependency_type, created_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    task,
+                    depends_on,
+                    field("dependency_type"),
+                    field("created_at")
+                ],
+            )?;
+            Ok(inserted > 0)
+        }
+        // Rows keyed by their own columns (log IDs, tags, comment and
+        // attachment times, epic names)
+        table => {
+            // Comments and attachments get a local ID, so their key is
+            // checked instead
+            let exists: bool = conn.query_row(
+                &format!(
+                    "SELECT EXISTS(SELECT 1 FROM {} WHERE {})",
+                    table,
+                    tracked(table)?.target
+                ),
+                params![op.key],
+                |r| r.get(0),
+            )?;
+            if exists {
+                return Ok(false);
+            }
+            if let Some(task) = row.get("task_id") {
+                let exists: bool = conn.query_row(
+                    "SELECT EXISTS(SELECT 1 FROM tasks WHERE id = ?1)",
+                    params![to_sql(task)],
+                    |r| r.get(0),
+                )?;
+                if !exists {
+                    report
+                        .rejected
+                        .push(format!("{} row for missing task ({})", table, op.key));
+                    return Ok(false);
+                }
+            }
+            let columns: Vec<&str> = row.keys().map(String::as_str).collect();
+            let values = columns.iter().map(|c| field(c)).collect();
+            insert_values(conn, "INSERT OR IGNORE", table, &columns, values)
+        }
+    }
+}
+
+fn insert_values(
+    conn: &Connection,
+    verb: &str,
+    table: &str,
+    columns: &[&str],
+    values: Vec<SqlValue>,
+) -> Result<bool> {
+    // Column names come from the other replica's JSON
+    if let Some(bad) = columns
+        .iter()
+        .find(|c| !c.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '_'))
+    {
+        anyhow::bail!("Invalid column name in operation log: {}", bad);
+    }
+    let placeholders: Vec<String> = (1..=columns.len()).map(|i| format!("?{}", i)).collect();
+    let inserted = conn.execute(
+        &format!(
+            "{} INTO {} ({}) VALUES ({})",
+            verb,
+            table,
+            columns.join(", "),
+            placeholders.join(", ")
+        ),
+        rusqlite::params_from_iter(values),
+    )?;
+    Ok(inserted > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Database, ExternalUpsert, Priority, Recurrence, TaskStatus};
+    use crate::db_extensions::{AcceptanceCriteriaOps, EpicDependencyOps};
+
+    #[test]
+    fn test_mutations_are_recorded() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        let task = db.create_task("Task".to_string(), None, Priority::High, None, None)?;
+        db.update_task_status(&task.id, TaskStatus::InProgress, None)?;
+        db.get_connection().add_criterion(1, "Works".to_string())?;
+
+        let ops = load_ops(db.get_connection())?;
+        let tables: Vec<(&str, Option<&str>)> = ops
+            .iter()
+            .map(|op| (op.table.as_str(), op.column.as_deref()))
+            .collect();
+        assert!(tables.contains(&("tasks", None)));
+        assert!(tables.contains(&("tasks", Some("status"))));
+        assert!(tables.contains(&("acceptance_criteria", None)));
+        assert!(tables.contains(&("task_logs", None)));
+
+        // Clocks increase and carry this replica's ID
+        let replica = replica_id(db.get_connection())?;
+        assert!(ops.windows(2).all(|w| w[0].clock <= w[1].clock));
+        assert!(ops.iter().all(|op| op.replica == replica));
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_is_idempotent_and_last_writer_wins() -> Result<()> {
+        let a = Database::new(":memory:")?;
+        let b = Database::new(":memory:")?;
+        let task = a.create_task("Shared".to_string(), None, Priority::Low, None, None)?;
+        a.get_connection().add_criterion(1, "Done".to_string())?;
+
+        let report = apply_ops(b.get_connection(), &load_ops(a.get_connection())?)?;
+        assert_eq!(report.skipped, 0);
+        assert_eq!(b.get_task(&task.id)?.unwrap().title, "Shared");
+        assert_eq!(b.get_connection().list_criteria(1)?.len(), 1);
+        let again = apply_ops(b.get_connection(), &load_ops(a.get_connection())?)?;
+        assert_eq!(again.applied, 0);
+
+        // Both edit the status; B's later clock wins on both sides
+        a.update_task_status(&task.id, TaskStatus::Blocked, None)?;
+        b.update_task_status(&task.id, TaskStatus::InProgress, None)?;
+        b.update_task_status(&task.id, TaskStatus::Review, None)?;
+        apply_ops(a.get_connection(), &load_ops(b.get_connection())?)?;
+        apply_ops(b.get_connection(), &load_ops(a.get_connection())?)?;
+        assert_eq!(a.get_task(&task.id)?.unwrap().status, TaskStatus::Review);
+        assert_eq!(b.get_task(&task.id)?.unwrap().status, TaskStatus::Review);
+
+        // Applied operations aren't re-recorded
+        let count = |db: &Database| load_ops(db.get_connection()).map(|ops| ops.len());
+        assert_eq!(count(&a)?, count(&b)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_trash_replicates() -> Result<()> {
+        let a = Database::new(":memory:")?;
+        let b = Database::new(":memory:")?;
+        let task = a.create_task("Scrapped".to_string(), None, Priority::Low, None, None)?;
+        apply_ops(b.get_connection(), &load_ops(a.get_connection())?)?;
+
+        a.delete_task(&task.id, None)?;
+        apply_ops(b.get_connection(), &load_ops(a.get_connection())?)?;
+        assert!(b.get_task(&task.id)?.is_none());
+
+        a.restore_task(&task.id, None)?;
+        apply_ops(b.get_connection(), &load_ops(a.get_connection())?)?;
+        assert!(b.get_task(&task.id)?.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_task_fields_replicate() -> Result<()> {
+        let a = Database::new(":memory:")?;
+        let b = Database::new(":memory:")?;
+        let task = a.create_task("Estimate".to_string(), None, Priority::Low, None, None)?;
+        a.set_task_points(&task.id, Some(5))?;
+        let start = chrono::Utc::now() + chrono::Duration::days(3);
+        a.set_task_start_after(&task.id, Some(start), None)?;
+        apply_ops(b.get_connection(), &load_ops(a.get_connection())?)?;
+        assert_eq!(b.get_task_points(&task.id)?, Some(5));
+        assert_eq!(
+            b.get_task_start_after(&task.id)?.map(|at| at.timestamp()),
+            Some(start.timestamp())
+        );
+
+        // The next occurrence stays linked, so completing again elsewhere
+        // doesn't spawn another
+        a.set_task_recurrence(&task.id, Some(&Recurrence::Days(7)), None)?;
+        a.update_task_status(&task.id, TaskStatus::Completed, None)?;
+        apply_ops(b.get_connection(), &load_ops(a.get_connection())?)?;
+        let next = a.next_occurrence(&task.id)?.unwrap();
+        assert_eq!(
+            b.next_occurrence(&task.id)?.map(|t| t.id),
+            Some(next.id.clone())
+        );
+        assert_eq!(b.get_task_recurrence(&next.id)?, Some(Recurrence::Days(7)));
+
+        a.update_task_status(&next.id, TaskStatus::Failed, None)?;
+        a.retry_task(&next.id, None, None)?;
+        apply_ops(b.get_connection(), &load_ops(a.get_connection())?)?;
+        assert_eq!(b.get_task_attempt(&next.id)?, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tags_replicate() -> Result<()> {
+        let a = Database::new(":memory:")?;
+        let b = Database::new(":memory:")?;
+        let task = a.create_task("Tagged".to_string(), None, Priority::Low, None, None)?;
+        a.add_task_tag(&task.id, "backend", None)?;
+        a.add_task_tag(&task.id, "urgent", None)?;
+        apply_ops(b.get_connection(), &load_ops(a.get_connection())?)?;
+        assert_eq!(b.get_task_tags(&task.id)?, ["backend", "urgent"]);
+
+        a.remove_task_tag(&task.id, "urgent", None)?;
+        apply_ops(b.get_connection(), &load_ops(a.get_connection())?)?;
+        assert_eq!(b.get_task_tags(&task.id)?, ["backend"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_comments_replicate() -> Result<()> {
+        let a = Database::new(":memory:")?;
+        let b = Database::new(":memory:")?;
+        let task = a.create_task("Discussed".to_string(), None, Priority::Low, None, None)?;
+        a.add_comment(&task.id, None, "Needs a migration")?;
+        let ops = load_ops(a.get_connection())?;
+        apply_ops(b.get_connection(), &ops)?;
+
+        // The same comment recorded again, as when both replicas backfill it,
+        // isn't duplicated
+        let mut again = ops
+            .iter()
+            .find(|op| op.table == "task_comments")
+            .cloned()
+            .unwrap();
+        again.id = "backfilled".to_string();
+        again.clock += 1;
+        apply_ops(b.get_connection(), &[again])?;
+        let comments = b.get_task_comments(&task.id)?;
+        assert_eq!(comments.len(), 1);
+        assert_eq!(comments[0].body, "Needs a migration");
+        Ok(())
+    }
+
+    #[test]
+    fn test_attachments_replicate() -> Result<()> {
+        let a = Database::new(":memory:")?;
+        let b = Database::new(":memory:")?;
+        let task = a.create_task("Shipped".to_string(), None, Priority::Low, None, None)?;
+        let attachment =
+            a.add_attachment(&task.id, "https://ci.example/run/1", Some("CI run"), None)?;
+        apply_ops(b.get_connection(), &load_ops(a.get_connection())?)?;
+        let copies = b.get_task_attachments(&task.id)?;
+        assert_eq!(copies.len(), 1);
+        assert_eq!(copies[0].label.as_deref(), Some("CI run"));
+
+        a.remove_attachment(&task.id, attachment.id, None)?;
+        apply_ops(b.get_connection(), &load_ops(a.get_connection())?)?;
+        assert!(b.get_task_attachments(&task.id)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_external_ids_replicate() -> Result<()> {
+        let a = Database::new(":memory:")?;
+        let b = Database::new(":memory:")?;
+        let (task, _) =
+            a.upsert_external_task("gh-7", "Imported".to_string(), None, None, None, None)?;
+        apply_ops(b.get_connection(), &load_ops(a.get_connection())?)?;
+        let (same, outcome) =
+            b.upsert_external_task("gh-7", "Imported".to_string(), None, None, None, None)?;
+        assert_eq!((same.id, outcome), (task.id, ExternalUpsert::Unchanged));
+
+        // Both imported the same issue before syncing: the key isn't applied twice
+        a.upsert_external_task("gh-8", "Twice".to_string(), None, None, None, None)?;
+        let (local, _) =
+            b.upsert_external_task("gh-8", "Twice".to_string(), None, None, None, None)?;
+        let report = apply_ops(b.get_connection(), &load_ops(a.get_connection())?)?;
+        assert_eq!(report.rejected.len(), 1);
+        assert_eq!(b.find_task_by_external_id("gh-8")?.unwrap().id, local.id);
+        Ok(())
+    }
+
+    #[test]
+    fn test_display_keys_replicate() -> Result<()> {
+        let a = Database::new(":memory:")?;
+        let b = Database::new(":memory:")?;
+        let task = a.create_task("Keyed".to_string(), None, Priority::Low, None, None)?;
+        a.assign_next_display_key(&task.id, "API")?;
+        apply_ops(b.get_connection(), &load_ops(a.get_connection())?)?;
+        assert_eq!(b.get_display_key(&task.id)?.as_deref(), Some("API-1"));
+
+        // Keys handed out on both sides before syncing stay with the local task
+        let ours = a.create_task("Ours".to_string(), None, Priority::Low, None, None)?;
+        let theirs = b.create_task("Theirs".to_string(), None, Priority::Low, None, None)?;
+        a.assign_next_display_key(&ours.id, "API")?;
+        b.assign_next_display_key(&theirs.id, "API")?;
+        let report = apply_ops(b.get_connection(), &load_ops(a.get_connection())?)?;
+        assert_eq!(report.rejected.len(), 1);
+        assert_eq!(b.get_display_key(&theirs.id)?.as_deref(), Some("API-2"));
+        assert_eq!(b.get_display_key(&ours.id)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_colliding_display_ids_are_renumbered() -> Result<()> {
+        let a = Database::new(":memory:")?;
+        let b = Database::new(":memory:")?;
+        a.create_task("From A".to_string(), None, Priority::Low, None, None)?;
+        let from_b = b.create_task("From B".to_string(), None, Priority::Low, None, None)?;
+
+        let report = apply_ops(a.get_connection(), &load_ops(b.get_connection())?)?;
+        assert_eq!(report.renumbered, vec![(from_b.id.clone(), 1, 2)]);
+        assert_eq!(a.get_task(&from_b.id)?.unwrap().display_id, Some(2));
+        Ok(())
+    }
+
+    #[test]
+    fn test_order_independent_apply_and_prev_values() -> Result<()> {
+        let a = Database::new(":memory:")?;
+        let task = a.create_task("Task".to_string(), None, Priority::Low, None, None)?;
+        a.update_task_status(&task.id, TaskStatus::InProgress, None)?;
+        a.update_task_status(&task.id, TaskStatus::Review, None)?;
+        let ops = load_ops(a.get_connection())?;
+
+        let status = ops
+            .iter()
+            .rfind(|op| op.column.as_deref() == Some("status"))
+            .unwrap();
+        assert_eq!(status.prev, Value::from("in_progress"));
+        assert_eq!(status.value, Value::from("review"));
+
+        // Updates arriving before their row are replayed once it exists
+        let b = Database::new(":memory:")?;
+        let updates: Vec<Op> = ops
+            .iter()
+            .filter(|op| op.cell().is_some())
+            .cloned()
+            .collect();
+        apply_ops(b.get_connection(), &updates)?;
+        assert!(b.get_task(&task.id)?.is_none());
+        apply_ops(b.get_connection(), &ops)?;
+        assert_eq!(b.get_task(&task.id)?.unwrap().status, TaskStatus::Review);
+        Ok(())
+    }
+
+    #[test]
+    fn test_deletes_and_epic_settings_replicate() -> Result<()> {
+        let a = Database::new(":memory:")?;
+        let b = Database::new(":memory:")?;
+        for epic in ["Build", "Ship"] {
+            a.create_task(
+                epic.to_string(),
+                None,
+                Priority::Low,
+                None,
+                Some(epic.to_string()),
+            )?;
+        }
+        let target = chrono::NaiveDate::from_ymd_opt(2030, 1, 1);
+        a.set_epic_target("Ship", target)?;
+        a.get_connection()
+            .add_epic_dependency("Ship", "Build", 50)?;
+        apply_ops(b.get_connection(), &load_ops(a.get_connection())?)?;
+        assert_eq!(b.get_connection().list_epic_dependencies()?.len(), 1);
+        let forecast = |db: &Database| -> Result<Option<chrono::NaiveDate>> {
+            Ok(db
+                .epic_forecasts(target.unwrap())?
+                .into_iter()
+                .find(|f| f.epic == "Ship")
+                .and_then(|f| f.target))
+        };
+        assert_eq!(forecast(&b)?, target);
+
+        a.set_epic_target("Ship", None)?;
+        a.get_connection().remove_epic_dependency("Ship", "Build")?;
+        let removed = ops_since(a.get_connection(), &version_vector(b.get_connection())?)?;
+        assert!(removed.iter().all(Op::is_delete));
+        assert_eq!(removed[0].prev["epic_name"], "Ship");
+        apply_ops(b.get_connection(), &removed)?;
+        assert!(b.get_connection().list_epic_dependencies()?.is_empty());
+        assert_eq!(forecast(&b)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_version_vectors_and_replay() -> Result<()> {
+        let a = Database::new(":memory:")?;
+        let b = Database::new(":memory:")?;
+        let task = a.create_task("Task".to_string(), None, Priority::Low, None, None)?;
+        apply_ops(b.get_connection(), &load_ops(a.get_connection())?)?;
+        let seen = version_vector(b.get_connection())?;
+        assert!(ops_since(a.get_connection(), &seen)?.is_empty());
+        assert_eq!(decode_vector(&encode_vector(&seen))?, seen);
+
+        a.update_task_status(&task.id, TaskStatus::Blocked, None)?;
+        let missing = ops_since(a.get_connection(), &seen)?;
+        assert!(missing.iter().all(|op| op.clock > seen[&op.replica]));
+        assert!(missing
+            .iter()
+            .any(|op| op.column.as_deref() == Some("status")));
+
+        let dir = tempfile::tempdir()?;
+        let output = dir.path().join("replayed.db");
+        replay(a.get_connection(), &output)?;
+        let replayed = Database::new(output.to_str().unwrap())?;
+        assert_eq!(
+            replayed.get_task(&task.id)?.unwrap().status,
+            TaskStatus::Blocked
+        );
+        assert!(replay(a.get_connection(), &output).is_err());
+        Ok(())
+    }
+}
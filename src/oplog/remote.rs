@@ -0,0 +1,371 @@
+use super::{
+    apply_ops, encode_vector, load_ops, ops_since, reset_replica_id, ApplyReport, Op, ReplicaState,
+    VersionVector,
+};
+use anyhow::{Context, Result};
+use rusqlite::Connection;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Largest request body sent to a `prd serve` remote (the server accepts 64 KB)
+const MAX_PUSH_BYTES: usize = 48 * 1024;
+
+/// Another replica: a database file or a `prd serve` instance
+#[derive(Debug, Clone)]
+pub enum Remote {
+    File(PathBuf),
+    Http { base: String, token: Option<String> },
+}
+
+impl Remote {
+    /// `http(s)://host:port` for a server, anything else (or `file://`) for a file
+    pub fn parse(target: &str, token: Option<String>) -> Self {
+        if target.starts_with("http://") || target.starts_with("https://") {
+            Remote::Http {
+                base: target.trim_end_matches('/').to_string(),
+                token,
+            }
+        } else {
+            Remote::File(PathBuf::from(
+                target.strip_prefix("file://").unwrap_or(target),
+            ))
+        }
+    }
+
+    fn open_file(path: &Path) -> Result<crate::db::Database> {
+        if !path.is_file() {
+            anyhow::bail!(
+                "Replica not found: {} (copy the database there to start a new replica)",
+                path.display()
+            );
+        }
+        crate::db::Database::new(path.to_str().context("Path is not valid UTF-8")?)
+    }
+
+    fn request(&self, method: &str, url: &str, token: &Option<String>) -> ureq::Request {
+        let mut request = ureq::request(method, url).timeout(Duration::from_secs(30));
+        if let Some(token) = token {
+            request = request.set("Authorization", &format!("Bearer {}", token));
+        }
+        request
+    }
+
+    /// The remote's replica ID and version vector
+    pub fn state(&self) -> Result<ReplicaState> {
+        match self {
+            Remote::File(path) => ReplicaState::load(Self::open_file(path)?.get_connection()),
+            Remote::Http { base, token } => {
+                let response = self
+                    .request("GET", &format!("{}/api/ops/vector", base), token)
+                    .call()
+                    .with_context(|| format!("Failed to reach {}", base))?;
+                Ok(serde_json::from_reader(response.into_reader())?)
+            }
+        }
+    }
+
+    /// Operations the remote has beyond `seen`, or all of them
+    pub fn fetch(&self, seen: Option<&VersionVector>) -> Result<Vec<Op>> {
+        match self {
+            Remote::File(path) => {
+                let db = Self::open_file(path)?;
+                match seen {
+                    Some(seen) => ops_since(db.get_connection(), seen),
+                    None => load_ops(db.get_connection()),
+                }
+            }
+            Remote::Http { base, token } => {
+                let mut url = format!("{}/api/ops", base);
+                if let Some(seen) = seen {
+                    url.push_str(&format!("?since={}", encode_vector(seen)));
+                }
+                let response = self
+                    .request("GET", &url, token)
+                    .call()
+                    .with_context(|| format!("Failed to fetch operations from {}", base))?;
+                Ok(serde_json::from_reader(response.into_reader())?)
+            }
+        }
+    }
+
+    /// Apply operations on the remote
+    pub fn send(&self, ops: &[Op]) -> Result<ApplyReport> {
+        match self {
+            Remote::File(path) => apply_ops(Self::open_file(path)?.get_connection(), ops),
+            Remote::Http { base, token } => {
+                let mut total = ApplyReport::default();
+                for chunk in chunk_by_size(ops) {
+                    let response = self
+                        .request("POST", &format!("{}/api/ops", base), token)
+                        .set("Content-Type", "application/json")
+                        .send_string(&serde_json::to_string(chunk)?)
+                        .with_context(|| format!("Failed to push operations to {}", base))?;
+                    let report: ApplyReport = serde_json::from_reader(response.into_reader())?;
+                    total.applied += report.applied;
+                    total.skipped += report.skipped;
+                    total.renumbered.extend(report.renumbered);
+                    total.rejected.extend(report.rejected);
+                }
+                Ok(total)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Remote {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Remote::File(path) => write!(f, "{}", path.display()),
+            Remote::Http { base, .. } => write!(f, "{}", base),
+        }
+    }
+}
+
+/// Split operations into request bodies under `MAX_PUSH_BYTES`
+fn chunk_by_size(ops: &[Op]) -> Vec<&[Op]> {
+    let mut chunks = Vec::new();
+    let (mut start, mut size) = (0, 0);
+    for (i, op) in ops.iter().enumerate() {
+        let op_size = serde_json::to_string(op).map(|s| s.len() + 1).unwrap_or(0);
+        if i > start && size + op_size > MAX_PUSH_BYTES {
+            chunks.push(&ops[start..i]);
+            start = i;
+            size = 0;
+        }
+        size += op_size;
+    }
+    if start < ops.len() {
+        chunks.push(&ops[start..]);
+    }
+    chunks
+}
+
+/// Which replica's write won a conflict
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Side {
+    Local,
+    Remote,
+}
+
+/// A cell both replicas changed to different values since they last synced
+#[derive(Debug, Clone, Serialize)]
+pub struct Conflict {
+    pub table: String,
+    pub key: String,
+    pub column: String,
+    pub local: Value,
+    pub remote: Value,
+    pub winner: Side,
+}
+
+/// Outcome of `prd sync-db push` / `pull`
+#[derive(Debug, Serialize)]
+pub struct SyncReport {
+    /// Operations sent (push) or received (pull)
+    pub transferred: usize,
+    pub applied: ApplyReport,
+    pub conflicts: Vec<Conflict>,
+}
+
+/// Operations each side has that the other doesn't, and the conflicts between them
+fn diff(local: Vec<Op>, remote: Vec<Op>) -> (Vec<Op>, Vec<Op>, Vec<Conflict>) {
+    let local_ids: HashSet<&str> = local.iter().map(|op| op.id.as_str()).collect();
+    let remote_ids: HashSet<&str> = remote.iter().map(|op| op.id.as_str()).collect();
+    let local_only: Vec<Op> = local
+        .iter()
+        .filter(|op| !remote_ids.contains(op.id.as_str()))
+        .cloned()
+        .collect();
+    let remote_only: Vec<Op> = remote
+        .iter()
+        .filter(|op| !local_ids.contains(op.id.as_str()))
+        .cloned()
+        .collect();
+
+    // Latest unsynced write per cell on each side
+    fn latest(ops: &[Op]) -> HashMap<(&str, &str, &str), &Op> {
+        let mut cells = HashMap::new();
+        for op in ops {
+            if let Some(cell) = op.cell() {
+                cells
+                    .entry(cell)
+                    .and_modify(|cur: &mut &Op| {
+                        if op.order() > cur.order() {
+                            *cur = op;
+                        }
+                    })
+                    .or_insert(op);
+            }
+        }
+        cells
+    }
+
+    let theirs = latest(&remote_only);
+    let mut conflicts: Vec<Conflict> = latest(&local_only)
+        .into_iter()
+        .filter_map(|(cell, mine)| {
+            // Bumped alongside every edit; not worth reporting on its own
+            if cell.2 == "updated_at" {
+                return None;
+            }
+            let other = theirs.get(&cell)?;
+            (mine.value != other.value).then(|| Conflict {
+                table: cell.0.to_string(),
+                key: cell.1.to_string(),
+                column: cell.2.to_string(),
+                local: mine.value.clone(),
+                remote: other.value.clone(),
+                winner: if mine.order() > other.order() {
+                    Side::Local
+                } else {
+                    Side::Remote
+                },
+            })
+        })
+        .collect();
+    conflicts.sort_by(|a, b| (&a.table, &a.key, &a.column).cmp(&(&b.table, &b.key, &b.column)));
+
+    (local_only, remote_only, conflicts)
+}
+
+/// Operations each side is missing, found from the version vectors
+fn exchange(conn: &Connection, remote: &Remote) -> Result<(Vec<Op>, Vec<Op>, Vec<Conflict>)> {
+    let theirs = remote.state()?;
+    let ours = ReplicaState::load(conn)?;
+    if theirs.replica == ours.replica {
+        // One database is a copy of the other made in place; compare whole
+        // logs this once and re-key this side so the vectors tell them apart
+        reset_replica_id(conn)?;
+        return Ok(diff(load_ops(conn)?, remote.fetch(None)?));
+    }
+    Ok(diff(
+        ops_since(conn, &theirs.vector)?,
+        remote.fetch(Some(&ours.vector))?,
+    ))
+}
+
+/// Send this replica's missing operations to the remote
+pub fn push(conn: &Connection, remote: &Remote) -> Result<SyncReport> {
+    let (local_only, _, conflicts) = exchange(conn, remote)?;
+    let applied = remote.send(&local_only)?;
+    Ok(SyncReport {
+        transferred: local_only.len(),
+        applied,
+        conflicts,
+    })
+}
+
+/// Apply the remote's missing operations here
+pub fn pull(conn: &Connection, remote: &Remote) -> Result<SyncReport> {
+    let (_, remote_only, conflicts) = exchange(conn, remote)?;
+    let applied = apply_ops(conn, &remote_only)?;
+    Ok(SyncReport {
+        transferred: remote_only.len(),
+        applied,
+        conflicts,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Database, Priority, TaskStatus};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_push_pull_between_files_reports_conflicts() -> Result<()> {
+        let dir = tempdir()?;
+        let path_a = dir.path().join("a.db");
+        let path_b = dir.path().join("b.db");
+        let a = Database::new(path_a.to_str().unwrap())?;
+        let b = Database::new(path_b.to_str().unwrap())?;
+        let remote_b = Remote::parse(path_b.to_str().unwrap(), None);
+
+        let task = a.create_task("Shared".to_string(), None, Priority::Low, None, None)?;
+        let report = push(a.get_connection(), &remote_b)?;
+        assert!(report.conflicts.is_empty());
+        assert_eq!(b.get_task(&task.id)?.unwrap().title, "Shared");
+
+        // Offline edits to the same field on both sides
+        a.update_task_status(&task.id, TaskStatus::Blocked, None)?;
+        b.update_task_status(&task.id, TaskStatus::Review, None)?;
+
+        let pulled = pull(a.get_connection(), &remote_b)?;
+        let status = pulled
+            .conflicts
+            .iter()
+            .find(|c| c.column == "status")
+            .unwrap();
+        assert_eq!(status.local, Value::from("blocked"));
+        assert_eq!(status.remote, Value::from("review"));
+
+        push(a.get_connection(), &remote_b)?;
+        let winner = match status.winner {
+            Side::Local => TaskStatus::Blocked,
+            Side::Remote => TaskStatus::Review,
+        };
+        assert_eq!(a.get_task(&task.id)?.unwrap().status, winner);
+        assert_eq!(b.get_task(&task.id)?.unwrap().status, winner);
+
+        // Nothing left to exchange
+        assert_eq!(push(a.get_connection(), &remote_b)?.transferred, 0);
+        assert_eq!(pull(a.get_connection(), &remote_b)?.transferred, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_copied_database_gets_its_own_replica() -> Result<()> {
+        let dir = tempdir()?;
+        let path_a = dir.path().join("a.db");
+        let path_b = dir.path().join("b.db");
+        let a = Database::new(path_a.to_str().unwrap())?;
+        a.create_task("Before copy".to_string(), None, Priority::Low, None, None)?;
+        std::fs::copy(&path_a, &path_b)?;
+        let b = Database::new(path_b.to_str().unwrap())?;
+        assert_ne!(
+            super::super::replica_id(a.get_connection())?,
+            super::super::replica_id(b.get_connection())?
+        );
+
+        let task = b.create_task("After copy".to_string(), None, Priority::Low, None, None)?;
+        let report = pull(
+            a.get_connection(),
+            &Remote::parse(path_b.to_str().unwrap(), None),
+        )?;
+        assert!(report.transferred > 0);
+        assert_eq!(a.get_task(&task.id)?.unwrap().title, "After copy");
+        Ok(())
+    }
+
+    #[test]
+    fn test_remote_parse_and_chunking() {
+        assert!(matches!(
+            Remote::parse("https://prd.example:7878/", None),
+            Remote::Http { ref base, .. } if base == "https://prd.example:7878"
+        ));
+        assert!(matches!(
+            Remote::parse("file:///tmp/x.db", None),
+            Remote::File(ref p) if p == &PathBuf::from("/tmp/x.db")
+        ));
+
+        let op = Op {
+            id: "x".repeat(32),
+            clock: 1,
+            replica: "r".to_string(),
+            table: "tasks".to_string(),
+            key: "k".to_string(),
+            column: Some("description".to_string()),
+            value: Value::from("d".repeat(10_000)),
+            prev: Value::Null,
+            created_at: String::new(),
+        };
+        let ops = vec![op; 12];
+        let chunks = chunk_by_size(&ops);
+        assert!(chunks.len() >= 3);
+        assert_eq!(chunks.iter().map(|c| c.len()).sum::<usize>(), 12);
+    }
+}
@@ -0,0 +1,35 @@
+//! Terminal styling switches shared by every command except the dashboard
+
+use colored::control::{self, SHOULD_COLORIZE};
+
+/// Turn off colors when `--no-color` is passed or `NO_COLOR` is set
+///
+/// `colored` already honors `NO_COLOR`, `CLICOLOR` and piped output; this
+/// adds the flag on top. Emoji follow the same switch via [`emoji`].
+pub fn configure(no_color: bool) {
+    if no_color || no_color_env() {
+        control::set_override(false);
+    }
+}
+
+/// Whether the `NO_COLOR` environment variable asks for plain output
+pub fn no_color_env() -> bool {
+    std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty())
+}
+
+/// `symbol`, or a plain-text stand-in when colors are off
+pub fn emoji(symbol: &'static str) -> &'static str {
+    if SHOULD_COLORIZE.should_colorize() {
+        return symbol;
+    }
+    match symbol {
+        "📋" => "[task]",
+        "💻" => "[code]",
+        "📄" => "[doc]",
+        "🗄" => "[db]",
+        "❌" => "x",
+        "⚠️" => "!",
+        "⏳" => "...",
+        _ => "*",
+    }
+}
@@ -0,0 +1,250 @@
+use crate::db::{Database, Task};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Role of a principal (agent or human actor) using the API
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// May do anything, including cancelling and re-prioritizing tasks
+    Planner,
+    /// May create tasks and work on (or claim) tasks assigned to itself
+    Agent,
+    /// Read-only access
+    Viewer,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Role::Planner => "planner",
+            Role::Agent => "agent",
+            Role::Viewer => "viewer",
+        }
+    }
+}
+
+/// Write operations subject to permission checks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    CreateTask,
+    UpdateTask,
+    AssignTask,
+    CancelTask,
+    Reprioritize,
+}
+
+impl Operation {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Operation::CreateTask => "create tasks",
+            Operation::UpdateTask => "update tasks",
+            Operation::AssignTask => "assign tasks",
+            Operation::CancelTask => "cancel tasks",
+            Operation::Reprioritize => "re-prioritize tasks",
+        }
+    }
+}
+
+/// Returned when a principal's role does not allow an operation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PermissionDenied {
+    pub principal: String,
+    pub role: Role,
+    pub operation: Operation,
+    pub reason: Option<String>,
+}
+
+impl std::fmt::Display for PermissionDenied {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Permission denied: {} ({}) may not {}",
+            self.principal,
+            self.role.as_str(),
+            self.operation.as_str()
+        )?;
+        if let Some(reason) = &self.reason {
+            write!(f, " ({})", reason)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for PermissionDenied {}
+
+/// Role-based restrictions for API clients (~/.prd/permissions.toml)
+///
+/// ```toml
+/// enabled = true
+/// own_tasks_only = true
+/// default_role = "agent"
+///
+/// [roles]
+/// planner-bot = "planner"
+/// wallboard = "viewer"
+/// ```
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct PermissionsConfig {
+    /// Whether restrictions are enforced at all
+    pub enabled: bool,
+
+    /// Agents may only change tasks assigned to them (or claim unassigned ones)
+    pub own_tasks_only: bool,
+
+    /// Role for principals not listed in `roles`
+    pub default_role: Role,
+
+    /// Principal (agent name or actor) -> role
+    pub roles: HashMap<String, Role>,
+}
+
+impl Default for PermissionsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            own_tasks_only: true,
+            default_role: Role::Agent,
+            roles: HashMap::new(),
+        }
+    }
+}
+
+impl PermissionsConfig {
+    /// Load configuration, falling back to defaults (disabled) if the file is missing
+    pub fn load() -> Result<Self> {
+        let config_path = Self::get_config_path()?;
+
+        if !config_path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(&config_path)
+            .context(format!("Failed to read config from {:?}", config_path))?;
+        toml::from_str(&content).context("Failed to parse permissions config as TOML")
+    }
+
+    /// Get the path to the config file (~/.prd/permissions.toml)
+    pub fn get_config_path() -> Result<PathBuf> {
+        let home = std::env::var("HOME")
+            .or_else(|_| std::env::var("USERPROFILE"))
+            .map_err(|_| anyhow::anyhow!("Could not determine home directory"))?;
+        Ok(PathBuf::from(home).join(".prd").join("permissions.toml"))
+    }
+
+    /// Role assigned to a principal
+    pub fn role_of(&self, principal: &str) -> Role {
+        self.roles
+            .get(principal)
+            .copied()
+            .unwrap_or(self.default_role)
+    }
+
+    /// Check whether `principal` may perform `operation`, optionally on `task`
+    pub fn check(
+        &self,
+        db: &Database,
+        principal: &str,
+        operation: Operation,
+        task: Option<&Task>,
+    ) -> Result<(), PermissionDenied> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        let role = self.role_of(principal);
+        let deny = |reason: Option<&str>| PermissionDenied {
+            principal: principal.to_string(),
+            role,
+            operation,
+            reason: reason.map(|r| r.to_string()),
+        };
+
+        match (role, operation) {
+            (Role::Planner, _) => Ok(()),
+            (Role::Viewer, _) => Err(deny(None)),
+            (Role::Agent, Operation::CancelTask | Operation::Reprioritize) => Err(deny(None)),
+            (Role::Agent, Operation::CreateTask) => Ok(()),
+            (Role::Agent, Operation::UpdateTask | Operation::AssignTask) => {
+                let Some(task) = task else { return Ok(()) };
+                if !self.own_tasks_only {
+                    return Ok(());
+                }
+
+                let agent_id = db.get_agent_by_name(principal).ok().flatten().map(|a| a.id);
+                match (&task.assigned_agent, agent_id) {
+                    (_, None) => Err(deny(Some("not a registered agent"))),
+                    (None, Some(_)) => Ok(()),
+                    (Some(assigned), Some(id)) if *assigned == id => Ok(()),
+                    (Some(_), Some(_)) => Err(deny(Some("task is assigned to another agent"))),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Priority;
+
+    fn enabled_config() -> PermissionsConfig {
+        toml::from_str(
+            r#"
+            enabled = true
+            [roles]
+            lead = "planner"
+            wallboard = "viewer"
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_disabled_by_default_allows_everything() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        let config = PermissionsConfig::default();
+        assert!(config
+            .check(&db, "anyone", Operation::Reprioritize, None)
+            .is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn test_roles_and_own_tasks() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        let config = enabled_config();
+        let owner = db.create_agent("owner".to_string())?;
+        db.create_agent("intruder".to_string())?;
+        let task = db.create_task("Task".to_string(), None, Priority::Low, None, None)?;
+        db.assign_task(&task.id, &owner.id)?;
+        let task = db.get_task(&task.id)?.unwrap();
+
+        assert!(config
+            .check(&db, "lead", Operation::Reprioritize, Some(&task))
+            .is_ok());
+        assert!(config
+            .check(&db, "owner", Operation::UpdateTask, Some(&task))
+            .is_ok());
+        assert!(config
+            .check(&db, "owner", Operation::CancelTask, Some(&task))
+            .is_err());
+
+        let err = config
+            .check(&db, "intruder", Operation::UpdateTask, Some(&task))
+            .unwrap_err();
+        assert_eq!(
+            err.reason.as_deref(),
+            Some("task is assigned to another agent")
+        );
+
+        assert!(config
+            .check(&db, "wallboard", Operation::CreateTask, None)
+            .is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,159 @@
+//! Agent queue - the tasks `prd queue <agent>` hands a worker: those assigned
+//! to it, then claimable ready ones. Its JSON fields are a stable contract for
+//! polling workers.
+
+use crate::db::{Database, Task, TaskStatus};
+use crate::db_extensions::DependencyOps;
+use anyhow::Result;
+use serde::Serialize;
+
+/// Default page size of `prd queue`
+pub const DEFAULT_QUEUE_LIMIT: usize = 20;
+
+/// Why a task is in an agent's queue
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueueSource {
+    /// Assigned to the agent and not started yet
+    Assigned,
+    /// Ready, unassigned and not leased to another agent
+    Claimable,
+}
+
+/// One task in an agent's queue
+///
+/// The fields are a stable subset of [`Task`] so worker processes polling
+/// the queue don't break when tasks gain columns.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QueueItem {
+    pub id: String,
+    pub display_id: Option<i32>,
+    pub title: String,
+    pub description: Option<String>,
+    pub priority: String,
+    pub epic: Option<String>,
+    pub source: QueueSource,
+    /// Whether every dependency is done
+    pub ready: bool,
+}
+
+impl QueueItem {
+    fn new(task: Task, source: QueueSource, ready: bool) -> Self {
+        QueueItem {
+            id: task.id,
+            display_id: task.display_id,
+            title: task.title,
+            description: task.description,
+            priority: task.priority.as_str().to_string(),
+            epic: task.epic_name,
+            source,
+            ready,
+        }
+    }
+}
+
+/// A page of an agent's queue
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentQueue {
+    pub agent_id: String,
+    pub items: Vec<QueueItem>,
+    /// Tasks in the whole queue
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+    /// Offset of the next page, if there is one
+    pub next_offset: Option<usize>,
+}
+
+/// The queue of agent `agent_id` (UUID): its assigned pending tasks, then
+/// ready unassigned tasks it may claim, each group in ready-queue order
+///
+/// Tasks leased to other agents are left out. Returns the `limit` tasks
+/// starting at `offset`.
+pub fn agent_queue(
+    db: &Database,
+    agent_id: &str,
+    offset: usize,
+    limit: usize,
+) -> Result<AgentQueue> {
+    let ready_ids = db.get_connection().get_ready_tasks()?;
+    let is_ready = |task: &Task| task.display_id.is_some_and(|id| ready_ids.contains(&id));
+
+    let mut assigned = Vec::new();
+    let mut claimable = Vec::new();
+    for task in db.list_tasks(Some(TaskStatus::Pending))? {
+        if let Some(lease) = db.get_active_lease(&task.id)? {
+            if lease.agent_id != agent_id {
+                continue;
+            }
+        }
+        match task.assigned_agent.as_deref() {
+            Some(owner) if owner == agent_id => assigned.push(task),
+            None if is_ready(&task) => claimable.push(task),
+            _ => {}
+        }
+    }
+    db.sort_ready_queue(&mut assigned)?;
+    db.sort_ready_queue(&mut claimable)?;
+
+    let all: Vec<QueueItem> = assigned
+        .into_iter()
+        .map(|t| {
+            let ready = is_ready(&t);
+            QueueItem::new(t, QueueSource::Assigned, ready)
+        })
+        .chain(
+            claimable
+                .into_iter()
+                .map(|t| QueueItem::new(t, QueueSource::Claimable, true)),
+        )
+        .collect();
+    let total = all.len();
+    let items: Vec<QueueItem> = all.into_iter().skip(offset).take(limit).collect();
+    let next_offset = Some(offset + items.len()).filter(|&next| next < total);
+
+    Ok(AgentQueue {
+        agent_id: agent_id.to_string(),
+        items,
+        total,
+        offset,
+        limit,
+        next_offset,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Priority;
+
+    #[test]
+    fn test_agent_queue() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        let me = db.create_agent("me".to_string())?;
+        let other = db.create_agent("other".to_string())?;
+        let low = db.create_task("Low".to_string(), None, Priority::Low, None, None)?;
+        let high = db.create_task("High".to_string(), None, Priority::High, None, None)?;
+        let mine = db.create_task("Mine".to_string(), None, Priority::Low, None, None)?;
+        let theirs = db.create_task("Theirs".to_string(), None, Priority::High, None, None)?;
+        let blocked = db.create_task("Waiting".to_string(), None, Priority::High, None, None)?;
+        db.assign_task(&mine.id, &me.id)?;
+        db.assign_task(&theirs.id, &other.id)?;
+        db.get_connection().add_dependency(
+            blocked.display_id.unwrap(),
+            low.display_id.unwrap(),
+            "blocks",
+        )?;
+
+        let queue = agent_queue(&db, &me.id, 0, 10)?;
+        let ids: Vec<&str> = queue.items.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(ids, [mine.id.as_str(), high.id.as_str(), low.id.as_str()]);
+        assert_eq!(queue.items[0].source, QueueSource::Assigned);
+        assert_eq!(queue.next_offset, None);
+
+        let page = agent_queue(&db, &me.id, 1, 1)?;
+        assert_eq!(page.items[0].id, high.id);
+        assert_eq!((page.total, page.next_offset), (3, Some(2)));
+        Ok(())
+    }
+}
@@ -0,0 +1,228 @@
+use crate::context::TaskRef;
+use crate::db::{Database, Release, StatusBucket, Task};
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::fmt::Write;
+
+/// Task counts for one epic of a release
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReleaseEpic {
+    pub epic: String,
+    pub total: usize,
+    pub done: usize,
+}
+
+/// How far a release is from shipping
+#[derive(Debug, Clone, Serialize)]
+pub struct ReleaseStatus {
+    pub release: Release,
+    pub total: usize,
+    /// Tasks in a done status, cancelled ones included
+    pub done: usize,
+    /// Tasks that still hold the release back
+    pub unfinished: Vec<TaskRef>,
+    pub epics: Vec<ReleaseEpic>,
+}
+
+impl ReleaseStatus {
+    /// Whether every task of the release is done
+    pub fn is_ready(&self) -> bool {
+        self.unfinished.is_empty()
+    }
+
+    pub fn percent(&self) -> u8 {
+        (self.done * 100).checked_div(self.total).unwrap_or(100) as u8
+    }
+
+    /// Past its target date without being ready or closed
+    pub fn is_late(&self, today: NaiveDate) -> bool {
+        !self.release.is_closed()
+            && !self.is_ready()
+            && self.release.target.is_some_and(|target| target < today)
+    }
+}
+
+fn find_release(db: &Database, version: &str) -> Result<Release> {
+    db.get_release(version)?
+        .ok_or_else(|| anyhow::anyhow!("Release {} not found", version))
+}
+
+/// Progress of release `version`, overall and per epic
+pub fn release_status(db: &Database, version: &str) -> Result<ReleaseStatus> {
+    let release = find_release(db, version)?;
+    let workflow = db.workflow()?;
+    let tasks = db.release_tasks(version)?;
+
+    let mut epics: Vec<ReleaseEpic> = release
+        .epics
+        .iter()
+        .map(|epic| ReleaseEpic {
+            epic: epic.clone(),
+            total: 0,
+            done: 0,
+        })
+        .collect();
+    let mut done = 0;
+    let mut unfinished = Vec::new();
+    for task in &tasks {
+        let is_done = workflow.bucket(&task.status) == StatusBucket::Done;
+        if is_done {
+            done += 1;
+        } else {
+            unfinished.push(TaskRef::from_task(task));
+        }
+        let epic = epics
+            .iter_mut()
+            .find(|e| task.epic_name.as_deref() == Some(e.epic.as_str()));
+        if let Some(epic) = epic {
+            epic.total += 1;
+            epic.done += is_done as usize;
+        }
+    }
+
+    Ok(ReleaseStatus {
+        release,
+        total: tasks.len(),
+        done,
+        unfinished,
+        epics,
+    })
+}
+
+/// Close release `version`
+///
+/// Refuses while tasks are unfinished, unless `force` is set.
+pub fn close_release(db: &Database, version: &str, force: bool) -> Result<ReleaseStatus> {
+    let status = release_status(db, version)?;
+    if !status.is_ready() && !force {
+        let ids: Vec<String> = status.unfinished.iter().map(|t| t.label()).collect();
+        anyhow::bail!(
+            "Release {} has {} unfinished task(s): {} (use --force to close anyway)",
+            version,
+            ids.len(),
+            ids.join(", ")
+        );
+    }
+    db.close_release(version)?;
+    release_status(db, version)
+}
+
+/// Markdown changelog of the tasks completed in release `version`, grouped
+/// by epic
+pub fn changelog(db: &Database, version: &str) -> Result<String> {
+    let release = find_release(db, version)?;
+    let workflow = db.workflow()?;
+    let mut completed: Vec<Task> = db
+        .release_tasks(version)?
+        .into_iter()
+        .filter(|t| workflow.is_completed(&t.status))
+        .collect();
+    completed.sort_by_key(|t| t.completed_at);
+
+    let mut out = String::new();
+    let date = release
+        .closed_at
+        .map(|at| at.date_naive())
+        .or(release.target);
+    match date {
+        Some(date) => {
+            let _ = writeln!(out, "## {} ({})\n", release.version, date);
+        }
+        None => {
+            let _ = writeln!(out, "## {}\n", release.version);
+        }
+    }
+    if completed.is_empty() {
+        let _ = writeln!(out, "No completed tasks yet.");
+        return Ok(out);
+    }
+
+    let mut sections: Vec<(Option<String>, Vec<&Task>)> = Vec::new();
+    for task in &completed {
+        match sections
+            .iter_mut()
+            .find(|(epic, _)| *epic == task.epic_name)
+        {
+            Some((_, tasks)) => tasks.push(task),
+            None => sections.push((task.epic_name.clone(), vec![task])),
+        }
+    }
+    // Epics in release order, loose tasks last
+    sections.sort_by_key(|(epic, _)| {
+        epic.as_ref()
+            .and_then(|e| release.epics.iter().position(|r| r == e))
+            .unwrap_or(usize::MAX)
+    });
+    for (epic, tasks) in sections {
+        let _ = writeln!(out, "### {}\n", epic.as_deref().unwrap_or("Other changes"));
+        for task in tasks {
+            let _ = writeln!(
+                out,
+                "- {} ({})",
+                task.title,
+                TaskRef::from_task(task).label()
+            );
+        }
+        let _ = writeln!(out);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Priority, TaskStatus};
+
+    #[test]
+    fn test_release_status_gating_and_changelog() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        let login = db.create_task(
+            "Login".to_string(),
+            None,
+            Priority::High,
+            None,
+            Some("auth".to_string()),
+        )?;
+        let logout = db.create_task(
+            "Logout".to_string(),
+            None,
+            Priority::Low,
+            None,
+            Some("auth".to_string()),
+        )?;
+        let hotfix = db.create_task("Hotfix".to_string(), None, Priority::High, None, None)?;
+        db.create_task("Unrelated".to_string(), None, Priority::Low, None, None)?;
+
+        let target = NaiveDate::from_ymd_opt(2026, 11, 1).unwrap();
+        db.create_release(
+            "1.2.0",
+            Some(target),
+            &["auth".to_string()],
+            std::slice::from_ref(&hotfix.id),
+        )?;
+        assert!(db.create_release("1.2.0", None, &[], &[]).is_err());
+
+        db.update_task_status(&login.id, TaskStatus::Completed, None)?;
+        db.update_task_status(&hotfix.id, TaskStatus::Completed, None)?;
+        let status = release_status(&db, "1.2.0")?;
+        assert_eq!((status.done, status.total), (2, 3));
+        assert_eq!(status.epics[0].done, 1);
+        assert_eq!(status.unfinished[0].id, logout.id);
+        assert!(status.is_late(NaiveDate::from_ymd_opt(2026, 11, 2).unwrap()));
+        assert!(close_release(&db, "1.2.0", false).is_err());
+
+        let notes = changelog(&db, "1.2.0")?;
+        assert!(notes.starts_with("## 1.2.0 (2026-11-01)"));
+        let auth = notes.find("### auth").unwrap();
+        let other = notes.find("### Other changes").unwrap();
+        assert!(auth < other);
+        assert!(!notes.contains("Logout"));
+
+        db.update_task_status(&logout.id, TaskStatus::Cancelled, None)?;
+        let closed = close_release(&db, "1.2.0", false)?;
+        assert!(closed.release.is_closed());
+        assert!(db.close_release("1.2.0").is_err());
+        Ok(())
+    }
+}
@@ -2,14 +2,50 @@ use anyhow::{Context, Result};
 use rusqlite::Connection;
 
 /// Resolves various ID formats to their full UUID
-/// Accepts: #42, 42, uuid-prefix, or full-uuid
+/// Accepts: #42, 42, API-42 (prefixed key), uuid-prefix, or full-uuid
+///
+/// Deleted tasks are not found; see [`resolve_deleted_task_id`].
 pub fn resolve_task_id(conn: &Connection, id_input: &str) -> Result<String> {
+    resolve_task(conn, id_input, false)
+}
+
+/// Resolves the ID of a task in the trash, in the same formats as
+/// [`resolve_task_id`]
+pub fn resolve_deleted_task_id(conn: &Connection, id_input: &str) -> Result<String> {
+    resolve_task(conn, id_input, true)
+}
+
+fn resolve_task(conn: &Connection, id_input: &str, deleted: bool) -> Result<String> {
     let cleaned = id_input.trim().trim_start_matches('#');
+    let in_trash = if deleted {
+        "deleted_at IS NOT NULL"
+    } else {
+        "deleted_at IS NULL"
+    };
+
+    // Try as a prefixed display key, e.g. API-42
+    if cleaned.contains('-') {
+        let uuid: Result<String, _> = conn.query_row(
+            &format!(
+                "SELECT id FROM tasks WHERE display_key = ?1 COLLATE NOCASE AND {}",
+                in_trash
+            ),
+            [cleaned],
+            |row| row.get(0),
+        );
+
+        if let Ok(uuid) = uuid {
+            return Ok(uuid);
+        }
+    }
 
     // Try as display_id first (most common case)
     if let Ok(display_id) = cleaned.parse::<i32>() {
         let uuid: Result<String, _> = conn.query_row(
-            "SELECT id FROM tasks WHERE display_id = ?1",
+            &format!(
+                "SELECT id FROM tasks WHERE display_id = ?1 AND {}",
+                in_trash
+            ),
             [display_id],
             |row| row.get(0),
         );
@@ -21,7 +57,10 @@ pub fn resolve_task_id(conn: &Connection, id_input: &str) -> Result<String> {
 
     // Try as UUID prefix or full UUID
     let uuid_result: Result<Vec<String>, _> = conn
-        .prepare("SELECT id FROM tasks WHERE id LIKE ?1 || '%'")?
+        .prepare(&format!(
+            "SELECT id FROM tasks WHERE id LIKE ?1 || '%' AND {}",
+            in_trash
+        ))?
         .query_map([cleaned], |row| row.get(0))?
         .collect();
 
@@ -32,6 +71,7 @@ pub fn resolve_task_id(conn: &Connection, id_input: &str) -> Result<String> {
             id_input,
             matches.len()
         )),
+        _ if deleted => Err(anyhow::anyhow!("Task not in the trash: {}", id_input)),
         _ => Err(anyhow::anyhow!("Task not found: {}", id_input)),
     }
 }
@@ -86,8 +126,18 @@ pub fn resolve_agent_id(conn: &Connection, id_input: &str) -> Result<String> {
     }
 }
 
-/// Formats a task ID for display (shows display_id instead of UUID)
+/// Formats a task ID for display (shows the prefixed key or display_id
+/// instead of UUID)
 pub fn format_task_id(conn: &Connection, uuid: &str) -> String {
+    let key: Result<String, _> = conn.query_row(
+        "SELECT display_key FROM tasks WHERE id = ?1 AND display_key IS NOT NULL",
+        [uuid],
+        |row| row.get(0),
+    );
+    if let Ok(key) = key {
+        return key;
+    }
+
     let display_id: Result<i32, _> = conn.query_row(
         "SELECT display_id FROM tasks WHERE id = ?1",
         [uuid],
@@ -122,10 +172,10 @@ mod tests {
     fn setup_test_db() -> Connection {
         let conn = Connection::open_in_memory().unwrap();
         conn.execute_batch(
-            "CREATE TABLE tasks (id TEXT PRIMARY KEY, display_id INTEGER, title TEXT);
+            "CREATE TABLE tasks (id TEXT PRIMARY KEY, display_id INTEGER, title TEXT, deleted_at TEXT);
              CREATE TABLE agents (id TEXT PRIMARY KEY, display_id INTEGER, name TEXT);
-             INSERT INTO tasks VALUES ('uuid-task-1', 1, 'Task 1');
-             INSERT INTO tasks VALUES ('uuid-task-2', 2, 'Task 2');
+             INSERT INTO tasks VALUES ('uuid-task-1', 1, 'Task 1', NULL);
+             INSERT INTO tasks VALUES ('uuid-task-2', 2, 'Task 2', NULL);
              INSERT INTO agents VALUES ('uuid-agent-1', 1, 'test-agent');
              INSERT INTO agents VALUES ('uuid-agent-2', 2, 'other-agent');",
         )
@@ -150,6 +200,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_resolve_task_by_display_key() {
+        let conn = setup_test_db();
+        conn.execute_batch(
+            "ALTER TABLE tasks ADD COLUMN display_key TEXT;
+             UPDATE tasks SET display_key = 'API-7' WHERE id = 'uuid-task-2';",
+        )
+        .unwrap();
+        assert_eq!(resolve_task_id(&conn, "API-7").unwrap(), "uuid-task-2");
+        assert_eq!(resolve_task_id(&conn, "api-7").unwrap(), "uuid-task-2");
+        assert_eq!(format_task_id(&conn, "uuid-task-2"), "API-7");
+        assert_eq!(format_task_id(&conn, "uuid-task-1"), "#1");
+    }
+
+    #[test]
+    fn test_resolve_deleted_task() {
+        let conn = setup_test_db();
+        conn.execute(
+            "UPDATE tasks SET deleted_at = '2026-10-15T00:00:00Z' WHERE id = 'uuid-task-2'",
+            [],
+        )
+        .unwrap();
+        assert!(resolve_task_id(&conn, "#2").is_err());
+        assert!(resolve_task_id(&conn, "uuid-task-2").is_err());
+        assert_eq!(resolve_deleted_task_id(&conn, "#2").unwrap(), "uuid-task-2");
+        assert!(resolve_deleted_task_id(&conn, "#1").is_err());
+        assert_eq!(format_task_id(&conn, "uuid-task-2"), "#2");
+    }
+
     #[test]
     fn test_resolve_agent_by_display_id() {
         let conn = setup_test_db();
@@ -0,0 +1,119 @@
+use crate::db::Database;
+use crate::vectors::{PruneReport, VectorStore};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// Retention policy for historical data (~/.prd/retention.toml)
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct RetentionConfig {
+    /// Delete progress reports older than this many days
+    pub progress_days: i64,
+
+    /// Keep each task's final progress report regardless of age
+    pub keep_final_snapshots: bool,
+
+    /// Run cleanup automatically from the watcher daemon
+    pub auto_cleanup: bool,
+
+    /// How often the daemon runs cleanup, in hours
+    pub cleanup_interval_hours: u64,
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            progress_days: 30,
+            keep_final_snapshots: true,
+            auto_cleanup: true,
+            cleanup_interval_hours: 24,
+        }
+    }
+}
+
+impl RetentionConfig {
+    /// Load configuration, falling back to defaults if the file is missing
+    pub fn load() -> Result<Self> {
+        let config_path = Self::get_config_path()?;
+
+        if !config_path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(&config_path)
+            .context(format!("Failed to read config from {:?}", config_path))?;
+        toml::from_str(&content).context("Failed to parse retention config as TOML")
+    }
+
+    /// Get the path to the config file (~/.prd/retention.toml)
+    pub fn get_config_path() -> Result<PathBuf> {
+        let home = std::env::var("HOME")
+            .or_else(|_| std::env::var("USERPROFILE"))
+            .map_err(|_| anyhow::anyhow!("Could not determine home directory"))?;
+        Ok(PathBuf::from(home).join(".prd").join("retention.toml"))
+    }
+}
+
+/// Result of a garbage collection run
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct GcReport {
+    pub progress_deleted: usize,
+    /// Embeddings of deleted or cancelled tasks and missing files
+    pub embeddings_pruned: PruneReport,
+}
+
+/// Apply the retention policy to the database and drop orphaned embeddings
+pub fn run_gc(db: &Database, config: &RetentionConfig) -> Result<GcReport> {
+    let progress_deleted = if config.keep_final_snapshots {
+        db.prune_progress_history(config.progress_days)?
+    } else {
+        db.cleanup_old_progress(config.progress_days)?
+    };
+
+    Ok(GcReport {
+        progress_deleted,
+        embeddings_pruned: VectorStore::prune_orphans(db.get_connection())?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Priority;
+    use chrono::Utc;
+
+    #[test]
+    fn test_partial_config_uses_defaults() {
+        let config: RetentionConfig = toml::from_str("progress_days = 7").unwrap();
+        assert_eq!(config.progress_days, 7);
+        assert!(config.keep_final_snapshots);
+        assert!(config.auto_cleanup);
+    }
+
+    #[test]
+    fn test_run_gc_respects_final_snapshots() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        let agent = db.create_agent("agent".to_string())?;
+        let task = db.create_task("Task".to_string(), None, Priority::Low, None, None)?;
+        let old = (Utc::now() - chrono::Duration::days(60)).to_rfc3339();
+        db.get_connection().execute(
+            "INSERT INTO agent_progress (agent_id, task_id, progress, timestamp)
+             VALUES (?1, ?2, 100, ?3)",
+            rusqlite::params![agent.id, task.display_id.unwrap(), old],
+        )?;
+
+        let report = run_gc(&db, &RetentionConfig::default())?;
+        assert_eq!(report.progress_deleted, 0);
+
+        let config = RetentionConfig {
+            keep_final_snapshots: false,
+            ..Default::default()
+        };
+        let report = run_gc(&db, &config)?;
+        assert_eq!(report.progress_deleted, 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,289 @@
+use crate::db::Database;
+use crate::resolver::resolve_agent_id;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// Log action recorded on a task each time a rule fires
+pub const RULE_APPLIED_ACTION: &str = "rule_applied";
+
+/// Kind of change to a task that rules can react to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    Created,
+    #[default]
+    StatusChanged,
+    Assigned,
+}
+
+impl EventKind {
+    pub fn as_str(&self) -> &str {
+        match self {
+            EventKind::Created => "created",
+            EventKind::StatusChanged => "status_changed",
+            EventKind::Assigned => "assigned",
+        }
+    }
+}
+
+/// A change to a task, dispatched to the rules after it is committed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskEvent {
+    pub kind: EventKind,
+    /// Task UUID
+    pub task_id: String,
+}
+
+impl TaskEvent {
+    pub fn new(kind: EventKind, task_id: &str) -> Self {
+        TaskEvent {
+            kind,
+            task_id: task_id.to_string(),
+        }
+    }
+}
+
+/// What a task must look like, after the event, for a rule to fire
+///
+/// Unset fields match anything.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Conditions {
+    pub status: Option<String>,
+    pub epic: Option<String>,
+    pub priority: Option<String>,
+    /// The task carries this tag
+    pub tag: Option<String>,
+}
+
+impl Conditions {
+    /// e.g. "status = review, epic = payments", or "any task"
+    pub fn describe(&self) -> String {
+        let parts: Vec<String> = [
+            ("status", &self.status),
+            ("epic", &self.epic),
+            ("priority", &self.priority),
+            ("tag", &self.tag),
+        ]
+        .iter()
+        .filter_map(|(field, value)| value.as_ref().map(|v| format!("{} = {}", field, v)))
+        .collect();
+        if parts.is_empty() {
+            "any task".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+/// Changes a rule makes to the task
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Actions {
+    /// Agent ID or name to assign
+    pub assign: Option<String>,
+    pub tags: Vec<String>,
+    pub priority: Option<String>,
+    /// Note added to the task's activity log
+    pub log: Option<String>,
+}
+
+impl Actions {
+    /// e.g. "assign A9, tag needs-security"
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(agent) = &self.assign {
+            parts.push(format!("assign {}", agent));
+        }
+        for tag in &self.tags {
+            parts.push(format!("tag {}", tag));
+        }
+        if let Some(priority) = &self.priority {
+            parts.push(format!("priority {}", priority));
+        }
+        if let Some(note) = &self.log {
+            parts.push(format!("log \"{}\"", note));
+        }
+        parts.join(", ")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+    pub name: String,
+    #[serde(default)]
+    pub on: EventKind,
+    #[serde(default)]
+    pub when: Conditions,
+    pub then: Actions,
+}
+
+/// Automation rules for changes too simple to need a hook script
+/// (~/.prd/rules.toml)
+///
+/// ```toml
+/// [[rule]]
+/// name = "security review for payments"
+/// on = "status_changed"
+/// when = { status = "review", epic = "payments" }
+/// then = { assign = "A9", tags = ["needs-security"] }
+/// ```
+///
+/// Changes made by rules don't trigger further rules.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct RulesConfig {
+    #[serde(default, rename = "rule")]
+    pub rules: Vec<Rule>,
+}
+
+impl RulesConfig {
+    /// Load rules, with none if the file is missing
+    pub fn load() -> Result<Self> {
+        let config_path = Self::get_config_path()?;
+
+        if !config_path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(&config_path)
+            .context(format!("Failed to read rules from {:?}", config_path))?;
+        toml::from_str(&content).context("Failed to parse rules config as TOML")
+    }
+
+    /// Get the path to the rules file (~/.prd/rules.toml)
+    pub fn get_config_path() -> Result<PathBuf> {
+        let home = std::env::var("HOME")
+            .or_else(|_| std::env::var("USERPROFILE"))
+            .map_err(|_| anyhow::anyhow!("Could not determine home directory"))?;
+        Ok(PathBuf::from(home).join(".prd").join("rules.toml"))
+    }
+
+    /// Apply every rule matching `event`, in file order, returning the names
+    /// of the rules that fired
+    ///
+    /// Each rule sees the task as left by the rules before it.
+    pub fn apply(&self, db: &Database, event: &TaskEvent) -> Result<Vec<String>> {
+        let mut fired = Vec::new();
+        for rule in self.rules.iter().filter(|r| r.on == event.kind) {
+            if !rule.matches(db, &event.task_id)? {
+                continue;
+            }
+            db.write_transaction(|| {
+                rule.run(db, &event.task_id)
+                    .with_context(|| format!("Rule '{}' failed", rule.name))
+            })?;
+            fired.push(rule.name.clone());
+        }
+        Ok(fired)
+    }
+}
+
+impl Rule {
+    fn matches(&self, db: &Database, task_id: &str) -> Result<bool> {
+        let Some(task) = db.get_task(task_id)? else {
+            return Ok(false);
+        };
+        let when = &self.when;
+        let same = |want: &Option<String>, have: Option<&str>| {
+            want.as_deref()
+                .is_none_or(|w| have.is_some_and(|h| h.eq_ignore_ascii_case(w)))
+        };
+        if !same(&when.status, Some(task.status.as_str()))
+            || !same(&when.epic, task.epic_name.as_deref())
+            || !same(&when.priority, Some(task.priority.as_str()))
+        {
+            return Ok(false);
+        }
+        match &when.tag {
+            Some(tag) => Ok(db
+                .get_task_tags(task_id)?
+                .iter()
+                .any(|t| t.eq_ignore_ascii_case(tag))),
+            None => Ok(true),
+        }
+    }
+
+    fn run(&self, db: &Database, task_id: &str) -> Result<()> {
+        let then = &self.then;
+        if let Some(agent) = &then.assign {
+            let agent_id = resolve_agent_id(db.get_connection(), agent)?;
+            db.check_lease(task_id, &agent_id)?;
+            db.assign_task(task_id, &agent_id)?;
+        }
+        for tag in &then.tags {
+            db.add_task_tag(task_id, tag, None)?;
+        }
+        if let Some(priority) = &then.priority {
+            db.update_task_priority(task_id, db.parse_priority(priority)?, None)?;
+        }
+        if let Some(note) = &then.log {
+            db.log_task_action(task_id, None, "note", Some(note))?;
+        }
+        db.log_task_action(task_id, None, RULE_APPLIED_ACTION, Some(&self.name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Priority, TaskStatus};
+
+    #[test]
+    fn test_rules_apply_on_matching_event() -> Result<()> {
+        let config: RulesConfig = toml::from_str(
+            r#"
+            [[rule]]
+            name = "payments review"
+            when = { status = "review", epic = "payments" }
+            then = { assign = "security", tags = ["needs-security"], priority = "critical" }
+            "#,
+        )?;
+        let db = Database::new(":memory:")?;
+        let reviewer = db.create_agent("security".to_string())?;
+        let payments = db.create_task(
+            "Refunds".to_string(),
+            None,
+            Priority::Low,
+            None,
+            Some("payments".to_string()),
+        )?;
+        let other = db.create_task("Docs".to_string(), None, Priority::Low, None, None)?;
+
+        // Wrong status, wrong epic, wrong event kind
+        assert!(config
+            .apply(&db, &TaskEvent::new(EventKind::StatusChanged, &payments.id))?
+            .is_empty());
+        db.update_task_status(&other.id, TaskStatus::Review, None)?;
+        assert!(config
+            .apply(&db, &TaskEvent::new(EventKind::StatusChanged, &other.id))?
+            .is_empty());
+        db.update_task_status(&payments.id, TaskStatus::Review, None)?;
+        assert!(config
+            .apply(&db, &TaskEvent::new(EventKind::Created, &payments.id))?
+            .is_empty());
+
+        let fired = config.apply(&db, &TaskEvent::new(EventKind::StatusChanged, &payments.id))?;
+        assert_eq!(fired, ["payments review"]);
+        let task = db.get_task(&payments.id)?.unwrap();
+        assert_eq!(task.assigned_agent, Some(reviewer.id));
+        assert_eq!(task.priority, Priority::Critical);
+        assert_eq!(db.get_task_tags(&payments.id)?, ["needs-security"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rules_config_rejects_unknown_fields() {
+        let result: Result<RulesConfig, _> = toml::from_str(
+            r#"
+            [[rule]]
+            name = "typo"
+            when = { stauts = "review" }
+            then = { assign = "A9" }
+            "#,
+        );
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,689 @@
+use crate::db::{
+    Agent, AgentStatus, Database, Priority, Task, TaskLog, TaskStatus, COMPLETED_STATUSES_SQL,
+};
+use crate::db_extensions::{AcceptanceCriteriaOps, AcceptanceCriterion, DependencyOps};
+use crate::hooks::HookExecutor;
+use anyhow::{Context, Result};
+use rusqlite::OptionalExtension;
+use serde::Serialize;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Exit code a command uses to hand its task back unfinished (EX_TEMPFAIL)
+pub const EXIT_RELEASE: i32 = 75;
+
+/// Recent log entries included in a task's context
+const CONTEXT_LOGS: usize = 20;
+
+/// Completed tasks from the same epic included in a task's context
+const CONTEXT_RELATED: usize = 5;
+
+/// What `prd agent run` runs, and which tasks it may claim
+#[derive(Debug, Clone)]
+pub struct RunnerConfig {
+    /// Shell command run once per claimed task
+    pub command: String,
+    /// Only claim tasks in this epic
+    pub epic: Option<String>,
+    /// Only claim tasks with this priority
+    pub priority: Option<Priority>,
+    /// How long a claim holds the task's lease; renewed while the command runs
+    pub lease: chrono::Duration,
+    /// Kill the command after this long
+    pub timeout: Option<Duration>,
+    /// Database path passed to the command as PRD_DATABASE
+    pub database: Option<PathBuf>,
+}
+
+/// A dependency of the task being worked on
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyContext {
+    pub display_id: i32,
+    pub title: String,
+    pub status: String,
+}
+
+/// A finished task from the same epic, for background
+#[derive(Debug, Clone, Serialize)]
+pub struct RelatedTask {
+    pub display_id: Option<i32>,
+    pub title: String,
+    pub description: Option<String>,
+}
+
+/// Everything a command is given about its task
+///
+/// Written to the file named by PRD_CONTEXT_FILE and piped to the command's
+/// stdin as JSON.
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskContext {
+    pub task: Task,
+    pub agent: String,
+    pub acceptance_criteria: Vec<AcceptanceCriterion>,
+    pub dependencies: Vec<DependencyContext>,
+    /// Most recent first
+    pub logs: Vec<TaskLog>,
+    pub related: Vec<RelatedTask>,
+}
+
+impl TaskContext {
+    pub fn load(db: &Database, task: &Task, agent: &Agent) -> Result<Self> {
+        let conn = db.get_connection();
+        let (acceptance_criteria, dependencies) = match task.display_id {
+            Some(display_id) => {
+                let mut dependencies = Vec::new();
+                for dep in conn.get_dependencies(display_id)? {
+                    let Some((title, status)) = conn
+                        .query_row(
+                            "SELECT title, status FROM tasks
+                             WHERE display_id = ?1 AND deleted_at IS NULL",
+                            [dep],
+                            |row| Ok((row.get(0)?, row.get(1)?)),
+                        )
+                        .optional()?
+                    else {
+                        continue;
+                    };
+                    dependencies.push(DependencyContext {
+                        display_id: dep,
+                        title,
+                        status,
+                    });
+                }
+                (conn.list_criteria(display_id)?, dependencies)
+            }
+            None => (Vec::new(), Vec::new()),
+        };
+
+        let mut logs = db.get_task_logs(&task.id)?;
+        logs.truncate(CONTEXT_LOGS);
+
+        let mut related = Vec::new();
+        if let Some(epic) = &task.epic_name {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT display_id, title, description FROM tasks
+                 WHERE epic_name = ?1 AND status IN ({}) AND id != ?2 AND deleted_at IS NULL
+                 ORDER BY completed_at DESC LIMIT ?3",
+                COMPLETED_STATUSES_SQL
+            ))?;
+            related = stmt
+                .query_map(
+                    rusqlite::params![epic, &task.id, CONTEXT_RELATED as i64],
+                    |row| {
+                        Ok(RelatedTask {
+                            display_id: row.get(0)?,
+                            title: row.get(1)?,
+                            description: row.get(2)?,
+                        })
+                    },
+                )?
+                .collect::<Result<_, _>>()?;
+        }
+
+        Ok(Self {
+            task: task.clone(),
+            agent: agent.name.clone(),
+            acceptance_criteria,
+            dependencies,
+            logs,
+            related,
+        })
+    }
+}
+
+/// A line the command printed to report back, prefixed with `prd:`
+///
+/// ```text
+/// prd:progress 40 Wrote the migration
+/// prd:log Skipped flaky test
+/// prd:block Needs an API key
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Report {
+    Progress(u8, Option<String>),
+    Log(String),
+    Block(String),
+}
+
+impl Report {
+    /// Parse a protocol line, or `None` for ordinary output
+    pub fn parse(line: &str) -> Option<Self> {
+        let rest = line.trim().strip_prefix("prd:")?;
+        let (verb, arg) = match rest.split_once(char::is_whitespace) {
+            Some((verb, arg)) => (verb, arg.trim()),
+            None => (rest, ""),
+        };
+        match verb {
+            "progress" => {
+                let (pct, message) = match arg.split_once(char::is_whitespace) {
+                    Some((pct, message)) => (pct, Some(message.trim().to_string())),
+                    None => (arg, None),
+                };
+                let pct: u8 = pct.trim_end_matches('%').parse().ok()?;
+                Some(Report::Progress(pct.min(100), message))
+            }
+            "log" if !arg.is_empty() => Some(Report::Log(arg.to_string())),
+            "block" => Some(Report::Block(if arg.is_empty() {
+                "Blocked by agent".to_string()
+            } else {
+                arg.to_string()
+            })),
+            _ => None,
+        }
+    }
+}
+
+/// How a run ended for its task
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "outcome", content = "reason", rename_all = "snake_case")]
+pub enum Outcome {
+    /// The command exited 0: the task is completed
+    Completed,
+    /// The command failed or reported a block: the task is blocked
+    Blocked(String),
+    /// The command exited 75 or was interrupted: the task is pending again
+    Released(String),
+}
+
+/// The result of running the command for one task
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskRun {
+    pub task_id: String,
+    pub display_id: Option<i32>,
+    pub title: String,
+    #[serde(flatten)]
+    pub outcome: Outcome,
+    pub exit_code: Option<i32>,
+    pub duration_secs: u64,
+}
+
+/// What the runner is doing, for the caller to display
+pub enum RunEvent<'a> {
+    Claimed(&'a Task),
+    Output(&'a str),
+    Report(&'a Report),
+    Finished(&'a TaskRun),
+    Idle,
+}
+
+/// Claims ready tasks for one agent and runs a command on each
+pub struct AgentRunner<'a> {
+    db: &'a Database,
+    agent: Agent,
+    config: RunnerConfig,
+    hooks: Option<HookExecutor>,
+    /// Cleared to stop after the current task
+    pub running: Arc<AtomicBool>,
+}
+
+impl<'a> AgentRunner<'a> {
+    /// Create a runner for the named agent, registering it if it's new
+    pub fn new(db: &'a Database, agent: &str, config: RunnerConfig) -> Result<Self> {
+        let agent = match db.get_agent_by_name(agent)? {
+            Some(existing) => existing,
+            None => db.create_agent(agent.to_string())?,
+        };
+        Ok(Self {
+            db,
+            agent,
+            config,
+            hooks: None,
+            running: Arc::new(AtomicBool::new(true)),
+        })
+    }
+
+    /// Fire on_task_start, on_task_complete and on_agent_error hooks
+    pub fn with_hooks(mut self, hooks: HookExecutor) -> Self {
+        self.hooks = Some(hooks);
+        self
+    }
+
+    pub fn agent(&self) -> &Agent {
+        &self.agent
+    }
+
+    fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    /// Claim the first task of the ready queue that nobody else has leased
+    ///
+    /// Leasing, assigning and starting the task happen in one transaction,
+    /// so two workers never claim the same task. Nothing is claimed outside
+    /// the agent's availability windows.
+    pub fn claim(&self) -> Result<Option<Task>> {
+        let now = chrono::Local::now().time();
+        if !self
+            .db
+            .get_agent_capacity(&self.agent.id)?
+            .is_available(now)
+        {
+            return Ok(None);
+        }
+        self.db.write_transaction(|| {
+            let mut candidates = Vec::new();
+            let conn = self.db.get_connection();
+            for display_id in conn.get_ready_tasks()? {
+                let id: String = conn.query_row(
+                    "SELECT id FROM tasks WHERE display_id = ?1",
+                    [display_id],
+                    |row| row.get(0),
+                )?;
+                let Some(task) = self.db.get_task(&id)? else {
+                    continue;
+                };
+                if task.status != TaskStatus::Pending
+                    || self
+                        .config
+                        .epic
+                        .as_ref()
+                        .is_some_and(|e| task.epic_name.as_ref() != Some(e))
+                    || self
+                        .config
+                        .priority
+                        .as_ref()
+                        .is_some_and(|p| &task.priority != p)
+                {
+                    continue;
+                }
+                match self.db.get_active_lease(&task.id)? {
+                    Some(lease) if lease.agent_id != self.agent.id => {}
+                    _ => candidates.push(task),
+                }
+            }
+            self.db.sort_ready_queue(&mut candidates)?;
+
+            // Stay idle rather than go over a WIP limit
+            let mut claimable = None;
+            for task in candidates {
+                let exceeded = self.db.check_wip(
+                    &task.id,
+                    Some(&self.agent.id),
+                    Some(&TaskStatus::InProgress),
+                )?;
+                if exceeded.is_empty() {
+                    claimable = Some(task);
+                    break;
+                }
+            }
+            let Some(mut task) = claimable else {
+                return Ok(None);
+            };
+            self.db
+                .acquire_lease(&task.id, &self.agent.id, self.config.lease)?;
+            self.db.assign_task(&task.id, &self.agent.id)?;
+            self.db
+                .update_task_status(&task.id, TaskStatus::InProgress, Some(&self.agent.id))?;
+            self.db
+                .update_agent_status(&self.agent.id, AgentStatus::Working, Some(&task.id))?;
+            task.status = TaskStatus::InProgress;
+            task.assigned_agent = Some(self.agent.id.clone());
+            Ok(Some(task))
+        })
+    }
+
+    /// Run the command for a claimed task and complete, block or release it
+    pub fn run_task(&self, task: &Task, on_event: &mut dyn FnMut(RunEvent)) -> Result<TaskRun> {
+        let started = Instant::now();
+        if let Some(hooks) = &self.hooks {
+            hooks.trigger_task_start(task, &self.agent)?;
+        }
+
+        let context = TaskContext::load(self.db, task, &self.agent)?;
+        let context_json = serde_json::to_string_pretty(&context)?;
+        let context_file = std::env::temp_dir().join(format!(
+            "prd-task-{}-{}.json",
+            task.display_id
+                .map(|id| id.to_string())
+                .unwrap_or_else(|| task.id.clone()),
+            std::process::id()
+        ));
+        std::fs::write(&context_file, &context_json)
+            .with_context(|| format!("Failed to write {}", context_file.display()))?;
+
+        let result = self.execute(task, &context_file, context_json, on_event);
+        let _ = std::fs::remove_file(&context_file);
+        let (exit_code, blocked) = match result {
+            Ok(ended) => ended,
+            Err(e) => (None, Some(format!("{:#}", e))),
+        };
+
+        let outcome = if !self.is_running() {
+            Outcome::Released("Interrupted".to_string())
+        } else if let Some(reason) = blocked {
+            Outcome::Blocked(reason)
+        } else {
+            match exit_code {
+                Some(0) => Outcome::Completed,
+                Some(EXIT_RELEASE) => Outcome::Released("Handed back by the command".to_string()),
+                Some(code) => Outcome::Blocked(format!("Command exited with status {}", code)),
+                None => Outcome::Blocked("Command was killed by a signal".to_string()),
+            }
+        };
+
+        let run = TaskRun {
+            task_id: task.id.clone(),
+            display_id: task.display_id,
+            title: task.title.clone(),
+            outcome,
+            exit_code,
+            duration_secs: started.elapsed().as_secs(),
+        };
+        self.finish(task, &run)?;
+        on_event(RunEvent::Finished(&run));
+        Ok(run)
+    }
+
+    /// Claim and run tasks until `limit` have run or the runner is stopped
+    ///
+    /// With no `poll` interval, returns as soon as no task is ready;
+    /// otherwise waits that long and looks again.
+    pub fn run(
+        &self,
+        limit: Option<usize>,
+        poll: Option<Duration>,
+        on_event: &mut dyn FnMut(RunEvent),
+    ) -> Result<Vec<TaskRun>> {
+        let mut runs = Vec::new();
+        while self.is_running() && limit.is_none_or(|n| runs.len() < n) {
+            match self.claim()? {
+                Some(task) => {
+                    on_event(RunEvent::Claimed(&task));
+                    runs.push(self.run_task(&task, on_event)?);
+                }
+                None => {
+                    on_event(RunEvent::Idle);
+                    let Some(poll) = poll else { break };
+                    let until = Instant::now() + poll;
+                    while self.is_running() && Instant::now() < until {
+                        thread::sleep(Duration::from_millis(200));
+                    }
+                }
+            }
+        }
+        self.db
+            .update_agent_status(&self.agent.id, AgentStatus::Idle, None)?;
+        Ok(runs)
+    }
+
+    /// Run the command, returning its exit code and any block it reported
+    fn execute(
+        &self,
+        task: &Task,
+        context_file: &std::path::Path,
+        context_json: String,
+        on_event: &mut dyn FnMut(RunEvent),
+    ) -> Result<(Option<i32>, Option<String>)> {
+        let mut command = shell_command(&self.config.command);
+        command
+            .env(
+                "PRD_TASK_ID",
+                task.display_id.map(|id| id.to_string()).unwrap_or_default(),
+            )
+            .env("PRD_TASK_UUID", &task.id)
+            .env("PRD_TASK_TITLE", &task.title)
+            .env("PRD_TASK_PRIORITY", task.priority.as_str())
+            .env("PRD_EPIC", task.epic_name.as_deref().unwrap_or(""))
+            .env("PRD_AGENT", &self.agent.name)
+            .env("PRD_AGENT_ID", &self.agent.id)
+            .env("PRD_CONTEXT_FILE", context_file)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit());
+        if let Some(database) = &self.config.database {
+            command.env("PRD_DATABASE", database);
+        }
+        let mut child = command
+            .spawn()
+            .with_context(|| format!("Failed to run '{}'", self.config.command))?;
+
+        // Commands that ignore stdin close it early; that's not an error
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        thread::spawn(move || {
+            let _ = stdin.write_all(context_json.as_bytes());
+        });
+
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let deadline = self.config.timeout.map(|t| Instant::now() + t);
+        let renew_every = (self.config.lease / 2)
+            .to_std()
+            .unwrap_or(Duration::from_secs(60));
+        let mut renewed = Instant::now();
+        let mut blocked = None;
+        let mut timed_out = false;
+        loop {
+            match rx.recv_timeout(Duration::from_millis(250)) {
+                Ok(line) => match Report::parse(&line) {
+                    Some(report) => {
+                        if let Some(reason) = self.record(task, &report)? {
+                            blocked = Some(reason);
+                        }
+                        on_event(RunEvent::Report(&report));
+                    }
+                    None => on_event(RunEvent::Output(&line)),
+                },
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+            if deadline.is_some_and(|d| Instant::now() >= d) {
+                timed_out = true;
+                let _ = child.kill();
+                break;
+            }
+            if renewed.elapsed() >= renew_every {
+                self.db
+                    .acquire_lease(&task.id, &self.agent.id, self.config.lease)?;
+                renewed = Instant::now();
+            }
+        }
+
+        let status = child.wait()?;
+        if timed_out {
+            let timeout = self.config.timeout.unwrap_or_default();
+            blocked = Some(format!("Command timed out after {}s", timeout.as_secs()));
+        }
+        Ok((status.code(), blocked))
+    }
+
+    /// Record a report, returning the reason if it blocks the task
+    fn record(&self, task: &Task, report: &Report) -> Result<Option<String>> {
+        match report {
+            Report::Progress(pct, message) => {
+                if let Some(display_id) = task.display_id {
+                    self.db
+                        .report_progress(&self.agent.id, display_id, *pct, message.clone())?;
+                }
+                Ok(None)
+            }
+            Report::Log(message) => {
+                self.db.log_task_action(
+                    &task.id,
+                    Some(&self.agent.id),
+                    "agent_log",
+                    Some(message),
+                )?;
+                Ok(None)
+            }
+            Report::Block(reason) => Ok(Some(reason.clone())),
+        }
+    }
+
+    fn finish(&self, task: &Task, run: &TaskRun) -> Result<()> {
+        let agent = Some(self.agent.id.as_str());
+        let details = match &run.outcome {
+            Outcome::Completed => format!("Completed in {}s", run.duration_secs),
+            Outcome::Blocked(reason) => format!("Blocked: {}", reason),
+            Outcome::Released(reason) => format!("Released: {}", reason),
+        };
+        self.db.write_transaction(|| {
+            match &run.outcome {
+                Outcome::Completed => {
+                    self.db
+                        .update_task_status(&task.id, TaskStatus::Completed, agent)?;
+                }
+                Outcome::Blocked(_) => {
+                    self.db
+                        .update_task_status(&task.id, TaskStatus::Blocked, agent)?;
+                    self.db.release_lease(&task.id)?;
+                }
+                Outcome::Released(_) => {
+                    self.db
+                        .update_task_status(&task.id, TaskStatus::Pending, agent)?;
+                    self.db.release_lease(&task.id)?;
+                }
+            }
+            self.db
+                .log_task_action(&task.id, agent, "agent_run", Some(&details))?;
+            self.db
+                .update_agent_status(&self.agent.id, AgentStatus::Idle, None)
+        })?;
+
+        if let Some(hooks) = &self.hooks {
+            let mut task = task.clone();
+            match &run.outcome {
+                Outcome::Completed => {
+                    task.status = TaskStatus::Completed;
+                    hooks.trigger_task_complete(&task, &self.agent)?;
+                }
+                Outcome::Blocked(reason) => {
+                    task.status = TaskStatus::Blocked;
+                    hooks.trigger_agent_error(&self.agent, &task, reason)?;
+                }
+                Outcome::Released(_) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Run a command line through the platform shell, so pipes and quoting work
+fn shell_command(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(command: &str) -> RunnerConfig {
+        RunnerConfig {
+            command: command.to_string(),
+            epic: None,
+            priority: None,
+            lease: chrono::Duration::minutes(5),
+            timeout: Some(Duration::from_secs(10)),
+            database: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_reports() {
+        assert_eq!(
+            Report::parse("prd:progress 40 Wrote tests"),
+            Some(Report::Progress(40, Some("Wrote tests".to_string())))
+        );
+        assert_eq!(
+            Report::parse("  prd:progress 150%"),
+            Some(Report::Progress(100, None))
+        );
+        assert_eq!(
+            Report::parse("prd:block"),
+            Some(Report::Block("Blocked by agent".to_string()))
+        );
+        assert_eq!(Report::parse("prd:progress lots"), None);
+        assert_eq!(Report::parse("building prd:progress 10"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_claims_by_priority_and_reports() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        let low = db.create_task("Low".to_string(), None, Priority::Low, None, None)?;
+        let high = db.create_task("High".to_string(), None, Priority::High, None, None)?;
+
+        let runner = AgentRunner::new(
+            &db,
+            "worker",
+            config(
+                "read -r first; echo \"$first\"; echo prd:progress 50 halfway; \
+                 test \"$PRD_TASK_TITLE\" = High && test -s \"$PRD_CONTEXT_FILE\"",
+            ),
+        )?;
+        let mut output = Vec::new();
+        let runs = runner.run(Some(1), None, &mut |event| {
+            if let RunEvent::Output(line) = event {
+                output.push(line.to_string());
+            }
+        })?;
+
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].task_id, high.id);
+        assert_eq!(runs[0].outcome, Outcome::Completed);
+        assert_eq!(output, vec!["{".to_string()]);
+        let task = db.get_task(&high.id)?.unwrap();
+        assert_eq!(task.status, TaskStatus::Completed);
+        assert_eq!(
+            task.assigned_agent.as_deref(),
+            Some(runner.agent().id.as_str())
+        );
+        let progress = db.get_task_progress(high.display_id.unwrap())?;
+        assert_eq!(progress[0].progress, 50);
+        assert_eq!(db.get_task(&low.id)?.unwrap().status, TaskStatus::Pending);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_failed_and_released_runs() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        let first = db.create_task("First".to_string(), None, Priority::High, None, None)?;
+        let second = db.create_task("Second".to_string(), None, Priority::Low, None, None)?;
+
+        let runner = AgentRunner::new(
+            &db,
+            "worker",
+            config("if [ \"$PRD_TASK_TITLE\" = First ]; then exit 3; else exit 75; fi"),
+        )?;
+        let runs = runner.run(Some(2), None, &mut |_| {})?;
+        assert_eq!(
+            runs[0].outcome,
+            Outcome::Blocked("Command exited with status 3".to_string())
+        );
+        assert!(matches!(runs[1].outcome, Outcome::Released(_)));
+
+        assert_eq!(db.get_task(&first.id)?.unwrap().status, TaskStatus::Blocked);
+        assert_eq!(
+            db.get_task(&second.id)?.unwrap().status,
+            TaskStatus::Pending
+        );
+        assert!(db.get_active_lease(&second.id)?.is_none());
+
+        // Blocked tasks aren't claimed again; the released one is
+        let again = runner.claim()?.unwrap();
+        assert_eq!(again.id, second.id);
+        Ok(())
+    }
+}
@@ -0,0 +1,263 @@
+//! Self-update - replace the running `prd` binary with the latest release
+//!
+//! Releases are looked up through the GitHub API for `PRD_UPDATE_REPO`
+//! (defaults to `captaindev404/prd-tools`). Each release carries one binary
+//! per platform, named `prd-<arch>-<os>` (`.exe` on Windows), and a
+//! `SHA256SUMS` file; a download is only installed if its hash is listed
+//! there. That file comes from the same release and is not signed, so the
+//! check catches a corrupted download but not a tampered release. The new
+//! binary is written next to the old one and renamed over it, so an
+//! interrupted update leaves the old binary in place.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Version of the running binary
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub const REPO_ENV: &str = "PRD_UPDATE_REPO";
+pub const DEFAULT_REPO: &str = "captaindev404/prd-tools";
+
+/// Release asset listing the SHA-256 of every other asset
+pub const CHECKSUMS_ASSET: &str = "SHA256SUMS";
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A published release
+#[derive(Debug, Clone, Deserialize)]
+pub struct Release {
+    #[serde(rename = "tag_name")]
+    pub tag: String,
+    #[serde(default)]
+    pub assets: Vec<Asset>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Asset {
+    pub name: String,
+    #[serde(rename = "browser_download_url")]
+    pub url: String,
+}
+
+impl Release {
+    /// Version without the leading `v` of the tag
+    pub fn version(&self) -> &str {
+        self.tag.trim_start_matches('v')
+    }
+
+    fn asset(&self, name: &str) -> Result<&Asset> {
+        self.assets
+            .iter()
+            .find(|asset| asset.name == name)
+            .with_context(|| format!("Release {} has no {} asset", self.tag, name))
+    }
+}
+
+/// The running version against the latest release
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionCheck {
+    pub current: String,
+    pub latest: String,
+    pub update_available: bool,
+}
+
+/// Result of [`self_update`]
+#[derive(Debug, Clone, Serialize)]
+pub enum UpdateOutcome {
+    /// Already on the latest release (or newer)
+    UpToDate { version: String },
+    /// Replaced the binary at `path`
+    Updated {
+        from: String,
+        to: String,
+        path: PathBuf,
+    },
+}
+
+/// Repository releases are fetched from
+pub fn repo() -> String {
+    std::env::var(REPO_ENV)
+        .ok()
+        .filter(|repo| !repo.is_empty())
+        .unwrap_or_else(|| DEFAULT_REPO.to_string())
+}
+
+/// Release asset name of the binary for this platform, e.g.
+/// `prd-x86_64-linux`
+pub fn asset_name() -> String {
+    let name = format!("prd-{}-{}", std::env::consts::ARCH, std::env::consts::OS);
+    if cfg!(windows) {
+        name + ".exe"
+    } else {
+        name
+    }
+}
+
+/// Latest published release
+pub fn latest_release() -> Result<Release> {
+    let url = format!("https://api.github.com/repos/{}/releases/latest", repo());
+    let body = ureq::get(&url)
+        .timeout(TIMEOUT)
+        .set("Accept", "application/vnd.github+json")
+        .set("User-Agent", &format!("prd/{}", CURRENT_VERSION))
+        .call()
+        .with_context(|| format!("Failed to fetch the latest release from {}", url))?
+        .into_string()?;
+    serde_json::from_str(&body).context("Unexpected release metadata from GitHub")
+}
+
+/// Compare the running version with the latest release
+pub fn check() -> Result<VersionCheck> {
+    let release = latest_release()?;
+    Ok(VersionCheck {
+        current: CURRENT_VERSION.to_string(),
+        latest: release.version().to_string(),
+        update_available: is_newer(release.version(), CURRENT_VERSION),
+    })
+}
+
+/// Whether `candidate` is a later version than `current`
+///
+/// Versions compare numerically by dot-separated component; a pre-release
+/// suffix (`-rc.1`) is ignored, and unparseable versions are never newer.
+pub fn is_newer(candidate: &str, current: &str) -> bool {
+    match (parse_version(candidate), parse_version(current)) {
+        (Some(candidate), Some(current)) => candidate > current,
+        _ => false,
+    }
+}
+
+fn parse_version(version: &str) -> Option<Vec<u64>> {
+    let version = version.trim_start_matches('v');
+    let core = version.split(['-', '+']).next()?;
+    let mut parts = core
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect::<Option<Vec<u64>>>()?;
+    while parts.len() > 1 && parts.last() == Some(&0) {
+        parts.pop();
+    }
+    Some(parts)
+}
+
+/// Update the running binary to the latest release unless it is already
+/// current; `force` reinstalls the latest release regardless
+pub fn self_update(force: bool) -> Result<UpdateOutcome> {
+    let release = latest_release()?;
+    if !force && !is_newer(release.version(), CURRENT_VERSION) {
+        return Ok(UpdateOutcome::UpToDate {
+            version: CURRENT_VERSION.to_string(),
+        });
+    }
+
+    let name = asset_name();
+    let asset = release
+        .asset(&name)
+        .with_context(|| format!("No {} build for this platform", name))?;
+    let binary = download(asset)?;
+    let sums = String::from_utf8(download(release.asset(CHECKSUMS_ASSET)?)?)
+        .context("SHA256SUMS is not valid UTF-8")?;
+    check_checksum(&binary, &sums, &name)?;
+
+    let path = std::env::current_exe().context("Failed to locate the running binary")?;
+    install(&binary, &path)?;
+    Ok(UpdateOutcome::Updated {
+        from: CURRENT_VERSION.to_string(),
+        to: release.version().to_string(),
+        path,
+    })
+}
+
+fn download(asset: &Asset) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    ureq::get(&asset.url)
+        .timeout(TIMEOUT)
+        .set("User-Agent", &format!("prd/{}", CURRENT_VERSION))
+        .call()
+        .with_context(|| format!("Failed to download {}", asset.name))?
+        .into_reader()
+        .read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Check `bytes` against the entry for `name` in a `sha256sum`-style list
+/// (`<hex digest>  <file name>` per line)
+///
+/// This only detects corruption: whoever can replace a release asset can
+/// replace the list too.
+pub fn check_checksum(bytes: &[u8], sums: &str, name: &str) -> Result<()> {
+    let expected = sums
+        .lines()
+        .filter_map(|line| line.split_once(char::is_whitespace))
+        .find(|(_, file)| file.trim().trim_start_matches('*') == name)
+        .map(|(digest, _)| digest.to_ascii_lowercase())
+        .with_context(|| format!("{} lists no checksum for {}", CHECKSUMS_ASSET, name))?;
+    let actual: String = Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    if actual != expected {
+        anyhow::bail!(
+            "Checksum mismatch for {}: expected {}, got {}",
+            name,
+            expected,
+            actual
+        );
+    }
+    Ok(())
+}
+
+/// Swap `binary` in for the executable at `path`
+fn install(binary: &[u8], path: &Path) -> Result<()> {
+    let staged = path.with_extension("new");
+    std::fs::write(&staged, binary)
+        .with_context(|| format!("Failed to write {}", staged.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o755))?;
+    }
+    // Windows can't replace a running executable, but it can rename it
+    #[cfg(windows)]
+    {
+        let old = path.with_extension("old");
+        let _ = std::fs::remove_file(&old);
+        std::fs::rename(path, &old)?;
+    }
+    std::fs::rename(&staged, path)
+        .with_context(|| format!("Failed to replace {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_comparison_and_checksums() -> Result<()> {
+        assert!(is_newer("v0.2.0", "0.1.9"));
+        assert!(is_newer("0.10.0", "0.9.0"));
+        assert!(is_newer("1.0.1", "1.0"));
+        assert!(!is_newer("1.0.0", "1.0"));
+        assert!(!is_newer("1.0.0-rc.1", "1.0.0"));
+        assert!(!is_newer("nightly", "0.1.0"));
+
+        let digest: String = Sha256::digest(b"binary")
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        let sums = format!(
+            "{}  prd-x86_64-linux\n{} *prd-aarch64-macos\n",
+            digest,
+            "0".repeat(64)
+        );
+        check_checksum(b"binary", &sums, "prd-x86_64-linux")?;
+        assert!(check_checksum(b"binary", &sums, "prd-aarch64-macos").is_err());
+        assert!(check_checksum(b"binary", &sums, "prd-x86_64-windows.exe").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,216 @@
+use super::http::Request;
+use crate::db::Database;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, OptionalExtension, Row};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+/// What an API token may do; each scope includes the ones below it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenScope {
+    /// Read-only access to the GET endpoints
+    Read,
+    /// Read access plus progress reporting
+    Agent,
+    /// Everything, including token management endpoints
+    Admin,
+}
+
+impl TokenScope {
+    pub fn as_str(&self) -> &str {
+        match self {
+            TokenScope::Read => "read",
+            TokenScope::Agent => "agent",
+            TokenScope::Admin => "admin",
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self> {
+        match s {
+            "read" | "read-only" => Ok(TokenScope::Read),
+            "agent" => Ok(TokenScope::Agent),
+            "admin" => Ok(TokenScope::Admin),
+            _ => Err(anyhow::anyhow!(
+                "Invalid scope '{}' (use read, agent or admin)",
+                s
+            )),
+        }
+    }
+
+    /// Whether this scope grants `required`
+    pub fn allows(&self, required: TokenScope) -> bool {
+        *self >= required
+    }
+}
+
+/// Metadata of a stored API token (the secret itself is only kept hashed)
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiToken {
+    pub name: String,
+    pub scope: TokenScope,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// Manages API tokens in the `api_tokens` table
+pub struct TokenStore<'a> {
+    db: &'a Database,
+}
+
+impl<'a> TokenStore<'a> {
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// Create a token, returning its metadata and the plaintext secret
+    ///
+    /// The secret is not stored and cannot be shown again.
+    pub fn create(&self, name: &str, scope: TokenScope) -> Result<(ApiToken, String)> {
+        let secret = format!(
+            "prd_{}{}",
+            uuid::Uuid::new_v4().simple(),
+            uuid::Uuid::new_v4().simple()
+        );
+        let now = Utc::now();
+
+        self.db
+            .get_connection()
+            .execute(
+                "INSERT INTO api_tokens (name, token_hash, scope, created_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![name, hash_token(&secret), scope.as_str(), now.to_rfc3339()],
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::SqliteFailure(f, _)
+                    if f.code == rusqlite::ErrorCode::ConstraintViolation =>
+                {
+                    anyhow::anyhow!("A token named '{}' already exists", name)
+                }
+                e => e.into(),
+            })?;
+
+        let token = ApiToken {
+            name: name.to_string(),
+            scope,
+            created_at: now,
+            last_used_at: None,
+            revoked_at: None,
+        };
+        Ok((token, secret))
+    }
+
+    /// Revoke a token by name, returning whether an active token was revoked
+    pub fn revoke(&self, name: &str) -> Result<bool> {
+        let updated = self.db.get_connection().execute(
+            "UPDATE api_tokens SET revoked_at = ?1 WHERE name = ?2 AND revoked_at IS NULL",
+            params![Utc::now().to_rfc3339(), name],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// List all tokens, including revoked ones
+    pub fn list(&self) -> Result<Vec<ApiToken>> {
+        let mut stmt = self.db.get_connection().prepare(
+            "SELECT name, scope, created_at, last_used_at, revoked_at
+             FROM api_tokens ORDER BY created_at ASC",
+        )?;
+        let tokens = stmt
+            .query_map([], row_to_token)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(tokens)
+    }
+
+    /// Look up an active token by its secret and record the use
+    pub fn authenticate(&self, secret: &str) -> Result<Option<ApiToken>> {
+        let hash = hash_token(secret);
+        let token = self
+            .db
+            .get_connection()
+            .query_row(
+                "SELECT name, scope, created_at, last_used_at, revoked_at
+                 FROM api_tokens WHERE token_hash = ?1 AND revoked_at IS NULL",
+                params![&hash],
+                row_to_token,
+            )
+            .optional()?;
+
+        if token.is_some() {
+            self.db.get_connection().execute(
+                "UPDATE api_tokens SET last_used_at = ?1 WHERE token_hash = ?2",
+                params![Utc::now().to_rfc3339(), &hash],
+            )?;
+        }
+        Ok(token)
+    }
+}
+
+/// Extract the token from `Authorization: Bearer ...` or a `?token=` query
+/// parameter (browsers cannot set headers on `EventSource`)
+pub fn request_token(request: &Request) -> Option<&str> {
+    request
+        .header("authorization")
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .map(|t| t.trim())
+        .or_else(|| request.query.get("token").map(|t| t.as_str()))
+        .filter(|t| !t.is_empty())
+}
+
+fn hash_token(secret: &str) -> String {
+    Sha256::digest(secret.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn parse_time(value: Option<String>) -> Option<DateTime<Utc>> {
+    value
+        .and_then(|v| DateTime::parse_from_rfc3339(&v).ok())
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+fn row_to_token(row: &Row) -> rusqlite::Result<ApiToken> {
+    let scope: String = row.get(1)?;
+    Ok(ApiToken {
+        name: row.get(0)?,
+        scope: TokenScope::parse(&scope).unwrap_or(TokenScope::Read),
+        created_at: parse_time(Some(row.get(2)?)).unwrap_or_else(Utc::now),
+        last_used_at: parse_time(row.get(3)?),
+        revoked_at: parse_time(row.get(4)?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_authenticate_revoke() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        let store = TokenStore::new(&db);
+
+        let (token, secret) = store.create("ci", TokenScope::Agent)?;
+        assert_eq!(token.scope, TokenScope::Agent);
+        assert!(store.create("ci", TokenScope::Read).is_err());
+
+        let found = store.authenticate(&secret)?.unwrap();
+        assert_eq!(found.name, "ci");
+        assert!(store.list()?[0].last_used_at.is_some());
+        assert!(store.authenticate("prd_wrong")?.is_none());
+
+        assert!(store.revoke("ci")?);
+        assert!(store.authenticate(&secret)?.is_none());
+        assert!(!store.revoke("ci")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_scope_ordering() {
+        assert!(TokenScope::Admin.allows(TokenScope::Agent));
+        assert!(TokenScope::Agent.allows(TokenScope::Read));
+        assert!(!TokenScope::Read.allows(TokenScope::Agent));
+    }
+}
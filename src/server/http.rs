@@ -0,0 +1,300 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+
+/// Largest request body accepted (the API only takes small JSON payloads)
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// A parsed HTTP request (only what the API needs)
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: HashMap<String, String>,
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// Parse the request line, headers and (`Content-Length`) body from a buffered stream
+    pub fn parse<R: BufRead>(reader: &mut R) -> Result<Self> {
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+
+        let mut parts = request_line.split_whitespace();
+        let method = parts
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Empty request"))?
+            .to_string();
+        let target = parts
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Missing request target"))?;
+
+        let (path, query_str) = match target.split_once('?') {
+            Some((p, q)) => (p.to_string(), q),
+            None => (target.to_string(), ""),
+        };
+
+        let query = query_str
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| match pair.split_once('=') {
+                Some((k, v)) => (percent_decode(k), percent_decode(v)),
+                None => (percent_decode(pair), String::new()),
+            })
+            .collect();
+
+        let mut headers = HashMap::new();
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line)? == 0 {
+                break;
+            }
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+            }
+        }
+
+        let length: usize = headers
+            .get("content-length")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0);
+        if length > MAX_BODY_BYTES {
+            return Err(anyhow::anyhow!("Request body too large"));
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body)?;
+
+        Ok(Self {
+            method,
+            path,
+            query,
+            headers,
+            body,
+        })
+    }
+
+    /// Get a header value (names are case-insensitive)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(&name.to_lowercase()).map(|s| s.as_str())
+    }
+
+    /// Whether the body is declared as JSON (`Content-Type: application/json`)
+    ///
+    /// Browsers can't send that type cross-origin without a preflight, which
+    /// the server never answers.
+    pub fn has_json_body(&self) -> bool {
+        self.header("content-type")
+            .and_then(|v| v.split(';').next())
+            .is_some_and(|v| v.trim().eq_ignore_ascii_case("application/json"))
+    }
+}
+
+/// Decode a query string component: `%XX` escapes and `+` for space
+///
+/// Malformed escapes are kept as written.
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' => match input
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                Some(byte) => {
+                    decoded.push(byte);
+                    i += 2;
+                }
+                None => decoded.push(b'%'),
+            },
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// An HTTP response with a fully buffered body
+#[derive(Debug, Clone)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: String,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    /// JSON response with status 200
+    pub fn json<T: serde::Serialize>(value: &T) -> Result<Self> {
+        Ok(Self {
+            status: 200,
+            content_type: "application/json".to_string(),
+            body: serde_json::to_vec_pretty(value)?,
+        })
+    }
+
+    /// HTML response with status 200
+    pub fn html(body: &str) -> Self {
+        Self {
+            status: 200,
+            content_type: "text/html; charset=utf-8".to_string(),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    /// SVG image response with status 200
+    pub fn svg(body: &str) -> Self {
+        Self {
+            status: 200,
+            content_type: "image/svg+xml".to_string(),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    /// JSON error response with the given status
+    pub fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            content_type: "application/json".to_string(),
+            body: serde_json::json!({ "error": message })
+                .to_string()
+                .into_bytes(),
+        }
+    }
+
+    /// Write the response to a stream and close the exchange
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> Result<()> {
+        write!(
+            writer,
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            self.status,
+            status_text(self.status),
+            self.content_type,
+            self.body.len()
+        )?;
+        writer.write_all(&self.body)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Write the headers that open a Server-Sent Events stream
+pub fn write_sse_headers<W: Write>(writer: &mut W) -> Result<()> {
+    write!(
+        writer,
+        "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n"
+    )?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Write a single SSE event
+pub fn write_sse_event<W: Write>(writer: &mut W, event: &str, data: &str) -> Result<()> {
+    writeln!(writer, "event: {}", event)?;
+    for line in data.lines() {
+        writeln!(writer, "data: {}", line)?;
+    }
+    writer.write_all(b"\n")?;
+    writer.flush()?;
+    Ok(())
+}
+
+fn status_text(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        415 => "Unsupported Media Type",
+        _ => "Internal Server Error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::BufReader;
+
+    #[test]
+    fn test_parse_request_with_query() {
+        let raw = "GET /api/tasks?status=pending&limit=5 HTTP/1.1\r\nHost: localhost\r\nX-Test: Yes\r\n\r\n";
+        let mut reader = BufReader::new(raw.as_bytes());
+        let req = Request::parse(&mut reader).unwrap();
+
+        assert_eq!(req.method, "GET");
+        assert_eq!(req.path, "/api/tasks");
+        assert_eq!(req.query.get("status").unwrap(), "pending");
+        assert_eq!(req.query.get("limit").unwrap(), "5");
+        assert_eq!(req.header("x-test"), Some("Yes"));
+        assert_eq!(req.header("HOST"), Some("localhost"));
+        assert!(req.body.is_empty());
+    }
+
+    #[test]
+    fn test_parse_request_decodes_query() {
+        let raw = "GET /badge.svg?label=build%20status&epic=Auth+%26+SSO&to%6Ben=a%2Fb&bad=100%25%zz HTTP/1.1\r\n\r\n";
+        let mut reader = BufReader::new(raw.as_bytes());
+        let req = Request::parse(&mut reader).unwrap();
+
+        assert_eq!(req.query.get("label").unwrap(), "build status");
+        assert_eq!(req.query.get("epic").unwrap(), "Auth & SSO");
+        assert_eq!(req.query.get("token").unwrap(), "a/b");
+        assert_eq!(req.query.get("bad").unwrap(), "100%%zz");
+        assert_eq!(percent_decode("caf%C3%A9"), "café");
+    }
+
+    #[test]
+    fn test_parse_request_body() {
+        let raw = "POST /api/progress HTTP/1.1\r\nContent-Length: 8\r\n\r\n{\"a\": 1}ignored";
+        let mut reader = BufReader::new(raw.as_bytes());
+        let mut req = Request::parse(&mut reader).unwrap();
+
+        assert_eq!(req.method, "POST");
+        assert_eq!(req.body, b"{\"a\": 1}");
+        assert!(!req.has_json_body());
+
+        req.headers.insert(
+            "content-type".to_string(),
+            "Application/JSON; charset=utf-8".to_string(),
+        );
+        assert!(req.has_json_body());
+    }
+
+    #[test]
+    fn test_parse_empty_request() {
+        let mut reader = BufReader::new("".as_bytes());
+        assert!(Request::parse(&mut reader).is_err());
+    }
+
+    #[test]
+    fn test_response_write() {
+        let mut out = Vec::new();
+        Response::error(404, "Not found")
+            .write_to(&mut out)
+            .unwrap();
+        let text = String::from_utf8(out).unwrap();
+
+        assert!(text.starts_with("HTTP/1.1 404 Not Found\r\n"));
+        assert!(text.contains("Content-Type: application/json"));
+        assert!(!text.contains("Access-Control-Allow-Origin"));
+        assert!(text.ends_with("{\"error\":\"Not found\"}"));
+    }
+
+    #[test]
+    fn test_sse_event_format() {
+        let mut out = Vec::new();
+        write_sse_event(&mut out, "snapshot", "{\"a\":1}").unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "event: snapshot\ndata: {\"a\":1}\n\n"
+        );
+    }
+}
@@ -0,0 +1,123 @@
+//! HTTP server for the PRD tool (`prd serve`)
+//!
+//! This module exposes a small REST API and a Server-Sent Events stream over
+//! the task database, plus an optional bundled web dashboard:
+//! - `GET /api/stats`  - task counts by status
+//! - `GET /api/agents` - agents with their latest progress
+//! - `GET /api/tasks`  - tasks (optionally `?status=...`)
+//! - `GET /api/snapshot` - stats, agents and tasks in one payload
+//! - `GET /api/events` - SSE stream of snapshots, pushed when data changes
+//! - `GET /api/tokens` - API token metadata (admin scope)
+//! - `POST /api/progress` - report agent progress (agent scope)
+//! - `GET /`           - web dashboard (only with `--web`)
+//!
+//! With `--auth`, API requests need a token created by `prd token create`,
+//! sent as `Authorization: Bearer <token>` or `?token=<token>`. Writes
+//! (`POST`, always `Content-Type: application/json`) need one even without
+//! it, since any web page can reach a loopback server, and responses carry
+//! no CORS headers. The server only listens beyond the loopback interface
+//! with `--auth`.
+//!
+//! The server is intentionally dependency-free (std `TcpListener`, one thread
+//! per connection) so it can run next to the TUI on a shared wall display.
+
+mod auth;
+mod http;
+mod routes;
+
+pub use auth::{request_token, ApiToken, TokenScope, TokenStore};
+pub use http::{Request, Response};
+pub use routes::{AgentSummary, Snapshot, TaskSummary};
+
+use anyhow::Result;
+use std::net::{TcpListener, ToSocketAddrs};
+use std::thread;
+use std::time::Duration;
+
+/// Server configuration for `prd serve`
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    /// Path to the SQLite database
+    pub db_path: String,
+    /// Address to bind, e.g. "127.0.0.1:7878"
+    pub bind: String,
+    /// Whether to serve the bundled web dashboard at `/`
+    pub web: bool,
+    /// How often the event stream checks for changes
+    pub refresh_interval: Duration,
+    /// Require an API token on every API request
+    pub auth: bool,
+}
+
+/// Run the HTTP server until the process is terminated
+pub fn run_server(config: ServerConfig) -> Result<()> {
+    if !config.auth && !is_loopback(&config.bind)? {
+        anyhow::bail!(
+            "Refusing to serve the API on {} without --auth; \
+             create a token with `prd token create <name>` and add --auth",
+            config.bind
+        );
+    }
+    if config.auth {
+        let db = crate::db::Database::new(&config.db_path)?;
+        let tokens = TokenStore::new(&db).list()?;
+        if !tokens.iter().any(|t| t.revoked_at.is_none()) {
+            anyhow::bail!("--auth needs an API token; create one with `prd token create <name>`");
+        }
+    }
+
+    let listener = TcpListener::bind(&config.bind)
+        .map_err(|e| anyhow::anyhow!("Failed to bind {}: {}", config.bind, e))?;
+
+    println!("✓ Serving PRD API on http://{}", config.bind);
+    if config.web {
+        println!("  Web dashboard: http://{}/", config.bind);
+    }
+    if config.auth {
+        println!("  API token required (Authorization: Bearer <token> or ?token=<token>)");
+    }
+    println!("  Press Ctrl+C to stop");
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(s) => s,
+            Err(e) => {
+                eprintln!("Connection error: {}", e);
+                continue;
+            }
+        };
+
+        let config = config.clone();
+        thread::spawn(move || {
+            if let Err(e) = routes::handle_connection(stream, &config) {
+                eprintln!("Request error: {}", e);
+            }
+        });
+    }
+
+    Ok(())
+}
+
+/// Whether every address `bind` resolves to is a loopback address
+fn is_loopback(bind: &str) -> Result<bool> {
+    let addrs: Vec<_> = bind
+        .to_socket_addrs()
+        .map_err(|e| anyhow::anyhow!("Invalid address {}: {}", bind, e))?
+        .collect();
+    Ok(!addrs.is_empty() && addrs.iter().all(|addr| addr.ip().is_loopback()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_loopback() -> Result<()> {
+        assert!(is_loopback("127.0.0.1:7878")?);
+        assert!(is_loopback("[::1]:7878")?);
+        assert!(!is_loopback("0.0.0.0:7878")?);
+        assert!(!is_loopback("192.168.1.10:7878")?);
+        assert!(is_loopback("not-an-address").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,532 @@
+use super::auth::{request_token, TokenScope, TokenStore};
+use super::http::{write_sse_event, write_sse_headers, Request, Response};
+use super::ServerConfig;
+use crate::blocked;
+use crate::colors::ColorScheme;
+use crate::db::{Database, EpicForecast, TaskStats, TaskStatus};
+use crate::export::board::status_heading;
+use crate::export::ProgressBadge;
+use crate::resolver::{resolve_agent_id, resolve_task_id};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::BufReader;
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+
+/// Bundled single-page web dashboard
+const DASHBOARD_HTML: &str = include_str!("dashboard.html");
+
+/// Heartbeat interval for idle SSE connections
+const SSE_HEARTBEAT: Duration = Duration::from_secs(15);
+
+/// Agent row as exposed by the API
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct AgentSummary {
+    pub id: String,
+    pub name: String,
+    pub status: String,
+    pub current_task: Option<String>,
+    pub progress: u8,
+    pub last_active: String,
+}
+
+/// Task row as exposed by the API
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct TaskSummary {
+    pub id: String,
+    pub uuid: String,
+    pub title: String,
+    pub status: String,
+    pub priority: String,
+    pub agent: Option<String>,
+    pub epic: Option<String>,
+}
+
+/// Kanban column on the web dashboard
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct StatusColumn {
+    pub status: String,
+    pub label: String,
+    /// WIP limit on the column, from the `wip.<status>` setting
+    pub limit: Option<usize>,
+}
+
+/// Task blocked for longer than the blocked SLA
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct BlockedAlert {
+    pub uuid: String,
+    /// How long it has been blocked, e.g. "3d 4h"
+    pub blocked_for: String,
+    pub reason: Option<String>,
+}
+
+/// Body of `POST /api/progress`
+#[derive(Debug, Deserialize)]
+struct ProgressReport {
+    /// Agent ID or name (e.g. "A3")
+    agent: String,
+    /// Task ID (e.g. "#42")
+    task: String,
+    progress: u8,
+    message: Option<String>,
+}
+
+/// Everything the web dashboard needs in one payload
+#[derive(Debug, Serialize)]
+pub struct Snapshot {
+    pub stats: TaskStats,
+    /// Board columns in workflow order, custom statuses included
+    pub columns: Vec<StatusColumn>,
+    pub agents: Vec<AgentSummary>,
+    pub tasks: Vec<TaskSummary>,
+    pub epics: Vec<EpicForecast>,
+    /// Tasks blocked past the `blocked.sla_hours` setting, longest first
+    pub blocked_alerts: Vec<BlockedAlert>,
+    /// Epic, tag and priority colors from the `color.*` settings
+    pub colors: ColorScheme,
+}
+
+impl Snapshot {
+    /// Build a snapshot from the current database state
+    pub fn load(db: &Database) -> Result<Self> {
+        let limits = db.wip_limits()?;
+        let now = chrono::Utc::now();
+        Ok(Self {
+            stats: db.get_stats()?,
+            columns: db
+                .workflow()?
+                .columns()
+                .iter()
+                .map(|status| StatusColumn {
+                    status: status.as_str().to_string(),
+                    label: status_heading(status),
+                    limit: limits.status(status),
+                })
+                .collect(),
+            agents: list_agents(db)?,
+            tasks: list_tasks(db, None)?,
+            epics: db.epic_forecasts(now.date_naive())?,
+            blocked_alerts: blocked::over_sla(db, now)?
+                .into_iter()
+                .map(|b| BlockedAlert {
+                    blocked_for: blocked::format_blocked_for(b.duration(now)),
+                    uuid: b.task.id,
+                    reason: b.reason,
+                })
+                .collect(),
+            colors: ColorScheme::load(db)?,
+        })
+    }
+}
+
+/// Handle a single client connection
+pub fn handle_connection(stream: TcpStream, config: &ServerConfig) -> Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let request = Request::parse(&mut reader)?;
+    let mut stream = stream;
+
+    if request.method != "GET" && request.method != "POST" {
+        return Response::error(405, "Only GET and POST are supported").write_to(&mut stream);
+    }
+    if request.method == "POST" && !request.has_json_body() {
+        return Response::error(415, "POST bodies must be sent as application/json")
+            .write_to(&mut stream);
+    }
+
+    let db = Database::new(&config.db_path)?;
+
+    if let Some(denied) = authorize(&request, &db, config)? {
+        return denied.write_to(&mut stream);
+    }
+
+    if request.path == "/api/events" {
+        return stream_events(&mut stream, &db, config.refresh_interval);
+    }
+
+    let response = match route(&request, &db, config) {
+        Ok(resp) => resp,
+        Err(e) => Response::error(500, &e.to_string()),
+    };
+    response.write_to(&mut stream)
+}
+
+/// Scope a request needs (`None` for public pages)
+///
+/// The badge stays public so it can be embedded in a README. Without
+/// `--auth` only writes need a token.
+fn required_scope(request: &Request) -> Option<TokenScope> {
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/" | "/index.html" | "/badge.svg") => None,
+        ("GET", "/api/tokens") | ("POST", "/api/ops") => Some(TokenScope::Admin),
+        ("POST", _) => Some(TokenScope::Agent),
+        _ => Some(TokenScope::Read),
+    }
+}
+
+/// Check the request's API token, returning a response to send instead when denied
+fn authorize(request: &Request, db: &Database, config: &ServerConfig) -> Result<Option<Response>> {
+    // Any web page can reach a loopback server, so writes are never open
+    if !config.auth && request.method != "POST" {
+        return Ok(None);
+    }
+    let Some(required) = required_scope(request) else {
+        return Ok(None);
+    };
+
+    let token = match request_token(request) {
+        Some(secret) => TokenStore::new(db).authenticate(secret)?,
+        None => None,
+    };
+
+    Ok(match token {
+        None => Some(Response::error(401, "Missing or invalid API token")),
+        Some(t) if !t.scope.allows(required) => Some(Response::error(
+            403,
+            &format!(
+                "Token scope '{}' does not allow this request (requires '{}')",
+                t.scope.as_str(),
+                required.as_str()
+            ),
+        )),
+        Some(_) => None,
+    })
+}
+
+/// Dispatch a non-streaming request
+fn route(request: &Request, db: &Database, config: &ServerConfig) -> Result<Response> {
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/" | "/index.html") if config.web => Ok(Response::html(DASHBOARD_HTML)),
+        ("GET", "/badge.svg") => {
+            let epic = request.query.get("epic").map(String::as_str);
+            let label = request.query.get("label").map(String::as_str);
+            let badge = ProgressBadge::load(db, epic, label)?;
+            Ok(Response::svg(&badge.to_svg()))
+        }
+        ("GET", "/api/stats") => Response::json(&db.get_stats()?),
+        ("GET", "/api/agents") => Response::json(&list_agents(db)?),
+        ("GET", "/api/tasks") => {
+            // Custom statuses filter too; unknown names are rejected
+            let status = match request.query.get("status").map(|s| db.parse_status(s)) {
+                Some(Err(e)) => return Ok(Response::error(400, &e.to_string())),
+                status => status.transpose()?,
+            };
+            Response::json(&list_tasks(db, status)?)
+        }
+        ("GET", "/api/snapshot") => Response::json(&Snapshot::load(db)?),
+        ("GET", "/api/tokens") => Response::json(&TokenStore::new(db).list()?),
+        ("GET", "/api/ops") => list_ops(request, db),
+        ("GET", "/api/ops/vector") => {
+            Response::json(&crate::oplog::ReplicaState::load(db.get_connection())?)
+        }
+        ("POST", "/api/progress") => report_progress(request, db),
+        ("POST", "/api/ops") => apply_ops(request, db),
+        _ => Ok(Response::error(404, "Not found")),
+    }
+}
+
+/// Record a progress report sent by an agent
+fn report_progress(request: &Request, db: &Database) -> Result<Response> {
+    let report: ProgressReport = match serde_json::from_slice(&request.body) {
+        Ok(r) => r,
+        Err(e) => return Ok(Response::error(400, &format!("Invalid JSON body: {}", e))),
+    };
+
+    let resolved = resolve_agent_id(db.get_connection(), &report.agent).and_then(|agent| {
+        let task = resolve_task_id(db.get_connection(), &report.task)?;
+        Ok((agent, task))
+    });
+    let (agent_uuid, task_uuid) = match resolved {
+        Ok(ids) => ids,
+        Err(e) => return Ok(Response::error(400, &e.to_string())),
+    };
+    let display_id = db
+        .get_task(&task_uuid)?
+        .and_then(|t| t.display_id)
+        .ok_or_else(|| anyhow::anyhow!("Task has no display ID"))?;
+
+    if let Err(e) = db.report_progress(&agent_uuid, display_id, report.progress, report.message) {
+        return Ok(Response::error(400, &e.to_string()));
+    }
+    Response::json(&serde_json::json!({ "ok": true }))
+}
+
+/// Operations for `prd sync-db`, all or those beyond `?since=<vector>`
+fn list_ops(request: &Request, db: &Database) -> Result<Response> {
+    let conn = db.get_connection();
+    match request.query.get("since") {
+        None => Response::json(&crate::oplog::load_ops(conn)?),
+        Some(since) => match crate::oplog::decode_vector(since) {
+            Ok(seen) => Response::json(&crate::oplog::ops_since(conn, &seen)?),
+            Err(e) => Ok(Response::error(400, &e.to_string())),
+        },
+    }
+}
+
+/// Apply operations pushed by `prd sync-db push`
+fn apply_ops(request: &Request, db: &Database) -> Result<Response> {
+    let ops: Vec<crate::oplog::Op> = match serde_json::from_slice(&request.body) {
+        Ok(ops) => ops,
+        Err(e) => return Ok(Response::error(400, &format!("Invalid JSON body: {}", e))),
+    };
+    Response::json(&crate::oplog::apply_ops(db.get_connection(), &ops)?)
+}
+
+/// Push a snapshot whenever the data changes, with a periodic heartbeat
+fn stream_events(stream: &mut TcpStream, db: &Database, interval: Duration) -> Result<()> {
+    write_sse_headers(stream)?;
+
+    let mut last_payload = String::new();
+    let mut last_sent = Instant::now();
+
+    loop {
+        let payload = serde_json::to_string(&Snapshot::load(db)?)?;
+
+        if payload != last_payload {
+            // A write error means the client went away
+            if write_sse_event(stream, "snapshot", &payload).is_err() {
+                return Ok(());
+            }
+            last_payload = payload;
+            last_sent = Instant::now();
+        } else if last_sent.elapsed() >= SSE_HEARTBEAT {
+            use std::io::Write;
+            if stream.write_all(b": heartbeat\n\n").is_err() {
+                return Ok(());
+            }
+            last_sent = Instant::now();
+        }
+
+        std::thread::sleep(interval);
+    }
+}
+
+/// Load agents with their latest reported progress
+pub fn list_agents(db: &Database) -> Result<Vec<AgentSummary>> {
+    let mut summaries = Vec::new();
+    let latest_progress: HashMap<String, u8> = db
+        .get_all_progress()?
+        .into_iter()
+        .map(|p| (p.agent_id, p.progress))
+        .collect();
+
+    for agent in db.list_agents()? {
+        let current_task = agent.current_task_id.as_ref().and_then(|uuid| {
+            db.get_task(uuid)
+                .ok()
+                .flatten()
+                .and_then(|t| t.display_id.map(|id| format!("#{}", id)))
+        });
+        let progress = latest_progress.get(&agent.id).copied().unwrap_or(0);
+
+        summaries.push(AgentSummary {
+            id: agent
+                .display_id
+                .map(|id| format!("A{}", id))
+                .unwrap_or_else(|| agent.id[..8].to_string()),
+            name: agent.name.clone(),
+            status: agent.status.as_str().to_string(),
+            current_task,
+            progress,
+            last_active: agent.last_active.to_rfc3339(),
+        });
+    }
+
+    Ok(summaries)
+}
+
+/// Load tasks, optionally filtered by status
+pub fn list_tasks(db: &Database, status: Option<TaskStatus>) -> Result<Vec<TaskSummary>> {
+    let tasks = db.list_tasks(status)?;
+
+    Ok(tasks
+        .iter()
+        .map(|t| TaskSummary {
+            id: t
+                .display_id
+                .map(|id| format!("#{}", id))
+                .unwrap_or_else(|| t.id[..8].to_string()),
+            uuid: t.id.clone(),
+            title: t.title.clone(),
+            status: t.status.as_str().to_string(),
+            priority: t.priority.as_str().to_string(),
+            agent: t.assigned_agent.as_ref().and_then(|uuid| {
+                db.get_agent(uuid)
+                    .ok()
+                    .flatten()
+                    .and_then(|a| a.display_id.map(|id| format!("A{}", id)))
+            }),
+            epic: t.epic_name.clone(),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{AgentStatus, Priority, StatusBucket};
+
+    fn get(path: &str) -> Request {
+        Request {
+            method: "GET".to_string(),
+            path: path.to_string(),
+            query: HashMap::new(),
+            headers: HashMap::new(),
+            body: Vec::new(),
+        }
+    }
+
+    fn config(web: bool) -> ServerConfig {
+        ServerConfig {
+            db_path: ":memory:".to_string(),
+            bind: "127.0.0.1:0".to_string(),
+            web,
+            refresh_interval: Duration::from_secs(1),
+            auth: false,
+        }
+    }
+
+    #[test]
+    fn test_snapshot_contents() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        let agent = db.create_agent("agent-1".to_string())?;
+        let task = db.create_task("Task 1".to_string(), None, Priority::High, None, None)?;
+        db.assign_task(&task.id, &agent.id)?;
+        db.update_agent_status(&agent.id, AgentStatus::Working, Some(&task.id))?;
+        db.report_progress(&agent.id, task.display_id.unwrap(), 40, None)?;
+        db.set_setting("color.priority.high", "#ff8800")?;
+
+        let snapshot = Snapshot::load(&db)?;
+        assert_eq!(snapshot.stats.total, 1);
+        assert_eq!(snapshot.agents.len(), 1);
+        assert_eq!(snapshot.agents[0].id, "A1");
+        assert_eq!(snapshot.agents[0].current_task, Some("#1".to_string()));
+        assert_eq!(snapshot.agents[0].progress, 40);
+        assert_eq!(snapshot.tasks[0].agent, Some("A1".to_string()));
+        assert_eq!(
+            serde_json::to_value(&snapshot.colors)?["priorities"]["high"],
+            "#ff8800"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dashboard_only_served_with_web_flag() -> Result<()> {
+        let db = Database::new(":memory:")?;
+
+        let resp = route(&get("/"), &db, &config(true))?;
+        assert_eq!(resp.status, 200);
+        assert!(resp.content_type.starts_with("text/html"));
+
+        let resp = route(&get("/"), &db, &config(false))?;
+        assert_eq!(resp.status, 404);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_badge_is_public_svg() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        let secured = ServerConfig {
+            auth: true,
+            ..config(false)
+        };
+        assert!(authorize(&get("/badge.svg"), &db, &secured)?.is_none());
+
+        let resp = route(&get("/badge.svg"), &db, &secured)?;
+        assert_eq!(resp.status, 200);
+        assert_eq!(resp.content_type, "image/svg+xml");
+        assert!(String::from_utf8(resp.body)?.contains("progress: no tasks"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_task_status_filter() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        let task = db.create_task("Done".to_string(), None, Priority::Low, None, None)?;
+        db.create_task("Open".to_string(), None, Priority::Low, None, None)?;
+        db.update_task_status(&task.id, TaskStatus::Completed, None)?;
+
+        let mut req = get("/api/tasks");
+        req.query
+            .insert("status".to_string(), "completed".to_string());
+        let resp = route(&req, &db, &config(false))?;
+        let tasks: serde_json::Value = serde_json::from_slice(&resp.body)?;
+        assert_eq!(tasks.as_array().unwrap().len(), 1);
+        assert_eq!(tasks[0]["title"], "Done");
+
+        db.add_custom_status("deployed", StatusBucket::Done)?;
+        db.update_task_status(&task.id, TaskStatus::from_str("deployed"), None)?;
+        req.query
+            .insert("status".to_string(), "Deployed".to_string());
+        let resp = route(&req, &db, &config(false))?;
+        let tasks: serde_json::Value = serde_json::from_slice(&resp.body)?;
+        assert_eq!(tasks[0]["title"], "Done");
+
+        req.query
+            .insert("status".to_string(), "shipped".to_string());
+        assert_eq!(route(&req, &db, &config(false))?.status, 400);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_auth_requires_token_with_scope() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        let store = TokenStore::new(&db);
+        let (_, read) = store.create("wallboard", TokenScope::Read)?;
+        let (_, admin) = store.create("ops", TokenScope::Admin)?;
+        let secured = ServerConfig {
+            auth: true,
+            ..config(true)
+        };
+
+        // Public page and disabled auth need no token
+        assert!(authorize(&get("/"), &db, &secured)?.is_none());
+        assert!(authorize(&get("/api/stats"), &db, &config(false))?.is_none());
+
+        let resp = authorize(&get("/api/stats"), &db, &secured)?.unwrap();
+        assert_eq!(resp.status, 401);
+
+        let mut req = get("/api/stats");
+        req.headers
+            .insert("authorization".to_string(), format!("Bearer {}", read));
+        assert!(authorize(&req, &db, &secured)?.is_none());
+
+        let mut req = get("/api/tokens");
+        req.query.insert("token".to_string(), read.clone());
+        assert_eq!(authorize(&req, &db, &secured)?.unwrap().status, 403);
+        req.query.insert("token".to_string(), admin);
+        assert!(authorize(&req, &db, &secured)?.is_none());
+
+        // Writes need a token even without --auth
+        let mut req = get("/api/ops");
+        req.method = "POST".to_string();
+        assert_eq!(authorize(&req, &db, &config(false))?.unwrap().status, 401);
+        req.query.insert("token".to_string(), read);
+        assert_eq!(authorize(&req, &db, &config(false))?.unwrap().status, 403);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_post_progress() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        let agent = db.create_agent("agent-1".to_string())?;
+        db.create_task("Task".to_string(), None, Priority::Low, None, None)?;
+
+        let mut req = get("/api/progress");
+        req.method = "POST".to_string();
+        req.body = br##"{"agent": "A1", "task": "#1", "progress": 60}"##.to_vec();
+        let resp = route(&req, &db, &config(false))?;
+        assert_eq!(resp.status, 200);
+        assert_eq!(db.get_latest_progress(&agent.id)?.unwrap().progress, 60);
+
+        req.body = b"not json".to_vec();
+        assert_eq!(route(&req, &db, &config(false))?.status, 400);
+
+        Ok(())
+    }
+}
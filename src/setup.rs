@@ -0,0 +1,199 @@
+//! Setup - guided first-run configuration (`prd setup`)
+//!
+//! Walks through what otherwise takes a handful of commands: creating the
+//! database, writing `~/.prd/config.toml`, installing the git hook, choosing
+//! the docs directory, registering agents and indexing content. Each step
+//! keeps what is already there, so setup is safe to re-run; `--yes` takes
+//! the defaults without prompting.
+
+use crate::output::emoji;
+use anyhow::Result;
+use colored::*;
+use dialoguer::{Confirm, Input};
+use prd_tool::db::{self, Database};
+use prd_tool::git::GitHookManager;
+use prd_tool::notifications::NotificationConfig;
+use prd_tool::resolver::format_agent_id;
+use prd_tool::vectors::{self, ContentIndexer, ContentType, Embedder};
+use std::path::{Path, PathBuf};
+
+pub struct Setup {
+    /// Accept every default instead of prompting
+    yes: bool,
+}
+
+impl Setup {
+    pub fn new(yes: bool) -> Self {
+        Self { yes }
+    }
+
+    pub fn run(&self, db_path: &Path) -> Result<()> {
+        println!("\n{}", "PRD Setup".bold().underline());
+
+        step(1, "Database");
+        if db_path.exists() {
+            println!(
+                "  {} Using existing database at {}",
+                "✓".green(),
+                db_path.display()
+            );
+        } else {
+            crate::cli::init_database(db_path, false, None)?;
+        }
+        let db = Database::new(db_path.to_str().unwrap())?;
+
+        step(2, "Configuration");
+        self.write_config()?;
+
+        step(3, "Git hook");
+        self.install_git_hook()?;
+
+        step(4, "Docs directory");
+        let docs_dir = self.docs_dir(&db)?;
+
+        step(5, "Agents");
+        self.create_agents(&db)?;
+
+        step(6, "Search index");
+        self.index(&db, &docs_dir)?;
+
+        println!("\n{}", "Setup complete!".green().bold());
+        println!("You can now use:");
+        println!("  prd create \"My first task\"");
+        println!("  prd next");
+        println!("  prd watch");
+        Ok(())
+    }
+
+    fn confirm(&self, prompt: &str, default: bool) -> Result<bool> {
+        if self.yes {
+            return Ok(default);
+        }
+        Ok(Confirm::new()
+            .with_prompt(prompt)
+            .default(default)
+            .interact()?)
+    }
+
+    fn input(&self, prompt: &str, default: &str) -> Result<String> {
+        if self.yes {
+            return Ok(default.to_string());
+        }
+        let value: String = Input::new()
+            .with_prompt(prompt)
+            .default(default.to_string())
+            .allow_empty(true)
+            .interact_text()?;
+        Ok(value.trim().to_string())
+    }
+
+    /// Write ~/.prd/config.toml with the actor and notification settings
+    fn write_config(&self) -> Result<()> {
+        let path = NotificationConfig::get_config_path()?;
+        if path.exists() && !self.confirm("Overwrite the existing ~/.prd/config.toml?", false)? {
+            println!("  {} Keeping {}", "✓".green(), path.display());
+            return Ok(());
+        }
+
+        let user = std::env::var("USER").unwrap_or_default();
+        let actor = self.input("Your name for attribution (PRD_ACTOR)", &user)?;
+        let config = NotificationConfig {
+            enabled: self.confirm("Enable desktop notifications?", true)?,
+            ..NotificationConfig::default()
+        };
+
+        let mut value = toml::Value::try_from(&config)?;
+        if let Some(table) = value.as_table_mut().filter(|_| !actor.is_empty()) {
+            table.insert("actor".to_string(), toml::Value::String(actor));
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, toml::to_string_pretty(&value)?)?;
+        println!("  {} Wrote {}", "✓".green(), path.display());
+        Ok(())
+    }
+
+    fn install_git_hook(&self) -> Result<()> {
+        let repo = std::env::current_dir()?;
+        if !repo.join(".git").exists() {
+            println!("  {} Not a git repository, skipping", "ℹ".blue());
+            return Ok(());
+        }
+        let prompt = "Install the post-commit hook that completes tasks named in commits?";
+        if !self.confirm(prompt, true)? {
+            return Ok(());
+        }
+        // Already installed is fine on a re-run
+        if let Err(e) = GitHookManager::new(repo).install() {
+            println!("  {} {}", "ℹ".blue(), e);
+        }
+        Ok(())
+    }
+
+    /// Create the docs directory and store it as `docs.dir` if it isn't the
+    /// current one
+    fn docs_dir(&self, db: &Database) -> Result<PathBuf> {
+        let current = db.docs_dir()?;
+        let dir = self.input(
+            "Directory for completion documents",
+            &current.display().to_string(),
+        )?;
+        let dir = PathBuf::from(dir);
+        std::fs::create_dir_all(&dir)?;
+        if dir != current {
+            db.set_setting(db::SETTING_DOCS_DIR, &dir.display().to_string())?;
+        }
+        println!(
+            "  {} Completion documents go in {}",
+            "✓".green(),
+            dir.display()
+        );
+        Ok(dir)
+    }
+
+    fn create_agents(&self, db: &Database) -> Result<()> {
+        let names = self.input("Agents to create (comma-separated, empty to skip)", "")?;
+        for name in names.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            if db.get_agent_by_name(name)?.is_some() {
+                println!("  {} Agent {} already exists", "ℹ".blue(), name);
+                continue;
+            }
+            let agent = db.create_agent(name.to_string())?;
+            println!(
+                "  {} Created agent {} ({})",
+                "✓".green(),
+                format_agent_id(db.get_connection(), &agent.id).cyan(),
+                agent.name
+            );
+        }
+        Ok(())
+    }
+
+    fn index(&self, db: &Database, docs_dir: &Path) -> Result<()> {
+        let prompt = "Index tasks and docs for semantic search now? (downloads a ~100MB model)";
+        if !self.confirm(prompt, false)? {
+            println!("  {} Skipped; run 'prd vector index' later", "ℹ".blue());
+            return Ok(());
+        }
+
+        let conn = db.get_connection();
+        vectors::attach::ensure_schema(conn)?;
+        println!("  {} Loading embedding model...", emoji("⏳").yellow());
+        let mut embedder = Embedder::new();
+        let mut indexer = ContentIndexer::new(&mut embedder, conn).with_progress(true);
+        let tasks = indexer.index_tasks(false)?;
+        let docs = indexer.index_directory(docs_dir, ContentType::Doc, &[], false)?;
+        println!(
+            "  {} Indexed {} tasks and {} documents",
+            "✓".green(),
+            tasks.items_indexed,
+            docs.items_indexed
+        );
+        Ok(())
+    }
+}
+
+fn step(number: usize, title: &str) {
+    println!("\n{} {}", format!("[{}/6]", number).dimmed(), title.bold());
+}
@@ -0,0 +1,202 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::{Connection, DatabaseName};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Manifest listing the snapshots in a snapshot directory
+const MANIFEST: &str = "snapshots.json";
+
+/// A named point-in-time copy of a database
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub id: u32,
+    pub name: String,
+    /// File name inside the snapshot directory
+    pub file: String,
+    pub created_at: DateTime<Utc>,
+    /// Tasks in the database when the snapshot was taken
+    pub tasks: usize,
+}
+
+/// Snapshots of one database, kept in `<database>.snapshots/`
+pub struct SnapshotStore {
+    dir: PathBuf,
+}
+
+impl SnapshotStore {
+    pub fn for_database(db_path: &Path) -> Self {
+        let mut dir = db_path.as_os_str().to_owned();
+        dir.push(".snapshots");
+        Self {
+            dir: PathBuf::from(dir),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Path of a snapshot's database file
+    pub fn path(&self, snapshot: &Snapshot) -> PathBuf {
+        self.dir.join(&snapshot.file)
+    }
+
+    /// Snapshots, oldest first
+    pub fn list(&self) -> Result<Vec<Snapshot>> {
+        let manifest = self.dir.join(MANIFEST);
+        if !manifest.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&manifest)
+            .with_context(|| format!("Failed to read {}", manifest.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", manifest.display()))
+    }
+
+    fn save(&self, snapshots: &[Snapshot]) -> Result<()> {
+        fs::write(
+            self.dir.join(MANIFEST),
+            serde_json::to_string_pretty(snapshots)?,
+        )?;
+        Ok(())
+    }
+
+    /// Find a snapshot by ID or name (the newest, if a name was reused)
+    pub fn find(&self, id_or_name: &str) -> Result<Snapshot> {
+        let snapshots = self.list()?;
+        let found = match id_or_name.trim_start_matches('#').parse::<u32>() {
+            Ok(id) => snapshots.into_iter().find(|s| s.id == id),
+            Err(_) => snapshots.into_iter().rev().find(|s| s.name == id_or_name),
+        };
+        found.with_context(|| {
+            format!(
+                "Snapshot '{}' not found (see `prd snapshot list`)",
+                id_or_name
+            )
+        })
+    }
+
+    /// Copy the database into a new snapshot with the SQLite backup API
+    ///
+    /// Safe while other processes are writing: the copy is a consistent
+    /// point-in-time image, unlike copying the file.
+    pub fn create(&self, conn: &Connection, name: &str) -> Result<Snapshot> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+
+        let mut snapshots = self.list()?;
+        let id = snapshots.iter().map(|s| s.id).max().unwrap_or(0) + 1;
+        let slug: String = name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        let snapshot = Snapshot {
+            id,
+            name: name.to_string(),
+            file: format!("{:04}-{}.db", id, slug.trim_matches('-')),
+            created_at: Utc::now(),
+            tasks: conn.query_row(
+                "SELECT COUNT(*) FROM tasks WHERE deleted_at IS NULL",
+                [],
+                |row| row.get::<_, i64>(0),
+            )? as usize,
+        };
+
+        conn.backup(DatabaseName::Main, self.path(&snapshot), None)
+            .context("Failed to write snapshot")?;
+        snapshots.push(snapshot.clone());
+        self.save(&snapshots)?;
+        Ok(snapshot)
+    }
+
+    /// Replace the database at `db_path` with a snapshot's contents
+    ///
+    /// The restored database takes a new replica ID: its operation log is
+    /// rewound, so clocks recorded from here on would otherwise repeat ones
+    /// other replicas have already seen.
+    pub fn restore(&self, snapshot: &Snapshot, db_path: &Path) -> Result<()> {
+        let source = self.path(snapshot);
+        if !source.is_file() {
+            anyhow::bail!("Snapshot file is missing: {}", source.display());
+        }
+
+        let mut conn = Connection::open(db_path)?;
+        conn.restore(
+            DatabaseName::Main,
+            &source,
+            None::<fn(rusqlite::backup::Progress)>,
+        )
+        .context("Failed to restore snapshot")?;
+
+        let has_oplog: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE name = 'oplog_meta')",
+            [],
+            |row| row.get(0),
+        )?;
+        if has_oplog {
+            crate::oplog::reset_replica_id(&conn)?;
+        }
+        Ok(())
+    }
+
+    /// Delete a snapshot and its file
+    pub fn delete(&self, snapshot: &Snapshot) -> Result<()> {
+        let path = self.path(snapshot);
+        if path.exists() {
+            fs::remove_file(&path)?;
+        }
+        let snapshots: Vec<Snapshot> = self
+            .list()?
+            .into_iter()
+            .filter(|s| s.id != snapshot.id)
+            .collect();
+        self.save(&snapshots)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Database, Priority};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_snapshot_create_list_restore() -> Result<()> {
+        let dir = tempdir()?;
+        let db_path = dir.path().join("prd.db");
+        let store = SnapshotStore::for_database(&db_path);
+        assert!(store.list()?.is_empty());
+
+        let db = Database::new(db_path.to_str().unwrap())?;
+        db.create_task("Keep".to_string(), None, Priority::Low, None, None)?;
+        let replica = crate::oplog::replica_id(db.get_connection())?;
+        let before = store.create(db.get_connection(), "Before replan!")?;
+        assert_eq!(before.file, "0001-before-replan.db");
+        assert_eq!(before.tasks, 1);
+
+        db.create_task("Discard".to_string(), None, Priority::Low, None, None)?;
+        store.create(db.get_connection(), "after")?;
+        assert_eq!(store.list()?.len(), 2);
+        assert_eq!(store.find("after")?.id, 2);
+        assert!(store.find("#3").is_err());
+        drop(db);
+
+        store.restore(&store.find("1")?, &db_path)?;
+        let db = Database::new(db_path.to_str().unwrap())?;
+        assert_eq!(db.list_tasks(None)?.len(), 1);
+        assert_ne!(crate::oplog::replica_id(db.get_connection())?, replica);
+
+        store.delete(&store.find("after")?)?;
+        assert_eq!(store.list()?.len(), 1);
+        assert_eq!(fs::read_dir(store.dir())?.count(), 2);
+        Ok(())
+    }
+}
@@ -0,0 +1,326 @@
+use crate::db::{Database, Sprint, StatusBucket, Task};
+use crate::db_extensions::DependencyOps;
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// A backlog task the plan puts in the sprint
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedTask {
+    pub task: Task,
+    pub points: u32,
+}
+
+/// Why a backlog task stayed out of the sprint
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum Unplanned {
+    /// No points estimate to plan with
+    Unestimated,
+    /// More points than the capacity left
+    TooBig { remaining: u32 },
+    /// Depends on unfinished tasks (display IDs) that aren't in the sprint
+    WaitsOn { tasks: Vec<i32> },
+}
+
+impl std::fmt::Display for Unplanned {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Unplanned::Unestimated => write!(f, "no points estimate"),
+            Unplanned::TooBig { remaining } => write!(f, "only {} points left", remaining),
+            Unplanned::WaitsOn { tasks } => {
+                let ids: Vec<String> = tasks.iter().map(|id| format!("#{}", id)).collect();
+                write!(f, "waits on {}", ids.join(", "))
+            }
+        }
+    }
+}
+
+/// A backlog task that doesn't fit, and why
+#[derive(Debug, Clone, Serialize)]
+pub struct LeftOut {
+    pub task: Task,
+    pub points: Option<u32>,
+    #[serde(flatten)]
+    pub reason: Unplanned,
+}
+
+/// What [`plan_sprint`] adds to a sprint and what it leaves out
+#[derive(Debug, Clone, Serialize)]
+pub struct SprintPlan {
+    pub sprint: Sprint,
+    pub capacity: u32,
+    /// Points of the tasks already in the sprint
+    pub committed: u32,
+    pub added: Vec<PlannedTask>,
+    pub left_out: Vec<LeftOut>,
+}
+
+impl SprintPlan {
+    /// Points in the sprint once the plan is applied
+    pub fn planned_points(&self) -> u32 {
+        self.committed + self.added.iter().map(|p| p.points).sum::<u32>()
+    }
+
+    pub fn remaining(&self) -> u32 {
+        self.capacity.saturating_sub(self.planned_points())
+    }
+}
+
+/// Fill sprint `number` from the backlog up to its capacity, adding the
+/// tasks to the sprint unless `dry_run` is set
+///
+/// The backlog is every unfinished task not yet in a sprint, taken in the
+/// order `prd next` hands them out. A task comes with the unfinished
+/// backlog tasks it depends on, prerequisites first, and only if they all
+/// fit; one that depends on work outside the backlog (e.g. a failed task
+/// or one in another sprint) is left out. `capacity` overrides the
+/// sprint's stored capacity.
+pub fn plan_sprint(
+    db: &Database,
+    number: i32,
+    capacity: Option<u32>,
+    dry_run: bool,
+) -> Result<SprintPlan> {
+    let sprint = db
+        .get_sprint_by_number(number)?
+        .ok_or_else(|| anyhow::anyhow!("Sprint {} not found", number))?;
+    let capacity = capacity.or(sprint.capacity).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Sprint {} has no capacity (prd sprint capacity {} <points>, or --capacity)",
+            number,
+            number
+        )
+    })?;
+    let workflow = db.workflow()?;
+    let conn = db.get_connection();
+
+    let current = db.get_sprint_tasks(sprint.id)?;
+    let mut committed = 0;
+    for task in &current {
+        committed += db.get_task_points(&task.id)?.unwrap_or(0);
+    }
+    let mut in_sprint: HashSet<i32> = current.iter().filter_map(|t| t.display_id).collect();
+
+    let scheduled = db.tasks_in_sprints()?;
+    let tasks = db.list_tasks(None)?;
+    let completed: HashSet<i32> = tasks
+        .iter()
+        .filter(|t| workflow.is_completed(&t.status))
+        .filter_map(|t| t.display_id)
+        .collect();
+    let mut backlog: Vec<Task> = tasks
+        .into_iter()
+        .filter(|t| workflow.bucket(&t.status) != StatusBucket::Done)
+        .filter(|t| t.display_id.is_some_and(|id| !scheduled.contains(&id)))
+        .collect();
+    db.sort_ready_queue(&mut backlog)?;
+
+    // Unfinished dependencies and points of each backlog task
+    let mut candidates: HashMap<i32, (Vec<i32>, Option<u32>)> = HashMap::new();
+    for task in &backlog {
+        let display_id = task.display_id.unwrap_or_default();
+        let depends_on = conn
+            .get_dependencies(display_id)?
+            .into_iter()
+            .filter(|dep| !completed.contains(dep))
+            .collect();
+        candidates.insert(display_id, (depends_on, db.get_task_points(&task.id)?));
+    }
+
+    let mut remaining = capacity.saturating_sub(committed);
+    let mut planned: Vec<i32> = Vec::new();
+    let mut left_out = Vec::new();
+    for task in &backlog {
+        let display_id = task.display_id.unwrap_or_default();
+        if in_sprint.contains(&display_id) {
+            continue;
+        }
+        if candidates[&display_id].1.is_none() {
+            left_out.push((display_id, Unplanned::Unestimated));
+            continue;
+        }
+        match prerequisites(display_id, &candidates, &in_sprint) {
+            Err(blockers) => left_out.push((display_id, Unplanned::WaitsOn { tasks: blockers })),
+            Ok(needed) => {
+                let total: u32 = needed.iter().filter_map(|id| candidates[id].1).sum();
+                if total <= remaining {
+                    remaining -= total;
+                    in_sprint.extend(needed.iter().copied());
+                    planned.extend(needed);
+                } else {
+                    left_out.push((display_id, Unplanned::TooBig { remaining }));
+                }
+            }
+        }
+    }
+
+    let by_id: HashMap<i32, &Task> = backlog
+        .iter()
+        .map(|t| (t.display_id.unwrap_or_default(), t))
+        .collect();
+    let added: Vec<PlannedTask> = planned
+        .iter()
+        .map(|id| PlannedTask {
+            task: by_id[id].clone(),
+            points: candidates[id].1.unwrap_or_default(),
+        })
+        .collect();
+    let left_out = left_out
+        .into_iter()
+        .map(|(id, reason)| LeftOut {
+            task: by_id[&id].clone(),
+            points: candidates[&id].1,
+            reason,
+        })
+        .collect();
+
+    if !dry_run {
+        db.write_transaction(|| {
+            for id in &planned {
+                db.assign_task_to_sprint(sprint.id, *id)?;
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(SprintPlan {
+        sprint,
+        capacity,
+        committed,
+        added,
+        left_out,
+    })
+}
+
+/// Backlog tasks to plan for `task` to fit, prerequisites first and `task`
+/// last, or the dependencies that keep it out: unfinished tasks outside
+/// the backlog, or prerequisites without points
+fn prerequisites(
+    task: i32,
+    candidates: &HashMap<i32, (Vec<i32>, Option<u32>)>,
+    in_sprint: &HashSet<i32>,
+) -> std::result::Result<Vec<i32>, Vec<i32>> {
+    let mut needed = Vec::new();
+    let mut blockers = Vec::new();
+    let mut visited = HashSet::new();
+    visit(
+        task,
+        candidates,
+        in_sprint,
+        &mut visited,
+        &mut needed,
+        &mut blockers,
+    );
+    if blockers.is_empty() {
+        Ok(needed)
+    } else {
+        blockers.sort_unstable();
+        Err(blockers)
+    }
+}
+
+fn visit(
+    task: i32,
+    candidates: &HashMap<i32, (Vec<i32>, Option<u32>)>,
+    in_sprint: &HashSet<i32>,
+    visited: &mut HashSet<i32>,
+    needed: &mut Vec<i32>,
+    blockers: &mut Vec<i32>,
+) {
+    if !visited.insert(task) || in_sprint.contains(&task) {
+        return;
+    }
+    let Some((depends_on, _)) = candidates.get(&task) else {
+        return;
+    };
+    for &dep in depends_on {
+        if in_sprint.contains(&dep) {
+            continue;
+        }
+        match candidates.get(&dep) {
+            Some((_, Some(_))) => visit(dep, candidates, in_sprint, visited, needed, blockers),
+            _ => blockers.push(dep),
+        }
+    }
+    needed.push(task);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Priority, TaskStatus};
+
+    #[test]
+    fn test_plan_sprint_up_to_capacity() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        let sprint = db.create_sprint(
+            1,
+            "2026-10-19".to_string(),
+            "2026-10-30".to_string(),
+            None,
+            Some(10),
+        )?;
+        let task = |title: &str, priority: Priority, points: Option<u32>| -> Result<Task> {
+            let task = db.create_task(title.to_string(), None, priority, None, None)?;
+            db.set_task_points(&task.id, points)?;
+            Ok(task)
+        };
+        let carried = task("Carried over", Priority::Low, Some(2))?;
+        db.assign_task_to_sprint(sprint.id, carried.display_id.unwrap())?;
+        let schema = task("Schema", Priority::Low, Some(3))?;
+        let api = task("API", Priority::Critical, Some(3))?;
+        let _spike = task("Spike", Priority::High, None)?;
+        let _rewrite = task("Rewrite", Priority::High, Some(8))?;
+        let design = task("Design", Priority::High, Some(1))?;
+        let ui = task("UI", Priority::High, Some(1))?;
+        let _docs = task("Docs", Priority::Medium, Some(5))?;
+        db.update_task_status(&design.id, TaskStatus::Failed, None)?;
+        let conn = db.get_connection();
+        // API pulls in the lower-ranked schema; UI waits on the failed design
+        conn.add_dependency(
+            api.display_id.unwrap(),
+            schema.display_id.unwrap(),
+            "blocks",
+        )?;
+        conn.add_dependency(ui.display_id.unwrap(), design.display_id.unwrap(), "blocks")?;
+
+        let preview = plan_sprint(&db, 1, None, true)?;
+        let titles: Vec<&str> = preview
+            .added
+            .iter()
+            .map(|p| p.task.title.as_str())
+            .collect();
+        assert_eq!(titles, ["Schema", "API"]);
+        assert_eq!(preview.committed, 2);
+        assert_eq!(preview.planned_points(), 8);
+        assert_eq!(preview.remaining(), 2);
+        let reason = |title: &str| {
+            preview
+                .left_out
+                .iter()
+                .find(|l| l.task.title == title)
+                .map(|l| l.reason.clone())
+        };
+        assert_eq!(reason("Spike"), Some(Unplanned::Unestimated));
+        assert_eq!(reason("Rewrite"), Some(Unplanned::TooBig { remaining: 2 }));
+        assert_eq!(
+            reason("UI"),
+            Some(Unplanned::WaitsOn {
+                tasks: vec![design.display_id.unwrap()]
+            })
+        );
+        assert_eq!(reason("Docs"), Some(Unplanned::TooBig { remaining: 2 }));
+        assert_eq!(db.get_sprint_tasks(sprint.id)?.len(), 1);
+
+        plan_sprint(&db, 1, None, false)?;
+        assert_eq!(db.get_sprint_tasks(sprint.id)?.len(), 3);
+        // Planned tasks count as committed next time
+        let again = plan_sprint(&db, 1, Some(13), true)?;
+        assert_eq!(again.committed, 8);
+        let titles: Vec<&str> = again.added.iter().map(|p| p.task.title.as_str()).collect();
+        assert_eq!(titles, ["Docs"]);
+        assert!(plan_sprint(&db, 2, None, true).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,216 @@
+use crate::db::{Database, Task, TaskStatus, COMPLETED_STATUSES_SQL};
+use crate::notifications::Notifier;
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::params;
+use serde::Serialize;
+
+/// Default number of quiet days after which a task counts as stale
+pub const DEFAULT_STALE_DAYS: i64 = 14;
+
+/// Why a task is stale
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StaleReason {
+    /// In progress with no log entries or progress reports since `last_activity`
+    Idle { last_activity: DateTime<Utc> },
+    /// Blocked although every task it depends on finished by `cleared_at`
+    BlockersDone {
+        cleared_at: DateTime<Utc>,
+        blockers: Vec<i32>,
+    },
+}
+
+impl StaleReason {
+    /// When the task last had a reason to move
+    pub fn since(&self) -> DateTime<Utc> {
+        match self {
+            StaleReason::Idle { last_activity } => *last_activity,
+            StaleReason::BlockersDone { cleared_at, .. } => *cleared_at,
+        }
+    }
+
+    /// One-line description, e.g. "no activity for 20 days"
+    pub fn describe(&self, now: DateTime<Utc>) -> String {
+        let days = (now - self.since()).num_days();
+        match self {
+            StaleReason::Idle { .. } => format!("no activity for {} days", days),
+            StaleReason::BlockersDone { blockers, .. } => {
+                let ids: Vec<String> = blockers.iter().map(|id| format!("#{}", id)).collect();
+                format!("blocked, but {} finished {} days ago", ids.join(", "), days)
+            }
+        }
+    }
+}
+
+/// A task that has sat untouched for too long
+#[derive(Debug, Clone, Serialize)]
+pub struct StaleTask {
+    pub task: Task,
+    /// Name of the assigned agent
+    pub agent: Option<String>,
+    pub reason: StaleReason,
+}
+
+/// Find in-progress tasks with no activity and blocked tasks whose blockers
+/// all finished, in both cases for at least `days` days, oldest first
+///
+/// Activity is any log entry or progress report on the task, or a change to
+/// the task itself.
+pub fn find_stale(db: &Database, days: i64, now: DateTime<Utc>) -> Result<Vec<StaleTask>> {
+    let cutoff = now - Duration::days(days);
+    let conn = db.get_connection();
+    let mut stale = Vec::new();
+
+    for task in db.list_tasks(Some(TaskStatus::InProgress))? {
+        let latest: Option<String> = conn.query_row(
+            "SELECT MAX(at) FROM (
+                 SELECT ?1 AS at
+                 UNION ALL SELECT MAX(created_at) FROM task_logs WHERE task_id = ?2
+                 UNION ALL SELECT MAX(timestamp) FROM agent_progress WHERE task_id = ?3
+             )",
+            params![task.updated_at.to_rfc3339(), &task.id, task.display_id],
+            |row| row.get(0),
+        )?;
+        let last_activity = latest
+            .and_then(|at| DateTime::parse_from_rfc3339(&at).ok())
+            .map(|at| at.with_timezone(&Utc))
+            .unwrap_or(task.updated_at);
+        if last_activity <= cutoff {
+            stale.push((task, StaleReason::Idle { last_activity }));
+        }
+    }
+
+    for task in db.list_tasks(Some(TaskStatus::Blocked))? {
+        let Some(display_id) = task.display_id else {
+            continue;
+        };
+        let (blockers, pending, cleared_at): (Option<String>, i64, Option<String>) = conn
+            .query_row(
+                &format!(
+                    "SELECT group_concat(dep.display_id),
+                            SUM(dep.status NOT IN ({}) OR dep.completed_at IS NULL),
+                            MAX(dep.completed_at)
+                     FROM task_dependencies td
+                     JOIN tasks dep ON dep.display_id = td.depends_on_display_id
+                     WHERE td.task_display_id = ?1 AND dep.deleted_at IS NULL",
+                    COMPLETED_STATUSES_SQL
+                ),
+                params![display_id],
+                |row| {
+                    Ok((
+                        row.get(0)?,
+                        row.get::<_, Option<i64>>(1)?.unwrap_or(0),
+                        row.get(2)?,
+                    ))
+                },
+            )?;
+        let (Some(blockers), Some(cleared_at)) = (blockers, cleared_at) else {
+            continue;
+        };
+        let Ok(cleared_at) = DateTime::parse_from_rfc3339(&cleared_at) else {
+            continue;
+        };
+        let cleared_at = cleared_at.with_timezone(&Utc);
+        if pending == 0 && cleared_at <= cutoff {
+            let blockers = blockers
+                .split(',')
+                .filter_map(|id| id.parse().ok())
+                .collect();
+            stale.push((
+                task,
+                StaleReason::BlockersDone {
+                    cleared_at,
+                    blockers,
+                },
+            ));
+        }
+    }
+
+    stale.sort_by_key(|(_, reason)| reason.since());
+    stale
+        .into_iter()
+        .map(|(task, reason)| {
+            let agent = match &task.assigned_agent {
+                Some(id) => db.get_agent(id)?.map(|a| a.name),
+                None => None,
+            };
+            Ok(StaleTask {
+                task,
+                agent,
+                reason,
+            })
+        })
+        .collect()
+}
+
+/// Notify each stale task's agent and log the nudge on the task
+///
+/// Tasks without an agent are skipped. Returns how many nudges were sent.
+pub fn nudge(db: &Database, stale: &[StaleTask], notifier: &mut Notifier) -> Result<usize> {
+    let now = Utc::now();
+    let mut sent = 0;
+    for item in stale {
+        let Some(agent_id) = &item.task.assigned_agent else {
+            continue;
+        };
+        let Some(agent) = db.get_agent(agent_id)? else {
+            continue;
+        };
+        let reason = item.reason.describe(now);
+        notifier.notify_stale(&item.task, &agent, &reason)?;
+        db.log_task_action(&item.task.id, Some(&agent.id), "nudged", Some(&reason))?;
+        sent += 1;
+    }
+    Ok(sent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Priority;
+    use crate::db_extensions::DependencyOps;
+
+    #[test]
+    fn test_find_stale() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        let agent = db.create_agent("agent-1".to_string())?;
+        let quiet = db.create_task("Quiet".to_string(), None, Priority::Low, None, None)?;
+        let busy = db.create_task("Busy".to_string(), None, Priority::Low, None, None)?;
+        let blocker = db.create_task("Blocker".to_string(), None, Priority::Low, None, None)?;
+        let waiting = db.create_task("Waiting".to_string(), None, Priority::Low, None, None)?;
+        db.get_connection().add_dependency(
+            waiting.display_id.unwrap(),
+            blocker.display_id.unwrap(),
+            "blocks",
+        )?;
+        db.assign_task(&quiet.id, &agent.id)?;
+        for task in [&quiet, &busy] {
+            db.update_task_status(&task.id, TaskStatus::InProgress, None)?;
+        }
+        db.update_task_status(&waiting.id, TaskStatus::Blocked, None)?;
+
+        // Nothing is stale yet, and a blocked task whose blocker is open never is
+        let later = Utc::now() + Duration::days(20);
+        let ids = |stale: &[StaleTask]| stale.iter().map(|s| s.task.id.clone()).collect::<Vec<_>>();
+        assert!(find_stale(&db, 14, Utc::now())?.is_empty());
+        assert_eq!(
+            ids(&find_stale(&db, 14, later)?),
+            [quiet.id.clone(), busy.id.clone()]
+        );
+
+        db.update_task_status(&blocker.id, TaskStatus::Completed, None)?;
+        db.get_connection().execute(
+            "INSERT INTO task_logs (id, task_id, action, created_at) VALUES ('log', ?1, 'note', ?2)",
+            params![&busy.id, (later - Duration::days(1)).to_rfc3339()],
+        )?;
+        let stale = find_stale(&db, 14, later)?;
+        assert_eq!(ids(&stale), [quiet.id.clone(), waiting.id.clone()]);
+        assert_eq!(stale[0].agent.as_deref(), Some("agent-1"));
+        assert!(matches!(
+            &stale[1].reason,
+            StaleReason::BlockersDone { blockers, .. } if blockers == &[blocker.display_id.unwrap()]
+        ));
+        Ok(())
+    }
+}
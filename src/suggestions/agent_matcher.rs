@@ -350,6 +350,7 @@ mod tests {
             estimated_duration: None,
             actual_duration: None,
             epic_name: None,
+            due_date: None,
         };
 
         let keywords = matcher.extract_task_keywords(&task);
@@ -387,6 +388,7 @@ mod tests {
             estimated_duration: None,
             actual_duration: None,
             epic_name: None,
+            due_date: None,
         };
 
         let matcher = AgentMatcher::new(db);
@@ -436,6 +438,7 @@ mod tests {
             estimated_duration: None,
             actual_duration: None,
             epic_name: None,
+            due_date: None,
         };
 
         let matcher = AgentMatcher::new(db);
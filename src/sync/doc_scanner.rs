@@ -27,6 +27,9 @@ struct Frontmatter {
     task_id: Option<i32>,
     agent_id: Option<String>,
     completed_at: Option<String>,
+    /// Set on `prd docgen` skeletons until the task is actually done
+    #[serde(default)]
+    draft: bool,
 }
 
 /// Extract task ID from filename
@@ -110,22 +113,42 @@ fn parse_frontmatter(content: &str) -> Option<Frontmatter> {
 ///
 /// # Returns
 /// * `Some(CompletionDoc)` on success
-/// * `None` if parsing fails (logs warning)
+/// * `None` if parsing fails (logs warning) or the document is a draft
 pub fn parse_completion_doc(path: PathBuf) -> Option<CompletionDoc> {
+    match read_completion_doc(path) {
+        Ok(doc) => doc,
+        Err(e) => {
+            eprintln!("Warning: {:#}", e);
+            None
+        }
+    }
+}
+
+/// Parse a single completion document, reporting why a malformed one
+/// was rejected
+///
+/// # Returns
+/// * `Ok(Some(CompletionDoc))` on success
+/// * `Ok(None)` if the document is a draft
+/// * `Err(_)` if the file can't be read or identified
+pub fn read_completion_doc(path: PathBuf) -> Result<Option<CompletionDoc>> {
     // Step 1: Extract task ID from filename
-    let task_id = extract_task_id_from_filename(&path)?;
+    let task_id = extract_task_id_from_filename(&path).with_context(|| {
+        format!(
+            "{} does not start with TASK-<id>-",
+            path.file_name().unwrap_or_default().to_string_lossy()
+        )
+    })?;
 
     // Step 2: Read file contents
-    let content = match fs::read_to_string(&path) {
-        Ok(c) => c,
-        Err(e) => {
-            eprintln!("Warning: Failed to read file {:?}: {}", path, e);
-            return None;
-        }
-    };
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read file {}", path.display()))?;
 
     // Step 3: Parse frontmatter
     let frontmatter = parse_frontmatter(&content);
+    if frontmatter.as_ref().is_some_and(|fm| fm.draft) {
+        return Ok(None);
+    }
 
     // Step 4: Get completion timestamp
     let completed_at = if let Some(ref fm) = frontmatter {
@@ -142,18 +165,19 @@ pub fn parse_completion_doc(path: PathBuf) -> Option<CompletionDoc> {
         get_file_modified_time(&path)
     };
 
-    let completed_at = completed_at?;
+    let completed_at =
+        completed_at.with_context(|| format!("No completion time for {}", path.display()))?;
 
     // Extract agent_id from frontmatter
     let agent_id = frontmatter.and_then(|fm| fm.agent_id);
 
-    Some(CompletionDoc {
+    Ok(Some(CompletionDoc {
         task_id,
         agent_id,
         completed_at,
         file_path: path,
         git_commit_hash: None,
-    })
+    }))
 }
 
 /// Scan a directory for completion documents
@@ -0,0 +1,175 @@
+use crate::db::{Database, Task};
+use crate::db_extensions::{AcceptanceCriteriaOps, AcceptanceCriterion};
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Longest slug kept in a generated file name
+const MAX_SLUG_LEN: usize = 50;
+
+/// File name for a task's completion document
+///
+/// Matches the `TASK-NNN-*.md` names `sync-docs` scans for, and the
+/// watcher's default `TASK-*COMPLETE*.md` pattern:
+/// - (42, "Add login form") → TASK-042-ADD-LOGIN-FORM-COMPLETION.md
+pub fn doc_file_name(display_id: i32, title: &str) -> String {
+    let mut slug = String::new();
+    for c in title.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_uppercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+        if slug.len() >= MAX_SLUG_LEN {
+            break;
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+
+    if slug.is_empty() {
+        format!("TASK-{:03}-COMPLETION.md", display_id)
+    } else {
+        format!("TASK-{:03}-{}-COMPLETION.md", display_id, slug)
+    }
+}
+
+/// Render a completion document skeleton for a task
+///
+/// The frontmatter is marked `draft: true` so neither `sync-docs` nor the
+/// watcher completes the task until the author removes it.
+pub fn render_doc(
+    display_id: i32,
+    task: &Task,
+    criteria: &[AcceptanceCriterion],
+    agent: Option<&str>,
+) -> String {
+    let mut doc = String::from("---\n");
+    doc.push_str(&format!("task_id: {}\n", display_id));
+    match agent {
+        Some(agent) => doc.push_str(&format!("agent_id: {}\n", agent)),
+        None => doc.push_str("agent_id:      # e.g. A12\n"),
+    }
+    doc.push_str("completed_at:  # RFC 3339, defaults to the file's modified time\n");
+    doc.push_str("draft: true    # remove once the task is done\n");
+    doc.push_str("---\n\n");
+
+    doc.push_str(&format!("# Task #{}: {}\n\n", display_id, task.title));
+    if let Some(epic) = &task.epic_name {
+        doc.push_str(&format!("**Epic:** {}\n", epic));
+    }
+    doc.push_str(&format!("**Priority:** {}\n", task.priority.as_str()));
+    doc.push_str(&format!(
+        "**Agent:** {}\n\n",
+        agent.unwrap_or("_unassigned_")
+    ));
+
+    doc.push_str("## Description\n\n");
+    match task.description.as_deref().map(str::trim) {
+        Some(description) if !description.is_empty() => {
+            doc.push_str(description);
+            doc.push_str("\n\n");
+        }
+        _ => doc.push_str("_No description._\n\n"),
+    }
+
+    doc.push_str("## Acceptance Criteria\n\n");
+    if criteria.is_empty() {
+        doc.push_str("- [ ] \n");
+    }
+    for criterion in criteria {
+        let mark = if criterion.completed { 'x' } else { ' ' };
+        doc.push_str(&format!("- [{}] {}\n", mark, criterion.criterion));
+    }
+
+    doc.push_str("\n## Implementation Notes\n\n<!-- What changed and why -->\n");
+    doc.push_str("\n## Testing\n\n<!-- How the change was verified -->\n");
+    doc
+}
+
+/// Write a completion document skeleton into `docs_dir`
+///
+/// Refuses to overwrite an existing document unless `force` is set.
+pub fn write_doc(db: &Database, task: &Task, docs_dir: &Path, force: bool) -> Result<PathBuf> {
+    let display_id = task
+        .display_id
+        .context("Task has no display ID; run `prd migrate latest` first")?;
+    let criteria = db.get_connection().list_criteria(display_id)?;
+    let agent = match &task.assigned_agent {
+        Some(uuid) => db
+            .get_agent(uuid)?
+            .and_then(|a| a.display_id)
+            .map(|id| format!("A{}", id)),
+        None => None,
+    };
+
+    let path = docs_dir.join(doc_file_name(display_id, &task.title));
+    if path.exists() && !force {
+        anyhow::bail!(
+            "{} already exists (use --force to overwrite)",
+            path.display()
+        );
+    }
+
+    fs::create_dir_all(docs_dir)
+        .with_context(|| format!("Failed to create {}", docs_dir.display()))?;
+    fs::write(
+        &path,
+        render_doc(display_id, task, &criteria, agent.as_deref()),
+    )
+    .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Priority;
+    use crate::sync::parse_completion_doc;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_doc_file_name_slug() {
+        assert_eq!(
+            doc_file_name(42, "Add login form (OAuth) "),
+            "TASK-042-ADD-LOGIN-FORM-OAUTH-COMPLETION.md"
+        );
+        assert_eq!(doc_file_name(7, "✨"), "TASK-007-COMPLETION.md");
+        assert!(doc_file_name(1, &"word ".repeat(40)).len() < 80);
+    }
+
+    #[test]
+    fn test_generated_doc_is_draft_until_edited() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        let task = db.create_task(
+            "Login form".to_string(),
+            Some("Email and password".to_string()),
+            Priority::High,
+            None,
+            Some("Auth".to_string()),
+        )?;
+        db.get_connection()
+            .add_criterion(1, "Validates email".to_string())?;
+        let agent = db.create_agent("agent-1".to_string())?;
+        db.assign_task(&task.id, &agent.id)?;
+        let task = db.get_task(&task.id)?.unwrap();
+
+        let dir = tempdir()?;
+        let path = write_doc(&db, &task, dir.path(), false)?;
+        assert!(path.ends_with("TASK-001-LOGIN-FORM-COMPLETION.md"));
+        let content = fs::read_to_string(&path)?;
+        assert!(content.contains("agent_id: A1\n"));
+        assert!(content.contains("- [ ] Validates email"));
+        assert!(content.contains("Email and password"));
+
+        // Drafts are ignored by sync until the flag is removed
+        assert!(parse_completion_doc(path.clone()).is_none());
+        fs::write(&path, content.replace("draft: true", ""))?;
+        let doc = parse_completion_doc(path.clone()).unwrap();
+        assert_eq!(doc.task_id, 1);
+        assert_eq!(doc.agent_id.as_deref(), Some("A1"));
+
+        assert!(write_doc(&db, &task, dir.path(), false).is_err());
+        assert!(write_doc(&db, &task, dir.path(), true).is_ok());
+        Ok(())
+    }
+}
@@ -1,10 +1,14 @@
 mod doc_scanner;
+mod docgen;
 mod reconcile;
 mod sync_engine;
 
 #[cfg(test)]
 mod tests;
 
-pub use doc_scanner::{parse_completion_doc, scan_completion_docs, CompletionDoc};
+pub use doc_scanner::{
+    parse_completion_doc, read_completion_doc, scan_completion_docs, CompletionDoc,
+};
+pub use docgen::write_doc;
 pub use reconcile::{reconcile, Inconsistency, ReconcileResult};
 pub use sync_engine::{sync_tasks_from_docs, SyncError, SyncResult};
@@ -1,4 +1,5 @@
 use crate::db::{AgentStatus, Database, TaskStatus};
+use crate::output::emoji;
 use crate::resolver::{format_agent_id, resolve_agent_id, resolve_task_id};
 use crate::sync::doc_scanner::scan_completion_docs;
 use anyhow::Result;
@@ -125,9 +126,11 @@ pub struct ReconcileResult {
 pub fn reconcile(db: &Database, docs_dir: &Path, auto_fix: bool) -> Result<ReconcileResult> {
     println!(
         "{} Reconciling PRD database with filesystem...\n",
-        "🔍".cyan()
+        emoji("🔍").cyan()
     );
 
+    let run_id = db.start_sync_run("reconcile")?;
+
     // 1. Find all inconsistencies
     let inconsistencies = find_all_inconsistencies(db, docs_dir)?;
 
@@ -138,6 +141,7 @@ pub fn reconcile(db: &Database, docs_dir: &Path, auto_fix: bool) -> Result<Recon
                 .green()
                 .bold()
         );
+        db.finish_sync_run(run_id, 0, 0, &[])?;
         return Ok(ReconcileResult {
             inconsistencies: vec![],
             fixed_count: 0,
@@ -173,6 +177,7 @@ pub fn reconcile(db: &Database, docs_dir: &Path, auto_fix: bool) -> Result<Recon
 
     if !should_fix {
         println!("{}", "No changes applied.".dimmed());
+        db.finish_sync_run(run_id, 0, inconsistencies.len(), &[])?;
         return Ok(ReconcileResult {
             inconsistencies,
             fixed_count: 0,
@@ -195,7 +200,8 @@ pub fn reconcile(db: &Database, docs_dir: &Path, auto_fix: bool) -> Result<Recon
             Err(e) => {
                 failed.push(format!("{}: {}", get_fix_description(issue), e));
                 println!(
-                    "❌ {}: {}",
+                    "{} {}: {}",
+                    emoji("❌"),
                     get_fix_description(issue).red(),
                     e.to_string().dimmed()
                 );
@@ -216,6 +222,7 @@ pub fn reconcile(db: &Database, docs_dir: &Path, auto_fix: bool) -> Result<Recon
         }
     }
 
+    db.finish_sync_run(run_id, fixed_count, 0, &failed)?;
     Ok(ReconcileResult {
         inconsistencies,
         fixed_count,
@@ -255,7 +262,7 @@ fn check_tasks_not_marked_complete(db: &Database, docs_dir: &Path) -> Result<Vec
     for doc in docs {
         // Check if task is complete in database
         let status: Result<String, _> = db.get_connection().query_row(
-            "SELECT status FROM tasks WHERE display_id = ?1",
+            "SELECT status FROM tasks WHERE display_id = ?1 AND deleted_at IS NULL",
             [doc.task_id],
             |row| row.get(0),
         );
@@ -280,9 +287,10 @@ fn check_tasks_without_docs(db: &Database, docs_dir: &Path) -> Result<Vec<Incons
     let mut issues = Vec::new();
 
     // Get all completed tasks from database
-    let mut stmt = db
-        .get_connection()
-        .prepare("SELECT display_id, title, completed_at FROM tasks WHERE status = 'completed'")?;
+    let mut stmt = db.get_connection().prepare(
+        "SELECT display_id, title, completed_at FROM tasks
+             WHERE status = 'completed' AND deleted_at IS NULL",
+    )?;
 
     let mut rows = stmt.query([])?;
 
@@ -365,7 +373,8 @@ fn check_task_agent_mismatches(db: &Database) -> Result<Vec<Inconsistency>> {
         "SELECT t.display_id, t.title, a.id, a.name
          FROM tasks t
          JOIN agents a ON t.assigned_agent = a.id
-         WHERE t.status = 'completed' AND a.status = 'working' AND a.current_task_id = t.id",
+         WHERE t.status = 'completed' AND t.deleted_at IS NULL
+           AND a.status = 'working' AND a.current_task_id = t.id",
     )?;
 
     let mut rows = stmt.query([])?;
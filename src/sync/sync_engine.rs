@@ -1,4 +1,4 @@
-use crate::db::Database;
+use crate::db::{Database, TaskStatus};
 use crate::sync::doc_scanner::{scan_completion_docs, CompletionDoc};
 use anyhow::{Context, Result};
 use colored::*;
@@ -11,6 +11,8 @@ pub struct SyncResult {
     pub already_synced: usize,
     pub failed: Vec<SyncError>,
     pub duration_ms: u128,
+    /// Run recorded in the sync history; None for dry runs
+    pub run_id: Option<i64>,
 }
 
 #[derive(Debug)]
@@ -46,7 +48,15 @@ enum SyncStatus {
 /// println!("Completed {} tasks", result.newly_completed);
 /// ```
 pub fn sync_tasks_from_docs(db: &Database, docs_dir: &Path, dry_run: bool) -> Result<SyncResult> {
+    let _span = crate::telemetry::span("sync.docs")
+        .with_attribute("sync.docs_dir", docs_dir.display().to_string())
+        .with_attribute("sync.dry_run", dry_run);
     let start = std::time::Instant::now();
+    let run_id = if dry_run {
+        None
+    } else {
+        Some(db.start_sync_run("docs")?)
+    };
 
     // 1. Scan for completion documents
     println!(
@@ -59,11 +69,15 @@ pub fn sync_tasks_from_docs(db: &Database, docs_dir: &Path, dry_run: bool) -> Re
 
     if docs.is_empty() {
         println!("{}", "No completion documents found.".yellow());
+        if let Some(run_id) = run_id {
+            db.finish_sync_run(run_id, 0, 0, &[])?;
+        }
         return Ok(SyncResult {
             newly_completed: 0,
             already_synced: 0,
             failed: Vec::new(),
             duration_ms: start.elapsed().as_millis(),
+            run_id,
         });
     }
 
@@ -83,7 +97,7 @@ pub fn sync_tasks_from_docs(db: &Database, docs_dir: &Path, dry_run: bool) -> Re
     let mut failed: Vec<SyncError> = Vec::new();
 
     for doc in docs {
-        match process_completion_doc(db, &doc, dry_run) {
+        match process_completion_doc(db, &doc, run_id) {
             Ok(status) => match status {
                 SyncStatus::Completed => {
                     newly_completed += 1;
@@ -136,17 +150,36 @@ pub fn sync_tasks_from_docs(db: &Database, docs_dir: &Path, dry_run: bool) -> Re
 
     let duration_ms = start.elapsed().as_millis();
     println!("  Time: {:.3}s", (duration_ms as f64 / 1000.0));
+    if let Some(run_id) = run_id {
+        let errors: Vec<String> = failed
+            .iter()
+            .map(|f| format!("#{}: {}", f.task_id, f.error))
+            .collect();
+        db.finish_sync_run(run_id, newly_completed, already_synced, &errors)?;
+        println!(
+            "  Sync run: {} {}",
+            run_id,
+            format!("(details: prd sync-history {})", run_id).dimmed()
+        );
+    }
 
     Ok(SyncResult {
         newly_completed,
         already_synced,
         failed,
         duration_ms,
+        run_id,
     })
 }
 
-/// Process a single completion document
-fn process_completion_doc(db: &Database, doc: &CompletionDoc, dry_run: bool) -> Result<SyncStatus> {
+/// Process a single completion document, recording it under `run_id`
+///
+/// Without a run (a dry run) nothing is written.
+fn process_completion_doc(
+    db: &Database,
+    doc: &CompletionDoc,
+    run_id: Option<i64>,
+) -> Result<SyncStatus> {
     // 1. Check if task exists
     let task_result = db.get_connection().query_row(
         "SELECT id, status FROM tasks WHERE display_id = ?1",
@@ -170,19 +203,24 @@ fn process_completion_doc(db: &Database, doc: &CompletionDoc, dry_run: bool) ->
     }
 
     // 3. If dry-run, stop here
-    if dry_run {
+    let Some(run_id) = run_id else {
         return Ok(SyncStatus::Completed);
-    }
+    };
 
     // 4. Update task in transaction
-    let conn = db.get_connection();
-    let tx = conn.unchecked_transaction()?;
+    let tx = db.immediate_transaction()?;
 
-    // Update task
+    // Resolve agent UUID (if provided)
+    let agent_uuid = doc
+        .agent_id
+        .as_ref()
+        .and_then(|agent_id| crate::resolver::resolve_agent_id(&tx, agent_id).ok());
+
+    // Update task, checked against the allowed transitions
+    db.update_task_status(&task_uuid, TaskStatus::Completed, agent_uuid.as_deref())?;
     tx.execute(
         "UPDATE tasks
-         SET status = 'completed',
-             completion_doc_path = ?1,
+         SET completion_doc_path = ?1,
              auto_completed = TRUE,
              completed_at = ?2,
              updated_at = ?2
@@ -194,21 +232,19 @@ fn process_completion_doc(db: &Database, doc: &CompletionDoc, dry_run: bool) ->
         ],
     )?;
 
-    // Update agent to idle (if provided)
-    if let Some(agent_id) = &doc.agent_id {
-        // Resolve agent UUID
-        if let Ok(agent_uuid) = crate::resolver::resolve_agent_id(&tx, agent_id) {
-            tx.execute(
-                "UPDATE agents
-                 SET status = 'idle',
-                     current_task_id = NULL,
-                     last_active = ?1
-                 WHERE id = ?2",
-                rusqlite::params![chrono::Utc::now().to_rfc3339(), agent_uuid],
-            )?;
-        }
+    // Update agent to idle
+    if let Some(agent_uuid) = &agent_uuid {
+        tx.execute(
+            "UPDATE agents
+             SET status = 'idle',
+                 current_task_id = NULL,
+                 last_active = ?1
+             WHERE id = ?2",
+            rusqlite::params![chrono::Utc::now().to_rfc3339(), agent_uuid],
+        )?;
     }
 
+    db.record_sync_completion(run_id, &task_uuid, &TaskStatus::from_str(&current_status))?;
     tx.commit()?;
 
     Ok(SyncStatus::Completed)
@@ -217,7 +253,7 @@ fn process_completion_doc(db: &Database, doc: &CompletionDoc, dry_run: bool) ->
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::db::{Database, Priority, TaskStatus};
+    use crate::db::{Database, Priority};
     use std::fs;
     use tempfile::tempdir;
 
@@ -226,6 +262,11 @@ mod tests {
         // 1. Setup: Create test database
         let temp_db = tempfile::NamedTempFile::new().unwrap();
         let db = Database::new(temp_db.path().to_str().unwrap()).unwrap();
+        db.get_connection()
+            .execute_batch(include_str!(
+                "../../migrations/004_add_completion_fields.sql"
+            ))
+            .unwrap();
 
         // 2. Create test task
         db.create_task("Test task".to_string(), None, Priority::Medium, None, None)
@@ -246,6 +287,7 @@ mod tests {
         assert_eq!(result.newly_completed, 1);
         assert_eq!(result.already_synced, 0);
         assert!(result.failed.is_empty());
+        assert!(result.run_id.is_some());
 
         // 6. Verify task status in DB
         let tasks = db.list_tasks(Some(TaskStatus::Completed)).unwrap();
@@ -293,6 +335,7 @@ mod tests {
 
         // Verify preview worked
         assert_eq!(result.newly_completed, 1);
+        assert!(result.run_id.is_none());
 
         // Verify database NOT updated
         let tasks = db.list_tasks(Some(TaskStatus::Completed)).unwrap();
@@ -316,6 +359,26 @@ mod tests {
         assert_eq!(result.failed[0].task_id, 999);
     }
 
+    #[test]
+    fn test_sync_respects_transitions() {
+        let temp_db = tempfile::NamedTempFile::new().unwrap();
+        let db = Database::new(temp_db.path().to_str().unwrap()).unwrap();
+
+        db.create_task("Test".to_string(), None, Priority::Medium, None, None)
+            .unwrap();
+        db.reset_transitions(true).unwrap();
+
+        let temp_docs = tempdir().unwrap();
+        fs::write(temp_docs.path().join("TASK-001-COMPLETION.md"), "# Done").unwrap();
+
+        // pending → completed isn't allowed, so the doc is reported instead
+        let result = sync_tasks_from_docs(&db, temp_docs.path(), false).unwrap();
+        assert_eq!(result.newly_completed, 0);
+        assert_eq!(result.failed.len(), 1);
+        let tasks = db.list_tasks(Some(TaskStatus::Pending)).unwrap();
+        assert_eq!(tasks.len(), 1);
+    }
+
     #[test]
     fn test_sync_updates_agent_status() {
         let temp_db = tempfile::NamedTempFile::new().unwrap();
@@ -0,0 +1,416 @@
+//! OpenTelemetry tracing for commands, database access, embeddings and syncs
+//!
+//! Disabled (and free) unless an OTLP endpoint is configured through the
+//! standard variables:
+//!
+//! - `OTEL_EXPORTER_OTLP_ENDPOINT` (e.g. `http://localhost:4318`), or
+//!   `OTEL_EXPORTER_OTLP_TRACES_ENDPOINT` for the full traces URL
+//! - `OTEL_EXPORTER_OTLP_HEADERS` (`key=value,key2=value2`) for auth headers
+//! - `OTEL_SERVICE_NAME` (defaults to `prd`)
+//!
+//! Spans are batched and sent as OTLP/HTTP JSON every few seconds, and on
+//! [`shutdown`] at the end of a command.
+
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+pub const TRACES_ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT";
+pub const HEADERS_ENV: &str = "OTEL_EXPORTER_OTLP_HEADERS";
+pub const SERVICE_NAME_ENV: &str = "OTEL_SERVICE_NAME";
+
+/// How often queued spans are exported in the background
+const EXPORT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Spans beyond this many are dropped until the next export
+const MAX_QUEUED_SPANS: usize = 4096;
+
+/// Longest SQL text recorded on a statement span
+const MAX_STATEMENT_LEN: usize = 512;
+
+/// Value of a span attribute
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttrValue {
+    Str(String),
+    Int(i64),
+    Bool(bool),
+}
+
+impl From<&str> for AttrValue {
+    fn from(v: &str) -> Self {
+        AttrValue::Str(v.to_string())
+    }
+}
+
+impl From<String> for AttrValue {
+    fn from(v: String) -> Self {
+        AttrValue::Str(v)
+    }
+}
+
+impl From<i64> for AttrValue {
+    fn from(v: i64) -> Self {
+        AttrValue::Int(v)
+    }
+}
+
+impl From<usize> for AttrValue {
+    fn from(v: usize) -> Self {
+        AttrValue::Int(v as i64)
+    }
+}
+
+impl From<bool> for AttrValue {
+    fn from(v: bool) -> Self {
+        AttrValue::Bool(v)
+    }
+}
+
+/// A finished span waiting to be exported
+#[derive(Debug, Clone)]
+struct SpanData {
+    trace_id: [u8; 16],
+    span_id: [u8; 8],
+    parent_id: Option<[u8; 8]>,
+    name: String,
+    start: SystemTime,
+    end: SystemTime,
+    attributes: Vec<(String, AttrValue)>,
+    error: Option<String>,
+}
+
+struct Exporter {
+    url: String,
+    headers: Vec<(String, String)>,
+    service_name: String,
+    queue: Mutex<Vec<SpanData>>,
+    warned: AtomicBool,
+}
+
+static EXPORTER: OnceLock<Exporter> = OnceLock::new();
+
+thread_local! {
+    /// (trace ID, span ID) of the open spans on this thread, innermost last
+    static CONTEXT: RefCell<Vec<([u8; 16], [u8; 8])>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Enable tracing if an OTLP endpoint is configured; returns whether it is
+pub fn init() -> bool {
+    let url = match std::env::var(TRACES_ENDPOINT_ENV) {
+        Ok(url) if !url.trim().is_empty() => url.trim().to_string(),
+        _ => match std::env::var(ENDPOINT_ENV) {
+            Ok(base) if !base.trim().is_empty() => {
+                format!("{}/v1/traces", base.trim().trim_end_matches('/'))
+            }
+            _ => return false,
+        },
+    };
+
+    let exporter = Exporter {
+        url,
+        headers: parse_headers(&std::env::var(HEADERS_ENV).unwrap_or_default()),
+        service_name: std::env::var(SERVICE_NAME_ENV)
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or_else(|| "prd".to_string()),
+        queue: Mutex::new(Vec::new()),
+        warned: AtomicBool::new(false),
+    };
+    if EXPORTER.set(exporter).is_err() {
+        return true;
+    }
+
+    // Long-running processes (the watcher daemon, `prd serve`) export as they go
+    std::thread::spawn(|| loop {
+        std::thread::sleep(EXPORT_INTERVAL);
+        flush();
+    });
+    true
+}
+
+/// Whether spans are being recorded
+pub fn enabled() -> bool {
+    EXPORTER.get().is_some()
+}
+
+/// Export everything still queued; call before the process exits
+pub fn shutdown() {
+    flush();
+}
+
+/// Start a span that ends when the returned guard is dropped
+///
+/// Spans opened while another is active on the same thread become its
+/// children; a span with no parent starts a new trace.
+pub fn span(name: impl Into<String>) -> Span {
+    if !enabled() {
+        return Span { inner: None };
+    }
+
+    let span_id = new_span_id();
+    let (trace_id, parent_id) = CONTEXT.with(|ctx| {
+        let mut ctx = ctx.borrow_mut();
+        let (trace_id, parent_id) = match ctx.last() {
+            Some(&(trace_id, parent)) => (trace_id, Some(parent)),
+            None => (*uuid::Uuid::new_v4().as_bytes(), None),
+        };
+        ctx.push((trace_id, span_id));
+        (trace_id, parent_id)
+    });
+
+    Span {
+        inner: Some(SpanData {
+            trace_id,
+            span_id,
+            parent_id,
+            name: name.into(),
+            start: SystemTime::now(),
+            end: SystemTime::now(),
+            attributes: Vec::new(),
+            error: None,
+        }),
+    }
+}
+
+/// An open span; records itself when dropped
+#[must_use = "the span ends as soon as it is dropped"]
+pub struct Span {
+    inner: Option<SpanData>,
+}
+
+impl Span {
+    /// Attach an attribute
+    pub fn set_attribute(&mut self, key: &str, value: impl Into<AttrValue>) {
+        if let Some(data) = &mut self.inner {
+            data.attributes.push((key.to_string(), value.into()));
+        }
+    }
+
+    /// Builder form of [`Span::set_attribute`]
+    pub fn with_attribute(mut self, key: &str, value: impl Into<AttrValue>) -> Self {
+        self.set_attribute(key, value);
+        self
+    }
+
+    /// Mark the span as failed
+    pub fn set_error(&mut self, error: &anyhow::Error) {
+        if let Some(data) = &mut self.inner {
+            data.error = Some(format!("{:#}", error));
+        }
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let Some(mut data) = self.inner.take() else {
+            return;
+        };
+        data.end = SystemTime::now();
+        CONTEXT.with(|ctx| {
+            let mut ctx = ctx.borrow_mut();
+            if let Some(pos) = ctx.iter().rposition(|&(_, id)| id == data.span_id) {
+                ctx.truncate(pos);
+            }
+        });
+        enqueue(data);
+    }
+}
+
+/// Record a finished SQL statement as a child of the current span
+///
+/// Matches `rusqlite::Connection::profile`; statements run outside any span
+/// (e.g. the daemon's periodic housekeeping) are not recorded.
+pub fn record_statement(sql: &str, duration: Duration) {
+    if !enabled() {
+        return;
+    }
+    let Some((trace_id, parent)) = CONTEXT.with(|ctx| ctx.borrow().last().copied()) else {
+        return;
+    };
+
+    let end = SystemTime::now();
+    let statement: String = sql.trim().chars().take(MAX_STATEMENT_LEN).collect();
+    let operation = statement
+        .split_whitespace()
+        .next()
+        .unwrap_or("SQL")
+        .to_uppercase();
+    enqueue(SpanData {
+        trace_id,
+        span_id: new_span_id(),
+        parent_id: Some(parent),
+        name: format!("db.{}", operation.to_lowercase()),
+        start: end.checked_sub(duration).unwrap_or(end),
+        end,
+        attributes: vec![
+            ("db.system".to_string(), "sqlite".into()),
+            ("db.operation".to_string(), operation.into()),
+            ("db.statement".to_string(), statement.into()),
+        ],
+        error: None,
+    });
+}
+
+fn enqueue(data: SpanData) {
+    if let Some(exporter) = EXPORTER.get() {
+        let mut queue = exporter.queue.lock().unwrap();
+        if queue.len() < MAX_QUEUED_SPANS {
+            queue.push(data);
+        }
+    }
+}
+
+/// Send queued spans to the collector, warning once on failure
+fn flush() {
+    let Some(exporter) = EXPORTER.get() else {
+        return;
+    };
+    let spans = std::mem::take(&mut *exporter.queue.lock().unwrap());
+    if spans.is_empty() {
+        return;
+    }
+
+    if let Err(e) = export(exporter, &spans) {
+        if !exporter.warned.swap(true, Ordering::SeqCst) {
+            eprintln!("⚠ Failed to export traces to {}: {}", exporter.url, e);
+        }
+    }
+}
+
+fn export(exporter: &Exporter, spans: &[SpanData]) -> Result<()> {
+    let body = otlp_json(&exporter.service_name, spans);
+    let mut request = ureq::post(&exporter.url)
+        .timeout(Duration::from_secs(3))
+        .set("Content-Type", "application/json");
+    for (key, value) in &exporter.headers {
+        request = request.set(key, value);
+    }
+    request.send_string(&body.to_string())?;
+    Ok(())
+}
+
+/// Encode spans as an OTLP/JSON `ExportTraceServiceRequest`
+fn otlp_json(service_name: &str, spans: &[SpanData]) -> Value {
+    let spans: Vec<Value> = spans
+        .iter()
+        .map(|span| {
+            let mut value = json!({
+                "traceId": hex(&span.trace_id),
+                "spanId": hex(&span.span_id),
+                "name": span.name,
+                "kind": 1,
+                "startTimeUnixNano": unix_nanos(span.start).to_string(),
+                "endTimeUnixNano": unix_nanos(span.end).to_string(),
+                "attributes": span
+                    .attributes
+                    .iter()
+                    .map(|(k, v)| attribute(k, v))
+                    .collect::<Vec<_>>(),
+            });
+            if let Some(parent) = &span.parent_id {
+                value["parentSpanId"] = json!(hex(parent));
+            }
+            if let Some(error) = &span.error {
+                value["status"] = json!({ "code": 2, "message": error });
+            }
+            value
+        })
+        .collect();
+
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [attribute("service.name", &AttrValue::from(service_name))]
+            },
+            "scopeSpans": [{
+                "scope": { "name": "prd-tool", "version": env!("CARGO_PKG_VERSION") },
+                "spans": spans
+            }]
+        }]
+    })
+}
+
+fn attribute(key: &str, value: &AttrValue) -> Value {
+    let value = match value {
+        AttrValue::Str(s) => json!({ "stringValue": s }),
+        // int64 values are strings in the protobuf JSON mapping
+        AttrValue::Int(i) => json!({ "intValue": i.to_string() }),
+        AttrValue::Bool(b) => json!({ "boolValue": b }),
+    };
+    json!({ "key": key, "value": value })
+}
+
+fn parse_headers(raw: &str) -> Vec<(String, String)> {
+    raw.split(',')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .filter(|(k, _)| !k.is_empty())
+        .collect()
+}
+
+fn new_span_id() -> [u8; 8] {
+    let mut id = [0u8; 8];
+    id.copy_from_slice(&uuid::Uuid::new_v4().as_bytes()[..8]);
+    id
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn unix_nanos(time: SystemTime) -> u128 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_otlp_json_encoding() {
+        let span = SpanData {
+            trace_id: [1; 16],
+            span_id: [2; 8],
+            parent_id: Some([3; 8]),
+            name: "prd sync".to_string(),
+            start: UNIX_EPOCH + Duration::from_secs(1),
+            end: UNIX_EPOCH + Duration::from_secs(2),
+            attributes: vec![
+                ("prd.count".to_string(), 3usize.into()),
+                ("prd.dry_run".to_string(), false.into()),
+            ],
+            error: Some("boom".to_string()),
+        };
+
+        let body = otlp_json("prd", &[span]);
+        let encoded = &body["resourceSpans"][0]["scopeSpans"][0]["spans"][0];
+        assert_eq!(
+            body["resourceSpans"][0]["resource"]["attributes"][0]["value"]["stringValue"],
+            "prd"
+        );
+        assert_eq!(encoded["traceId"], "01".repeat(16));
+        assert_eq!(encoded["parentSpanId"], "03".repeat(8));
+        assert_eq!(encoded["startTimeUnixNano"], "1000000000");
+        assert_eq!(encoded["attributes"][0]["value"]["intValue"], "3");
+        assert_eq!(encoded["attributes"][1]["value"]["boolValue"], false);
+        assert_eq!(encoded["status"]["code"], 2);
+    }
+
+    #[test]
+    fn test_parse_headers() {
+        assert_eq!(
+            parse_headers("authorization=Bearer x, x-team = prd,bad"),
+            vec![
+                ("authorization".to_string(), "Bearer x".to_string()),
+                ("x-team".to_string(), "prd".to_string())
+            ]
+        );
+        assert!(parse_headers("").is_empty());
+    }
+}
@@ -0,0 +1,318 @@
+use crate::db::Database;
+use crate::db_extensions::{AcceptanceCriteriaOps, DependencyOps, EpicDependencyOps};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Presets shipped with the binary, by name
+const BUNDLED: &[(&str, &str)] = &[
+    ("feature-launch", include_str!("feature-launch.toml")),
+    ("service-migration", include_str!("service-migration.toml")),
+];
+
+/// Epic/task/acceptance-criteria structure used to seed a new database
+#[derive(Debug, Deserialize)]
+pub struct ProjectTemplate {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub epics: Vec<EpicTemplate>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EpicTemplate {
+    pub name: String,
+    /// Epic that must progress before this one's tasks are ready
+    #[serde(default)]
+    pub depends_on: Option<String>,
+    /// Percent of `depends_on` that must be completed (default 100)
+    #[serde(default)]
+    pub threshold: Option<u8>,
+    #[serde(default)]
+    pub tasks: Vec<TaskTemplate>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TaskTemplate {
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub priority: Option<String>,
+    #[serde(default)]
+    pub criteria: Vec<String>,
+    /// Titles of other tasks in the template this one depends on
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    #[serde(default)]
+    pub subtasks: Vec<TaskTemplate>,
+}
+
+/// What applying a template created
+#[derive(Debug, Default, PartialEq)]
+pub struct TemplateSummary {
+    pub epics: usize,
+    pub tasks: usize,
+    pub criteria: usize,
+    pub dependencies: usize,
+}
+
+impl ProjectTemplate {
+    /// Load a template by file path, user template name or bundled preset name
+    ///
+    /// Names are looked up in ~/.prd/templates/<name>.{toml,yaml,yml} before
+    /// the bundled presets, so a user template can override one.
+    pub fn resolve(name_or_path: &str) -> Result<Self> {
+        let path = Path::new(name_or_path);
+        if path.is_file() {
+            return Self::from_file(path);
+        }
+
+        if let Ok(dir) = Self::user_dir() {
+            for ext in ["toml", "yaml", "yml"] {
+                let candidate = dir.join(format!("{}.{}", name_or_path, ext));
+                if candidate.is_file() {
+                    return Self::from_file(&candidate);
+                }
+            }
+        }
+
+        match BUNDLED.iter().find(|(name, _)| *name == name_or_path) {
+            Some((_, content)) => Self::from_toml(content),
+            None => anyhow::bail!(
+                "Unknown template '{}'. Available: {}",
+                name_or_path,
+                Self::available().join(", ")
+            ),
+        }
+    }
+
+    /// Names of bundled presets and templates in ~/.prd/templates
+    pub fn available() -> Vec<String> {
+        let mut names: Vec<String> = BUNDLED.iter().map(|(n, _)| n.to_string()).collect();
+        if let Ok(entries) = Self::user_dir().and_then(|d| Ok(fs::read_dir(d)?)) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let is_template = matches!(
+                    path.extension().and_then(|e| e.to_str()),
+                    Some("toml" | "yaml" | "yml")
+                );
+                if let (true, Some(stem)) = (is_template, path.file_stem()) {
+                    names.push(stem.to_string_lossy().into_owned());
+                }
+            }
+        }
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Directory for user templates (~/.prd/templates)
+    pub fn user_dir() -> Result<PathBuf> {
+        let home = std::env::var("HOME")
+            .or_else(|_| std::env::var("USERPROFILE"))
+            .context("Could not determine home directory")?;
+        Ok(PathBuf::from(home).join(".prd").join("templates"))
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read template {}", path.display()))?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("yaml" | "yml") => serde_yaml::from_str(&content)
+                .with_context(|| format!("Failed to parse {} as YAML", path.display())),
+            _ => Self::from_toml(&content)
+                .with_context(|| format!("Failed to parse {}", path.display())),
+        }
+    }
+
+    pub fn from_toml(content: &str) -> Result<Self> {
+        toml::from_str(content).context("Failed to parse template as TOML")
+    }
+
+    /// Create the template's epics, tasks, criteria and dependencies
+    ///
+    /// Runs in one transaction, so a template with a bad reference leaves
+    /// the database untouched.
+    pub fn apply(&self, db: &Database) -> Result<TemplateSummary> {
+        db.write_transaction(|| {
+            let mut summary = TemplateSummary::default();
+            let mut ids: HashMap<&str, i32> = HashMap::new();
+
+            for epic in &self.epics {
+                summary.epics += 1;
+                for task in &epic.tasks {
+                    create_task(db, task, &epic.name, None, &mut ids, &mut summary)?;
+                }
+            }
+
+            // Dependencies may point forward, so link once everything exists
+            for epic in &self.epics {
+                link_dependencies(db, &epic.tasks, &ids, &mut summary)?;
+                if let Some(depends_on) = &epic.depends_on {
+                    db.get_connection().add_epic_dependency(
+                        &epic.name,
+                        depends_on,
+                        epic.threshold.unwrap_or(100),
+                    )?;
+                    summary.dependencies += 1;
+                }
+            }
+
+            Ok(summary)
+        })
+    }
+}
+
+fn create_task<'a>(
+    db: &Database,
+    template: &'a TaskTemplate,
+    epic: &str,
+    parent: Option<String>,
+    ids: &mut HashMap<&'a str, i32>,
+    summary: &mut TemplateSummary,
+) -> Result<()> {
+    if ids.contains_key(template.title.as_str()) {
+        anyhow::bail!("Duplicate task title in template: '{}'", template.title);
+    }
+
+    let priority = match &template.priority {
+        Some(name) => db
+            .parse_priority(name)
+            .with_context(|| format!("Task '{}'", template.title))?,
+        None => db.priority_levels()?.default_level(),
+    };
+    let task = db.create_task(
+        template.title.clone(),
+        template.description.clone(),
+        priority,
+        parent,
+        Some(epic.to_string()),
+    )?;
+    let display_id = task.display_id.context("Created task has no display ID")?;
+    ids.insert(&template.title, display_id);
+    summary.tasks += 1;
+
+    for criterion in &template.criteria {
+        db.get_connection()
+            .add_criterion(display_id, criterion.clone())?;
+        summary.criteria += 1;
+    }
+
+    for subtask in &template.subtasks {
+        create_task(db, subtask, epic, Some(task.id.clone()), ids, summary)?;
+    }
+    Ok(())
+}
+
+fn link_dependencies(
+    db: &Database,
+    tasks: &[TaskTemplate],
+    ids: &HashMap<&str, i32>,
+    summary: &mut TemplateSummary,
+) -> Result<()> {
+    for task in tasks {
+        for title in &task.depends_on {
+            let depends_on = ids
+                .get(title.as_str())
+                .with_context(|| format!("'{}' depends on unknown task '{}'", task.title, title))?;
+            db.get_connection()
+                .add_dependency(ids[task.title.as_str()], *depends_on, "blocks")?;
+            summary.dependencies += 1;
+        }
+        link_dependencies(db, &task.subtasks, ids, summary)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_templates_apply() -> Result<()> {
+        for (name, _) in BUNDLED {
+            let db = Database::new(":memory:")?;
+            let template = ProjectTemplate::resolve(name)?;
+            let summary = template.apply(&db)?;
+            assert!(summary.tasks > 0 && summary.criteria > 0, "{}", name);
+            assert_eq!(db.list_tasks(None)?.len(), summary.tasks);
+        }
+        assert!(ProjectTemplate::resolve("no-such-template").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_structure_and_rollback() -> Result<()> {
+        let template = ProjectTemplate::from_toml(
+            r#"
+            name = "demo"
+
+            [[epics]]
+            name = "Build"
+
+            [[epics.tasks]]
+            title = "Write code"
+            priority = "high"
+            criteria = ["Compiles"]
+            depends_on = ["Design"]
+
+            [[epics.tasks.subtasks]]
+            title = "Write tests"
+
+            [[epics]]
+            name = "Plan"
+
+            [[epics.tasks]]
+            title = "Design"
+
+            [[epics]]
+            name = "Ship"
+            depends_on = "Build"
+            threshold = 50
+
+            [[epics.tasks]]
+            title = "Release"
+            "#,
+        )?;
+        let db = Database::new(":memory:")?;
+        let summary = template.apply(&db)?;
+        assert_eq!(
+            summary,
+            TemplateSummary {
+                epics: 3,
+                tasks: 4,
+                criteria: 1,
+                dependencies: 2,
+            }
+        );
+
+        let conn = db.get_connection();
+        assert_eq!(conn.get_dependencies(1)?, vec![3]);
+        assert_eq!(conn.list_epic_dependencies()?[0].threshold, 50);
+        let subtask = db
+            .list_tasks(None)?
+            .into_iter()
+            .find(|t| t.title == "Write tests")
+            .unwrap();
+        assert!(subtask.parent_id.is_some());
+
+        let broken = ProjectTemplate::from_toml(
+            r#"
+            name = "broken"
+            [[epics]]
+            name = "Build"
+            [[epics.tasks]]
+            title = "Write code"
+            depends_on = ["Missing"]
+            "#,
+        )?;
+        let db = Database::new(":memory:")?;
+        assert!(broken.apply(&db).is_err());
+        assert!(db.list_tasks(None)?.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,365 @@
+//! Testing - seeded in-memory databases for tests
+//!
+//! [`Fixture`] builds an in-memory [`Database`] from a declarative list of
+//! agents, tasks and dependencies, so tests here and in downstream crates
+//! don't each repeat the same `create_task`/`assign_task`/`UPDATE tasks SET
+//! completed_at` setup. Builds are deterministic: tasks get display IDs in
+//! the order they are added, and every timestamp is derived from a fixed
+//! epoch rather than the clock. Only UUIDs are random.
+//!
+//! Enabled for this crate's tests and, for other crates, with the `testing`
+//! feature.
+//!
+//! ```ignore
+//! let seeded = Fixture::new()
+//!     .agent("worker")
+//!     .task(TaskFixture::new("Schema").status(TaskStatus::Completed).agent("worker"))
+//!     .task(TaskFixture::new("API").depends_on(1))
+//!     .build()?;
+//! assert_eq!(seeded.task(2).title, "API");
+//! ```
+
+use crate::db::{Agent, Database, Priority, Task, TaskStatus};
+use crate::db_extensions::DependencyOps;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use rusqlite::params;
+use std::collections::HashMap;
+
+/// Default epoch fixture timestamps are derived from
+pub fn epoch() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap()
+}
+
+/// A task to seed, referred to by its position (from 1), which is also its
+/// display ID
+#[derive(Debug, Clone)]
+pub struct TaskFixture {
+    title: String,
+    description: Option<String>,
+    priority: Priority,
+    status: TaskStatus,
+    epic: Option<String>,
+    parent: Option<i32>,
+    agent: Option<String>,
+    depends_on: Vec<i32>,
+    tags: Vec<String>,
+    due: Option<NaiveDate>,
+    created_at: Option<DateTime<Utc>>,
+    completed_at: Option<DateTime<Utc>>,
+}
+
+impl TaskFixture {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            description: None,
+            priority: Priority::Medium,
+            status: TaskStatus::Pending,
+            epic: None,
+            parent: None,
+            agent: None,
+            depends_on: Vec::new(),
+            tags: Vec::new(),
+            due: None,
+            created_at: None,
+            completed_at: None,
+        }
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn status(mut self, status: TaskStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn epic(mut self, epic: &str) -> Self {
+        self.epic = Some(epic.to_string());
+        self
+    }
+
+    /// Make this a subtask of an earlier task
+    pub fn parent(mut self, display_id: i32) -> Self {
+        self.parent = Some(display_id);
+        self
+    }
+
+    /// Assign to an agent added with [`Fixture::agent`]
+    pub fn agent(mut self, name: &str) -> Self {
+        self.agent = Some(name.to_string());
+        self
+    }
+
+    /// Block this task on another one ("blocks" dependency)
+    pub fn depends_on(mut self, display_id: i32) -> Self {
+        self.depends_on.push(display_id);
+        self
+    }
+
+    pub fn tag(mut self, tag: &str) -> Self {
+        self.tags.push(tag.to_string());
+        self
+    }
+
+    pub fn due(mut self, due: NaiveDate) -> Self {
+        self.due = Some(due);
+        self
+    }
+
+    /// Creation time; defaults to the epoch plus one minute per earlier task
+    pub fn created_at(mut self, at: DateTime<Utc>) -> Self {
+        self.created_at = Some(at);
+        self
+    }
+
+    /// Completion time of a completed task; defaults to an hour after it was
+    /// created
+    pub fn completed_at(mut self, at: DateTime<Utc>) -> Self {
+        self.completed_at = Some(at);
+        self
+    }
+}
+
+/// Builder for a seeded in-memory database
+#[derive(Debug, Clone)]
+pub struct Fixture {
+    epoch: DateTime<Utc>,
+    agents: Vec<String>,
+    tasks: Vec<TaskFixture>,
+}
+
+impl Default for Fixture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Fixture {
+    pub fn new() -> Self {
+        Self {
+            epoch: epoch(),
+            agents: Vec::new(),
+            tasks: Vec::new(),
+        }
+    }
+
+    /// Derive default timestamps from `epoch` instead of [`epoch()`]
+    pub fn epoch(mut self, epoch: DateTime<Utc>) -> Self {
+        self.epoch = epoch;
+        self
+    }
+
+    pub fn agent(mut self, name: &str) -> Self {
+        self.agents.push(name.to_string());
+        self
+    }
+
+    pub fn task(mut self, task: TaskFixture) -> Self {
+        self.tasks.push(task);
+        self
+    }
+
+    /// `count` pending tasks titled "Task 1", "Task 2", ...
+    pub fn tasks(mut self, count: usize) -> Self {
+        let start = self.tasks.len();
+        for i in 1..=count {
+            self.tasks
+                .push(TaskFixture::new(&format!("Task {}", start + i)));
+        }
+        self
+    }
+
+    pub fn build(self) -> Result<Seeded> {
+        let db = Database::new(":memory:")?;
+        let mut agents = HashMap::new();
+        for name in &self.agents {
+            let agent = db.create_agent(name.clone())?;
+            agents.insert(name.clone(), agent.id);
+        }
+
+        let mut ids: Vec<String> = Vec::with_capacity(self.tasks.len());
+        for (i, spec) in self.tasks.iter().enumerate() {
+            let parent = spec
+                .parent
+                .map(|parent| lookup(&ids, parent, i))
+                .transpose()?;
+            let task = db.create_task(
+                spec.title.clone(),
+                spec.description.clone(),
+                spec.priority.clone(),
+                parent,
+                spec.epic.clone(),
+            )?;
+            if let Some(name) = &spec.agent {
+                let agent = agents.get(name).with_context(|| {
+                    format!("Fixture task {} uses unknown agent {}", i + 1, name)
+                })?;
+                db.assign_task(&task.id, agent)?;
+            }
+            for tag in &spec.tags {
+                db.add_task_tag(&task.id, tag, None)?;
+            }
+            if spec.due.is_some() {
+                db.set_task_due_date(&task.id, spec.due, None)?;
+            }
+            if spec.status != TaskStatus::Pending {
+                db.force_task_status(&task.id, spec.status.clone(), None)?;
+            }
+            ids.push(task.id);
+        }
+
+        let workflow = db.workflow()?;
+        for (i, spec) in self.tasks.iter().enumerate() {
+            for &depends_on in &spec.depends_on {
+                lookup(&ids, depends_on, self.tasks.len())?;
+                db.get_connection()
+                    .add_dependency(i as i32 + 1, depends_on, "blocks")?;
+            }
+
+            let created_at = spec
+                .created_at
+                .unwrap_or_else(|| self.epoch + Duration::minutes(i as i64));
+            let completed_at = workflow
+                .is_completed(&spec.status)
+                .then(|| spec.completed_at.unwrap_or(created_at + Duration::hours(1)));
+            let updated_at = completed_at.unwrap_or(created_at);
+            let conn = db.get_connection();
+            conn.execute(
+                "UPDATE tasks SET created_at = ?1, updated_at = ?2, completed_at = ?3
+                 WHERE id = ?4",
+                params![
+                    created_at.to_rfc3339(),
+                    updated_at.to_rfc3339(),
+                    completed_at.map(|at| at.to_rfc3339()),
+                    ids[i]
+                ],
+            )?;
+            conn.execute(
+                "UPDATE task_logs SET created_at = ?1 WHERE task_id = ?2",
+                params![updated_at.to_rfc3339(), ids[i]],
+            )?;
+        }
+        db.get_connection().execute(
+            "UPDATE agents SET created_at = ?1, last_active = ?1",
+            params![self.epoch.to_rfc3339()],
+        )?;
+
+        let tasks = ids
+            .iter()
+            .map(|id| {
+                db.get_task(id)?
+                    .with_context(|| format!("Fixture task {} vanished", id))
+            })
+            .collect::<Result<_>>()?;
+        let agents = self
+            .agents
+            .iter()
+            .map(|name| {
+                let agent = db
+                    .get_agent(&agents[name])?
+                    .with_context(|| format!("Fixture agent {} vanished", name))?;
+                Ok((name.clone(), agent))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Seeded { db, tasks, agents })
+    }
+}
+
+/// UUID of the task at `display_id`, which must come before `limit`
+fn lookup(ids: &[String], display_id: i32, limit: usize) -> Result<String> {
+    match usize::try_from(display_id - 1) {
+        Ok(index) if index < limit.min(ids.len()) => Ok(ids[index].clone()),
+        _ => anyhow::bail!("Fixture refers to unknown task #{}", display_id),
+    }
+}
+
+/// A built fixture: the database and the rows seeded into it
+pub struct Seeded {
+    pub db: Database,
+    tasks: Vec<Task>,
+    agents: HashMap<String, Agent>,
+}
+
+impl Seeded {
+    /// Task by display ID, as it was after seeding
+    pub fn task(&self, display_id: i32) -> &Task {
+        &self.tasks[display_id as usize - 1]
+    }
+
+    pub fn tasks(&self) -> &[Task] {
+        &self.tasks
+    }
+
+    /// Agent by name, as it was after seeding
+    pub fn agent(&self, name: &str) -> &Agent {
+        &self.agents[name]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixture_is_deterministic() -> Result<()> {
+        let build = || {
+            Fixture::new()
+                .agent("worker")
+                .task(
+                    TaskFixture::new("Schema")
+                        .status(TaskStatus::Completed)
+                        .agent("worker"),
+                )
+                .task(
+                    TaskFixture::new("API")
+                        .parent(1)
+                        .depends_on(1)
+                        .tag("backend"),
+                )
+                .tasks(2)
+                .build()
+        };
+        let first = build()?;
+        let second = build()?;
+
+        let stamps = |seeded: &Seeded| {
+            seeded
+                .tasks()
+                .iter()
+                .map(|t| (t.display_id, t.created_at, t.updated_at, t.completed_at))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(stamps(&first), stamps(&second));
+        assert_eq!(first.task(4).title, "Task 4");
+        assert_eq!(
+            first.task(1).completed_at,
+            Some(epoch() + Duration::hours(1))
+        );
+        assert_eq!(first.task(2).created_at, epoch() + Duration::minutes(1));
+        assert_eq!(
+            first.task(2).parent_id.as_deref(),
+            Some(first.task(1).id.as_str())
+        );
+        assert_eq!(
+            first.task(1).assigned_agent.as_deref(),
+            Some(first.agent("worker").id.as_str())
+        );
+        assert_eq!(first.db.get_connection().get_dependencies(2)?, vec![1]);
+        assert_eq!(first.db.get_task_tags(&first.task(2).id)?, vec!["backend"]);
+
+        assert!(Fixture::new()
+            .task(TaskFixture::new("Orphan").parent(2))
+            .build()
+            .is_err());
+        Ok(())
+    }
+}
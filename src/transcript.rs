@@ -0,0 +1,528 @@
+use crate::db::{Database, Priority, TaskStatus};
+use crate::resolver::resolve_task_id;
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// Log action linking a task to the transcript line it came from
+pub const TRANSCRIPT_ACTION: &str = "transcript";
+
+/// Log action recording a decision taken in a transcript
+pub const DECISION_ACTION: &str = "decision";
+
+/// Line patterns used unless `builtin_extractors = false`
+///
+/// Each matches one line of assistant text; the `title` group becomes the
+/// item title.
+const BUILTIN_EXTRACTORS: &[(ItemKind, &str)] = &[
+    (
+        ItemKind::Completed,
+        r"(?i)^\s*(?:[-*]\s*)?(?:✅\s*)?(?:done|completed|fixed|implemented):\s*(?P<title>.+)$",
+    ),
+    (ItemKind::Completed, r"^\s*[-*]\s*\[[xX]\]\s*(?P<title>.+)$"),
+    (
+        ItemKind::Decision,
+        r"(?i)^\s*(?:[-*]\s*)?decision:\s*(?P<title>.+)$",
+    ),
+    (
+        ItemKind::FollowUp,
+        r"(?i)^\s*(?:[-*]\s*)?(?:todo|follow[- ]?ups?|next steps?):\s*(?P<title>.+)$",
+    ),
+    (ItemKind::FollowUp, r"^\s*[-*]\s*\[ \]\s*(?P<title>.+)$"),
+];
+
+/// What a transcript item says about the work
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemKind {
+    /// Work the session finished
+    Completed,
+    /// A choice made during the session, logged on the related task
+    Decision,
+    /// Work left for later
+    FollowUp,
+}
+
+impl ItemKind {
+    pub fn as_str(&self) -> &str {
+        match self {
+            ItemKind::Completed => "completed",
+            ItemKind::Decision => "decision",
+            ItemKind::FollowUp => "follow_up",
+        }
+    }
+}
+
+/// A work item extracted from a transcript
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct TranscriptItem {
+    pub kind: ItemKind,
+    pub title: String,
+    #[serde(default)]
+    pub detail: Option<String>,
+    /// Task the item refers to, e.g. "#42"
+    #[serde(default)]
+    pub task: Option<String>,
+    /// 1-based transcript line; unset for items from the LLM command
+    #[serde(default)]
+    pub line: Option<usize>,
+}
+
+/// A user-defined line pattern
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Extractor {
+    pub kind: ItemKind,
+    /// Regex matched against each line; the title is the `title` group, else
+    /// the first group, else the whole match
+    pub pattern: String,
+}
+
+/// How `prd ingest-transcript` finds work items (~/.prd/transcripts.toml)
+///
+/// ```toml
+/// builtin_extractors = true
+/// todos = true
+/// llm_command = "llm -m claude-haiku -s 'List work items as JSON lines'"
+///
+/// [[extractor]]
+/// kind = "follow_up"
+/// pattern = '^FIXME\((?P<title>[^)]+)\)'
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TranscriptConfig {
+    /// Use the built-in "Done:", "Decision:", "TODO:" and checklist patterns
+    pub builtin_extractors: bool,
+
+    /// Read the session's last todo list (TodoWrite tool calls)
+    pub todos: bool,
+
+    #[serde(rename = "extractor")]
+    pub extractors: Vec<Extractor>,
+
+    /// Command given the assistant text on stdin, printing one JSON item per
+    /// line (`{"kind": "follow_up", "title": "...", "task": "#42"}`)
+    pub llm_command: Option<String>,
+}
+
+impl Default for TranscriptConfig {
+    fn default() -> Self {
+        Self {
+            builtin_extractors: true,
+            todos: true,
+            extractors: Vec::new(),
+            llm_command: None,
+        }
+    }
+}
+
+impl TranscriptConfig {
+    /// Load configuration, falling back to defaults if the file is missing
+    pub fn load() -> Result<Self> {
+        let config_path = Self::get_config_path()?;
+
+        if !config_path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(&config_path)
+            .context(format!("Failed to read config from {:?}", config_path))?;
+        toml::from_str(&content).context("Failed to parse transcripts config as TOML")
+    }
+
+    /// Get the path to the config file (~/.prd/transcripts.toml)
+    pub fn get_config_path() -> Result<PathBuf> {
+        let home = std::env::var("HOME")
+            .or_else(|_| std::env::var("USERPROFILE"))
+            .map_err(|_| anyhow::anyhow!("Could not determine home directory"))?;
+        Ok(PathBuf::from(home).join(".prd").join("transcripts.toml"))
+    }
+
+    /// Extract work items from a JSONL transcript, first mention wins
+    ///
+    /// Understands Claude Code session files (`{"type": "assistant",
+    /// "message": {"content": [...]}}`) and plain `{"role", "content"}` lines.
+    pub fn extract(&self, content: &str, use_llm: bool) -> Result<Vec<TranscriptItem>> {
+        let mut patterns = Vec::new();
+        if self.builtin_extractors {
+            for (kind, pattern) in BUILTIN_EXTRACTORS {
+                patterns.push((*kind, Regex::new(pattern)?));
+            }
+        }
+        for extractor in &self.extractors {
+            let regex = Regex::new(&extractor.pattern)
+                .with_context(|| format!("Invalid extractor pattern '{}'", extractor.pattern))?;
+            patterns.push((extractor.kind, regex));
+        }
+
+        let mut items = Vec::new();
+        let mut assistant_text = String::new();
+        let mut last_todos: Option<(usize, Vec<Value>)> = None;
+        for (index, line) in content.lines().enumerate() {
+            let Ok(entry) = serde_json::from_str::<Value>(line) else {
+                continue;
+            };
+            let message = entry.get("message").unwrap_or(&entry);
+            if message.get("role").and_then(Value::as_str) != Some("assistant") {
+                continue;
+            }
+
+            for block in content_blocks(message) {
+                if let Some(text) = block.as_str().or_else(|| text_of(block)) {
+                    assistant_text.push_str(text);
+                    assistant_text.push('\n');
+                    for text_line in text.lines() {
+                        if let Some(item) = match_line(&patterns, text_line, index + 1) {
+                            items.push(item);
+                        }
+                    }
+                } else if block.get("name").and_then(Value::as_str) == Some("TodoWrite") {
+                    if let Some(todos) = block.pointer("/input/todos").and_then(Value::as_array) {
+                        last_todos = Some((index + 1, todos.clone()));
+                    }
+                }
+            }
+        }
+
+        // Each TodoWrite call replaces the list, so only the last one counts
+        if let (true, Some((line, todos))) = (self.todos, last_todos) {
+            for todo in todos {
+                let Some(title) = todo.get("content").and_then(Value::as_str) else {
+                    continue;
+                };
+                let kind = match todo.get("status").and_then(Value::as_str) {
+                    Some("completed") => ItemKind::Completed,
+                    _ => ItemKind::FollowUp,
+                };
+                items.push(new_item(kind, title, Some(line)));
+            }
+        }
+
+        if use_llm {
+            if let Some(command) = &self.llm_command {
+                items.extend(run_llm(command, &assistant_text)?);
+            }
+        }
+
+        let mut seen = HashSet::new();
+        items.retain(|item| seen.insert((item.kind, item.title.to_lowercase())));
+        Ok(items)
+    }
+}
+
+/// Content blocks of a message; a plain string counts as one block
+fn content_blocks(message: &Value) -> Vec<&Value> {
+    match message.get("content") {
+        Some(Value::Array(blocks)) => blocks.iter().collect(),
+        Some(text @ Value::String(_)) => vec![text],
+        _ => Vec::new(),
+    }
+}
+
+fn text_of(block: &Value) -> Option<&str> {
+    if block.get("type").and_then(Value::as_str) == Some("text") {
+        block.get("text").and_then(Value::as_str)
+    } else {
+        None
+    }
+}
+
+fn match_line(patterns: &[(ItemKind, Regex)], text: &str, line: usize) -> Option<TranscriptItem> {
+    patterns.iter().find_map(|(kind, regex)| {
+        let caps = regex.captures(text)?;
+        let title = caps
+            .name("title")
+            .or_else(|| caps.get(1))
+            .or_else(|| caps.get(0))?;
+        Some(new_item(*kind, title.as_str(), Some(line)))
+    })
+}
+
+fn new_item(kind: ItemKind, title: &str, line: Option<usize>) -> TranscriptItem {
+    let task_ref = Regex::new(r"#(\d+)\b").unwrap();
+    let title = title.trim().trim_end_matches('.').trim();
+    TranscriptItem {
+        kind,
+        title: title.to_string(),
+        detail: None,
+        task: task_ref
+            .captures(title)
+            .map(|caps| format!("#{}", &caps[1])),
+        line,
+    }
+}
+
+/// Pipe the assistant text to the LLM command and parse its JSON lines
+fn run_llm(command: &str, text: &str) -> Result<Vec<TranscriptItem>> {
+    let parts = shell_words::split(command)
+        .map_err(|e| anyhow::anyhow!("Failed to parse llm_command: {}", e))?;
+    let Some((program, args)) = parts.split_first() else {
+        return Ok(Vec::new());
+    };
+
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .with_context(|| format!("Failed to run llm_command '{}'", program))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        anyhow::bail!("llm_command exited with {}", output.status);
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<TranscriptItem>(line.trim()).ok())
+        .map(|item| TranscriptItem { line: None, ..item })
+        .collect())
+}
+
+/// What ingesting an item did (or would do, in a dry run)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IngestAction {
+    /// A new task was created (already completed for finished work)
+    Created,
+    /// An existing task was marked completed
+    Completed,
+    /// A decision was logged on a task
+    Logged,
+    /// Nothing to do: already tracked, already done, or no task for a decision
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IngestResult {
+    pub item: TranscriptItem,
+    pub action: IngestAction,
+    /// Task UUID the item was applied to
+    pub task_id: Option<String>,
+}
+
+/// Where ingested items go
+#[derive(Debug, Clone, Default)]
+pub struct IngestOptions {
+    /// Transcript name used in the links logged on tasks
+    pub source: String,
+    /// Agent UUID credited with completed work
+    pub agent_id: Option<String>,
+    /// Epic for created tasks
+    pub epic: Option<String>,
+    /// Task UUID that decisions without a task reference are logged on
+    pub decision_task: Option<String>,
+    pub dry_run: bool,
+}
+
+/// Create and update tasks from transcript items, logging a link back to
+/// the transcript on every task touched
+///
+/// Items match existing tasks by their `#N` reference, else by title
+/// (case-insensitive), and decisions already logged are skipped, so ingesting
+/// the same transcript twice changes nothing.
+pub fn ingest(
+    db: &Database,
+    items: &[TranscriptItem],
+    options: &IngestOptions,
+) -> Result<Vec<IngestResult>> {
+    let workflow = db.workflow()?;
+    let existing = db.list_tasks(None)?;
+    let find = |item: &TranscriptItem| -> Result<Option<String>> {
+        if let Some(task_ref) = &item.task {
+            return resolve_task_id(db.get_connection(), task_ref).map(Some);
+        }
+        Ok(existing
+            .iter()
+            .find(|t| t.title.trim().eq_ignore_ascii_case(&item.title))
+            .map(|t| t.id.clone()))
+    };
+
+    let run = || -> Result<Vec<IngestResult>> {
+        let mut results = Vec::new();
+        for item in items {
+            let target = find(item)?;
+            let (action, task_id) = match (item.kind, target) {
+                (ItemKind::Completed, Some(id)) => {
+                    let task = db
+                        .get_task(&id)?
+                        .with_context(|| format!("Task not found: {}", id))?;
+                    if workflow.bucket(&task.status) == crate::db::StatusBucket::Done {
+                        (IngestAction::Skipped, Some(id))
+                    } else {
+                        (IngestAction::Completed, Some(id))
+                    }
+                }
+                (ItemKind::Completed | ItemKind::FollowUp, None) => (IngestAction::Created, None),
+                (ItemKind::FollowUp, Some(id)) => (IngestAction::Skipped, Some(id)),
+                (ItemKind::Decision, target) => {
+                    match target.or_else(|| options.decision_task.clone()) {
+                        Some(id) if logged_decision(db, &id, &item.title)? => {
+                            (IngestAction::Skipped, Some(id))
+                        }
+                        Some(id) => (IngestAction::Logged, Some(id)),
+                        None => (IngestAction::Skipped, None),
+                    }
+                }
+            };
+
+            let task_id = if options.dry_run {
+                task_id
+            } else {
+                apply(db, item, action, task_id, options)?
+            };
+            results.push(IngestResult {
+                item: item.clone(),
+                action,
+                task_id,
+            });
+        }
+        Ok(results)
+    };
+
+    if options.dry_run {
+        run()
+    } else {
+        db.write_transaction(run)
+    }
+}
+
+/// Whether `decision` is already logged on the task
+fn logged_decision(db: &Database, task_id: &str, decision: &str) -> Result<bool> {
+    Ok(db
+        .get_task_logs(task_id)?
+        .iter()
+        .any(|log| log.action == DECISION_ACTION && log.details.as_deref() == Some(decision)))
+}
+
+fn apply(
+    db: &Database,
+    item: &TranscriptItem,
+    action: IngestAction,
+    task_id: Option<String>,
+    options: &IngestOptions,
+) -> Result<Option<String>> {
+    let agent_id = options.agent_id.as_deref();
+    let task_id = match (action, task_id) {
+        (IngestAction::Skipped, task_id) => return Ok(task_id),
+        (IngestAction::Created, _) => {
+            let task = db.create_task(
+                item.title.clone(),
+                item.detail.clone(),
+                Priority::Medium,
+                None,
+                options.epic.clone(),
+            )?;
+            if item.kind == ItemKind::Completed {
+                db.update_task_status(&task.id, TaskStatus::Completed, agent_id)?;
+            }
+            task.id
+        }
+        (IngestAction::Completed, Some(id)) => {
+            db.update_task_status(&id, TaskStatus::Completed, agent_id)?;
+            id
+        }
+        (IngestAction::Logged, Some(id)) => {
+            db.log_task_action(&id, agent_id, DECISION_ACTION, Some(&item.title))?;
+            id
+        }
+        (_, None) => return Ok(None),
+    };
+
+    let link = match item.line {
+        Some(line) => format!("{}:{}", options.source, line),
+        None => options.source.clone(),
+    };
+    db.log_task_action(&task_id, agent_id, TRANSCRIPT_ACTION, Some(&link))?;
+    Ok(Some(task_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SESSION: &str = r##"{"type":"user","message":{"role":"user","content":"TODO: not from the assistant"}}
+{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Done: Add login endpoint\nDecision: Use JWT for sessions\n- [ ] Write API docs"}]}}
+{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","name":"TodoWrite","input":{"todos":[{"content":"Fix #2 flaky test","status":"pending"}]}}]}}
+{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","name":"TodoWrite","input":{"todos":[{"content":"Fix #2 flaky test","status":"completed"}]}}]}}
+not json"##;
+
+    #[test]
+    fn test_extract_items() -> Result<()> {
+        let items = TranscriptConfig::default().extract(SESSION, false)?;
+        let summary: Vec<(ItemKind, &str, Option<usize>)> = items
+            .iter()
+            .map(|i| (i.kind, i.title.as_str(), i.line))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (ItemKind::Completed, "Add login endpoint", Some(2)),
+                (ItemKind::Decision, "Use JWT for sessions", Some(2)),
+                (ItemKind::FollowUp, "Write API docs", Some(2)),
+                (ItemKind::Completed, "Fix #2 flaky test", Some(4)),
+            ]
+        );
+        assert_eq!(items[3].task.as_deref(), Some("#2"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_ingest_is_idempotent() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        let epic = db.create_task("Auth".to_string(), None, Priority::High, None, None)?;
+        let flaky = db.create_task("Flaky test".to_string(), None, Priority::Low, None, None)?;
+        let items = TranscriptConfig::default().extract(SESSION, false)?;
+        let options = IngestOptions {
+            source: "session.jsonl".to_string(),
+            decision_task: Some(epic.id.clone()),
+            ..Default::default()
+        };
+
+        let dry_run = IngestOptions {
+            dry_run: true,
+            ..options.clone()
+        };
+        ingest(&db, &items, &dry_run)?;
+        assert_eq!(db.list_tasks(None)?.len(), 2);
+
+        let actions: Vec<IngestAction> = ingest(&db, &items, &options)?
+            .iter()
+            .map(|r| r.action)
+            .collect();
+        assert_eq!(
+            actions,
+            [
+                IngestAction::Created,
+                IngestAction::Logged,
+                IngestAction::Created,
+                IngestAction::Completed,
+            ]
+        );
+        assert_eq!(
+            db.get_task(&flaky.id)?.unwrap().status,
+            TaskStatus::Completed
+        );
+        let logs = db.get_task_logs(&flaky.id)?;
+        assert!(logs
+            .iter()
+            .any(|l| l.action == TRANSCRIPT_ACTION
+                && l.details.as_deref() == Some("session.jsonl:4")));
+
+        let again = ingest(&db, &items, &options)?;
+        assert!(again.iter().all(|r| r.action == IngestAction::Skipped));
+        assert_eq!(db.list_tasks(None)?.len(), 4);
+        Ok(())
+    }
+}
@@ -0,0 +1,212 @@
+use crate::db::{Database, StatusBucket, TaskStatus};
+use anyhow::Result;
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Counts of one status across the snapshot days of a trend
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TrendSeries {
+    pub name: String,
+    /// One count per day in [`StatsTrend::days`]
+    pub values: Vec<usize>,
+}
+
+impl TrendSeries {
+    /// Change from the first to the latest snapshot
+    pub fn delta(&self) -> i64 {
+        match (self.values.first(), self.values.last()) {
+            (Some(&first), Some(&last)) => last as i64 - first as i64,
+            _ => 0,
+        }
+    }
+}
+
+/// Done and total tasks of one epic across the snapshot days of a trend
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EpicTrend {
+    pub epic: String,
+    pub done: Vec<usize>,
+    pub total: Vec<usize>,
+}
+
+/// Stored daily stats over a window, for deltas and sparklines
+#[derive(Debug, Clone, Serialize)]
+pub struct StatsTrend {
+    pub from: NaiveDate,
+    /// Days with a snapshot, oldest first
+    pub days: Vec<NaiveDate>,
+    pub statuses: Vec<TrendSeries>,
+    pub epics: Vec<EpicTrend>,
+}
+
+impl StatsTrend {
+    /// Change over the last week of `values`, against the latest snapshot at
+    /// least 7 days older than the newest one; None without such a snapshot
+    pub fn week_delta(&self, values: &[usize]) -> Option<i64> {
+        let last = *self.days.last()?;
+        let baseline = self
+            .days
+            .iter()
+            .rposition(|d| *d <= last - Duration::days(7))?;
+        Some(values[values.len() - 1] as i64 - values[baseline] as i64)
+    }
+
+    /// Notable changes, e.g. "+12 completed this week" or "blocked count
+    /// doubled (3 → 6)"
+    pub fn highlights(&self) -> Vec<String> {
+        let mut highlights = Vec::new();
+        for series in &self.statuses {
+            if let Some(change) = self.week_delta(&series.values).filter(|c| *c != 0) {
+                highlights.push(format!("{:+} {} this week", change, series.name));
+            }
+        }
+        for series in &self.statuses {
+            let (first, last) = match (series.values.first(), series.values.last()) {
+                (Some(&first), Some(&last)) if first > 0 => (first, last),
+                _ => continue,
+            };
+            if last >= first * 2 {
+                highlights.push(format!(
+                    "{} count doubled ({} → {})",
+                    series.name, first, last
+                ));
+            } else if last * 2 <= first {
+                highlights.push(format!(
+                    "{} count halved ({} → {})",
+                    series.name, first, last
+                ));
+            }
+        }
+        highlights
+    }
+}
+
+/// Trend of the snapshots taken in the `days` days up to `today`
+///
+/// Snapshots are recorded with [`Database::record_stats_snapshot`]; days
+/// without one are left out rather than interpolated.
+pub fn stats_trend(db: &Database, today: NaiveDate, days: i64) -> Result<StatsTrend> {
+    let from = today - Duration::days(days.max(1) - 1);
+    let workflow = db.workflow()?;
+    let rows = db.stats_snapshots_since(from)?;
+
+    let mut snapshot_days: Vec<NaiveDate> = rows.iter().map(|r| r.day).collect();
+    snapshot_days.dedup();
+    let index = |day: NaiveDate| snapshot_days.binary_search(&day).unwrap_or_default();
+
+    // Built-in statuses always get a row, custom ones once they hold tasks
+    let mut statuses: Vec<TrendSeries> = [
+        TaskStatus::Pending,
+        TaskStatus::InProgress,
+        TaskStatus::Blocked,
+        TaskStatus::Review,
+        TaskStatus::Completed,
+        TaskStatus::Failed,
+        TaskStatus::Cancelled,
+    ]
+    .iter()
+    .map(|status| TrendSeries {
+        name: status.as_str().to_string(),
+        values: vec![0; snapshot_days.len()],
+    })
+    .collect();
+    let mut custom: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    let mut epics: BTreeMap<String, EpicTrend> = BTreeMap::new();
+    for row in &rows {
+        let day = index(row.day);
+        let values = match statuses.iter_mut().find(|s| s.name == row.status.as_str()) {
+            Some(series) => &mut series.values,
+            None => custom
+                .entry(row.status.as_str().to_string())
+                .or_insert_with(|| vec![0; snapshot_days.len()]),
+        };
+        values[day] += row.tasks;
+
+        if let Some(epic) = &row.epic {
+            let trend = epics.entry(epic.clone()).or_insert_with(|| EpicTrend {
+                epic: epic.clone(),
+                done: vec![0; snapshot_days.len()],
+                total: vec![0; snapshot_days.len()],
+            });
+            trend.total[day] += row.tasks;
+            if workflow.bucket(&row.status) == StatusBucket::Done {
+                trend.done[day] += row.tasks;
+            }
+        }
+    }
+    statuses.extend(
+        custom
+            .into_iter()
+            .map(|(name, values)| TrendSeries { name, values }),
+    );
+
+    Ok(StatsTrend {
+        from,
+        days: snapshot_days,
+        statuses,
+        epics: epics.into_values().collect(),
+    })
+}
+
+/// One block character per value, scaled between the lowest and highest
+pub fn sparkline(values: &[usize]) -> String {
+    let (Some(&min), Some(&max)) = (values.iter().min(), values.iter().max()) else {
+        return String::new();
+    };
+    let span = (max - min).max(1);
+    values
+        .iter()
+        .map(|v| SPARK_LEVELS[(v - min) * (SPARK_LEVELS.len() - 1) / span])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Priority;
+
+    #[test]
+    fn test_trend_from_snapshots() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        let day = |d: u32| NaiveDate::from_ymd_opt(2026, 10, d).unwrap();
+        let mut tasks = Vec::new();
+        for i in 0..6 {
+            let epic = (i < 4).then(|| "auth".to_string());
+            tasks.push(db.create_task(format!("Task {}", i), None, Priority::Low, None, epic)?);
+        }
+        db.update_task_status(&tasks[0].id, TaskStatus::Blocked, None)?;
+        db.record_stats_snapshot(day(1))?;
+
+        db.update_task_status(&tasks[1].id, TaskStatus::Blocked, None)?;
+        db.update_task_status(&tasks[2].id, TaskStatus::Completed, None)?;
+        db.record_stats_snapshot(day(5))?;
+        db.update_task_status(&tasks[3].id, TaskStatus::Completed, None)?;
+        db.record_stats_snapshot(day(9))?;
+        // A second snapshot the same day replaces the first
+        db.update_task_status(&tasks[4].id, TaskStatus::Completed, None)?;
+        db.record_stats_snapshot(day(9))?;
+
+        let trend = stats_trend(&db, day(9), 30)?;
+        assert_eq!(trend.days, vec![day(1), day(5), day(9)]);
+        let series = |name: &str| trend.statuses.iter().find(|s| s.name == name).unwrap();
+        assert_eq!(series("completed").values, vec![0, 1, 3]);
+        assert_eq!(series("pending").delta(), -4);
+        assert_eq!(trend.week_delta(&series("completed").values), Some(3));
+        assert_eq!(trend.epics[0].done, vec![0, 1, 2]);
+        assert_eq!(trend.epics[0].total, vec![4, 4, 4]);
+
+        let highlights = trend.highlights();
+        assert!(highlights.contains(&"+3 completed this week".to_string()));
+        assert!(highlights.contains(&"blocked count doubled (1 → 2)".to_string()));
+        assert!(highlights.contains(&"pending count halved (5 → 1)".to_string()));
+
+        // Outside the window
+        assert_eq!(stats_trend(&db, day(9), 3)?.days, vec![day(9)]);
+        assert_eq!(sparkline(&[0, 1, 3]), "▁▃█");
+        assert_eq!(sparkline(&[2, 2]), "▁▁");
+        Ok(())
+    }
+}
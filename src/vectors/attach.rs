@@ -0,0 +1,254 @@
+//! Separate vector database - keeps embeddings out of the task database
+//!
+//! When `prd-vectors.db` (named after the task database, e.g. `tools/prd.db`
+//! gets `tools/prd-vectors.db`) exists next to the task database, it is
+//! attached as the `vectors` schema whenever the task database is opened.
+//! Vector queries use unqualified table names, so they find the embeddings
+//! wherever they live.
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use std::path::{Path, PathBuf};
+
+use super::store::VectorStore;
+
+/// Schema the separate vector database is attached as
+pub const SCHEMA: &str = "vectors";
+
+/// Vector tables and their seed rows, as created by migration 008
+const VECTOR_SCHEMA: &str = include_str!("../../migrations/008_add_vectors.sql");
+
+/// Embedding cache table, as created by migration 019
+const CACHE_SCHEMA: &str = include_str!("../../migrations/019_add_embedding_cache.sql");
+
+const EMBEDDING_COLUMNS: &str = "id, content_type, content_id, chunk_index, content_preview, \
+     content_hash, embedding, metadata, created_at, updated_at";
+
+const STATS_COLUMNS: &str =
+    "content_type, total_items, total_chunks, last_indexed_at, index_duration_ms";
+
+const CACHE_COLUMNS: &str = "model, text_hash, embedding, created_at, last_used_at";
+
+/// Path of the separate vector database for a task database
+pub fn sibling_path(db_path: &Path) -> PathBuf {
+    let stem = db_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "prd".to_string());
+    db_path.with_file_name(format!("{}-vectors.db", stem))
+}
+
+/// Attach the separate vector database if one exists next to `db_path`
+///
+/// Returns whether it was attached. In-memory databases never have one.
+pub fn attach_sibling(conn: &Connection, db_path: &str) -> Result<bool> {
+    if db_path == ":memory:" || db_path.is_empty() || is_separate(conn)? {
+        return Ok(false);
+    }
+    let path = sibling_path(Path::new(db_path));
+    if !path.is_file() {
+        return Ok(false);
+    }
+    attach(conn, &path)?;
+    Ok(true)
+}
+
+/// Whether the vector tables live in an attached database
+pub fn is_separate(conn: &Connection) -> Result<bool> {
+    let attached = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM pragma_database_list WHERE name = ?1)",
+        params![SCHEMA],
+        |row| row.get(0),
+    )?;
+    Ok(attached)
+}
+
+/// Create the vector tables if missing, in the separate database when one is
+/// attached
+pub fn ensure_schema(conn: &Connection) -> Result<()> {
+    if VectorStore::has_index(conn)? && has_table(conn, "embedding_cache")? {
+        return Ok(());
+    }
+    let schema = vector_schema();
+    if is_separate(conn)? {
+        let path: String = conn.query_row(
+            "SELECT file FROM pragma_database_list WHERE name = ?1",
+            params![SCHEMA],
+            |row| row.get(0),
+        )?;
+        Connection::open(&path)?.execute_batch(&schema)?;
+        return Ok(());
+    }
+    conn.execute_batch(&schema)?;
+    Ok(())
+}
+
+/// Move the vector index out of the task database into its sibling file and
+/// attach it
+///
+/// The task database is vacuumed afterwards so it actually shrinks.
+pub fn split(conn: &Connection, db_path: &str) -> Result<PathBuf> {
+    if is_separate(conn)? {
+        anyhow::bail!("Vector index is already in a separate database");
+    }
+    if db_path == ":memory:" {
+        anyhow::bail!("An in-memory database can't have a separate vector database");
+    }
+    let path = sibling_path(Path::new(db_path));
+    if path.exists() {
+        anyhow::bail!("{} already exists", path.display());
+    }
+
+    Connection::open(&path)
+        .and_then(|vectors| vectors.execute_batch(&vector_schema()))
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    attach(conn, &path)?;
+
+    if has_main_tables(conn)? {
+        conn.execute_batch(&format!(
+            "BEGIN IMMEDIATE;
+             {cache_sql}
+             INSERT INTO {schema}.embeddings ({e}) SELECT {e} FROM main.embeddings;
+             INSERT OR REPLACE INTO {schema}.vector_stats ({s}) SELECT {s} FROM main.vector_stats;
+             INSERT OR REPLACE INTO {schema}.embedding_cache ({c}) SELECT {c} FROM main.embedding_cache;
+             DROP TABLE main.embeddings;
+             DROP TABLE main.vector_stats;
+             DROP TABLE main.embedding_cache;
+             COMMIT;",
+            cache_sql = CACHE_SCHEMA,
+            schema = SCHEMA,
+            e = EMBEDDING_COLUMNS,
+            s = STATS_COLUMNS,
+            c = CACHE_COLUMNS
+        ))
+        .inspect_err(|_| {
+            let _ = conn.execute_batch("ROLLBACK");
+        })?;
+        conn.execute_batch("VACUUM main")?;
+    }
+    Ok(path)
+}
+
+/// Move the vector index back into the task database and delete the
+/// separate file
+pub fn join(conn: &Connection) -> Result<PathBuf> {
+    if !is_separate(conn)? {
+        anyhow::bail!("Vector index is not in a separate database");
+    }
+    let path: String = conn.query_row(
+        "SELECT file FROM pragma_database_list WHERE name = ?1",
+        params![SCHEMA],
+        |row| row.get(0),
+    )?;
+
+    conn.execute_batch(&format!(
+        "BEGIN IMMEDIATE;
+         {schema_sql}
+         DELETE FROM main.vector_stats;
+         INSERT INTO main.embeddings ({e}) SELECT {e} FROM {schema}.embeddings;
+         INSERT INTO main.vector_stats ({s}) SELECT {s} FROM {schema}.vector_stats;
+         INSERT OR REPLACE INTO main.embedding_cache ({c}) SELECT {c} FROM {schema}.embedding_cache;
+         COMMIT;",
+        schema_sql = vector_schema(),
+        schema = SCHEMA,
+        e = EMBEDDING_COLUMNS,
+        s = STATS_COLUMNS,
+        c = CACHE_COLUMNS
+    ))
+    .inspect_err(|_| {
+        let _ = conn.execute_batch("ROLLBACK");
+    })?;
+    conn.execute("DETACH DATABASE vectors", [])?;
+    std::fs::remove_file(&path).with_context(|| format!("Failed to remove {}", path))?;
+    Ok(PathBuf::from(path))
+}
+
+fn vector_schema() -> String {
+    format!("{}\n{}", VECTOR_SCHEMA, CACHE_SCHEMA)
+}
+
+fn attach(conn: &Connection, path: &Path) -> Result<()> {
+    conn.execute(
+        "ATTACH DATABASE ?1 AS vectors",
+        params![path.to_string_lossy()],
+    )
+    .with_context(|| format!("Failed to attach {}", path.display()))?;
+    Ok(())
+}
+
+fn has_table(conn: &Connection, name: &str) -> Result<bool> {
+    let exists = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM pragma_table_list WHERE name = ?1)",
+        params![name],
+        |row| row.get(0),
+    )?;
+    Ok(exists)
+}
+
+fn has_main_tables(conn: &Connection) -> Result<bool> {
+    let exists = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM main.sqlite_master WHERE type = 'table' AND name = 'embeddings')",
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(exists)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vectors::{ContentType, EMBEDDING_DIM};
+
+    fn count(conn: &Connection, sql: &str) -> i64 {
+        conn.query_row(sql, [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn test_split_and_join() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let db_path = dir.path().join("prd.db");
+        let db_str = db_path.to_str().unwrap();
+        let conn = Connection::open(&db_path)?;
+        ensure_schema(&conn)?;
+        VectorStore::store_embedding(
+            &conn,
+            ContentType::Task,
+            "#1",
+            0,
+            None,
+            "hash",
+            &vec![0.0; EMBEDDING_DIM],
+            None,
+        )?;
+
+        VectorStore::cache_embedding(&conn, "text-hash", &vec![0.0; EMBEDDING_DIM])?;
+
+        let vectors_path = split(&conn, db_str)?;
+        assert_eq!(vectors_path, dir.path().join("prd-vectors.db"));
+        assert!(is_separate(&conn)?);
+        assert!(!has_main_tables(&conn)?);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM embeddings"), 1);
+        assert_eq!(
+            count(&conn, "SELECT COUNT(*) FROM vectors.embedding_cache"),
+            1
+        );
+        assert!(split(&conn, db_str).is_err());
+
+        // A fresh connection picks the separate file up again
+        let reopened = Connection::open(&db_path)?;
+        assert!(attach_sibling(&reopened, db_str)?);
+        assert_eq!(
+            VectorStore::get_content_hash(&reopened, ContentType::Task, "#1")?,
+            Some("hash".to_string())
+        );
+        drop(reopened);
+
+        join(&conn)?;
+        assert!(!is_separate(&conn)?);
+        assert!(!vectors_path.exists());
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM main.embeddings"), 1);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM main.vector_stats"), 3);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM main.embedding_cache"), 1);
+        Ok(())
+    }
+}
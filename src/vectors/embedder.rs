@@ -5,6 +5,10 @@ use fastembed::{EmbeddingModel, InitOptions, TextEmbedding};
 
 use super::EMBEDDING_DIM;
 
+/// Texts per model run; fastembed spreads the runs of one call across the
+/// current rayon pool
+const EMBED_BATCH_SIZE: usize = 32;
+
 /// Wrapper around fastembed for generating text embeddings
 pub struct Embedder {
     model: Option<TextEmbedding>,
@@ -37,10 +41,12 @@ impl Embedder {
         if texts.is_empty() {
             return Ok(vec![]);
         }
+        let _span =
+            crate::telemetry::span("embedding.embed_batch").with_attribute("embedding.texts", texts.len());
 
         let model = self.ensure_loaded()?;
         let embeddings = model
-            .embed(texts.to_vec(), None)
+            .embed(texts.to_vec(), Some(EMBED_BATCH_SIZE))
             .context("Failed to generate embeddings")?;
 
         // Validate dimensions
@@ -0,0 +1,290 @@
+//! Vector index health - disk usage, drift from sources and embedding checks
+
+use anyhow::Result;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use super::indexer::{task_text, ContentIndexer};
+use super::store::{ContentType, VectorStore};
+use super::EMBEDDING_DIM;
+
+/// Size, drift and dimensions of one content type's embeddings
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexHealth {
+    pub content_type: ContentType,
+    pub items: usize,
+    pub chunks: usize,
+    /// Bytes stored for embeddings, previews, hashes and metadata
+    pub bytes: i64,
+    /// Items whose source changed since they were indexed
+    pub stale_items: usize,
+    pub stale_chunks: usize,
+    /// Items whose task or file no longer exists
+    pub missing_items: usize,
+    /// Distinct embedding dimensions stored; anything but the model's means
+    /// the index was built by a different model
+    pub dimensions: Vec<usize>,
+}
+
+/// A problem found by [`check_sample`]
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckIssue {
+    pub content_type: ContentType,
+    pub content_id: String,
+    pub problem: String,
+}
+
+/// Result of verifying a sample of indexed items
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CheckReport {
+    pub items_checked: usize,
+    pub chunks_checked: usize,
+    pub issues: Vec<CheckIssue>,
+}
+
+/// Health of each content type's index, comparing every item's stored hash
+/// with a hash of its current source
+pub fn index_health(conn: &Connection) -> Result<Vec<IndexHealth>> {
+    if !VectorStore::has_index(conn)? {
+        return Ok(Vec::new());
+    }
+
+    let mut health = Vec::new();
+    for content_type in [ContentType::Task, ContentType::Code, ContentType::Doc] {
+        let (chunks, bytes): (i64, Option<i64>) = conn.query_row(
+            "SELECT COUNT(*),
+                    SUM(length(embedding) + length(content_id) + length(content_hash)
+                        + IFNULL(length(content_preview), 0) + IFNULL(length(metadata), 0))
+             FROM embeddings WHERE content_type = ?1",
+            params![content_type.as_str()],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT length(embedding) / 4 FROM embeddings WHERE content_type = ?1",
+        )?;
+        let dimensions = stmt
+            .query_map(params![content_type.as_str()], |row| row.get::<_, i64>(0))?
+            .map(|dim| dim.map(|d| d as usize))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut entry = IndexHealth {
+            content_type,
+            items: 0,
+            chunks: chunks as usize,
+            bytes: bytes.unwrap_or(0),
+            stale_items: 0,
+            stale_chunks: 0,
+            missing_items: 0,
+            dimensions,
+        };
+        for (content_id, (hash, item_chunks)) in indexed_items(conn, content_type)? {
+            entry.items += 1;
+            match source_hash(conn, content_type, &content_id)? {
+                None => entry.missing_items += 1,
+                Some(current) if current != hash => {
+                    entry.stale_items += 1;
+                    entry.stale_chunks += item_chunks;
+                }
+                Some(_) => {}
+            }
+        }
+        health.push(entry);
+    }
+    Ok(health)
+}
+
+/// Verify up to `sample` randomly chosen items: every chunk must decode to a
+/// finite vector of the model's dimension and carry the hash of the item's
+/// current source
+pub fn check_sample(conn: &Connection, sample: usize) -> Result<CheckReport> {
+    let mut report = CheckReport::default();
+    if !VectorStore::has_index(conn)? {
+        return Ok(report);
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT content_type, content_id FROM embeddings
+         GROUP BY content_type, content_id ORDER BY random() LIMIT ?1",
+    )?;
+    let items = stmt
+        .query_map(params![sample as i64], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (type_str, content_id) in items {
+        let Some(content_type) = ContentType::from_str(&type_str) else {
+            continue;
+        };
+        report.items_checked += 1;
+        let mut problems = Vec::new();
+
+        let current = source_hash(conn, content_type, &content_id)?;
+        let mut stmt = conn.prepare(
+            "SELECT chunk_index, content_hash, embedding FROM embeddings
+             WHERE content_type = ?1 AND content_id = ?2 ORDER BY chunk_index",
+        )?;
+        let chunks = stmt
+            .query_map(params![type_str, content_id], |row| {
+                Ok((
+                    row.get::<_, i32>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Vec<u8>>(2)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match &current {
+            None => problems.push("source no longer exists".to_string()),
+            Some(hash) if chunks.iter().any(|(_, h, _)| h != hash) => {
+                problems.push("source changed since indexing".to_string())
+            }
+            Some(_) => {}
+        }
+        for (chunk_index, _, blob) in chunks {
+            report.chunks_checked += 1;
+            let problem = match VectorStore::decode_embedding(&blob) {
+                Err(_) => Some("embedding can't be decoded".to_string()),
+                Ok(vector) if vector.len() != EMBEDDING_DIM => Some(format!(
+                    "{} dimensions, expected {}",
+                    vector.len(),
+                    EMBEDDING_DIM
+                )),
+                Ok(vector) if vector.iter().any(|v| !v.is_finite()) => {
+                    Some("embedding has non-finite values".to_string())
+                }
+                Ok(_) => None,
+            };
+            if let Some(problem) = problem {
+                problems.push(format!("chunk {}: {}", chunk_index, problem));
+            }
+        }
+        report
+            .issues
+            .extend(problems.into_iter().map(|problem| CheckIssue {
+                content_type,
+                content_id: content_id.clone(),
+                problem,
+            }));
+    }
+    Ok(report)
+}
+
+/// Stored hash and chunk count of each indexed item of a type
+fn indexed_items(
+    conn: &Connection,
+    content_type: ContentType,
+) -> Result<BTreeMap<String, (String, usize)>> {
+    let mut stmt = conn.prepare(
+        "SELECT content_id, MIN(content_hash), COUNT(*) FROM embeddings
+         WHERE content_type = ?1 GROUP BY content_id",
+    )?;
+    let items = stmt
+        .query_map(params![content_type.as_str()], |row| {
+            Ok((row.get(0)?, (row.get(1)?, row.get::<_, i64>(2)? as usize)))
+        })?
+        .collect::<Result<_, _>>()?;
+    Ok(items)
+}
+
+/// Hash of an item's current source, as the indexer would compute it, or
+/// None when the task was deleted or cancelled or the file is gone
+fn source_hash(
+    conn: &Connection,
+    content_type: ContentType,
+    content_id: &str,
+) -> Result<Option<String>> {
+    let text = match content_type {
+        ContentType::Task => {
+            let display_id = content_id
+                .strip_prefix('#')
+                .and_then(|id| id.parse::<i32>().ok());
+            let task: Option<(Option<i32>, String, Option<String>)> = conn
+                .query_row(
+                    "SELECT display_id, title, description FROM tasks
+                     WHERE (id = ?1 OR display_id = ?2) AND status != 'cancelled'
+                       AND deleted_at IS NULL",
+                    params![content_id, display_id],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+                )
+                .optional()?;
+            match task {
+                Some((display_id, title, description)) => {
+                    task_text(conn, display_id, &title, description.as_deref())?
+                }
+                None => return Ok(None),
+            }
+        }
+        ContentType::Code | ContentType::Doc => match std::fs::read_to_string(content_id) {
+            Ok(content) => content,
+            Err(_) => return Ok(None),
+        },
+    };
+    Ok(Some(ContentIndexer::hash_content(&text)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_health_and_check() -> Result<()> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(include_str!("../../migrations/008_add_vectors.sql"))?;
+        conn.execute_batch(
+            "CREATE TABLE tasks (id TEXT PRIMARY KEY, display_id INTEGER, title TEXT,
+                                 description TEXT, status TEXT, deleted_at TEXT);
+             CREATE TABLE acceptance_criteria (id INTEGER PRIMARY KEY, task_display_id INTEGER,
+                                               criterion TEXT);
+             INSERT INTO tasks VALUES ('uuid-1', 1, 'Fresh', NULL, 'pending', NULL),
+                                      ('uuid-2', 2, 'Edited', NULL, 'pending', NULL);",
+        )?;
+        let embedding = vec![0.1; EMBEDDING_DIM];
+        let fresh = ContentIndexer::hash_content(&task_text(&conn, Some(1), "Fresh", None)?);
+        for (content_id, hash) in [("#1", fresh.as_str()), ("#2", "old"), ("#3", "gone")] {
+            VectorStore::store_embedding(
+                &conn,
+                ContentType::Task,
+                content_id,
+                0,
+                None,
+                hash,
+                &embedding,
+                None,
+            )?;
+        }
+
+        let health = index_health(&conn)?;
+        let tasks = &health[0];
+        assert_eq!((tasks.items, tasks.chunks), (3, 3));
+        assert_eq!((tasks.stale_items, tasks.missing_items), (1, 1));
+        assert_eq!(tasks.dimensions, [EMBEDDING_DIM]);
+        assert!(tasks.bytes > (3 * EMBEDDING_DIM * 4) as i64);
+        assert_eq!(health[1].items, 0);
+
+        // Corrupt the fresh task's vector
+        conn.execute(
+            "UPDATE embeddings SET embedding = ?1 WHERE content_id = '#1'",
+            params![VectorStore::encode_embedding(&[f32::NAN; EMBEDDING_DIM])?],
+        )?;
+        let report = check_sample(&conn, 10)?;
+        assert_eq!((report.items_checked, report.chunks_checked), (3, 3));
+        let mut problems: Vec<String> = report
+            .issues
+            .iter()
+            .map(|i| format!("{} {}", i.content_id, i.problem))
+            .collect();
+        problems.sort();
+        assert_eq!(
+            problems,
+            [
+                "#1 chunk 0: embedding has non-finite values",
+                "#2 source changed since indexing",
+                "#3 source no longer exists",
+            ]
+        );
+        Ok(())
+    }
+}
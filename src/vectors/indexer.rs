@@ -2,12 +2,16 @@
 
 use anyhow::{Context, Result};
 use ignore::WalkBuilder;
-use rusqlite::Connection;
+use indicatif::{ProgressBar, ProgressStyle};
+use rayon::prelude::*;
+use rayon::ThreadPool;
+use rusqlite::{Connection, Transaction, TransactionBehavior};
 use sha2::{Digest, Sha256};
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 
-use super::chunker::TextChunker;
+use super::chunker::{Chunk, TextChunker};
 use super::embedder::Embedder;
 use super::store::{ContentType, VectorStore};
 
@@ -18,6 +22,10 @@ pub struct IndexStats {
     pub items_skipped: usize,
     pub chunks_created: usize,
     pub errors: usize,
+    /// Embeddings removed because their task or file is gone
+    pub chunks_pruned: usize,
+    /// Chunks whose embedding came from the cache instead of the model
+    pub cache_hits: usize,
     pub duration_ms: u64,
 }
 
@@ -27,15 +35,39 @@ impl IndexStats {
         self.items_skipped += other.items_skipped;
         self.chunks_created += other.chunks_created;
         self.errors += other.errors;
+        self.chunks_pruned += other.chunks_pruned;
+        self.cache_hits += other.cache_hits;
         self.duration_ms += other.duration_ms;
     }
 }
 
+/// Files read, chunked and embedded together before their embeddings are
+/// written in one transaction
+const FILE_BATCH: usize = 64;
+
+/// A file read and chunked, ready to embed
+struct PreparedFile {
+    content_id: String,
+    hash: String,
+    extension: String,
+    /// Each chunk with the hash of its text
+    chunks: Vec<(Chunk, String)>,
+}
+
+/// Outcome of reading one file for indexing
+enum FileRead {
+    Prepared(PreparedFile),
+    Skipped,
+    Failed(anyhow::Error),
+}
+
 /// Content indexer for creating embeddings
 pub struct ContentIndexer<'a> {
     embedder: &'a mut Embedder,
     conn: &'a Connection,
     chunker: TextChunker,
+    jobs: usize,
+    progress: bool,
 }
 
 impl<'a> ContentIndexer<'a> {
@@ -45,17 +77,33 @@ impl<'a> ContentIndexer<'a> {
             embedder,
             conn,
             chunker: TextChunker::new(),
+            jobs: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            progress: false,
         }
     }
 
+    /// Read, chunk and embed files on `jobs` threads (all cores by default)
+    pub fn with_jobs(mut self, jobs: usize) -> Self {
+        self.jobs = jobs.max(1);
+        self
+    }
+
+    /// Show a progress bar while indexing directories
+    pub fn with_progress(mut self, progress: bool) -> Self {
+        self.progress = progress;
+        self
+    }
+
     /// Index all tasks from the database
     pub fn index_tasks(&mut self, force: bool) -> Result<IndexStats> {
+        let _span = crate::telemetry::span("vector.index_tasks").with_attribute("vector.force", force);
         let start = Instant::now();
         let mut stats = IndexStats::default();
 
         // Get all tasks
         let mut stmt = self.conn.prepare(
-            "SELECT id, display_id, title, description FROM tasks WHERE status != 'cancelled'",
+            "SELECT id, display_id, title, description FROM tasks
+             WHERE status != 'cancelled' AND deleted_at IS NULL",
         )?;
 
         let tasks: Vec<(String, Option<i32>, String, Option<String>)> = stmt
@@ -71,28 +119,7 @@ impl<'a> ContentIndexer<'a> {
                 None => task_id.clone(),
             };
 
-            // Build full task text
-            let mut text = format!("Task: {}\n\n", title);
-            if let Some(desc) = &description {
-                text.push_str(&format!("Description:\n{}\n\n", desc));
-            }
-
-            // Add acceptance criteria
-            let criteria: Vec<String> = self
-                .conn
-                .prepare(
-                    "SELECT criterion FROM acceptance_criteria WHERE task_display_id = ?1 ORDER BY id",
-                )?
-                .query_map([display_id.unwrap_or(0)], |row| row.get(0))?
-                .collect::<Result<Vec<_>, _>>()
-                .unwrap_or_default();
-
-            if !criteria.is_empty() {
-                text.push_str("Acceptance Criteria:\n");
-                for (i, criterion) in criteria.iter().enumerate() {
-                    text.push_str(&format!("{}. {}\n", i + 1, criterion));
-                }
-            }
+            let text = task_text(self.conn, display_id, &title, description.as_deref())?;
 
             // Check if content changed
             let hash = Self::hash_content(&text);
@@ -108,7 +135,7 @@ impl<'a> ContentIndexer<'a> {
             }
 
             // Generate embedding
-            match self.embedder.embed_one(&text) {
+            match self.embed(&text, &mut stats) {
                 Ok(embedding) => {
                     let preview = Self::create_preview(&text, 200);
                     VectorStore::store_embedding(
@@ -135,6 +162,7 @@ impl<'a> ContentIndexer<'a> {
             }
         }
 
+        stats.chunks_pruned = VectorStore::prune_missing_tasks(self.conn)?;
         stats.duration_ms = start.elapsed().as_millis() as u64;
 
         // Update stats
@@ -157,6 +185,9 @@ impl<'a> ContentIndexer<'a> {
         patterns: &[String],
         force: bool,
     ) -> Result<IndexStats> {
+        let _span = crate::telemetry::span("vector.index_directory")
+            .with_attribute("vector.path", path.display().to_string())
+            .with_attribute("vector.force", force);
         let start = Instant::now();
         let mut stats = IndexStats::default();
 
@@ -172,6 +203,7 @@ impl<'a> ContentIndexer<'a> {
             .git_global(true)
             .git_exclude(true);
 
+        let mut files = Vec::new();
         for entry in walker.build() {
             let entry = match entry {
                 Ok(e) => e,
@@ -208,16 +240,34 @@ impl<'a> ContentIndexer<'a> {
             if !Self::is_indexable_file(file_path, content_type) {
                 continue;
             }
+            files.push(file_path.to_path_buf());
+        }
 
-            match self.index_file(file_path, content_type, force) {
-                Ok(file_stats) => stats.merge(&file_stats),
-                Err(e) => {
-                    eprintln!("Error indexing {}: {}", file_path.display(), e);
-                    stats.errors += 1;
-                }
-            }
+        let existing = if force {
+            HashMap::new()
+        } else {
+            VectorStore::content_hashes(self.conn, content_type)?
+        };
+        let pool = self.thread_pool()?;
+        let progress = if self.progress {
+            let pb = ProgressBar::new(files.len() as u64);
+            pb.set_style(
+                ProgressStyle::default_bar()
+                    .template("{spinner:.green} [{bar:40.cyan/blue}] {pos}/{len} files ({eta})")
+                    .unwrap()
+                    .progress_chars("#>-"),
+            );
+            pb
+        } else {
+            ProgressBar::hidden()
+        };
+        for batch in files.chunks(FILE_BATCH) {
+            stats.merge(&self.index_batch(&pool, batch, content_type, &existing)?);
+            progress.inc(batch.len() as u64);
         }
+        progress.finish_and_clear();
 
+        stats.chunks_pruned = VectorStore::prune_missing_files(self.conn, Some(path))?;
         stats.duration_ms = start.elapsed().as_millis() as u64;
 
         // Update stats
@@ -238,92 +288,200 @@ impl<'a> ContentIndexer<'a> {
         path: &Path,
         content_type: ContentType,
         force: bool,
+    ) -> Result<IndexStats> {
+        let content_id = path.to_string_lossy().to_string();
+        let mut existing = HashMap::new();
+        if !force {
+            if let Some(hash) = VectorStore::get_content_hash(self.conn, content_type, &content_id)? {
+                existing.insert(content_id, hash);
+            }
+        }
+        let pool = self.thread_pool()?;
+        self.index_batch(&pool, &[path.to_path_buf()], content_type, &existing)
+    }
+
+    /// Index a batch of files: read and chunk them in parallel, embed every
+    /// chunk missing from the cache in one parallel model run, then replace
+    /// the files' embeddings in a single transaction
+    ///
+    /// Files whose hash matches `existing` are skipped. When the model fails
+    /// the batch's changed files keep their old embeddings and count as errors.
+    fn index_batch(
+        &mut self,
+        pool: &ThreadPool,
+        files: &[PathBuf],
+        content_type: ContentType,
+        existing: &HashMap<String, String>,
     ) -> Result<IndexStats> {
         let mut stats = IndexStats::default();
+        let chunker = &self.chunker;
+        let reads: Vec<FileRead> = pool.install(|| {
+            files
+                .par_iter()
+                .map(|path| Self::read_file(chunker, path, content_type, existing))
+                .collect()
+        });
+
+        let mut prepared = Vec::new();
+        for (path, read) in files.iter().zip(reads) {
+            match read {
+                FileRead::Prepared(file) => prepared.push(file),
+                FileRead::Skipped => stats.items_skipped += 1,
+                FileRead::Failed(e) => {
+                    eprintln!("Error indexing {}: {}", path.display(), e);
+                    stats.errors += 1;
+                }
+            }
+        }
 
-        // Read file content
-        let content = std::fs::read_to_string(path)
-            .with_context(|| format!("Failed to read file: {}", path.display()))?;
+        // Reuse cached embeddings and run the model once for the rest
+        let mut vectors: HashMap<&str, Vec<f32>> = HashMap::new();
+        let mut fresh: Vec<(&str, &str)> = Vec::new();
+        let mut uncached = HashSet::new();
+        for (chunk, text_hash) in prepared.iter().flat_map(|file| &file.chunks) {
+            let text_hash = text_hash.as_str();
+            if vectors.contains_key(text_hash) || uncached.contains(text_hash) {
+                continue;
+            }
+            match VectorStore::cached_embedding(self.conn, text_hash)? {
+                Some(embedding) => {
+                    vectors.insert(text_hash, embedding);
+                }
+                None => {
+                    uncached.insert(text_hash);
+                    fresh.push((text_hash, &chunk.text));
+                }
+            }
+        }
+        let texts: Vec<&str> = fresh.iter().map(|(_, text)| *text).collect();
+        let embedder = &mut *self.embedder;
+        match pool.install(|| embedder.embed_batch(&texts)) {
+            Ok(embeddings) => {
+                for ((text_hash, _), embedding) in fresh.iter().zip(embeddings) {
+                    vectors.insert(text_hash, embedding);
+                }
+            }
+            Err(e) => eprintln!("Error embedding {} chunks: {}", texts.len(), e),
+        }
 
-        if content.is_empty() {
-            stats.items_skipped += 1;
-            return Ok(stats);
+        let tx = Transaction::new_unchecked(self.conn, TransactionBehavior::Immediate)?;
+        for (text_hash, _) in &fresh {
+            if let Some(embedding) = vectors.get(text_hash) {
+                VectorStore::cache_embedding(&tx, text_hash, embedding)?;
+            }
         }
+        for file in &prepared {
+            if file
+                .chunks
+                .iter()
+                .any(|(_, text_hash)| !vectors.contains_key(text_hash.as_str()))
+            {
+                stats.errors += 1;
+                continue;
+            }
 
-        let content_id = path.to_string_lossy().to_string();
-        let hash = Self::hash_content(&content);
+            // Replace the file's old embeddings
+            VectorStore::delete_embeddings(&tx, content_type, &file.content_id)?;
+            for (chunk, text_hash) in &file.chunks {
+                let preview = Self::create_preview(&chunk.text, 200);
+                let metadata = serde_json::json!({
+                    "file_path": file.content_id,
+                    "file_type": file.extension,
+                    "line_start": chunk.line_start,
+                    "line_end": chunk.line_end,
+                    "char_start": chunk.start_char,
+                    "char_end": chunk.end_char,
+                });
 
-        // Check if content changed
-        if !force {
-            if let Some(existing_hash) =
-                VectorStore::get_content_hash(self.conn, content_type, &content_id)?
-            {
-                if existing_hash == hash {
-                    stats.items_skipped += 1;
-                    return Ok(stats);
+                VectorStore::store_embedding(
+                    &tx,
+                    content_type,
+                    &file.content_id,
+                    chunk.index as i32,
+                    Some(&preview),
+                    &file.hash,
+                    &vectors[text_hash.as_str()],
+                    Some(&metadata.to_string()),
+                )?;
+                stats.chunks_created += 1;
+                if !uncached.contains(text_hash.as_str()) {
+                    stats.cache_hits += 1;
                 }
             }
+            if !file.chunks.is_empty() {
+                stats.items_indexed += 1;
+            }
         }
+        tx.commit()?;
 
-        // Delete old embeddings for this file
-        VectorStore::delete_embeddings(self.conn, content_type, &content_id)?;
+        Ok(stats)
+    }
+
+    /// Read and chunk a file, skipping it when empty or unchanged
+    fn read_file(
+        chunker: &TextChunker,
+        path: &Path,
+        content_type: ContentType,
+        existing: &HashMap<String, String>,
+    ) -> FileRead {
+        let content = match std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read file: {}", path.display()))
+        {
+            Ok(content) => content,
+            Err(e) => return FileRead::Failed(e),
+        };
+        if content.is_empty() {
+            return FileRead::Skipped;
+        }
+
+        let content_id = path.to_string_lossy().to_string();
+        let hash = Self::hash_content(&content);
+        if existing.get(&content_id) == Some(&hash) {
+            return FileRead::Skipped;
+        }
 
-        // Get file extension
         let extension = path
             .extension()
             .and_then(|e| e.to_str())
-            .unwrap_or("");
-
-        // Chunk the content
+            .unwrap_or("")
+            .to_string();
         let chunks = if content_type == ContentType::Code {
-            self.chunker.chunk_code(&content, extension)
+            chunker.chunk_code(&content, &extension)
         } else {
-            self.chunker.chunk(&content)
+            chunker.chunk(&content)
         };
+        FileRead::Prepared(PreparedFile {
+            content_id,
+            hash,
+            extension,
+            chunks: chunks
+                .into_iter()
+                .map(|chunk| {
+                    let text_hash = Self::hash_content(&chunk.text);
+                    (chunk, text_hash)
+                })
+                .collect(),
+        })
+    }
 
-        // Generate embeddings for each chunk
-        for chunk in &chunks {
-            match self.embedder.embed_one(&chunk.text) {
-                Ok(embedding) => {
-                    let preview = Self::create_preview(&chunk.text, 200);
-                    let metadata = serde_json::json!({
-                        "file_path": content_id,
-                        "file_type": extension,
-                        "line_start": chunk.line_start,
-                        "line_end": chunk.line_end,
-                        "char_start": chunk.start_char,
-                        "char_end": chunk.end_char,
-                    });
-
-                    VectorStore::store_embedding(
-                        self.conn,
-                        content_type,
-                        &content_id,
-                        chunk.index as i32,
-                        Some(&preview),
-                        &hash,
-                        &embedding,
-                        Some(&metadata.to_string()),
-                    )?;
-                    stats.chunks_created += 1;
-                }
-                Err(e) => {
-                    eprintln!(
-                        "Error embedding chunk {} of {}: {}",
-                        chunk.index,
-                        path.display(),
-                        e
-                    );
-                    stats.errors += 1;
-                }
-            }
-        }
+    fn thread_pool(&self) -> Result<ThreadPool> {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(self.jobs)
+            .build()
+            .context("Failed to start indexing threads")
+    }
 
-        if stats.chunks_created > 0 {
-            stats.items_indexed = 1;
+    /// Embed text, reusing the cached embedding of identical text when there
+    /// is one so the model only runs (and loads) for new content
+    fn embed(&mut self, text: &str, stats: &mut IndexStats) -> Result<Vec<f32>> {
+        let text_hash = Self::hash_content(text);
+        if let Some(embedding) = VectorStore::cached_embedding(self.conn, &text_hash)? {
+            stats.cache_hits += 1;
+            return Ok(embedding);
         }
-
-        Ok(stats)
+        let embedding = self.embedder.embed_one(text)?;
+        VectorStore::cache_embedding(self.conn, &text_hash, &embedding)?;
+        Ok(embedding)
     }
 
     /// Check if a file should be indexed based on content type
@@ -386,13 +544,40 @@ impl<'a> ContentIndexer<'a> {
     }
 
     /// Hash content for change detection
-    fn hash_content(content: &str) -> String {
+    pub(crate) fn hash_content(content: &str) -> String {
         let mut hasher = Sha256::new();
         hasher.update(content.as_bytes());
         format!("{:x}", hasher.finalize())
     }
 }
 
+/// Text a task is embedded from: title, description and acceptance criteria
+pub(crate) fn task_text(
+    conn: &Connection,
+    display_id: Option<i32>,
+    title: &str,
+    description: Option<&str>,
+) -> Result<String> {
+    let mut text = format!("Task: {}\n\n", title);
+    if let Some(desc) = description {
+        text.push_str(&format!("Description:\n{}\n\n", desc));
+    }
+
+    let criteria: Vec<String> = conn
+        .prepare("SELECT criterion FROM acceptance_criteria WHERE task_display_id = ?1 ORDER BY id")?
+        .query_map([display_id.unwrap_or(0)], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap_or_default();
+
+    if !criteria.is_empty() {
+        text.push_str("Acceptance Criteria:\n");
+        for (i, criterion) in criteria.iter().enumerate() {
+            text.push_str(&format!("{}. {}\n", i + 1, criterion));
+        }
+    }
+    Ok(text)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -455,6 +640,8 @@ mod tests {
             items_skipped: 2,
             chunks_created: 10,
             errors: 1,
+            chunks_pruned: 0,
+            cache_hits: 4,
             duration_ms: 100,
         };
 
@@ -463,6 +650,8 @@ mod tests {
             items_skipped: 1,
             chunks_created: 6,
             errors: 0,
+            chunks_pruned: 2,
+            cache_hits: 1,
             duration_ms: 50,
         };
 
@@ -472,6 +661,64 @@ mod tests {
         assert_eq!(stats1.items_skipped, 3);
         assert_eq!(stats1.chunks_created, 16);
         assert_eq!(stats1.errors, 1);
+        assert_eq!(stats1.chunks_pruned, 2);
+        assert_eq!(stats1.cache_hits, 5);
         assert_eq!(stats1.duration_ms, 150);
     }
+
+    #[test]
+    fn test_index_tasks_from_cache() -> Result<()> {
+        let conn = Connection::open_in_memory()?;
+        crate::vectors::attach::ensure_schema(&conn)?;
+        conn.execute_batch(
+            "CREATE TABLE tasks (id TEXT PRIMARY KEY, display_id INTEGER, title TEXT,
+                                 description TEXT, status TEXT, deleted_at TEXT);
+             CREATE TABLE acceptance_criteria (id INTEGER PRIMARY KEY, task_display_id INTEGER,
+                                               criterion TEXT);
+             INSERT INTO tasks VALUES ('uuid-1', 1, 'Cached', NULL, 'pending', NULL);",
+        )?;
+        let text = task_text(&conn, Some(1), "Cached", None)?;
+        let embedding = vec![0.25; crate::vectors::EMBEDDING_DIM];
+        VectorStore::cache_embedding(&conn, &ContentIndexer::hash_content(&text), &embedding)?;
+
+        // Identical text is embedded from the cache without loading the model
+        let mut embedder = Embedder::new();
+        let stats = ContentIndexer::new(&mut embedder, &conn).index_tasks(true)?;
+        assert_eq!((stats.items_indexed, stats.cache_hits, stats.errors), (1, 1, 0));
+        assert!(!embedder.is_loaded());
+        let stored = VectorStore::get_all_embeddings(&conn, Some(ContentType::Task))?;
+        assert_eq!(stored[0].1, embedding);
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_directory_in_batches() -> Result<()> {
+        let conn = Connection::open_in_memory()?;
+        crate::vectors::attach::ensure_schema(&conn)?;
+        let dir = tempfile::tempdir()?;
+        let content = "# Notes\n\nShared text in two files.";
+        std::fs::write(dir.path().join("a.md"), content)?;
+        std::fs::write(dir.path().join("b.md"), content)?;
+        std::fs::write(dir.path().join("empty.md"), "")?;
+        let embedding = vec![0.5; crate::vectors::EMBEDDING_DIM];
+        for chunk in TextChunker::new().chunk(content) {
+            VectorStore::cache_embedding(
+                &conn,
+                &ContentIndexer::hash_content(&chunk.text),
+                &embedding,
+            )?;
+        }
+
+        let mut embedder = Embedder::new();
+        let mut indexer = ContentIndexer::new(&mut embedder, &conn).with_jobs(2);
+        let stats = indexer.index_directory(dir.path(), ContentType::Doc, &[], false)?;
+        assert_eq!((stats.items_indexed, stats.items_skipped), (2, 1));
+        assert_eq!((stats.chunks_created, stats.cache_hits, stats.errors), (2, 2, 0));
+
+        // Unchanged files are skipped on the next run
+        let stats = indexer.index_directory(dir.path(), ContentType::Doc, &[], false)?;
+        assert_eq!((stats.items_indexed, stats.items_skipped), (0, 3));
+        assert!(!embedder.is_loaded());
+        Ok(())
+    }
 }
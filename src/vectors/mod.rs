@@ -2,21 +2,36 @@
 //!
 //! This module provides:
 //! - Text embeddings using fastembed (BAAI/bge-small-en-v1.5)
-//! - Vector storage in SQLite
+//! - Vector storage in SQLite, optionally in a separate attached file
 //! - Content indexing (tasks, code, documentation)
 //! - Similarity search
+//!
+//! Embedding and indexing need the `vectors` feature; the store and searches
+//! over stored embeddings are always available.
 
+pub mod attach;
 pub mod chunker;
+#[cfg(feature = "vectors")]
 pub mod embedder;
+#[cfg(feature = "vectors")]
+pub mod health;
+#[cfg(feature = "vectors")]
 pub mod indexer;
 pub mod search;
 pub mod store;
 
 pub use chunker::{Chunk, TextChunker};
+#[cfg(feature = "vectors")]
 pub use embedder::Embedder;
+#[cfg(feature = "vectors")]
+pub use health::{CheckReport, IndexHealth};
+#[cfg(feature = "vectors")]
 pub use indexer::{ContentIndexer, IndexStats};
-pub use search::{SearchResult, VectorSearch};
-pub use store::{ContentType, EmbeddingRecord, VectorStore};
+pub use search::{RelatedContent, SearchResult, VectorSearch};
+pub use store::{ContentType, EmbeddingRecord, PruneReport, SearchScope, VectorStore};
+
+/// Embedding model every index is built with
+pub const EMBEDDING_MODEL: &str = "BAAI/bge-small-en-v1.5";
 
 /// Vector dimension for bge-small-en-v1.5 model
 pub const EMBEDDING_DIM: usize = 384;
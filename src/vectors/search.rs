@@ -4,7 +4,8 @@ use anyhow::Result;
 use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
 
-use super::store::{ContentType, EmbeddingRecord, VectorStore};
+use super::store::{ContentType, EmbeddingRecord, SearchScope, VectorStore};
+#[cfg(feature = "vectors")]
 use super::Embedder;
 
 /// A search result with similarity score
@@ -15,21 +16,44 @@ pub struct SearchResult {
     pub rank: usize,
 }
 
+/// Content related to a task, by type, most similar first
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RelatedContent {
+    pub tasks: Vec<SearchResult>,
+    pub code: Vec<SearchResult>,
+    pub docs: Vec<SearchResult>,
+}
+
+impl RelatedContent {
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty() && self.code.is_empty() && self.docs.is_empty()
+    }
+}
+
 /// Vector search engine
 pub struct VectorSearch;
 
 impl VectorSearch {
     /// Search for similar content using a text query
+    #[cfg(feature = "vectors")]
     pub fn search_text(
         conn: &Connection,
         embedder: &mut Embedder,
         query: &str,
         content_type: Option<ContentType>,
+        scope: &SearchScope,
         limit: usize,
         threshold: f32,
     ) -> Result<Vec<SearchResult>> {
         let query_embedding = embedder.embed_one(query)?;
-        Self::search_embedding(conn, &query_embedding, content_type, limit, threshold)
+        Self::search_embedding(
+            conn,
+            &query_embedding,
+            content_type,
+            scope,
+            limit,
+            threshold,
+        )
     }
 
     /// Search for similar content using an embedding vector
@@ -37,10 +61,11 @@ impl VectorSearch {
         conn: &Connection,
         query_embedding: &[f32],
         content_type: Option<ContentType>,
+        scope: &SearchScope,
         limit: usize,
         threshold: f32,
     ) -> Result<Vec<SearchResult>> {
-        let all_embeddings = VectorStore::get_all_embeddings(conn, content_type)?;
+        let all_embeddings = VectorStore::get_embeddings(conn, content_type, scope)?;
 
         let mut results: Vec<SearchResult> = all_embeddings
             .into_iter()
@@ -120,7 +145,10 @@ impl VectorSearch {
         let mut seen_ids = std::collections::HashSet::new();
         let mut deduped = Vec::new();
         for mut result in results {
-            let key = format!("{}:{}", result.record.content_type, result.record.content_id);
+            let key = format!(
+                "{}:{}",
+                result.record.content_type, result.record.content_id
+            );
             if seen_ids.insert(key) {
                 result.rank = deduped.len() + 1;
                 deduped.push(result);
@@ -130,6 +158,38 @@ impl VectorSearch {
         Ok(deduped.into_iter().take(limit).collect())
     }
 
+    /// Up to `limit` tasks, code files and docs each that are at least
+    /// `threshold` similar to an indexed task
+    ///
+    /// Returns None when the task (or the whole index) hasn't been indexed.
+    pub fn related(
+        conn: &Connection,
+        task_content_id: &str,
+        limit: usize,
+        threshold: f32,
+    ) -> Result<Option<RelatedContent>> {
+        if !VectorStore::has_index(conn)?
+            || VectorStore::get_content_hash(conn, ContentType::Task, task_content_id)?.is_none()
+        {
+            return Ok(None);
+        }
+        let similar = |content_type| {
+            Self::find_similar(
+                conn,
+                ContentType::Task,
+                task_content_id,
+                Some(vec![content_type]),
+                limit,
+                threshold,
+            )
+        };
+        Ok(Some(RelatedContent {
+            tasks: similar(ContentType::Task)?,
+            code: similar(ContentType::Code)?,
+            docs: similar(ContentType::Doc)?,
+        }))
+    }
+
     /// Calculate cosine similarity between two vectors
     pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
         if a.len() != b.len() || a.is_empty() {
@@ -217,4 +277,48 @@ mod tests {
         let sim = VectorSearch::cosine_similarity(&a, &b);
         assert_eq!(sim, 0.0);
     }
+
+    #[test]
+    fn test_related() -> Result<()> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(include_str!("../../migrations/008_add_vectors.sql"))?;
+        assert!(VectorSearch::related(&conn, "#1", 3, 0.5)?.is_none());
+
+        let along = |axis: usize| {
+            let mut v = vec![0.0; crate::vectors::EMBEDDING_DIM];
+            v[axis] = 1.0;
+            v[axis + 1] = 0.2;
+            v
+        };
+        for (content_type, content_id, axis) in [
+            (ContentType::Task, "#1", 0),
+            (ContentType::Task, "#2", 0),
+            (ContentType::Task, "#3", 5),
+            (ContentType::Code, "src/auth.rs", 0),
+            (ContentType::Doc, "docs/billing.md", 5),
+        ] {
+            VectorStore::store_embedding(
+                &conn,
+                content_type,
+                content_id,
+                0,
+                None,
+                "hash",
+                &along(axis),
+                None,
+            )?;
+        }
+
+        let related = VectorSearch::related(&conn, "#1", 3, 0.5)?.unwrap();
+        let ids = |results: &[SearchResult]| {
+            results
+                .iter()
+                .map(|r| r.record.content_id.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids(&related.tasks), ["#2"]);
+        assert_eq!(ids(&related.code), ["src/auth.rs"]);
+        assert!(related.docs.is_empty());
+        Ok(())
+    }
 }
@@ -3,11 +3,13 @@
 use anyhow::{Context, Result};
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use chrono::{DateTime, Utc};
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::io::Cursor;
+use std::path::Path;
 
-use super::EMBEDDING_DIM;
+use super::{EMBEDDING_DIM, EMBEDDING_MODEL};
 
 /// Content type for embeddings
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -66,6 +68,32 @@ pub struct VectorStats {
     pub index_duration_ms: Option<i64>,
 }
 
+/// Embeddings removed because the task or file they were built from is gone
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize)]
+pub struct PruneReport {
+    /// Rows for deleted or cancelled tasks
+    pub tasks: usize,
+    /// Rows for code and doc files that no longer exist
+    pub files: usize,
+}
+
+impl PruneReport {
+    pub fn total(&self) -> usize {
+        self.tasks + self.files
+    }
+}
+
+/// Limits a search to tasks in an epic and/or files under a path
+///
+/// An item is in scope when it falls within any scope that is set: a task
+/// whose epic matches (case-insensitively), or a code or doc file whose path
+/// starts with the prefix. With neither set everything is in scope.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchScope {
+    pub epic: Option<String>,
+    pub path_prefix: Option<String>,
+}
+
 /// Vector store operations
 pub struct VectorStore;
 
@@ -146,6 +174,176 @@ impl VectorStore {
         Ok(deleted)
     }
 
+    /// Stored content hash of every indexed item of a type
+    pub fn content_hashes(
+        conn: &Connection,
+        content_type: ContentType,
+    ) -> Result<HashMap<String, String>> {
+        let mut stmt = conn.prepare(
+            "SELECT content_id, MIN(content_hash) FROM embeddings
+             WHERE content_type = ?1 GROUP BY content_id",
+        )?;
+        let hashes = stmt
+            .query_map(params![content_type.as_str()], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?
+            .collect::<Result<_, _>>()?;
+        Ok(hashes)
+    }
+
+    /// Look up a cached embedding of text with this hash, marking it used
+    pub fn cached_embedding(conn: &Connection, text_hash: &str) -> Result<Option<Vec<f32>>> {
+        let blob: Option<Vec<u8>> = conn
+            .query_row(
+                "UPDATE embedding_cache SET last_used_at = ?3
+                 WHERE model = ?1 AND text_hash = ?2 RETURNING embedding",
+                params![EMBEDDING_MODEL, text_hash, Utc::now().to_rfc3339()],
+                |row| row.get(0),
+            )
+            .optional()?;
+        match blob {
+            Some(blob) => {
+                let embedding = Self::decode_embedding(&blob)?;
+                Ok((embedding.len() == EMBEDDING_DIM).then_some(embedding))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Cache the embedding of text with this hash
+    pub fn cache_embedding(conn: &Connection, text_hash: &str, embedding: &[f32]) -> Result<()> {
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO embedding_cache (model, text_hash, embedding, created_at, last_used_at)
+             VALUES (?1, ?2, ?3, ?4, ?4)
+             ON CONFLICT(model, text_hash) DO UPDATE SET
+                 embedding = excluded.embedding,
+                 last_used_at = excluded.last_used_at",
+            params![
+                EMBEDDING_MODEL,
+                text_hash,
+                Self::encode_embedding(embedding)?,
+                now
+            ],
+        )
+        .context("Failed to cache embedding")?;
+        Ok(())
+    }
+
+    /// Number of cached embeddings and the bytes they take
+    pub fn cache_size(conn: &Connection) -> Result<(usize, i64)> {
+        let (entries, bytes): (i64, Option<i64>) = conn.query_row(
+            "SELECT COUNT(*), SUM(length(embedding) + length(text_hash) + length(model))
+             FROM embedding_cache",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        Ok((entries as usize, bytes.unwrap_or(0)))
+    }
+
+    /// Empty the embedding cache, returning how many entries were removed
+    pub fn clear_cache(conn: &Connection) -> Result<usize> {
+        let deleted = conn
+            .execute("DELETE FROM embedding_cache", [])
+            .context("Failed to clear embedding cache")?;
+        Ok(deleted)
+    }
+
+    /// Whether the database, or its attached vector database, has a vector
+    /// index (`prd vector` creates it on first use)
+    pub fn has_index(conn: &Connection) -> Result<bool> {
+        let exists = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM pragma_table_list WHERE name = 'embeddings')",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(exists)
+    }
+
+    /// Delete a task's embeddings, indexed under its display ID or UUID
+    pub fn delete_task_embeddings(
+        conn: &Connection,
+        task_id: &str,
+        display_id: Option<i32>,
+    ) -> Result<usize> {
+        if !Self::has_index(conn)? {
+            return Ok(0);
+        }
+        let display = display_id.map(|id| format!("#{}", id));
+        let deleted = conn
+            .execute(
+                "DELETE FROM embeddings WHERE content_type = 'task' AND content_id IN (?1, ?2)",
+                params![task_id, display],
+            )
+            .context("Failed to delete task embeddings")?;
+        Ok(deleted)
+    }
+
+    /// Delete embeddings of code and doc files that no longer exist,
+    /// optionally only those under `under`
+    ///
+    /// Paths are stored as they were given to the indexer, so relative ones
+    /// are checked against the working directory.
+    pub fn prune_missing_files(conn: &Connection, under: Option<&Path>) -> Result<usize> {
+        if !Self::has_index(conn)? {
+            return Ok(0);
+        }
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT content_type, content_id FROM embeddings
+             WHERE content_type IN ('code', 'doc')",
+        )?;
+        let files = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut deleted = 0;
+        for (content_type, content_id) in files {
+            let path = Path::new(&content_id);
+            if under.is_some_and(|dir| !path.starts_with(dir)) || path.exists() {
+                continue;
+            }
+            deleted += conn.execute(
+                "DELETE FROM embeddings WHERE content_type = ?1 AND content_id = ?2",
+                params![content_type, content_id],
+            )?;
+        }
+        Ok(deleted)
+    }
+
+    /// Delete embeddings whose task was deleted or cancelled, or whose file
+    /// no longer exists
+    pub fn prune_orphans(conn: &Connection) -> Result<PruneReport> {
+        if !Self::has_index(conn)? {
+            return Ok(PruneReport::default());
+        }
+        Ok(PruneReport {
+            tasks: Self::prune_missing_tasks(conn)?,
+            files: Self::prune_missing_files(conn, None)?,
+        })
+    }
+
+    /// Delete embeddings of tasks that were deleted or cancelled
+    pub fn prune_missing_tasks(conn: &Connection) -> Result<usize> {
+        if !Self::has_index(conn)? {
+            return Ok(0);
+        }
+        let deleted = conn
+            .execute(
+                "DELETE FROM embeddings WHERE content_type = 'task' AND content_id NOT IN (
+                     SELECT '#' || display_id FROM tasks
+                     WHERE display_id IS NOT NULL AND status != 'cancelled'
+                       AND deleted_at IS NULL
+                     UNION ALL SELECT id FROM tasks
+                     WHERE status != 'cancelled' AND deleted_at IS NULL
+                 )",
+                [],
+            )
+            .context("Failed to prune task embeddings")?;
+        Ok(deleted)
+    }
+
     /// Get content hash for a content item (to check if re-indexing needed)
     pub fn get_content_hash(
         conn: &Connection,
@@ -167,19 +365,67 @@ impl VectorStore {
         conn: &Connection,
         content_type: Option<ContentType>,
     ) -> Result<Vec<(EmbeddingRecord, Vec<f32>)>> {
-        let query = match content_type {
-            Some(ct) => format!(
-                "SELECT id, content_type, content_id, chunk_index, content_preview, content_hash, embedding, metadata, created_at, updated_at
-                 FROM embeddings WHERE content_type = '{}'
-                 ORDER BY content_id, chunk_index",
-                ct.as_str()
-            ),
-            None => "SELECT id, content_type, content_id, chunk_index, content_preview, content_hash, embedding, metadata, created_at, updated_at
-                     FROM embeddings ORDER BY content_type, content_id, chunk_index".to_string(),
-        };
+        Self::get_embeddings(conn, content_type, &SearchScope::default())
+    }
+
+    /// Get the embeddings of a type within a scope
+    ///
+    /// The epic is matched through the task ID in each task chunk's metadata;
+    /// paths are compared without a leading "./".
+    pub fn get_embeddings(
+        conn: &Connection,
+        content_type: Option<ContentType>,
+        scope: &SearchScope,
+    ) -> Result<Vec<(EmbeddingRecord, Vec<f32>)>> {
+        let mut conditions = Vec::new();
+        let mut values: Vec<rusqlite::types::Value> = Vec::new();
+        if let Some(ct) = content_type {
+            values.push(ct.as_str().to_string().into());
+            conditions.push(format!("content_type = ?{}", values.len()));
+        }
+
+        let mut scopes = Vec::new();
+        if let Some(epic) = &scope.epic {
+            values.push(epic.clone().into());
+            scopes.push(format!(
+                "(content_type = 'task' AND json_extract(metadata, '$.task_id') IN (
+                     SELECT id FROM tasks WHERE lower(epic_name) = lower(?{})))",
+                values.len()
+            ));
+        }
+        if let Some(prefix) = &scope.path_prefix {
+            values.push(
+                prefix
+                    .strip_prefix("./")
+                    .unwrap_or(prefix)
+                    .to_string()
+                    .into(),
+            );
+            scopes.push(format!(
+                "(content_type IN ('code', 'doc') AND substr(
+                     CASE WHEN content_id LIKE './%' THEN substr(content_id, 3) ELSE content_id END,
+                     1, length(?{n})) = ?{n})",
+                n = values.len()
+            ));
+        }
+        if !scopes.is_empty() {
+            conditions.push(format!("({})", scopes.join(" OR ")));
+        }
+
+        let query = format!(
+            "SELECT id, content_type, content_id, chunk_index, content_preview, content_hash, embedding, metadata, created_at, updated_at
+             FROM embeddings{}
+             ORDER BY content_type, content_id, chunk_index",
+            if conditions.is_empty() {
+                String::new()
+            } else {
+                format!(" WHERE {}", conditions.join(" AND "))
+            }
+        );
 
         let mut stmt = conn.prepare(&query)?;
-        let rows = stmt.query_map([], |row| {
+        let params = rusqlite::params_from_iter(values);
+        let rows = stmt.query_map(params, |row| {
             let ct_str: String = row.get(1)?;
             let embedding_blob: Vec<u8> = row.get(6)?;
             let created_str: String = row.get(8)?;
@@ -272,7 +518,7 @@ impl VectorStore {
     }
 
     /// Encode embedding as binary blob
-    fn encode_embedding(embedding: &[f32]) -> Result<Vec<u8>> {
+    pub(crate) fn encode_embedding(embedding: &[f32]) -> Result<Vec<u8>> {
         let mut buf = Vec::with_capacity(embedding.len() * 4);
         for &val in embedding {
             buf.write_f32::<LittleEndian>(val)?;
@@ -281,7 +527,7 @@ impl VectorStore {
     }
 
     /// Decode embedding from binary blob
-    fn decode_embedding(blob: &[u8]) -> Result<Vec<f32>> {
+    pub(crate) fn decode_embedding(blob: &[u8]) -> Result<Vec<f32>> {
         let mut cursor = Cursor::new(blob);
         let mut embedding = Vec::with_capacity(blob.len() / 4);
         while cursor.position() < blob.len() as u64 {
@@ -299,6 +545,8 @@ mod tests {
         let conn = Connection::open(":memory:").unwrap();
         conn.execute_batch(include_str!("../../migrations/008_add_vectors.sql"))
             .unwrap();
+        conn.execute_batch(include_str!("../../migrations/019_add_embedding_cache.sql"))
+            .unwrap();
         conn
     }
 
@@ -358,4 +606,137 @@ mod tests {
         let hash = VectorStore::get_content_hash(&conn, ContentType::Task, "task-1").unwrap();
         assert!(hash.is_none());
     }
+
+    #[test]
+    fn test_prune_orphans() -> Result<()> {
+        let conn = setup_test_db();
+        conn.execute_batch(
+            "CREATE TABLE tasks (id TEXT PRIMARY KEY, display_id INTEGER, status TEXT,
+                                 deleted_at TEXT);
+             INSERT INTO tasks VALUES ('uuid-1', 1, 'pending', NULL),
+                                      ('uuid-2', 2, 'cancelled', NULL);",
+        )?;
+        let embedding: Vec<f32> = vec![0.0; EMBEDDING_DIM];
+        let dir = tempfile::tempdir()?;
+        let kept_file = dir.path().join("kept.md");
+        std::fs::write(&kept_file, "kept")?;
+        let gone_file = dir.path().join("gone.md");
+        for (content_type, content_id) in [
+            (ContentType::Task, "#1".to_string()),
+            (ContentType::Task, "#2".to_string()),
+            (ContentType::Task, "#3".to_string()),
+            (ContentType::Doc, kept_file.to_string_lossy().to_string()),
+            (ContentType::Doc, gone_file.to_string_lossy().to_string()),
+        ] {
+            VectorStore::store_embedding(
+                &conn,
+                content_type,
+                &content_id,
+                0,
+                None,
+                "hash",
+                &embedding,
+                None,
+            )?;
+        }
+
+        let report = VectorStore::prune_orphans(&conn)?;
+        assert_eq!(report, PruneReport { tasks: 2, files: 1 });
+        let remaining: i64 =
+            conn.query_row("SELECT COUNT(*) FROM embeddings", [], |row| row.get(0))?;
+        assert_eq!(remaining, 2);
+
+        assert_eq!(
+            VectorStore::delete_task_embeddings(&conn, "uuid-1", Some(1))?,
+            1
+        );
+        assert!(!VectorStore::has_index(&Connection::open_in_memory()?)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_scoped_embeddings() -> Result<()> {
+        let conn = setup_test_db();
+        conn.execute_batch(
+            "CREATE TABLE tasks (id TEXT PRIMARY KEY, epic_name TEXT);
+             INSERT INTO tasks VALUES ('uuid-1', 'Migration'), ('uuid-2', 'UI');",
+        )?;
+        let embedding: Vec<f32> = vec![0.0; EMBEDDING_DIM];
+        for (content_type, content_id, metadata) in [
+            (
+                ContentType::Task,
+                "#1",
+                Some(r#"{"task_id":"uuid-1","display_id":1}"#),
+            ),
+            (
+                ContentType::Task,
+                "#2",
+                Some(r#"{"task_id":"uuid-2","display_id":2}"#),
+            ),
+            (ContentType::Code, "./backend/db.rs", None),
+            (ContentType::Code, "frontend/app.ts", None),
+            (ContentType::Doc, "./backend/README.md", None),
+        ] {
+            VectorStore::store_embedding(
+                &conn,
+                content_type,
+                content_id,
+                0,
+                None,
+                "hash",
+                &embedding,
+                metadata,
+            )?;
+        }
+
+        let ids = |content_type, epic: Option<&str>, path_prefix: Option<&str>| {
+            let scope = SearchScope {
+                epic: epic.map(str::to_string),
+                path_prefix: path_prefix.map(str::to_string),
+            };
+            VectorStore::get_embeddings(&conn, content_type, &scope).map(|records| {
+                records
+                    .into_iter()
+                    .map(|(record, _)| record.content_id)
+                    .collect::<Vec<_>>()
+            })
+        };
+        assert_eq!(ids(None, None, None)?.len(), 5);
+        assert_eq!(ids(None, Some("migration"), None)?, ["#1"]);
+        assert_eq!(
+            ids(None, None, Some("./backend/"))?,
+            ["./backend/db.rs", "./backend/README.md"]
+        );
+        assert_eq!(
+            ids(Some(ContentType::Code), Some("UI"), Some("frontend"))?,
+            ["frontend/app.ts"]
+        );
+        assert_eq!(
+            ids(None, Some("UI"), Some("backend"))?,
+            ["./backend/db.rs", "./backend/README.md", "#2"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_embedding_cache() -> Result<()> {
+        let conn = setup_test_db();
+        let embedding: Vec<f32> = (0..EMBEDDING_DIM).map(|i| i as f32 * 0.001).collect();
+        assert!(VectorStore::cached_embedding(&conn, "hash")?.is_none());
+
+        VectorStore::cache_embedding(&conn, "hash", &embedding)?;
+        VectorStore::cache_embedding(&conn, "hash", &embedding)?;
+        assert_eq!(
+            VectorStore::cached_embedding(&conn, "hash")?,
+            Some(embedding)
+        );
+        assert_eq!(VectorStore::cache_size(&conn)?.0, 1);
+
+        // Clearing the index keeps the cache
+        VectorStore::delete_all_by_type(&conn, ContentType::Code)?;
+        assert_eq!(VectorStore::cache_size(&conn)?.0, 1);
+        assert_eq!(VectorStore::clear_cache(&conn)?, 1);
+        assert!(VectorStore::cached_embedding(&conn, "hash")?.is_none());
+        Ok(())
+    }
 }
@@ -0,0 +1,297 @@
+use crate::db::{Database, StatusBucket, Task};
+use crate::vectors::{ContentType, VectorStore};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Which derived data a discrepancy is in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DerivedData {
+    AgentMetrics,
+    CompletedAt,
+    ParentRollup,
+    VectorStats,
+}
+
+impl DerivedData {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DerivedData::AgentMetrics => "agent metrics",
+            DerivedData::CompletedAt => "completed_at",
+            DerivedData::ParentRollup => "parent rollup",
+            DerivedData::VectorStats => "vector stats",
+        }
+    }
+}
+
+/// Stored derived data that disagrees with its source tables
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Discrepancy {
+    pub data: DerivedData,
+    /// Agent, task or content type the value belongs to
+    pub subject: String,
+    pub found: String,
+    pub expected: String,
+    /// Whether the stored value was corrected
+    pub repaired: bool,
+}
+
+/// Everything [`verify`] found
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct VerifyReport {
+    pub discrepancies: Vec<Discrepancy>,
+}
+
+impl VerifyReport {
+    pub fn is_clean(&self) -> bool {
+        self.discrepancies.is_empty()
+    }
+
+    pub fn repaired(&self) -> usize {
+        self.discrepancies.iter().filter(|d| d.repaired).count()
+    }
+}
+
+/// Recompute derived data from the source-of-truth tables, repairing what
+/// disagrees unless `dry_run` is set
+///
+/// Agent metrics, `completed_at` and vector index counters are rewritten.
+/// Parent rollups are computed when read, so parents whose subtasks
+/// contradict their status (all done while the parent is open, or open
+/// under a done parent) are only reported.
+pub fn verify(db: &Database, dry_run: bool) -> Result<VerifyReport> {
+    let repair = !dry_run;
+    db.write_transaction(|| {
+        let mut report = VerifyReport::default();
+        check_agent_metrics(db, repair, &mut report)?;
+        check_completed_at(db, repair, &mut report)?;
+        check_parent_rollups(db, &mut report)?;
+        check_vector_stats(db, repair, &mut report)?;
+        Ok(report)
+    })
+}
+
+fn check_agent_metrics(db: &Database, repair: bool, report: &mut VerifyReport) -> Result<()> {
+    let describe = |m: &crate::db::AgentMetrics| {
+        format!(
+            "{} tasks, {} completed, {} failed, {:.2}h avg",
+            m.total_tasks, m.completed_tasks, m.failed_tasks, m.avg_completion_time_hours
+        )
+    };
+    for agent in db.list_agents()? {
+        let stored = db.get_agent_metrics(&agent.id)?;
+        let expected = db.compute_agent_metrics(&agent.id)?;
+        let same = stored.total_tasks == expected.total_tasks
+            && stored.completed_tasks == expected.completed_tasks
+            && stored.failed_tasks == expected.failed_tasks
+            && (stored.avg_completion_time_hours - expected.avg_completion_time_hours).abs() < 0.01;
+        if same {
+            continue;
+        }
+        if repair {
+            db.update_agent_metrics(&agent.id)?;
+        }
+        report.discrepancies.push(Discrepancy {
+            data: DerivedData::AgentMetrics,
+            subject: agent.name.clone(),
+            found: describe(&stored),
+            expected: describe(&expected),
+            repaired: repair,
+        });
+    }
+    Ok(())
+}
+
+/// Completed tasks need a completion time and other tasks must not have
+/// one; a missing time is taken from the task's last update
+fn check_completed_at(db: &Database, repair: bool, report: &mut VerifyReport) -> Result<()> {
+    let workflow = db.workflow()?;
+    let show = |at: Option<DateTime<Utc>>| at.map_or("none".to_string(), |at| at.to_rfc3339());
+    for task in db.list_tasks(None)? {
+        let completed = workflow.is_completed(&task.status);
+        let fix = match (completed, task.completed_at) {
+            (true, None) => Some(Some(task.updated_at)),
+            (false, Some(_)) => Some(None),
+            _ => None,
+        };
+        let Some(completed_at) = fix else {
+            continue;
+        };
+        if repair {
+            db.get_connection().execute(
+                "UPDATE tasks SET completed_at = ?1 WHERE id = ?2",
+                params![completed_at.map(|at| at.to_rfc3339()), task.id],
+            )?;
+            db.log_task_action(
+                &task.id,
+                None,
+                "repaired",
+                Some(&format!("completed_at set to {}", show(completed_at))),
+            )?;
+        }
+        report.discrepancies.push(Discrepancy {
+            data: DerivedData::CompletedAt,
+            subject: label(&task),
+            found: show(task.completed_at),
+            expected: show(completed_at),
+            repaired: repair,
+        });
+    }
+    Ok(())
+}
+
+fn check_parent_rollups(db: &Database, report: &mut VerifyReport) -> Result<()> {
+    let workflow = db.workflow()?;
+    let tasks = db.list_tasks(None)?;
+    let mut children: HashMap<&str, Vec<&Task>> = HashMap::new();
+    for task in &tasks {
+        if let Some(parent) = &task.parent_id {
+            children.entry(parent.as_str()).or_default().push(task);
+        }
+    }
+    for parent in &tasks {
+        let Some(subtasks) = children.get(parent.id.as_str()) else {
+            continue;
+        };
+        let done = subtasks
+            .iter()
+            .filter(|t| workflow.bucket(&t.status) == StatusBucket::Done)
+            .count();
+        let parent_done = workflow.bucket(&parent.status) == StatusBucket::Done;
+        let expected = if done == subtasks.len() && !parent_done {
+            "done, like all its subtasks"
+        } else if done < subtasks.len() && parent_done {
+            "open until its subtasks are done"
+        } else {
+            continue;
+        };
+        report.discrepancies.push(Discrepancy {
+            data: DerivedData::ParentRollup,
+            subject: label(parent),
+            found: format!(
+                "{} with {}/{} subtasks done",
+                parent.status.as_str(),
+                done,
+                subtasks.len()
+            ),
+            expected: expected.to_string(),
+            repaired: false,
+        });
+    }
+    Ok(())
+}
+
+/// Item and chunk counters of each content type against the stored
+/// embeddings
+fn check_vector_stats(db: &Database, repair: bool, report: &mut VerifyReport) -> Result<()> {
+    let conn = db.get_connection();
+    if !VectorStore::has_index(conn)? {
+        return Ok(());
+    }
+    for content_type in [ContentType::Task, ContentType::Code, ContentType::Doc] {
+        let (items, chunks): (i64, i64) = conn.query_row(
+            "SELECT COUNT(DISTINCT content_id), COUNT(*) FROM embeddings WHERE content_type = ?1",
+            params![content_type.as_str()],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        let stored: Option<(i64, i64)> = conn
+            .query_row(
+                "SELECT total_items, total_chunks FROM vector_stats WHERE content_type = ?1",
+                params![content_type.as_str()],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        let found = stored.unwrap_or((0, 0));
+        if found == (items, chunks) {
+            continue;
+        }
+        if repair {
+            conn.execute(
+                "INSERT INTO vector_stats (content_type, total_items, total_chunks)
+                 VALUES (?1, ?2, ?3)
+                 ON CONFLICT(content_type)
+                 DO UPDATE SET total_items = excluded.total_items,
+                               total_chunks = excluded.total_chunks",
+                params![content_type.as_str(), items, chunks],
+            )?;
+        }
+        report.discrepancies.push(Discrepancy {
+            data: DerivedData::VectorStats,
+            subject: content_type.as_str().to_string(),
+            found: format!("{} items, {} chunks", found.0, found.1),
+            expected: format!("{} items, {} chunks", items, chunks),
+            repaired: repair,
+        });
+    }
+    Ok(())
+}
+
+fn label(task: &Task) -> String {
+    task.display_id
+        .map(|id| format!("#{}", id))
+        .unwrap_or_else(|| task.id.chars().take(8).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Priority, TaskStatus};
+
+    #[test]
+    fn test_verify_repairs_derived_data() -> Result<()> {
+        let db = Database::new(":memory:")?;
+        let agent = db.create_agent("worker".to_string())?;
+        let parent = db.create_task("Parent".to_string(), None, Priority::Low, None, None)?;
+        let child = db.create_task(
+            "Child".to_string(),
+            None,
+            Priority::Low,
+            Some(parent.id.clone()),
+            None,
+        )?;
+        db.assign_task(&child.id, &agent.id)?;
+        db.update_task_status(&child.id, TaskStatus::Completed, None)?;
+        db.update_agent_metrics(&agent.id)?;
+        assert!(verify(&db, false)?
+            .discrepancies
+            .iter()
+            .all(|d| d.data == DerivedData::ParentRollup));
+
+        // Manual edits behind the tool's back
+        db.get_connection().execute_batch(&format!(
+            "UPDATE agent_metrics SET completed_tasks = 7;
+             UPDATE tasks SET completed_at = NULL WHERE id = '{}';
+             UPDATE tasks SET completed_at = '2026-10-01T00:00:00+00:00' WHERE id = '{}';",
+            child.id, parent.id
+        ))?;
+
+        let preview = verify(&db, true)?;
+        let kinds: Vec<DerivedData> = preview.discrepancies.iter().map(|d| d.data).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                DerivedData::AgentMetrics,
+                DerivedData::CompletedAt,
+                DerivedData::CompletedAt,
+                DerivedData::ParentRollup,
+            ]
+        );
+        assert_eq!(preview.repaired(), 0);
+        assert_eq!(db.get_agent_metrics(&agent.id)?.completed_tasks, 7);
+
+        let fixed = verify(&db, false)?;
+        assert_eq!(fixed.repaired(), 3);
+        assert_eq!(db.get_agent_metrics(&agent.id)?.completed_tasks, 1);
+        assert!(db.get_task(&child.id)?.unwrap().completed_at.is_some());
+        assert!(db.get_task(&parent.id)?.unwrap().completed_at.is_none());
+
+        // Only the unfixable rollup note remains
+        let again = verify(&db, false)?;
+        assert_eq!(again.discrepancies.len(), 1);
+        assert_eq!(again.discrepancies[0].data, DerivedData::ParentRollup);
+        Ok(())
+    }
+}
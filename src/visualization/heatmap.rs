@@ -0,0 +1,212 @@
+use crate::db::Database;
+use anyhow::Result;
+use chrono::{Datelike, Duration, NaiveDate, Utc};
+use colored::Colorize;
+use std::collections::HashMap;
+
+/// Row labels, Monday first; only every other row is labelled
+const WEEKDAY_LABELS: [&str; 7] = ["Mon", "", "Wed", "", "Fri", "", "Sun"];
+
+/// GitHub's contribution palette, lightest first
+const LEVEL_COLORS: [(u8, u8, u8); 4] =
+    [(155, 233, 168), (64, 196, 99), (48, 161, 78), (33, 110, 57)];
+
+/// Renders a GitHub-style calendar of tasks completed per day
+pub struct HeatmapRenderer {
+    db: Database,
+}
+
+impl HeatmapRenderer {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Render a calendar year, or the last 52 weeks when `year` is `None`
+    pub fn render(&self, agent_id: Option<&str>, year: Option<i32>) -> Result<String> {
+        let (start, end) = match year {
+            Some(year) => (
+                NaiveDate::from_ymd_opt(year, 1, 1)
+                    .ok_or_else(|| anyhow::anyhow!("Invalid year: {}", year))?,
+                NaiveDate::from_ymd_opt(year, 12, 31)
+                    .ok_or_else(|| anyhow::anyhow!("Invalid year: {}", year))?,
+            ),
+            None => {
+                let today = Utc::now().date_naive();
+                (today - Duration::weeks(52) + Duration::days(1), today)
+            }
+        };
+
+        let counts: HashMap<NaiveDate, usize> = self
+            .db
+            .completions_per_day(agent_id)?
+            .into_iter()
+            .filter(|(day, _)| *day >= start && *day <= end)
+            .collect();
+
+        let title = match year {
+            Some(year) => format!("Completions in {}", year),
+            None => "Completions in the last year".to_string(),
+        };
+        let mut output = format!("{}\n\n", title.bold().cyan());
+        output.push_str(&render_grid(&counts, start, end));
+        output.push('\n');
+        output.push_str(&render_summary(&counts, start, end));
+        Ok(output)
+    }
+}
+
+/// Calendar grid: one column per week, one row per weekday
+fn render_grid(counts: &HashMap<NaiveDate, usize>, start: NaiveDate, end: NaiveDate) -> String {
+    let max = counts.values().copied().max().unwrap_or(0);
+    let first_monday = start - Duration::days(start.weekday().num_days_from_monday() as i64);
+    let weeks = ((end - first_monday).num_days() / 7 + 1) as usize;
+
+    // Month labels above the week in which each month starts
+    let mut months = vec![' '; weeks + 3];
+    let mut last_month = 0;
+    let mut next_free = 0;
+    for week in 0..weeks {
+        let monday = first_monday + Duration::weeks(week as i64);
+        let day = monday.max(start);
+        if day.month() != last_month && week >= next_free {
+            last_month = day.month();
+            next_free = week + 4;
+            for (i, c) in day.format("%b").to_string().chars().enumerate() {
+                months[week + i] = c;
+            }
+        }
+    }
+    let mut output = format!("    {}\n", months.iter().collect::<String>().trim_end());
+
+    for (weekday, label) in WEEKDAY_LABELS.iter().enumerate() {
+        output.push_str(&format!("{:<4}", label));
+        for week in 0..weeks {
+            let day = first_monday + Duration::days((week * 7 + weekday) as i64);
+            if day < start || day > end {
+                output.push(' ');
+                continue;
+            }
+            let cell = match level(counts.get(&day).copied().unwrap_or(0), max) {
+                0 => "■".bright_black(),
+                n => {
+                    let (r, g, b) = LEVEL_COLORS[n - 1];
+                    "■".truecolor(r, g, b)
+                }
+            };
+            output.push_str(&cell.to_string());
+        }
+        output.push('\n');
+    }
+
+    output.push_str(&format!("\n    Less {} ", "■".bright_black()));
+    for (r, g, b) in LEVEL_COLORS {
+        output.push_str(&format!("{} ", "■".truecolor(r, g, b)));
+    }
+    output.push_str("More\n");
+    output
+}
+
+/// Totals and streaks for the displayed range
+fn render_summary(counts: &HashMap<NaiveDate, usize>, start: NaiveDate, end: NaiveDate) -> String {
+    let total: usize = counts.values().sum();
+    let active_days = counts.len();
+
+    let mut longest = 0;
+    // Consecutive active days up to `end`
+    let mut current = 0;
+    let mut day = start;
+    while day <= end {
+        if counts.contains_key(&day) {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+        day += Duration::days(1);
+    }
+
+    let mut output = format!(
+        "{} completed • {} active • longest streak {}",
+        plural(total, "task"),
+        plural(active_days, "day"),
+        plural(longest, "day")
+    );
+    if end == Utc::now().date_naive() {
+        output.push_str(&format!(" • current streak {}", plural(current, "day")));
+    }
+    output.push('\n');
+    if let Some((day, count)) = counts.iter().max_by_key(|(day, count)| (**count, *day)) {
+        output.push_str(&format!(
+            "Busiest day: {} ({})\n",
+            day.format("%a %Y-%m-%d"),
+            plural(*count, "task")
+        ));
+    }
+    output
+}
+
+/// "1 day", "3 days"
+fn plural(n: usize, noun: &str) -> String {
+    if n == 1 {
+        format!("1 {}", noun)
+    } else {
+        format!("{} {}s", n, noun)
+    }
+}
+
+/// Color level 0-4 for a day's count relative to the busiest day
+fn level(count: usize, max: usize) -> usize {
+    if count == 0 || max == 0 {
+        return 0;
+    }
+    count.saturating_mul(LEVEL_COLORS.len()).div_ceil(max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::TaskStatus;
+    use crate::testing::{Fixture, TaskFixture};
+
+    #[test]
+    fn test_level_scale() {
+        assert_eq!(level(0, 10), 0);
+        assert_eq!(level(1, 10), 1);
+        assert_eq!(level(5, 10), 2);
+        assert_eq!(level(10, 10), 4);
+        assert_eq!(level(1, 1), 4);
+    }
+
+    #[test]
+    fn test_heatmap_counts_completions_per_day() -> Result<()> {
+        let days = [
+            "2025-03-03T10:00:00+00:00",
+            "2025-03-03T15:00:00+00:00",
+            "2025-03-04T09:00:00+00:00",
+            "2024-12-31T09:00:00+00:00",
+        ];
+        let mut fixture = Fixture::new().agent("agent-1");
+        for (i, completed_at) in days.iter().enumerate() {
+            let mut task = TaskFixture::new(&format!("Task {}", i))
+                .status(TaskStatus::Completed)
+                .completed_at(completed_at.parse()?);
+            if i < 2 {
+                task = task.agent("agent-1");
+            }
+            fixture = fixture.task(task);
+        }
+        let seeded = fixture.build()?;
+        let agent = seeded.agent("agent-1").clone();
+
+        let renderer = HeatmapRenderer::new(seeded.db);
+        let output = renderer.render(None, Some(2025))?;
+        assert!(output.contains("3 tasks completed • 2 days active"));
+        assert!(output.contains("longest streak 2 days\n"));
+        assert!(output.contains("Busiest day: Mon 2025-03-03 (2 tasks)"));
+        assert!(output.contains("Jan"));
+
+        let output = renderer.render(Some(&agent.id), Some(2025))?;
+        assert!(output.contains("2 tasks completed • 1 day active"));
+        Ok(())
+    }
+}
@@ -1,3 +1,7 @@
+pub mod heatmap;
 pub mod timeline;
+pub mod tree;
 
+pub use heatmap::HeatmapRenderer;
 pub use timeline::TimelineRenderer;
+pub use tree::{TaskForest, TaskTreeRenderer, TreeNode};
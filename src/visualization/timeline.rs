@@ -1,4 +1,4 @@
-use crate::db::{Database, Sprint, Task, TaskStatus};
+use crate::db::{Database, Sprint, Task};
 use anyhow::Result;
 use chrono::{Datelike, Duration, NaiveDate, Utc};
 use colored::Colorize;
@@ -143,10 +143,11 @@ impl TimelineRenderer {
         }
 
         // Estimate completion
+        let workflow = self.db.workflow()?;
         let all_tasks = self.db.get_all_tasks()?;
         let completed_count = all_tasks
             .iter()
-            .filter(|t| t.status == TaskStatus::Completed)
+            .filter(|t| workflow.is_completed(&t.status))
             .count();
         let remaining = all_tasks.len() - completed_count;
 
@@ -240,6 +241,7 @@ impl TimelineRenderer {
     }
 
     fn sprints_with_info(&self, sprints: Vec<Sprint>) -> Result<Vec<SprintInfo>> {
+        let workflow = self.db.workflow()?;
         let mut sprint_infos = Vec::new();
 
         for sprint in sprints {
@@ -247,7 +249,7 @@ impl TimelineRenderer {
 
             let completed_tasks = tasks
                 .iter()
-                .filter(|t| t.status == TaskStatus::Completed)
+                .filter(|t| workflow.is_completed(&t.status))
                 .count() as i32;
             let total_tasks = tasks.len() as i32;
 
@@ -286,13 +288,14 @@ impl TimelineRenderer {
     }
 
     fn infer_sprints_from_tasks(&self) -> Result<Vec<SprintInfo>> {
+        let workflow = self.db.workflow()?;
         let tasks = self.db.get_all_tasks()?;
 
         // Group by week
         let mut weeks: HashMap<String, Vec<Task>> = HashMap::new();
 
         for task in tasks {
-            if task.status == TaskStatus::Completed {
+            if workflow.is_completed(&task.status) {
                 if let Some(completed_at) = task.completed_at {
                     let date = completed_at.date_naive();
                     let week_start =
@@ -317,7 +320,7 @@ impl TimelineRenderer {
 
                 let completed = tasks
                     .iter()
-                    .filter(|t| t.status == TaskStatus::Completed)
+                    .filter(|t| workflow.is_completed(&t.status))
                     .count() as i32;
 
                 let mut agent_tasks: HashMap<String, i32> = HashMap::new();
@@ -356,13 +359,14 @@ impl TimelineRenderer {
     }
 
     fn get_daily_snapshots(&self) -> Result<Vec<ProgressSnapshot>> {
+        let workflow = self.db.workflow()?;
         let tasks = self.db.get_all_tasks()?;
 
         let mut date_map: HashMap<String, usize> = HashMap::new();
 
         // Count completed tasks per day
         for task in &tasks {
-            if task.status == TaskStatus::Completed {
+            if workflow.is_completed(&task.status) {
                 if let Some(completed_at) = task.completed_at {
                     let date = completed_at.date_naive();
                     let date_str = date.format("%Y-%m-%d").to_string();
@@ -426,7 +430,7 @@ struct ProgressSnapshot {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::db::Priority;
+    use crate::db::{Priority, TaskStatus};
 
     #[test]
     fn test_timeline_renderer_empty() -> Result<()> {
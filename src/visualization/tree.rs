@@ -0,0 +1,373 @@
+use crate::db::{Database, Task, TaskStatus, Workflow};
+use anyhow::Result;
+use colored::Colorize;
+use std::collections::{HashMap, HashSet};
+
+/// A task placed within its parent/subtask hierarchy
+#[derive(Debug)]
+pub struct TreeNode<'a> {
+    pub task: &'a Task,
+    /// 0 for roots
+    pub depth: usize,
+    /// Box-drawing connectors to print before the task
+    pub prefix: String,
+    /// (completed, total) descendants, including any beyond the depth limit
+    pub subtotal: (usize, usize),
+}
+
+/// Parent/subtask structure of a set of tasks
+pub struct TaskForest<'a> {
+    tasks: &'a [Task],
+    children: HashMap<&'a str, Vec<&'a Task>>,
+    workflow: Workflow,
+}
+
+impl<'a> TaskForest<'a> {
+    pub fn new(tasks: &'a [Task]) -> Self {
+        let mut children: HashMap<&str, Vec<&Task>> = HashMap::new();
+        for task in tasks {
+            if let Some(parent) = &task.parent_id {
+                children.entry(parent.as_str()).or_default().push(task);
+            }
+        }
+        for siblings in children.values_mut() {
+            siblings.sort_by_key(|t| (t.created_at, t.display_id));
+        }
+        Self {
+            tasks,
+            children,
+            workflow: Workflow::default(),
+        }
+    }
+
+    /// Count subtasks in `workflow`'s custom done statuses as completed
+    pub fn with_workflow(mut self, workflow: Workflow) -> Self {
+        self.workflow = workflow;
+        self
+    }
+
+    /// Tasks without a parent, or whose parent is not in the set
+    pub fn roots(&self) -> Vec<&'a Task> {
+        let ids: HashSet<&str> = self.tasks.iter().map(|t| t.id.as_str()).collect();
+        let mut roots: Vec<&Task> = self
+            .tasks
+            .iter()
+            .filter(|t| match &t.parent_id {
+                Some(parent) => !ids.contains(parent.as_str()),
+                None => true,
+            })
+            .collect();
+        roots.sort_by_key(|t| t.display_id.unwrap_or(i32::MAX));
+        roots
+    }
+
+    /// Depth-first walk from `roots`, each subtask following its parent
+    ///
+    /// Subtasks deeper than `max_depth` are left out but still counted in
+    /// their ancestors' subtotals.
+    pub fn flatten(&self, roots: &[&'a Task], max_depth: Option<usize>) -> Vec<TreeNode<'a>> {
+        let mut nodes = Vec::new();
+        let mut seen = HashSet::new();
+        for root in roots {
+            self.walk(root, 0, "", None, max_depth, &mut nodes, &mut seen);
+        }
+        nodes
+    }
+
+    /// Push one node and its visible descendants; `is_last` is `None` for a root
+    #[allow(clippy::too_many_arguments)]
+    fn walk(
+        &self,
+        task: &'a Task,
+        depth: usize,
+        prefix: &str,
+        is_last: Option<bool>,
+        max_depth: Option<usize>,
+        nodes: &mut Vec<TreeNode<'a>>,
+        seen: &mut HashSet<&'a str>,
+    ) {
+        let connector = match is_last {
+            None => "",
+            Some(true) => "└── ",
+            Some(false) => "├── ",
+        };
+        nodes.push(TreeNode {
+            task,
+            depth,
+            prefix: format!("{}{}", prefix, connector),
+            subtotal: self.subtotal(task, &mut HashSet::new()),
+        });
+
+        // Guard against malformed parent cycles
+        if !seen.insert(task.id.as_str()) || max_depth.is_some_and(|max| depth >= max) {
+            return;
+        }
+
+        let child_prefix = match is_last {
+            None => prefix.to_string(),
+            Some(true) => format!("{}    ", prefix),
+            Some(false) => format!("{}│   ", prefix),
+        };
+        let subtasks = self
+            .children
+            .get(task.id.as_str())
+            .map_or(&[][..], Vec::as_slice);
+        for (i, subtask) in subtasks.iter().enumerate() {
+            let last = Some(i + 1 == subtasks.len());
+            self.walk(
+                subtask,
+                depth + 1,
+                &child_prefix,
+                last,
+                max_depth,
+                nodes,
+                seen,
+            );
+        }
+    }
+
+    /// Count (completed, total) descendants of a task
+    fn subtotal(&self, task: &'a Task, seen: &mut HashSet<&'a str>) -> (usize, usize) {
+        let mut counts = (0, 0);
+        if !seen.insert(task.id.as_str()) {
+            return counts;
+        }
+        for subtask in self.children.get(task.id.as_str()).into_iter().flatten() {
+            let (completed, total) = self.subtotal(subtask, seen);
+            let done = self.workflow.is_completed(&subtask.status) as usize;
+            counts = (counts.0 + completed + done, counts.1 + total + 1);
+        }
+        counts
+    }
+}
+
+/// Renders the parent/subtask hierarchy as an ASCII tree
+pub struct TaskTreeRenderer {
+    db: Database,
+    max_depth: Option<usize>,
+}
+
+impl TaskTreeRenderer {
+    pub fn new(db: Database) -> Self {
+        Self {
+            db,
+            max_depth: None,
+        }
+    }
+
+    /// Stop descending below this many levels of subtasks
+    pub fn with_max_depth(mut self, max_depth: Option<usize>) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Render a single task and all of its descendants
+    pub fn render_task(&self, task_uuid: &str) -> Result<String> {
+        let tasks = self.db.list_tasks(None)?;
+        let task = tasks
+            .iter()
+            .find(|t| t.id == task_uuid)
+            .ok_or_else(|| anyhow::anyhow!("Task not found"))?;
+
+        let forest = TaskForest::new(&tasks).with_workflow(self.db.workflow()?);
+        self.render_nodes(&forest.flatten(&[task], self.max_depth))
+    }
+
+    /// Render every tree rooted in an epic (or all top-level tasks)
+    ///
+    /// Roots are tasks without a parent, or whose parent lies outside the epic.
+    pub fn render_roots(&self, epic: Option<&str>) -> Result<String> {
+        let tasks = self.db.list_tasks(None)?;
+        let in_epic = |t: &Task| epic.is_none_or(|e| t.epic_name.as_deref() == Some(e));
+
+        let in_scope: HashSet<&str> = tasks
+            .iter()
+            .filter(|t| in_epic(t))
+            .map(|t| t.id.as_str())
+            .collect();
+        let mut roots: Vec<&Task> = tasks
+            .iter()
+            .filter(|t| in_epic(t))
+            .filter(|t| match &t.parent_id {
+                Some(parent) => !in_scope.contains(parent.as_str()),
+                None => true,
+            })
+            .collect();
+        roots.sort_by_key(|t| t.display_id.unwrap_or(i32::MAX));
+
+        let mut output = String::new();
+        if let Some(epic) = epic {
+            output.push_str(&format!("{}\n", epic.cyan().bold()));
+        }
+        if roots.is_empty() {
+            output.push_str("No tasks found\n");
+            return Ok(output);
+        }
+
+        let forest = TaskForest::new(&tasks).with_workflow(self.db.workflow()?);
+        output.push_str(&self.render_nodes(&forest.flatten(&roots, self.max_depth))?);
+        Ok(output)
+    }
+
+    fn render_nodes(&self, nodes: &[TreeNode]) -> Result<String> {
+        let mut output = String::new();
+        for node in nodes {
+            output.push_str(&format!("{}{}", node.prefix, self.format_line(node.task)?));
+            let (completed, total) = node.subtotal;
+            if total > 0 {
+                output.push_str(
+                    &format!(" ({}/{} subtasks)", completed, total)
+                        .dimmed()
+                        .to_string(),
+                );
+            }
+            output.push('\n');
+        }
+        Ok(output)
+    }
+
+    fn format_line(&self, task: &Task) -> Result<String> {
+        let id = task
+            .display_id
+            .map(|id| format!("#{}", id))
+            .unwrap_or_else(|| task.id[..8].to_string());
+
+        let mut line = format!("{} {} {}", status_glyph(&task.status), id, task.title);
+
+        if let Some(display_id) = task.display_id {
+            let (met, total) = self.criteria_counts(display_id)?;
+            if total > 0 {
+                line.push_str(&format!(" [{}/{} AC]", met, total).dimmed().to_string());
+            }
+        }
+        Ok(line)
+    }
+
+    /// Count (met, total) acceptance criteria for a task
+    fn criteria_counts(&self, task_display_id: i32) -> Result<(i32, i32)> {
+        let counts = self.db.get_connection().query_row(
+            "SELECT COALESCE(SUM(completed), 0), COUNT(*) FROM acceptance_criteria
+             WHERE task_display_id = ?1",
+            [task_display_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        Ok(counts)
+    }
+}
+
+/// Status glyphs shared with `prd stats`
+fn status_glyph(status: &TaskStatus) -> String {
+    match status {
+        TaskStatus::Pending => "○".white().to_string(),
+        TaskStatus::InProgress => "◐".blue().to_string(),
+        TaskStatus::Blocked => "■".red().to_string(),
+        TaskStatus::Review => "◇".yellow().to_string(),
+        TaskStatus::Completed => "●".green().to_string(),
+        TaskStatus::Failed => "✗".red().to_string(),
+        TaskStatus::Cancelled => "✕".dimmed().to_string(),
+        TaskStatus::Custom(_) => "◆".magenta().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Priority;
+
+    fn setup() -> Result<(Database, Task)> {
+        let db = Database::new(":memory:")?;
+        let parent = db.create_task(
+            "Auth".to_string(),
+            None,
+            Priority::High,
+            None,
+            Some("Security".to_string()),
+        )?;
+        let login = db.create_task(
+            "Login".to_string(),
+            None,
+            Priority::Medium,
+            Some(parent.id.clone()),
+            Some("Security".to_string()),
+        )?;
+        db.create_task(
+            "Logout".to_string(),
+            None,
+            Priority::Medium,
+            Some(parent.id.clone()),
+            None,
+        )?;
+        db.create_task(
+            "Form".to_string(),
+            None,
+            Priority::Low,
+            Some(login.id.clone()),
+            None,
+        )?;
+        Ok((db, parent))
+    }
+
+    #[test]
+    fn test_render_task_hierarchy() -> Result<()> {
+        let (db, parent) = setup()?;
+        let renderer = TaskTreeRenderer::new(db);
+        let output = renderer.render_task(&parent.id)?;
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].contains("#1 Auth"));
+        assert!(lines[1].starts_with("├── ") && lines[1].contains("#2 Login"));
+        assert!(lines[2].starts_with("│   └── ") && lines[2].contains("#4 Form"));
+        assert!(lines[3].starts_with("└── ") && lines[3].contains("#3 Logout"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_epic_and_criteria_counts() -> Result<()> {
+        let (db, _) = setup()?;
+        db.get_connection().execute(
+            "INSERT INTO acceptance_criteria (task_display_id, criterion, completed, created_at)
+             VALUES (1, 'a', 1, datetime('now')), (1, 'b', 0, datetime('now'))",
+            [],
+        )?;
+        let renderer = TaskTreeRenderer::new(db);
+
+        let output = renderer.render_roots(Some("Security"))?;
+        assert!(output.contains("[1/2 AC]"));
+        // Subtasks outside the epic still appear beneath their in-epic parent
+        assert!(output.contains("#3 Logout"));
+
+        let empty = renderer.render_roots(Some("Missing"))?;
+        assert!(empty.contains("No tasks found"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_forest_depth_and_subtotals() -> Result<()> {
+        let (db, parent) = setup()?;
+        let form = db.list_tasks(None)?.into_iter().find(|t| t.title == "Form");
+        db.update_task_status(&form.unwrap().id, TaskStatus::Completed, None)?;
+        let tasks = db.list_tasks(None)?;
+        let forest = TaskForest::new(&tasks);
+
+        let nodes = forest.flatten(&forest.roots(), Some(1));
+        let titles: Vec<&str> = nodes.iter().map(|n| n.task.title.as_str()).collect();
+        assert_eq!(titles, ["Auth", "Login", "Logout"]);
+        assert_eq!(nodes[0].subtotal, (1, 3));
+        assert_eq!(nodes[1].subtotal, (1, 1));
+        assert_eq!(nodes[1].prefix, "├── ");
+
+        // A subset whose parent is missing is rooted at the orphan
+        let subset: Vec<Task> = tasks.into_iter().filter(|t| t.id != parent.id).collect();
+        let forest = TaskForest::new(&subset);
+        let roots: Vec<&str> = forest.roots().iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(roots, ["Login", "Logout"]);
+
+        let output = TaskTreeRenderer::new(db)
+            .with_max_depth(Some(0))
+            .render_task(&parent.id)?;
+        assert_eq!(output.lines().count(), 1);
+        assert!(output.contains("(1/3 subtasks)"));
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+use anyhow::{Context, Result};
+use glob::Pattern;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File watcher settings (~/.prd/watcher.toml)
+///
+/// ```toml
+/// paths = ["../api/docs/tasks"]
+/// patterns = ["TASK-*COMPLETE*.md", "TASK-*DONE*.md"]
+/// notify_on_complete = true
+/// max_attempts = 5
+/// retry_delay_secs = 2
+/// ```
+///
+/// A running daemon re-reads this file on `prd watch-files reload` (or SIGHUP).
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct WatcherConfig {
+    /// Directories watched in addition to `--docs-path`
+    pub paths: Vec<PathBuf>,
+
+    /// File name globs for completion documents; names must still start
+    /// with `TASK-<id>-` so the task can be identified
+    pub patterns: Vec<String>,
+
+    /// Send a desktop notification (per ~/.prd/config.toml) on auto-completion
+    pub notify_on_complete: bool,
+
+    /// Attempts at a failing document before it is dead-lettered
+    pub max_attempts: u32,
+
+    /// Wait before the first retry; each later retry waits twice as long
+    pub retry_delay_secs: u64,
+}
+
+impl Default for WatcherConfig {
+    fn default() -> Self {
+        Self {
+            paths: Vec::new(),
+            patterns: vec![
+                "TASK-*COMPLETE*.md".to_string(),
+                "TASK-*COMPLETION*.md".to_string(),
+            ],
+            notify_on_complete: false,
+            max_attempts: 5,
+            retry_delay_secs: 2,
+        }
+    }
+}
+
+impl WatcherConfig {
+    /// Load configuration, falling back to defaults if the file is missing
+    pub fn load() -> Result<Self> {
+        let config_path = Self::get_config_path()?;
+
+        if !config_path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(&config_path)
+            .context(format!("Failed to read config from {:?}", config_path))?;
+        toml::from_str(&content).context("Failed to parse watcher config as TOML")
+    }
+
+    /// Get the path to the config file (~/.prd/watcher.toml)
+    pub fn get_config_path() -> Result<PathBuf> {
+        let home = std::env::var("HOME")
+            .or_else(|_| std::env::var("USERPROFILE"))
+            .map_err(|_| anyhow::anyhow!("Could not determine home directory"))?;
+        Ok(PathBuf::from(home).join(".prd").join("watcher.toml"))
+    }
+
+    /// Compile the file name patterns
+    pub fn compile_patterns(&self) -> Result<Vec<Pattern>> {
+        self.patterns
+            .iter()
+            .map(|p| Pattern::new(p).context(format!("Invalid watch pattern '{}'", p)))
+            .collect()
+    }
+
+    /// All directories to watch: `docs_path` first, then the extra paths
+    pub fn watched_paths(&self, docs_path: &Path) -> Vec<PathBuf> {
+        let mut paths = vec![docs_path.to_path_buf()];
+        for path in &self.paths {
+            if !paths.contains(path) {
+                paths.push(path.clone());
+            }
+        }
+        paths
+    }
+}
+
+/// Whether a file name matches any of the completion document patterns
+pub fn matches_patterns(patterns: &[Pattern], path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .map(|n| patterns.iter().any(|p| p.matches(n)))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_patterns_match_completion_docs() -> Result<()> {
+        let patterns = WatcherConfig::default().compile_patterns()?;
+        assert!(matches_patterns(
+            &patterns,
+            Path::new("TASK-033-COMPLETION.md")
+        ));
+        assert!(matches_patterns(
+            &patterns,
+            Path::new("docs/TASK-50-COMPLETE.md")
+        ));
+        assert!(!matches_patterns(&patterns, Path::new("TASK-033.md")));
+        assert!(!matches_patterns(&patterns, Path::new("README.md")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_config() -> Result<()> {
+        let config: WatcherConfig = toml::from_str(
+            r#"
+            paths = ["docs/tasks", "other/docs"]
+            patterns = ["TASK-*DONE*.md"]
+            "#,
+        )?;
+        assert!(!config.notify_on_complete);
+        assert_eq!(config.max_attempts, 5);
+        assert_eq!(
+            config.watched_paths(Path::new("docs/tasks")),
+            vec![PathBuf::from("docs/tasks"), PathBuf::from("other/docs")]
+        );
+        let patterns = config.compile_patterns()?;
+        assert!(matches_patterns(&patterns, Path::new("TASK-7-DONE.md")));
+
+        let bad = WatcherConfig {
+            patterns: vec!["TASK-[".to_string()],
+            ..Default::default()
+        };
+        assert!(bad.compile_patterns().is_err());
+        Ok(())
+    }
+}
@@ -1,9 +1,32 @@
 use anyhow::Result;
 use std::fs;
+use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 
-const PID_FILE: &str = "/tmp/prd-watcher.pid";
-const LOG_FILE: &str = "/tmp/prd-watcher.log";
+const PID_FILE_NAME: &str = "prd-watcher.pid";
+const LOG_FILE_NAME: &str = "prd-watcher.log";
+const RELOAD_FILE_NAME: &str = "prd-watcher.reload";
+
+/// How many 100ms intervals to wait for a freshly spawned daemon
+const STARTUP_POLLS: u32 = 30;
+
+/// PID file of the running daemon (in the system temp directory)
+pub fn pid_file() -> PathBuf {
+    std::env::temp_dir().join(PID_FILE_NAME)
+}
+
+/// Log file the daemon writes to (in the system temp directory)
+pub fn log_file() -> PathBuf {
+    std::env::temp_dir().join(LOG_FILE_NAME)
+}
+
+/// Marker file a reload request leaves for the daemon on platforms without SIGHUP
+pub fn reload_file() -> PathBuf {
+    std::env::temp_dir().join(RELOAD_FILE_NAME)
+}
 
 /// Start the watcher as a daemon process
 pub fn start_daemon(docs_path: PathBuf, db_path: PathBuf) -> Result<()> {
@@ -14,46 +37,51 @@ pub fn start_daemon(docs_path: PathBuf, db_path: PathBuf) -> Result<()> {
         ));
     }
 
-    #[cfg(unix)]
-    {
-        use std::process::{Command, Stdio};
-
-        // Get current executable path
-        let exe_path = std::env::current_exe()?;
-
-        // Spawn background process
-        let child = Command::new(exe_path)
-            .arg("watch-files")
-            .arg("--docs-path")
-            .arg(&docs_path)
-            .arg("--daemon-mode")
-            .stdin(Stdio::null())
-            .stdout(Stdio::from(fs::File::create(LOG_FILE)?))
-            .stderr(Stdio::from(
-                fs::OpenOptions::new()
-                    .create(true)
-                    .append(true)
-                    .open(LOG_FILE)?,
-            ))
-            .spawn()?;
-
-        // Write PID file
-        fs::write(PID_FILE, child.id().to_string())?;
-
-        println!("✓ File watcher started in background (PID: {})", child.id());
-        println!("  Logs: {}", LOG_FILE);
-        println!("  Use 'prd watch-files --status' to check status");
-        println!("  Use 'prd watch-files --stop' to stop");
+    let log_path = log_file();
+    let log = fs::File::create(&log_path)?;
 
-        Ok(())
-    }
+    // Get current executable path
+    let exe_path = std::env::current_exe()?;
 
-    #[cfg(not(unix))]
-    {
-        Err(anyhow::anyhow!(
-            "Daemon mode only supported on Unix systems"
-        ))
+    // Spawn background process
+    let mut command = Command::new(exe_path);
+    command
+        .arg("--database")
+        .arg(&db_path)
+        .arg("watch-files")
+        .arg("--docs-path")
+        .arg(&docs_path)
+        .arg("--daemon-mode")
+        .stdin(Stdio::null())
+        .stdout(Stdio::from(log.try_clone()?))
+        .stderr(Stdio::from(log));
+    platform::detach(&mut command);
+    let mut child = command.spawn()?;
+
+    // Wait until the daemon holds its instance lock, surfacing early exits
+    for _ in 0..STARTUP_POLLS {
+        if let Some(exit) = child.try_wait()? {
+            return Err(anyhow::anyhow!(
+                "File watcher exited during startup ({}); see {}",
+                exit,
+                log_path.display()
+            ));
+        }
+        if platform::is_alive(child.id()) {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
     }
+
+    // Write PID file
+    fs::write(pid_file(), child.id().to_string())?;
+
+    println!("✓ File watcher started in background (PID: {})", child.id());
+    println!("  Logs: {}", log_path.display());
+    println!("  Use 'prd watch-files --status' to check status");
+    println!("  Use 'prd watch-files --stop' to stop");
+
+    Ok(())
 }
 
 /// Stop the daemon process
@@ -62,33 +90,34 @@ pub fn stop_daemon() -> Result<()> {
         return Err(anyhow::anyhow!("File watcher not running"));
     }
 
-    let pid_str = fs::read_to_string(PID_FILE)?;
-    let pid = pid_str.trim().parse::<i32>()?;
-
-    #[cfg(unix)]
-    {
-        use nix::sys::signal::{kill, Signal};
-        use nix::unistd::Pid;
+    let pid = read_pid()?;
+    platform::terminate(pid)?;
 
-        // Send SIGTERM
-        kill(Pid::from_raw(pid), Signal::SIGTERM)?;
+    // Wait a bit for graceful shutdown
+    std::thread::sleep(std::time::Duration::from_secs(1));
 
-        // Wait a bit for graceful shutdown
-        std::thread::sleep(std::time::Duration::from_secs(1));
+    // Remove PID file
+    let _ = fs::remove_file(pid_file());
 
-        // Remove PID file
-        let _ = fs::remove_file(PID_FILE);
+    println!("✓ File watcher stopped");
+    Ok(())
+}
 
-        println!("✓ File watcher stopped");
-        Ok(())
+/// Ask the running daemon to reload its configuration
+pub fn request_reload() -> Result<()> {
+    if !is_running()? {
+        return Err(anyhow::anyhow!("File watcher not running"));
     }
 
-    #[cfg(not(unix))]
-    {
-        Err(anyhow::anyhow!(
-            "Daemon mode only supported on Unix systems"
-        ))
-    }
+    let pid = read_pid()?;
+    platform::request_reload(pid)?;
+
+    println!(
+        "✓ Asked file watcher (PID {}) to reload its configuration",
+        pid
+    );
+    println!("  Check 'prd watch-files --status' for the result");
+    Ok(())
 }
 
 /// Check the status of the daemon
@@ -98,51 +127,277 @@ pub fn status() -> Result<()> {
         return Ok(());
     }
 
-    let pid_str = fs::read_to_string(PID_FILE)?;
-    let pid = pid_str.trim();
-
-    println!("File watcher: Running (PID {})", pid);
-    println!("Logs: {}", LOG_FILE);
+    let log_path = log_file();
+    println!("File watcher: Running (PID {})", read_pid()?);
+    println!("Logs: {}", log_path.display());
 
     // Try to show tail of log
-    if Path::new(LOG_FILE).exists() {
-        println!("\nRecent log entries:");
-        let _ = std::process::Command::new("tail")
-            .args(&["-n", "5", LOG_FILE])
-            .status();
+    if let Ok(lines) = tail(&log_path, 5) {
+        if !lines.is_empty() {
+            println!("\nRecent log entries:");
+            for line in lines {
+                println!("{}", line);
+            }
+        }
     }
 
     Ok(())
 }
 
+/// Hold the single-instance lock for the lifetime of a daemon process
+///
+/// On Windows this creates a named mutex that `--status` and `--stop` use to
+/// tell a live daemon from a stale PID file (PIDs are recycled quickly there).
+/// On Unix the PID file alone is enough, so the lock is a no-op.
+pub fn acquire_instance_lock() -> Result<InstanceLock> {
+    platform::acquire_instance_lock()
+}
+
+pub use platform::InstanceLock;
+
+/// Set `flag` whenever a reload is requested (SIGHUP on Unix)
+pub fn install_reload_handler(flag: Arc<AtomicBool>) -> Result<()> {
+    platform::install_reload_handler(flag)
+}
+
 /// Check if the daemon is running
 fn is_running() -> Result<bool> {
-    if !Path::new(PID_FILE).exists() {
+    if !pid_file().exists() {
         return Ok(false);
     }
 
-    let pid_str = fs::read_to_string(PID_FILE)?;
-    let pid = pid_str.trim().parse::<i32>()?;
+    let pid = read_pid()?;
+    if platform::is_alive(pid) {
+        Ok(true)
+    } else {
+        // Process doesn't exist, clean up PID file
+        let _ = fs::remove_file(pid_file());
+        Ok(false)
+    }
+}
+
+fn read_pid() -> Result<u32> {
+    let pid_str = fs::read_to_string(pid_file())?;
+    Ok(pid_str.trim().parse::<u32>()?)
+}
+
+/// Last `count` lines of a file
+fn tail(path: &Path, count: usize) -> Result<Vec<String>> {
+    let reader = BufReader::new(fs::File::open(path)?);
+    let lines: Vec<String> = reader.lines().collect::<std::io::Result<_>>()?;
+    let start = lines.len().saturating_sub(count);
+    Ok(lines[start..].to_vec())
+}
+
+#[cfg(unix)]
+mod platform {
+    use anyhow::Result;
+    use nix::sys::signal::{kill, signal, SigHandler, Signal};
+    use nix::unistd::Pid;
+    use std::process::Command;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, OnceLock};
+
+    static RELOAD_FLAG: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+
+    extern "C" fn handle_sighup(_: libc::c_int) {
+        if let Some(flag) = RELOAD_FLAG.get() {
+            flag.store(true, Ordering::SeqCst);
+        }
+    }
+
+    pub fn install_reload_handler(flag: Arc<AtomicBool>) -> Result<()> {
+        let _ = RELOAD_FLAG.set(flag);
+        unsafe { signal(Signal::SIGHUP, SigHandler::Handler(handle_sighup)) }?;
+        Ok(())
+    }
+
+    pub fn request_reload(pid: u32) -> Result<()> {
+        kill(Pid::from_raw(pid as i32), Signal::SIGHUP)?;
+        Ok(())
+    }
+
+    /// No-op: the PID file identifies the daemon on Unix
+    pub struct InstanceLock;
+
+    pub fn acquire_instance_lock() -> Result<InstanceLock> {
+        Ok(InstanceLock)
+    }
+
+    pub fn detach(command: &mut Command) {
+        use std::os::unix::process::CommandExt;
+
+        // Ignore SIGHUP until the daemon installs its reload handler, so an
+        // early reload request (or the terminal closing) cannot kill it
+        unsafe {
+            command.pre_exec(|| {
+                signal(Signal::SIGHUP, SigHandler::SigIgn)
+                    .map(|_| ())
+                    .map_err(std::io::Error::from)
+            });
+        }
+    }
+
+    pub fn is_alive(pid: u32) -> bool {
+        // Signal 0 checks for existence without killing
+        kill(Pid::from_raw(pid as i32), None).is_ok()
+    }
+
+    pub fn terminate(pid: u32) -> Result<()> {
+        // SIGTERM lets the watcher's Ctrl+C handler shut down cleanly
+        kill(Pid::from_raw(pid as i32), Signal::SIGTERM)?;
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use anyhow::Result;
+    use std::os::windows::process::CommandExt;
+    use std::process::Command;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use windows_sys::Win32::Foundation::{CloseHandle, GetLastError, ERROR_ALREADY_EXISTS, HANDLE};
+    use windows_sys::Win32::System::Threading::{
+        CreateMutexW, OpenMutexW, OpenProcess, TerminateProcess, CREATE_NEW_PROCESS_GROUP,
+        DETACHED_PROCESS, PROCESS_TERMINATE, SYNCHRONIZATION_SYNCHRONIZE,
+    };
+
+    /// Poll for the reload marker left by `request_reload`
+    pub fn install_reload_handler(flag: Arc<AtomicBool>) -> Result<()> {
+        let marker = super::reload_file();
+        let _ = std::fs::remove_file(&marker);
+        std::thread::spawn(move || loop {
+            if std::fs::remove_file(&marker).is_ok() {
+                flag.store(true, Ordering::SeqCst);
+            }
+            std::thread::sleep(std::time::Duration::from_millis(500));
+        });
+        Ok(())
+    }
+
+    pub fn request_reload(_pid: u32) -> Result<()> {
+        std::fs::write(super::reload_file(), b"")?;
+        Ok(())
+    }
+
+    /// Per-session mutex held by the running daemon
+    const MUTEX_NAME: &str = "Local\\prd-watcher";
 
-    #[cfg(unix)]
-    {
-        use nix::sys::signal::kill;
-        use nix::unistd::Pid;
+    /// Owns the named mutex; Windows releases it when the process exits
+    pub struct InstanceLock(HANDLE);
 
-        // Check if process exists (signal 0 doesn't kill)
-        match kill(Pid::from_raw(pid), None) {
-            Ok(_) => Ok(true),
-            Err(_) => {
-                // Process doesn't exist, clean up PID file
-                let _ = fs::remove_file(PID_FILE);
-                Ok(false)
+    impl Drop for InstanceLock {
+        fn drop(&mut self) {
+            unsafe {
+                CloseHandle(self.0);
             }
         }
     }
 
-    #[cfg(not(unix))]
-    {
-        // On Windows, just check if PID file exists
-        Ok(true)
+    fn wide(s: &str) -> Vec<u16> {
+        s.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+
+    pub fn acquire_instance_lock() -> Result<InstanceLock> {
+        let name = wide(MUTEX_NAME);
+        let handle = unsafe { CreateMutexW(std::ptr::null(), 1, name.as_ptr()) };
+        if handle.is_null() {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        if unsafe { GetLastError() } == ERROR_ALREADY_EXISTS {
+            unsafe {
+                CloseHandle(handle);
+            }
+            return Err(anyhow::anyhow!("File watcher already running"));
+        }
+        Ok(InstanceLock(handle))
+    }
+
+    pub fn detach(command: &mut Command) {
+        // No console (so no window), and out of the parent's Ctrl+C group
+        command.creation_flags(DETACHED_PROCESS | CREATE_NEW_PROCESS_GROUP);
+    }
+
+    pub fn is_alive(_pid: u32) -> bool {
+        // The mutex disappears with the daemon, even if its PID is reused
+        let name = wide(MUTEX_NAME);
+        let handle = unsafe { OpenMutexW(SYNCHRONIZATION_SYNCHRONIZE, 0, name.as_ptr()) };
+        if handle.is_null() {
+            return false;
+        }
+        unsafe {
+            CloseHandle(handle);
+        }
+        true
+    }
+
+    pub fn terminate(pid: u32) -> Result<()> {
+        // A detached process has no console to receive Ctrl+C; every write
+        // the watcher makes is transactional, so terminating it is safe
+        let handle = unsafe { OpenProcess(PROCESS_TERMINATE, 0, pid) };
+        if handle.is_null() {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        let ok = unsafe { TerminateProcess(handle, 0) };
+        unsafe {
+            CloseHandle(handle);
+        }
+        if ok == 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod platform {
+    use anyhow::Result;
+    use std::process::Command;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+
+    pub struct InstanceLock;
+
+    pub fn acquire_instance_lock() -> Result<InstanceLock> {
+        Ok(InstanceLock)
+    }
+
+    pub fn install_reload_handler(_flag: Arc<AtomicBool>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn request_reload(_pid: u32) -> Result<()> {
+        Err(anyhow::anyhow!(
+            "Reloading is not supported on this platform"
+        ))
+    }
+
+    pub fn detach(_command: &mut Command) {}
+
+    pub fn is_alive(_pid: u32) -> bool {
+        true
+    }
+
+    pub fn terminate(_pid: u32) -> Result<()> {
+        Err(anyhow::anyhow!(
+            "Daemon mode is not supported on this platform"
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tail_returns_last_lines() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("watcher.log");
+        fs::write(&path, "one\ntwo\nthree\nfour\n")?;
+
+        assert_eq!(tail(&path, 2)?, vec!["three", "four"]);
+        assert_eq!(tail(&path, 10)?.len(), 4);
+        Ok(())
     }
 }
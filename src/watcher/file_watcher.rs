@@ -1,5 +1,6 @@
 use anyhow::Result;
-use notify::{event::EventKind, Event, RecursiveMode, Watcher};
+use glob::Pattern;
+use notify::{Event, RecursiveMode, Watcher};
 use rusqlite::params;
 use std::path::{Path, PathBuf};
 use std::sync::{
@@ -8,8 +9,16 @@ use std::sync::{
 };
 use std::time::Duration;
 
-use crate::db::Database;
-use crate::sync::parse_completion_doc;
+use super::config::{matches_patterns, WatcherConfig};
+use super::retry::{failures_file, RetryDecision, RetryQueue};
+use crate::db::{is_busy_error, Database, TaskStatus};
+use crate::notifications::{NotificationConfig, Notifier};
+use crate::output::emoji;
+use crate::retention::{run_gc, RetentionConfig};
+use crate::sync::read_completion_doc;
+
+/// How often the daemon releases expired task leases
+const LEASE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
 
 /// Statistics for the file watcher
 #[derive(Debug, Default)]
@@ -19,39 +28,88 @@ struct WatcherStats {
     start_time: Option<std::time::Instant>,
 }
 
+/// A task the watcher marked complete from a completion document
+struct AutoCompleted {
+    task_uuid: String,
+    agent_uuid: Option<String>,
+}
+
 /// File watcher for detecting new completion documents
 pub struct FileWatcher {
     db: Database,
     docs_path: PathBuf,
     pub running: Arc<AtomicBool>,
+    /// Set to make the running watcher re-read its configuration
+    pub reload: Arc<AtomicBool>,
     stats: Arc<Mutex<WatcherStats>>,
+    config: WatcherConfig,
+    patterns: Arc<Mutex<Vec<Pattern>>>,
+    notifier: Option<Notifier>,
+    retention: Option<RetentionConfig>,
+    manages_retention: bool,
+    retries: RetryQueue,
 }
 
 impl FileWatcher {
     /// Create a new file watcher
     pub fn new(docs_path: PathBuf, db: Database) -> Result<Self> {
+        let config = WatcherConfig::default();
         Ok(Self {
             db,
             docs_path,
             running: Arc::new(AtomicBool::new(false)),
+            reload: Arc::new(AtomicBool::new(false)),
             stats: Arc::new(Mutex::new(WatcherStats::default())),
+            patterns: Arc::new(Mutex::new(config.compile_patterns()?)),
+            retries: RetryQueue::new(
+                config.max_attempts,
+                Duration::from_secs(config.retry_delay_secs),
+                failures_file(),
+            )?,
+            config,
+            notifier: None,
+            retention: None,
+            manages_retention: false,
         })
     }
 
+    /// Use extra watch paths, patterns and notification settings
+    pub fn with_config(mut self, config: WatcherConfig) -> Result<Self> {
+        *self.patterns.lock().unwrap() = config.compile_patterns()?;
+        self.notifier = completion_notifier(&self.db, &config)?;
+        self.retries.set_policy(
+            config.max_attempts,
+            Duration::from_secs(config.retry_delay_secs),
+        );
+        self.config = config;
+        Ok(self)
+    }
+
+    /// Periodically apply a retention policy while watching
+    pub fn with_retention(mut self, config: RetentionConfig) -> Self {
+        self.retention = Some(config).filter(|c| c.auto_cleanup);
+        self.manages_retention = true;
+        self
+    }
+
     /// Start watching for file changes
     pub fn start(&mut self) -> Result<()> {
-        println!(
-            "👁 Watching {} for completion documents...",
-            self.docs_path.display()
-        );
+        let mut watched = self.config.watched_paths(&self.docs_path);
+        for path in &watched {
+            println!(
+                "{} Watching {} for completion documents...",
+                emoji("👁"),
+                path.display()
+            );
+        }
         println!("Press Ctrl+C to stop...\n");
 
         self.running.store(true, Ordering::SeqCst);
         self.stats.lock().unwrap().start_time = Some(std::time::Instant::now());
 
-        let docs_path = self.docs_path.clone();
         let running = Arc::clone(&self.running);
         let stats = Arc::clone(&self.stats);
+        let patterns = Arc::clone(&self.patterns);
 
         // Create watcher with recommended implementation
         let mut watcher =
@@ -61,8 +119,9 @@ impl FileWatcher {
                     // This is simpler than trying to share the database across threads
                     match event.kind {
                         notify::EventKind::Create(_) | notify::EventKind::Modify(_) => {
+                            let patterns = patterns.lock().unwrap();
                             for path in &event.paths {
-                                if is_completion_doc(path) {
+                                if matches_patterns(&patterns, path) {
                                     println!(
                                         "✓ Detected new file: {}",
                                         path.file_name().unwrap().to_str().unwrap()
@@ -79,47 +138,187 @@ impl FileWatcher {
                 }
             })?;
 
-        // Watch directory for new files (non-recursive)
-        watcher.watch(&docs_path, RecursiveMode::NonRecursive)?;
+        // Watch directories for new files (non-recursive); only the primary
+        // docs path is required to exist
+        watcher.watch(&self.docs_path, RecursiveMode::NonRecursive)?;
+        watched.retain(|path| path == &self.docs_path || watch_path(&mut watcher, path));
 
         // Keep running until stopped
         // Check for new files periodically
         let mut last_check = std::time::Instant::now();
+        let mut last_gc: Option<std::time::Instant> = None;
+        let mut last_lease_check: Option<std::time::Instant> = None;
         while running.load(Ordering::SeqCst) {
             std::thread::sleep(Duration::from_millis(100));
 
+            // Pick up configuration changes without restarting
+            if self.reload.swap(false, Ordering::SeqCst) {
+                match self.reload_config(&mut watcher, &mut watched) {
+                    Ok(()) => println!(
+                        "{} Reloaded configuration ({} paths, {} patterns)",
+                        emoji("🔄"),
+                        watched.len(),
+                        self.config.patterns.len()
+                    ),
+                    Err(e) => eprintln!("{} Keeping previous configuration: {:#}", emoji("❌"), e),
+                }
+            }
+
             // Every second, scan for new completion docs
             if last_check.elapsed() > Duration::from_secs(1) {
-                if let Err(e) = self.scan_and_process() {
-                    eprintln!("❌ Error scanning for completions: {}", e);
-                    self.stats.lock().unwrap().errors += 1;
+                for dir in &watched {
+                    if let Err(e) = self.scan_and_process(dir) {
+                        eprintln!("{} Error scanning for completions: {}", emoji("❌"), e);
+                        self.stats.lock().unwrap().errors += 1;
+                    }
                 }
                 last_check = std::time::Instant::now();
             }
+
+            // Release leases held by agents that stopped renewing them
+            if last_lease_check.is_none_or(|t| t.elapsed() >= LEASE_CHECK_INTERVAL) {
+                match self.db.release_expired_leases() {
+                    Ok(released) => {
+                        for lease in released {
+                            println!(
+                                "{} Released expired lease on {}",
+                                emoji("🔓"),
+                                task_label(&self.db, &lease.task_id)
+                            );
+                        }
+                    }
+                    Err(e) if is_busy_error(&e) => {}
+                    Err(e) => eprintln!("{} Error releasing expired leases: {}", emoji("❌"), e),
+                }
+                last_lease_check = Some(std::time::Instant::now());
+            }
+
+            if let Some(config) = &self.retention {
+                let interval = Duration::from_secs(config.cleanup_interval_hours * 3600);
+                if last_gc.is_none_or(|t| t.elapsed() >= interval) {
+                    match run_gc(&self.db, config) {
+                        Ok(report) => {
+                            if report.progress_deleted > 0 {
+                                println!(
+                                    "{} Pruned {} old progress reports",
+                                    emoji("🧹"),
+                                    report.progress_deleted
+                                )
+                            }
+                            if report.embeddings_pruned.total() > 0 {
+                                println!(
+                                    "{} Pruned {} orphaned embeddings",
+                                    emoji("🧹"),
+                                    report.embeddings_pruned.total()
+                                )
+                            }
+                        }
+                        Err(e) => eprintln!("{} Error running cleanup: {}", emoji("❌"), e),
+                    }
+                    last_gc = Some(std::time::Instant::now());
+                }
+            }
         }
 
-        println!("\n⏹ File watcher stopped");
+        println!("\n{} File watcher stopped", emoji("⏹"));
         self.print_stats();
 
         Ok(())
     }
 
-    /// Scan for new completion documents and process them
-    fn scan_and_process(&self) -> Result<()> {
-        use glob::glob;
+    /// Re-read watcher, notification and retention settings
+    ///
+    /// Everything is loaded before anything is applied, so an invalid file
+    /// leaves the previous configuration (and the existing watches) in place.
+    fn reload_config(
+        &mut self,
+        watcher: &mut impl Watcher,
+        watched: &mut Vec<PathBuf>,
+    ) -> Result<()> {
+        let config = WatcherConfig::load()?;
+        let patterns = config.compile_patterns()?;
+        let notifier = completion_notifier(&self.db, &config)?;
+        let retention = match self.manages_retention {
+            true => Some(RetentionConfig::load()?).filter(|c| c.auto_cleanup),
+            false => None,
+        };
 
-        let pattern = self.docs_path.join("TASK-*.md");
-        let pattern_str = pattern
-            .to_str()
-            .ok_or_else(|| anyhow::anyhow!("Invalid path"))?;
+        let wanted = config.watched_paths(&self.docs_path);
+        watched.retain(|path| {
+            let keep = wanted.contains(path);
+            if !keep {
+                let _ = watcher.unwatch(path);
+                println!("  - Stopped watching {}", path.display());
+            }
+            keep
+        });
+        for path in wanted {
+            if !watched.contains(&path) && watch_path(watcher, &path) {
+                println!("  + Watching {}", path.display());
+                watched.push(path);
+            }
+        }
 
-        for entry in glob(pattern_str)? {
-            if let Ok(path) = entry {
-                if is_completion_doc(&path) {
-                    if let Err(e) = process_completion_doc(path, &self.db, &self.stats) {
-                        eprintln!("❌ Error processing document: {}", e);
-                        self.stats.lock().unwrap().errors += 1;
+        *self.patterns.lock().unwrap() = patterns;
+        self.notifier = notifier;
+        self.retention = retention;
+        self.retries.reload()?;
+        self.retries.set_policy(
+            config.max_attempts,
+            Duration::from_secs(config.retry_delay_secs),
+        );
+        self.config = config;
+        Ok(())
+    }
+
+    /// Scan a directory for new completion documents and process them
+    fn scan_and_process(&mut self, dir: &Path) -> Result<()> {
+        let mut docs: Vec<PathBuf> = {
+            let patterns = self.patterns.lock().unwrap();
+            std::fs::read_dir(dir)?
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| path.is_file() && matches_patterns(&patterns, path))
+                .collect()
+        };
+        docs.sort();
+
+        for path in docs {
+            if !self.retries.is_ready(&path, std::time::Instant::now())? {
+                continue;
+            }
+            let error = match process_completion_doc(path.clone(), &self.db, &self.stats) {
+                Ok(completed) => {
+                    self.retries.succeeded(&path);
+                    if let Some(completed) = completed {
+                        self.notify_completed(&completed);
                     }
+                    continue;
+                }
+                Err(e) if is_busy_error(&e) => "database busy".to_string(),
+                Err(e) => format!("{:#}", e),
+            };
+
+            // Left unprocessed; tried again once the backoff has passed
+            match self
+                .retries
+                .failed(&path, &error, std::time::Instant::now())?
+            {
+                RetryDecision::Retry { attempt, delay } => eprintln!(
+                    "{} {}: {} (attempt {}, retrying in {}s)",
+                    emoji("⏳"),
+                    path.display(),
+                    error,
+                    attempt,
+                    delay.as_secs()
+                ),
+                RetryDecision::DeadLettered => {
+                    eprintln!(
+                        "{} Giving up on {}: {} (see prd watch-files failures)",
+                        emoji("❌"),
+                        path.display(),
+                        error
+                    );
+                    self.stats.lock().unwrap().errors += 1;
                 }
             }
         }
@@ -127,6 +326,25 @@ impl FileWatcher {
         Ok(())
     }
 
+    /// Send a desktop notification for an auto-completed task, if enabled
+    fn notify_completed(&mut self, completed: &AutoCompleted) {
+        let Some(notifier) = self.notifier.as_mut() else {
+            return;
+        };
+        let Ok(Some(task)) = self.db.get_task(&completed.task_uuid) else {
+            return;
+        };
+        let agent_uuid = completed
+            .agent_uuid
+            .clone()
+            .or_else(|| task.assigned_agent.clone());
+        if let Some(Ok(Some(agent))) = agent_uuid.map(|id| self.db.get_agent(&id)) {
+            if let Err(e) = notifier.notify_task_complete(&task, &agent) {
+                eprintln!("⚠ Notification failed: {}", e);
+            }
+        }
+    }
+
     /// Stop the file watcher
     pub fn stop(&mut self) {
         self.running.store(false, Ordering::SeqCst);
@@ -145,31 +363,33 @@ impl FileWatcher {
     }
 }
 
-/// Handle a file system event
-fn handle_file_event(event: &Event, db: &Database, stats: &Arc<Mutex<WatcherStats>>) -> Result<()> {
-    match event.kind {
-        EventKind::Create(_) | EventKind::Modify(_) => {
-            for path in &event.paths {
-                if is_completion_doc(path) {
-                    process_completion_doc(path.clone(), db, stats)?;
-                }
-            }
+/// Start watching an extra directory, warning instead of failing
+fn watch_path(watcher: &mut impl Watcher, path: &Path) -> bool {
+    match watcher.watch(path, RecursiveMode::NonRecursive) {
+        Ok(()) => true,
+        Err(e) => {
+            eprintln!("⚠ Cannot watch {}: {}", path.display(), e);
+            false
         }
-        _ => {}
     }
-    Ok(())
 }
 
-/// Check if a file is a completion document
-fn is_completion_doc(path: &Path) -> bool {
-    path.file_name()
-        .and_then(|n| n.to_str())
-        .map(|n| {
-            n.starts_with("TASK-")
-                && (n.contains("COMPLETION") || n.contains("COMPLETE"))
-                && n.ends_with(".md")
-        })
-        .unwrap_or(false)
+/// Notifier for auto-completions, when the config asks for one
+fn completion_notifier(db: &Database, config: &WatcherConfig) -> Result<Option<Notifier>> {
+    if !config.notify_on_complete {
+        return Ok(None);
+    }
+    Ok(Some(Notifier::new(NotificationConfig::load_shared(db)?)))
+}
+
+/// Display ID of a task for log output, falling back to its UUID prefix
+fn task_label(db: &Database, task_uuid: &str) -> String {
+    db.get_task(task_uuid)
+        .ok()
+        .flatten()
+        .and_then(|task| task.display_id)
+        .map(|id| format!("#{}", id))
+        .unwrap_or_else(|| task_uuid.chars().take(8).collect())
 }
 
 /// Process a completion document
@@ -177,19 +397,16 @@ fn process_completion_doc(
     path: PathBuf,
     db: &Database,
     stats: &Arc<Mutex<WatcherStats>>,
-) -> Result<()> {
-    // Parse using Phase 1 document scanner
-    let doc = match parse_completion_doc(path.clone()) {
-        Some(d) => d,
-        None => {
-            return Ok(()); // Skip if parsing failed
-        }
+) -> Result<Option<AutoCompleted>> {
+    // Parse using Phase 1 document scanner; drafts are skipped
+    let Some(doc) = read_completion_doc(path.clone())? else {
+        return Ok(None);
     };
 
     {
         // Get task by display_id
         let task_result: Result<String, rusqlite::Error> = db.get_connection().query_row(
-            "SELECT id, status FROM tasks WHERE display_id = ?1",
+            "SELECT id, status FROM tasks WHERE display_id = ?1 AND deleted_at IS NULL",
             params![doc.task_id],
             |row| Ok(row.get::<_, String>(0)?),
         );
@@ -198,7 +415,7 @@ fn process_completion_doc(
             Ok(uuid) => uuid,
             Err(_) => {
                 println!("  ⚠ Task #{} not found in database, skipping", doc.task_id);
-                return Ok(());
+                return Ok(None);
             }
         };
 
@@ -207,31 +424,18 @@ fn process_completion_doc(
         if let Some(t) = task {
             if t.status.as_str() == "completed" {
                 println!("  ⚠ Task #{} already complete, skipping", doc.task_id);
-                return Ok(());
+                return Ok(None);
             }
         }
 
         // Mark complete with transaction
-        let conn = db.get_connection();
-        let tx = conn.unchecked_transaction()?;
-
-        tx.execute(
-            "UPDATE tasks
-             SET status = 'completed',
-                 completed_at = ?,
-                 updated_at = ?
-             WHERE display_id = ?",
-            params![
-                doc.completed_at.to_rfc3339(),
-                chrono::Utc::now().to_rfc3339(),
-                doc.task_id
-            ],
-        )?;
+        let _span = crate::telemetry::span("watcher.complete_task")
+            .with_attribute("task.display_id", doc.task_id as i64);
+        let tx = db.immediate_transaction()?;
 
-        // Update agent if provided
-        if let Some(agent_id) = &doc.agent_id {
-            // Try to resolve agent ID (could be A11 format or UUID)
-            let agent_uuid_result: Result<String, rusqlite::Error> = tx.query_row(
+        // Try to resolve agent ID (could be A11 format or UUID)
+        let agent_uuid: Option<String> = doc.agent_id.as_ref().and_then(|agent_id| {
+            tx.query_row(
                 "SELECT id FROM agents WHERE display_id = ?1 OR id = ?2 OR name = ?3",
                 params![
                     agent_id.trim_start_matches('A').parse::<i32>().ok(),
@@ -239,9 +443,22 @@ fn process_completion_doc(
                     agent_id
                 ],
                 |row| row.get(0),
-            );
+            )
+            .ok()
+        });
+
+        // Through the regular status change, so configured transitions are
+        // checked and leases and recurrences handled
+        db.update_task_status(&task_uuid, TaskStatus::Completed, agent_uuid.as_deref())?;
+        tx.execute(
+            "UPDATE tasks SET completed_at = ? WHERE id = ?",
+            params![doc.completed_at.to_rfc3339(), task_uuid],
+        )?;
 
-            if let Ok(agent_uuid) = agent_uuid_result {
+        // Update agent if provided
+        let mut completed_by = None;
+        match (&doc.agent_id, agent_uuid) {
+            (Some(agent_id), Some(agent_uuid)) => {
                 tx.execute(
                     "UPDATE agents
                      SET status = 'idle',
@@ -250,25 +467,26 @@ fn process_completion_doc(
                      WHERE id = ?",
                     params![chrono::Utc::now().to_rfc3339(), agent_uuid],
                 )?;
+                completed_by = Some(agent_uuid);
 
                 println!(
                     "  → Marked task #{} complete (agent {})",
                     doc.task_id, agent_id
                 );
-            } else {
-                println!("  → Marked task #{} complete", doc.task_id);
             }
-        } else {
-            println!("  → Marked task #{} complete", doc.task_id);
+            _ => println!("  → Marked task #{} complete", doc.task_id),
         }
 
         tx.commit()?;
 
         // Update stats
         stats.lock().unwrap().tasks_completed += 1;
-    }
 
-    Ok(())
+        Ok(Some(AutoCompleted {
+            task_uuid,
+            agent_uuid: completed_by,
+        }))
+    }
 }
 
 /// Format a duration for display
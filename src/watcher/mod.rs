@@ -1,8 +1,12 @@
+pub mod config;
 pub mod daemon;
 pub mod file_watcher;
+pub mod retry;
 
 // Temporarily disabled - pre-existing compilation errors
 // #[cfg(test)]
 // mod tests;
 
+pub use config::WatcherConfig;
 pub use file_watcher::FileWatcher;
+pub use retry::{FailedDoc, RetryQueue};
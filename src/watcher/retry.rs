@@ -0,0 +1,239 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+const FAILURES_FILE_NAME: &str = "prd-watcher-failures.json";
+
+/// Longest wait between two attempts at the same document
+const MAX_BACKOFF: Duration = Duration::from_secs(15 * 60);
+
+/// Dead-letter list of documents the watcher gave up on (in the system temp
+/// directory, next to the daemon's PID and log files)
+pub fn failures_file() -> PathBuf {
+    std::env::temp_dir().join(FAILURES_FILE_NAME)
+}
+
+/// A completion document that could not be processed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailedDoc {
+    pub path: PathBuf,
+    pub attempts: u32,
+    pub error: String,
+    pub first_failed_at: DateTime<Utc>,
+    pub last_failed_at: DateTime<Utc>,
+}
+
+/// What the queue decided after a failed attempt
+#[derive(Debug, Clone, PartialEq)]
+pub enum RetryDecision {
+    /// Try again after the delay
+    Retry { attempt: u32, delay: Duration },
+    /// Out of attempts; the document moved to the dead-letter list
+    DeadLettered,
+}
+
+#[derive(Debug)]
+struct Pending {
+    failure: FailedDoc,
+    next_attempt: Instant,
+    /// File modification time when it last failed; editing the file
+    /// gives it a fresh set of attempts
+    modified: Option<SystemTime>,
+}
+
+/// Documents that failed to process, retried with exponential backoff
+///
+/// The n-th retry waits `base_delay * 2^(n-1)`, capped at 15 minutes. After
+/// `max_attempts` failures a document is dead-lettered: it is written to
+/// [`failures_file`] and skipped until the file changes or the list is
+/// cleared with `prd watch-files failures --clear`.
+#[derive(Debug)]
+pub struct RetryQueue {
+    max_attempts: u32,
+    base_delay: Duration,
+    pending: HashMap<PathBuf, Pending>,
+    dead: HashMap<PathBuf, Pending>,
+    store: PathBuf,
+}
+
+impl RetryQueue {
+    /// Queue backed by `store`, starting with the documents already dead-lettered there
+    pub fn new(max_attempts: u32, base_delay: Duration, store: PathBuf) -> Result<Self> {
+        let dead = load_failures(&store)?
+            .into_iter()
+            .map(|failure| {
+                let pending = Pending {
+                    modified: modified(&failure.path),
+                    next_attempt: Instant::now(),
+                    failure,
+                };
+                (pending.failure.path.clone(), pending)
+            })
+            .collect();
+        Ok(Self {
+            max_attempts: max_attempts.max(1),
+            base_delay,
+            pending: HashMap::new(),
+            dead,
+            store,
+        })
+    }
+
+    /// Change the attempt limit and first retry delay, keeping queued documents
+    pub fn set_policy(&mut self, max_attempts: u32, base_delay: Duration) {
+        self.max_attempts = max_attempts.max(1);
+        self.base_delay = base_delay;
+    }
+
+    /// Whether `path` may be processed now: not waiting out a backoff and
+    /// not dead-lettered, unless the file changed since it last failed
+    pub fn is_ready(&mut self, path: &Path, now: Instant) -> Result<bool> {
+        if let Some(dead) = self.dead.get(path) {
+            if dead.modified == modified(path) {
+                return Ok(false);
+            }
+            self.dead.remove(path);
+            self.save()?;
+        }
+        match self.pending.get(path) {
+            Some(pending) if pending.modified != modified(path) => {
+                self.pending.remove(path);
+                Ok(true)
+            }
+            Some(pending) => Ok(now >= pending.next_attempt),
+            None => Ok(true),
+        }
+    }
+
+    /// Forget a document that was processed successfully
+    pub fn succeeded(&mut self, path: &Path) {
+        self.pending.remove(path);
+    }
+
+    /// Record a failed attempt and schedule the next one
+    pub fn failed(&mut self, path: &Path, error: &str, now: Instant) -> Result<RetryDecision> {
+        let at = Utc::now();
+        let mut entry = self.pending.remove(path).unwrap_or_else(|| Pending {
+            failure: FailedDoc {
+                path: path.to_path_buf(),
+                attempts: 0,
+                error: String::new(),
+                first_failed_at: at,
+                last_failed_at: at,
+            },
+            next_attempt: now,
+            modified: None,
+        });
+        entry.failure.attempts += 1;
+        entry.failure.error = error.to_string();
+        entry.failure.last_failed_at = at;
+        entry.modified = modified(path);
+
+        let attempt = entry.failure.attempts;
+        if attempt >= self.max_attempts {
+            self.dead.insert(path.to_path_buf(), entry);
+            self.save()?;
+            return Ok(RetryDecision::DeadLettered);
+        }
+        let delay = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt - 1))
+            .min(MAX_BACKOFF);
+        entry.next_attempt = now + delay;
+        self.pending.insert(path.to_path_buf(), entry);
+        Ok(RetryDecision::Retry { attempt, delay })
+    }
+
+    /// Re-read the dead-letter list, picking up `--clear` from another process
+    pub fn reload(&mut self) -> Result<()> {
+        let stored = load_failures(&self.store)?;
+        self.dead
+            .retain(|path, _| stored.iter().any(|f| &f.path == path));
+        Ok(())
+    }
+
+    fn save(&self) -> Result<()> {
+        let mut failures: Vec<&FailedDoc> = self.dead.values().map(|d| &d.failure).collect();
+        failures.sort_by_key(|f| f.last_failed_at);
+        if failures.is_empty() {
+            return clear_failures(&self.store);
+        }
+        fs::write(&self.store, serde_json::to_string_pretty(&failures)?)
+            .with_context(|| format!("Failed to write {}", self.store.display()))
+    }
+}
+
+/// Dead-lettered documents, oldest failure first
+pub fn load_failures(store: &Path) -> Result<Vec<FailedDoc>> {
+    if !store.exists() {
+        return Ok(Vec::new());
+    }
+    let content =
+        fs::read_to_string(store).with_context(|| format!("Failed to read {}", store.display()))?;
+    serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", store.display()))
+}
+
+/// Empty the dead-letter list so the watcher tries those documents again
+pub fn clear_failures(store: &Path) -> Result<()> {
+    match fs::remove_file(store) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_then_dead_letter() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = dir.path().join("failures.json");
+        let doc = dir.path().join("TASK-7-COMPLETION.md");
+        fs::write(&doc, "# Done")?;
+
+        let mut queue = RetryQueue::new(3, Duration::from_secs(2), store.clone())?;
+        let now = Instant::now();
+        assert!(queue.is_ready(&doc, now)?);
+        assert_eq!(
+            queue.failed(&doc, "database is locked", now)?,
+            RetryDecision::Retry {
+                attempt: 1,
+                delay: Duration::from_secs(2)
+            }
+        );
+        assert!(!queue.is_ready(&doc, now + Duration::from_secs(1))?);
+        assert!(queue.is_ready(&doc, now + Duration::from_secs(2))?);
+        assert_eq!(
+            queue.failed(&doc, "database is locked", now)?,
+            RetryDecision::Retry {
+                attempt: 2,
+                delay: Duration::from_secs(4)
+            }
+        );
+        assert_eq!(
+            queue.failed(&doc, "database is locked", now)?,
+            RetryDecision::DeadLettered
+        );
+        assert!(!queue.is_ready(&doc, now + MAX_BACKOFF)?);
+
+        // Survives a restart, and clearing the list releases the document
+        let failures = load_failures(&store)?;
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].attempts, 3);
+        let mut restarted = RetryQueue::new(3, Duration::from_secs(2), store.clone())?;
+        assert!(!restarted.is_ready(&doc, now)?);
+        clear_failures(&store)?;
+        restarted.reload()?;
+        assert!(restarted.is_ready(&doc, now)?);
+        Ok(())
+    }
+}